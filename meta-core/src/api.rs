@@ -0,0 +1,436 @@
+//! Shared client for hosting-provider REST APIs (GitHub today).
+//!
+//! Bulk operations make an API call or two per repository, which across a
+//! workspace of a hundred-odd projects is enough to trip a host's rate limits
+//! and get the account throttled. Every such call goes through the one
+//! process-wide [`ApiClient`] from [`client`], which:
+//!
+//! - spaces requests to each host with a token bucket,
+//! - stops sending to a host once it reports an exhausted quota, until the
+//!   quota resets,
+//! - waits out `429`s and rate-limit `403`s (per `Retry-After`, else
+//!   `x-ratelimit-reset`) and retries, and
+//! - caches responses the caller marks as stable ([`ApiClient::get_cached`]),
+//!   so asking the same question twice in one run costs one request.
+//!
+//! Requests go through the system `curl`, which is ubiquitous on developer
+//! machines, rather than an HTTP stack compiled into every plugin.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Requests a host may receive back to back.
+const BURST: f64 = 10.0;
+/// Sustained requests per second to one host.
+const RATE_PER_SEC: f64 = 2.0;
+/// How many times a rate-limited request is retried.
+const MAX_RETRIES: u32 = 3;
+/// Longest wait for a rate limit to clear; a longer one fails the request.
+const MAX_WAIT: Duration = Duration::from_secs(120);
+
+/// A token bucket that hands out waits instead of refusals: tokens may go
+/// negative, so concurrent callers queue up behind each other.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket of `capacity` tokens refilling at `rate` per second.
+    pub fn new(capacity: f64, rate: f64, now: Instant) -> Self {
+        Self {
+            capacity,
+            rate,
+            tokens: capacity,
+            last: now,
+        }
+    }
+
+    /// Take a token; returns how long to wait before using it.
+    pub fn take(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// A response: status, lower-cased headers, and body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn into_result(self, url: &str) -> Result<String> {
+        if (200..300).contains(&self.status) {
+            return Ok(self.body);
+        }
+        Err(anyhow!(
+            "request to {} failed: HTTP {} {}",
+            url,
+            self.status,
+            self.body.trim()
+        ))
+    }
+}
+
+/// Parse `curl -i` output. Redirects and interim responses put further
+/// header blocks ahead of the body; the last block is the one that counts.
+pub fn parse_response(raw: &str) -> Result<Response> {
+    let mut rest = raw;
+    loop {
+        let (head, body) = rest
+            .split_once("\r\n\r\n")
+            .or_else(|| rest.split_once("\n\n"))
+            .unwrap_or((rest, ""));
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| anyhow!("malformed HTTP response"))?;
+        if body.starts_with("HTTP/") {
+            rest = body;
+            continue;
+        }
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+        return Ok(Response {
+            status,
+            headers,
+            body: body.to_string(),
+        });
+    }
+}
+
+/// Time until the quota reported by `response` resets, when it is spent.
+/// `now` is in Unix seconds.
+pub fn quota_reset(response: &Response, now: u64) -> Option<Duration> {
+    if response.header("x-ratelimit-remaining")? != "0" {
+        return None;
+    }
+    let reset: u64 = response.header("x-ratelimit-reset")?.parse().ok()?;
+    Some(Duration::from_secs(reset.saturating_sub(now).max(1)))
+}
+
+/// How long to wait before retrying, when `response` is a rate-limit
+/// rejection. `now` is in Unix seconds.
+pub fn retry_delay(response: &Response, now: u64) -> Option<Duration> {
+    let retry_after = response
+        .header("retry-after")
+        .and_then(|secs| secs.parse().ok())
+        .map(Duration::from_secs);
+    match response.status {
+        // GitHub asks for at least a minute when it gives no hint.
+        429 => Some(
+            retry_after
+                .or_else(|| quota_reset(response, now))
+                .unwrap_or(Duration::from_secs(60)),
+        ),
+        // A 403 is a rate limit only with one of these headers; otherwise it
+        // is a real permission error.
+        403 => retry_after.or_else(|| quota_reset(response, now)),
+        _ => None,
+    }
+}
+
+fn host_of(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?']).next().unwrap_or(rest)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Stable responses by URL: the body, or the error message of a 404.
+type Cache = HashMap<String, std::result::Result<String, String>>;
+
+struct HostState {
+    bucket: TokenBucket,
+    blocked_until: Option<Instant>,
+}
+
+/// Rate-limited, caching API client. Use the shared one from [`client`] so
+/// every caller draws on the same per-host budget.
+#[derive(Default)]
+pub struct ApiClient {
+    hosts: Mutex<HashMap<String, HostState>>,
+    cache: Mutex<Cache>,
+}
+
+/// The process-wide client.
+pub fn client() -> &'static ApiClient {
+    static CLIENT: OnceLock<ApiClient> = OnceLock::new();
+    CLIENT.get_or_init(ApiClient::default)
+}
+
+impl ApiClient {
+    /// GET `url`, returning the body of a 2xx response. `bearer` is sent as an
+    /// `Authorization: Bearer` header when set.
+    pub fn get(&self, url: &str, bearer: Option<&str>) -> Result<String> {
+        let host = host_of(url);
+        let mut attempt = 0;
+        loop {
+            self.wait_turn(host);
+            let response = send(url, bearer)?;
+            let now = unix_now();
+            if let Some(reset) = quota_reset(&response, now) {
+                self.block(host, reset);
+            }
+            match retry_delay(&response, now) {
+                Some(wait) if attempt < MAX_RETRIES && wait <= MAX_WAIT => {
+                    self.block(host, wait);
+                    attempt += 1;
+                }
+                _ => return response.into_result(url),
+            }
+        }
+    }
+
+    /// Like [`get`](Self::get), for responses that will not change during
+    /// the run (repository metadata, file contents at a ref): each URL is
+    /// requested once, and a 404 is remembered as well.
+    pub fn get_cached(&self, url: &str, bearer: Option<&str>) -> Result<String> {
+        if let Some(hit) = self.lock_cache().get(url) {
+            return hit.clone().map_err(|e| anyhow!(e));
+        }
+        let result = self.get(url, bearer);
+        let entry = match &result {
+            Ok(body) => Some(Ok(body.clone())),
+            Err(e) if e.to_string().contains("HTTP 404") => Some(Err(e.to_string())),
+            Err(_) => None,
+        };
+        if let Some(entry) = entry {
+            self.lock_cache().insert(url.to_string(), entry);
+        }
+        result
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, Cache> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_hosts(&self) -> MutexGuard<'_, HashMap<String, HostState>> {
+        self.hosts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sleep until `host` may receive another request.
+    fn wait_turn(&self, host: &str) {
+        let now = Instant::now();
+        let (wait, blocked) = {
+            let mut hosts = self.lock_hosts();
+            let state = hosts.entry(host.to_string()).or_insert_with(|| HostState {
+                bucket: TokenBucket::new(BURST, RATE_PER_SEC, now),
+                blocked_until: None,
+            });
+            let spacing = state.bucket.take(now);
+            let blocked = state
+                .blocked_until
+                .map(|until| until.saturating_duration_since(now))
+                .unwrap_or_default();
+            (spacing.max(blocked), blocked)
+        };
+        if blocked >= Duration::from_secs(2) {
+            eprintln!(
+                "Waiting {}s for the {} rate limit to reset...",
+                blocked.as_secs(),
+                host
+            );
+        }
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// Send nothing more to `host` for `wait`.
+    fn block(&self, host: &str, wait: Duration) {
+        let until = Instant::now() + wait.min(MAX_WAIT);
+        let mut hosts = self.lock_hosts();
+        if let Some(state) = hosts.get_mut(host) {
+            state.blocked_until = Some(state.blocked_until.map_or(until, |u| u.max(until)));
+        }
+    }
+}
+
+fn send(url: &str, bearer: Option<&str>) -> Result<Response> {
+    let mut cmd = Command::new("curl");
+    cmd.args([
+        "-sS",
+        "-i",
+        "-L",
+        "-m",
+        "30",
+        "-H",
+        "Accept: application/json",
+    ]);
+    // The token goes in on stdin (`-H @-`) so it never shows up in the
+    // process list.
+    if bearer.is_some() {
+        cmd.args(["-H", "@-"]);
+    }
+    cmd.arg(url)
+        .stdin(if bearer.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(anyhow!(
+                "curl is required to reach {} but was not found on PATH",
+                host_of(url)
+            ));
+        }
+        Err(e) => return Err(e).context("running curl"),
+    };
+    if let (Some(token), Some(mut stdin)) = (bearer, child.stdin.take()) {
+        stdin
+            .write_all(format!("Authorization: Bearer {token}\n").as_bytes())
+            .context("passing the token to curl")?;
+    }
+    let out = child.wait_with_output().context("running curl")?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "request to {} failed: {}",
+            url,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    parse_response(&String::from_utf8_lossy(&out.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bearer_token_reaches_the_server_without_curl_argv() {
+        use std::io::{BufRead, BufReader};
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut auth = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("Authorization: ") {
+                    auth = value.trim().to_string();
+                }
+            }
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                auth.len(),
+                auth
+            )
+            .unwrap();
+        });
+        let response = send(&format!("http://127.0.0.1:{port}/"), Some("s3cret")).unwrap();
+        server.join().unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "Bearer s3cret");
+    }
+
+    #[test]
+    fn bucket_allows_a_burst_then_spaces_requests() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, 4.0, start);
+        assert!(bucket.take(start).is_zero());
+        assert!(bucket.take(start).is_zero());
+        assert_eq!(bucket.take(start), Duration::from_millis(250));
+        // Queued callers wait behind each other.
+        assert_eq!(bucket.take(start), Duration::from_millis(500));
+        // After a second the debt is paid and two tokens are back.
+        let later = start + Duration::from_millis(1500);
+        assert!(bucket.take(later).is_zero());
+    }
+
+    #[test]
+    fn parses_the_final_response_after_redirects() {
+        let raw = "HTTP/1.1 301 Moved Permanently\r\nLocation: /new\r\n\r\n\
+                   HTTP/2 200\r\nContent-Type: application/json\r\n\
+                   X-RateLimit-Remaining: 41\r\n\r\n{\"size\": 12}";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("x-ratelimit-remaining"), Some("41"));
+        assert_eq!(response.body, "{\"size\": 12}");
+        assert_eq!(
+            host_of("https://api.github.com/repos/a/b"),
+            "api.github.com"
+        );
+    }
+
+    #[test]
+    fn rate_limit_responses_say_how_long_to_wait() {
+        let response = |status: u16, headers: &[(&str, &str)]| Response {
+            status,
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: String::new(),
+        };
+        let now = 1_000;
+
+        let limited = response(429, &[("retry-after", "30")]);
+        assert_eq!(retry_delay(&limited, now), Some(Duration::from_secs(30)));
+        let limited = response(429, &[]);
+        assert_eq!(retry_delay(&limited, now), Some(Duration::from_secs(60)));
+
+        let spent = [
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1090"),
+        ];
+        let forbidden = response(403, &spent);
+        assert_eq!(retry_delay(&forbidden, now), Some(Duration::from_secs(90)));
+        // A plain 403 is a permission error, not worth retrying.
+        assert_eq!(retry_delay(&response(403, &[]), now), None);
+
+        // A success that spends the last request still blocks the host.
+        let last = response(200, &spent);
+        assert_eq!(retry_delay(&last, now), None);
+        assert_eq!(quota_reset(&last, now), Some(Duration::from_secs(90)));
+    }
+}
@@ -0,0 +1,1227 @@
+//! Targeted edits to config file text.
+//!
+//! Re-serializing a whole [`MetaConfig`] reorders `projects` (a hash map),
+//! reflows hand-formatted values, and drops TOML comments, so a one-project
+//! change shows up as a rewrite of the file. Instead, saving diffs the config
+//! against what is on disk ([`diff`]) and splices only the changed values into
+//! the existing text ([`apply`]); everything else stays byte-for-byte as it was.
+//!
+//! A key that disappears while another appears with the same value (`meta
+//! project rename`) is a [`ConfigEdit::Rename`]: only the key token changes,
+//! so the entry keeps its place and the comments around it.
+//!
+//! JSON and TOML are edited in place; YAML only takes renames in place. Other
+//! YAML edits, and the rare edit the text layer can't express (a dotted TOML
+//! key turning into a table, say), fall back to a full serialization; see
+//! [`MetaConfig::to_string_preserving`].
+
+use crate::config_format::{self, ConfigFormat};
+use crate::MetaConfig;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::ops::Range;
+
+/// One change to a config document, addressed by the key path from the root.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigEdit {
+    /// Set the value at `path`, adding the key (and any missing parent
+    /// tables) when it isn't there.
+    Set { path: Vec<String>, value: Value },
+    /// Remove the key at `path`. Removing a missing key is a no-op.
+    Remove { path: Vec<String> },
+    /// Rename the key at `path` to `to`, keeping its value and position.
+    Rename { path: Vec<String>, to: String },
+}
+
+/// The edits that turn `old` into `new`. Objects are compared key by key, so
+/// only the values that differ are touched; anything else (a scalar, an array)
+/// is replaced whole. A `null` in `new` removes the key, since none of the
+/// formats keeps an absent optional field as `null`. When an object loses one
+/// key and gains one with the same value, that is a rename.
+pub fn diff(old: &Value, new: &Value) -> Vec<ConfigEdit> {
+    let mut edits = Vec::new();
+    diff_into(&mut Vec::new(), old, new, &mut edits);
+    edits
+}
+
+fn diff_into(path: &mut Vec<String>, old: &Value, new: &Value, edits: &mut Vec<ConfigEdit>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let renamed = renamed_key(old, new);
+            for (key, old_value) in old {
+                path.push(key.clone());
+                match (new.get(key), renamed) {
+                    (Some(new_value), _) => diff_into(path, old_value, new_value, edits),
+                    (None, Some((from, to))) if from == key => edits.push(ConfigEdit::Rename {
+                        path: path.clone(),
+                        to: to.clone(),
+                    }),
+                    (None, _) if !old_value.is_null() => {
+                        edits.push(ConfigEdit::Remove { path: path.clone() })
+                    }
+                    (None, _) => {}
+                }
+                path.pop();
+            }
+            for (key, value) in new {
+                let is_rename = renamed.is_some_and(|(_, to)| to == key);
+                if !old.contains_key(key) && !value.is_null() && !is_rename {
+                    let mut path = path.clone();
+                    path.push(key.clone());
+                    edits.push(ConfigEdit::Set {
+                        path,
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        _ if old == new => {}
+        (_, Value::Null) => edits.push(ConfigEdit::Remove { path: path.clone() }),
+        _ => edits.push(ConfigEdit::Set {
+            path: path.clone(),
+            value: new.clone(),
+        }),
+    }
+}
+
+/// The key `old` lost and the key `new` gained in its place: the only one
+/// removed and the only one added, holding the same value.
+fn renamed_key<'a>(
+    old: &'a serde_json::Map<String, Value>,
+    new: &'a serde_json::Map<String, Value>,
+) -> Option<(&'a String, &'a String)> {
+    let only = |from: &'a serde_json::Map<String, Value>, to: &serde_json::Map<String, Value>| {
+        let mut keys = from
+            .iter()
+            .filter(|(key, value)| !to.contains_key(*key) && !value.is_null());
+        match (keys.next(), keys.next()) {
+            (Some(entry), None) => Some(entry),
+            _ => None,
+        }
+    };
+    let (from, old_value) = only(old, new)?;
+    let (to, new_value) = only(new, old)?;
+    (old_value == new_value).then_some((from, to))
+}
+
+/// Apply `edits` to `content`, config text in `format`, changing nothing else.
+/// Returns `None` when the text can't be parsed or an edit can't be made in
+/// place (for YAML, anything but a rename); the caller then re-serializes
+/// instead.
+pub fn apply(content: &str, format: ConfigFormat, edits: &[ConfigEdit]) -> Option<String> {
+    let mut text = content.to_string();
+    for edit in edits {
+        text = match format {
+            ConfigFormat::Json => json::apply(&text, edit)?,
+            ConfigFormat::Toml => toml_text::apply(&text, edit)?,
+            ConfigFormat::Yaml => yaml_text::apply(&text, edit)?,
+        };
+    }
+    Some(text)
+}
+
+impl MetaConfig {
+    /// Serialize this config as an edit of `existing`, the config file's
+    /// current text in `format`: keys, formatting, and comments the change
+    /// doesn't touch are kept. Falls back to a full serialization when
+    /// `existing` doesn't parse or the edits can't be made in place, and
+    /// checks that the edited text reads back as exactly this config.
+    pub fn to_string_preserving(&self, existing: &str, format: ConfigFormat) -> Result<String> {
+        let new = serde_json::to_value(self)?;
+        let as_value = |text: &str| {
+            config_format::deserialize_from_str::<MetaConfig>(text, format)
+                .ok()
+                .and_then(|config| serde_json::to_value(config).ok())
+        };
+        if let Some(old) = as_value(existing) {
+            if let Some(text) = apply(existing, format, &diff(&old, &new)) {
+                if as_value(&text).as_ref() == Some(&new) {
+                    return Ok(text);
+                }
+            }
+        }
+        config_format::serialize_to_string(self, format)
+    }
+}
+
+/// Start of the line holding `pos`.
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// End of the line holding `pos`, past its newline.
+fn line_end(text: &str, pos: usize) -> usize {
+    text[pos..].find('\n').map_or(text.len(), |i| pos + i + 1)
+}
+
+/// The leading whitespace of the line holding `pos`.
+fn line_indent(text: &str, pos: usize) -> &str {
+    let start = line_start(text, pos);
+    let line = &text[start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+fn splice(text: &str, range: Range<usize>, with: &str) -> String {
+    let mut out = String::with_capacity(text.len() + with.len());
+    out.push_str(&text[..range.start]);
+    out.push_str(with);
+    out.push_str(&text[range.end..]);
+    out
+}
+
+/// `value` nested under `keys`, innermost last.
+fn nest(keys: &[String], value: &Value) -> Value {
+    keys.iter()
+        .rev()
+        .fold(value.clone(), |inner, key| json!({ key: inner }))
+}
+
+mod json {
+    //! JSON edits over a minimal parser that records where each value and
+    //! object member sits in the text.
+
+    use super::{line_indent, nest, splice, ConfigEdit};
+    use serde::Serialize;
+    use serde_json::ser::{PrettyFormatter, Serializer};
+    use serde_json::Value;
+
+    struct Node {
+        start: usize,
+        end: usize,
+        /// The members, for an object.
+        members: Option<Vec<Member>>,
+    }
+
+    struct Member {
+        key: String,
+        key_start: usize,
+        key_end: usize,
+        value: Node,
+    }
+
+    struct Parser<'a> {
+        text: &'a [u8],
+        pos: usize,
+    }
+
+    impl Parser<'_> {
+        fn skip_whitespace(&mut self) {
+            while matches!(self.text.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+                self.pos += 1;
+            }
+        }
+
+        fn bump(&mut self) -> Option<u8> {
+            let byte = *self.text.get(self.pos)?;
+            self.pos += 1;
+            Some(byte)
+        }
+
+        fn value(&mut self) -> Option<Node> {
+            self.skip_whitespace();
+            let start = self.pos;
+            let mut members = None;
+            match *self.text.get(self.pos)? {
+                b'{' => {
+                    self.pos += 1;
+                    let mut list = Vec::new();
+                    self.skip_whitespace();
+                    if self.text.get(self.pos) == Some(&b'}') {
+                        self.pos += 1;
+                    } else {
+                        loop {
+                            self.skip_whitespace();
+                            let key_start = self.pos;
+                            let key = self.string()?;
+                            let key_end = self.pos;
+                            self.skip_whitespace();
+                            if self.bump()? != b':' {
+                                return None;
+                            }
+                            let value = self.value()?;
+                            list.push(Member {
+                                key,
+                                key_start,
+                                key_end,
+                                value,
+                            });
+                            self.skip_whitespace();
+                            match self.bump()? {
+                                b',' => {}
+                                b'}' => break,
+                                _ => return None,
+                            }
+                        }
+                    }
+                    members = Some(list);
+                }
+                b'[' => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.text.get(self.pos) == Some(&b']') {
+                        self.pos += 1;
+                    } else {
+                        loop {
+                            self.value()?;
+                            self.skip_whitespace();
+                            match self.bump()? {
+                                b',' => {}
+                                b']' => break,
+                                _ => return None,
+                            }
+                        }
+                    }
+                }
+                b'"' => {
+                    self.string()?;
+                }
+                _ => {
+                    // A number, `true`, `false`, or `null`; the reparse after
+                    // editing rejects anything else.
+                    while !matches!(
+                        self.text.get(self.pos),
+                        None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r')
+                    ) {
+                        self.pos += 1;
+                    }
+                    if self.pos == start {
+                        return None;
+                    }
+                }
+            }
+            Some(Node {
+                start,
+                end: self.pos,
+                members,
+            })
+        }
+
+        fn string(&mut self) -> Option<String> {
+            let start = self.pos;
+            if self.bump()? != b'"' {
+                return None;
+            }
+            loop {
+                match self.bump()? {
+                    b'\\' => self.pos += 1,
+                    b'"' => break,
+                    _ => {}
+                }
+            }
+            serde_json::from_slice(self.text.get(start..self.pos)?).ok()
+        }
+    }
+
+    fn parse(text: &str) -> Option<Node> {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            pos: 0,
+        };
+        let root = parser.value()?;
+        parser.skip_whitespace();
+        (parser.pos == text.len()).then_some(root)
+    }
+
+    /// The indentation step the file uses, from its first indented line.
+    fn indent_unit(text: &str) -> &str {
+        text.lines()
+            .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+            .find(|indent| !indent.is_empty())
+            .unwrap_or("  ")
+    }
+
+    /// `value` pretty-printed with `unit` indentation, continuation lines
+    /// starting at `indent`.
+    fn render(value: &Value, indent: &str, unit: &str) -> Option<String> {
+        let mut out = Vec::new();
+        let formatter = PrettyFormatter::with_indent(unit.as_bytes());
+        value
+            .serialize(&mut Serializer::with_formatter(&mut out, formatter))
+            .ok()?;
+        let pretty = String::from_utf8(out).ok()?;
+        Some(pretty.replace('\n', &format!("\n{}", indent)))
+    }
+
+    pub(super) fn apply(text: &str, edit: &ConfigEdit) -> Option<String> {
+        let root = parse(text)?;
+        let unit = indent_unit(text);
+        let (ConfigEdit::Set { path, .. }
+        | ConfigEdit::Remove { path }
+        | ConfigEdit::Rename { path, .. }) = edit;
+
+        // Walk down as far as the path exists.
+        let mut parent: Option<(&Node, usize)> = None;
+        let mut node = &root;
+        let mut depth = 0;
+        while depth < path.len() {
+            let members = node.members.as_ref()?;
+            let Some(index) = members.iter().position(|m| m.key == path[depth]) else {
+                break;
+            };
+            parent = Some((node, index));
+            node = &members[index].value;
+            depth += 1;
+        }
+
+        match edit {
+            ConfigEdit::Set { value, .. } if depth == path.len() => {
+                let (object, index) = parent?;
+                let members = object.members.as_ref()?;
+                let indent = line_indent(text, members[index].key_start);
+                let rendered = render(value, indent, unit)?;
+                Some(splice(text, node.start..node.end, &rendered))
+            }
+            ConfigEdit::Set { value, .. } => {
+                let members = node.members.as_ref()?;
+                let key = serde_json::to_string(&path[depth]).ok()?;
+                let value = nest(&path[depth + 1..], value);
+                match members.last() {
+                    Some(last) if !text[node.start..node.end].contains('\n') => {
+                        let member = format!(", {}: {}", key, serde_json::to_string(&value).ok()?);
+                        Some(splice(text, last.value.end..last.value.end, &member))
+                    }
+                    Some(last) => {
+                        let indent = line_indent(text, last.key_start);
+                        let member =
+                            format!(",\n{}{}: {}", indent, key, render(&value, indent, unit)?);
+                        Some(splice(text, last.value.end..last.value.end, &member))
+                    }
+                    None => {
+                        let outer = line_indent(text, node.start);
+                        let inner = format!("{}{}", outer, unit);
+                        let object = format!(
+                            "{{\n{}{}: {}\n{}}}",
+                            inner,
+                            key,
+                            render(&value, &inner, unit)?,
+                            outer
+                        );
+                        Some(splice(text, node.start..node.end, &object))
+                    }
+                }
+            }
+            ConfigEdit::Rename { .. } if depth < path.len() => None,
+            ConfigEdit::Rename { to, .. } => {
+                let (object, index) = parent?;
+                let member = &object.members.as_ref()?[index];
+                let key = serde_json::to_string(to).ok()?;
+                Some(splice(text, member.key_start..member.key_end, &key))
+            }
+            ConfigEdit::Remove { .. } if depth < path.len() => Some(text.to_string()),
+            ConfigEdit::Remove { .. } => {
+                let (object, index) = parent?;
+                let members = object.members.as_ref()?;
+                let range = if members.len() == 1 {
+                    return Some(splice(text, object.start..object.end, "{}"));
+                } else if index > 0 {
+                    members[index - 1].value.end..members[index].value.end
+                } else {
+                    members[0].key_start..members[1].key_start
+                };
+                Some(splice(text, range, ""))
+            }
+        }
+    }
+}
+
+mod toml_text {
+    //! TOML edits over the spans `toml`'s own parser reports.
+
+    use super::{line_end, line_start, nest, splice, ConfigEdit};
+    use serde_json::{json, Value};
+    use std::ops::Range;
+    use toml::de::{DeTable, DeValue};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Kind {
+        Root,
+        /// A `key = value` pair.
+        Value,
+        /// `key = { ... }`.
+        Inline,
+        /// A table with its own `[header]`.
+        Header,
+        /// A table that only exists as part of deeper headers.
+        Implicit,
+        /// A table made of `key.sub = value` pairs.
+        Dotted,
+        /// `[[key]]` sections.
+        TableArray,
+    }
+
+    struct Node {
+        key: String,
+        key_span: Range<usize>,
+        span: Range<usize>,
+        kind: Kind,
+        children: Vec<Node>,
+    }
+
+    impl Node {
+        /// Where the node's text ends; a dotted table ends with its last pair.
+        fn extent_end(&self) -> usize {
+            match self.kind {
+                Kind::Dotted => self
+                    .children
+                    .iter()
+                    .map(Node::extent_end)
+                    .max()
+                    .unwrap_or(self.span.end),
+                _ => self.span.end,
+            }
+        }
+    }
+
+    struct Document {
+        root: Node,
+        /// Every `[header]` and `[[header]]`: its table path and offset.
+        headers: Vec<(Vec<String>, usize)>,
+    }
+
+    fn parse(text: &str) -> Option<Document> {
+        let table = DeTable::parse(text).ok()?.into_inner();
+        let mut headers = Vec::new();
+        let children = build(text, &table, &mut Vec::new(), &mut headers);
+        headers.sort_by_key(|(_, start)| *start);
+        Some(Document {
+            root: Node {
+                key: String::new(),
+                key_span: 0..0,
+                span: 0..text.len(),
+                kind: Kind::Root,
+                children,
+            },
+            headers,
+        })
+    }
+
+    fn build(
+        text: &str,
+        table: &DeTable,
+        path: &mut Vec<String>,
+        headers: &mut Vec<(Vec<String>, usize)>,
+    ) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        for (key, value) in table.iter() {
+            path.push(key.get_ref().to_string());
+            let span = value.span();
+            let source = &text[span.clone()];
+            let (kind, children) = match value.get_ref() {
+                DeValue::Table(inner) => {
+                    let kind = if source.starts_with('{') {
+                        Kind::Inline
+                    } else if source.starts_with('[') {
+                        headers.push((path.clone(), span.start));
+                        Kind::Header
+                    } else if text[line_start(text, span.start)..].starts_with('[') {
+                        Kind::Implicit
+                    } else {
+                        Kind::Dotted
+                    };
+                    (kind, build(text, inner, path, headers))
+                }
+                DeValue::Array(items) if source.starts_with("[[") => {
+                    for item in items.iter() {
+                        headers.push((path.clone(), item.span().start));
+                        if let DeValue::Table(inner) = item.get_ref() {
+                            build(text, inner, path, headers);
+                        }
+                    }
+                    (Kind::TableArray, Vec::new())
+                }
+                _ => (Kind::Value, Vec::new()),
+            };
+            nodes.push(Node {
+                key: path.pop().unwrap_or_default(),
+                key_span: key.span(),
+                span,
+                kind,
+                children,
+            });
+        }
+        nodes
+    }
+
+    /// Back up from `start`, a line start, over the comment lines directly
+    /// above it, which describe what follows.
+    fn with_leading_comments(text: &str, mut start: usize) -> usize {
+        while start > 0 {
+            let previous = line_start(text, start - 1);
+            if !text[previous..start].trim_start().starts_with('#') {
+                break;
+            }
+            start = previous;
+        }
+        start
+    }
+
+    impl Document {
+        /// Where the section of the header at `start` ends: at the next
+        /// header, or its comments.
+        fn section_end(&self, text: &str, start: usize) -> usize {
+            self.headers
+                .iter()
+                .map(|(_, s)| *s)
+                .find(|s| *s > start)
+                .map(|s| with_leading_comments(text, line_start(text, s)))
+                .unwrap_or(text.len())
+        }
+
+        /// The text ranges of every section under the table at `path`.
+        fn sections(&self, text: &str, path: &[String]) -> Vec<Range<usize>> {
+            self.headers
+                .iter()
+                .filter(|(p, _)| p.starts_with(path))
+                .map(|(_, start)| {
+                    with_leading_comments(text, line_start(text, *start))
+                        ..self.section_end(text, *start)
+                })
+                .collect()
+        }
+    }
+
+    /// `value` as the right-hand side of a `key = value` pair.
+    fn render_value(value: &Value) -> Option<String> {
+        let line = toml::to_string(&json!({ "v": value })).ok()?;
+        Some(
+            line.strip_prefix("v = ")?
+                .trim_end_matches('\n')
+                .to_string(),
+        )
+    }
+
+    /// A `key = value` line, or `None` when the value needs sections.
+    fn render_pair(key: &str, value: &Value) -> Option<String> {
+        let line = toml::to_string(&json!({ key: value })).ok()?;
+        (!line.starts_with('[')).then_some(line)
+    }
+
+    /// TOML has no null: optional fields are left out.
+    fn strip_nulls(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(_, v)| !v.is_null())
+                    .map(|(k, v)| (k.clone(), strip_nulls(v)))
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(strip_nulls).collect()),
+            other => other.clone(),
+        }
+    }
+
+    pub(super) fn apply(text: &str, edit: &ConfigEdit) -> Option<String> {
+        let doc = parse(text)?;
+        let (ConfigEdit::Set { path, .. }
+        | ConfigEdit::Remove { path }
+        | ConfigEdit::Rename { path, .. }) = edit;
+
+        let mut node = &doc.root;
+        let mut depth = 0;
+        while depth < path.len() {
+            let Some(child) = node.children.iter().find(|c| c.key == path[depth]) else {
+                break;
+            };
+            node = child;
+            depth += 1;
+        }
+
+        match edit {
+            ConfigEdit::Rename { .. } if depth < path.len() => None,
+            ConfigEdit::Rename { to, .. } => rename(text, &doc, node, path, to),
+            ConfigEdit::Remove { .. } if depth < path.len() => Some(text.to_string()),
+            ConfigEdit::Remove { .. } => remove(text, &doc, node, path),
+            ConfigEdit::Set { value, .. } => {
+                let value = strip_nulls(value);
+                if depth < path.len() {
+                    return insert(text, &doc, node, &path[..depth], &path[depth..], &value);
+                }
+                if node.kind == Kind::Value && !value.is_object() {
+                    return Some(splice(text, node.span.clone(), &render_value(&value)?));
+                }
+                // A change of shape: drop the old entry and add the new one.
+                let removed = remove(text, &doc, node, path)?;
+                apply(
+                    &removed,
+                    &ConfigEdit::Set {
+                        path: path.clone(),
+                        value,
+                    },
+                )
+            }
+        }
+    }
+
+    /// `key` as it is written on the left of `=` or in a header.
+    fn render_key(key: &str) -> Option<String> {
+        let line = toml::to_string(&json!({ key: 0 })).ok()?;
+        Some(line.strip_suffix(" = 0\n")?.to_string())
+    }
+
+    /// The spans of the keys of the `[header]` or `[[header]]` at `start`.
+    fn header_keys(text: &str, start: usize) -> Option<Vec<Range<usize>>> {
+        let bytes = text.as_bytes();
+        let mut pos = start;
+        while bytes.get(pos) == Some(&b'[') {
+            pos += 1;
+        }
+        let mut keys = Vec::new();
+        loop {
+            while matches!(bytes.get(pos), Some(b' ' | b'\t')) {
+                pos += 1;
+            }
+            let key_start = pos;
+            match *bytes.get(pos)? {
+                quote @ (b'"' | b'\'') => {
+                    pos += 1;
+                    while *bytes.get(pos)? != quote {
+                        pos += if quote == b'"' && bytes[pos] == b'\\' {
+                            2
+                        } else {
+                            1
+                        };
+                    }
+                    pos += 1;
+                }
+                _ => {
+                    let bare = |b: &u8| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-');
+                    while bytes.get(pos).is_some_and(bare) {
+                        pos += 1;
+                    }
+                }
+            }
+            if pos == key_start {
+                return None;
+            }
+            keys.push(key_start..pos);
+            while matches!(bytes.get(pos), Some(b' ' | b'\t')) {
+                pos += 1;
+            }
+            match *bytes.get(pos)? {
+                b'.' => pos += 1,
+                b']' => return Some(keys),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Rename the key of `node`, at `path`, in place: in its `key = value`
+    /// pair, or in every header of its table and the tables under it.
+    fn rename(
+        text: &str,
+        doc: &Document,
+        node: &Node,
+        path: &[String],
+        to: &str,
+    ) -> Option<String> {
+        let key = render_key(to)?;
+        let mut spans = match node.kind {
+            Kind::Value | Kind::Inline => vec![node.key_span.clone()],
+            Kind::Header | Kind::Implicit | Kind::TableArray => {
+                let mut spans = Vec::new();
+                for (_, start) in doc.headers.iter().filter(|(p, _)| p.starts_with(path)) {
+                    spans.push(header_keys(text, *start)?.get(path.len() - 1)?.clone());
+                }
+                spans
+            }
+            Kind::Root | Kind::Dotted => return None,
+        };
+        spans.sort_by_key(|span| std::cmp::Reverse(span.start));
+        Some(
+            spans
+                .into_iter()
+                .fold(text.to_string(), |text, span| splice(&text, span, &key)),
+        )
+    }
+
+    fn remove(text: &str, doc: &Document, node: &Node, path: &[String]) -> Option<String> {
+        let mut ranges = match node.kind {
+            Kind::Value | Kind::Inline => {
+                let start = line_start(text, node.key_span.start);
+                let end = line_end(text, node.span.end);
+                let before = &text[start..node.key_span.start];
+                let after = text[node.span.end..end].trim();
+                if !before.trim().is_empty() || !(after.is_empty() || after.starts_with('#')) {
+                    return None;
+                }
+                std::iter::once(start..end).collect()
+            }
+            Kind::Header | Kind::Implicit | Kind::TableArray => doc.sections(text, path),
+            Kind::Root | Kind::Dotted => return None,
+        };
+        ranges.sort_by_key(|r| r.start);
+        let mut out = String::with_capacity(text.len());
+        let mut kept = 0;
+        for range in &ranges {
+            out.push_str(&text[kept..range.start.max(kept)]);
+            kept = kept.max(range.end);
+        }
+        out.push_str(&text[kept..]);
+        if kept == text.len() {
+            // The removed entries ended the file: don't leave blank lines.
+            out.truncate(out.trim_end().len());
+            if !out.is_empty() {
+                out.push('\n');
+            }
+        }
+        Some(out)
+    }
+
+    /// Add `rest` (the missing part of the path) under `node`, the deepest
+    /// table of the path that exists, at `table`.
+    fn insert(
+        text: &str,
+        doc: &Document,
+        node: &Node,
+        table: &[String],
+        rest: &[String],
+        value: &Value,
+    ) -> Option<String> {
+        let value = nest(&rest[1..], value);
+        let pair = match node.kind {
+            Kind::Root | Kind::Header | Kind::Implicit => render_pair(&rest[0], &value),
+            _ => return None,
+        };
+
+        if pair.is_some() && node.kind == Kind::Implicit {
+            // The table has no header of its own yet: give it one, ahead of
+            // the sections of its subtables.
+            let section = toml::to_string_pretty(&nest(table, &json!({ &rest[0]: value }))).ok()?;
+            let (_, first) = doc.headers.iter().find(|(p, _)| p.starts_with(table))?;
+            let at = line_start(text, *first);
+            return Some(splice(text, at..at, &format!("{}\n", section)));
+        }
+        if let Some(pair) = pair {
+            // A `key = value` line after the table's last pair.
+            let last_pair = node
+                .children
+                .iter()
+                .filter(|c| matches!(c.kind, Kind::Value | Kind::Inline | Kind::Dotted))
+                .map(Node::extent_end)
+                .max();
+            let (at, blank_after) = match (last_pair, node.kind, doc.headers.first()) {
+                (Some(end), _, _) => (line_end(text, end), false),
+                (None, Kind::Header, _) => (line_end(text, node.span.end), false),
+                (None, _, Some((_, first))) => {
+                    (with_leading_comments(text, line_start(text, *first)), true)
+                }
+                (None, _, None) => (text.len(), false),
+            };
+            let mut insertion = String::new();
+            if at > 0 && !text[..at].ends_with('\n') {
+                insertion.push('\n');
+            }
+            insertion.push_str(&pair);
+            if blank_after {
+                insertion.push('\n');
+            }
+            return Some(splice(text, at..at, &insertion));
+        }
+
+        // New `[table]` sections after the last section under `table`.
+        if !value.is_object() {
+            return None;
+        }
+        let mut full = table.to_vec();
+        full.push(rest[0].clone());
+        let sections = toml::to_string_pretty(&nest(&full, &value)).ok()?;
+        let at = if table.is_empty() {
+            text.len()
+        } else {
+            let (_, start) = doc.headers.iter().rfind(|(p, _)| p.starts_with(table))?;
+            doc.section_end(text, *start)
+        };
+        let before = &text[..at];
+        let mut insertion = String::new();
+        if !before.is_empty() && !before.ends_with("\n\n") {
+            insertion.push_str(if before.ends_with('\n') { "\n" } else { "\n\n" });
+        }
+        insertion.push_str(&sections);
+        if at < text.len() {
+            insertion.push('\n');
+        }
+        Some(splice(text, at..at, &insertion))
+    }
+}
+
+mod yaml_text {
+    //! YAML renames over the block-mapping layout `meta` writes: each key on
+    //! its own line, children indented under their parent. Anything else is
+    //! left to a full serialization.
+
+    use super::{line_end, splice, ConfigEdit};
+
+    /// The indentation of a line that holds content, or `None` for blank and
+    /// comment lines.
+    fn content_indent(line: &str) -> Option<usize> {
+        let trimmed = line.trim_start_matches(' ');
+        (!trimmed.trim().is_empty() && !trimmed.starts_with('#'))
+            .then_some(line.len() - trimmed.len())
+    }
+
+    /// The span of the mapping key `key` written at the start of `line`.
+    fn key_token(line: &str, key: &str) -> Option<std::ops::Range<usize>> {
+        let end = match line.as_bytes().first()? {
+            b'"' => serde_json::to_string(key)
+                .ok()
+                .filter(|quoted| line.starts_with(quoted.as_str()))?
+                .len(),
+            b'\'' => {
+                let quoted = format!("'{}'", key.replace('\'', "''"));
+                line.starts_with(&quoted).then_some(quoted.len())?
+            }
+            _ => line.starts_with(key).then_some(key.len())?,
+        };
+        let rest = &line[end..];
+        (rest.starts_with(':') && rest[1..].chars().next().is_none_or(char::is_whitespace))
+            .then_some(0..end)
+    }
+
+    /// `key` written as a plain scalar when that reads back as the same
+    /// string, else double-quoted.
+    fn render_key(key: &str) -> Option<String> {
+        let plain = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '/' | '.'))
+            && serde_yaml::from_str::<serde_yaml::Value>(key).ok()?
+                == serde_yaml::Value::String(key.to_string());
+        if plain {
+            Some(key.to_string())
+        } else {
+            serde_json::to_string(key).ok()
+        }
+    }
+
+    pub(super) fn apply(text: &str, edit: &ConfigEdit) -> Option<String> {
+        let ConfigEdit::Rename { path, to } = edit else {
+            return None;
+        };
+        // Narrow the search to the block under each key of the path in turn.
+        let mut start = 0;
+        let mut parent_indent: Option<usize> = None;
+        for (depth, key) in path.iter().enumerate() {
+            let mut child_indent = None;
+            let mut found = None;
+            let mut pos = start;
+            while pos < text.len() {
+                let next = line_end(text, pos);
+                let line = text[pos..next].trim_end_matches(['\n', '\r']);
+                if let Some(indent) = content_indent(line) {
+                    if parent_indent.is_some_and(|parent| indent <= parent) {
+                        break;
+                    }
+                    let expected = *child_indent.get_or_insert(indent);
+                    if indent == expected {
+                        if let Some(token) = key_token(&line[indent..], key) {
+                            found = Some((pos + indent, token, indent, next));
+                            break;
+                        }
+                    }
+                }
+                pos = next;
+            }
+            let (line_at, token, indent, next) = found?;
+            if depth + 1 == path.len() {
+                let range = line_at + token.start..line_at + token.end;
+                return Some(splice(text, range, &render_key(to)?));
+            }
+            start = next;
+            parent_indent = Some(indent);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProjectEntry, ProjectMetadata};
+
+    fn project(url: &str) -> ProjectMetadata {
+        match MetaConfig::load_from_str(
+            &format!(r#"{{"projects": {{"p": {{"url": "{}"}}}}}}"#, url),
+            ConfigFormat::Json,
+        )
+        .unwrap()
+        .projects
+        .remove("p")
+        {
+            Some(ProjectEntry::Metadata(metadata)) => metadata,
+            _ => unreachable!(),
+        }
+    }
+
+    fn edited(existing: &str, format: ConfigFormat, change: impl Fn(&mut MetaConfig)) -> String {
+        let mut config = MetaConfig::load_from_str(existing, format).unwrap();
+        change(&mut config);
+        let text = config.to_string_preserving(existing, format).unwrap();
+        assert_eq!(
+            serde_json::to_value(MetaConfig::load_from_str(&text, format).unwrap()).unwrap(),
+            serde_json::to_value(&config).unwrap(),
+            "edited text does not read back as the config:\n{}",
+            text
+        );
+        text
+    }
+
+    const JSON: &str = r#"{
+    "projects": {
+        "zeta": "git@github.com:acme/zeta.git",
+        "alpha": {
+            "url": "git@github.com:acme/alpha.git",
+            "aliases": ["backend"]
+        }
+    },
+    "ignore": [".env"]
+}
+"#;
+
+    #[test]
+    fn diffs_objects_key_by_key() {
+        let old = json!({ "a": 1, "b": { "c": [1], "d": 2 }, "e": null });
+        let new = json!({ "a": 1, "b": { "c": [1, 2] }, "f": "x", "g": null });
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                ConfigEdit::Set {
+                    path: vec!["b".into(), "c".into()],
+                    value: json!([1, 2]),
+                },
+                ConfigEdit::Remove {
+                    path: vec!["b".into(), "d".into()],
+                },
+                ConfigEdit::Set {
+                    path: vec!["f".into()],
+                    value: json!("x"),
+                },
+            ]
+        );
+        assert!(diff(&old, &old).is_empty());
+
+        let renamed = json!({ "p": { "b": 1, "c": 2 } });
+        assert_eq!(
+            diff(&json!({ "p": { "a": 1, "c": 2 } }), &renamed),
+            vec![ConfigEdit::Rename {
+                path: vec!["p".into(), "a".into()],
+                to: "b".into(),
+            }]
+        );
+        // A different value is a removal and an addition.
+        assert_eq!(diff(&json!({ "p": { "a": 3, "c": 2 } }), &renamed).len(), 2);
+    }
+
+    fn renamed(existing: &str, format: ConfigFormat, from: &str, to: &str) -> String {
+        edited(existing, format, |config| {
+            let entry = config.projects.remove(from).unwrap();
+            config.projects.insert(to.to_string(), entry);
+        })
+    }
+
+    #[test]
+    fn json_renames_keep_the_entry_in_place() {
+        assert_eq!(
+            renamed(JSON, ConfigFormat::Json, "zeta", "omega"),
+            JSON.replace("\"zeta\":", "\"omega\":")
+        );
+        assert_eq!(
+            renamed(JSON, ConfigFormat::Json, "alpha", "web/alpha"),
+            JSON.replace("\"alpha\":", "\"web/alpha\":")
+        );
+    }
+
+    #[test]
+    fn json_edits_keep_the_rest_of_the_file() {
+        let added = edited(JSON, ConfigFormat::Json, |config| {
+            config.projects.insert(
+                "mid".into(),
+                ProjectEntry::Url("git@github.com:acme/mid.git".into()),
+            );
+        });
+        assert_eq!(
+            added,
+            JSON.replace(
+                "[\"backend\"]\n        }",
+                "[\"backend\"]\n        },\n        \"mid\": \"git@github.com:acme/mid.git\""
+            )
+        );
+
+        let removed = edited(JSON, ConfigFormat::Json, |config| {
+            config.projects.remove("zeta");
+        });
+        assert_eq!(
+            removed,
+            JSON.replace("\"zeta\": \"git@github.com:acme/zeta.git\",\n        ", "")
+        );
+
+        let tagged = edited(JSON, ConfigFormat::Json, |config| {
+            if let Some(ProjectEntry::Metadata(alpha)) = config.projects.get_mut("alpha") {
+                alpha.aliases.push("rust".into());
+            }
+        });
+        assert_eq!(
+            tagged,
+            JSON.replace(
+                "[\"backend\"]",
+                "[\n                \"backend\",\n                \"rust\"\n            ]"
+            )
+        );
+    }
+
+    #[test]
+    fn json_inserts_into_empty_and_single_line_objects() {
+        let empty = "{\n  \"projects\": {}\n}\n";
+        let text = edited(empty, ConfigFormat::Json, |config| {
+            config
+                .projects
+                .insert("api".into(), ProjectEntry::Url("u".into()));
+        });
+        assert_eq!(text, "{\n  \"projects\": {\n    \"api\": \"u\"\n  }\n}\n");
+
+        let compact = r#"{"projects": {"api": "u"}}"#;
+        let text = edited(compact, ConfigFormat::Json, |config| {
+            config
+                .projects
+                .insert("web".into(), ProjectEntry::Url("w".into()));
+        });
+        assert_eq!(text, r#"{"projects": {"api": "u", "web": "w"}}"#);
+    }
+
+    const TOML: &str = r#"# Workspace for acme.
+ignore = [".env"] # never committed
+
+[projects]
+zeta = "git@github.com:acme/zeta.git"
+
+# The main service.
+[projects.alpha]
+url = "git@github.com:acme/alpha.git"
+aliases = ["backend"]
+
+[projects.alpha.scripts]
+test = "cargo test"
+"#;
+
+    #[test]
+    fn toml_edits_keep_comments_and_order() {
+        let added = edited(TOML, ConfigFormat::Toml, |config| {
+            config.projects.insert(
+                "mid".into(),
+                ProjectEntry::Url("git@github.com:acme/mid.git".into()),
+            );
+        });
+        assert_eq!(
+            added,
+            TOML.replace(
+                "zeta.git\"\n",
+                "zeta.git\"\nmid = \"git@github.com:acme/mid.git\"\n"
+            )
+        );
+
+        let tagged = edited(TOML, ConfigFormat::Toml, |config| {
+            if let Some(ProjectEntry::Metadata(alpha)) = config.projects.get_mut("alpha") {
+                alpha.aliases.push("rust".into());
+            }
+        });
+        assert_eq!(
+            tagged,
+            TOML.replace("[\"backend\"]", "[\"backend\", \"rust\"]")
+        );
+
+        let removed = edited(TOML, ConfigFormat::Toml, |config| {
+            config.projects.remove("alpha");
+        });
+        assert_eq!(removed, TOML[..TOML.find("\n\n# The main").unwrap() + 1]);
+    }
+
+    #[test]
+    fn toml_renames_keep_comments_and_order() {
+        let pairs = "[projects]\n# the web app\nweb = \"w.git\"\napi = \"a.git\"\n";
+        assert_eq!(
+            renamed(pairs, ConfigFormat::Toml, "web", "web2"),
+            pairs.replace("web =", "web2 =")
+        );
+
+        // A table is renamed in its header and the headers of its subtables.
+        assert_eq!(
+            renamed(TOML, ConfigFormat::Toml, "alpha", "services/alpha"),
+            TOML.replace("[projects.alpha", "[projects.\"services/alpha\"")
+        );
+    }
+
+    #[test]
+    fn toml_adds_tables_as_new_sections() {
+        let text = edited(TOML, ConfigFormat::Toml, |config| {
+            config
+                .projects
+                .insert("beta".into(), ProjectEntry::Metadata(project("b.git")));
+        });
+        assert!(text.starts_with(TOML), "{}", text);
+        let beta = &text[TOML.len()..];
+        assert!(beta.starts_with("\n[projects.beta]\n"), "{}", text);
+        assert!(beta.contains("\nurl = \"b.git\"\n"), "{}", text);
+
+        // A plain URL entry becoming a table moves into its own section.
+        let text = edited(TOML, ConfigFormat::Toml, |config| {
+            config
+                .projects
+                .insert("zeta".into(), ProjectEntry::Metadata(project("z.git")));
+        });
+        assert!(!text.contains("zeta = "), "{}", text);
+        assert!(text.contains("# Workspace for acme."));
+        assert!(text.contains("\n[projects.zeta]\n"), "{}", text);
+    }
+
+    #[test]
+    fn toml_gives_implicit_tables_a_header() {
+        let hand_written = "# Hand-edited\n[projects.web]\nurl = \"w.git\"\n";
+        let text = edited(hand_written, ConfigFormat::Toml, |config| {
+            config
+                .projects
+                .insert("api".into(), ProjectEntry::Url("a.git".into()));
+        });
+        assert_eq!(
+            text,
+            "# Hand-edited\n[projects]\napi = \"a.git\"\n\n[projects.web]\nurl = \"w.git\"\n"
+        );
+    }
+
+    #[test]
+    fn yaml_renames_keep_comments_and_order() {
+        let yaml = "projects:\n  # the web app\n  web: w.git\n  api:\n    url: a.git\n    \
+                    aliases:\n    - backend\nignore:\n- .env\n";
+        assert_eq!(
+            renamed(yaml, ConfigFormat::Yaml, "web", "web2"),
+            yaml.replace("web:", "web2:")
+        );
+        assert_eq!(
+            renamed(yaml, ConfigFormat::Yaml, "api", "api v2"),
+            yaml.replace("api:", "\"api v2\":")
+        );
+    }
+
+    #[test]
+    fn yaml_and_unparseable_text_are_reserialized() {
+        let yaml = "projects:\n  api: u\n";
+        let set = ConfigEdit::Set {
+            path: vec!["ignore".into()],
+            value: json!([".env"]),
+        };
+        assert!(apply(yaml, ConfigFormat::Yaml, &[set]).is_none());
+        let text = edited(yaml, ConfigFormat::Yaml, |config| {
+            config
+                .projects
+                .insert("web".into(), ProjectEntry::Url("w".into()));
+        });
+        assert!(text.contains("web: w"), "{}", text);
+
+        let config = MetaConfig::default();
+        let text = config
+            .to_string_preserving("{ not json", ConfigFormat::Json)
+            .unwrap();
+        assert_eq!(
+            text,
+            config_format::serialize_to_string(&config, ConfigFormat::Json).unwrap()
+        );
+    }
+}
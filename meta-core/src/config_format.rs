@@ -11,7 +11,8 @@ use std::path::Path;
 /// `.meta` files continue to work indefinitely.
 pub const CANONICAL_FILENAME: &str = ".metarepo";
 
-/// Legacy filename — predates the multi-format support. Always treated as JSON.
+/// Legacy filename — predates the multi-format support. Read as JSON unless
+/// its contents are TOML or YAML (see [`ConfigFormat::sniff`]).
 pub const LEGACY_FILENAME: &str = ".meta";
 
 /// Filenames probed in each ancestor directory during discovery. Ordering is
@@ -24,6 +25,10 @@ pub const KNOWN_FILENAMES: &[&str] = &[
     ".metarepo.yaml",
     ".metarepo.yml",
     ".metarepo.toml",
+    ".meta.json",
+    ".meta.yaml",
+    ".meta.yml",
+    ".meta.toml",
 ];
 
 /// On-disk serialization format for a metarepo config file.
@@ -64,6 +69,41 @@ impl ConfigFormat {
         }
     }
 
+    /// Guess the format of an extensionless config from its contents: for
+    /// anything but a JSON object, TOML or YAML when it parses as a TOML table
+    /// or YAML mapping. Everything else is JSON, so a broken JSON file still
+    /// reports JSON errors.
+    pub fn sniff(content: &str) -> Self {
+        let trimmed = content.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('{') {
+            return ConfigFormat::Json;
+        }
+        if toml::from_str::<toml::Table>(content).is_ok() {
+            ConfigFormat::Toml
+        } else if serde_yaml::from_str::<serde_yaml::Mapping>(content).is_ok() {
+            ConfigFormat::Yaml
+        } else {
+            ConfigFormat::Json
+        }
+    }
+
+    /// Detect the format of the config file at `path`. Like [`from_path`],
+    /// except that an existing extensionless file (`.metarepo`, `.meta`) is
+    /// [sniffed](Self::sniff), so a hand-converted `.meta` keeps working.
+    ///
+    /// [`from_path`]: Self::from_path
+    pub fn of_file(path: &Path) -> Option<Self> {
+        let format = Self::from_path(path)?;
+        let name = path.file_name()?.to_str()?;
+        if name != CANONICAL_FILENAME && name != LEGACY_FILENAME {
+            return Some(format);
+        }
+        match std::fs::read_to_string(path) {
+            Ok(content) => Some(Self::sniff(&content)),
+            Err(_) => Some(format),
+        }
+    }
+
     /// Map a user-supplied format name (case-insensitive) to the enum.
     pub fn parse(name: &str) -> Result<Self> {
         match name.to_ascii_lowercase().as_str() {
@@ -173,6 +213,35 @@ mod tests {
         assert_eq!(ConfigFormat::from_path(&PathBuf::from(".rando")), None);
     }
 
+    #[test]
+    fn sniffs_extensionless_configs() {
+        assert_eq!(ConfigFormat::sniff(""), ConfigFormat::Json);
+        assert_eq!(
+            ConfigFormat::sniff("{\"projects\": {}}"),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::sniff("# workspace\n[projects]\nweb = \"git@h:web.git\"\n"),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::sniff("projects:\n  web: git@h:web.git\n"),
+            ConfigFormat::Yaml
+        );
+        // Broken JSON stays JSON so the error names the real problem.
+        assert_eq!(ConfigFormat::sniff("{\"projects\": {"), ConfigFormat::Json);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let meta = tmp.path().join(".meta");
+        assert_eq!(ConfigFormat::of_file(&meta), Some(ConfigFormat::Json));
+        std::fs::write(&meta, "[projects]\n").unwrap();
+        assert_eq!(ConfigFormat::of_file(&meta), Some(ConfigFormat::Toml));
+        assert_eq!(
+            ConfigFormat::of_file(&tmp.path().join(".meta.toml")),
+            Some(ConfigFormat::Toml)
+        );
+    }
+
     #[test]
     fn parses_format_names() {
         assert_eq!(ConfigFormat::parse("json").unwrap(), ConfigFormat::Json);
@@ -0,0 +1,364 @@
+//! Shared config fragments: the `extends` list in `.meta`.
+//!
+//! Workspaces that share scripts, ignore patterns, or settings list the
+//! fragments they build on, and the fragments are merged underneath the file
+//! at load time:
+//!
+//! ```json
+//! "extends": [
+//!   "../shared/base.meta.json",
+//!   { "git": "git@github.com:acme/meta-shared.git", "path": "node.meta.yaml", "ref": "v2" }
+//! ]
+//! ```
+//!
+//! Precedence, lowest first: each fragment in list order (a fragment's own
+//! `extends` are merged underneath it), then the file itself. Objects merge
+//! key by key, so a workspace can override one script or one setting and
+//! inherit the rest; other values are replaced by the later source. The
+//! `ignore`, `disabled`, and `default_excludes` lists are combined instead.
+//! Unset (`null`) and empty values never override inherited ones.
+//!
+//! Only reads see the merged result: commands that edit `.meta` load and save
+//! the file itself, so fragments are never copied into it.
+
+use crate::{ConfigFormat, MetaConfig};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Lists that accumulate across fragments rather than being replaced.
+const COMBINED_LISTS: &[&str] = &["ignore", "disabled", "default_excludes"];
+
+/// One entry of `extends`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ExtendsEntry {
+    /// A config file, relative to the config that lists it.
+    Path(String),
+    /// A config file in a git repository, cloned once into the user's cache
+    /// (`$XDG_CACHE_HOME/metarepo/extends`) and refreshed on request.
+    Git {
+        git: String,
+        /// File within the repository.
+        path: String,
+        /// Branch or tag to clone (default: the remote's default branch).
+        #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+        reference: Option<String>,
+    },
+}
+
+impl std::fmt::Display for ExtendsEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtendsEntry::Path(path) => write!(f, "{}", path),
+            ExtendsEntry::Git {
+                git,
+                path,
+                reference,
+            } => match reference {
+                Some(reference) => write!(f, "{}@{}:{}", git, reference, path),
+                None => write!(f, "{}:{}", git, path),
+            },
+        }
+    }
+}
+
+fn cache_root() -> Result<PathBuf> {
+    let base = match std::env::var_os("XDG_CACHE_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".cache"))
+            .ok_or_else(|| anyhow!("Cannot locate a cache directory for git-hosted extends"))?,
+    };
+    Ok(base.join("metarepo").join("extends"))
+}
+
+/// Clone (or, with `refresh`, re-clone) `git` at `reference` under `cache`,
+/// returning the checkout.
+fn checkout(cache: &Path, git: &str, reference: Option<&str>, refresh: bool) -> Result<PathBuf> {
+    let key: String = format!("{}@{}", git, reference.unwrap_or("HEAD"))
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let dir = cache.join(key);
+    if dir.exists() && !refresh {
+        return Ok(dir);
+    }
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::create_dir_all(cache)?;
+
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--quiet", "--depth", "1"]);
+    if let Some(reference) = reference {
+        cmd.args(["--branch", reference]);
+    }
+    let output = cmd
+        .arg(git)
+        .arg(&dir)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to clone {}: {}",
+            git,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(dir)
+}
+
+/// Merge `overlay` onto `base`; see the module docs for the rules.
+pub(crate) fn merge(base: &mut Value, overlay: Value, top_level: bool) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                if value.is_null() || value.as_array().is_some_and(|a| a.is_empty()) {
+                    continue;
+                }
+                match base.get_mut(&key) {
+                    Some(Value::Array(existing))
+                        if top_level && COMBINED_LISTS.contains(&key.as_str()) =>
+                    {
+                        for item in value.as_array().into_iter().flatten() {
+                            if !existing.contains(item) {
+                                existing.push(item.clone());
+                            }
+                        }
+                    }
+                    Some(existing) => merge(existing, value, false),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// `config` (read from `path`) with its fragments merged underneath, as JSON.
+/// `stack` holds the files being resolved, to catch cycles.
+fn resolve(
+    config: &MetaConfig,
+    path: &Path,
+    cache: &Path,
+    refresh: bool,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Value> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut merged = Value::Object(Default::default());
+    for entry in &config.extends {
+        let file = match entry {
+            ExtendsEntry::Path(file) => dir.join(file),
+            ExtendsEntry::Git {
+                git,
+                path,
+                reference,
+            } => {
+                let checkout = checkout(cache, git, reference.as_deref(), refresh)?;
+                crate::ensure_within_base(&checkout, &checkout.join(path))?
+            }
+        };
+        let canonical = file
+            .canonicalize()
+            .with_context(|| format!("Config fragment not found: {}", entry))?;
+        if stack.contains(&canonical) {
+            return Err(anyhow!(
+                "Config fragments extend each other in a cycle at {}",
+                file.display()
+            ));
+        }
+
+        // Fragments need not use a config filename: go by the extension, or
+        // failing that the contents.
+        let content = std::fs::read_to_string(&file)?;
+        let format = ConfigFormat::of_file(&file)
+            .or_else(|| {
+                let ext = file.extension()?.to_str()?;
+                ConfigFormat::parse(ext).ok()
+            })
+            .unwrap_or_else(|| ConfigFormat::sniff(&content));
+        let fragment = MetaConfig::load_from_str(&content, format)
+            .with_context(|| format!("Failed to load config fragment {}", entry))?;
+        stack.push(canonical);
+        let mut resolved = resolve(&fragment, &file, cache, refresh, stack)?;
+        stack.pop();
+        // Only the top-level file's own list is kept, as a record of sources.
+        if let Some(object) = resolved.as_object_mut() {
+            object.remove("extends");
+        }
+        merge(&mut merged, resolved, true);
+    }
+    merge(&mut merged, serde_json::to_value(config)?, true);
+    Ok(merged)
+}
+
+fn resolve_into_config(
+    config: &MetaConfig,
+    path: &Path,
+    cache: &Path,
+    refresh: bool,
+) -> Result<MetaConfig> {
+    if config.extends.is_empty() {
+        return Ok(config.clone());
+    }
+    let start = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let merged = resolve(config, path, cache, refresh, &mut vec![start])?;
+    let mut resolved: MetaConfig = serde_json::from_value(merged)?;
+    resolved.sanitize_after_load();
+    Ok(resolved)
+}
+
+impl MetaConfig {
+    /// This config (read from `path`) with the fragments listed in `extends`
+    /// merged underneath it. Git-hosted fragments are cloned on first use;
+    /// `refresh` clones them again to pick up upstream changes. A config
+    /// without `extends` is returned unchanged.
+    pub fn resolve_extends(&self, path: &Path, refresh: bool) -> Result<MetaConfig> {
+        let cache = if self.extends.is_empty() {
+            PathBuf::new()
+        } else {
+            cache_root()?
+        };
+        resolve_into_config(self, path, &cache, refresh)
+    }
+
+    /// Load the config file at `path` with its `extends` resolved. Use
+    /// [`MetaConfig::load_from_file`] instead when the config will be saved.
+    pub fn load_resolved<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        Self::load_from_file(path)?.resolve_extends(path, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScriptEntry;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn script(config: &MetaConfig, name: &str) -> Option<String> {
+        config
+            .scripts
+            .as_ref()?
+            .get(name)
+            .map(ScriptEntry::command)
+            .map(str::to_string)
+    }
+
+    #[test]
+    fn fragments_merge_underneath_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write(
+            &root.join("shared/base.meta.json"),
+            r#"{"ignore": ["node_modules"], "scripts": {"lint": "eslint .", "test": "npm test"},
+                "notify": {"on_complete_secs": 60, "desktop": true}}"#,
+        );
+        write(
+            &root.join("shared/rust.meta.yaml"),
+            "extends: [base.meta.json]\nignore: [target]\nscripts:\n  test: cargo test\n",
+        );
+        let meta = root.join("ws/.meta");
+        write(
+            &meta,
+            r#"{"extends": ["../shared/rust.meta.yaml"], "ignore": [".git"],
+                "scripts": {"lint": "cargo clippy"}, "notify": {"on_complete_secs": 5},
+                "projects": {"api": "git@h:api.git"}}"#,
+        );
+
+        let resolved = MetaConfig::load_resolved(&meta).unwrap();
+        assert_eq!(resolved.ignore, ["node_modules", "target", ".git"]);
+        assert_eq!(script(&resolved, "lint").as_deref(), Some("cargo clippy"));
+        assert_eq!(script(&resolved, "test").as_deref(), Some("cargo test"));
+        let notify = resolved.notify.unwrap();
+        assert_eq!(notify.on_complete_secs, Some(5));
+        assert_eq!(notify.desktop, Some(true));
+        assert!(resolved.projects.contains_key("api"));
+        assert_eq!(
+            resolved.extends,
+            [ExtendsEntry::Path("../shared/rust.meta.yaml".into())]
+        );
+
+        // The file itself is untouched by loading.
+        let raw = MetaConfig::load_from_file(&meta).unwrap();
+        assert_eq!(script(&raw, "test"), None);
+    }
+
+    #[test]
+    fn cycles_and_missing_fragments_are_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write(&root.join("a/.meta"), r#"{"extends": ["../b/.meta"]}"#);
+        write(&root.join("b/.meta"), r#"{"extends": ["../a/.meta"]}"#);
+        let err = MetaConfig::load_resolved(root.join("a/.meta")).unwrap_err();
+        assert!(err.to_string().contains("cycle"), "{}", err);
+
+        write(&root.join(".meta"), r#"{"extends": ["missing.json"]}"#);
+        assert!(MetaConfig::load_resolved(root.join(".meta")).is_err());
+    }
+
+    #[test]
+    fn git_fragments_are_cloned_into_the_cache() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let git = |dir: &Path, args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(dir)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        let shared = root.join("shared");
+        write(
+            &shared.join("base.meta.json"),
+            r#"{"scripts": {"test": "make test"}}"#,
+        );
+        git(&shared, &["init", "-q", "-b", "main"]);
+        git(&shared, &["add", "."]);
+        git(&shared, &["commit", "-qm", "base"]);
+
+        let url = format!("file://{}", shared.display());
+        let meta = root.join("ws/.meta");
+        let fragment = serde_json::json!({"git": &url, "path": "base.meta.json", "ref": "main"});
+        write(
+            &meta,
+            &serde_json::json!({ "extends": [fragment] }).to_string(),
+        );
+        let cache = root.join("cache");
+        let config = MetaConfig::load_from_file(&meta).unwrap();
+        let resolved = resolve_into_config(&config, &meta, &cache, false).unwrap();
+        assert_eq!(script(&resolved, "test").as_deref(), Some("make test"));
+
+        // Upstream changes are picked up on refresh only.
+        write(
+            &shared.join("base.meta.json"),
+            r#"{"scripts": {"test": "just test"}}"#,
+        );
+        git(&shared, &["commit", "-qam", "just"]);
+        let cached = resolve_into_config(&config, &meta, &cache, false).unwrap();
+        assert_eq!(script(&cached, "test").as_deref(), Some("make test"));
+        let refreshed = resolve_into_config(&config, &meta, &cache, true).unwrap();
+        assert_eq!(script(&refreshed, "test").as_deref(), Some("just test"));
+
+        let escaping = MetaConfig {
+            extends: vec![ExtendsEntry::Git {
+                git: url.clone(),
+                path: "../../outside.json".into(),
+                reference: None,
+            }],
+            ..Default::default()
+        };
+        assert!(resolve_into_config(&escaping, &meta, &cache, false).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Project groups: named, described sets of projects.
+//!
+//! ```json
+//! "groups": {
+//!   "backend": {
+//!     "description": "The API and the services behind it",
+//!     "projects": ["api", "services/*"],
+//!     "default_script": "test"
+//!   }
+//! }
+//! ```
+//!
+//! Members are project selectors, as `--projects` takes them. `--group <name>`
+//! on `exec`, `run`, `git status`/`git pull`, and the worktree commands selects
+//! a group's members, and `meta run --group <name>` with no script runs the
+//! group's `default_script`. Unlike a profile, a group does not narrow the
+//! workspace; it is only a selection.
+
+use crate::MetaConfig;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// One entry in the `groups` block of `.meta`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ProjectGroup {
+    /// What the group is for, shown by `meta project group list`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Member selectors: project keys, aliases, basenames, or globs.
+    #[serde(default)]
+    pub projects: Vec<String>,
+    /// Script `meta run --group <name>` runs when no script is named.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_script: Option<String>,
+}
+
+impl MetaConfig {
+    /// The group names, sorted.
+    pub fn group_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .groups
+            .as_ref()
+            .map(|g| g.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// Group `name`, or an error listing the groups there are.
+    pub fn group(&self, name: &str) -> Result<&ProjectGroup> {
+        self.groups
+            .as_ref()
+            .and_then(|g| g.get(name))
+            .ok_or_else(|| {
+                let names = self.group_names();
+                if names.is_empty() {
+                    anyhow!("Unknown group '{}': the workspace defines no groups", name)
+                } else {
+                    anyhow!(
+                        "Unknown group '{}'. Available groups: {}",
+                        name,
+                        names.join(", ")
+                    )
+                }
+            })
+    }
+
+    /// The project keys in group `name`, sorted. Members that match no
+    /// project are ignored, so a group survives a project being removed.
+    pub fn group_projects(&self, name: &str) -> Result<Vec<String>> {
+        let group = self.group(name)?;
+        let mut projects = self.select_projects(&group.projects).projects;
+        projects.sort();
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConfigFormat, MetaConfig};
+
+    fn workspace() -> MetaConfig {
+        MetaConfig::load_from_str(
+            r#"{
+                "projects": {
+                    "web": "git@github.com:acme/web.git",
+                    "api": "git@github.com:acme/api.git",
+                    "services/billing": "git@github.com:acme/billing.git"
+                },
+                "groups": {
+                    "backend": {
+                        "description": "Server side",
+                        "projects": ["services/*", "api", "gone"],
+                        "default_script": "test"
+                    },
+                    "frontend": { "projects": ["web"] }
+                }
+            }"#,
+            ConfigFormat::Json,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn groups_resolve_members_to_project_keys() {
+        let config = workspace();
+        assert_eq!(config.group_names(), vec!["backend", "frontend"]);
+        assert_eq!(
+            config.group_projects("backend").unwrap(),
+            vec!["api", "services/billing"]
+        );
+        let backend = config.group("backend").unwrap();
+        assert_eq!(backend.description.as_deref(), Some("Server side"));
+        assert_eq!(backend.default_script.as_deref(), Some("test"));
+        assert_eq!(config.group("frontend").unwrap().default_script, None);
+    }
+
+    #[test]
+    fn unknown_groups_are_rejected_with_the_choices() {
+        let mut config = workspace();
+        let err = config.group("mobile").unwrap_err().to_string();
+        assert!(err.contains("backend, frontend"), "{}", err);
+
+        config.groups = None;
+        let err = config.group_projects("backend").unwrap_err().to_string();
+        assert!(err.contains("defines no groups"), "{}", err);
+    }
+}
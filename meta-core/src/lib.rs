@@ -1,3 +1,6 @@
+// The config schema is one large `json!` literal.
+#![recursion_limit = "256"]
+
 use anyhow::Result;
 use clap::{ArgMatches, Command};
 use serde::{Deserialize, Serialize};
@@ -5,19 +8,29 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 // New plugin system modules
+pub mod api;
+pub mod config_edit;
 pub mod config_format;
 pub mod config_setting;
+mod extends;
+mod groups;
 pub mod interactive;
 mod module_manifest;
+pub mod output;
 mod plugin_base;
 mod plugin_builder;
 mod plugin_manifest;
+mod profiles;
 pub mod protocol;
+mod schema;
 pub mod security;
 pub mod tui;
+mod user_config;
 
 pub use config_format::{ConfigFormat, CANONICAL_FILENAME, KNOWN_FILENAMES, LEGACY_FILENAME};
 pub use config_setting::{ConfigSetting, ConfigValueType};
+pub use extends::ExtendsEntry;
+pub use groups::ProjectGroup;
 pub use interactive::{
     is_interactive, prompt_confirm, prompt_multiselect, prompt_select, prompt_text, prompt_url,
     NonInteractiveMode,
@@ -25,6 +38,7 @@ pub use interactive::{
 pub use module_manifest::{
     MetaModuleManifest, ModuleInfo, ModulePluginRef, ModuleSkillRef, MODULE_MANIFEST_FILENAMES,
 };
+pub use output::{hyperlink, strip_hyperlinks, Align, Table};
 pub use plugin_base::{
     ArgumentInfo, BasePlugin, CommandInfo, HelpFormat, HelpFormatter, JsonHelpFormatter,
     MarkdownHelpFormatter, PluginMetadata, TerminalHelpFormatter, YamlHelpFormatter,
@@ -37,10 +51,13 @@ pub use plugin_manifest::{
     ArgValueType, Dependency, Example, ExecutionConfig, ManifestArg, ManifestCommand, PluginConfig,
     PluginInfo, PluginManifest, MANIFEST_FILENAMES,
 };
+pub use profiles::PROFILE_ENV;
+pub use schema::config_json_schema;
 pub use security::{
     canonicalize_creatable, ensure_within_base, is_dangerous_env_var, is_supported_git_url,
     is_unencrypted_git_scheme, validate_path_segment, validate_project_url, DANGEROUS_ENV_VARS,
 };
+pub use user_config::{config_origins, user_config_path, ConfigOrigin, USER_CONFIG_ENV};
 
 /// Trait that all meta plugins must implement
 pub trait MetaPlugin: Send + Sync {
@@ -86,10 +103,37 @@ pub struct RuntimeConfig {
     /// When true, multi-project commands operate on every project regardless of
     /// the current directory (set by the global `--workspace`/`-w` flag).
     pub scope_workspace: bool,
+    /// The workspace profile selected with `--profile`, if any. `meta_config`
+    /// is already narrowed to its projects.
+    pub profile: Option<String>,
     /// Aggregated configuration settings declared by all registered plugins
     /// (see [`MetaPlugin::settings`]). Populated by the host before dispatch so
     /// the `config` command can list/validate them. Empty by default.
     pub settings_catalog: Vec<ConfigSetting>,
+    /// Top-level commands declared by more than one plugin, as the host's
+    /// plugin registry resolved them. Populated before dispatch so `meta
+    /// plugin list` can show them. Empty by default.
+    pub plugin_conflicts: Vec<PluginConflict>,
+}
+
+/// A top-level command that more than one plugin declared. Built-in plugins
+/// always keep theirs; between external plugins `plugin_priority` in the
+/// config decides, else the first loaded (those declared in `.metarepo`
+/// before discovered ones, each in name order).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginConflict {
+    /// The contested command (or alias).
+    pub command: String,
+    /// The plugin that kept it.
+    pub winner: String,
+    pub winner_builtin: bool,
+    /// The external plugin that lost it.
+    pub loser: String,
+    /// Whether the loser is still reachable as `meta ext <loser>`; not when
+    /// another external plugin has the same name.
+    pub namespaced: bool,
+    /// The winner was chosen by `plugin_priority`.
+    pub pinned: bool,
 }
 
 impl RuntimeConfig {
@@ -101,6 +145,16 @@ impl RuntimeConfig {
         meta_root_of(self.meta_file_path.as_deref())
     }
 
+    /// [`Self::meta_root`], for commands that cannot run outside a workspace:
+    /// without a config, an error telling the user how to get one.
+    pub fn require_meta_root(&self) -> Result<PathBuf> {
+        self.meta_root().ok_or_else(|| {
+            anyhow::anyhow!(
+                "No metarepo config found. Run 'meta init' first, or pass --config <path>."
+            )
+        })
+    }
+
     pub fn is_experimental(&self) -> bool {
         self.experimental
     }
@@ -134,6 +188,12 @@ impl RuntimeConfig {
         self.meta_config.resolve_identifier(identifier)
     }
 
+    /// Resolve project selectors (names, aliases, or globs). Delegates to
+    /// [`MetaConfig::select_projects`].
+    pub fn select_projects<S: AsRef<str>>(&self, selectors: &[S]) -> ProjectSelection {
+        self.meta_config.select_projects(selectors)
+    }
+
     /// Get all valid identifiers for a project (full name, basename, aliases)
     pub fn project_identifiers(&self, project_name: &str) -> Vec<String> {
         let mut identifiers = vec![project_name.to_string()];
@@ -280,6 +340,116 @@ pub fn pattern_matches(text: &str, pattern: &str) -> bool {
     true
 }
 
+/// Whether a project selector uses glob syntax (`*`, `?`, `[...]`) rather than
+/// naming a single project.
+pub fn is_glob(selector: &str) -> bool {
+    selector.contains(['*', '?', '['])
+}
+
+/// Match a project key against a path-style glob. `*` and `?` stay within one
+/// path segment, `**` spans any number of segments (including none when
+/// followed by `/`), and `[abc]` / `[a-z]` / `[!a]` match character classes.
+/// Unlike [`pattern_matches`] there is no substring fallback: the whole key
+/// must match.
+pub fn glob_matches(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+    glob_match_from(&pattern, &key)
+}
+
+fn glob_match_from(p: &[char], t: &[char]) -> bool {
+    match p.first() {
+        None => t.is_empty(),
+        Some('*') if p.get(1) == Some(&'*') => {
+            let rest = &p[2..];
+            // `**/` also matches zero segments: `libs/**/api` matches `libs/api`.
+            if rest.first() == Some(&'/') && glob_match_from(&rest[1..], t) {
+                return true;
+            }
+            (0..=t.len()).any(|i| glob_match_from(rest, &t[i..]))
+        }
+        Some('*') => {
+            for i in 0..=t.len() {
+                if glob_match_from(&p[1..], &t[i..]) {
+                    return true;
+                }
+                if i < t.len() && t[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => match t.first() {
+            Some(c) if *c != '/' => glob_match_from(&p[1..], &t[1..]),
+            _ => false,
+        },
+        Some('[') => {
+            let Some(close) = p.iter().skip(1).position(|c| *c == ']').map(|i| i + 1) else {
+                // No closing bracket: treat `[` literally.
+                return t.first() == Some(&'[') && glob_match_from(&p[1..], &t[1..]);
+            };
+            let Some(c) = t.first() else {
+                return false;
+            };
+            let class = &p[1..close];
+            let (negated, class) = match class.first() {
+                Some('!') | Some('^') => (true, &class[1..]),
+                _ => (false, class),
+            };
+            let mut hit = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    hit |= class[i] <= *c && *c <= class[i + 2];
+                    i += 3;
+                } else {
+                    hit |= class[i] == *c;
+                    i += 1;
+                }
+            }
+            hit != negated && *c != '/' && glob_match_from(&p[close + 1..], &t[1..])
+        }
+        Some(c) => t.first() == Some(c) && glob_match_from(&p[1..], &t[1..]),
+    }
+}
+
+/// The outcome of resolving project selectors (keys, aliases, basenames, or
+/// globs) against a workspace. See [`MetaConfig::select_projects`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectSelection {
+    /// Matched project keys, in selector order without duplicates.
+    pub projects: Vec<String>,
+    /// Selectors that matched no project.
+    pub unmatched: Vec<String>,
+}
+
+impl ProjectSelection {
+    /// Turn the selection into the project list a command should act on. It is
+    /// an error when nothing matched at all, and — with `strict` — when any
+    /// single selector matched nothing.
+    pub fn into_projects(self, strict: bool) -> Result<Vec<String>> {
+        if self.unmatched.is_empty() {
+            return Ok(self.projects);
+        }
+        let listed = self
+            .unmatched
+            .iter()
+            .map(|s| format!("'{}'", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if self.projects.is_empty() {
+            return Err(anyhow::anyhow!("No projects match {}", listed));
+        }
+        if strict {
+            return Err(anyhow::anyhow!(
+                "No projects match {} (--strict rejects partial matches)",
+                listed
+            ));
+        }
+        Ok(self.projects)
+    }
+}
+
 /// Configuration for nested repository handling
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NestedConfig {
@@ -297,6 +467,104 @@ pub struct NestedConfig {
     pub namespace_separator: Option<String>,
     #[serde(default)]
     pub preserve_structure: bool,
+    /// How a recursive import treats a repository that already appears under
+    /// another name (matched by [`canonical_repo_url`]).
+    #[serde(default)]
+    pub dedupe: DedupeMode,
+}
+
+/// Handling of duplicate repositories across nested `.meta` files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupeMode {
+    /// Share the first clone: later occurrences become symlinks to it.
+    Link,
+    /// Clone each occurrence but print a consolidation suggestion.
+    #[default]
+    Warn,
+    /// Clone each occurrence silently (pre-dedupe behavior).
+    Off,
+}
+
+/// A remote URL split into its parts: `scheme://[user@]host[:port]/path` or
+/// scp-like `[user@]host:path`. User info is dropped, and the path loses its
+/// surrounding `/` and a trailing `.git`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteUrl<'a> {
+    /// `None` for the scp-like form.
+    pub scheme: Option<&'a str>,
+    pub host: &'a str,
+    pub port: Option<&'a str>,
+    pub path: &'a str,
+}
+
+impl<'a> RemoteUrl<'a> {
+    /// Split `url`, or `None` when it is a plain filesystem path.
+    pub fn parse(url: &'a str) -> Option<Self> {
+        let (scheme, authority, path) = match url.split_once("://") {
+            // Only the authority can carry user info, so an `@` in the path
+            // is left alone.
+            Some((scheme, rest)) => {
+                let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+                (Some(scheme), authority, path)
+            }
+            None => {
+                let (authority, path) = url.split_once(':')?;
+                if authority.contains('/') || path.starts_with("//") {
+                    return None;
+                }
+                (None, authority, path)
+            }
+        };
+        let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+        let (host, port) = match host.rsplit_once(':') {
+            Some((name, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => {
+                (name, Some(port))
+            }
+            _ => (host, None),
+        };
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        Some(Self {
+            scheme,
+            host,
+            port,
+            path,
+        })
+    }
+}
+
+/// Normalize a repository URL so different spellings of the same remote
+/// compare equal: `https://github.com/Acme/web.git`, `git@github.com:Acme/web`
+/// and `ssh://git@github.com/Acme/web/` all become `github.com/Acme/web`.
+/// The host is lowercased and a scheme's default port (`:22` for ssh, `:443`
+/// for https) is dropped; the path keeps its case. `external:` prefixes are
+/// stripped. Returns `None` for `local:` entries, which have no remote.
+pub fn canonical_repo_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let url = url.strip_prefix("external:").unwrap_or(url);
+    if url.is_empty() || url.starts_with("local:") {
+        return None;
+    }
+
+    let Some(remote) = RemoteUrl::parse(url) else {
+        // Plain filesystem path: compare as-is.
+        return Some(url.trim_end_matches('/').to_string());
+    };
+    let default_port = match remote.scheme.map(str::to_ascii_lowercase).as_deref() {
+        Some("ssh" | "git+ssh" | "ssh+git") => Some("22"),
+        Some("https") => Some("443"),
+        Some("http") => Some("80"),
+        Some("git") => Some("9418"),
+        _ => None,
+    };
+    let host = remote.host.to_ascii_lowercase();
+    match remote.port {
+        Some(port) if Some(port) != default_port => {
+            Some(format!("{}:{}/{}", host, port, remote.path))
+        }
+        _ => Some(format!("{}/{}", host, remote.path)),
+    }
 }
 
 fn default_recursive_import() -> bool {
@@ -319,6 +587,7 @@ impl Default for NestedConfig {
             ignore_nested: Vec::new(),
             namespace_separator: None,
             preserve_structure: false,
+            dedupe: DedupeMode::default(),
         }
     }
 }
@@ -326,6 +595,7 @@ impl Default for NestedConfig {
 /// Project metadata including scripts and configuration
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum ProjectEntry {
     /// Simple string format (backwards compatible)
     Url(String),
@@ -333,20 +603,227 @@ pub enum ProjectEntry {
     Metadata(ProjectMetadata),
 }
 
+/// A script in `scripts`: either a bare command string, or an object adding
+/// discovery metadata shown by `meta run --list` and shell completion:
+///
+/// ```json
+/// "scripts": {
+///   "test": "cargo test",
+///   "deploy": { "cmd": "./deploy.sh", "description": "Ship to staging", "tags": ["ops"] }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ScriptEntry {
+    /// Plain command string (backwards compatible)
+    Command(String),
+    /// Command with a description and tags
+    Detailed(ScriptSpec),
+}
+
+/// The object form of a [`ScriptEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptSpec {
+    pub cmd: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ScriptEntry {
+    /// The command line to run.
+    pub fn command(&self) -> &str {
+        match self {
+            ScriptEntry::Command(cmd) => cmd,
+            ScriptEntry::Detailed(spec) => &spec.cmd,
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            ScriptEntry::Command(_) => None,
+            ScriptEntry::Detailed(spec) => spec.description.as_deref(),
+        }
+    }
+
+    pub fn tags(&self) -> &[String] {
+        match self {
+            ScriptEntry::Command(_) => &[],
+            ScriptEntry::Detailed(spec) => &spec.tags,
+        }
+    }
+
+    /// Replace the command, keeping any description and tags.
+    pub fn set_command(&mut self, cmd: impl Into<String>) {
+        match self {
+            ScriptEntry::Command(existing) => *existing = cmd.into(),
+            ScriptEntry::Detailed(spec) => spec.cmd = cmd.into(),
+        }
+    }
+}
+
+impl From<String> for ScriptEntry {
+    fn from(cmd: String) -> Self {
+        ScriptEntry::Command(cmd)
+    }
+}
+
+impl From<&str> for ScriptEntry {
+    fn from(cmd: &str) -> Self {
+        ScriptEntry::Command(cmd.to_string())
+    }
+}
+
+impl PartialEq<str> for ScriptEntry {
+    fn eq(&self, other: &str) -> bool {
+        self.command() == other
+    }
+}
+
+impl PartialEq<&str> for ScriptEntry {
+    fn eq(&self, other: &&str) -> bool {
+        self.command() == *other
+    }
+}
+
+impl std::fmt::Display for ScriptEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.command())
+    }
+}
+
+/// Who a project may be shared with, set per project as `"visibility"`.
+/// `meta rules check` refuses dependencies from public projects on
+/// restricted ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Open source, or safe to publish.
+    Public,
+    /// Shared within the organization.
+    Internal,
+    /// Limited to the people who need it.
+    Restricted,
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Visibility::Public => "public",
+            Visibility::Internal => "internal",
+            Visibility::Restricted => "restricted",
+        })
+    }
+}
+
+/// The version-control system a project is checked out with, set per project
+/// as `"vcs"`. Git is the default and the only backend every command
+/// supports; the others take part in cloning, listing, status, and exec.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Vcs {
+    #[default]
+    Git,
+    /// Jujutsu.
+    Jj,
+    /// Mercurial.
+    Hg,
+    /// Sapling.
+    #[serde(alias = "sl")]
+    Sapling,
+}
+
+impl Vcs {
+    pub const ALL: [Vcs; 4] = [Vcs::Git, Vcs::Jj, Vcs::Hg, Vcs::Sapling];
+
+    /// The directory that marks a checkout made with this VCS.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Vcs::Git => ".git",
+            Vcs::Jj => ".jj",
+            Vcs::Hg => ".hg",
+            Vcs::Sapling => ".sl",
+        }
+    }
+}
+
+impl std::fmt::Display for Vcs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Vcs::Git => "git",
+            Vcs::Jj => "jj",
+            Vcs::Hg => "hg",
+            Vcs::Sapling => "sapling",
+        })
+    }
+}
+
+/// A project on its way out, set per project as `"deprecated"`. `meta
+/// project list` and `meta which` show it, `meta exec`/`meta run` warn when
+/// they target the project, and `meta doctor` flags it once `remove_after`
+/// has passed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deprecation {
+    /// When the project was deprecated: a date or a version, shown as given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// What to use instead, usually another project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    /// Date (`YYYY-MM-DD`) after which the project should be removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_after: Option<String>,
+}
+
+impl Deprecation {
+    /// One line describing the deprecation, e.g. `deprecated since 2.0; use
+    /// api-v2 instead; remove after 2025-06-30`.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![match &self.since {
+            Some(since) => format!("deprecated since {}", since),
+            None => "deprecated".to_string(),
+        }];
+        if let Some(replacement) = &self.replacement {
+            parts.push(format!("use {} instead", replacement));
+        }
+        if let Some(date) = &self.remove_after {
+            parts.push(format!("remove after {}", date));
+        }
+        parts.join("; ")
+    }
+
+    /// Whether `remove_after` lies before `today`; both are `YYYY-MM-DD`,
+    /// which order as strings.
+    pub fn is_overdue(&self, today: &str) -> bool {
+        self.remove_after
+            .as_deref()
+            .is_some_and(|date| date < today)
+    }
+}
+
 /// Detailed project metadata
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectMetadata {
     pub url: String,
     #[serde(default)]
     pub aliases: Vec<String>,
+    /// Labels for selecting projects with `--tag` (e.g. `meta exec --tag
+    /// backend`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     #[serde(default)]
-    pub scripts: HashMap<String, String>,
+    pub scripts: HashMap<String, ScriptEntry>,
     #[serde(default)]
     pub env: HashMap<String, String>,
     #[serde(default)]
     pub worktree_init: Option<String>,
     #[serde(default)]
     pub bare: Option<bool>,
+    /// Script `meta run` runs for this project when no script is named, and the
+    /// fallback for `meta start`/`meta dev`. Overrides the workspace default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_script: Option<String>,
     /// When `Some(false)`, this project is excluded from default and bulk
     /// operations (directory scope, `--all`, `--workspace`). It remains in the
     /// config and can be targeted explicitly with `--include-disabled`.
@@ -357,11 +834,35 @@ pub struct ProjectMetadata {
     /// re-clones (`meta git update`) stay shallow. `None` means a full clone.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub depth: Option<i32>,
+    /// A git ref (tag, branch, or commit) the project is frozen at, set with
+    /// `meta project pin`. Pulls and updates leave pinned projects at the pin
+    /// and only warn when the remote has moved on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<String>,
+    /// The commit the project was vendored from by `meta project vendor`: its
+    /// files are committed in the workspace repository instead of living in a
+    /// separate clone of `url`. `meta project unvendor` restores the clone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendored: Option<String>,
+    /// Access level of the project. `None` leaves it unclassified, which no
+    /// rule treats as public or restricted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+    /// Version-control system of the project; `None` means git.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs: Option<Vcs>,
+    /// Set when the project is being retired.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecation>,
 }
 
 /// The .meta file configuration format
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetaConfig {
+    /// Shared config fragments merged underneath this file at load time; see
+    /// [`ExtendsEntry`] and [`MetaConfig::resolve_extends`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extends: Vec<ExtendsEntry>,
     #[serde(default)]
     pub ignore: Vec<String>,
     #[serde(default)]
@@ -371,8 +872,36 @@ pub struct MetaConfig {
     /// all are normalized to canonical project keys so an alias cannot bypass it.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub disabled: Vec<String>,
+    /// Project selectors (keys, aliases, basenames, or globs) left out of
+    /// implicit and bulk selections — the directory-aware scope and `--all` —
+    /// but still reachable when named explicitly. Softer than `disabled`; meant
+    /// for archived or vendored projects.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_excludes: Vec<String>,
+    /// Named project subsets selected with the global `--profile` flag; each
+    /// value is a list of project selectors. See [`MetaConfig::apply_profile`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<HashMap<String, Vec<String>>>,
+    /// Named, described project groups selected with `--group`; see
+    /// [`ProjectGroup`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<HashMap<String, ProjectGroup>>,
     #[serde(default)]
     pub plugins: Option<HashMap<String, String>>, // name -> version/path
+    /// Which external plugin gets a top-level command that several declare:
+    /// command -> plugin name. The others stay reachable as
+    /// `meta ext <plugin>`; built-in commands cannot be taken this way.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin_priority: Option<HashMap<String, String>>,
+    /// Built-in plugins to leave out, by name (e.g. `rules`); their commands
+    /// disappear from meta.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_plugins: Vec<String>,
+    /// Built-in plugins replaced by an external plugin: built-in name ->
+    /// plugin name (`"override": {"git": "my-git-plugin"}`). The plugin gets
+    /// the built-in's commands; while it is not installed the built-in stays.
+    #[serde(default, rename = "override", skip_serializing_if = "Option::is_none")]
+    pub plugin_overrides: Option<HashMap<String, String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub modules: Option<HashMap<String, String>>, // module name -> repo-relative path
     #[serde(default)]
@@ -380,9 +909,25 @@ pub struct MetaConfig {
     #[serde(default)]
     pub aliases: Option<HashMap<String, String>>, // Global aliases: alias -> project_path
     #[serde(default)]
-    pub scripts: Option<HashMap<String, String>>, // Global scripts
+    pub scripts: Option<HashMap<String, ScriptEntry>>, // Global scripts
     #[serde(default)]
     pub worktree_init: Option<String>, // Global worktree post-create command
+    /// Naming rule for new branches created by `meta worktree add`; see
+    /// [`BranchPolicy`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_policy: Option<BranchPolicy>,
+    /// Project templates for `meta project new --template <name>`, keyed by
+    /// name; see [`ProjectTemplate`]. Usually set in the user config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates: Option<HashMap<String, ProjectTemplate>>,
+    /// Issue trackers whose references are linked where branch names are
+    /// shown; see [`IssueTracker`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issue_trackers: Vec<IssueTracker>,
+    /// Workspace-wide default for `meta run` with no script name; projects
+    /// override it with their own `default_script`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_script: Option<String>,
     #[serde(default)]
     pub default_bare: Option<bool>, // Global default for bare repository clones
     #[serde(rename = "plugins-integrity", default)]
@@ -410,11 +955,328 @@ pub struct MetaConfig {
     pub skill: Option<SkillSettings>, // `meta skill` configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mcp: Option<McpSettings>, // experimental `meta mcp serve` policy
+    #[serde(rename = "clone", default, skip_serializing_if = "Option::is_none")]
+    pub clone_settings: Option<CloneSettings>, // clone-time checks (the `clone` block)
+    #[serde(rename = "exec", default, skip_serializing_if = "Option::is_none")]
+    pub exec_settings: Option<ExecSettings>, // `meta exec` behavior (the `exec` block)
+    /// Named command lines for `meta exec @<name>`, whose arguments may use
+    /// `{name}`, `{path}`, `{branch}`, `{url}`, and the project's `env` values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_templates: Option<HashMap<String, String>>,
+    #[serde(rename = "run", default, skip_serializing_if = "Option::is_none")]
+    pub run_settings: Option<RunSettings>, // `meta run` behavior (the `run` block)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub onboard: Option<OnboardSettings>, // the `meta onboard` flow (the `onboard` block)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<NotifySettings>, // completion notifications (the `notify` block)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub badges: Option<BadgeSettings>, // README badges for `meta docs badges`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub todos: Option<TodoSettings>, // `meta todos` scanning (the `todos` block)
+    /// Files rendered from templates into projects by `meta files sync`; see
+    /// [`SyncFileSettings`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_files: Option<SyncFileSettings>,
+    /// Long-running services for `meta services up`, keyed by project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub services: Option<HashMap<String, ServiceSpec>>,
+    /// Minimum percentage of projects that must succeed for a multi-project
+    /// command to exit 0, keyed by command (`exec`, `run`, `start`, `dev`,
+    /// and `update` and `pull` for `meta git`).
+    /// `--min-success` overrides it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_success: Option<HashMap<String, f64>>,
+    /// Dangerous operations that must be confirmed by typing the workspace
+    /// name (or with `--yes-i-know`): `force-push` (`meta git push --force`),
+    /// `remove` (`meta project remove --force`), and `clean`
+    /// (`meta git clean -f`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected: Vec<String>,
     /// Per-command `helpDescription` overrides keyed by dotted command path
     /// (e.g. "project" or "project.add"). A user-set entry replaces whatever the
     /// plugin/module declared for that command's man-page `Description:` section.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub help_descriptions: Option<HashMap<String, String>>,
+    /// Worker threads for meta's parallel git and filesystem scans (pull,
+    /// maintenance, doctor) and for `meta exec --parallel`. Unset (or 0)
+    /// uses one per CPU.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallelism: Option<usize>,
+    /// Colored output: `auto` (the default; honors `NO_COLOR` and whether
+    /// stdout is a terminal), `always`, or `never`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Suggest a next step after a command when the workspace calls for one
+    /// (on by default; see `meta tips`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hints: Option<bool>,
+    /// Extra directories searched for plugins, alongside
+    /// `~/.config/metarepo/plugins`. Only read from the user config (see
+    /// [`user_config_path`]); a workspace cannot add plugin directories.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugin_dirs: Vec<String>,
+    /// HTTPS credentials for clones and fetches, keyed by host; see
+    /// [`HostCredentials`]. Only read from the user config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<HashMap<String, HostCredentials>>,
+    /// SSH keys and usernames for clones and fetches, keyed by host or by
+    /// `host/repo-path` prefix; see [`SshHost`]. Only read from the user
+    /// config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<HashMap<String, SshHost>>,
+}
+
+/// Branch naming rule (the `branch_policy` block in `.meta`). New branches
+/// whose names do not match `pattern` are rejected, keeping branch names
+/// consistent across repositories for tooling that keys off them.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct BranchPolicy {
+    /// Regular expression a branch name must match. It is anchored at both
+    /// ends, so `feat/.+` rejects `my-feat/x`.
+    pub pattern: String,
+    /// Conforming names shown when a branch is rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+}
+
+/// A template for `meta project new` (an entry in the `templates` block).
+/// `remote` and `create_remote` may use `{project}` (the new project's path)
+/// and `{name}` (its last path component).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ProjectTemplate {
+    /// Repository the new project is copied from.
+    pub url: String,
+    /// Shown by `meta project new --list-templates`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Remote URL for new projects, e.g. `git@github.com:acme/{name}.git`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// Shell command that creates the remote repository, run by
+    /// `--create-remote` before the first push, e.g.
+    /// `gh repo create acme/{name} --private`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub create_remote: Option<String>,
+}
+
+/// An issue tracker (an entry in the `issue_trackers` list in `.meta`). Text
+/// matching `pattern` in a branch name is shown as a link to the issue.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct IssueTracker {
+    /// Regular expression matching a reference, such as `[A-Z][A-Z0-9]+-\d+`
+    /// for Jira keys or `#(\d+)` for GitHub issues.
+    pub pattern: String,
+    /// Link template. `{id}` is the pattern's first capture group, or the
+    /// whole match when it has none; `{repo}` is the project's remote
+    /// `owner/name` path.
+    pub url: String,
+}
+
+/// Clone-time checks (the `clone` block in `.meta`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct CloneSettings {
+    /// Warn before cloning a repository the host reports as larger than this
+    /// many megabytes (default: 500; 0 disables the warning).
+    #[serde(
+        rename = "size-warn-mb",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub size_warn_mb: Option<u64>,
+    /// History depth for new clones when `--depth` is not given. Unset (the
+    /// default) makes full clones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<i32>,
+    /// Bandwidth limit for clones and fetches, in KB/s, when `--limit-rate`
+    /// is not given. Unset (the default) means unlimited.
+    #[serde(
+        rename = "limit-rate",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub limit_rate: Option<u64>,
+    /// Initialize and update git submodules in new clones and on `meta
+    /// project update`, when `--recurse-submodules` is not given.
+    #[serde(
+        rename = "recurse-submodules",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recurse_submodules: Option<bool>,
+}
+
+/// Completion notifications (the `notify` block in `.meta`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct NotifySettings {
+    /// Ring the terminal bell when `meta exec`, `meta run`, or a clone takes at
+    /// least this many seconds. Unset (the default) disables notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_complete_secs: Option<u64>,
+    /// Also show a desktop notification (notify-send on Linux, osascript on
+    /// macOS). Defaults to false.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desktop: Option<bool>,
+}
+
+/// `meta todos` scanning (the `todos` block in `.meta`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct TodoSettings {
+    /// The markers to look for, matched case-sensitively as whole words.
+    /// Empty (the default) means `TODO`, `FIXME`, and `HACK`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<String>,
+}
+
+/// Files kept in step across projects by `meta files sync` (the `sync_files`
+/// block in `.meta`). Each source is a minijinja template rendered with the
+/// workspace and project metadata.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SyncFileSettings {
+    /// Workspace-wide template variables, available as `vars.<name>`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vars: HashMap<String, String>,
+    /// Secret names templates may reference as `{{ secrets:NAME }}`. Only the
+    /// names live here; the values never pass through meta.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<String>,
+    /// What a secret placeholder is written as, with `{name}` replaced by the
+    /// secret's name. Defaults to GitHub Actions' `${{ secrets.{name} }}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<SyncFile>,
+}
+
+/// A file written by `meta files sync` (an entry in `sync_files.files`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SyncFile {
+    /// Template, relative to the workspace root.
+    pub source: String,
+    /// Where the rendered file goes, relative to each project (or to the
+    /// workspace root when `root` is set).
+    pub target: String,
+    /// Project selectors (keys, aliases, or globs) that get the file. Empty
+    /// means every project not left out by `disabled` or `default_excludes`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    /// Render once into the workspace root instead of into each project, as
+    /// for the workspace's own CI definition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<bool>,
+}
+
+/// README badges written by `meta docs badges` (the `badges` block in `.meta`).
+/// Image and link values may use `{project}`, `{name}` (the directory name),
+/// `{repo}` (the remote's `owner/name` path), and `{url}` (its web page).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct BadgeSettings {
+    /// Build status image URL. Projects without a remote get no build badge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<String>,
+    /// Coverage image URL. Projects without a remote get no coverage badge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<String>,
+    /// Link to the meta workspace, shown as a "meta workspace" badge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    /// README file to update in each project (default: README.md).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readme: Option<String>,
+}
+
+/// `meta exec` behavior (the `exec` block in `.meta`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ExecSettings {
+    /// How output is shown, as with `--output`: `buffered` (the default; one
+    /// block per project), `prefixed` (every line live, prefixed with its
+    /// project), or `silent` (only each project's result). `sections` and
+    /// `prefix` are accepted for `buffered` and `prefixed`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+/// The new-developer flow of `meta onboard` (the `onboard` block in
+/// `.meta`). Each project's own setup lives in its `post_clone` and `verify`
+/// scripts; this block holds what spans the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct OnboardSettings {
+    /// Project group to clone and set up; unset means every project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Programs that must be on `PATH`, e.g. `["node", "cargo", "docker"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// Steps meta cannot do for the developer, shown as a checklist at the
+    /// end, e.g. "Ask #infra for VPN access".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub manual_steps: Vec<String>,
+}
+
+/// `meta run` behavior (the `run` block in `.meta`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct RunSettings {
+    /// Native task runners to try, in order, when a project has no `.meta`
+    /// script of the requested name: `npm`, `yarn`, `pnpm`, `just`,
+    /// `cargo-make`, or `make`. Empty (the default) disables the fallback.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback: Vec<String>,
+}
+
+/// How to authenticate to one HTTPS host (an entry in the `credentials`
+/// block of the user config). The token itself is never stored in the
+/// config: it is read from an environment variable or a command's output.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct HostCredentials {
+    /// Username sent with the token. Defaults to the username in the URL,
+    /// else `x-access-token`, which GitHub and GitLab accept for tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Environment variable holding the token or password.
+    #[serde(rename = "token-env", default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+    /// Shell command printing the token, e.g. `gh auth token`. Used when
+    /// `token-env` is unset or empty.
+    #[serde(
+        rename = "token-command",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub token_command: Option<String>,
+}
+
+/// SSH settings for one host, or for the repositories under one path on a
+/// host (an entry in the `hosts` block of the user config). Keys are a host
+/// (`github.com`) or a host and repository path prefix
+/// (`gitlab.internal/team/api`); the longest matching key wins.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SshHost {
+    /// Private key to authenticate with (`~/` expands to the home
+    /// directory). When set, only this key and the SSH agent are tried,
+    /// not the default keys in `~/.ssh`.
+    #[serde(rename = "ssh-key", default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    /// SSH username, overriding the one in the URL (usually `git`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// A project's long-running service (an entry in the `services` block).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ServiceSpec {
+    /// Script that starts the service. Defaults to the project's `dev` entry
+    /// point: its `dev` script, else its `default_script`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// Labels for selecting services with `meta services up --tag`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Restart the service when it exits (default: true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<bool>,
+    /// Ports the service listens on. Checked for conflicts before starting and
+    /// exported to the service as `PORT` (the first) and `PORTS`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<u16>,
 }
 
 /// Configuration for the `meta skill` commands (the `[skill]` block in `.meta`).
@@ -506,6 +1368,7 @@ pub struct McpServeSettings {
 impl Default for MetaConfig {
     fn default() -> Self {
         Self {
+            extends: Vec::new(),
             ignore: vec![
                 ".git".to_string(),
                 ".vscode".to_string(),
@@ -515,19 +1378,47 @@ impl Default for MetaConfig {
             ],
             projects: HashMap::new(),
             disabled: Vec::new(),
+            default_excludes: Vec::new(),
+            profiles: None,
+            groups: None,
             plugins: None,
+            plugin_priority: None,
+            disabled_plugins: Vec::new(),
+            plugin_overrides: None,
             modules: None,
             nested: None,
             aliases: None,
             scripts: None,
             worktree_init: None,
+            branch_policy: None,
+            templates: None,
+            issue_trackers: Vec::new(),
+            default_script: None,
             default_bare: None,
             plugins_integrity: None,
             allow_version_mismatch: None,
             plugin_allow_any_path: None,
             skill: None,
             mcp: None,
+            clone_settings: None,
+            exec_settings: None,
+            exec_templates: None,
+            run_settings: None,
+            onboard: None,
+            services: None,
+            min_success: None,
+            protected: Vec::new(),
+            notify: None,
+            badges: None,
+            todos: None,
+            sync_files: None,
             help_descriptions: None,
+            parallelism: None,
+            color: None,
+            hints: None,
+            plugin_dirs: Vec::new(),
+            credentials: None,
+            hosts: None,
         }
     }
 }
@@ -593,12 +1484,22 @@ impl MetaConfig {
             .unwrap_or(false)
     }
 
+    /// Worker threads for parallel scans: the `parallelism` setting, or one
+    /// per CPU when it is unset or 0.
+    pub fn worker_count(&self) -> usize {
+        self.parallelism.filter(|n| *n > 0).unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4)
+        })
+    }
+
     /// Read a config file from disk. Format is detected from the path's
     /// filename/extension; unrecognized names are rejected so callers don't
     /// accidentally try to parse, say, `package.json` as a metarepo config.
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref();
-        let format = ConfigFormat::from_path(path).ok_or_else(|| {
+        let format = ConfigFormat::of_file(path).ok_or_else(|| {
             anyhow::anyhow!(
                 "Unrecognized config filename: {}. Expected one of: {}",
                 path.display(),
@@ -615,7 +1516,12 @@ impl MetaConfig {
         format: ConfigFormat,
     ) -> Result<Self> {
         let content = std::fs::read_to_string(path.as_ref())?;
-        let mut config: MetaConfig = config_format::deserialize_from_str(&content, format)?;
+        Self::load_from_str(&content, format)
+    }
+
+    /// Parse config text in `format`, e.g. a past revision read from git.
+    pub fn load_from_str(content: &str, format: ConfigFormat) -> Result<Self> {
+        let mut config: MetaConfig = config_format::deserialize_from_str(content, format)?;
         config.sanitize_after_load();
         Ok(config)
     }
@@ -660,21 +1566,27 @@ impl MetaConfig {
     }
 
     /// Write the config to disk, choosing the on-wire format from the path's
-    /// filename/extension. Unrecognized paths default to JSON so that legacy
+    /// filename/extension, or for an existing extensionless file the format it
+    /// is already in. Unrecognized paths default to JSON so that legacy
     /// callers that pass arbitrary paths still get a sensible serialization.
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let path = path.as_ref();
-        let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json);
+        let format = ConfigFormat::of_file(path).unwrap_or(ConfigFormat::Json);
         self.save_to_file_with_format(path, format)
     }
 
-    /// Write the config to disk in an explicit format.
+    /// Write the config to disk in an explicit format. An existing file is
+    /// edited rather than rewritten, so only the values that changed move
+    /// (see [`config_edit`]).
     pub fn save_to_file_with_format<P: AsRef<Path>>(
         &self,
         path: P,
         format: ConfigFormat,
     ) -> Result<()> {
-        let content = config_format::serialize_to_string(self, format)?;
+        let content = match std::fs::read_to_string(path.as_ref()) {
+            Ok(existing) => self.to_string_preserving(&existing, format)?,
+            Err(_) => config_format::serialize_to_string(self, format)?,
+        };
         std::fs::write(path.as_ref(), content)?;
         Ok(())
     }
@@ -708,7 +1620,7 @@ impl MetaConfig {
                 }
                 1 => {
                     let path = found.into_iter().next().unwrap();
-                    let format = ConfigFormat::from_path(&path).unwrap_or(ConfigFormat::Json);
+                    let format = ConfigFormat::of_file(&path).unwrap_or(ConfigFormat::Json);
                     return Ok(Some(DiscoveredConfig { path, format }));
                 }
                 _ => {
@@ -744,7 +1656,7 @@ impl MetaConfig {
                 0 => {}
                 1 => {
                     let path = found.into_iter().next().unwrap();
-                    let format = ConfigFormat::from_path(&path).unwrap_or(ConfigFormat::Json);
+                    let format = ConfigFormat::of_file(&path).unwrap_or(ConfigFormat::Json);
                     // Keep the highest one seen so far; keep walking upward.
                     outermost = Some(DiscoveredConfig { path, format });
                 }
@@ -785,7 +1697,7 @@ impl MetaConfig {
                 0 => {}
                 1 => {
                     let path = found.into_iter().next().unwrap();
-                    let format = ConfigFormat::from_path(&path).unwrap_or(ConfigFormat::Json);
+                    let format = ConfigFormat::of_file(&path).unwrap_or(ConfigFormat::Json);
                     chain.push(DiscoveredConfig { path, format });
                 }
                 _ => {
@@ -838,7 +1750,7 @@ impl MetaConfig {
         for name in KNOWN_FILENAMES {
             let candidate = dir.join(name);
             if candidate.is_file() {
-                let format = ConfigFormat::from_path(&candidate).unwrap_or(ConfigFormat::Json);
+                let format = ConfigFormat::of_file(&candidate).unwrap_or(ConfigFormat::Json);
                 return Some(DiscoveredConfig {
                     path: candidate,
                     format,
@@ -907,6 +1819,80 @@ impl MetaConfig {
         None
     }
 
+    /// Resolve project selectors to project keys. Each selector is either a
+    /// glob matched against every key ([`glob_matches`]) or an identifier
+    /// resolved through [`resolve_identifier`](Self::resolve_identifier).
+    /// Selectors may be comma-separated lists.
+    pub fn select_projects<S: AsRef<str>>(&self, selectors: &[S]) -> ProjectSelection {
+        let mut keys: Vec<&String> = self.projects.keys().collect();
+        keys.sort();
+
+        let mut selection = ProjectSelection::default();
+        for selector in selectors
+            .iter()
+            .flat_map(|s| s.as_ref().split(','))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let matched: Vec<String> = if is_glob(selector) {
+                keys.iter()
+                    .filter(|key| glob_matches(selector, key))
+                    .map(|key| key.to_string())
+                    .collect()
+            } else {
+                self.resolve_identifier(selector).into_iter().collect()
+            };
+            if matched.is_empty() {
+                selection.unmatched.push(selector.to_string());
+            }
+            for key in matched {
+                if !selection.projects.contains(&key) {
+                    selection.projects.push(key);
+                }
+            }
+        }
+        selection
+    }
+
+    /// Resolve exclusion selectors. Like [`select_projects`](Self::select_projects),
+    /// except that a selector naming no project is taken as a tag and matches
+    /// every project carrying it, so `archived` can exclude a whole class of
+    /// projects.
+    pub fn select_excluded<S: AsRef<str>>(&self, selectors: &[S]) -> ProjectSelection {
+        let mut selection = self.select_projects(selectors);
+        let unmatched = std::mem::take(&mut selection.unmatched);
+        for selector in unmatched {
+            let mut tagged: Vec<String> = if is_glob(&selector) {
+                Vec::new()
+            } else {
+                self.tagged_project_keys(std::slice::from_ref(&selector))
+                    .into_iter()
+                    .collect()
+            };
+            if tagged.is_empty() {
+                selection.unmatched.push(selector);
+                continue;
+            }
+            tagged.sort();
+            for key in tagged {
+                if !selection.projects.contains(&key) {
+                    selection.projects.push(key);
+                }
+            }
+        }
+        selection
+    }
+
+    /// Canonical keys matched by the workspace `default_excludes` list
+    /// (projects, globs, or tags; see [`select_excluded`](Self::select_excluded)).
+    /// Entries that match nothing are ignored.
+    pub fn default_excluded_keys(&self) -> std::collections::HashSet<String> {
+        self.select_excluded(&self.default_excludes)
+            .projects
+            .into_iter()
+            .collect()
+    }
+
     /// The set of canonical project keys that are disabled, via either the
     /// per-project `enabled: false` flag or the top-level `disabled` list.
     ///
@@ -954,7 +1940,7 @@ impl MetaConfig {
     }
 
     /// Get scripts for a specific project
-    pub fn get_project_scripts(&self, project_name: &str) -> Option<HashMap<String, String>> {
+    pub fn get_project_scripts(&self, project_name: &str) -> Option<HashMap<String, ScriptEntry>> {
         self.projects
             .get(project_name)
             .and_then(|entry| match entry {
@@ -976,7 +1962,7 @@ impl MetaConfig {
     ///
     /// Only global scripts are merged here; project-specific scripts live in the
     /// nearest config and still override globals at lookup time.
-    pub fn merge_global_scripts(chain: &[MetaConfig]) -> HashMap<String, String> {
+    pub fn merge_global_scripts(chain: &[MetaConfig]) -> HashMap<String, ScriptEntry> {
         let mut merged = HashMap::new();
         for cfg in chain {
             if let Some(scripts) = &cfg.scripts {
@@ -986,8 +1972,12 @@ impl MetaConfig {
         merged
     }
 
-    /// Get all available scripts (project-specific and global)
-    pub fn get_all_scripts(&self, project_name: Option<&str>) -> HashMap<String, String> {
+    /// Get all available scripts (project-specific and global), with their
+    /// descriptions and tags
+    pub fn get_all_script_entries(
+        &self,
+        project_name: Option<&str>,
+    ) -> HashMap<String, ScriptEntry> {
         let mut scripts = HashMap::new();
 
         // Add global scripts first
@@ -1005,6 +1995,14 @@ impl MetaConfig {
         scripts
     }
 
+    /// Get all available scripts (project-specific and global) as name -> command
+    pub fn get_all_scripts(&self, project_name: Option<&str>) -> HashMap<String, String> {
+        self.get_all_script_entries(project_name)
+            .into_iter()
+            .map(|(name, entry)| (name, entry.command().to_string()))
+            .collect()
+    }
+
     /// Check if a project exists (for backwards compatibility)
     pub fn project_exists(&self, project_name: &str) -> bool {
         self.projects.contains_key(project_name)
@@ -1038,6 +2036,121 @@ impl MetaConfig {
         None
     }
 
+    /// The ref a project is pinned at, if any.
+    pub fn get_project_pin(&self, project_name: &str) -> Option<&str> {
+        match self.projects.get(project_name) {
+            Some(ProjectEntry::Metadata(metadata)) => metadata.pinned.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Pin a project at `pin`, or unpin it with `None`.
+    pub fn set_project_pin(&mut self, project_name: &str, pin: Option<String>) -> Result<()> {
+        self.project_metadata_mut(project_name)?.pinned = pin;
+        Ok(())
+    }
+
+    /// The commit a vendored project was vendored from, if it is vendored.
+    pub fn get_vendored_commit(&self, project_name: &str) -> Option<&str> {
+        match self.projects.get(project_name) {
+            Some(ProjectEntry::Metadata(metadata)) => metadata.vendored.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether a project's files are vendored into the workspace repository.
+    pub fn is_vendored(&self, project_name: &str) -> bool {
+        self.get_vendored_commit(project_name).is_some()
+    }
+
+    /// A project's access level, if one is set.
+    pub fn get_project_visibility(&self, project_name: &str) -> Option<Visibility> {
+        match self.projects.get(project_name) {
+            Some(ProjectEntry::Metadata(metadata)) => metadata.visibility,
+            _ => None,
+        }
+    }
+
+    /// Keys of the projects marked `"visibility": "restricted"`. Their names
+    /// and details are kept out of AI-facing output (`meta mcp serve`, the
+    /// daemon API).
+    pub fn restricted_project_keys(&self) -> std::collections::HashSet<String> {
+        self.projects
+            .keys()
+            .filter(|name| self.get_project_visibility(name) == Some(Visibility::Restricted))
+            .cloned()
+            .collect()
+    }
+
+    /// The tags a project carries; empty for a plain URL entry.
+    pub fn get_project_tags(&self, project_name: &str) -> &[String] {
+        match self.projects.get(project_name) {
+            Some(ProjectEntry::Metadata(metadata)) => &metadata.tags,
+            _ => &[],
+        }
+    }
+
+    /// The projects carrying any of `tags`.
+    pub fn tagged_project_keys(&self, tags: &[String]) -> std::collections::HashSet<String> {
+        self.projects
+            .keys()
+            .filter(|key| {
+                self.get_project_tags(key)
+                    .iter()
+                    .any(|tag| tags.contains(tag))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The version-control system a project is checked out with.
+    pub fn get_project_vcs(&self, project_name: &str) -> Vcs {
+        match self.projects.get(project_name) {
+            Some(ProjectEntry::Metadata(metadata)) => metadata.vcs.unwrap_or_default(),
+            _ => Vcs::Git,
+        }
+    }
+
+    /// A project's deprecation, when it has one.
+    pub fn get_project_deprecation(&self, project_name: &str) -> Option<&Deprecation> {
+        match self.projects.get(project_name) {
+            Some(ProjectEntry::Metadata(metadata)) => metadata.deprecated.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// A project's metadata for editing, promoting a plain URL entry to the
+    /// metadata form first.
+    pub fn project_metadata_mut(&mut self, project_name: &str) -> Result<&mut ProjectMetadata> {
+        let entry = self
+            .projects
+            .get_mut(project_name)
+            .ok_or_else(|| anyhow::anyhow!("Project '{}' not found", project_name))?;
+        if let ProjectEntry::Url(url) = entry {
+            *entry = ProjectEntry::Metadata(ProjectMetadata {
+                url: url.clone(),
+                aliases: Vec::new(),
+                tags: Vec::new(),
+                scripts: HashMap::new(),
+                env: HashMap::new(),
+                worktree_init: None,
+                bare: None,
+                default_script: None,
+                enabled: None,
+                depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
+            });
+        }
+        match entry {
+            ProjectEntry::Metadata(metadata) => Ok(metadata),
+            ProjectEntry::Url(_) => unreachable!("URL entries were promoted above"),
+        }
+    }
+
     /// Deserialize a plugin's top-level config block (the table named `name`,
     /// e.g. `skill`) into a plugin-defined settings struct. Returns `None` when
     /// the block is absent or null. This is the typed accessor plugins use to
@@ -1272,12 +2385,19 @@ mod tests {
         ProjectEntry::Metadata(ProjectMetadata {
             url: url.to_string(),
             aliases: aliases.iter().map(|s| s.to_string()).collect(),
+            tags: Vec::new(),
             scripts: HashMap::new(),
             env: HashMap::new(),
             worktree_init: None,
             bare: None,
+            default_script: None,
             enabled,
             depth: None,
+            pinned: None,
+            vendored: None,
+            visibility: None,
+            vcs: None,
+            deprecated: None,
         })
     }
 
@@ -1390,9 +2510,47 @@ mod tests {
         // Chain is outermost -> nearest.
         let merged = MetaConfig::merge_global_scripts(&[outer, inner]);
         // Nearest wins for overlapping keys...
-        assert_eq!(merged.get("build").map(String::as_str), Some("inner-build"));
+        assert_eq!(
+            merged.get("build").map(ScriptEntry::command),
+            Some("inner-build")
+        );
         // ...and outer-only keys are inherited.
-        assert_eq!(merged.get("lint").map(String::as_str), Some("outer-lint"));
+        assert_eq!(
+            merged.get("lint").map(ScriptEntry::command),
+            Some("outer-lint")
+        );
+    }
+
+    #[test]
+    fn scripts_accept_plain_and_detailed_forms() {
+        let config: MetaConfig = serde_json::from_str(
+            r#"{"projects":{},"scripts":{
+                "test":"cargo test",
+                "deploy":{"cmd":"./deploy.sh","description":"Ship it","tags":["ops"]}
+            }}"#,
+        )
+        .unwrap();
+        let scripts = config.scripts.as_ref().unwrap();
+        assert_eq!(
+            scripts["test"],
+            ScriptEntry::Command("cargo test".to_string())
+        );
+        assert_eq!(scripts["test"].description(), None);
+        assert_eq!(scripts["deploy"].command(), "./deploy.sh");
+        assert_eq!(scripts["deploy"].description(), Some("Ship it"));
+        assert_eq!(scripts["deploy"].tags(), ["ops".to_string()]);
+        assert_eq!(
+            config
+                .get_all_scripts(None)
+                .get("deploy")
+                .map(String::as_str),
+            Some("./deploy.sh")
+        );
+
+        // Plain strings stay plain when written back.
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["scripts"]["test"], "cargo test");
+        assert_eq!(json["scripts"]["deploy"]["cmd"], "./deploy.sh");
     }
 
     #[test]
@@ -1402,7 +2560,10 @@ mod tests {
         let inner: MetaConfig = serde_json::from_str(r#"{"projects":{}}"#).unwrap();
 
         let merged = MetaConfig::merge_global_scripts(&[outer, inner]);
-        assert_eq!(merged.get("build").map(String::as_str), Some("outer-build"));
+        assert_eq!(
+            merged.get("build").map(ScriptEntry::command),
+            Some("outer-build")
+        );
         assert_eq!(merged.len(), 1);
     }
 
@@ -1474,6 +2635,7 @@ mod tests {
                 ignore_nested: vec!["ignored-project".to_string()],
                 namespace_separator: Some("::".to_string()),
                 preserve_structure: true,
+                dedupe: DedupeMode::Link,
             }),
             ..Default::default()
         };
@@ -1492,6 +2654,182 @@ mod tests {
         assert_eq!(nested.ignore_nested, vec!["ignored-project".to_string()]);
         assert_eq!(nested.namespace_separator, Some("::".to_string()));
         assert!(nested.preserve_structure);
+        assert_eq!(nested.dedupe, DedupeMode::Link);
+    }
+
+    #[test]
+    fn test_glob_matches_segments_and_classes() {
+        assert!(glob_matches("services/*", "services/api"));
+        assert!(!glob_matches("services/*", "services/api/v2"));
+        assert!(glob_matches("libs/**", "libs/a/b"));
+        assert!(glob_matches("libs/**/core", "libs/core"));
+        assert!(glob_matches("libs/**/core", "libs/x/y/core"));
+        assert!(glob_matches("api-v?", "api-v2"));
+        assert!(!glob_matches("api-v?", "api-v10"));
+        assert!(glob_matches("[ab]pp", "app"));
+        assert!(glob_matches("[!a]pp", "bpp"));
+        assert!(glob_matches("web-[0-9]", "web-7"));
+        assert!(!glob_matches("web", "web-ui"), "no substring fallback");
+    }
+
+    #[test]
+    fn test_select_projects_resolves_globs_and_names() {
+        let mut config = MetaConfig::default();
+        for key in ["services/api", "services/web", "libs/core", "docs"] {
+            config
+                .projects
+                .insert(key.to_string(), ProjectEntry::Url(format!("local:{key}")));
+        }
+
+        let selection = config.select_projects(&["services/*", "docs,core", "nope/*"]);
+        assert_eq!(
+            selection.projects,
+            vec!["services/api", "services/web", "docs", "libs/core"]
+        );
+        assert_eq!(selection.unmatched, vec!["nope/*"]);
+
+        assert!(selection.clone().into_projects(false).is_ok());
+        assert!(selection.into_projects(true).is_err());
+        assert!(config
+            .select_projects(&["missing/**"])
+            .into_projects(false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_default_excluded_keys_resolves_selectors() {
+        let mut config: MetaConfig = serde_json::from_str(
+            r#"{
+                "default_excludes": ["vendor/*", "old", "missing"],
+                "projects": {
+                    "vendor/zlib": "local:vendor/zlib",
+                    "archive/old": "local:archive/old",
+                    "api": "local:api"
+                }
+            }"#,
+        )
+        .unwrap();
+        let excluded = config.default_excluded_keys();
+        assert!(excluded.contains("vendor/zlib"));
+        assert!(excluded.contains("archive/old"));
+        assert!(!excluded.contains("api"));
+
+        config.default_excludes.clear();
+        assert!(!serde_json::to_string(&config)
+            .unwrap()
+            .contains("default_excludes"));
+    }
+
+    #[test]
+    fn test_excludes_name_tags_when_no_project_matches() {
+        let config: MetaConfig = serde_json::from_str(
+            r#"{
+                "default_excludes": ["archived"],
+                "projects": {
+                    "legacy": { "url": "u", "tags": ["archived"] },
+                    "old": { "url": "u", "tags": ["archived"] },
+                    "api": { "url": "u", "tags": ["backend"] },
+                    "backend": "u"
+                }
+            }"#,
+        )
+        .unwrap();
+        let excluded = config.default_excluded_keys();
+        assert_eq!(excluded, ["legacy".to_string(), "old".to_string()].into());
+
+        // A project name wins over a tag of the same name; misses are kept.
+        let selection = config.select_excluded(&["backend", "missing", "x/*"]);
+        assert_eq!(selection.projects, vec!["backend"]);
+        assert_eq!(selection.unmatched, vec!["missing", "x/*"]);
+    }
+
+    #[test]
+    fn test_canonical_repo_url_matches_equivalent_spellings() {
+        let expected = Some("github.com/Acme/web".to_string());
+        assert_eq!(
+            canonical_repo_url("https://github.com/Acme/web.git"),
+            expected
+        );
+        assert_eq!(canonical_repo_url("git@github.com:Acme/web"), expected);
+        assert_eq!(
+            canonical_repo_url("ssh://git@GitHub.com:22/Acme/web/"),
+            expected
+        );
+        assert_eq!(
+            canonical_repo_url("external:https://github.com/Acme/web"),
+            expected
+        );
+        assert_eq!(canonical_repo_url("local:web"), None);
+        assert_ne!(
+            canonical_repo_url("https://github.com/Acme/web.git"),
+            canonical_repo_url("https://github.com/Acme/api.git")
+        );
+    }
+
+    #[test]
+    fn test_canonical_repo_url_drops_default_ports() {
+        let expected = Some("github.com/Acme/web".to_string());
+        assert_eq!(
+            canonical_repo_url("ssh://git@github.com:22/Acme/web"),
+            canonical_repo_url("git@github.com:Acme/web")
+        );
+        assert_eq!(
+            canonical_repo_url("https://github.com:443/Acme/web.git"),
+            expected
+        );
+        assert_eq!(
+            canonical_repo_url("http://github.com:80/Acme/web"),
+            expected
+        );
+        // A non-default port is a different server.
+        assert_eq!(
+            canonical_repo_url("ssh://git@git.example.com:2222/Acme/web"),
+            Some("git.example.com:2222/Acme/web".to_string())
+        );
+    }
+
+    #[test]
+    fn test_canonical_repo_url_splits_user_info_off_the_authority_only() {
+        assert_eq!(
+            canonical_repo_url("https://user@git.example.com/team/web@v2.git"),
+            Some("git.example.com/team/web@v2".to_string())
+        );
+        assert_eq!(
+            canonical_repo_url("https://git.example.com/team/web@v2"),
+            Some("git.example.com/team/web@v2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_remote_url_parses_both_forms() {
+        assert_eq!(
+            RemoteUrl::parse("ssh://git@gitlab.internal:2222/team/api.git/"),
+            Some(RemoteUrl {
+                scheme: Some("ssh"),
+                host: "gitlab.internal",
+                port: Some("2222"),
+                path: "team/api",
+            })
+        );
+        assert_eq!(
+            RemoteUrl::parse("git@github.com:Acme/web.git"),
+            Some(RemoteUrl {
+                scheme: None,
+                host: "github.com",
+                port: None,
+                path: "Acme/web",
+            })
+        );
+        assert_eq!(RemoteUrl::parse("/srv/git/api.git"), None);
+        assert_eq!(RemoteUrl::parse("./a:b"), None);
+    }
+
+    #[test]
+    fn test_nested_dedupe_parses_lowercase_and_defaults_to_warn() {
+        let config: NestedConfig = serde_json::from_str(r#"{"dedupe":"link"}"#).unwrap();
+        assert_eq!(config.dedupe, DedupeMode::Link);
+        let config: NestedConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.dedupe, DedupeMode::Warn);
     }
 
     #[test]
@@ -1599,7 +2937,9 @@ mod tests {
             experimental: false,
             non_interactive: None,
             scope_workspace: false,
+            profile: None,
             settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
         };
 
         let config_without_meta = RuntimeConfig {
@@ -1609,7 +2949,9 @@ mod tests {
             experimental: false,
             non_interactive: None,
             scope_workspace: false,
+            profile: None,
             settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
         };
 
         assert!(config_with_meta.has_meta_file());
@@ -1629,7 +2971,9 @@ mod tests {
             experimental: false,
             non_interactive: None,
             scope_workspace: false,
+            profile: None,
             settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
         };
 
         assert_eq!(config.meta_root(), Some(temp_dir.path().join("subdir")));
@@ -1779,6 +3123,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pinning_promotes_url_entries_and_unpinning_clears() {
+        let mut config = MetaConfig::default();
+        config
+            .projects
+            .insert("vendor/lib".to_string(), ProjectEntry::Url("u".to_string()));
+        assert_eq!(config.get_project_pin("vendor/lib"), None);
+
+        config
+            .set_project_pin("vendor/lib", Some("v1.2.0".to_string()))
+            .unwrap();
+        assert_eq!(config.get_project_pin("vendor/lib"), Some("v1.2.0"));
+        assert_eq!(config.get_project_url("vendor/lib").as_deref(), Some("u"));
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"pinned\":\"v1.2.0\""), "got: {json}");
+
+        config.set_project_pin("vendor/lib", None).unwrap();
+        assert_eq!(config.get_project_pin("vendor/lib"), None);
+        assert!(config.set_project_pin("missing", None).is_err());
+    }
+
+    #[test]
+    fn visibility_parses_lowercase_levels() {
+        let json = r#"{
+            "projects": {
+                "billing": { "url": "u", "visibility": "restricted" },
+                "docs": { "url": "u", "visibility": "public" },
+                "web": "u"
+            }
+        }"#;
+        let config: MetaConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.get_project_visibility("billing"),
+            Some(Visibility::Restricted)
+        );
+        assert_eq!(
+            config.get_project_visibility("docs"),
+            Some(Visibility::Public)
+        );
+        assert_eq!(config.get_project_visibility("web"), None);
+        assert_eq!(
+            config.restricted_project_keys(),
+            ["billing".to_string()].into()
+        );
+        assert!(serde_json::from_str::<MetaConfig>(
+            r#"{"projects": {"x": {"url": "u", "visibility": "secret"}}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn vcs_defaults_to_git() {
+        let json = r#"{
+            "projects": {
+                "tools": { "url": "u", "vcs": "jj" },
+                "legacy": { "url": "u", "vcs": "sl" },
+                "web": "u"
+            }
+        }"#;
+        let config: MetaConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.get_project_vcs("tools"), Vcs::Jj);
+        assert_eq!(config.get_project_vcs("legacy"), Vcs::Sapling);
+        assert_eq!(config.get_project_vcs("web"), Vcs::Git);
+        assert_eq!(config.get_project_vcs("missing"), Vcs::Git);
+        assert_eq!(Vcs::Hg.marker(), ".hg");
+    }
+
+    #[test]
+    fn deprecation_summary_and_overdue() {
+        let json = r#"{
+            "projects": {
+                "old-api": { "url": "u", "deprecated": {
+                    "since": "2.0", "replacement": "api", "remove_after": "2025-06-30"
+                } },
+                "legacy": { "url": "u", "deprecated": {} },
+                "api": "u"
+            }
+        }"#;
+        let config: MetaConfig = serde_json::from_str(json).unwrap();
+        let old = config.get_project_deprecation("old-api").unwrap();
+        assert_eq!(
+            old.summary(),
+            "deprecated since 2.0; use api instead; remove after 2025-06-30"
+        );
+        assert!(!old.is_overdue("2025-06-30"));
+        assert!(old.is_overdue("2025-07-01"));
+
+        let legacy = config.get_project_deprecation("legacy").unwrap();
+        assert_eq!(legacy.summary(), "deprecated");
+        assert!(!legacy.is_overdue("2099-01-01"));
+        assert!(config.get_project_deprecation("api").is_none());
+    }
+
     #[test]
     fn project_metadata_depth_none_is_omitted_from_serialized_json() {
         // No depth was recorded (full clone) — the field must be skipped
@@ -1786,12 +3223,19 @@ mod tests {
         let metadata = ProjectMetadata {
             url: "https://example.com/full-project.git".to_string(),
             aliases: Vec::new(),
+            tags: Vec::new(),
             scripts: HashMap::new(),
             env: HashMap::new(),
             worktree_init: None,
             bare: None,
+            default_script: None,
             enabled: None,
             depth: None,
+            pinned: None,
+            vendored: None,
+            visibility: None,
+            vcs: None,
+            deprecated: None,
         };
         let json = serde_json::to_string(&metadata).unwrap();
         assert!(
@@ -0,0 +1,359 @@
+//! Terminal output helpers shared by the plugins.
+//!
+//! [`Table`] renders rows as aligned columns: each column is as wide as its
+//! widest cell, measured in terminal cells (so colored text, CJK, and emoji
+//! line up), and when the table is wider than the terminal the columns marked
+//! [`Table::shrink`] are narrowed and their cells truncated with `…` rather
+//! than letting lines wrap. Cells may carry [`hyperlink`]s, which take no
+//! width; a truncated cell loses its links.
+
+use console::{measure_text_width, pad_str, style, truncate_str, Alignment, Term};
+use std::borrow::Cow;
+
+/// Spaces between columns.
+const GAP: usize = 2;
+
+/// A shrinkable column is never narrowed below this, or its header.
+const MIN_SHRUNK_WIDTH: usize = 8;
+
+/// How a column's cells are aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    header: String,
+    align: Align,
+    shrink: bool,
+}
+
+/// A table of text rows (see the module docs). Cells may contain ANSI styling.
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    indent: usize,
+    max_width: Option<usize>,
+}
+
+impl Table {
+    /// A table with these column headers, sized to fit the terminal when
+    /// stdout is one (see [`terminal_width`]).
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            columns: headers
+                .into_iter()
+                .map(|header| Column {
+                    header: header.into(),
+                    align: Align::Left,
+                    shrink: false,
+                })
+                .collect(),
+            rows: Vec::new(),
+            indent: 0,
+            max_width: terminal_width(),
+        }
+    }
+
+    /// Align column `index`.
+    pub fn align(mut self, index: usize, align: Align) -> Self {
+        if let Some(column) = self.columns.get_mut(index) {
+            column.align = align;
+        }
+        self
+    }
+
+    /// Let column `index` be narrowed, truncating its cells, when the table
+    /// does not fit. Columns that are not marked are never truncated.
+    pub fn shrink(mut self, index: usize) -> Self {
+        if let Some(column) = self.columns.get_mut(index) {
+            column.shrink = true;
+        }
+        self
+    }
+
+    /// Indent every line by `indent` spaces.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Fit the table in `width` columns instead of the terminal's width;
+    /// `None` never truncates.
+    pub fn max_width(mut self, width: Option<usize>) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    /// Add a row. Missing cells are blank and extra cells are dropped.
+    pub fn row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut cells: Vec<String> = cells.into_iter().map(Into::into).collect();
+        cells.resize(self.columns.len(), String::new());
+        self.rows.push(cells);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The width of each column after fitting the table in `max_width`.
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                self.rows
+                    .iter()
+                    .map(|row| cell_width(&row[i]))
+                    .chain(std::iter::once(measure_text_width(&column.header)))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let Some(max_width) = self.max_width else {
+            return widths;
+        };
+        let floor: Vec<usize> = self
+            .columns
+            .iter()
+            .map(|column| measure_text_width(&column.header).max(MIN_SHRUNK_WIDTH))
+            .collect();
+        let total = |widths: &[usize]| {
+            self.indent + widths.iter().sum::<usize>() + GAP * widths.len().saturating_sub(1)
+        };
+        // Take one cell at a time from the widest column that may still give
+        // one up, so the shrinkable columns end up evenly narrowed.
+        while total(&widths) > max_width {
+            let Some(widest) = (0..widths.len())
+                .filter(|&i| self.columns[i].shrink && widths[i] > floor[i])
+                .max_by_key(|&i| widths[i])
+            else {
+                break;
+            };
+            widths[widest] -= 1;
+        }
+        widths
+    }
+
+    /// The table as lines of text: a bold header row, a rule, then the rows.
+    pub fn render(&self) -> Vec<String> {
+        let widths = self.widths();
+        let indent = " ".repeat(self.indent);
+        let line = |cells: Vec<String>| {
+            let last = cells.len().saturating_sub(1);
+            let mut out = indent.clone();
+            for (i, cell) in cells.iter().enumerate() {
+                let column = &self.columns[i];
+                let width = cell_width(cell);
+                if width > widths[i] {
+                    let cell = truncate_str(&strip_hyperlinks(cell), widths[i], "…").into_owned();
+                    if i == last && column.align == Align::Left {
+                        out.push_str(&cell);
+                    } else {
+                        let align = match column.align {
+                            Align::Left => Alignment::Left,
+                            Align::Right => Alignment::Right,
+                        };
+                        out.push_str(&pad_str(&cell, widths[i], align, None));
+                    }
+                } else {
+                    // Pad by the measured width ourselves: console does not
+                    // know hyperlinks take no space.
+                    let padding = " ".repeat(widths[i] - width);
+                    match column.align {
+                        // No trailing padding on the last column.
+                        Align::Left if i == last => out.push_str(cell),
+                        Align::Left => {
+                            out.push_str(cell);
+                            out.push_str(&padding);
+                        }
+                        Align::Right => {
+                            out.push_str(&padding);
+                            out.push_str(cell);
+                        }
+                    }
+                }
+                if i < last {
+                    out.push_str(&" ".repeat(GAP));
+                }
+            }
+            out.trim_end().to_string()
+        };
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 2);
+        lines.push(line(
+            self.columns
+                .iter()
+                .map(|c| style(&c.header).bold().to_string())
+                .collect(),
+        ));
+        let rule_width = widths.iter().sum::<usize>() + GAP * widths.len().saturating_sub(1);
+        lines.push(format!("{}{}", indent, style("─".repeat(rule_width)).dim()));
+        lines.extend(self.rows.iter().map(|row| line(row.clone())));
+        lines
+    }
+
+    /// Print the table to stdout.
+    pub fn print(&self) {
+        for line in self.render() {
+            println!("{}", line);
+        }
+    }
+}
+
+/// `text` as an OSC 8 hyperlink to `url`. Terminals without hyperlink support
+/// show just the text; callers decide whether to emit links at all.
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// `text` with the OSC 8 hyperlink markers removed, keeping the link text.
+pub fn strip_hyperlinks(text: &str) -> Cow<'_, str> {
+    if !text.contains("\x1b]8;") {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("\x1b]8;") {
+        out.push_str(&rest[..start]);
+        // The marker runs to the string terminator (ESC \ or BEL).
+        let marker = &rest[start..];
+        let end = marker
+            .find("\x1b\\")
+            .map(|i| i + 2)
+            .or_else(|| marker.find('\x07').map(|i| i + 1))
+            .unwrap_or(marker.len());
+        rest = &marker[end..];
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Terminal cells `cell` takes up, ignoring styling and hyperlink markers.
+fn cell_width(cell: &str) -> usize {
+    measure_text_width(&strip_hyperlinks(cell))
+}
+
+/// The width of the terminal stdout is attached to, or `None` when stdout is
+/// not a terminal (output piped to a file or another program is never
+/// truncated).
+pub fn terminal_width() -> Option<usize> {
+    Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| columns as usize)
+        .filter(|&columns| columns > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: Vec<String>) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| console::strip_ansi_codes(line).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn columns_fit_their_widest_cell() {
+        let mut table = Table::new(["Project", "Changes"])
+            .align(1, Align::Right)
+            .max_width(None);
+        table.row(["api", "3"]);
+        table.row(["services/billing", "12"]);
+        assert_eq!(
+            plain(table.render()),
+            [
+                "Project           Changes",
+                "─────────────────────────",
+                "api                     3",
+                "services/billing       12",
+            ]
+        );
+    }
+
+    #[test]
+    fn widths_count_terminal_cells_not_bytes() {
+        let mut table = Table::new(["Name", "State"]).indent(2).max_width(None);
+        table.row([
+            "日本語",
+            &style("ok").green().force_styling(true).to_string(),
+        ]);
+        table.row(["✨ new", "missing"]);
+        table.row(["x", ""]);
+        assert_eq!(
+            plain(table.render()),
+            [
+                "  Name    State",
+                "  ───────────────",
+                "  日本語  ok",
+                "  ✨ new  missing",
+                "  x",
+            ]
+        );
+    }
+
+    #[test]
+    fn hyperlinks_take_no_width() {
+        let link = hyperlink("ENG-42", "https://acme.atlassian.net/browse/ENG-42");
+        assert_eq!(strip_hyperlinks(&link), "ENG-42");
+        assert_eq!(strip_hyperlinks("\x1b]8;;u\x07x\x1b]8;;\x07"), "x");
+
+        let mut table = Table::new(["Branch", "Changes"])
+            .align(1, Align::Right)
+            .max_width(None);
+        table.row([format!("feat/{}-login", link).as_str(), "3"]);
+        table.row(["main", "12"]);
+        let lines = table.render();
+        assert!(lines[2].contains(&link));
+        let lines: Vec<String> = plain(lines)
+            .iter()
+            .map(|l| strip_hyperlinks(l).into_owned())
+            .collect();
+        assert_eq!(lines[2], "feat/ENG-42-login        3");
+        assert_eq!(lines[3], "main                    12");
+
+        // Truncation drops the link but keeps its text.
+        let mut table = Table::new(["Branch"]).shrink(0).max_width(Some(10));
+        table.row([format!("feat/{}-login", link)]);
+        assert_eq!(plain(table.render())[2], "feat/ENG-…");
+    }
+
+    #[test]
+    fn only_shrinkable_columns_are_truncated_to_fit() {
+        let mut table = Table::new(["Project", "Remote", "Status"])
+            .shrink(1)
+            .max_width(Some(40));
+        table.row([
+            "api",
+            "git@github.com:acme/a-very-long-repository-name.git",
+            "active",
+        ]);
+        let lines = plain(table.render());
+        assert_eq!(lines[2], "api      git@github.com:acme/a-…  active");
+        assert!(
+            lines.iter().all(|l| measure_text_width(l) <= 40),
+            "{:?}",
+            lines
+        );
+
+        // Nothing may shrink: the table is left as wide as it needs to be.
+        let mut table = Table::new(["Project", "Remote"]).max_width(Some(10));
+        table.row(["api", "git@github.com:acme/api.git"]);
+        assert!(plain(table.render())[2].ends_with("acme/api.git"));
+    }
+}
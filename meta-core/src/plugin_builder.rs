@@ -342,6 +342,7 @@ pub struct ArgBuilder {
     takes_value: bool,
     default_value: Option<String>,
     possible_values: Vec<String>,
+    multiple: bool,
 }
 
 impl ArgBuilder {
@@ -356,6 +357,7 @@ impl ArgBuilder {
             takes_value: false,
             default_value: None,
             possible_values: Vec::new(),
+            multiple: false,
         }
     }
 
@@ -401,6 +403,17 @@ impl ArgBuilder {
         self
     }
 
+    /// Accept several values: a positional takes every remaining value, an
+    /// option may be repeated. Implies `takes_value`. Read them back with
+    /// `get_many`.
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        if multiple {
+            self.takes_value = true;
+        }
+        self
+    }
+
     /// Build the clap Arg
     fn build(&self) -> Arg {
         let name: &'static str = Box::leak(self.name.clone().into_boxed_str());
@@ -424,7 +437,12 @@ impl ArgBuilder {
             arg = arg.required(true);
         }
 
-        if self.takes_value {
+        if self.multiple {
+            arg = arg.action(clap::ArgAction::Append);
+            if self.short.is_none() && self.long.is_none() {
+                arg = arg.num_args(1..);
+            }
+        } else if self.takes_value {
             arg = arg.action(clap::ArgAction::Set);
         } else {
             arg = arg.action(clap::ArgAction::SetTrue);
@@ -615,6 +633,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_arg_builder_multiple_positional_collects_values() {
+        let cmd = Command::new("test").arg(arg("targets").multiple(true).build());
+        let matches = cmd
+            .try_get_matches_from(["test", "services/*", "libs/**"])
+            .unwrap();
+        let values: Vec<&String> = matches.get_many::<String>("targets").unwrap().collect();
+        assert_eq!(values, vec!["services/*", "libs/**"]);
+    }
+
     #[test]
     fn test_arg_builder_with_value() {
         let arg = arg("input")
@@ -724,7 +752,9 @@ mod tests {
             experimental: false,
             non_interactive: None,
             scope_workspace: false,
+            profile: None,
             settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
         };
 
         // Handle the command - the plugin will look for subcommands in the matches
@@ -0,0 +1,124 @@
+//! Workspace profiles: named subsets of the projects.
+//!
+//! ```json
+//! "profiles": {
+//!   "frontend": ["web", "design-system", "libs/ui-*"],
+//!   "backend": ["api", "services/**"],
+//!   "minimal": ["api"]
+//! }
+//! ```
+//!
+//! Entries are project selectors, as `--projects` takes them. The global
+//! `--profile <name>` flag narrows the workspace to one profile before any
+//! command sees it, so cloning, updating, `exec`, `run`, and the worktree
+//! commands only touch that slice; projects outside it behave as if they were
+//! not in the workspace at all.
+
+use crate::MetaConfig;
+use anyhow::{anyhow, Result};
+
+/// Environment variable naming the profile to use when `--profile` is not
+/// given.
+pub const PROFILE_ENV: &str = "METAREPO_PROFILE";
+
+impl MetaConfig {
+    /// The profile names, sorted.
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .profiles
+            .as_ref()
+            .map(|p| p.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// The project keys in profile `name`, sorted. Selectors that match no
+    /// project are ignored, so a profile survives a project being removed.
+    pub fn profile_projects(&self, name: &str) -> Result<Vec<String>> {
+        let selectors = self
+            .profiles
+            .as_ref()
+            .and_then(|p| p.get(name))
+            .ok_or_else(|| {
+                let names = self.profile_names();
+                if names.is_empty() {
+                    anyhow!(
+                        "Unknown profile '{}': the workspace defines no profiles",
+                        name
+                    )
+                } else {
+                    anyhow!(
+                        "Unknown profile '{}'. Available profiles: {}",
+                        name,
+                        names.join(", ")
+                    )
+                }
+            })?;
+        let mut projects = self.select_projects(selectors).projects;
+        projects.sort();
+        Ok(projects)
+    }
+
+    /// Narrow the workspace to profile `name`, dropping every project outside
+    /// it.
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let keep = self.profile_projects(name)?;
+        self.projects.retain(|key, _| keep.contains(key));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConfigFormat, MetaConfig};
+
+    fn workspace() -> MetaConfig {
+        MetaConfig::load_from_str(
+            r#"{
+                "projects": {
+                    "web": "git@github.com:acme/web.git",
+                    "api": "git@github.com:acme/api.git",
+                    "services/billing": "git@github.com:acme/billing.git",
+                    "services/search": "git@github.com:acme/search.git"
+                },
+                "profiles": {
+                    "backend": ["api", "services/*", "gone"],
+                    "frontend": ["web"]
+                }
+            }"#,
+            ConfigFormat::Json,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn profiles_resolve_selectors_to_project_keys() {
+        let config = workspace();
+        assert_eq!(config.profile_names(), vec!["backend", "frontend"]);
+        assert_eq!(
+            config.profile_projects("backend").unwrap(),
+            vec!["api", "services/billing", "services/search"]
+        );
+    }
+
+    #[test]
+    fn applying_a_profile_narrows_the_workspace() {
+        let mut config = workspace();
+        config.apply_profile("frontend").unwrap();
+        let keys: Vec<&String> = config.projects.keys().collect();
+        assert_eq!(keys, vec!["web"]);
+        assert!(config.resolve_identifier("api").is_none());
+    }
+
+    #[test]
+    fn unknown_profiles_are_rejected_with_the_choices() {
+        let mut config = workspace();
+        let err = config.apply_profile("mobile").unwrap_err().to_string();
+        assert!(err.contains("backend, frontend"), "{}", err);
+        assert_eq!(config.projects.len(), 4);
+
+        config.profiles = None;
+        assert!(config.profile_projects("backend").is_err());
+    }
+}
@@ -152,6 +152,9 @@ pub struct RuntimeConfigDto {
     /// Defaults to `false` so older hosts/plugins remain compatible.
     #[serde(default)]
     pub scope_workspace: bool,
+    /// The active workspace profile; `meta_config` is already narrowed to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
 }
 
 impl RuntimeConfigDto {
@@ -175,6 +178,7 @@ impl From<&RuntimeConfig> for RuntimeConfigDto {
             meta_file_path: config.meta_file_path.clone(),
             experimental: config.experimental,
             scope_workspace: config.scope_workspace,
+            profile: config.profile.clone(),
         }
     }
 }
@@ -188,7 +192,9 @@ impl From<RuntimeConfigDto> for RuntimeConfig {
             experimental: dto.experimental,
             non_interactive: None,
             scope_workspace: dto.scope_workspace,
+            profile: dto.profile,
             settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
         }
     }
 }
@@ -302,7 +308,9 @@ mod tests {
             experimental: false,
             non_interactive: None,
             scope_workspace: false,
+            profile: None,
             settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
         };
         let dto: RuntimeConfigDto = (&config).into();
         assert_eq!(dto.working_dir, config.working_dir);
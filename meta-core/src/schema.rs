@@ -0,0 +1,622 @@
+//! A JSON Schema for the workspace config, printed by
+//! `meta config schema --json-schema`.
+//!
+//! Editors use it to complete and check `.meta` files edited by hand: register
+//! the printed file with VS Code's `json.schemas` setting, or point a YAML
+//! config at it with a `# yaml-language-server: $schema=<path>` comment. The
+//! schema is written by hand next to [`MetaConfig`] rather than derived, so a
+//! test keeps the two in step. Settings that plugins declare (see
+//! [`ConfigSetting`]) are merged in, so a plugin's keys complete too.
+
+use crate::{ConfigSetting, ConfigValueType};
+use serde_json::{json, Map, Value};
+
+/// The JSON Schema (draft-07, which editors support best) describing the
+/// workspace config, including the settings in `settings`.
+pub fn config_json_schema(settings: &[ConfigSetting]) -> Value {
+    let mut schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "metarepo workspace config",
+        "description": "The .meta file at the root of a metarepo workspace.",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": top_level_properties(),
+        "definitions": definitions(),
+    });
+    for setting in settings {
+        merge_setting(&mut schema, setting);
+    }
+    schema
+}
+
+fn top_level_properties() -> Value {
+    json!({
+        "extends": {
+            "type": "array",
+            "description": "Shared config fragments merged underneath this file at load time.",
+            "items": { "$ref": "#/definitions/extendsEntry" }
+        },
+        "ignore": string_list("Paths left out of project discovery and the workspace .gitignore."),
+        "projects": {
+            "type": "object",
+            "description": "The workspace's projects, keyed by their path in the workspace.",
+            "additionalProperties": { "$ref": "#/definitions/project" }
+        },
+        "disabled": string_list(
+            "Projects excluded from default and bulk operations (keys, aliases, or globs)."
+        ),
+        "default_excludes": string_list(
+            "Projects (keys, aliases, globs, or tags) left out of implicit and --all selections but reachable by name."
+        ),
+        "profiles": {
+            "type": "object",
+            "description": "Named project subsets selected with --profile.",
+            "additionalProperties": { "type": "array", "items": { "type": "string" } }
+        },
+        "groups": {
+            "type": "object",
+            "description": "Named, described project groups selected with --group.",
+            "additionalProperties": { "$ref": "#/definitions/group" }
+        },
+        "plugins": string_map("Plugins to load: name -> version or path."),
+        "plugin_priority": string_map(
+            "External plugin that gets a contested command: command -> plugin."
+        ),
+        "disabled_plugins": string_list("Built-in plugins to leave out, by name."),
+        "override": string_map(
+            "Built-in plugins replaced by an external plugin: built-in -> plugin."
+        ),
+        "modules": string_map("Workspace modules: name -> repository-relative path."),
+        "nested": object(
+            "How nested .meta files are imported.",
+            json!({
+                "recursive_import": boolean(
+                    "Import projects from nested .meta files (default: false)."
+                ),
+                "max_depth": count("How deep to follow nested .meta files (default: 3)."),
+                "flatten": boolean("Import nested projects at the top level."),
+                "cycle_detection": boolean("Stop at a .meta that imports itself (default: true)."),
+                "ignore_nested": string_list("Nested projects not to import."),
+                "namespace_separator": string("Separator between namespace and project name."),
+                "preserve_structure": boolean("Keep the nested directory layout."),
+                "dedupe": choice(
+                    "How a repository imported under another name is handled (default: warn).",
+                    &["link", "warn", "off"]
+                )
+            }),
+        ),
+        "aliases": string_map("Global aliases: alias -> project key."),
+        "scripts": {
+            "type": "object",
+            "description": "Scripts every project can run with meta run.",
+            "additionalProperties": { "$ref": "#/definitions/script" }
+        },
+        "worktree_init": string("Command run in each new worktree."),
+        "branch_policy": {
+            "type": "object",
+            "description": "Naming rule for new branches created by meta worktree add.",
+            "required": ["pattern"],
+            "properties": {
+                "pattern": string("Regular expression new branch names must match (anchored)."),
+                "examples": string_list("Conforming branch names shown on rejection.")
+            },
+            "additionalProperties": false
+        },
+        "templates": {
+            "type": "object",
+            "description": "Project templates for meta project new, keyed by name.",
+            "additionalProperties": {
+                "type": "object",
+                "required": ["url"],
+                "properties": {
+                    "url": string("Repository the new project is copied from."),
+                    "description": string("What the template is for."),
+                    "remote": string("Remote URL for new projects; may use {project} and {name}."),
+                    "create_remote": string("Shell command that creates the remote repository.")
+                },
+                "additionalProperties": false
+            }
+        },
+        "issue_trackers": {
+            "type": "array",
+            "description": "Issue trackers whose references are linked in branch names.",
+            "items": {
+                "type": "object",
+                "required": ["pattern", "url"],
+                "properties": {
+                    "pattern": string("Regular expression matching an issue reference."),
+                    "url": string("Link template; {id} is the first capture group or the match.")
+                },
+                "additionalProperties": false
+            }
+        },
+        "default_script": string("Script meta run runs when no script is named."),
+        "default_bare": boolean("Clone new projects as bare repositories."),
+        "plugins-integrity": choice(
+            "Whether plugins must match a recorded checksum (default: off).",
+            &["off", "required"]
+        ),
+        "allow-version-mismatch": boolean(
+            "Warn instead of failing when a plugin's version differs from its pin."
+        ),
+        "plugin-allow-any-path": boolean("Load plugins from any directory."),
+        "skill": object(
+            "Settings for the meta skill commands.",
+            json!({
+                "dest": string("Default install directory for stolen skills."),
+                "adapt-command": string("AI command used by --adapt (default: claude)."),
+                "adapt-args": string_list("Arguments for the adapt command; {prompt} is replaced."),
+                "search-url": string("skills.sh search endpoint."),
+                "detail-url": string("skills.sh skill-detail endpoint."),
+                "search-limit": count("Default number of meta skill search hits (default: 25)."),
+                "api-key": string("skills.sh API key; SKILLS_SH_API_KEY takes precedence.")
+            }),
+        ),
+        "mcp": object(
+            "Settings for the experimental meta mcp plugin.",
+            json!({
+                "serve": object(
+                    "What an MCP client may do to this workspace under meta mcp serve.",
+                    json!({
+                        "mode": choice(
+                            "Access level (default: full).",
+                            &["full", "read-write", "read-only"]
+                        ),
+                        "allow-exec": boolean("Allow the exec tool (default: true)."),
+                        "tools": string_list("Only expose these tools."),
+                        "projects": string_list("Projects the exec tool may target.")
+                    }),
+                )
+            }),
+        ),
+        "clone": object(
+            "Clone-time checks.",
+            json!({
+                "size-warn-mb": count(
+                    "Warn before cloning a repository larger than this (default: 500)."
+                ),
+                "depth": count("History depth for new clones (default: full clones)."),
+                "limit-rate": count(
+                    "Bandwidth limit for clones and fetches, in KB/s (default: unlimited)."
+                ),
+                "recurse-submodules": boolean(
+                    "Initialize and update git submodules in clones and project updates."
+                )
+            }),
+        ),
+        "exec": object(
+            "meta exec behavior.",
+            json!({
+                "output": choice(
+                    "Output mode: one block per project, live lines prefixed with the project, \
+                     or only each project's result.",
+                    &["buffered", "prefixed", "silent", "sections", "prefix"]
+                )
+            }),
+        ),
+        "exec_templates": string_map(
+            "Command lines for meta exec @<name>, keyed by name; may use {name}, {path}, \
+             {branch}, {url}, and project env values."
+        ),
+        "run": object(
+            "meta run behavior.",
+            json!({
+                "fallback": {
+                    "type": "array",
+                    "description": "Task runners to try when a project has no script of that name.",
+                    "items": { "enum": ["npm", "yarn", "pnpm", "just", "cargo-make", "make"] }
+                }
+            }),
+        ),
+        "onboard": object(
+            "The new-developer flow run by meta onboard.",
+            json!({
+                "group": string("Project group to clone and set up (default: every project)."),
+                "tools": string_list("Programs that must be on PATH."),
+                "manual_steps": string_list("Steps to do by hand, listed at the end.")
+            }),
+        ),
+        "notify": object(
+            "Completion notifications.",
+            json!({
+                "on_complete_secs": count("Notify when a command runs at least this many seconds."),
+                "desktop": boolean("Also show a desktop notification.")
+            }),
+        ),
+        "badges": object(
+            "README badges written by meta docs badges.",
+            json!({
+                "build": string("Build status image URL."),
+                "coverage": string("Coverage image URL."),
+                "workspace": string("Link to the meta workspace."),
+                "readme": string("README file to update in each project (default: README.md).")
+            }),
+        ),
+        "todos": object(
+            "What meta todos looks for.",
+            json!({
+                "markers": string_list("Markers to find (default: TODO, FIXME, HACK).")
+            }),
+        ),
+        "sync_files": object(
+            "Files rendered from templates into projects by meta files sync.",
+            json!({
+                "vars": string_map("Template variables, available as vars.<name>."),
+                "secrets": string_list("Secret names templates may use as {{ secrets:NAME }}."),
+                "secret_ref": string(
+                    "What a secret placeholder is written as; {name} is the secret's name \
+                     (default: ${{ secrets.{name} }})."
+                ),
+                "files": {
+                    "type": "array",
+                    "description": "The files to write.",
+                    "items": require(
+                        object(
+                            "A template and where it is written.",
+                            json!({
+                                "source": string("Template, relative to the workspace root."),
+                                "target": string("Path written, relative to each project."),
+                                "projects": string_list(
+                                    "Projects that get the file (default: every project)."
+                                ),
+                                "root": boolean("Write once into the workspace root instead.")
+                            }),
+                        ),
+                        &["source", "target"],
+                    )
+                }
+            }),
+        ),
+        "services": {
+            "type": "object",
+            "description": "Long-running services for meta services up, keyed by project.",
+            "additionalProperties": { "$ref": "#/definitions/service" }
+        },
+        "min_success": {
+            "type": "object",
+            "description": "Percentage of projects that must succeed, keyed by command.",
+            "propertyNames": { "enum": ["exec", "run", "start", "dev", "update", "pull"] },
+            "additionalProperties": { "type": "number", "minimum": 0, "maximum": 100 }
+        },
+        "protected": {
+            "type": "array",
+            "description": "Operations that must be confirmed by typing the workspace name.",
+            "items": { "type": "string", "enum": ["force-push", "remove", "clean"] }
+        },
+        "help_descriptions": string_map("Help description overrides keyed by command path."),
+        "parallelism": count("Worker threads for parallel scans (default: one per CPU)."),
+        "color": choice("Colored output (default: auto).", &["auto", "always", "never"]),
+        "hints": boolean("Suggest next steps after commands (default: true)."),
+        "plugin_dirs": string_list("Extra plugin directories (read from the user config only)."),
+        "credentials": keyed(
+            "HTTPS credentials keyed by host (read from the user config only).",
+            "host_credentials"
+        ),
+        "hosts": keyed(
+            "SSH keys keyed by host or host/path prefix (read from the user config only).",
+            "ssh_host"
+        )
+    })
+}
+
+fn definitions() -> Value {
+    json!({
+        "project": {
+            "oneOf": [
+                {
+                    "type": "string",
+                    "description": "Repository URL, local:<path>, or external:<url>."
+                },
+                require(
+                    object(
+                    "A project with its settings.",
+                    json!({
+                        "url": string("Repository URL, local:<path>, or external:<url>."),
+                        "aliases": string_list("Other names the project can be selected by."),
+                        "tags": string_list("Labels for selecting the project with --tag."),
+                        "scripts": {
+                            "type": "object",
+                            "description": "The project's scripts.",
+                            "additionalProperties": { "$ref": "#/definitions/script" }
+                        },
+                        "env": string_map("Environment variables for the project's commands."),
+                        "worktree_init": string("Command run in each new worktree of the project."),
+                        "bare": boolean("Clone the project as a bare repository."),
+                        "default_script": string("Script meta run runs when no script is named."),
+                        "enabled": boolean("When false, leave the project out of bulk operations."),
+                        "depth": count("Shallow-clone depth recorded when the project was added."),
+                        "pinned": string("Git ref the project is frozen at."),
+                        "vendored": string("Commit the project was vendored from."),
+                        "visibility": choice(
+                            "Who the project may be shared with.",
+                            &["public", "internal", "restricted"]
+                        ),
+                        "vcs": choice(
+                            "Version-control system of the project (default: git).",
+                            &["git", "jj", "hg", "sapling", "sl"]
+                        ),
+                        "deprecated": object(
+                            "Marks the project as being retired.",
+                            json!({
+                                "since": string("When it was deprecated (a date or version)."),
+                                "replacement": string("What to use instead."),
+                                "remove_after": string("Date (YYYY-MM-DD) it should be gone by.")
+                            }),
+                        )
+                    }),
+                    ),
+                    &["url"],
+                )
+            ]
+        },
+        "script": {
+            "oneOf": [
+                { "type": "string", "description": "Command to run." },
+                require(
+                    object(
+                    "A command with discovery metadata.",
+                    json!({
+                        "cmd": string("Command to run."),
+                        "description": string("Shown by meta run --list."),
+                        "tags": string_list("Labels for finding the script.")
+                    }),
+                    ),
+                    &["cmd"],
+                )
+            ]
+        },
+        "extendsEntry": {
+            "oneOf": [
+                { "type": "string", "description": "Config file, relative to this one." },
+                require(
+                    object(
+                    "A config file in a git repository.",
+                    json!({
+                        "git": string("Repository URL."),
+                        "path": string("File within the repository."),
+                        "ref": string("Branch or tag (default: the remote's default branch).")
+                    }),
+                    ),
+                    &["git", "path"],
+                )
+            ]
+        },
+        "group": object(
+            "A named set of projects.",
+            json!({
+                "description": string("What the group is for."),
+                "projects": string_list("Members: project keys, aliases, basenames, or globs."),
+                "default_script": string("Script meta run --group runs when no script is named.")
+            }),
+        ),
+        "service": object(
+            "A project's long-running service.",
+            json!({
+                "script": string("Script that starts the service (default: the dev script)."),
+                "tags": string_list("Labels for meta services up --tag."),
+                "restart": boolean("Restart the service when it exits (default: true)."),
+                "ports": {
+                    "type": "array",
+                    "description": "Ports the service listens on.",
+                    "items": { "type": "integer", "minimum": 1, "maximum": 65535 }
+                }
+            }),
+        ),
+        "host_credentials": object(
+            "How to authenticate to one HTTPS host.",
+            json!({
+                "username": string("Username sent with the token (default: x-access-token)."),
+                "token-env": string("Environment variable holding the token."),
+                "token-command": string("Shell command printing the token.")
+            }),
+        ),
+        "ssh_host": object(
+            "SSH settings for a host or repository path.",
+            json!({
+                "ssh-key": string("Private key to authenticate with."),
+                "username": string("SSH username, overriding the one in the URL.")
+            }),
+        )
+    })
+}
+
+/// Describe a plugin-declared setting at its dotted key. Keys the schema
+/// already types keep their type; the setting only adds what is missing.
+fn merge_setting(schema: &mut Value, setting: &ConfigSetting) {
+    let mut node = schema;
+    for part in setting.key.split('.') {
+        let properties = node
+            .as_object_mut()
+            .expect("schema nodes are objects")
+            .entry("properties")
+            .or_insert_with(|| Value::Object(Map::new()));
+        node = properties
+            .as_object_mut()
+            .expect("properties is an object")
+            .entry(part)
+            .or_insert_with(|| json!({ "type": "object", "additionalProperties": false }));
+    }
+    let leaf = node.as_object_mut().expect("schema nodes are objects");
+    if leaf.get("type") == Some(&json!("object")) && !leaf.contains_key("properties") {
+        leaf.clear();
+        leaf.extend(
+            value_schema(setting.value_type)
+                .as_object()
+                .cloned()
+                .unwrap_or_default(),
+        );
+    }
+    leaf.entry("description")
+        .or_insert_with(|| json!(setting.description));
+    if let Some(choices) = &setting.choices {
+        leaf.entry("enum").or_insert_with(|| json!(choices));
+    }
+}
+
+fn value_schema(value_type: ConfigValueType) -> Value {
+    match value_type {
+        ConfigValueType::String => json!({ "type": "string" }),
+        ConfigValueType::Bool => json!({ "type": "boolean" }),
+        ConfigValueType::Integer => json!({ "type": "integer" }),
+        ConfigValueType::StringList => json!({ "type": "array", "items": { "type": "string" } }),
+    }
+}
+
+fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+fn boolean(description: &str) -> Value {
+    json!({ "type": "boolean", "description": description })
+}
+
+fn count(description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 0, "description": description })
+}
+
+fn choice(description: &str, choices: &[&str]) -> Value {
+    json!({ "type": "string", "enum": choices, "description": description })
+}
+
+fn string_list(description: &str) -> Value {
+    json!({ "type": "array", "items": { "type": "string" }, "description": description })
+}
+
+/// An object whose values are all the named definition.
+fn keyed(description: &str, definition: &str) -> Value {
+    json!({
+        "type": "object",
+        "description": description,
+        "additionalProperties": { "$ref": format!("#/definitions/{}", definition) }
+    })
+}
+
+fn string_map(description: &str) -> Value {
+    json!({
+        "type": "object",
+        "additionalProperties": { "type": "string" },
+        "description": description
+    })
+}
+
+/// An object accepting only `properties`.
+fn object(description: &str, properties: Value) -> Value {
+    json!({
+        "type": "object",
+        "description": description,
+        "properties": properties,
+        "additionalProperties": false
+    })
+}
+
+/// `schema` with `keys` marked as required.
+fn require(mut schema: Value, keys: &[&str]) -> Value {
+    schema["required"] = json!(keys);
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetaConfig;
+    use std::collections::BTreeSet;
+
+    fn keys(value: &Value) -> BTreeSet<String> {
+        value.as_object().unwrap().keys().cloned().collect()
+    }
+
+    /// A config setting every key, so serializing it lists them all.
+    const FULL: &str = r#"{
+        "extends": ["base.json"],
+        "disabled": ["old"],
+        "default_excludes": ["vendor/*"],
+        "profiles": {"web": ["web"]},
+        "groups": {"all": {"projects": ["*"]}},
+        "plugins": {"x": "1.0"},
+        "plugin_priority": {"deploy": "acme-deploy"},
+        "disabled_plugins": ["rules"],
+        "override": {"git": "my-git-plugin"},
+        "modules": {"m": "m"},
+        "nested": {},
+        "aliases": {"w": "web"},
+        "scripts": {"test": "make test"},
+        "worktree_init": "make",
+        "branch_policy": {"pattern": "feat/.+", "examples": ["feat/login"]},
+        "templates": {"svc": {
+            "url": "git@h:t.git", "description": "d", "remote": "git@h:{name}.git",
+            "create_remote": "gh repo create {name}"
+        }},
+        "issue_trackers": [{"pattern": "GH-(\\d+)", "url": "https://h/{repo}/issues/{id}"}],
+        "default_script": "test",
+        "default_bare": false,
+        "plugins-integrity": "off",
+        "allow-version-mismatch": false,
+        "plugin-allow-any-path": false,
+        "skill": {"dest": "~/skills"},
+        "mcp": {"serve": {"mode": "full"}},
+        "clone": {"depth": 1, "recurse-submodules": true},
+        "exec": {"output": "prefix"},
+        "exec_templates": {"image": "docker build -t {registry}/{name}:{branch} ."},
+        "run": {"fallback": ["npm"]},
+        "onboard": {"group": "backend", "tools": ["cargo"], "manual_steps": ["Get VPN access"]},
+        "notify": {"desktop": true},
+        "badges": {"readme": "README.md"},
+        "todos": {"markers": ["TODO", "XXX"]},
+        "sync_files": {"vars": {"rust": "1.80"}, "files": [{"source": "ci.yml", "target": "ci.yml"}]},
+        "services": {"web": {"ports": [3000]}},
+        "min_success": {"exec": 90},
+        "protected": ["force-push"],
+        "help_descriptions": {"project": "Projects"},
+        "parallelism": 4,
+        "color": "auto",
+        "hints": false,
+        "plugin_dirs": ["~/plugins"],
+        "credentials": {"github.com": {"username": "me", "token-env": "GH_TOKEN", "token-command": "gh auth token"}},
+        "hosts": {"gitlab.internal": {"ssh-key": "~/.ssh/deploy", "username": "git"}},
+        "projects": {"web": {
+            "url": "git@h:web.git", "tags": ["backend"], "default_script": "dev", "enabled": true,
+            "depth": 1, "pinned": "v1", "vendored": "abc", "visibility": "public",
+            "vcs": "git",
+            "deprecated": {"since": "2.0", "replacement": "api-v2", "remove_after": "2025-06-30"}
+        }}
+    }"#;
+
+    #[test]
+    fn schema_covers_every_config_key() {
+        let schema = config_json_schema(&[]);
+        let config: MetaConfig = serde_json::from_str(FULL).unwrap();
+        let written = serde_json::to_value(&config).unwrap();
+        assert_eq!(keys(&written), keys(&schema["properties"]));
+
+        let project = serde_json::to_value(&config.projects["web"]).unwrap();
+        let described = &schema["definitions"]["project"]["oneOf"][1]["properties"];
+        assert_eq!(keys(&project), keys(described));
+        let nested = serde_json::to_value(config.nested.unwrap()).unwrap();
+        assert_eq!(
+            keys(&nested),
+            keys(&schema["properties"]["nested"]["properties"])
+        );
+    }
+
+    #[test]
+    fn declared_settings_are_merged_in() {
+        let declared =
+            ConfigSetting::new("deploy.target", "Where to deploy", ConfigValueType::String)
+                .with_choices(["staging", "prod"]);
+        let known = ConfigSetting::new("skill.dest", "Install dir", ConfigValueType::Integer);
+        let schema = config_json_schema(&[declared, known]);
+
+        let target = &schema["properties"]["deploy"]["properties"]["target"];
+        assert_eq!(target["type"], "string");
+        assert_eq!(target["enum"], json!(["staging", "prod"]));
+        assert_eq!(target["description"], "Where to deploy");
+
+        // The hand-written type and description win over a declaration.
+        let dest = &schema["properties"]["skill"]["properties"]["dest"];
+        assert_eq!(dest["type"], "string");
+        assert_eq!(
+            dest["description"],
+            "Default install directory for stolen skills."
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! User-level defaults: `~/.config/metarepo/config.toml`.
+//!
+//! The user config holds personal defaults that apply in every workspace:
+//!
+//! ```toml
+//! parallelism = 8
+//! color = "always"
+//! plugin_dirs = ["~/src/meta-plugins"]
+//!
+//! [clone]
+//! depth = 1
+//!
+//! [credentials."git.example.com"]
+//! token-env = "EXAMPLE_TOKEN"
+//!
+//! [hosts."gitlab.internal"]
+//! ssh-key = "~/.ssh/gitlab_deploy"
+//! ```
+//!
+//! It uses the same keys as `.meta` (minus `projects` and `extends`) and is
+//! merged underneath the workspace config after the workspace's `extends`
+//! fragments, with the same rules: objects merge key by key, the combined
+//! lists accumulate, and anything the workspace sets wins.
+
+use crate::{extends::merge, ConfigFormat, MetaConfig};
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the user config file, overriding the default
+/// location. Set it to an empty string to skip the user config entirely.
+pub const USER_CONFIG_ENV: &str = "METAREPO_USER_CONFIG";
+
+/// Location of the user config: `$METAREPO_USER_CONFIG`, else
+/// `$XDG_CONFIG_HOME/metarepo/config.toml`, else
+/// `~/.config/metarepo/config.toml`. `None` when it is disabled or no home
+/// directory is known. The file need not exist.
+pub fn user_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(USER_CONFIG_ENV) {
+        return (!path.is_empty()).then(|| PathBuf::from(path));
+    }
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            PathBuf::from(std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?)
+                .join(".config")
+        }
+    };
+    Some(base.join("metarepo").join("config.toml"))
+}
+
+/// Where a value in the merged config came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// The workspace config file itself.
+    Workspace,
+    /// A fragment listed in the workspace's `extends`.
+    Extends,
+    /// The user config.
+    User,
+    /// Built-in default.
+    Default,
+}
+
+impl std::fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ConfigOrigin::Workspace => "workspace",
+            ConfigOrigin::Extends => "extends",
+            ConfigOrigin::User => "user",
+            ConfigOrigin::Default => "default",
+        })
+    }
+}
+
+impl MetaConfig {
+    /// Read the user config at `path` (TOML). A missing file is an empty
+    /// config; `projects` and `extends` are workspace-only and rejected.
+    pub fn load_user_config(path: &Path) -> Result<MetaConfig> {
+        if !path.exists() {
+            return Ok(MetaConfig {
+                ignore: Vec::new(),
+                ..MetaConfig::default()
+            });
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config = MetaConfig::load_from_str(&content, ConfigFormat::Toml)
+            .with_context(|| format!("Failed to load user config {}", path.display()))?;
+        if !config.projects.is_empty() || !config.extends.is_empty() {
+            return Err(anyhow!(
+                "{}: projects and extends belong in a workspace config, not the user config",
+                path.display()
+            ));
+        }
+        Ok(config)
+    }
+
+    /// This config with `user` merged underneath it, as commands see it.
+    pub fn with_user_defaults(&self, user: &MetaConfig) -> Result<MetaConfig> {
+        let mut merged = serde_json::to_value(user)?;
+        merge(&mut merged, serde_json::to_value(self)?, true);
+        let mut config: MetaConfig = serde_json::from_value(merged)?;
+        config.sanitize_after_load();
+        Ok(config)
+    }
+}
+
+/// Whether `config` sets the value at `path`: present, not null, and not an
+/// empty list (the values merging treats as unset).
+fn sets(config: Option<&MetaConfig>, path: &[String]) -> bool {
+    let Some(json) = config.and_then(|c| serde_json::to_value(c).ok()) else {
+        return false;
+    };
+    let mut current = &json;
+    for part in path {
+        match current.get(part) {
+            Some(value) => current = value,
+            None => return false,
+        }
+    }
+    !current.is_null() && !current.as_array().is_some_and(|a| a.is_empty())
+}
+
+fn leaves(value: &Value, path: &mut Vec<String>, out: &mut Vec<(Vec<String>, Value)>) {
+    match value {
+        Value::Object(object) => {
+            for (key, child) in object {
+                path.push(key.clone());
+                leaves(child, path, out);
+                path.pop();
+            }
+        }
+        Value::Null => {}
+        Value::Array(items) if items.is_empty() => {}
+        _ => out.push((path.clone(), value.clone())),
+    }
+}
+
+/// Every value set in `merged`, as `(dotted key, value, origin)`, sorted by
+/// key. `file` is the workspace config as written, `resolved` the same with
+/// its `extends` merged in, and `user` the user config. A value is credited
+/// to the highest layer that sets it; for the combined lists (`ignore`,
+/// `disabled`, `default_excludes`) that layer may not have contributed every
+/// entry.
+pub fn config_origins(
+    merged: &MetaConfig,
+    file: Option<&MetaConfig>,
+    resolved: Option<&MetaConfig>,
+    user: Option<&MetaConfig>,
+) -> Vec<(String, Value, ConfigOrigin)> {
+    let mut found = Vec::new();
+    if let Ok(json) = serde_json::to_value(merged) {
+        leaves(&json, &mut Vec::new(), &mut found);
+    }
+    let mut origins: Vec<(String, Value, ConfigOrigin)> = found
+        .into_iter()
+        .map(|(path, value)| {
+            let origin = if sets(file, &path) {
+                ConfigOrigin::Workspace
+            } else if sets(resolved, &path) {
+                ConfigOrigin::Extends
+            } else if sets(user, &path) {
+                ConfigOrigin::User
+            } else {
+                ConfigOrigin::Default
+            };
+            (path.join("."), value, origin)
+        })
+        .collect();
+    origins.sort_by(|a, b| a.0.cmp(&b.0));
+    origins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(json: &str) -> MetaConfig {
+        MetaConfig::load_from_str(json, ConfigFormat::Json).unwrap()
+    }
+
+    fn user(toml: &str) -> MetaConfig {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, toml).unwrap();
+        MetaConfig::load_user_config(&path).unwrap()
+    }
+
+    #[test]
+    fn user_defaults_merge_underneath_the_workspace() {
+        let user = user(
+            "parallelism = 8\ncolor = \"never\"\nignore = [\"dist\"]\n\
+             [clone]\ndepth = 1\nsize-warn-mb = 100\n",
+        );
+        let file = workspace(r#"{"ignore": [".git"], "color": "always", "clone": {"depth": 5}}"#);
+        let merged = file.with_user_defaults(&user).unwrap();
+
+        assert_eq!(merged.parallelism, Some(8));
+        assert_eq!(merged.color.as_deref(), Some("always"));
+        assert_eq!(merged.ignore, vec!["dist", ".git"]);
+        let clone = merged.clone_settings.clone().unwrap();
+        assert_eq!((clone.depth, clone.size_warn_mb), (Some(5), Some(100)));
+
+        let origins = config_origins(&merged, Some(&file), Some(&file), Some(&user));
+        let origin = |key: &str| origins.iter().find(|(k, _, _)| k == key).unwrap().2;
+        assert_eq!(origin("parallelism"), ConfigOrigin::User);
+        assert_eq!(origin("color"), ConfigOrigin::Workspace);
+        assert_eq!(origin("clone.depth"), ConfigOrigin::Workspace);
+        assert_eq!(origin("clone.size-warn-mb"), ConfigOrigin::User);
+        assert_eq!(origin("ignore"), ConfigOrigin::Workspace);
+    }
+
+    #[test]
+    fn user_config_rejects_projects_and_tolerates_a_missing_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let missing = MetaConfig::load_user_config(&tmp.path().join("none.toml")).unwrap();
+        assert!(missing.ignore.is_empty() && missing.parallelism.is_none());
+
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, "[projects]\napi = \"git@github.com:acme/api.git\"\n").unwrap();
+        assert!(MetaConfig::load_user_config(&path).is_err());
+    }
+
+    #[test]
+    fn worker_count_prefers_the_setting() {
+        let mut config = MetaConfig::default();
+        assert!(config.worker_count() >= 1);
+        config.parallelism = Some(3);
+        assert_eq!(config.worker_count(), 3);
+        config.parallelism = Some(0);
+        assert!(config.worker_count() >= 1);
+    }
+}
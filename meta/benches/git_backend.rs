@@ -0,0 +1,127 @@
+//! Compares the libgit2 and gitoxide backends on the read-only queries
+//! `meta git status`, `meta git log`, and `meta git branch list` run across
+//! a workspace.
+//!
+//!     cargo bench -p metarepo --features gix --bench git_backend
+//!
+//! Builds a workspace of `METAREPO_BENCH_REPOS` repositories (60 by default),
+//! each with some history, branches, and a dirty worktree, then times every
+//! query over all of them with each backend and prints the medians. Which
+//! backend wins depends on the platform and the shape of the repositories,
+//! so measure before switching with `METAREPO_GIT_BACKEND=gix`.
+
+use metarepo::plugins::shared::git_operations::{GitBackend, Libgit2Backend};
+use metarepo::plugins::shared::gix_backend::GixBackend;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const COMMITS: usize = 40;
+const FILES: usize = 200;
+const ROUNDS: usize = 9;
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .expect("git runs");
+    assert!(output.status.success(), "git {:?} failed", args);
+}
+
+fn make_repo(root: &Path, index: usize) -> PathBuf {
+    let path = root.join(format!("project-{:03}", index));
+    std::fs::create_dir_all(path.join("src")).unwrap();
+    git(&path, &["init", "-q", "-b", "main"]);
+    git(&path, &["config", "user.email", "bench@example.com"]);
+    git(&path, &["config", "user.name", "Bench"]);
+    for file in 0..FILES {
+        std::fs::write(path.join(format!("src/file{}.txt", file)), "v0").unwrap();
+    }
+    git(&path, &["add", "."]);
+    git(&path, &["commit", "-q", "-m", "Initial"]);
+    for commit in 1..COMMITS {
+        git(
+            &path,
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                &format!("Change {}", commit),
+            ],
+        );
+    }
+    for branch in ["develop", "feature/a", "feature/b"] {
+        git(&path, &["branch", branch]);
+    }
+    // Packed, as a clone is.
+    git(&path, &["gc", "-q"]);
+    std::fs::write(path.join("src/file0.txt"), "edited").unwrap();
+    std::fs::write(path.join("untracked.txt"), "new").unwrap();
+    path
+}
+
+/// Median time for `query` to run over every repository.
+fn time(repos: &[PathBuf], query: impl Fn(&Path)) -> Duration {
+    let mut samples: Vec<Duration> = (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            for repo in repos {
+                query(repo);
+            }
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+    samples[ROUNDS / 2]
+}
+
+fn main() {
+    let count: usize = std::env::var("METAREPO_BENCH_REPOS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(60);
+    let tmp = tempfile::tempdir().unwrap();
+    let repos: Vec<PathBuf> = (0..count).map(|i| make_repo(tmp.path(), i)).collect();
+    // Files written in the same second as the index are racily clean and
+    // get rehashed on every status; let git settle the index first, as it
+    // has in any checkout that has been around for a while.
+    std::thread::sleep(Duration::from_millis(1100));
+    for repo in &repos {
+        git(repo, &["status", "--porcelain"]);
+    }
+    println!(
+        "{} repositories, {} commits and {} files each; median of {} rounds\n",
+        count, COMMITS, FILES, ROUNDS
+    );
+
+    let backends: [&dyn GitBackend; 2] = [&Libgit2Backend, &GixBackend];
+    println!(
+        "{:<10} {:>12} {:>12} {:>8}",
+        "query", "libgit2", "gix", "speedup"
+    );
+    type Query = fn(&dyn GitBackend, &Path);
+    let queries: [(&str, Query); 3] = [
+        ("status", |b, p| {
+            b.status(p).unwrap();
+        }),
+        ("log", |b, p| {
+            b.log(p, None).unwrap();
+        }),
+        ("branches", |b, p| {
+            b.branches(p).unwrap();
+        }),
+    ];
+    for (name, query) in queries {
+        let [libgit2, gix] = backends.map(|backend| time(&repos, |repo| query(backend, repo)));
+        println!(
+            "{:<10} {:>12.2?} {:>12.2?} {:>7.2}x",
+            name,
+            libgit2,
+            gix,
+            libgit2.as_secs_f64() / gix.as_secs_f64()
+        );
+    }
+}
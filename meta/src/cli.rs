@@ -1,4 +1,4 @@
-use crate::{create_runtime_config_full, PluginRegistry};
+use crate::{create_runtime_config_full, create_runtime_config_without_workspace, PluginRegistry};
 use anyhow::Result;
 use clap::{Arg, ColorChoice, Command};
 use metarepo_core::NonInteractiveMode;
@@ -188,6 +188,13 @@ impl MetarepoCli {
                     .action(clap::ArgAction::SetTrue)
                     .help("Resolve the outermost enclosing metarepo instead of the nearest one")
                     .global(true)
+            )
+            .arg(
+                Arg::new("profile")
+                    .long("profile")
+                    .value_name("NAME")
+                    .help("Only operate on the projects in this workspace profile (also METAREPO_PROFILE)")
+                    .global(true)
             );
 
         // Apply the standard help layout (Options before Commands) to the whole
@@ -220,7 +227,9 @@ impl MetarepoCli {
         // up in --help and are recognized during argument parsing.
         let meta_config = metarepo_core::MetaConfig::load().ok();
         if let Some(ref mc) = meta_config {
-            self.registry.borrow_mut().load_external_plugins(mc);
+            self.registry
+                .borrow_mut()
+                .load_external_plugins(&with_user_plugin_settings(mc));
         }
 
         let mut app = self.build_app();
@@ -242,24 +251,39 @@ impl MetarepoCli {
         let config_override = resolve_config_override(matches.get_one::<String>("config"));
         let scope_workspace = matches.get_flag("workspace");
         let discover_root = matches.get_flag("root");
+        let profile = resolve_profile(matches.get_one::<String>("profile"));
 
         // Load runtime configuration
-        let mut config = create_runtime_config_full(
+        let mut config = match create_runtime_config_full(
             false,
             non_interactive,
             config_override,
             scope_workspace,
             discover_root,
-        )?;
+            profile,
+        ) {
+            Ok(config) => config,
+            // `meta reconstruct` rebuilds a lost or unreadable workspace
+            // config, so it must run without one.
+            Err(e) if matches.subcommand_name() == Some("reconstruct") => {
+                eprintln!("warning: ignoring the workspace config: {}", e);
+                create_runtime_config_without_workspace(false, non_interactive)?
+            }
+            Err(e) => return Err(e),
+        };
         // Aggregate declared plugin settings so `meta config` can list them.
         config.settings_catalog = self.registry.borrow().collect_settings();
+        config.plugin_conflicts = self.registry.borrow().conflicts().to_vec();
+        apply_color_setting(&config.meta_config);
 
         // Route to appropriate plugin
         match matches.subcommand() {
             Some((command_name, sub_matches)) => {
                 self.registry
                     .borrow()
-                    .handle_command(command_name, sub_matches, &config)
+                    .handle_command(command_name, sub_matches, &config)?;
+                crate::plugins::tips::show_hints(command_name, sub_matches, &config);
+                Ok(())
             }
             None => {
                 // No subcommand provided, show help
@@ -276,7 +300,9 @@ impl MetarepoCli {
         // up in --help and are recognized during argument parsing.
         let meta_config = metarepo_core::MetaConfig::load().ok();
         if let Some(ref mc) = meta_config {
-            self.registry.borrow_mut().load_external_plugins(mc);
+            self.registry
+                .borrow_mut()
+                .load_external_plugins(&with_user_plugin_settings(mc));
         }
 
         // Parse with experimental plugins available
@@ -299,16 +325,29 @@ impl MetarepoCli {
         let config_override = resolve_config_override(matches.get_one::<String>("config"));
         let scope_workspace = matches.get_flag("workspace");
         let discover_root = matches.get_flag("root");
+        let profile = resolve_profile(matches.get_one::<String>("profile"));
 
         // Load runtime configuration with experimental flag
-        let mut config = create_runtime_config_full(
+        let mut config = match create_runtime_config_full(
             true,
             non_interactive,
             config_override,
             scope_workspace,
             discover_root,
-        )?;
+            profile,
+        ) {
+            Ok(config) => config,
+            // `meta reconstruct` rebuilds a lost or unreadable workspace
+            // config, so it must run without one.
+            Err(e) if matches.subcommand_name() == Some("reconstruct") => {
+                eprintln!("warning: ignoring the workspace config: {}", e);
+                create_runtime_config_without_workspace(true, non_interactive)?
+            }
+            Err(e) => return Err(e),
+        };
         config.settings_catalog = self.registry.borrow().collect_settings();
+        config.plugin_conflicts = self.registry.borrow().conflicts().to_vec();
+        apply_color_setting(&config.meta_config);
 
         tracing::debug!("Experimental features enabled");
 
@@ -317,7 +356,9 @@ impl MetarepoCli {
             Some((command_name, sub_matches)) => {
                 self.registry
                     .borrow()
-                    .handle_command(command_name, sub_matches, &config)
+                    .handle_command(command_name, sub_matches, &config)?;
+                crate::plugins::tips::show_hints(command_name, sub_matches, &config);
+                Ok(())
             }
             None => {
                 // No subcommand provided, show help
@@ -349,6 +390,38 @@ impl Default for MetarepoCli {
     }
 }
 
+/// Honor the `color` setting. `auto` (or unset) leaves `colored`'s own
+/// detection in place, which respects `NO_COLOR` and non-terminal output.
+fn apply_color_setting(config: &metarepo_core::MetaConfig) {
+    match config.color.as_deref() {
+        Some("always") => colored::control::set_override(true),
+        Some("never") => colored::control::set_override(false),
+        _ => {}
+    }
+}
+
+/// The workspace config with the user config's `disabled_plugins` and
+/// `override` underneath it, so an organization can set those once per
+/// machine. The workspace's own settings win.
+fn with_user_plugin_settings(workspace: &metarepo_core::MetaConfig) -> metarepo_core::MetaConfig {
+    let mut config = workspace.clone();
+    let Some(user) = metarepo_core::user_config_path()
+        .and_then(|path| metarepo_core::MetaConfig::load_user_config(&path).ok())
+    else {
+        return config;
+    };
+    if config.disabled_plugins.is_empty() {
+        config.disabled_plugins = user.disabled_plugins;
+    }
+    if let Some(user_overrides) = user.plugin_overrides {
+        let overrides = config.plugin_overrides.get_or_insert_with(HashMap::new);
+        for (builtin, plugin) in user_overrides {
+            overrides.entry(builtin).or_insert(plugin);
+        }
+    }
+    config
+}
+
 /// Resolve the effective `--config` override: explicit flag wins, then the
 /// `METAREPO_CONFIG` env var, otherwise None (let discovery run).
 fn resolve_config_override(flag: Option<&String>) -> Option<PathBuf> {
@@ -358,6 +431,17 @@ fn resolve_config_override(flag: Option<&String>) -> Option<PathBuf> {
     std::env::var_os("METAREPO_CONFIG").map(PathBuf::from)
 }
 
+/// The workspace profile: `--profile`, else `METAREPO_PROFILE` when set and
+/// non-empty.
+fn resolve_profile(flag: Option<&String>) -> Option<String> {
+    if let Some(name) = flag {
+        return Some(name.clone());
+    }
+    std::env::var(metarepo_core::PROFILE_ENV)
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,6 +7,7 @@
 //! writing the script with any cache refresh the shell needs.
 
 use anyhow::{Context, Result};
+use clap::builder::{PossibleValue, TypedValueParser};
 use clap::Command;
 use clap_complete::Shell;
 use std::path::{Path, PathBuf};
@@ -27,6 +28,63 @@ fn generate(shell: Shell, app: Command) -> Vec<u8> {
     buf
 }
 
+/// Script names offered when completing `meta run <script>`, with their
+/// descriptions as hints (shown by zsh, fish, and PowerShell).
+///
+/// Accepts any value: it only advertises candidates to the generator, so the
+/// real CLI is never restricted to the scripts known at install time.
+#[derive(Clone)]
+struct ScriptHints(Vec<PossibleValue>);
+
+impl ScriptHints {
+    fn new(hints: Vec<(String, Option<String>)>) -> Self {
+        Self(
+            hints
+                .into_iter()
+                .map(|(name, description)| {
+                    // Without clap's `string` feature a PossibleValue needs a
+                    // 'static name; the completion app is built once per install.
+                    let name: &'static str = Box::leak(name.into_boxed_str());
+                    let value = PossibleValue::new(name);
+                    match description {
+                        Some(description) => value.help(description),
+                        None => value,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+impl TypedValueParser for ScriptHints {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> std::result::Result<String, clap::Error> {
+        clap::builder::StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(self.0.iter().cloned()))
+    }
+}
+
+/// Attach the workspace's script names to `meta run <script>` so shells can
+/// complete them. A no-op when there are no scripts.
+fn with_script_hints(app: Command, hints: Vec<(String, Option<String>)>) -> Command {
+    if hints.is_empty() || app.find_subcommand("run").is_none() {
+        return app;
+    }
+    let parser = ScriptHints::new(hints);
+    app.mut_subcommand("run", |run| {
+        run.mut_arg("script", |script| script.value_parser(parser))
+    })
+}
+
 /// Detect the user's shell from the basename of `$SHELL`.
 pub fn detect_shell() -> Option<Shell> {
     let shell_path = std::env::var("SHELL").ok()?;
@@ -73,12 +131,19 @@ fn home_dir() -> Result<PathBuf> {
 
 /// Generate and install a completion script for `shell`, refreshing caches as
 /// needed. Returns where the script was written and any manual follow-up step.
+///
+/// When run inside a workspace, the scripts it defines (with descriptions) are
+/// baked in as `meta run` candidates; re-install to pick up new scripts.
 pub fn install(shell: Shell) -> Result<InstallOutcome> {
     let home = home_dir()?;
     let omz = oh_my_zsh_dir(&home);
     let (path, refresh, manual_note) = resolve_target(shell, &home, omz.as_deref())?;
 
-    let script = generate(shell, crate::cli::MetarepoCli::new().build_app());
+    let hints = std::env::current_dir()
+        .map(|cwd| crate::plugins::run::completion_hints(&cwd))
+        .unwrap_or_default();
+    let app = with_script_hints(crate::cli::MetarepoCli::new().build_app(), hints);
+    let script = generate(shell, app);
 
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)
@@ -250,4 +315,26 @@ mod tests {
         assert!(!script.is_empty());
         assert!(String::from_utf8_lossy(&script).contains("project"));
     }
+
+    #[test]
+    fn script_hints_appear_in_completions_without_restricting_values() {
+        let app = Command::new("meta")
+            .version("0.0.0")
+            .subcommand(Command::new("run").arg(clap::Arg::new("script").index(1).required(false)));
+        let app = with_script_hints(
+            app,
+            vec![
+                ("deploy".to_string(), Some("Ship to staging".to_string())),
+                ("lint".to_string(), None),
+            ],
+        );
+        let script = String::from_utf8_lossy(&generate(Shell::Zsh, app.clone())).to_string();
+        assert!(script.contains("deploy"));
+        assert!(script.contains("Ship to staging"));
+        assert!(script.contains("lint"));
+
+        let matches = app.try_get_matches_from(["meta", "run", "other"]).unwrap();
+        let run = matches.subcommand_matches("run").unwrap();
+        assert_eq!(run.get_one::<String>("script").unwrap(), "other");
+    }
 }
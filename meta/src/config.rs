@@ -10,11 +10,12 @@ pub fn create_runtime_config_with_flags(
     experimental: bool,
     non_interactive: Option<NonInteractiveMode>,
 ) -> Result<RuntimeConfig> {
-    create_runtime_config_full(experimental, non_interactive, None, false, false)
+    create_runtime_config_full(experimental, non_interactive, None, false, false, None)
 }
 
 /// Build the runtime config, allowing the caller to override config discovery
-/// with an explicit file path (typically from `--config` or `METAREPO_CONFIG`).
+/// with an explicit file path (typically from `--config` or `METAREPO_CONFIG`)
+/// and to narrow the workspace to a profile (`--profile`).
 #[allow(clippy::fn_params_excessive_bools)]
 pub fn create_runtime_config_full(
     experimental: bool,
@@ -22,14 +23,16 @@ pub fn create_runtime_config_full(
     config_override: Option<PathBuf>,
     scope_workspace: bool,
     discover_root: bool,
+    profile: Option<String>,
 ) -> Result<RuntimeConfig> {
     let working_dir = std::env::current_dir()?;
 
     let (meta_config, meta_file_path) = if let Some(path) = config_override {
         // Explicit override: load from this path verbatim. Format detection is
         // best-effort; an unrecognized extension falls back to JSON.
-        let format = ConfigFormat::from_path(&path).unwrap_or(ConfigFormat::Json);
-        let config = MetaConfig::load_from_file_with_format(&path, format)?;
+        let format = ConfigFormat::of_file(&path).unwrap_or(ConfigFormat::Json);
+        let config =
+            MetaConfig::load_from_file_with_format(&path, format)?.resolve_extends(&path, false)?;
         (config, Some(path))
     } else {
         // `--root` resolves the outermost enclosing metarepo; otherwise the
@@ -41,7 +44,8 @@ pub fn create_runtime_config_full(
         };
         match discovered {
             Ok(Some(found)) => {
-                let config = MetaConfig::load_from_file_with_format(&found.path, found.format)?;
+                let config = MetaConfig::load_from_file_with_format(&found.path, found.format)?
+                    .resolve_extends(&found.path, false)?;
                 (config, Some(found.path))
             }
             Ok(None) => (MetaConfig::default(), None),
@@ -53,6 +57,20 @@ pub fn create_runtime_config_full(
         }
     };
 
+    // Personal defaults from the user config sit underneath everything the
+    // workspace sets.
+    let mut meta_config = match metarepo_core::user_config_path() {
+        Some(path) => meta_config.with_user_defaults(&MetaConfig::load_user_config(&path)?)?,
+        None => meta_config,
+    };
+
+    // Commands only ever see the profile's slice of the workspace. Outside a
+    // workspace there is nothing to narrow yet: `meta git clone` applies the
+    // profile to the workspace it clones.
+    if let (Some(name), Some(_)) = (&profile, &meta_file_path) {
+        meta_config.apply_profile(name)?;
+    }
+
     Ok(RuntimeConfig {
         meta_config,
         working_dir,
@@ -60,8 +78,36 @@ pub fn create_runtime_config_full(
         experimental,
         non_interactive,
         scope_workspace,
+        profile,
         // Populated by the CLI after the plugin registry is available.
         settings_catalog: Vec::new(),
+        plugin_conflicts: Vec::new(),
+    })
+}
+
+/// The runtime config with no workspace: the user config over the defaults.
+/// For commands that must run where the workspace config cannot be loaded,
+/// such as `meta reconstruct` rebuilding a corrupted one.
+pub fn create_runtime_config_without_workspace(
+    experimental: bool,
+    non_interactive: Option<NonInteractiveMode>,
+) -> Result<RuntimeConfig> {
+    let meta_config = match metarepo_core::user_config_path() {
+        Some(path) => {
+            MetaConfig::default().with_user_defaults(&MetaConfig::load_user_config(&path)?)?
+        }
+        None => MetaConfig::default(),
+    };
+    Ok(RuntimeConfig {
+        meta_config,
+        working_dir: std::env::current_dir()?,
+        meta_file_path: None,
+        experimental,
+        non_interactive,
+        scope_workspace: false,
+        profile: None,
+        settings_catalog: Vec::new(),
+        plugin_conflicts: Vec::new(),
     })
 }
 
@@ -7,6 +7,7 @@ pub mod plugins;
 pub use cli::MetarepoCli;
 pub use config::{
     create_runtime_config, create_runtime_config_full, create_runtime_config_with_flags,
+    create_runtime_config_without_workspace,
 };
 pub use metarepo_core::{MetaConfig, MetaPlugin, NonInteractiveMode, RuntimeConfig};
 pub use plugin::PluginRegistry;
@@ -27,6 +28,11 @@ pub enum MetarepoError {
 
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
+
+    /// A multi-project command in which some or all projects failed; `code` is
+    /// the process exit code (see `plugins::shared::outcome`).
+    #[error("{message}")]
+    ProjectsFailed { code: i32, message: String },
 }
 
 #[cfg(test)]
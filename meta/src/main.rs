@@ -1,4 +1,4 @@
-use metarepo::MetarepoCli;
+use metarepo::{MetarepoCli, MetarepoError};
 use std::env;
 use std::process;
 
@@ -7,6 +7,14 @@ fn main() {
     let cli = MetarepoCli::new();
 
     if let Err(e) = cli.run(args) {
+        if let Some(MetarepoError::ProjectsFailed { code, message }) =
+            e.downcast_ref::<MetarepoError>()
+        {
+            // The per-project summary is already printed; exit with the
+            // all-failed (1) or partial-failure (2) code.
+            eprintln!("Error: {}", message);
+            process::exit(*code);
+        }
         // Check if this is a clap error for help or version
         if let Some(clap_err) = e.downcast_ref::<clap::Error>() {
             match clap_err.kind() {
@@ -1,25 +1,141 @@
 use crate::{MetarepoError, RuntimeConfig};
 use anyhow::Result;
 use clap::{ArgMatches, Command};
-use metarepo_core::MetaPlugin;
-use std::collections::HashMap;
+use metarepo_core::{MetaPlugin, PluginConflict};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The command that reaches an external plugin by name when another plugin
+/// has its top-level command: `meta ext <plugin> <command...>`.
+pub const NAMESPACE_COMMAND: &str = "ext";
+
+/// Commands meta itself owns, which no plugin can take.
+const RESERVED_COMMANDS: [&str; 2] = [NAMESPACE_COMMAND, "help"];
 
 pub struct PluginRegistry {
     plugins: HashMap<String, Box<dyn MetaPlugin>>,
+    builtin: HashSet<String>,
+    /// Each top-level command and alias, and the plugin that owns it.
+    commands: HashMap<String, String>,
+    /// External plugins that lost a command to another plugin, by name.
+    namespaced: BTreeMap<String, Box<dyn MetaPlugin>>,
+    conflicts: Vec<PluginConflict>,
 }
 
 impl PluginRegistry {
     pub fn new() -> Self {
         Self {
             plugins: HashMap::new(),
+            builtin: HashSet::new(),
+            commands: HashMap::new(),
+            namespaced: BTreeMap::new(),
+            conflicts: Vec::new(),
         }
     }
 
+    /// Register a built-in plugin. Built-ins keep their commands whatever
+    /// external plugins declare.
     pub fn register(&mut self, plugin: Box<dyn MetaPlugin>) {
         let name = plugin.name().to_string();
+        for command in top_level_commands(plugin.as_ref()) {
+            self.commands.insert(command, name.clone());
+        }
+        self.builtin.insert(name.clone());
+        self.plugins.insert(name, plugin);
+    }
+
+    /// Register an external plugin. It gets its top-level commands unless
+    /// another plugin has one of them: a built-in always keeps it, and
+    /// between externals `priority` (command -> plugin, the config's
+    /// `plugin_priority`) decides, else the one registered first. The loser
+    /// is moved under `meta ext <plugin>`.
+    pub fn register_external(
+        &mut self,
+        plugin: Box<dyn MetaPlugin>,
+        priority: &HashMap<String, String>,
+    ) {
+        let name = plugin.name().to_string();
+        let commands = top_level_commands(plugin.as_ref());
+        let mut contested: Vec<(String, String)> = commands
+            .iter()
+            .filter_map(|command| {
+                let owner = if RESERVED_COMMANDS.contains(&command.as_str()) {
+                    "meta".to_string()
+                } else {
+                    self.commands.get(command)?.clone()
+                };
+                Some((command.clone(), owner))
+            })
+            .collect();
+        contested.sort();
+
+        if self.plugins.contains_key(&name) && !self.builtin.contains(&name)
+            || self.namespaced.contains_key(&name)
+        {
+            // Another external plugin has this name, so it cannot be told
+            // apart even under `meta ext`: the first one stays.
+            self.conflicts.push(PluginConflict {
+                command: name.clone(),
+                winner: name.clone(),
+                winner_builtin: false,
+                loser: name,
+                namespaced: false,
+                pinned: false,
+            });
+            return;
+        }
+
+        let takes_over = !contested.is_empty()
+            && contested.iter().all(|(command, owner)| {
+                !self.builtin.contains(owner)
+                    && owner != "meta"
+                    && priority.get(command) == Some(&name)
+            });
+        if takes_over {
+            for (command, owner) in &contested {
+                self.demote(owner);
+                self.conflicts.push(PluginConflict {
+                    command: command.clone(),
+                    winner: name.clone(),
+                    winner_builtin: false,
+                    loser: owner.clone(),
+                    namespaced: true,
+                    pinned: true,
+                });
+            }
+        } else if !contested.is_empty() {
+            for (command, owner) in contested {
+                self.conflicts.push(PluginConflict {
+                    winner_builtin: self.builtin.contains(&owner) || owner == "meta",
+                    pinned: priority.get(&command) == Some(&owner),
+                    command,
+                    winner: owner,
+                    loser: name.clone(),
+                    namespaced: true,
+                });
+            }
+            self.namespaced.insert(name, plugin);
+            return;
+        }
+
+        for command in commands {
+            self.commands.insert(command, name.clone());
+        }
         self.plugins.insert(name, plugin);
     }
 
+    /// Move the external plugin `name` off the top level, under `meta ext`.
+    fn demote(&mut self, name: &str) {
+        if let Some(plugin) = self.plugins.remove(name) {
+            self.commands.retain(|_, owner| owner != name);
+            self.namespaced.insert(name.to_string(), plugin);
+        }
+    }
+
+    /// The command conflicts found while registering, in registration order.
+    pub fn conflicts(&self) -> &[PluginConflict] {
+        &self.conflicts
+    }
+
     pub fn register_all_workspace_plugins(&mut self) {
         self.register_all_workspace_plugins_with_flags(false);
     }
@@ -32,13 +148,41 @@ impl PluginRegistry {
         self.register(Box::new(plugins::skill::SkillPlugin::new()));
         self.register(Box::new(plugins::module::ModulePlugin::new()));
         self.register(Box::new(plugins::git::GitPlugin::new()));
+        self.register(Box::new(plugins::gitignore::GitignorePlugin::new()));
+        self.register(Box::new(plugins::maintenance::MaintenancePlugin::new()));
+        self.register(Box::new(plugins::schedule::SchedulePlugin::new()));
         self.register(Box::new(plugins::project::ProjectPlugin::new()));
+        self.register(Box::new(plugins::export::ExportPlugin::new()));
         self.register(Box::new(plugins::config::ConfigPlugin::new()));
+        self.register(Box::new(plugins::auth::AuthPlugin::new()));
+        self.register(Box::new(plugins::migrate::MigratePlugin::new()));
+        self.register(Box::new(plugins::impact::ImpactPlugin::new()));
+        self.register(Box::new(plugins::docs::DocsPlugin::new()));
+        self.register(Box::new(plugins::files::FilesPlugin::new()));
+        self.register(Box::new(plugins::test_report::TestPlugin::new()));
         self.register(Box::new(plugins::exec::ExecPlugin::new()));
         self.register(Box::new(plugins::rules::RulesPlugin::new()));
         self.register(Box::new(plugins::worktree::WorktreePlugin::new()));
         self.register(Box::new(plugins::run::RunPlugin::new()));
+        self.register(Box::new(plugins::run::EntryPointPlugin::start()));
+        self.register(Box::new(plugins::run::EntryPointPlugin::dev()));
+        self.register(Box::new(plugins::run::VerifySetupPlugin));
+        self.register(Box::new(plugins::onboard::OnboardPlugin::new()));
+        self.register(Box::new(plugins::services::ServicesPlugin::new()));
+        self.register(Box::new(plugins::last::LastPlugin::new()));
+        self.register(Box::new(plugins::track::TrackPlugin::new()));
+        self.register(Box::new(plugins::stats::StatsPlugin::new()));
+        self.register(Box::new(plugins::todos::TodosPlugin::new()));
+        self.register(Box::new(plugins::feed::FeedPlugin::new()));
+        self.register(Box::new(plugins::reconstruct::ReconstructPlugin::new()));
+        self.register(Box::new(plugins::profile::ProfilePlugin::new()));
+        self.register(Box::new(plugins::lock::LockPlugin::new()));
+        self.register(Box::new(plugins::lock::VerifyPlugin::new()));
         self.register(Box::new(plugins::status::StatusPlugin::new()));
+        self.register(Box::new(plugins::daemon::DaemonPlugin::new()));
+        self.register(Box::new(plugins::which::WhichPlugin::new()));
+        self.register(Box::new(plugins::tips::TipsPlugin::new()));
+        self.register(Box::new(plugins::doctor::DoctorPlugin::new()));
         self.register(Box::new(plugins::plugin_manager::PluginManagerPlugin::new()));
 
         // Only register experimental plugins if flag is set
@@ -47,16 +191,23 @@ impl PluginRegistry {
         }
     }
 
+    /// Load the external plugins `config` declares or the plugin directories
+    /// hold, after leaving out the built-ins `config` disables or overrides
+    /// (`disabled_plugins`, `override`).
     pub fn load_external_plugins(&mut self, config: &metarepo_core::MetaConfig) {
         use crate::plugins::PluginLoader;
 
-        // Load plugins from configuration
-        let external_plugins = PluginLoader::load_from_config(config);
-        for plugin in external_plugins {
-            tracing::debug!("Loaded external plugin: {}", plugin.name());
-            self.register(plugin);
+        for name in &config.disabled_plugins {
+            if !self.remove_builtin(name) {
+                eprintln!("warning: disabled_plugins: no built-in plugin '{}'", name);
+            }
         }
 
+        // Load plugins from configuration, in name order so conflicts between
+        // them resolve the same way every run.
+        let mut external_plugins = PluginLoader::load_from_config(config);
+        external_plugins.sort_by(|a, b| a.name().cmp(b.name()));
+
         // Discover ambient plugins in standard locations, skipping any already
         // declared in config (those were loaded and enforced above; re-loading
         // here would bypass integrity/version checks).
@@ -66,11 +217,71 @@ impl PluginRegistry {
             .map(|m| m.keys().cloned().collect())
             .unwrap_or_default();
         let allow_any_path = crate::plugins::plugin_loader::plugin_allow_any_path(Some(config));
-        let discovered = PluginLoader::discover_plugins(&configured, allow_any_path);
+        let mut discovered = PluginLoader::discover_plugins(&configured, allow_any_path);
+        discovered.sort_by(|a, b| a.name().cmp(b.name()));
+
+        // A built-in is only replaced once its replacement has loaded, which
+        // then gets the built-in's commands ahead of other plugins.
+        let mut priority = config.plugin_priority.clone().unwrap_or_default();
+        let mut overrides: Vec<(&String, &String)> =
+            config.plugin_overrides.iter().flatten().collect();
+        overrides.sort();
+        for (builtin, replacement) in overrides {
+            let loaded = external_plugins
+                .iter()
+                .chain(&discovered)
+                .any(|plugin| plugin.name() == replacement);
+            let commands = match self.plugins.get(builtin) {
+                Some(plugin) if self.builtin.contains(builtin) => {
+                    top_level_commands(plugin.as_ref())
+                }
+                _ => {
+                    eprintln!("warning: override: no built-in plugin '{}'", builtin);
+                    continue;
+                }
+            };
+            if !loaded {
+                eprintln!(
+                    "warning: override: plugin '{}' is not installed; keeping the built-in '{}'",
+                    replacement, builtin
+                );
+                continue;
+            }
+            self.remove_builtin(builtin);
+            for command in commands {
+                priority
+                    .entry(command)
+                    .or_insert_with(|| replacement.clone());
+            }
+        }
+
+        for plugin in external_plugins {
+            tracing::debug!("Loaded external plugin: {}", plugin.name());
+            self.register_external(plugin, &priority);
+        }
         for plugin in discovered {
             tracing::debug!("Discovered plugin: {}", plugin.name());
-            self.register(plugin);
+            self.register_external(plugin, &priority);
+        }
+        for conflict in &self.conflicts {
+            tracing::debug!(
+                "Command '{}' kept by plugin '{}' over '{}'",
+                conflict.command,
+                conflict.winner,
+                conflict.loser
+            );
+        }
+    }
+
+    /// Drop the built-in plugin `name` and free its commands. False if there
+    /// is no such built-in.
+    fn remove_builtin(&mut self, name: &str) -> bool {
+        if !self.builtin.remove(name) {
+            return false;
         }
+        self.plugins.remove(name);
+        self.commands.retain(|_, owner| owner != name);
+        true
     }
 
     pub fn build_cli(&self, base_app: Command) -> Command {
@@ -122,6 +333,50 @@ impl PluginRegistry {
                     })
         }
 
+        let namespaced: Vec<_> = self
+            .namespaced
+            .iter()
+            .filter(|(_, plugin)| experimental || !plugin.is_experimental())
+            .collect();
+        if !namespaced.is_empty() {
+            let ext = namespaced.into_iter().fold(
+                Command::new(NAMESPACE_COMMAND)
+                    .about("Run an external plugin whose command another plugin has")
+                    .version(env!("CARGO_PKG_VERSION"))
+                    .after_long_help(metarepo_core::format_help_description(
+                        "When two plugins declare the same top-level command, built-in\n\
+                         plugins keep theirs, and between external plugins the one named\n\
+                         in plugin_priority in .meta (else the first loaded) gets it. The\n\
+                         others run through here, by plugin name, taking the same\n\
+                         arguments as their command would; `meta plugin list` shows the\n\
+                         conflicts.\n\
+                         \n\
+                         Examples:\n  \
+                           meta ext acme-status --all\n",
+                    ))
+                    .subcommand_required(true)
+                    .arg_required_else_help(true),
+                |ext, (name, plugin)| {
+                    let name: &'static str = Box::leak(name.clone().into_boxed_str());
+                    let mut commands = plugin_commands(plugin.as_ref());
+                    // A plugin with one command (the usual case) is run by its
+                    // name alone; one with several takes the command after it.
+                    let command = if commands.len() == 1 {
+                        commands.remove(0).name(name)
+                    } else {
+                        commands
+                            .into_iter()
+                            .fold(Command::new(name), Command::subcommand)
+                            .about(format!("Commands of the {} plugin", name))
+                            .version(env!("CARGO_PKG_VERSION"))
+                            .subcommand_required(true)
+                    };
+                    ext.subcommand(command)
+                },
+            );
+            app_with_regular = app_with_regular.subcommand(ext);
+        }
+
         app_with_regular
     }
 
@@ -131,7 +386,26 @@ impl PluginRegistry {
         matches: &ArgMatches,
         config: &RuntimeConfig,
     ) -> Result<()> {
-        if let Some(plugin) = self.plugins.get(command_name) {
+        if command_name == NAMESPACE_COMMAND {
+            // `meta ext <plugin> ...`: hand the plugin the matches of its own
+            // command, as if it had been run at the top level.
+            if let Some((name, plugin_matches)) = matches.subcommand() {
+                if let Some(plugin) = self.namespaced.get(name) {
+                    if plugin_commands(plugin.as_ref()).len() == 1 {
+                        return plugin.handle_command(plugin_matches, config);
+                    }
+                    if let Some((_, command_matches)) = plugin_matches.subcommand() {
+                        return plugin.handle_command(command_matches, config);
+                    }
+                }
+            }
+        }
+        let owner = self
+            .commands
+            .get(command_name)
+            .map(String::as_str)
+            .unwrap_or(command_name);
+        if let Some(plugin) = self.plugins.get(owner) {
             plugin.handle_command(matches, config)
         } else {
             Err(MetarepoError::Plugin(format!("Unknown command: {}", command_name)).into())
@@ -139,15 +413,22 @@ impl PluginRegistry {
     }
 
     pub fn get_plugin(&self, name: &str) -> Option<&dyn MetaPlugin> {
-        self.plugins.get(name).map(|plugin| plugin.as_ref())
+        self.plugins
+            .get(name)
+            .or_else(|| self.namespaced.get(name))
+            .map(|plugin| plugin.as_ref())
     }
 
     /// Aggregate the configuration settings declared by every registered
     /// plugin, sorted by key. Feeds `RuntimeConfig::settings_catalog` so the
     /// `config` command can list and validate them.
     pub fn collect_settings(&self) -> Vec<metarepo_core::ConfigSetting> {
-        let mut settings: Vec<metarepo_core::ConfigSetting> =
-            self.plugins.values().flat_map(|p| p.settings()).collect();
+        let mut settings: Vec<metarepo_core::ConfigSetting> = self
+            .plugins
+            .values()
+            .chain(self.namespaced.values())
+            .flat_map(|p| p.settings())
+            .collect();
         settings.sort_by(|a, b| a.key.cmp(&b.key));
         settings
     }
@@ -160,6 +441,28 @@ impl PluginRegistry {
     }
 }
 
+/// The top-level commands `plugin` registers.
+fn plugin_commands(plugin: &dyn MetaPlugin) -> Vec<Command> {
+    plugin
+        .register_commands(Command::new("meta"))
+        .get_subcommands()
+        .cloned()
+        .collect()
+}
+
+/// The top-level commands and aliases `plugin` registers.
+fn top_level_commands(plugin: &dyn MetaPlugin) -> Vec<String> {
+    plugin_commands(plugin)
+        .iter()
+        .flat_map(|command| {
+            std::iter::once(command.get_name())
+                .chain(command.get_all_aliases())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 impl Default for PluginRegistry {
     fn default() -> Self {
         Self::new()
@@ -180,4 +483,82 @@ mod tests {
         let plugins = registry.list_plugins();
         assert_eq!(plugins.len(), 0);
     }
+
+    /// A plugin registering `command`, recording its name when run.
+    struct Fake {
+        name: &'static str,
+        command: &'static str,
+    }
+
+    impl MetaPlugin for Fake {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn register_commands(&self, app: Command) -> Command {
+            app.subcommand(Command::new(self.command).subcommand(Command::new("go")))
+        }
+
+        fn handle_command(&self, _matches: &ArgMatches, _config: &RuntimeConfig) -> Result<()> {
+            Err(anyhow::anyhow!("ran {}", self.name))
+        }
+    }
+
+    fn fake(name: &'static str, command: &'static str) -> Box<dyn MetaPlugin> {
+        Box::new(Fake { name, command })
+    }
+
+    fn run(registry: &PluginRegistry, args: &[&str]) -> String {
+        let app = registry.build_cli(Command::new("meta"));
+        let matches = app.try_get_matches_from(args).unwrap();
+        let (command, sub) = matches.subcommand().unwrap();
+        let config = crate::create_runtime_config(false).unwrap();
+        registry
+            .handle_command(command, sub, &config)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn built_ins_keep_their_commands_and_priority_settles_externals() {
+        let mut registry = PluginRegistry::new();
+        registry.register(fake("status", "status"));
+        let none = HashMap::new();
+        registry.register_external(fake("status", "status"), &none);
+        registry.register_external(fake("acme", "deploy"), &none);
+        let pinned = HashMap::from([("deploy".to_string(), "zeta".to_string())]);
+        registry.register_external(fake("zeta", "deploy"), &pinned);
+        registry.register_external(fake("zeta", "other"), &none);
+
+        assert_eq!(run(&registry, &["meta", "status", "go"]), "ran status");
+        assert_eq!(run(&registry, &["meta", "deploy", "go"]), "ran zeta");
+        assert_eq!(
+            run(&registry, &["meta", "ext", "status", "go"]),
+            "ran status"
+        );
+        assert_eq!(run(&registry, &["meta", "ext", "acme", "go"]), "ran acme");
+
+        let conflicts: Vec<(&str, &str, &str, bool, bool)> = registry
+            .conflicts()
+            .iter()
+            .map(|c| {
+                (
+                    c.command.as_str(),
+                    c.winner.as_str(),
+                    c.loser.as_str(),
+                    c.winner_builtin,
+                    c.namespaced,
+                )
+            })
+            .collect();
+        assert_eq!(
+            conflicts,
+            [
+                ("status", "status", "status", true, true),
+                ("deploy", "zeta", "acme", false, true),
+                ("zeta", "zeta", "zeta", false, false),
+            ]
+        );
+        assert!(registry.conflicts()[1].pinned);
+    }
 }
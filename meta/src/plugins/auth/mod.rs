@@ -0,0 +1,174 @@
+//! `meta auth` — keep provider tokens in the OS keychain.
+//!
+//! `login` stores a token for GitHub, GitLab, the skills.sh index, or any
+//! other git host; `status` shows where each provider's token would come
+//! from; `logout` removes a stored token. Tokens are read from a hidden
+//! prompt, or from stdin when it is not a terminal, so they never reach the
+//! shell history. See [`crate::plugins::shared::keychain`] for who reads
+//! them back.
+
+use anyhow::Result;
+use colored::Colorize;
+use metarepo_core::Table;
+use std::io::{IsTerminal, Read};
+
+use crate::plugins::shared::credentials;
+use crate::plugins::shared::keychain::{self, Provider};
+
+pub use self::plugin::AuthPlugin;
+
+mod plugin;
+
+/// The environment variable that supplies `provider`'s token ahead of the
+/// keychain, if one is set. Git hosts follow [`credentials::env_token_source`],
+/// so `METAREPO_GIT_TOKEN` only counts for hosts listed in
+/// `METAREPO_GIT_TOKEN_HOSTS`.
+fn env_source(provider: &Provider, env: &dyn Fn(&str) -> Option<String>) -> Option<&'static str> {
+    if provider.git_host {
+        return credentials::env_token_source(&provider.account, env);
+    }
+    provider
+        .env
+        .iter()
+        .copied()
+        .find(|var| env(var).is_some_and(|v| !v.trim().is_empty()))
+}
+
+/// A token for `provider` from a hidden prompt, or from stdin when it is
+/// not a terminal.
+fn read_token(provider: &Provider) -> Result<String> {
+    let token = if std::io::stdin().is_terminal() {
+        dialoguer::Password::new()
+            .with_prompt(format!("Token for {}", provider.account))
+            .interact()?
+    } else {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        input
+    };
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err(anyhow::anyhow!("No token given"));
+    }
+    Ok(token)
+}
+
+/// Store a token for `provider`.
+pub fn login(provider: &Provider) -> Result<()> {
+    let token = read_token(provider)?;
+    keychain::set(&provider.account, &token)?;
+    println!(
+        "  {} Stored a token for {} in the OS keychain",
+        "✓".green(),
+        provider.account.bold()
+    );
+    let env = |var: &str| std::env::var(var).ok();
+    if let Some(var) = env_source(provider, &env) {
+        println!(
+            "  {}",
+            format!("{} is set and is used instead while it is.", var).bright_black()
+        );
+    }
+    Ok(())
+}
+
+/// Remove the stored token for `provider`.
+pub fn logout(provider: &Provider) -> Result<()> {
+    if keychain::delete(&provider.account)? {
+        println!(
+            "  {} Removed the token for {} from the OS keychain",
+            "✓".green(),
+            provider.account.bold()
+        );
+    } else {
+        println!("  No token stored for {}", provider.account.bold());
+    }
+    Ok(())
+}
+
+/// Print where each of `providers` gets its token.
+pub fn status(providers: &[Provider]) {
+    let env = |var: &str| std::env::var(var).ok();
+    let disabled = keychain::disabled();
+    let mut unavailable = None;
+    let mut table = Table::new(["Provider", "Host", "Environment", "Keychain"]);
+    for provider in providers {
+        let environment = match env_source(provider, &env) {
+            Some(var) => var.green().to_string(),
+            None => "—".bright_black().to_string(),
+        };
+        let stored = match keychain::lookup(&provider.account) {
+            Ok(Some(_)) if disabled => "stored (lookups off)".yellow().to_string(),
+            Ok(Some(_)) => "stored".green().to_string(),
+            Ok(None) => "—".bright_black().to_string(),
+            Err(e) => {
+                unavailable = Some(e);
+                "unavailable".red().to_string()
+            }
+        };
+        table.row([
+            provider.name.bold().to_string(),
+            provider.account.clone(),
+            environment,
+            stored,
+        ]);
+    }
+    println!("\n  {}", "Provider tokens".bold());
+    println!("  {}", "═".repeat(60).bright_black());
+    table.indent(2).print();
+    println!(
+        "\n  {}",
+        "A variable in the Environment column is used ahead of the keychain.".bright_black()
+    );
+    if let Some(e) = unavailable {
+        println!("  {}", e.to_string().red());
+    }
+    if disabled {
+        println!(
+            "  {}",
+            format!(
+                "{} is set: stored tokens are not read.",
+                keychain::DISABLE_ENV
+            )
+            .yellow()
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_source_follows_credential_precedence() {
+        let github = keychain::provider("github").unwrap();
+        let skills = keychain::provider("skills").unwrap();
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |var: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == var)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        let only_github = env(&[("GITHUB_TOKEN", "gh")]);
+        assert_eq!(env_source(&github, &only_github), Some("GITHUB_TOKEN"));
+        assert_eq!(env_source(&skills, &only_github), None);
+
+        // The generic git token only counts for hosts that opt in.
+        let generic = env(&[(credentials::TOKEN_ENV, "t"), ("GITHUB_TOKEN", "gh")]);
+        assert_eq!(env_source(&github, &generic), Some("GITHUB_TOKEN"));
+        let opted_in = env(&[
+            (credentials::TOKEN_ENV, "t"),
+            (credentials::TOKEN_HOSTS_ENV, "github.com"),
+            ("GITHUB_TOKEN", "gh"),
+        ]);
+        assert_eq!(env_source(&github, &opted_in), Some(credentials::TOKEN_ENV));
+        let gitlab = keychain::provider("gitlab").unwrap();
+        assert_eq!(env_source(&gitlab, &opted_in), None);
+        // The generic git token is not a skills.sh key.
+        assert_eq!(env_source(&skills, &opted_in), None);
+        assert_eq!(env_source(&github, &env(&[("GH_TOKEN", " ")])), None);
+    }
+}
@@ -0,0 +1,149 @@
+//! Plugin wiring for `meta auth`.
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{login, logout, status};
+use crate::plugins::shared::keychain;
+
+/// Registers the top-level `meta auth` command.
+pub struct AuthPlugin;
+
+impl AuthPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AuthPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn provider_arg() -> Arg {
+    Arg::new("provider")
+        .value_name("PROVIDER")
+        .required(true)
+        .help("github, gitlab, skills, or a git host name")
+}
+
+impl MetaPlugin for AuthPlugin {
+    fn name(&self) -> &str {
+        "auth"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("auth")
+                .about("Store provider tokens in the OS keychain")
+                .version(env!("CARGO_PKG_VERSION"))
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .after_long_help(metarepo_core::format_help_description(
+                    "Keep tokens for GitHub, GitLab, the skills.sh index, or any other git\n\
+                     host in the OS keychain (macOS Keychain, Windows Credential Manager,\n\
+                     Secret Service on Linux) instead of environment variables or config\n\
+                     files.\n\
+                     \n\
+                     HTTPS clones and fetches use a stored token for the remote's host\n\
+                     after the credentials block of the user config and the token\n\
+                     variables; GitHub API lookups use the github token after\n\
+                     GITHUB_TOKEN/GH_TOKEN; meta skill uses the skills key after\n\
+                     SKILLS_SH_API_KEY and [skill] api-key. Set METAREPO_NO_KEYCHAIN to\n\
+                     stop reading the keychain.\n\
+                     \n\
+                     Examples:\n  \
+                       meta auth login github\n  \
+                       gh auth token | meta auth login github\n  \
+                       meta auth login git.example.com < token.txt\n  \
+                       meta auth status\n  \
+                       meta auth logout gitlab\n",
+                ))
+                .subcommand(
+                    Command::new("login")
+                        .about("Store a token for a provider")
+                        .version(env!("CARGO_PKG_VERSION"))
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Store a token for PROVIDER in the OS keychain, replacing any\n\
+                             stored before. The token is read from a hidden prompt, or from\n\
+                             stdin when it is not a terminal, so it stays out of your shell\n\
+                             history.\n\
+                             \n\
+                             Examples:\n  \
+                               meta auth login github\n  \
+                               meta auth login skills < key.txt\n",
+                        ))
+                        .arg(provider_arg()),
+                )
+                .subcommand(
+                    Command::new("logout")
+                        .about("Remove the stored token for a provider")
+                        .version(env!("CARGO_PKG_VERSION"))
+                        .arg(provider_arg()),
+                )
+                .subcommand(
+                    Command::new("status")
+                        .about("Show where each provider's token comes from")
+                        .version(env!("CARGO_PKG_VERSION"))
+                        .after_long_help(metarepo_core::format_help_description(
+                            "List the providers with the environment variable that supplies\n\
+                             each one's token, if any, and whether the keychain holds one.\n\
+                             Tokens themselves are never printed. Name providers to check\n\
+                             other hosts; the default is github, gitlab, and skills.\n\
+                             \n\
+                             Examples:\n  \
+                               meta auth status\n  \
+                               meta auth status git.example.com\n",
+                        ))
+                        .arg(
+                            Arg::new("provider")
+                                .value_name("PROVIDER")
+                                .num_args(1..)
+                                .help("Providers to show (default: github, gitlab, skills)"),
+                        ),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, _config: &RuntimeConfig) -> Result<()> {
+        let provider = |m: &ArgMatches| {
+            keychain::provider(
+                m.get_one::<String>("provider")
+                    .expect("provider is required"),
+            )
+        };
+        match matches.subcommand() {
+            Some(("login", m)) => login(&provider(m)?),
+            Some(("logout", m)) => logout(&provider(m)?),
+            Some(("status", m)) => {
+                let providers = match m.get_many::<String>("provider") {
+                    Some(names) => names
+                        .map(|name| keychain::provider(name))
+                        .collect::<Result<Vec<_>>>()?,
+                    None => keychain::known(),
+                };
+                status(&providers);
+                Ok(())
+            }
+            _ => Err(anyhow::anyhow!(
+                "Unknown auth command: use login, logout, or status"
+            )),
+        }
+    }
+}
+
+impl BasePlugin for AuthPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Store provider tokens in the OS keychain")
+    }
+}
@@ -0,0 +1,368 @@
+//! `meta config diff`: compare the workspace config with a git revision of it,
+//! or with another config file, in terms of projects and scripts rather than
+//! lines.
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use metarepo_core::{
+    ConfigFormat, MetaConfig, ProjectEntry, ScriptEntry, CANONICAL_FILENAME, LEGACY_FILENAME,
+};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::process::Command;
+
+/// A project present on only one side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectChange {
+    pub project: String,
+    pub url: String,
+}
+
+/// A project whose URL changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UrlChange {
+    pub project: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// A script that was added (`from` is `None`), removed (`to` is `None`), or
+/// changed. `project` is `None` for workspace-wide scripts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScriptChange {
+    pub project: Option<String>,
+    pub script: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// What changed between two configs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConfigDiff {
+    pub added: Vec<ProjectChange>,
+    pub removed: Vec<ProjectChange>,
+    pub url_changes: Vec<UrlChange>,
+    pub script_changes: Vec<ScriptChange>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.url_changes.is_empty()
+            && self.script_changes.is_empty()
+    }
+}
+
+fn project_scripts<'a>(
+    config: &'a MetaConfig,
+    project: &str,
+) -> Option<&'a HashMap<String, ScriptEntry>> {
+    match config.projects.get(project) {
+        Some(ProjectEntry::Metadata(metadata)) => Some(&metadata.scripts),
+        _ => None,
+    }
+}
+
+fn diff_scripts(
+    project: Option<&str>,
+    old: Option<&HashMap<String, ScriptEntry>>,
+    new: Option<&HashMap<String, ScriptEntry>>,
+    changes: &mut Vec<ScriptChange>,
+) {
+    let names: BTreeSet<&String> = old
+        .into_iter()
+        .chain(new)
+        .flat_map(|scripts| scripts.keys())
+        .collect();
+    for name in names {
+        let from = old.and_then(|s| s.get(name));
+        let to = new.and_then(|s| s.get(name));
+        if from != to {
+            changes.push(ScriptChange {
+                project: project.map(str::to_string),
+                script: name.clone(),
+                from: from.map(|s| s.command().to_string()),
+                to: to.map(|s| s.command().to_string()),
+            });
+        }
+    }
+}
+
+/// Compare `old` with `new`. Scripts are compared for the workspace and for
+/// projects on both sides; an added project's scripts come with it.
+pub fn diff_configs(old: &MetaConfig, new: &MetaConfig) -> ConfigDiff {
+    let mut diff = ConfigDiff::default();
+    let names: BTreeSet<&String> = old.projects.keys().chain(new.projects.keys()).collect();
+
+    diff_scripts(
+        None,
+        old.scripts.as_ref(),
+        new.scripts.as_ref(),
+        &mut diff.script_changes,
+    );
+    for name in names {
+        match (old.get_project_url(name), new.get_project_url(name)) {
+            (None, Some(url)) => diff.added.push(ProjectChange {
+                project: name.clone(),
+                url,
+            }),
+            (Some(url), None) => diff.removed.push(ProjectChange {
+                project: name.clone(),
+                url,
+            }),
+            (Some(from), Some(to)) => {
+                if from != to {
+                    diff.url_changes.push(UrlChange {
+                        project: name.clone(),
+                        from,
+                        to,
+                    });
+                }
+                diff_scripts(
+                    Some(name),
+                    project_scripts(old, name),
+                    project_scripts(new, name),
+                    &mut diff.script_changes,
+                );
+            }
+            (None, None) => {}
+        }
+    }
+    diff
+}
+
+/// Load what to compare the config at `config_path` against: the file
+/// `against` if it exists, otherwise the config as of git revision `against`.
+/// Returns the config and a label for it.
+pub fn load_baseline(config_path: &Path, against: &str) -> Result<(MetaConfig, String)> {
+    let format = ConfigFormat::from_path(config_path).unwrap_or(ConfigFormat::Json);
+    let file = Path::new(against);
+    if file.is_file() {
+        // Fall back to the workspace config's format for unusual names.
+        let format = ConfigFormat::from_path(file).unwrap_or(format);
+        let config = MetaConfig::load_from_file_with_format(file, format)
+            .with_context(|| format!("Failed to parse {}", file.display()))?;
+        return Ok((config, file.display().to_string()));
+    }
+
+    let dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = config_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("{}:./{}", against, file_name))
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "'{}' is neither a file nor a git revision containing {}: {}",
+            against,
+            file_name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let content = String::from_utf8_lossy(&output.stdout);
+    // An extensionless config may hold TOML; judge the revision by its own
+    // contents rather than the working tree's.
+    let format = if file_name == CANONICAL_FILENAME || file_name == LEGACY_FILENAME {
+        ConfigFormat::sniff(&content)
+    } else {
+        format
+    };
+    let config = MetaConfig::load_from_str(&content, format)
+        .with_context(|| format!("Failed to parse {} at {}", file_name, against))?;
+    Ok((config, format!("{}:{}", against, file_name)))
+}
+
+fn script_label(change: &ScriptChange) -> String {
+    match &change.project {
+        Some(project) => format!("{}: {}", project, change.script),
+        None => change.script.clone(),
+    }
+}
+
+/// Print `diff` between the configs labelled `from` and `to`.
+pub fn print_diff(diff: &ConfigDiff, from: &str, to: &str) {
+    println!(
+        "\n  {} {}",
+        "📋".bright_blue(),
+        format!("Config changes: {} → {}", from, to).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+
+    if diff.is_empty() {
+        println!(
+            "  {} {}",
+            "✓".green(),
+            "No project or script changes".green()
+        );
+        println!();
+        return;
+    }
+
+    if !diff.added.is_empty() || !diff.removed.is_empty() || !diff.url_changes.is_empty() {
+        println!("\n  {}", "Projects".bold());
+        for p in &diff.added {
+            println!(
+                "    {} {}  {}",
+                "+".green(),
+                p.project.green(),
+                p.url.bright_black()
+            );
+        }
+        for p in &diff.removed {
+            println!(
+                "    {} {}  {}",
+                "-".red(),
+                p.project.red(),
+                p.url.bright_black()
+            );
+        }
+        for c in &diff.url_changes {
+            println!(
+                "    {} {}  {} → {}",
+                "~".yellow(),
+                c.project.yellow(),
+                c.from.bright_black(),
+                c.to.bright_white()
+            );
+        }
+    }
+
+    if !diff.script_changes.is_empty() {
+        println!("\n  {}", "Scripts".bold());
+        for c in &diff.script_changes {
+            let label = script_label(c);
+            match (&c.from, &c.to) {
+                (None, Some(to)) => {
+                    println!(
+                        "    {} {}  {}",
+                        "+".green(),
+                        label.green(),
+                        to.bright_black()
+                    )
+                }
+                (Some(from), None) => {
+                    println!("    {} {}  {}", "-".red(), label.red(), from.bright_black())
+                }
+                (Some(from), Some(to)) => println!(
+                    "    {} {}  {} → {}",
+                    "~".yellow(),
+                    label.yellow(),
+                    from.bright_black(),
+                    to.bright_white()
+                ),
+                (None, None) => {}
+            }
+        }
+    }
+
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} added, {} removed, {} URL changed, {} script changes",
+        "Summary:".bright_black(),
+        diff.added.len().to_string().green(),
+        diff.removed.len().to_string().red(),
+        diff.url_changes.len().to_string().yellow(),
+        diff.script_changes.len().to_string().yellow()
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(json: &str) -> MetaConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn reports_projects_urls_and_scripts() {
+        let old = cfg(r#"{
+            "projects": {
+                "web": { "url": "git@h:web.git", "scripts": { "test": "npm test", "lint": "eslint ." } },
+                "api": "git@h:api.git",
+                "old": "git@h:old.git"
+            },
+            "scripts": { "build": "make" }
+        }"#);
+        let new = cfg(r#"{
+            "projects": {
+                "web": { "url": "git@h:web.git", "scripts": { "test": "vitest", "fmt": "prettier -w ." } },
+                "api": "git@h:api-v2.git",
+                "docs": { "url": "git@h:docs.git", "scripts": { "serve": "mkdocs serve" } }
+            },
+            "scripts": { "build": "make" }
+        }"#);
+
+        let diff = diff_configs(&old, &new);
+        assert_eq!(
+            diff.added,
+            [ProjectChange {
+                project: "docs".into(),
+                url: "git@h:docs.git".into()
+            }]
+        );
+        assert_eq!(diff.removed[0].project, "old");
+        assert_eq!(
+            diff.url_changes,
+            [UrlChange {
+                project: "api".into(),
+                from: "git@h:api.git".into(),
+                to: "git@h:api-v2.git".into()
+            }]
+        );
+        let scripts: Vec<_> = diff
+            .script_changes
+            .iter()
+            .map(|c| (script_label(c), c.from.as_deref(), c.to.as_deref()))
+            .collect();
+        assert_eq!(
+            scripts,
+            [
+                ("web: fmt".to_string(), None, Some("prettier -w .")),
+                ("web: lint".to_string(), Some("eslint ."), None),
+                ("web: test".to_string(), Some("npm test"), Some("vitest")),
+            ]
+        );
+
+        assert!(diff_configs(&new, &new).is_empty());
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["url_changes"][0]["to"], "git@h:api-v2.git");
+    }
+
+    #[test]
+    fn loads_a_git_revision_of_the_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(root)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["init", "-q"]);
+        let meta = root.join(".meta");
+        std::fs::write(&meta, r#"{"projects": {"web": "git@h:web.git"}}"#).unwrap();
+        run(&["add", ".meta"]);
+        run(&["commit", "-qm", "init"]);
+        std::fs::write(&meta, r#"{"projects": {"api": "git@h:api.git"}}"#).unwrap();
+
+        let (head, label) = load_baseline(&meta, "HEAD").unwrap();
+        assert_eq!(label, "HEAD:.meta");
+        let diff = diff_configs(&head, &MetaConfig::load_from_file(&meta).unwrap());
+        assert_eq!(diff.added[0].project, "api");
+        assert_eq!(diff.removed[0].project, "web");
+
+        assert!(load_baseline(&meta, "no-such-rev").is_err());
+    }
+}
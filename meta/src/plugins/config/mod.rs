@@ -1,4 +1,7 @@
+pub mod diff;
 mod plugin;
+pub mod schema;
 mod tui_editor;
+pub mod validate;
 
 pub use plugin::ConfigPlugin;
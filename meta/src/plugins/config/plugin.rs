@@ -3,10 +3,18 @@
 use anyhow::{anyhow, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use colored::Colorize;
-use metarepo_core::{BasePlugin, ConfigFormat, MetaConfig, MetaPlugin, RuntimeConfig};
-use std::path::PathBuf;
+use metarepo_core::{
+    BasePlugin, ConfigFormat, ConfigOrigin, MetaConfig, MetaPlugin, RuntimeConfig,
+    CANONICAL_FILENAME, LEGACY_FILENAME,
+};
+use std::path::{Path, PathBuf};
 
+use super::diff::{diff_configs, load_baseline, print_diff};
+use super::schema::print_schema_keys;
 use super::tui_editor::ConfigEditor;
+use super::validate::{print_problems, validate_file};
+use crate::plugins::rules::RulesConfig;
+use crate::plugins::shared::{keychain, secrets};
 
 pub struct ConfigPlugin;
 
@@ -98,6 +106,83 @@ impl ConfigPlugin {
         Ok(())
     }
 
+    fn handle_diff(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let path = config.meta_file_path.clone().ok_or_else(|| {
+            anyhow!("No metarepo config found. Run 'meta init' first or pass --config <path>.")
+        })?;
+        let against = matches
+            .get_one::<String>("against")
+            .map(|s| s.as_str())
+            .unwrap_or("HEAD");
+
+        let (baseline, label) = load_baseline(&path, against)?;
+        let current = MetaConfig::load_from_file(&path)?;
+        let diff = diff_configs(&baseline, &current);
+
+        if matches.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&diff)?);
+        } else {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            print_diff(&diff, &label, &name);
+        }
+        Ok(())
+    }
+
+    fn handle_convert(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let target_format = ConfigFormat::parse(
+            matches
+                .get_one::<String>("to")
+                .map(|s| s.as_str())
+                .unwrap_or("json"),
+        )?;
+        let source = config.meta_file_path.clone().ok_or_else(|| {
+            anyhow!(
+                "No metarepo config found to convert. Run 'meta init' first or pass --config <path>."
+            )
+        })?;
+        let destination = convert_destination(&source, target_format)?;
+
+        if ConfigFormat::of_file(&source) == Some(target_format) && source == destination {
+            println!(
+                "  {} {} is already {} — nothing to do.",
+                "·".bright_black(),
+                source.display(),
+                target_format.label()
+            );
+            return Ok(());
+        }
+        if destination != source && destination.exists() {
+            return Err(anyhow!(
+                "{} already exists. Remove it or use 'meta config migrate --to <path> --force'.",
+                destination.display()
+            ));
+        }
+
+        // Re-read the file itself rather than the runtime config so nothing
+        // inherited from a parent workspace is written into it.
+        let converted = MetaConfig::load_from_file(&source)?;
+        converted.save_to_file_with_format(&destination, target_format)?;
+        if destination != source {
+            std::fs::remove_file(&source)?;
+        }
+
+        println!(
+            "  {} Converted {} to {}{}",
+            "✓".green(),
+            source.display(),
+            target_format.label(),
+            if destination != source {
+                format!(" ({})", destination.display())
+            } else {
+                String::new()
+            }
+        );
+        Ok(())
+    }
+
     fn handle_edit(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
         let meta_file = if let Some(file) = matches.get_one::<String>("file") {
             PathBuf::from(file)
@@ -117,22 +202,43 @@ impl ConfigPlugin {
     }
 
     fn handle_show(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        if matches.get_flag("origin") {
+            return Self::show_origins(config);
+        }
         let format = matches
             .get_one::<String>("format")
             .map(|s| s.as_str())
             .unwrap_or("json");
 
+        // The runtime config has `extends` resolved; by default show the file
+        // as written.
+        let shown = match &config.meta_file_path {
+            Some(_) if matches.get_flag("resolved") && !matches.get_flag("refresh") => {
+                config.meta_config.clone()
+            }
+            Some(path) => {
+                let format = ConfigFormat::of_file(path).unwrap_or(ConfigFormat::Json);
+                let file = MetaConfig::load_from_file_with_format(path, format)?;
+                if matches.get_flag("resolved") {
+                    file.resolve_extends(path, true)?
+                } else {
+                    file
+                }
+            }
+            None => config.meta_config.clone(),
+        };
+
         match format {
             "json" => {
-                let json = serde_json::to_string_pretty(&config.meta_config)?;
+                let json = serde_json::to_string_pretty(&shown)?;
                 println!("{}", json);
             }
             "yaml" => {
-                let yaml = serde_yaml::to_string(&config.meta_config)?;
+                let yaml = serde_yaml::to_string(&shown)?;
                 println!("{}", yaml);
             }
             "toml" => {
-                let toml = toml::to_string_pretty(&config.meta_config)?;
+                let toml = toml::to_string_pretty(&shown)?;
                 println!("{}", toml);
             }
             _ => {
@@ -146,13 +252,60 @@ impl ConfigPlugin {
         Ok(())
     }
 
+    /// Print each effective value with the layer that set it.
+    fn show_origins(config: &RuntimeConfig) -> Result<()> {
+        let (file, resolved) = match &config.meta_file_path {
+            Some(path) => {
+                let format = ConfigFormat::of_file(path).unwrap_or(ConfigFormat::Json);
+                let file = MetaConfig::load_from_file_with_format(path, format)?;
+                let resolved = file.resolve_extends(path, false)?;
+                (Some(file), Some(resolved))
+            }
+            None => (None, None),
+        };
+        let user_path = metarepo_core::user_config_path();
+        let user = match &user_path {
+            Some(path) => Some(MetaConfig::load_user_config(path)?),
+            None => None,
+        };
+
+        let origins = metarepo_core::config_origins(
+            &config.meta_config,
+            file.as_ref(),
+            resolved.as_ref(),
+            user.as_ref(),
+        );
+        for (key, value, origin) in origins {
+            let source = match origin {
+                ConfigOrigin::Workspace => config
+                    .meta_file_path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| origin.to_string()),
+                ConfigOrigin::User => user_path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| origin.to_string()),
+                ConfigOrigin::Extends | ConfigOrigin::Default => origin.to_string(),
+            };
+            println!(
+                "{} = {}  {}",
+                key.cyan(),
+                value,
+                format!("({})", source).bright_black()
+            );
+        }
+        Ok(())
+    }
+
     /// Load the inherited config chain (outermost → nearest) for cascade-aware
-    /// reads. Falls back to the active runtime config when no files are found.
+    /// reads, with each config's `extends` resolved. Falls back to the active
+    /// runtime config when no files are found.
     fn config_chain(config: &RuntimeConfig) -> Vec<(PathBuf, MetaConfig)> {
         let mut chain = Vec::new();
         if let Ok(found) = MetaConfig::discover_chain_from(&config.working_dir) {
             for d in found {
-                if let Ok(c) = MetaConfig::load_from_file(&d.path) {
+                if let Ok(c) = MetaConfig::load_resolved(&d.path) {
                     chain.push((d.path, c));
                 }
             }
@@ -302,17 +455,21 @@ impl ConfigPlugin {
         // Pick the write target. By default a set lands in the nearest .meta
         // (the active config). With --root it lands in the outermost .meta of
         // the chain — the shared defaults every nested workspace inherits.
-        let (meta_file, base_config) = if to_root {
-            Self::root_write_target(&Self::config_chain(config)).ok_or_else(|| {
-                anyhow!("--root requires a discoverable .meta chain; none was found")
-            })?
+        let meta_file = if to_root {
+            Self::root_write_target(&Self::config_chain(config))
+                .map(|(path, _)| path)
+                .ok_or_else(|| {
+                    anyhow!("--root requires a discoverable .meta chain; none was found")
+                })?
         } else {
-            let path = config
+            config
                 .meta_file_path
                 .clone()
-                .ok_or_else(|| anyhow!("Could not find .meta file path"))?;
-            (path, config.meta_config.clone())
+                .ok_or_else(|| anyhow!("Could not find .meta file path"))?
         };
+        // Edit the file as written, not with its `extends` fragments merged in.
+        let format = ConfigFormat::of_file(&meta_file).unwrap_or(ConfigFormat::Json);
+        let base_config = MetaConfig::load_from_file_with_format(&meta_file, format)?;
 
         // Apply with intermediate objects created as needed (so `skill.dest`
         // works even when the `[skill]` block does not exist yet).
@@ -343,17 +500,115 @@ impl ConfigPlugin {
                 .ok_or_else(|| anyhow!("Could not find .meta file. Use --file to specify path."))?
         };
 
-        // Try to load the config
-        match MetaConfig::load_from_file(&meta_file) {
-            Ok(_) => {
-                println!("✓ Config file is valid: {}", meta_file.display());
-                Ok(())
+        let problems = validate_file(&meta_file, &config.settings_catalog)?;
+        if matches.get_flag("json") {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "file": meta_file.display().to_string(),
+                    "valid": problems.is_empty(),
+                    "problems": problems,
+                }))?
+            );
+        } else {
+            print_problems(&meta_file, &problems);
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "{} has {} problem(s)",
+                meta_file.display(),
+                problems.len()
+            ))
+        }
+    }
+
+    fn handle_schema(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let schema = if matches.get_flag("rules") {
+            RulesConfig::json_schema()
+        } else {
+            metarepo_core::config_json_schema(&config.settings_catalog)
+        };
+        let output = matches.get_one::<String>("output");
+        if !matches.get_flag("json-schema") && output.is_none() {
+            print_schema_keys(&schema);
+            return Ok(());
+        }
+
+        let text = serde_json::to_string_pretty(&schema)?;
+        match output {
+            Some(path) => {
+                std::fs::write(path, format!("{}\n", text))?;
+                println!("{} Wrote the schema to {}", "✅".green(), path.bold());
+            }
+            None => println!("{}", text),
+        }
+        Ok(())
+    }
+
+    fn handle_keygen(&self, matches: &ArgMatches) -> Result<()> {
+        let key = secrets::generate_key();
+        if matches.get_flag("stdout") {
+            println!("{}", key);
+            return Ok(());
+        }
+        if matches.get_flag("keychain") {
+            if keychain::lookup(secrets::KEY_ACCOUNT)?.is_some() && !matches.get_flag("force") {
+                return Err(anyhow!(
+                    "The OS keychain already holds a key; pass --force to replace it (values sealed with the old key stop decrypting)"
+                ));
             }
-            Err(e) => {
-                println!("✗ Config file validation failed: {}", e);
-                Err(e)
+            keychain::set(secrets::KEY_ACCOUNT, &key)?;
+            println!("{} Stored a new key in the OS keychain", "✅".green());
+            println!(
+                "   {}",
+                "Share it with your team out of band; anyone holding it can read the encrypted values."
+                    .bright_black()
+            );
+            return Ok(());
+        }
+        let path = secrets::key_path().ok_or_else(|| {
+            anyhow!(
+                "No home directory; pass --stdout and set {}",
+                secrets::KEY_ENV
+            )
+        })?;
+        if path.exists() {
+            if !matches.get_flag("force") {
+                return Err(anyhow!(
+                    "{} already exists; pass --force to replace it (values sealed with the old key stop decrypting)",
+                    path.display()
+                ));
             }
+            std::fs::remove_file(&path)?;
         }
+        secrets::write_key(&path, &key)?;
+        println!(
+            "{} Wrote a new key to {}",
+            "✅".green(),
+            path.display().to_string().bold()
+        );
+        println!(
+            "   {}",
+            "Share it with your team out of band; anyone holding it can read the encrypted values."
+                .bright_black()
+        );
+        Ok(())
+    }
+
+    fn handle_encrypt(&self, matches: &ArgMatches) -> Result<()> {
+        let value = match matches.get_one::<String>("value") {
+            Some(value) => value.clone(),
+            None => {
+                let mut input = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)?;
+                input.trim_end_matches(['\r', '\n']).to_string()
+            }
+        };
+        let key = secrets::load_key()?;
+        println!("{}", secrets::encrypt(&key, &value)?);
+        Ok(())
     }
 }
 
@@ -373,7 +628,8 @@ impl MetaPlugin for ConfigPlugin {
                      The config holds your registered projects and the typed settings declared\n\
                      by core, plugins, and modules. Subcommands let you open an interactive tree\n\
                      editor, dump the file in json/yaml/toml, read or write individual keys, list\n\
-                     declared settings, validate the file, and migrate between formats.\n\
+                     declared settings, validate the file, print a JSON Schema for editors,\n\
+                     and migrate between formats.\n\
                      \n\
                      Reads are cascade-aware: in a nested workspace, get and list resolve each\n\
                      key to the nearest config that sets it and note where an inherited value\n\
@@ -420,14 +676,29 @@ impl MetaPlugin for ConfigPlugin {
                         .after_long_help(metarepo_core::format_help_description(
                             "Print the active configuration serialized to a chosen format.\n\
                              \n\
-                             Dumps the whole loaded config (projects and all settings) to stdout.\n\
+                             Dumps the whole config file (projects and all settings) to stdout.\n\
                              Use --format to pick json (default), yaml, or toml. This shows the\n\
-                             active config as parsed; it does not resolve inherited values across\n\
-                             a nested chain (use get/list for cascade-aware reads).\n\
+                             file as written; it does not resolve inherited values across a nested\n\
+                             chain (use get/list for cascade-aware reads).\n\
+                             \n\
+                             --resolved shows the config commands actually use, with the fragments\n\
+                             listed in extends merged underneath: each fragment in order, then the\n\
+                             file itself, later sources overriding earlier ones key by key (ignore,\n\
+                             disabled, and default_excludes are combined instead). Git-hosted\n\
+                             fragments are cloned once into the user cache; add --refresh to fetch\n\
+                             them again.\n\
+                             \n\
+                             --origin lists every value commands see, one per line, with where it\n\
+                             came from: the workspace file, an extends fragment, the user config\n\
+                             (~/.config/metarepo/config.toml, or $METAREPO_USER_CONFIG), or the\n\
+                             built-in default. User config values sit underneath everything the\n\
+                             workspace sets.\n\
                              \n\
                              Examples:\n  \
                                meta config show\n  \
-                               meta config show --format yaml\n",
+                               meta config show --format yaml\n  \
+                               meta config show --resolved --refresh\n  \
+                               meta config show --origin\n",
                         ))
                         .arg(
                             Arg::new("format")
@@ -437,6 +708,26 @@ impl MetaPlugin for ConfigPlugin {
                                 .help("Output format (json, yaml, toml)")
                                 .default_value("json")
                                 .value_parser(["json", "yaml", "toml"]),
+                        )
+                        .arg(
+                            Arg::new("resolved")
+                                .long("resolved")
+                                .action(ArgAction::SetTrue)
+                                .help("Show the config with its extends fragments merged in"),
+                        )
+                        .arg(
+                            Arg::new("refresh")
+                                .long("refresh")
+                                .requires("resolved")
+                                .action(ArgAction::SetTrue)
+                                .help("Fetch git-hosted extends fragments again"),
+                        )
+                        .arg(
+                            Arg::new("origin")
+                                .long("origin")
+                                .conflicts_with_all(["format", "resolved", "refresh"])
+                                .action(ArgAction::SetTrue)
+                                .help("List every effective value with the file it came from"),
                         ),
                 )
                 .subcommand(
@@ -521,20 +812,55 @@ impl MetaPlugin for ConfigPlugin {
                                meta config ls\n",
                         )),
                 )
+                .subcommand(
+                    Command::new("diff")
+                        .about("Show project and script changes against a revision or file")
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Compare the workspace config with an earlier version of it.\n\
+                             \n\
+                             AGAINST is a git revision of the config file (default HEAD) or the\n\
+                             path of another config file. Instead of a line diff, the changes are\n\
+                             reported as projects added or removed, project URLs changed, and\n\
+                             scripts added, removed, or changed (workspace-wide and per project),\n\
+                             which is easier to review than the raw file. Pass --json for the\n\
+                             same report as JSON.\n\
+                             \n\
+                             Examples:\n  \
+                               meta config diff                    Uncommitted changes to .meta\n  \
+                               meta config diff origin/main        Changes on this branch\n  \
+                               meta config diff ../other/.meta     Compare with another workspace\n",
+                        ))
+                        .arg(
+                            Arg::new("against")
+                                .value_name("AGAINST")
+                                .help("Git revision or config file to compare with (default HEAD)"),
+                        )
+                        .arg(
+                            Arg::new("json")
+                                .long("json")
+                                .help("Print the changes as JSON")
+                                .action(ArgAction::SetTrue),
+                        ),
+                )
                 .subcommand(
                     Command::new("validate")
-                        .about("Check that the config file parses correctly")
+                        .about("Check the config file for mistakes")
                         .after_long_help(metarepo_core::format_help_description(
-                            "Check that the configuration file parses into a valid structure.\n\
+                            "Check the configuration file against the shape meta reads.\n\
                              \n\
                              Loads the active config (or the file given with --file) and reports\n\
-                             whether it parses. On success it prints the validated path; on failure\n\
-                             it prints the parse error and exits non-zero. Useful in CI or after a\n\
-                             hand edit to confirm the file is well-formed.\n\
+                             every problem with the key it was found at: values of the wrong type,\n\
+                             keys meta does not read (often typos), declared settings outside their\n\
+                             type or allowed choices (see meta config list), malformed project URLs\n\
+                             and names, aliases claimed by several projects or shadowing a project\n\
+                             name, global aliases pointing at unknown projects, and env vars that\n\
+                             are ignored for safety. Exits non-zero when any problem is found, so CI\n\
+                             can gate on it; --json prints the problems for other tooling.\n\
                              \n\
                              Examples:\n  \
                                meta config validate\n  \
-                               meta config validate --file ./.meta\n",
+                               meta config validate --file ./.meta\n  \
+                               meta config validate --json\n",
                         ))
                         .arg(
                             Arg::new("file")
@@ -542,6 +868,75 @@ impl MetaPlugin for ConfigPlugin {
                                 .long("file")
                                 .value_name("FILE")
                                 .help("Path to .meta file to validate"),
+                        )
+                        .arg(
+                            Arg::new("json")
+                                .long("json")
+                                .help("Print the result as JSON")
+                                .action(ArgAction::SetTrue),
+                        ),
+                )
+                .subcommand(
+                    Command::new("schema")
+                        .about("Describe the config keys, or print a JSON Schema for editors")
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Describe every key the workspace config accepts.\n\
+                             \n\
+                             Without flags, prints each dotted key with its type and description,\n\
+                             including the settings the active plugins declare. --json-schema prints\n\
+                             a JSON Schema instead: register it with your editor (VS Code's\n\
+                             json.schemas setting, or a `# yaml-language-server: $schema=<file>`\n\
+                             comment in a YAML config) to get completion and validation while\n\
+                             editing .meta by hand. --rules describes .rules.yaml instead.\n\
+                             \n\
+                             Examples:\n  \
+                               meta config schema                                  List the keys\n  \
+                               meta config schema --json-schema -o .meta.schema.json\n  \
+                               meta config schema --rules --json-schema            Schema for .rules.yaml\n",
+                        ))
+                        .arg(
+                            Arg::new("json-schema")
+                                .long("json-schema")
+                                .help("Print a JSON Schema instead of the key table")
+                                .action(ArgAction::SetTrue),
+                        )
+                        .arg(
+                            Arg::new("rules")
+                                .long("rules")
+                                .help("Describe .rules.yaml instead of the workspace config")
+                                .action(ArgAction::SetTrue),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .short('o')
+                                .long("output")
+                                .value_name("FILE")
+                                .help("Write the JSON Schema to FILE instead of stdout"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("convert")
+                        .about("Rewrite the workspace config in another format")
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Rewrite the active config in another format (json, yaml, toml).\n\
+                             \n\
+                             An extensionless .metarepo or .meta is rewritten in place: its format is\n\
+                             detected from its contents, so the name stays the same. A file with an\n\
+                             extension is replaced by one with the new extension (.metarepo.json\n\
+                             becomes .metarepo.yaml). All project metadata is carried over. Use\n\
+                             migrate instead to keep the original or choose the destination.\n\
+                             \n\
+                             Examples:\n  \
+                               meta config convert --to yaml\n  \
+                               meta config convert --to json\n",
+                        ))
+                        .arg(
+                            Arg::new("to")
+                                .long("to")
+                                .required(true)
+                                .value_name("FORMAT")
+                                .value_parser(["json", "yaml", "yml", "toml"])
+                                .help("Format to convert to"),
                         ),
                 )
                 .subcommand(
@@ -589,6 +984,71 @@ impl MetaPlugin for ConfigPlugin {
                                 .action(ArgAction::SetTrue)
                                 .help("Overwrite the destination if it already exists"),
                         ),
+                )
+                .subcommand(
+                    Command::new("keygen")
+                        .about("Create the key for encrypted project env values")
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Create a key for encrypting values in project env maps.\n\
+                             \n\
+                             The key is written to ~/.config/metarepo/env.key (under\n\
+                             $XDG_CONFIG_HOME when set), readable only by you. --keychain\n\
+                             stores it in the OS keychain instead (the env-key entry of the\n\
+                             metarepo service), which wins over the file. Share it with the team\n\
+                             out of band; in CI, put it in METAREPO_ENV_KEY instead, which wins\n\
+                             over both. --stdout prints a key without saving it. Refuses to\n\
+                             replace an existing key unless --force is given: values encrypted\n\
+                             with the old key cannot be decrypted with a new one.\n\
+                             \n\
+                             Examples:\n  \
+                               meta config keygen\n  \
+                               meta config keygen --keychain\n  \
+                               meta config keygen --stdout\n",
+                        ))
+                        .arg(
+                            Arg::new("stdout")
+                                .long("stdout")
+                                .action(ArgAction::SetTrue)
+                                .help("Print the key instead of writing the key file"),
+                        )
+                        .arg(
+                            Arg::new("keychain")
+                                .long("keychain")
+                                .conflicts_with("stdout")
+                                .action(ArgAction::SetTrue)
+                                .help("Store the key in the OS keychain instead of the key file"),
+                        )
+                        .arg(
+                            Arg::new("force")
+                                .long("force")
+                                .conflicts_with("stdout")
+                                .action(ArgAction::SetTrue)
+                                .help("Replace an existing key"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("encrypt")
+                        .about("Encrypt a value for a project env map")
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Encrypt a value with the workspace key and print it as enc:<base64>.\n\
+                             \n\
+                             Put the result in a project's env map; meta exec, meta run, and\n\
+                             worktree_init hooks decrypt it when they start, using\n\
+                             METAREPO_ENV_KEY, the OS keychain, or the key file from meta config\n\
+                             keygen. Without VALUE the value is read from\n\
+                             stdin, which keeps it out of your shell history. Each call uses a\n\
+                             fresh nonce, so the same value encrypts differently every time.\n\
+                             \n\
+                             Examples:\n  \
+                               meta config encrypt < token.txt\n  \
+                               meta config set projects.api.env.NPM_TOKEN \"$(meta config encrypt < token.txt)\"\n",
+                        ))
+                        .arg(
+                            Arg::new("value")
+                                .value_name("VALUE")
+                                .allow_hyphen_values(true)
+                                .help("Value to encrypt (default: read stdin)"),
+                        ),
                 );
         // The global `--version` arg propagates (global=true) into every
         // subcommand and uses ArgAction::Version, which clap asserts requires a
@@ -604,8 +1064,13 @@ impl MetaPlugin for ConfigPlugin {
             Some(("get", sub_matches)) => self.handle_get(sub_matches, config),
             Some(("set", sub_matches)) => self.handle_set(sub_matches, config),
             Some(("list", _)) => self.handle_list(config),
+            Some(("diff", sub_matches)) => self.handle_diff(sub_matches, config),
             Some(("validate", sub_matches)) => self.handle_validate(sub_matches, config),
+            Some(("schema", sub_matches)) => self.handle_schema(sub_matches, config),
+            Some(("convert", sub_matches)) => self.handle_convert(sub_matches, config),
             Some(("migrate", sub_matches)) => self.handle_migrate(sub_matches, config),
+            Some(("keygen", sub_matches)) => self.handle_keygen(sub_matches),
+            Some(("encrypt", sub_matches)) => self.handle_encrypt(sub_matches),
             _ => {
                 // Default to edit if no subcommand provided
                 self.handle_edit(matches, config)
@@ -614,6 +1079,21 @@ impl MetaPlugin for ConfigPlugin {
     }
 }
 
+/// Where `meta config convert` writes `source` in `format`. Extensionless
+/// names (`.metarepo`, `.meta`) are rewritten in place, since their format is
+/// read from their contents; others get the new extension.
+fn convert_destination(source: &Path, format: ConfigFormat) -> Result<PathBuf> {
+    let name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow!("Invalid config path {}", source.display()))?;
+    if name == CANONICAL_FILENAME || name == LEGACY_FILENAME {
+        return Ok(source.to_path_buf());
+    }
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    Ok(source.with_file_name(format!("{}.{}", stem, format.label())))
+}
+
 impl BasePlugin for ConfigPlugin {
     fn version(&self) -> Option<&str> {
         Some(env!("CARGO_PKG_VERSION"))
@@ -682,6 +1162,44 @@ mod tests {
         assert_eq!(path, PathBuf::from("/ws/.meta"));
     }
 
+    #[test]
+    fn convert_rewrites_extensionless_configs_in_place() {
+        assert_eq!(
+            convert_destination(Path::new("/ws/.meta"), ConfigFormat::Yaml).unwrap(),
+            PathBuf::from("/ws/.meta")
+        );
+        assert_eq!(
+            convert_destination(Path::new("/ws/.metarepo.json"), ConfigFormat::Yaml).unwrap(),
+            PathBuf::from("/ws/.metarepo.yaml")
+        );
+        assert_eq!(
+            convert_destination(Path::new("/ws/.meta.yml"), ConfigFormat::Json).unwrap(),
+            PathBuf::from("/ws/.meta.json")
+        );
+
+        // A YAML .meta keeps every project field when read back.
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(".meta");
+        let original = cfg(r#"{"projects": {
+            "web": {
+                "url": "git@h:web.git",
+                "aliases": ["w"],
+                "scripts": {"test": "npm test", "deploy": {"cmd": "./deploy.sh", "tags": ["ops"]}},
+                "env": {"PORT": "3000"},
+                "depth": 1,
+                "pinned": "v1",
+                "visibility": "internal"
+            },
+            "api": "git@h:api.git"
+        }}"#);
+        original
+            .save_to_file_with_format(&path, ConfigFormat::Yaml)
+            .unwrap();
+        assert_eq!(ConfigFormat::of_file(&path), Some(ConfigFormat::Yaml));
+        let loaded = MetaConfig::load_from_file(&path).unwrap();
+        assert_eq!(loaded.projects, original.projects);
+    }
+
     #[test]
     fn root_write_target_none_for_placeholder_path() {
         // The config_chain fallback pushes an empty path when nothing is found.
@@ -0,0 +1,112 @@
+//! `meta config schema`: the keys of `.meta` (or `.rules.yaml`) as a table,
+//! or, with `--json-schema`, as a JSON Schema for editors.
+
+use metarepo_core::Table;
+use serde_json::Value;
+
+/// One documented key: its dotted path, a short type label, and description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaKey {
+    pub path: String,
+    pub type_label: String,
+    pub description: String,
+}
+
+/// Every key `schema` describes, depth first. Entries of a list of objects
+/// are shown as `list[].field`.
+pub fn schema_keys(schema: &Value) -> Vec<SchemaKey> {
+    let mut keys = Vec::new();
+    collect(schema, "", &mut keys);
+    keys
+}
+
+fn collect(node: &Value, prefix: &str, keys: &mut Vec<SchemaKey>) {
+    let Some(properties) = node["properties"].as_object() else {
+        return;
+    };
+    for (name, property) in properties {
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        keys.push(SchemaKey {
+            path: path.clone(),
+            type_label: type_label(property),
+            description: property["description"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        });
+        if property["items"]["properties"].is_object() {
+            collect(&property["items"], &format!("{}[]", path), keys);
+        } else {
+            collect(property, &path, keys);
+        }
+    }
+}
+
+/// A compact label such as `string`, `list`, `map of script`, or
+/// `full|read-write|read-only`.
+fn type_label(property: &Value) -> String {
+    if let Some(reference) = property["$ref"].as_str() {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(choices) = property["enum"].as_array() {
+        let choices: Vec<&str> = choices.iter().filter_map(Value::as_str).collect();
+        return choices.join("|");
+    }
+    if let Some(variants) = property["oneOf"].as_array() {
+        let labels: Vec<String> = variants.iter().map(type_label).collect();
+        return labels.join(" or ");
+    }
+    match property["type"].as_str() {
+        Some("array") => "list".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("integer") => "int".to_string(),
+        Some("object") if property["additionalProperties"].is_object() => {
+            format!("map of {}", type_label(&property["additionalProperties"]))
+        }
+        Some(other) => other.to_string(),
+        None => "any".to_string(),
+    }
+}
+
+/// Print `schema`'s keys as a table.
+pub fn print_schema_keys(schema: &Value) {
+    let mut table = Table::new(["Key", "Type", "Description"]).shrink(2);
+    for key in schema_keys(schema) {
+        table.row([key.path, key.type_label, key.description]);
+    }
+    table.print();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::rules::RulesConfig;
+
+    fn find<'a>(keys: &'a [SchemaKey], path: &str) -> &'a SchemaKey {
+        keys.iter().find(|k| k.path == path).unwrap()
+    }
+
+    #[test]
+    fn keys_are_dotted_with_short_types() {
+        let keys = schema_keys(&metarepo_core::config_json_schema(&[]));
+        assert_eq!(find(&keys, "projects").type_label, "map of project");
+        assert_eq!(find(&keys, "nested.max_depth").type_label, "int");
+        assert_eq!(
+            find(&keys, "mcp.serve.mode").type_label,
+            "full|read-write|read-only"
+        );
+        assert_eq!(find(&keys, "color").type_label, "auto|always|never");
+
+        let rules = schema_keys(&RulesConfig::json_schema());
+        assert_eq!(find(&rules, "size[].max_lines").type_label, "int");
+        assert_eq!(find(&rules, "directories").type_label, "list");
+    }
+}
@@ -738,7 +738,7 @@ impl ConfigEditor {
                     for (script_name, script_cmd) in &meta.scripts {
                         let mut script_node = TreeNode::with_value(
                             script_name,
-                            script_cmd,
+                            script_cmd.command(),
                             project_script_node_type(name, script_name),
                         );
                         script_node.depth = 3;
@@ -799,7 +799,7 @@ impl ConfigEditor {
 
                 for (name, cmd) in scripts {
                     let mut script_node =
-                        TreeNode::with_value(name, cmd, global_script_node_type(name));
+                        TreeNode::with_value(name, cmd.command(), global_script_node_type(name));
                     script_node.depth = 1;
                     scripts_node.add_child(script_node);
                 }
@@ -1084,20 +1084,24 @@ impl ConfigEditor {
         }
     }
 
-    /// Insert or update a script command in the appropriate map.
+    /// Insert or update a script command in the appropriate map. An existing
+    /// script keeps its description and tags.
     fn set_script(&mut self, target: &ScriptRef, cmd: String) {
-        match target {
-            ScriptRef::Global(name) => {
-                self.config
-                    .scripts
-                    .get_or_insert_with(Default::default)
-                    .insert(name.clone(), cmd);
-            }
-            ScriptRef::Project { proj, name } => {
-                if let Some(metarepo_core::ProjectEntry::Metadata(meta)) =
-                    self.config.projects.get_mut(proj)
-                {
-                    meta.scripts.insert(name.clone(), cmd);
+        let scripts = match target {
+            ScriptRef::Global(_) => Some(self.config.scripts.get_or_insert_with(Default::default)),
+            ScriptRef::Project { proj, .. } => match self.config.projects.get_mut(proj) {
+                Some(metarepo_core::ProjectEntry::Metadata(meta)) => Some(&mut meta.scripts),
+                _ => None,
+            },
+        };
+        let name = match target {
+            ScriptRef::Global(name) | ScriptRef::Project { name, .. } => name,
+        };
+        if let Some(scripts) = scripts {
+            match scripts.get_mut(name) {
+                Some(entry) => entry.set_command(cmd),
+                None => {
+                    scripts.insert(name.clone(), cmd.into());
                 }
             }
         }
@@ -1318,12 +1322,19 @@ impl ConfigEditor {
                 metarepo_core::ProjectEntry::Metadata(metarepo_core::ProjectMetadata {
                     url: String::new(),
                     aliases: Vec::new(),
+                    tags: Vec::new(),
                     scripts: std::collections::HashMap::new(),
                     env: std::collections::HashMap::new(),
                     worktree_init: None,
                     bare: None,
+                    default_script: None,
                     enabled: None,
                     depth: None,
+                    pinned: None,
+                    vendored: None,
+                    visibility: None,
+                    vcs: None,
+                    deprecated: None,
                 }),
             );
             self.state.modified = true;
@@ -1342,12 +1353,10 @@ impl ConfigEditor {
         if let AddContext::ProjectEnv(proj) = &ctx {
             let proj = proj.clone();
             match self.config.projects.get(&proj) {
-                Some(metarepo_core::ProjectEntry::Metadata(m)) => {
-                    if m.env.contains_key(&name) {
-                        self.state
-                            .set_status(format!("Env var {name} already exists"));
-                        return;
-                    }
+                Some(metarepo_core::ProjectEntry::Metadata(m)) if m.env.contains_key(&name) => {
+                    self.state
+                        .set_status(format!("Env var {name} already exists"));
+                    return;
                 }
                 Some(metarepo_core::ProjectEntry::Url(_)) => {
                     self.state.set_status(format!(
@@ -1355,7 +1364,7 @@ impl ConfigEditor {
                     ));
                     return;
                 }
-                None => {}
+                _ => {}
             }
             self.set_env(&proj, &name, String::new());
             self.state.modified = true;
@@ -0,0 +1,478 @@
+//! `meta config validate`: check a config file against the shape meta reads —
+//! the config types themselves plus the settings plugins declare — and report
+//! every problem with the dotted path it was found at, rather than stopping at
+//! the first parse error.
+
+use crate::plugins::shared::branch_policy;
+use crate::plugins::shared::issues::IssueLinker;
+use crate::plugins::shared::secrets;
+use anyhow::Result;
+use colored::Colorize;
+use metarepo_core::config_format::deserialize_from_str;
+use metarepo_core::{
+    is_dangerous_env_var, is_supported_git_url, validate_path_segment, validate_project_url,
+    ConfigFormat, ConfigSetting, MetaConfig, ProjectEntry, ProjectMetadata,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Something wrong with the config, at a dotted `path` (empty for the file
+/// itself).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Problem {
+    pub path: String,
+    pub message: String,
+}
+
+fn problem(path: impl Into<String>, message: impl Into<String>) -> Problem {
+    Problem {
+        path: path.into(),
+        message: message.into(),
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Why `url` is not a usable project URL, if it is not.
+fn url_problem(url: &str) -> Option<String> {
+    if let Err(e) = validate_project_url(url) {
+        return Some(e.to_string());
+    }
+    if url.contains(char::is_whitespace) {
+        return Some("URL contains whitespace".to_string());
+    }
+    let url = url.strip_prefix("external:").unwrap_or(url);
+    if let Some(name) = url.strip_prefix("local:") {
+        return name
+            .is_empty()
+            .then(|| "local: entry needs a name".to_string());
+    }
+
+    let (host, path) = if let Some((scheme, rest)) = url.split_once("://") {
+        if scheme != "file" && !is_supported_git_url(url) {
+            return Some(format!("unsupported URL scheme '{}'", scheme));
+        }
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, r)| r);
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        // file:///path has no host.
+        (if scheme == "file" { "localhost" } else { host }, path)
+    } else if let Some((userhost, path)) = url
+        .split_once(':')
+        .filter(|(userhost, _)| !userhost.contains('/'))
+    {
+        // scp-like: [user@]host:path
+        (userhost.rsplit_once('@').map_or(userhost, |(_, h)| h), path)
+    } else {
+        // A filesystem path.
+        return None;
+    };
+    if host.is_empty() {
+        Some("URL has no host".to_string())
+    } else if path.trim_matches('/').is_empty() {
+        Some("URL has no repository path".to_string())
+    } else {
+        None
+    }
+}
+
+/// Type errors, checked one top-level key (and one project) at a time so
+/// each is reported where it occurs.
+fn type_problems(raw: &Map<String, Value>, problems: &mut Vec<Problem>) {
+    for (key, value) in raw {
+        if key == "projects" {
+            let Some(projects) = value.as_object() else {
+                problems.push(problem(key, "must be an object of projects"));
+                continue;
+            };
+            for (name, entry) in projects {
+                let path = join(key, name);
+                match entry {
+                    Value::String(_) => {}
+                    Value::Object(_) => {
+                        if let Err(e) = serde_json::from_value::<ProjectMetadata>(entry.clone()) {
+                            problems.push(problem(path, e.to_string()));
+                        }
+                    }
+                    _ => problems.push(problem(
+                        path,
+                        "must be a URL string or an object with a \"url\"",
+                    )),
+                }
+            }
+            continue;
+        }
+        let single = Value::Object(Map::from_iter([(key.clone(), value.clone())]));
+        if let Err(e) = serde_json::from_value::<MetaConfig>(single) {
+            problems.push(problem(key, e.to_string()));
+        }
+    }
+}
+
+/// Keys in `raw` that `typed` (the same config after a round trip through
+/// [`MetaConfig`]) dropped, i.e. keys meta does not read.
+fn unknown_keys(raw: &Value, typed: &Value, path: &str, problems: &mut Vec<Problem>) {
+    let (Some(raw), Some(typed)) = (raw.as_object(), typed.as_object()) else {
+        return;
+    };
+    for (key, value) in raw {
+        let key_path = join(path, key);
+        match typed.get(key) {
+            Some(kept) => unknown_keys(value, kept, &key_path, problems),
+            // Unset and empty values are not written back; they are harmless.
+            None if value.is_null() || value.as_array().is_some_and(|a| a.is_empty()) => {}
+            None => problems.push(problem(key_path, "unknown key")),
+        }
+    }
+}
+
+fn lookup<'a>(raw: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(raw, |value, segment| value.get(segment))
+}
+
+/// Values of declared settings that do not match their type or choices.
+fn setting_problems(raw: &Value, catalog: &[ConfigSetting], problems: &mut Vec<Problem>) {
+    for setting in catalog {
+        let Some(value) = lookup(raw, &setting.key).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        if !setting.value_type.matches(value) {
+            problems.push(problem(
+                &setting.key,
+                format!("expected {}, found {}", setting.value_type.label(), value),
+            ));
+        } else if let (Some(choices), Some(s)) = (&setting.choices, value.as_str()) {
+            if !choices.iter().any(|c| c == s) {
+                problems.push(problem(
+                    &setting.key,
+                    format!("'{}' is not one of: {}", s, choices.join(", ")),
+                ));
+            }
+        }
+    }
+}
+
+/// Project names, URLs, env, and aliases.
+fn project_problems(config: &MetaConfig, problems: &mut Vec<Problem>) {
+    let mut names: Vec<&String> = config.projects.keys().collect();
+    names.sort();
+    // alias -> projects claiming it
+    let mut claims: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+    for name in names {
+        let path = join("projects", name);
+        if let Err(e) = validate_path_segment("project name", name) {
+            problems.push(problem(&path, e.to_string()));
+        }
+        let entry = &config.projects[name];
+        let url = match entry {
+            ProjectEntry::Url(url) => url,
+            ProjectEntry::Metadata(metadata) => &metadata.url,
+        };
+        if let Some(message) = url_problem(url) {
+            let at = match entry {
+                ProjectEntry::Url(_) => path.clone(),
+                ProjectEntry::Metadata(_) => join(&path, "url"),
+            };
+            problems.push(problem(at, message));
+        }
+        let ProjectEntry::Metadata(metadata) = entry else {
+            continue;
+        };
+        let mut env: Vec<&String> = metadata.env.keys().collect();
+        env.sort();
+        for var in env.into_iter().filter(|v| is_dangerous_env_var(v)) {
+            problems.push(problem(
+                join(&join(&path, "env"), var),
+                "ignored: known to subvert subprocesses",
+            ));
+        }
+        let mut encrypted: Vec<(&String, &String)> = metadata
+            .env
+            .iter()
+            .filter(|(_, value)| secrets::is_encrypted(value))
+            .collect();
+        encrypted.sort();
+        for (var, value) in encrypted {
+            if let Some(message) = secrets::format_problem(value) {
+                problems.push(problem(join(&join(&path, "env"), var), message));
+            }
+        }
+        for alias in &metadata.aliases {
+            claims
+                .entry(alias.as_str())
+                .or_default()
+                .push(name.as_str());
+        }
+    }
+
+    if let Some(aliases) = &config.aliases {
+        let mut global: Vec<(&String, &String)> = aliases.iter().collect();
+        global.sort();
+        for (alias, target) in global {
+            if !config.projects.contains_key(target) {
+                problems.push(problem(
+                    join("aliases", alias),
+                    format!("points to unknown project '{}'", target),
+                ));
+            }
+            if let Some(owners) = claims.get(alias.as_str()) {
+                if owners.iter().any(|o| *o != target.as_str()) {
+                    problems.push(problem(
+                        join("aliases", alias),
+                        format!("also an alias of project '{}'", owners.join("', '")),
+                    ));
+                }
+            }
+        }
+    }
+    for (alias, owners) in claims {
+        let at = format!("projects.{}.aliases", owners[0]);
+        if owners.len() > 1 {
+            problems.push(problem(
+                at,
+                format!(
+                    "alias '{}' is used by several projects: {}",
+                    alias,
+                    owners.join(", ")
+                ),
+            ));
+        } else if config.projects.contains_key(alias) && alias != owners[0] {
+            problems.push(problem(
+                at,
+                format!("alias '{}' is the name of another project", alias),
+            ));
+        }
+    }
+}
+
+fn branch_policy_problems(config: &MetaConfig, problems: &mut Vec<Problem>) {
+    let Some(policy) = &config.branch_policy else {
+        return;
+    };
+    let pattern = match branch_policy::compile(policy) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            problems.push(problem("branch_policy.pattern", e.to_string()));
+            return;
+        }
+    };
+    for example in policy.examples.iter().filter(|e| !pattern.is_match(e)) {
+        problems.push(problem(
+            "branch_policy.examples",
+            format!("example '{}' does not match the pattern", example),
+        ));
+    }
+}
+
+fn issue_tracker_problems(config: &MetaConfig, problems: &mut Vec<Problem>) {
+    for (i, tracker) in config.issue_trackers.iter().enumerate() {
+        if let Err(e) = IssueLinker::new(std::slice::from_ref(tracker)) {
+            problems.push(problem(
+                format!("issue_trackers.{}.pattern", i),
+                e.to_string(),
+            ));
+        }
+    }
+}
+
+/// Check `content` (in `format`) against the config types and `catalog`.
+pub fn validate_str(
+    content: &str,
+    format: ConfigFormat,
+    catalog: &[ConfigSetting],
+) -> Vec<Problem> {
+    let raw: Value = match deserialize_from_str(content, format) {
+        Ok(raw) => raw,
+        Err(e) => {
+            return vec![problem(
+                "",
+                format!("does not parse as {}: {}", format.label(), e),
+            )]
+        }
+    };
+    let Some(object) = raw.as_object() else {
+        return vec![problem("", "the config must be an object")];
+    };
+
+    let mut problems = Vec::new();
+    type_problems(object, &mut problems);
+    setting_problems(&raw, catalog, &mut problems);
+    // The remaining checks need the whole config to deserialize.
+    if let Ok(config) = serde_json::from_value::<MetaConfig>(raw.clone()) {
+        if let Ok(typed) = serde_json::to_value(&config) {
+            unknown_keys(&raw, &typed, "", &mut problems);
+        }
+        project_problems(&config, &mut problems);
+        branch_policy_problems(&config, &mut problems);
+        issue_tracker_problems(&config, &mut problems);
+    }
+    problems
+}
+
+/// Check the config file at `path`.
+pub fn validate_file(path: &Path, catalog: &[ConfigSetting]) -> Result<Vec<Problem>> {
+    let content = std::fs::read_to_string(path)?;
+    let format = ConfigFormat::of_file(path).unwrap_or(ConfigFormat::Json);
+    Ok(validate_str(&content, format, catalog))
+}
+
+/// Print the result of validating `file`.
+pub fn print_problems(file: &Path, problems: &[Problem]) {
+    println!(
+        "\n  {} {}",
+        "🔍".bright_blue(),
+        format!("Validating {}", file.display()).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    if problems.is_empty() {
+        println!("  {} {}", "✓".green(), "Config file is valid".green());
+        println!();
+        return;
+    }
+    for p in problems {
+        let at = if p.path.is_empty() {
+            "(file)"
+        } else {
+            p.path.as_str()
+        };
+        println!("  {} {}: {}", "✗".red(), at.bright_white(), p.message);
+    }
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} problem(s)",
+        "Summary:".bright_black(),
+        problems.len().to_string().red()
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ConfigValueType;
+
+    fn paths(problems: &[Problem]) -> Vec<&str> {
+        problems.iter().map(|p| p.path.as_str()).collect()
+    }
+
+    #[test]
+    fn a_well_formed_config_has_no_problems() {
+        let content = r#"{
+            "ignore": [".git"],
+            "projects": {
+                "web": "git@github.com:acme/web.git",
+                "api": {
+                    "url": "https://github.com/acme/api",
+                    "aliases": ["a"],
+                    "scripts": {"test": {"cmd": "cargo test", "tags": []}},
+                    "pinned": null
+                },
+                "scratch": "local:scratch"
+            },
+            "aliases": {"w": "web"},
+            "disabled": [],
+            "branch_policy": {"pattern": "feat/.+", "examples": ["feat/login"]},
+            "notify": {"on_complete_secs": 30}
+        }"#;
+        let catalog = [ConfigSetting::new(
+            "notify.on_complete_secs",
+            "",
+            ConfigValueType::Integer,
+        )];
+        assert_eq!(validate_str(content, ConfigFormat::Json, &catalog), []);
+    }
+
+    #[test]
+    fn reports_each_problem_where_it_occurs() {
+        let content = r#"{
+            "projects": {
+                "web": {"url": "git@github.com:", "aliases": ["w"], "colour": "red"},
+                "api": {
+                    "url": "https:/ /github.com/acme/api",
+                    "aliases": ["w", "web"],
+                    "env": {"TOKEN": "enc:AAAA", "PLAIN": "enc"}
+                },
+                "docs": 42
+            },
+            "aliases": {"d": "docs-site"},
+            "mcp": {"serve": {"mode": "everything"}},
+            "branch_policy": {"pattern": "feat/.+", "examples": ["fix/crash"]},
+            "issue_trackers": [{"pattern": "ENG-(", "url": "https://example.com/{id}"}],
+            "frobnicate": true
+        }"#;
+        let catalog = [
+            ConfigSetting::new("mcp.serve.mode", "", ConfigValueType::String).with_choices([
+                "full",
+                "read-write",
+                "read-only",
+            ]),
+        ];
+        let problems = validate_str(content, ConfigFormat::Json, &catalog);
+        // A project that does not type-check stops the checks that need a
+        // whole config.
+        assert_eq!(paths(&problems), ["projects.docs", "mcp.serve.mode"]);
+
+        let content = content.replace("\"docs\": 42", "\"docs\": \"git@github.com:acme/docs\"");
+        let problems = validate_str(&content, ConfigFormat::Json, &catalog);
+        let mut found = paths(&problems);
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                "aliases.d",
+                "branch_policy.examples",
+                "frobnicate",
+                "issue_trackers.0.pattern",
+                "mcp.serve.mode",
+                "projects.api.aliases",
+                "projects.api.aliases",
+                "projects.api.env.TOKEN",
+                "projects.api.url",
+                "projects.web.colour",
+                "projects.web.url",
+            ]
+        );
+        let messages: Vec<&str> = problems.iter().map(|p| p.message.as_str()).collect();
+        assert!(messages.contains(&"unknown key"));
+        assert!(messages.contains(&"URL has no repository path"));
+        assert!(messages.contains(&"URL contains whitespace"));
+        assert!(messages.contains(&"points to unknown project 'docs-site'"));
+        assert!(messages.contains(&"alias 'w' is used by several projects: api, web"));
+        assert!(messages.contains(&"alias 'web' is the name of another project"));
+        assert!(messages.contains(&"example 'fix/crash' does not match the pattern"));
+        assert!(messages.contains(&"encrypted value is truncated"));
+    }
+
+    #[test]
+    fn parse_errors_and_urls() {
+        assert_eq!(
+            paths(&validate_str("projects = [", ConfigFormat::Toml, &[])),
+            [""]
+        );
+        assert_eq!(url_problem("git@github.com:acme/web.git"), None);
+        assert_eq!(url_problem("ssh://git@host:22/acme/web"), None);
+        assert_eq!(url_problem("file:///srv/git/web"), None);
+        assert_eq!(url_problem("../web"), None);
+        assert_eq!(
+            url_problem("ftp://host/web").as_deref(),
+            Some("unsupported URL scheme 'ftp'")
+        );
+        assert_eq!(
+            url_problem("https:///web").as_deref(),
+            Some("URL has no host")
+        );
+        assert_eq!(
+            url_problem("local:").as_deref(),
+            Some("local: entry needs a name")
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! `meta daemon`: a background process that keeps a workspace's git status warm.
+//!
+//! The daemon listens on a Unix socket under the workspace's state directory
+//! and speaks a versioned JSON-RPC 2.0 API, one message per line (see
+//! `docs/DAEMON_API.md`). Commands that read git status (`meta status`, the
+//! MCP server's `git_status` tool) ask it first and fall back to scanning the
+//! repositories themselves when no daemon is running. Editor extensions keep
+//! a connection open: besides status they get the project tree, run scripts,
+//! open worktrees, and subscribe to [`EVENTS`]. Cached entries are reused until
+//! the repository's index, HEAD, or refs change, or until they are
+//! [`CACHE_TTL`] old, so a warm daemon answers without opening a single
+//! repository.
+
+mod plugin;
+mod state;
+
+pub use plugin::DaemonPlugin;
+pub use state::DaemonState;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::plugins::shared::state as state_dir;
+use crate::plugins::status::RepoStatus;
+
+/// Name of the daemon's socket inside the workspace state directory.
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+/// Version of the daemon API. Bumped when a method is removed or changes
+/// incompatibly; new methods and new optional fields keep the version.
+pub const API_VERSION: u32 = 1;
+
+/// The methods the daemon answers, as reported by `api.version`.
+pub const METHODS: &[&str] = &[
+    "api.version",
+    "daemon.ping",
+    "daemon.info",
+    "daemon.stop",
+    "workspace.projects",
+    "workspace.tree",
+    "status.get",
+    "script.run",
+    "script.status",
+    "worktree.open",
+    "events.subscribe",
+    "events.unsubscribe",
+];
+
+/// The notifications a connection can subscribe to with `events.subscribe`.
+pub const EVENTS: &[&str] = &["status.changed", "script.exited"];
+
+/// How long a cached status is trusted when nothing on disk has changed. Some
+/// changes (editing a tracked file without staging it) do not touch the files
+/// the fingerprint looks at, so entries still expire.
+pub const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// How often the server looks for finished scripts and, while anyone is
+/// subscribed to `status.changed`, for status changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a client waits for the daemon before giving up on it.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// A JSON-RPC 2.0 request. Requests without an `id` are notifications and get
+/// no response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub params: Value,
+}
+
+impl RpcRequest {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(id)),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC 2.0 error object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// A JSON-RPC 2.0 response: exactly one of `result` and `error` is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn new(id: Value, outcome: std::result::Result<Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// Path of the daemon socket for the workspace at `base_path`.
+pub fn socket_path(base_path: &Path) -> PathBuf {
+    state_dir::state_path(base_path, SOCKET_FILE)
+}
+
+/// Call `method` on the workspace's daemon and return its result.
+#[cfg(unix)]
+pub fn call(base_path: &Path, method: &str, params: Value) -> Result<Value> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket_path(base_path))?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let request = RpcRequest::new(1, method, params);
+    writeln!(stream, "{}", serde_json::to_string(&request)?)?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let response: RpcResponse = serde_json::from_str(&line)?;
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(anyhow::anyhow!("{}", error.message)),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err(anyhow::anyhow!("daemon sent an empty response")),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn call(_base_path: &Path, _method: &str, _params: Value) -> Result<Value> {
+    Err(anyhow::anyhow!("meta daemon is only supported on Unix"))
+}
+
+/// Whether a daemon is answering for the workspace at `base_path`.
+pub fn is_running(base_path: &Path) -> bool {
+    socket_path(base_path).exists() && call(base_path, "daemon.ping", Value::Null).is_ok()
+}
+
+/// Status from the workspace's daemon, or `None` when none is running (or it
+/// could not answer), in which case the caller gathers status itself. `None`
+/// for `projects` asks for every enabled, unrestricted project.
+pub fn cached_status(
+    base_path: &Path,
+    projects: Option<&[String]>,
+    refresh: bool,
+) -> Option<Vec<RepoStatus>> {
+    if !socket_path(base_path).exists() {
+        return None;
+    }
+    let mut params = json!({ "refresh": refresh });
+    if let Some(projects) = projects {
+        params["projects"] = json!(projects);
+    }
+    let result = call(base_path, "status.get", params).ok()?;
+    serde_json::from_value(result).ok()
+}
+
+/// Serve the workspace at `base_path` until `daemon.stop` is called. Each
+/// connection gets its own thread and may send any number of requests, so an
+/// editor can stay connected and receive the events it subscribes to. Fails
+/// if another daemon already answers on the socket; a socket left behind by a
+/// daemon that died is replaced.
+#[cfg(unix)]
+pub fn serve(base_path: &Path) -> Result<()> {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    let socket = socket_path(base_path);
+    if socket.exists() {
+        if is_running(base_path) {
+            return Err(anyhow::anyhow!(
+                "A daemon is already running for this workspace"
+            ));
+        }
+        std::fs::remove_file(&socket)?;
+    }
+    if let Some(parent) = socket.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(&socket)?;
+    // The API runs scripts and creates worktrees: only the owner may connect.
+    std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600))?;
+    let daemon = Arc::new(Mutex::new(DaemonState::new(base_path.to_path_buf())));
+    let stopping = Arc::new(AtomicBool::new(false));
+
+    {
+        let daemon = Arc::clone(&daemon);
+        let stopping = Arc::clone(&stopping);
+        std::thread::spawn(move || {
+            while !stopping.load(Ordering::SeqCst) {
+                std::thread::sleep(WATCH_INTERVAL);
+                daemon.lock().unwrap().poll();
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        if stopping.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else { continue };
+        let daemon = Arc::clone(&daemon);
+        let stopping = Arc::clone(&stopping);
+        let socket = socket.clone();
+        std::thread::spawn(move || {
+            let _ = stream.set_write_timeout(Some(CLIENT_TIMEOUT));
+            let Ok(writer) = stream.try_clone() else {
+                return;
+            };
+            // Responses and events share the connection, so both are written
+            // through the daemon state, under its lock.
+            let connection = daemon.lock().unwrap().connect(Box::new(writer));
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if daemon.lock().unwrap().receive(connection, &line) {
+                    stopping.store(true, Ordering::SeqCst);
+                    // Wake the accept loop so it sees the flag.
+                    let _ = UnixStream::connect(&socket);
+                    break;
+                }
+            }
+            daemon.lock().unwrap().disconnect(connection);
+        });
+    }
+
+    let _ = std::fs::remove_file(&socket);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(_base_path: &Path) -> Result<()> {
+    Err(anyhow::anyhow!("meta daemon is only supported on Unix"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::status::RepoState;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn serves_requests_over_the_socket_until_stopped() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().to_path_buf();
+        assert!(!is_running(&base));
+        assert!(cached_status(&base, Some(&["api".to_string()]), false).is_none());
+
+        let server = {
+            let base = base.clone();
+            std::thread::spawn(move || serve(&base))
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !is_running(&base) {
+            assert!(Instant::now() < deadline, "daemon did not start");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        // A long-lived connection doesn't block other clients.
+        let mut editor = UnixStream::connect(socket_path(&base)).unwrap();
+        let statuses = cached_status(&base, Some(&["api".to_string()]), false).unwrap();
+        assert_eq!(statuses[0].state, RepoState::Missing);
+        assert!(serve(&base).is_err());
+        for id in 1..=2 {
+            writeln!(
+                editor,
+                r#"{{"jsonrpc":"2.0","id":{},"method":"daemon.ping"}}"#,
+                id
+            )
+            .unwrap();
+        }
+        let mut lines = BufReader::new(editor.try_clone().unwrap()).lines();
+        for id in 1..=2 {
+            let response: RpcResponse =
+                serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+            assert_eq!(response.id, json!(id));
+        }
+
+        call(&base, "daemon.stop", Value::Null).unwrap();
+        server.join().unwrap().unwrap();
+        assert!(!socket_path(&base).exists());
+    }
+}
@@ -0,0 +1,238 @@
+use super::{call, is_running, serve, socket_path};
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use colored::*;
+use metarepo_core::{command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+use serde_json::Value;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long `meta daemon start` waits for the new daemon to answer.
+const START_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// DaemonPlugin for the background workspace daemon
+pub struct DaemonPlugin;
+
+impl DaemonPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("daemon")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Keep workspace git status warm in a background process")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Keep workspace git status warm in a background process.\n\
+                 \n\
+                 The daemon serves the workspace's git status from a cache over a\n\
+                 local socket (.meta-state/daemon.sock). `meta status` and the MCP\n\
+                 server's git_status tool ask it first and scan the repositories\n\
+                 themselves when no daemon is running, so the daemon is never\n\
+                 required. Each meta invocation still starts its own process; what\n\
+                 the daemon saves is re-reading every repository.\n\
+                 \n\
+                 Editors and other tools can talk to the socket directly: it speaks\n\
+                 a versioned JSON-RPC 2.0 API, one message per line, described in\n\
+                 docs/DAEMON_API.md. Besides status it serves the project tree,\n\
+                 runs scripts, opens worktrees, and pushes status.changed and\n\
+                 script.exited events to subscribed connections.\n\
+                 \n\
+                 Unix only.",
+            )
+            .command(
+                command("start")
+                    .about("Start the daemon for this workspace in the background")
+                    .help_description(
+                        "Start the daemon for this workspace in the background.\n\
+                         \n\
+                         Does nothing if one is already running. The daemon keeps\n\
+                         running after the shell exits; stop it with `meta daemon stop`.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta daemon start\n\
+                           meta daemon status",
+                    )
+                    .with_help_formatting(),
+            )
+            .command(
+                command("run")
+                    .about("Run the daemon in the foreground")
+                    .help_description(
+                        "Run the daemon in the foreground until it is stopped.\n\
+                         \n\
+                         `meta daemon start` runs this in the background; use it directly\n\
+                         under a process supervisor.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta daemon run",
+                    )
+                    .with_help_formatting(),
+            )
+            .command(
+                command("stop")
+                    .about("Stop the daemon for this workspace")
+                    .help_description(
+                        "Stop the daemon for this workspace.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta daemon stop",
+                    )
+                    .with_help_formatting(),
+            )
+            .command(
+                command("status")
+                    .about("Show whether the daemon is running")
+                    .help_description(
+                        "Show whether the daemon is running, with its pid, uptime, the\n\
+                         number of requests served, and how many projects it has cached.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta daemon status",
+                    )
+                    .with_help_formatting(),
+            )
+            .handler("start", handle_start)
+            .handler("run", handle_run)
+            .handler("stop", handle_stop)
+            .handler("status", handle_status)
+            .build()
+    }
+}
+
+/// Handler for the start command
+fn handle_start(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    if is_running(&base_path) {
+        println!(
+            "{} Daemon already running for {}",
+            "ℹ".bright_black(),
+            base_path.display()
+        );
+        return Ok(());
+    }
+    if cfg!(not(unix)) {
+        return Err(anyhow::anyhow!("meta daemon is only supported on Unix"));
+    }
+
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.args(["daemon", "run"])
+        .current_dir(&base_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Detach from the terminal's process group so Ctrl-C in the shell
+        // that started it does not stop the daemon.
+        cmd.process_group(0);
+    }
+    cmd.spawn().context("Failed to start the daemon")?;
+
+    let deadline = Instant::now() + START_TIMEOUT;
+    while !is_running(&base_path) {
+        if Instant::now() >= deadline {
+            return Err(anyhow::anyhow!(
+                "The daemon did not start; run `meta daemon run` to see why"
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    println!("{} Daemon started for {}", "✓".green(), base_path.display());
+    Ok(())
+}
+
+/// Handler for the run command
+fn handle_run(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    println!(
+        "{} Serving {} on {}",
+        "ℹ".bright_black(),
+        base_path.display(),
+        socket_path(&base_path).display()
+    );
+    serve(&base_path)
+}
+
+/// Handler for the stop command
+fn handle_stop(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    if !is_running(&base_path) {
+        println!("{} No daemon running", "ℹ".bright_black());
+        return Ok(());
+    }
+    call(&base_path, "daemon.stop", Value::Null)?;
+    println!("{} Daemon stopped", "✓".green());
+    Ok(())
+}
+
+/// Handler for the status command
+fn handle_status(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    if !is_running(&base_path) {
+        println!("{} No daemon running", "ℹ".bright_black());
+        return Ok(());
+    }
+    let info = call(&base_path, "daemon.info", Value::Null)?;
+    println!("{} Daemon running", "✓".green());
+    println!("  {:<10} {}", "pid".bright_black(), info["pid"]);
+    println!("  {:<10} v{}", "api".bright_black(), info["api_version"]);
+    println!("  {:<10} {}s", "uptime".bright_black(), info["uptime_secs"]);
+    println!("  {:<10} {}", "requests".bright_black(), info["requests"]);
+    println!(
+        "  {:<10} {} project(s)",
+        "cached".bright_black(),
+        info["cached_projects"]
+    );
+    println!(
+        "  {:<10} {}",
+        "socket".bright_black(),
+        socket_path(&base_path).display()
+    );
+    Ok(())
+}
+
+impl MetaPlugin for DaemonPlugin {
+    fn name(&self) -> &str {
+        "daemon"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for DaemonPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Keep workspace git status warm in a background process")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for DaemonPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
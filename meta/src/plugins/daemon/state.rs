@@ -0,0 +1,845 @@
+//! What the daemon keeps between requests, and the API methods built on it.
+
+use anyhow::Result;
+use metarepo_core::MetaConfig;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Instant, SystemTime};
+
+use super::{
+    RpcError, RpcRequest, RpcResponse, API_VERSION, CACHE_TTL, EVENTS, INVALID_PARAMS,
+    INVALID_REQUEST, METHODS, METHOD_NOT_FOUND, PARSE_ERROR, SERVER_ERROR,
+};
+use crate::plugins::shared::state;
+use crate::plugins::status::{gather_one, RepoState, RepoStatus};
+use crate::plugins::worktree::{add_worktrees, list_worktrees, short_branch_name, WorktreeInfo};
+
+/// Directory, inside the state directory, holding `script.run` logs.
+const RUNS_DIR: &str = "daemon-runs";
+
+/// Where a connection's responses and notifications are written.
+pub type Sink = Box<dyn Write + Send>;
+
+/// Modification times of the files git rewrites when a repository's status
+/// can change: the index, HEAD, fetched refs, and branch refs.
+fn fingerprint(repo: &Path) -> Vec<Option<SystemTime>> {
+    let git_dir = repo.join(".git");
+    [
+        repo.to_path_buf(),
+        git_dir.join("index"),
+        git_dir.join("HEAD"),
+        git_dir.join("FETCH_HEAD"),
+        git_dir.join("packed-refs"),
+        git_dir.join("refs").join("heads"),
+    ]
+    .iter()
+    .map(|path| modified(path))
+    .collect()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// The worktrees of `repo` other than its main one.
+fn linked_worktrees(repo: &Path) -> Vec<WorktreeInfo> {
+    // `git worktree list` always puts the main worktree first.
+    list_worktrees(repo)
+        .unwrap_or_default()
+        .into_iter()
+        .skip(1)
+        .filter(|w| !w.is_bare)
+        .map(|mut w| {
+            w.branch = short_branch_name(&w.branch).to_string();
+            w
+        })
+        .collect()
+}
+
+fn invalid_params(e: impl std::fmt::Display) -> RpcError {
+    RpcError::new(INVALID_PARAMS, e.to_string())
+}
+
+fn server_error(e: impl std::fmt::Display) -> RpcError {
+    RpcError::new(SERVER_ERROR, e.to_string())
+}
+
+/// Decode `params`, treating absent params as all defaults.
+fn decode<T: for<'de> Deserialize<'de> + Default>(params: Value) -> Result<T, RpcError> {
+    if params.is_null() {
+        Ok(T::default())
+    } else {
+        serde_json::from_value(params).map_err(invalid_params)
+    }
+}
+
+/// Reject values that would be read as an option by the commands they are
+/// passed to.
+fn plain_name(label: &str, value: &str) -> Result<(), RpcError> {
+    if value.is_empty() || value.starts_with('-') {
+        return Err(invalid_params(format!("invalid {} '{}'", label, value)));
+    }
+    Ok(())
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StatusParams {
+    /// Projects to report; all enabled projects when omitted.
+    projects: Option<Vec<String>>,
+    /// Rescan instead of answering from the cache.
+    refresh: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TreeParams {
+    refresh: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SubscribeParams {
+    /// Events to receive; all of [`EVENTS`] when omitted.
+    events: Option<Vec<String>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RunParams {
+    project: String,
+    script: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RunIdParams {
+    id: u64,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorktreeParams {
+    project: String,
+    branch: String,
+    /// Start point for a branch that exists neither locally nor on a remote.
+    #[serde(default)]
+    from: Option<String>,
+}
+
+struct Cached {
+    fingerprint: Vec<Option<SystemTime>>,
+    at: Instant,
+    state: RepoState,
+}
+
+/// The workspace config, as of the file's last change.
+struct Workspace {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    config: MetaConfig,
+    /// Enabled projects that are not restricted, sorted.
+    projects: Vec<String>,
+}
+
+struct Connection {
+    sink: Sink,
+    /// Events the connection subscribed to.
+    events: Vec<String>,
+}
+
+/// A script started with `script.run`.
+struct Run {
+    project: String,
+    script: String,
+    child: Child,
+    log: PathBuf,
+    exit_code: Option<i32>,
+}
+
+/// Everything the daemon keeps between requests.
+pub struct DaemonState {
+    base_path: PathBuf,
+    /// The `meta` binary `script.run` invokes.
+    meta_exe: PathBuf,
+    started: Instant,
+    requests: u64,
+    cache: HashMap<String, Cached>,
+    workspace: Option<Workspace>,
+    connections: HashMap<u64, Connection>,
+    next_connection: u64,
+    runs: BTreeMap<u64, Run>,
+    next_run: u64,
+    /// Status last reported to `status.changed` subscribers.
+    reported: HashMap<String, RepoState>,
+}
+
+impl DaemonState {
+    pub fn new(base_path: PathBuf) -> Self {
+        Self {
+            base_path,
+            meta_exe: std::env::current_exe().unwrap_or_else(|_| PathBuf::from("meta")),
+            started: Instant::now(),
+            requests: 0,
+            cache: HashMap::new(),
+            workspace: None,
+            connections: HashMap::new(),
+            next_connection: 1,
+            runs: BTreeMap::new(),
+            next_run: 1,
+            reported: HashMap::new(),
+        }
+    }
+
+    /// Register a client connection; responses and events for it go to `sink`.
+    pub fn connect(&mut self, sink: Sink) -> u64 {
+        let id = self.next_connection;
+        self.next_connection += 1;
+        self.connections.insert(
+            id,
+            Connection {
+                sink,
+                events: Vec::new(),
+            },
+        );
+        id
+    }
+
+    pub fn disconnect(&mut self, connection: u64) {
+        self.connections.remove(&connection);
+    }
+
+    /// Handle one line from `connection` and write the response to it (none
+    /// for notifications). Returns true when the daemon should shut down.
+    pub fn receive(&mut self, connection: u64, line: &str) -> bool {
+        let (response, stop) = self.handle_line(connection, line);
+        if let Some(response) = response {
+            self.send(connection, &response);
+        }
+        stop
+    }
+
+    fn send(&mut self, connection: u64, line: &str) {
+        let failed = match self.connections.get_mut(&connection) {
+            Some(c) => writeln!(c.sink, "{}", line)
+                .and_then(|_| c.sink.flush())
+                .is_err(),
+            None => false,
+        };
+        if failed {
+            self.disconnect(connection);
+        }
+    }
+
+    /// Answer one line of input. Returns the response line (none for
+    /// notifications) and whether the daemon should shut down after sending
+    /// it.
+    fn handle_line(&mut self, connection: u64, line: &str) -> (Option<String>, bool) {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => return (Some(error_line(PARSE_ERROR, e.to_string())), false),
+        };
+        let request: RpcRequest = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => return (Some(error_line(INVALID_REQUEST, e.to_string())), false),
+        };
+        if request.jsonrpc != "2.0" {
+            return (
+                Some(error_line(INVALID_REQUEST, "jsonrpc must be \"2.0\"")),
+                false,
+            );
+        }
+        let stop = request.method == "daemon.stop";
+        let outcome = self.handle(connection, &request.method, request.params);
+        let line = request
+            .id
+            .map(|id| serde_json::to_string(&RpcResponse::new(id, outcome)).unwrap_or_default());
+        (line, stop)
+    }
+
+    /// Run one method for `connection`.
+    pub fn handle(
+        &mut self,
+        connection: u64,
+        method: &str,
+        params: Value,
+    ) -> std::result::Result<Value, RpcError> {
+        self.requests += 1;
+        match method {
+            "api.version" => Ok(json!({
+                "version": API_VERSION,
+                "methods": METHODS,
+                "events": EVENTS,
+            })),
+            "daemon.ping" => Ok(json!("pong")),
+            "daemon.info" => Ok(json!({
+                "api_version": API_VERSION,
+                "pid": std::process::id(),
+                "workspace": self.base_path.display().to_string(),
+                "uptime_secs": self.started.elapsed().as_secs(),
+                "requests": self.requests,
+                "cached_projects": self.cache.len(),
+                "connections": self.connections.len(),
+            })),
+            "daemon.stop" => Ok(json!("stopping")),
+            "workspace.projects" => self.projects().map(|names| json!(names)),
+            "workspace.tree" => {
+                let params: TreeParams = decode(params)?;
+                self.tree(params.refresh)
+            }
+            "status.get" => {
+                let params: StatusParams = decode(params)?;
+                let projects = match params.projects {
+                    Some(projects) => projects,
+                    None => self.projects()?,
+                };
+                self.status(&projects, params.refresh)
+                    .map(|statuses| json!(statuses))
+                    .map_err(invalid_params)
+            }
+            "events.subscribe" => {
+                let params: SubscribeParams = decode(params)?;
+                let events = params
+                    .events
+                    .unwrap_or_else(|| EVENTS.iter().map(|e| e.to_string()).collect());
+                if let Some(unknown) = events.iter().find(|e| !EVENTS.contains(&e.as_str())) {
+                    return Err(invalid_params(format!("unknown event '{}'", unknown)));
+                }
+                if events.iter().any(|e| e == "status.changed") && self.reported.is_empty() {
+                    // Report changes from now on, not the initial state.
+                    let projects = self.projects()?;
+                    for status in self.status(&projects, false).map_err(server_error)? {
+                        self.reported.insert(status.name, status.state);
+                    }
+                }
+                let Some(c) = self.connections.get_mut(&connection) else {
+                    return Err(server_error("connection is closed"));
+                };
+                c.events = events.clone();
+                Ok(json!({ "events": events }))
+            }
+            "events.unsubscribe" => {
+                if let Some(c) = self.connections.get_mut(&connection) {
+                    c.events.clear();
+                }
+                Ok(json!({ "events": [] }))
+            }
+            "script.run" => {
+                let params: RunParams = decode(params)?;
+                self.run_script(params)
+            }
+            "script.status" => {
+                let params: RunIdParams = decode(params)?;
+                let run = self
+                    .runs
+                    .get_mut(&params.id)
+                    .ok_or_else(|| invalid_params(format!("no run with id {}", params.id)))?;
+                if run.exit_code.is_none() {
+                    if let Ok(Some(status)) = run.child.try_wait() {
+                        run.exit_code = Some(status.code().unwrap_or(-1));
+                    }
+                }
+                Ok(json!({
+                    "id": params.id,
+                    "project": run.project,
+                    "script": run.script,
+                    "running": run.exit_code.is_none(),
+                    "exit_code": run.exit_code,
+                    "log": run.log.display().to_string(),
+                }))
+            }
+            "worktree.open" => {
+                let params: WorktreeParams = decode(params)?;
+                self.open_worktree(params)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method '{}'", method),
+            )),
+        }
+    }
+
+    /// The workspace config, re-read when the file changes.
+    fn workspace(&mut self) -> std::result::Result<&Workspace, RpcError> {
+        let found = MetaConfig::config_in_dir(&self.base_path).ok_or_else(|| {
+            server_error(format!(
+                "No metarepo config in {}",
+                self.base_path.display()
+            ))
+        })?;
+        let current = modified(&found.path);
+        let fresh = self
+            .workspace
+            .as_ref()
+            .is_some_and(|w| w.path == found.path && w.modified == current);
+        if !fresh {
+            let config = MetaConfig::load_resolved(&found.path).map_err(server_error)?;
+            // Restricted projects are kept from the API's clients, which are
+            // often AI agents.
+            let mut hidden = config.disabled_project_keys();
+            hidden.extend(config.restricted_project_keys());
+            let mut projects: Vec<String> = config
+                .projects
+                .keys()
+                .filter(|name| !hidden.contains(*name))
+                .cloned()
+                .collect();
+            projects.sort();
+            self.workspace = Some(Workspace {
+                path: found.path,
+                modified: current,
+                config,
+                projects,
+            });
+        }
+        Ok(self.workspace.as_ref().expect("workspace was just loaded"))
+    }
+
+    /// The workspace's enabled projects, leaving out restricted ones.
+    fn projects(&mut self) -> std::result::Result<Vec<String>, RpcError> {
+        Ok(self.workspace()?.projects.clone())
+    }
+
+    /// `project` if it is one of the workspace's [`projects`](Self::projects).
+    fn known_project(&mut self, project: &str) -> std::result::Result<(), RpcError> {
+        if self.projects()?.iter().any(|p| p == project) {
+            Ok(())
+        } else {
+            Err(invalid_params(format!(
+                "'{}' is not a project in this workspace",
+                project
+            )))
+        }
+    }
+
+    /// Status of each project, reusing cached entries that are still fresh.
+    fn status(&mut self, projects: &[String], refresh: bool) -> Result<Vec<RepoStatus>> {
+        let mut statuses = Vec::with_capacity(projects.len());
+        for name in projects {
+            metarepo_core::validate_path_segment("project", name)?;
+            let path = self.base_path.join(name);
+            let current = fingerprint(&path);
+            let fresh = !refresh
+                && self.cache.get(name).is_some_and(|cached| {
+                    cached.fingerprint == current && cached.at.elapsed() < CACHE_TTL
+                });
+            if !fresh {
+                self.cache.insert(
+                    name.clone(),
+                    Cached {
+                        fingerprint: current,
+                        at: Instant::now(),
+                        state: gather_one(&path),
+                    },
+                );
+            }
+            statuses.push(RepoStatus {
+                name: name.clone(),
+                state: self.cache[name].state.clone(),
+            });
+        }
+        Ok(statuses)
+    }
+
+    /// Every project with its path, URL, status, and extra worktrees.
+    fn tree(&mut self, refresh: bool) -> std::result::Result<Value, RpcError> {
+        let projects = self.projects()?;
+        let statuses = self.status(&projects, refresh).map_err(server_error)?;
+        let base = self.base_path.clone();
+        let workspace = self.workspace()?;
+        let entries: Vec<Value> = statuses
+            .into_iter()
+            .map(|status| {
+                let path = base.join(&status.name);
+                let worktrees: Vec<Value> = if matches!(status.state, RepoState::Ok { .. }) {
+                    linked_worktrees(&path)
+                        .into_iter()
+                        .map(|w| json!({ "branch": w.branch, "path": w.path }))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                json!({
+                    "name": status.name,
+                    "path": path,
+                    "url": workspace.config.get_project_url(&status.name),
+                    "state": status.state,
+                    "worktrees": worktrees,
+                })
+            })
+            .collect();
+        Ok(json!(entries))
+    }
+
+    /// Start `meta run <script> --project <project>` in the background, logging
+    /// to the state directory. Its exit is reported by `script.status` and the
+    /// `script.exited` event.
+    fn run_script(&mut self, params: RunParams) -> std::result::Result<Value, RpcError> {
+        self.known_project(&params.project)?;
+        plain_name("script", &params.script)?;
+
+        let id = self.next_run;
+        let dir = state::state_path(&self.base_path, RUNS_DIR);
+        std::fs::create_dir_all(&dir).map_err(server_error)?;
+        let log = dir.join(format!("{}.log", id));
+        let file = std::fs::File::create(&log).map_err(server_error)?;
+        let stderr = file.try_clone().map_err(server_error)?;
+        let child = Command::new(&self.meta_exe)
+            .args(["run", &params.script, "--project", &params.project])
+            .arg("--no-progress")
+            .current_dir(&self.base_path)
+            .stdin(Stdio::null())
+            .stdout(file)
+            .stderr(stderr)
+            .spawn()
+            .map_err(server_error)?;
+        self.next_run += 1;
+        let pid = child.id();
+        self.runs.insert(
+            id,
+            Run {
+                project: params.project,
+                script: params.script,
+                child,
+                log: log.clone(),
+                exit_code: None,
+            },
+        );
+        Ok(json!({ "id": id, "pid": pid, "log": log }))
+    }
+
+    /// The worktree of `project` on `branch`, creating it when there is none.
+    /// Never runs `worktree_init` hooks: nobody is there to approve them.
+    fn open_worktree(&mut self, params: WorktreeParams) -> std::result::Result<Value, RpcError> {
+        self.known_project(&params.project)?;
+        plain_name("branch", &params.branch)?;
+        metarepo_core::validate_path_segment("branch", &params.branch).map_err(invalid_params)?;
+        if let Some(from) = &params.from {
+            plain_name("start point", from)?;
+        }
+        let project_path = self.base_path.join(&params.project);
+        let find = |path: &Path| {
+            linked_worktrees(path)
+                .into_iter()
+                .find(|w| w.branch == params.branch)
+        };
+
+        let mut created = false;
+        if find(&project_path).is_none() {
+            let config = self.workspace()?.config.clone();
+            add_worktrees(
+                &params.branch,
+                std::slice::from_ref(&params.project),
+                &self.base_path,
+                None,
+                false,
+                Some(params.from.as_deref().unwrap_or("HEAD")),
+                true,
+                false,
+                None,
+                &config,
+            )
+            .map_err(server_error)?;
+            created = true;
+        }
+        let worktree = find(&project_path).ok_or_else(|| {
+            server_error(format!(
+                "Could not create a worktree for '{}' on {}",
+                params.project, params.branch
+            ))
+        })?;
+        Ok(json!({
+            "project": params.project,
+            "branch": params.branch,
+            "path": worktree.path,
+            "created": created,
+        }))
+    }
+
+    /// Check for finished scripts and changed status, notifying subscribers.
+    /// Called periodically by the server.
+    pub fn poll(&mut self) {
+        let mut exited = Vec::new();
+        for (id, run) in self.runs.iter_mut() {
+            if run.exit_code.is_some() {
+                continue;
+            }
+            if let Ok(Some(status)) = run.child.try_wait() {
+                let code = status.code().unwrap_or(-1);
+                run.exit_code = Some(code);
+                exited.push(json!({
+                    "id": id,
+                    "project": run.project,
+                    "script": run.script,
+                    "exit_code": code,
+                }));
+            }
+        }
+        for params in exited {
+            self.emit("script.exited", params);
+        }
+
+        let watching = self
+            .connections
+            .values()
+            .any(|c| c.events.iter().any(|e| e == "status.changed"));
+        if !watching {
+            // Nobody is watching: start afresh at the next subscription.
+            self.reported.clear();
+            return;
+        }
+        let Ok(projects) = self.projects() else {
+            return;
+        };
+        let Ok(statuses) = self.status(&projects, false) else {
+            return;
+        };
+        let changed: Vec<RepoStatus> = statuses
+            .into_iter()
+            .filter(|s| self.reported.get(&s.name) != Some(&s.state))
+            .collect();
+        if changed.is_empty() {
+            return;
+        }
+        for status in &changed {
+            self.reported
+                .insert(status.name.clone(), status.state.clone());
+        }
+        self.emit("status.changed", json!({ "projects": changed }));
+    }
+
+    /// Send a notification to every connection subscribed to `event`.
+    fn emit(&mut self, event: &str, params: Value) {
+        let line = json!({ "jsonrpc": "2.0", "method": event, "params": params }).to_string();
+        let subscribed: Vec<u64> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.events.iter().any(|e| e == event))
+            .map(|(id, _)| *id)
+            .collect();
+        for connection in subscribed {
+            self.send(connection, &line);
+        }
+    }
+}
+
+fn error_line(code: i64, message: impl Into<String>) -> String {
+    serde_json::to_string(&RpcResponse::new(
+        Value::Null,
+        Err(RpcError::new(code, message)),
+    ))
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    /// A sink tests can read back.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Buffer {
+        /// Lines written since the last call.
+        fn take(&self) -> Vec<Value> {
+            let bytes = std::mem::take(&mut *self.0.lock().unwrap());
+            String::from_utf8(bytes)
+                .unwrap()
+                .lines()
+                .map(|l| serde_json::from_str(l).unwrap())
+                .collect()
+        }
+    }
+
+    fn workspace(projects: &[&str]) -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let projects: serde_json::Map<String, Value> = projects
+            .iter()
+            .map(|p| {
+                (
+                    p.to_string(),
+                    json!(format!("git@github.com:acme/{}.git", p)),
+                )
+            })
+            .collect();
+        std::fs::write(
+            temp.path().join(".meta"),
+            json!({ "projects": projects }).to_string(),
+        )
+        .unwrap();
+        temp
+    }
+
+    #[test]
+    fn speaks_json_rpc() {
+        let temp = TempDir::new().unwrap();
+        let mut daemon = DaemonState::new(temp.path().to_path_buf());
+        let out = Buffer::default();
+        let conn = daemon.connect(Box::new(out.clone()));
+
+        daemon.receive(conn, r#"{"jsonrpc":"2.0","id":1,"method":"api.version"}"#);
+        daemon.receive(conn, r#"{"jsonrpc":"2.0","id":"a","method":"nope"}"#);
+        daemon.receive(conn, "{");
+        daemon.receive(conn, r#"{"jsonrpc":"1.0","id":2,"method":"daemon.ping"}"#);
+        // Notifications get no reply.
+        daemon.receive(conn, r#"{"jsonrpc":"2.0","method":"daemon.ping"}"#);
+        let responses = out.take();
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["version"], API_VERSION);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[3]["error"]["code"], INVALID_REQUEST);
+
+        assert!(daemon.receive(conn, r#"{"jsonrpc":"2.0","id":3,"method":"daemon.stop"}"#));
+    }
+
+    #[test]
+    fn status_is_cached_until_refreshed() {
+        let temp = TempDir::new().unwrap();
+        let mut daemon = DaemonState::new(temp.path().to_path_buf());
+        let status = |daemon: &mut DaemonState, refresh| {
+            let result = daemon
+                .handle(
+                    0,
+                    "status.get",
+                    json!({ "projects": ["api"], "refresh": refresh }),
+                )
+                .unwrap();
+            serde_json::from_value::<Vec<RepoStatus>>(result).unwrap()
+        };
+
+        assert_eq!(status(&mut daemon, false)[0].state, RepoState::Missing);
+        // The cached entry survives the directory appearing until the
+        // fingerprint check or a refresh notices.
+        std::fs::create_dir(temp.path().join("api")).unwrap();
+        daemon.cache.get_mut("api").unwrap().fingerprint = fingerprint(&temp.path().join("api"));
+        assert_eq!(status(&mut daemon, false)[0].state, RepoState::Missing);
+        assert_eq!(status(&mut daemon, true)[0].state, RepoState::NotGit);
+
+        let info = daemon.handle(0, "daemon.info", Value::Null).unwrap();
+        assert_eq!(info["requests"], 4);
+        assert_eq!(info["cached_projects"], 1);
+    }
+
+    #[test]
+    fn restricted_projects_are_left_out() {
+        let temp = workspace(&["web"]);
+        std::fs::write(
+            temp.path().join(".meta"),
+            json!({ "projects": {
+                "web": "git@github.com:acme/web.git",
+                "billing": { "url": "git@github.com:acme/billing.git", "visibility": "restricted" }
+            }})
+            .to_string(),
+        )
+        .unwrap();
+        let mut daemon = DaemonState::new(temp.path().to_path_buf());
+        assert_eq!(
+            daemon.handle(0, "workspace.projects", Value::Null).unwrap(),
+            json!(["web"])
+        );
+        let tree = daemon.handle(0, "workspace.tree", Value::Null).unwrap();
+        assert!(!tree.to_string().contains("billing"), "{}", tree);
+        let statuses = daemon.handle(0, "status.get", Value::Null).unwrap();
+        assert_eq!(statuses.as_array().unwrap().len(), 1);
+        let run = daemon.handle(
+            0,
+            "script.run",
+            json!({ "project": "billing", "script": "test" }),
+        );
+        assert_eq!(run.unwrap_err().code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn workspace_methods_follow_the_config() {
+        let temp = workspace(&["web", "api"]);
+        let mut daemon = DaemonState::new(temp.path().to_path_buf());
+        assert_eq!(
+            daemon.handle(0, "workspace.projects", Value::Null).unwrap(),
+            json!(["api", "web"])
+        );
+        let statuses = daemon.handle(0, "status.get", Value::Null).unwrap();
+        assert_eq!(statuses.as_array().unwrap().len(), 2);
+
+        let tree = daemon.handle(0, "workspace.tree", Value::Null).unwrap();
+        assert_eq!(tree[0]["name"], "api");
+        assert_eq!(tree[0]["url"], "git@github.com:acme/api.git");
+        assert_eq!(tree[0]["state"], "Missing");
+        assert_eq!(tree[0]["worktrees"], json!([]));
+
+        let bad = daemon.handle(0, "status.get", json!({ "projects": ["../elsewhere"] }));
+        assert_eq!(bad.unwrap_err().code, INVALID_PARAMS);
+        let typo = daemon.handle(0, "status.get", json!({ "project": ["api"] }));
+        assert_eq!(typo.unwrap_err().code, INVALID_PARAMS);
+        let unknown = daemon.handle(
+            0,
+            "script.run",
+            json!({ "project": "billing", "script": "test" }),
+        );
+        assert_eq!(unknown.unwrap_err().code, INVALID_PARAMS);
+        let option = daemon.handle(
+            0,
+            "worktree.open",
+            json!({ "project": "api", "branch": "--force" }),
+        );
+        assert_eq!(option.unwrap_err().code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn subscribers_are_told_about_status_changes() {
+        let temp = workspace(&["api"]);
+        let mut daemon = DaemonState::new(temp.path().to_path_buf());
+        let watcher = Buffer::default();
+        let bystander = Buffer::default();
+        let conn = daemon.connect(Box::new(watcher.clone()));
+        daemon.connect(Box::new(bystander.clone()));
+
+        daemon.receive(
+            conn,
+            &json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "events.subscribe",
+                "params": { "events": ["status.changed"] },
+            })
+            .to_string(),
+        );
+        assert_eq!(
+            watcher.take()[0]["result"]["events"],
+            json!(["status.changed"])
+        );
+
+        // Nothing changed yet: no event.
+        daemon.poll();
+        assert!(watcher.take().is_empty());
+
+        std::fs::create_dir(temp.path().join("api")).unwrap();
+        daemon.poll();
+        let events = watcher.take();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["method"], "status.changed");
+        assert_eq!(events[0]["params"]["projects"][0]["name"], "api");
+        assert_eq!(events[0]["params"]["projects"][0]["state"], "NotGit");
+        assert!(bystander.take().is_empty());
+
+        let wrong = daemon.handle(conn, "events.subscribe", json!({ "events": ["nope"] }));
+        assert_eq!(wrong.unwrap_err().code, INVALID_PARAMS);
+    }
+}
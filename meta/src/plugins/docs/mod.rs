@@ -0,0 +1,279 @@
+//! `meta docs badges` — keep a standard badge block at the top of every
+//! project's README.
+//!
+//! The badges come from the `badges` block in `.meta` and are written between
+//! a pair of HTML comments, so reruns replace them in place and nothing else
+//! in the README is touched:
+//!
+//! ```text
+//! <!-- meta:badges -->
+//! [![build](…)](…) [![meta workspace](…)](…)
+//! <!-- /meta:badges -->
+//! ```
+
+use anyhow::Result;
+use colored::*;
+use metarepo_core::{canonical_repo_url, BadgeSettings, MetaConfig};
+use std::path::Path;
+
+pub use self::plugin::DocsPlugin;
+
+mod plugin;
+
+/// Opening marker of the badge block.
+pub const BADGES_BEGIN: &str = "<!-- meta:badges -->";
+/// Closing marker of the badge block.
+pub const BADGES_END: &str = "<!-- /meta:badges -->";
+
+const DEFAULT_README: &str = "README.md";
+const WORKSPACE_BADGE: &str = "https://img.shields.io/badge/meta-workspace-blue";
+
+/// Fill the `{project}`, `{name}`, `{repo}`, and `{url}` placeholders for
+/// `project`. `None` when the template needs a remote the project lacks.
+fn expand(template: &str, project: &str, remote: Option<&str>) -> Option<String> {
+    let name = project.rsplit('/').next().unwrap_or(project);
+    let mut out = template
+        .replace("{project}", project)
+        .replace("{name}", name);
+    if out.contains("{repo}") || out.contains("{url}") {
+        let remote = remote?;
+        let repo = remote.split_once('/').map_or(remote, |(_, path)| path);
+        out = out
+            .replace("{repo}", repo)
+            .replace("{url}", &format!("https://{}", remote));
+    }
+    Some(out)
+}
+
+/// The badge line for `project`, or `None` when no badge applies.
+pub fn badge_line(settings: &BadgeSettings, config: &MetaConfig, project: &str) -> Option<String> {
+    let remote = config
+        .get_project_url(project)
+        .and_then(|url| canonical_repo_url(&url))
+        // Plain filesystem paths have no web page.
+        .filter(|remote| !remote.starts_with('/') && remote.contains('/'));
+    let link = remote.as_ref().map(|r| format!("https://{}", r));
+
+    let mut badges = Vec::new();
+    for (label, template) in [("build", &settings.build), ("coverage", &settings.coverage)] {
+        let (Some(template), Some(link)) = (template, &link) else {
+            continue;
+        };
+        if let Some(image) = expand(template, project, remote.as_deref()) {
+            badges.push(format!("[![{}]({})]({})", label, image, link));
+        }
+    }
+    if let Some(workspace) = settings
+        .workspace
+        .as_deref()
+        .and_then(|w| expand(w, project, remote.as_deref()))
+    {
+        badges.push(format!(
+            "[![meta workspace]({})]({})",
+            WORKSPACE_BADGE, workspace
+        ));
+    }
+    (!badges.is_empty()).then(|| badges.join(" "))
+}
+
+/// `content` with its badge block set to `line`. A README without a block
+/// gets one under its title (a leading `# ` heading), or at the top.
+pub fn render_readme(content: &str, line: &str) -> String {
+    let block = format!("{}\n{}\n{}", BADGES_BEGIN, line, BADGES_END);
+    let lines: Vec<&str> = content.lines().collect();
+
+    let begin = lines.iter().position(|l| l.trim() == BADGES_BEGIN);
+    let end = begin.and_then(|b| {
+        lines[b + 1..]
+            .iter()
+            .position(|l| l.trim() == BADGES_END)
+            .map(|i| b + 1 + i)
+    });
+    let mut out: Vec<String> = Vec::new();
+    match (begin, end) {
+        (Some(begin), Some(end)) => {
+            out.extend(lines[..begin].iter().map(|l| l.to_string()));
+            out.push(block);
+            out.extend(lines[end + 1..].iter().map(|l| l.to_string()));
+        }
+        _ => {
+            let title = lines
+                .iter()
+                .position(|l| !l.trim().is_empty())
+                .filter(|&i| lines[i].starts_with("# "));
+            let split = title.map_or(0, |i| i + 1);
+            out.extend(lines[..split].iter().map(|l| l.to_string()));
+            if split > 0 {
+                out.push(String::new());
+            }
+            out.push(block);
+            let rest = &lines[split..];
+            let rest = match rest.iter().position(|l| !l.trim().is_empty()) {
+                Some(i) => &rest[i..],
+                None => &[],
+            };
+            if !rest.is_empty() {
+                out.push(String::new());
+                out.extend(rest.iter().map(|l| l.to_string()));
+            }
+        }
+    }
+    out.join("\n") + "\n"
+}
+
+/// Update the badge block in the README of each of `projects`. With `check`,
+/// nothing is written and an error is returned if any README is out of date.
+pub fn update_badges(
+    base_path: &Path,
+    config: &MetaConfig,
+    projects: &[String],
+    check: bool,
+) -> Result<()> {
+    let settings = config.badges.clone().unwrap_or_default();
+    if settings.build.is_none() && settings.coverage.is_none() && settings.workspace.is_none() {
+        return Err(anyhow::anyhow!(
+            "No badges configured. Add a \"badges\" block (build, coverage, workspace) to .meta"
+        ));
+    }
+    let readme_name = settings.readme.as_deref().unwrap_or(DEFAULT_README);
+
+    println!(
+        "\n  {} {}",
+        "🏷".bright_blue(),
+        if check {
+            "Checking README badges".bold()
+        } else {
+            "Updating README badges".bold()
+        }
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+
+    let mut projects = projects.to_vec();
+    projects.sort();
+    let (mut changed, mut current) = (0, 0);
+    for name in &projects {
+        let readme = base_path.join(name).join(readme_name);
+        let skip = |reason: &str| {
+            println!(
+                "  {} {} {}",
+                "⏭".yellow(),
+                name.bright_white(),
+                format!("({})", reason).yellow()
+            );
+        };
+        let Some(line) = badge_line(&settings, config, name) else {
+            skip("no badges apply");
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(&readme) else {
+            skip(&format!("no {}", readme_name));
+            continue;
+        };
+
+        let updated = render_readme(&content, &line);
+        if updated == content {
+            current += 1;
+            println!(
+                "  {} {} {}",
+                "✓".green(),
+                name.bright_white(),
+                "up to date".bright_black()
+            );
+            continue;
+        }
+        changed += 1;
+        if check {
+            println!(
+                "  {} {} {}",
+                "✗".red(),
+                name.bright_white(),
+                "badges out of date".red()
+            );
+        } else {
+            std::fs::write(&readme, updated)?;
+            println!(
+                "  {} {} {}",
+                "✓".green(),
+                name.bright_white(),
+                "updated".green()
+            );
+        }
+    }
+
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} {}, {} up to date",
+        "Summary:".bright_black(),
+        changed.to_string().yellow(),
+        if check { "out of date" } else { "updated" },
+        current.to_string().green()
+    );
+    println!();
+
+    if check && changed > 0 {
+        return Err(anyhow::anyhow!(
+            "{} README(s) need badge updates; run 'meta docs badges'",
+            changed
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ProjectEntry;
+
+    fn settings() -> BadgeSettings {
+        BadgeSettings {
+            build: Some("https://github.com/{repo}/actions/workflows/ci.yml/badge.svg".into()),
+            coverage: None,
+            workspace: Some("https://github.com/acme/meta".into()),
+            readme: None,
+        }
+    }
+
+    #[test]
+    fn badge_lines_follow_the_remote() {
+        let mut config = MetaConfig::default();
+        config.projects.insert(
+            "services/api".into(),
+            ProjectEntry::Url("git@github.com:acme/api.git".into()),
+        );
+        config
+            .projects
+            .insert("scratch".into(), ProjectEntry::Url("local:scratch".into()));
+
+        assert_eq!(
+            badge_line(&settings(), &config, "services/api").unwrap(),
+            "[![build](https://github.com/acme/api/actions/workflows/ci.yml/badge.svg)]\
+             (https://github.com/acme/api) \
+             [![meta workspace](https://img.shields.io/badge/meta-workspace-blue)]\
+             (https://github.com/acme/meta)"
+        );
+        // Without a remote only the workspace badge applies.
+        assert_eq!(
+            badge_line(&settings(), &config, "scratch").unwrap(),
+            "[![meta workspace](https://img.shields.io/badge/meta-workspace-blue)](https://github.com/acme/meta)"
+        );
+    }
+
+    #[test]
+    fn readme_block_goes_under_the_title_and_is_replaced_in_place() {
+        let readme = "# API\n\nThe API service.\n";
+        let once = render_readme(readme, "[![a](b)](c)");
+        assert_eq!(
+            once,
+            "# API\n\n<!-- meta:badges -->\n[![a](b)](c)\n<!-- /meta:badges -->\n\nThe API service.\n"
+        );
+        let twice = render_readme(&once, "[![x](y)](z)");
+        assert_eq!(twice, once.replace("[![a](b)](c)", "[![x](y)](z)"));
+        assert_eq!(render_readme(&twice, "[![x](y)](z)"), twice);
+
+        // No title: the block goes first.
+        assert_eq!(
+            render_readme("Just text\n", "B"),
+            "<!-- meta:badges -->\nB\n<!-- /meta:badges -->\n\nJust text\n"
+        );
+    }
+}
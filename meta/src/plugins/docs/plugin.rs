@@ -0,0 +1,169 @@
+use super::update_badges;
+use crate::plugins::shared::selection;
+use anyhow::Result;
+use clap::ArgMatches;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+
+/// DocsPlugin for keeping project documentation consistent
+pub struct DocsPlugin;
+
+impl DocsPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("docs")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Keep project documentation consistent across the workspace")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Keep project documentation consistent across the workspace.\n\
+                 \n\
+                 `meta docs badges` maintains a standard badge block in every project's\n\
+                 README from the workspace's badges configuration.",
+            )
+            .command(
+                command("badges")
+                    .about("Insert or update the badge block in each project's README")
+                    .help_description(
+                        "Insert or update a standard badge block in each project's README.\n\
+                         \n\
+                         Badges come from the badges block in .meta: build and coverage are\n\
+                         image URLs, and workspace is a link back to the meta repository.\n\
+                         Values may use {project}, {name}, {repo} (the remote's owner/name),\n\
+                         and {url} (its web page). Projects without a remote only get the\n\
+                         workspace badge. The block sits between <!-- meta:badges --> markers:\n\
+                         it is replaced in place on later runs, or added under the README's\n\
+                         title the first time. Set badges.readme for a file other than\n\
+                         README.md.\n\
+                         \n\
+                         Name projects (or globs) to update only those. --check writes nothing\n\
+                         and exits non-zero when a README is out of date, for CI.\n\
+                         \n\
+                         Example configuration:\n\
+                         \n\
+                           \"badges\": {\n\
+                             \"build\": \"https://github.com/{repo}/actions/workflows/ci.yml/badge.svg\",\n\
+                             \"workspace\": \"https://github.com/acme/meta\"\n\
+                           }\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta docs badges\n\
+                           meta docs badges --check\n\
+                           meta docs badges 'services/*'",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to update (names, aliases, or globs)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("check")
+                            .long("check")
+                            .help("Report out-of-date READMEs without writing; fail if any"),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    ),
+            )
+            .handler("badges", handle_badges)
+            .build()
+    }
+}
+
+/// Handler for the badges command
+fn handle_badges(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let projects = match selection::explicit_projects(matches, config)? {
+        Some(mut selected) => {
+            selection::apply_excludes(matches, config, &mut selected, true);
+            selected
+        }
+        None => selection::all_projects(matches, config),
+    };
+    update_badges(
+        &base_path,
+        &config.meta_config,
+        &projects,
+        matches.get_flag("check"),
+    )
+}
+
+impl MetaPlugin for DocsPlugin {
+    fn name(&self) -> &str {
+        "docs"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+
+    fn settings(&self) -> Vec<metarepo_core::ConfigSetting> {
+        use metarepo_core::{ConfigSetting, ConfigValueType};
+        vec![
+            ConfigSetting::new(
+                "badges.build",
+                "Build status badge image URL for `meta docs badges`",
+                ConfigValueType::String,
+            ),
+            ConfigSetting::new(
+                "badges.coverage",
+                "Coverage badge image URL for `meta docs badges`",
+                ConfigValueType::String,
+            ),
+            ConfigSetting::new(
+                "badges.workspace",
+                "Link to the meta workspace, shown as a badge in each README",
+                ConfigValueType::String,
+            ),
+            ConfigSetting::new(
+                "badges.readme",
+                "README file `meta docs badges` updates in each project",
+                ConfigValueType::String,
+            )
+            .with_default("README.md"),
+        ]
+    }
+}
+
+impl BasePlugin for DocsPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Keep project documentation consistent across the workspace")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for DocsPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,516 @@
+//! `meta doctor` — a single workspace health score with a recorded trend.
+//!
+//! The score starts at 100 and loses up to a fixed weight per category, in
+//! proportion to how much of the workspace the category affects:
+//!
+//! | Category        | Weight | Measured as                                  |
+//! |-----------------|--------|----------------------------------------------|
+//! | Missing clones  | 30     | enabled projects not cloned / projects       |
+//! | Drift           | 20     | `meta project check` findings / projects     |
+//! | Rule violations | 30     | projects with error or warning violations    |
+//! | Stale branches  | 20     | projects with a local branch idle N+ days    |
+//!
+//! Every run appends the result to `.meta-state/doctor-history.json` so
+//! `meta doctor --history` can show how the workspace is trending.
+
+use anyhow::Result;
+use colored::*;
+use metarepo_core::MetaConfig;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::plugins::project::workspace_drift;
+use crate::plugins::rules::{load_rules_config, RuleEngine, RulesConfig, Severity};
+use crate::plugins::shared::state;
+
+pub use self::plugin::DoctorPlugin;
+
+mod plugin;
+
+/// State file holding past health records.
+pub const HISTORY_FILE: &str = "doctor-history.json";
+/// Number of records kept in the history; older ones are dropped.
+pub const HISTORY_LIMIT: usize = 200;
+/// A local branch whose tip is older than this many days counts as stale.
+pub const DEFAULT_STALE_DAYS: u64 = 90;
+
+const WEIGHT_MISSING: f64 = 30.0;
+const WEIGHT_DRIFT: f64 = 20.0;
+const WEIGHT_VIOLATIONS: f64 = 30.0;
+const WEIGHT_STALE: f64 = 20.0;
+
+/// One health measurement of the workspace.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealthRecord {
+    /// Unix seconds when the measurement was taken.
+    pub timestamp: u64,
+    pub score: u32,
+    /// Projects in the config (disabled ones excluded).
+    pub projects: usize,
+    pub missing_clones: usize,
+    pub drift: usize,
+    pub violations: usize,
+    pub projects_with_violations: usize,
+    pub stale_branches: usize,
+    pub projects_with_stale_branches: usize,
+}
+
+/// Recorded health measurements, oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HealthHistory {
+    pub records: Vec<HealthRecord>,
+}
+
+/// Deduction for one category: `weight` scaled by the affected share of the
+/// workspace, capped at the full weight.
+fn deduction(weight: f64, affected: usize, projects: usize) -> f64 {
+    if affected == 0 {
+        return 0.0;
+    }
+    weight * (affected as f64 / projects.max(1) as f64).min(1.0)
+}
+
+impl HealthRecord {
+    /// The per-category deductions, in table order.
+    pub fn deductions(&self) -> [f64; 4] {
+        [
+            deduction(WEIGHT_MISSING, self.missing_clones, self.projects),
+            deduction(WEIGHT_DRIFT, self.drift, self.projects),
+            deduction(
+                WEIGHT_VIOLATIONS,
+                self.projects_with_violations,
+                self.projects,
+            ),
+            deduction(
+                WEIGHT_STALE,
+                self.projects_with_stale_branches,
+                self.projects,
+            ),
+        ]
+    }
+
+    /// Recompute `score` from the counts.
+    pub fn with_score(mut self) -> Self {
+        let lost: f64 = self.deductions().iter().sum();
+        self.score = (100.0 - lost).round().clamp(0.0, 100.0) as u32;
+        self
+    }
+}
+
+/// Per-project findings gathered in parallel.
+#[derive(Debug, Default, Clone)]
+struct ProjectFindings {
+    violations: usize,
+    stale_branches: usize,
+}
+
+/// Rules for a project: its own `.rules.yaml`, else the workspace one. `None`
+/// when neither exists, so unconfigured workspaces are not scored on rules.
+fn rules_for(project_path: &Path, workspace_rules: Option<&RulesConfig>) -> Option<RulesConfig> {
+    let own = project_path.join(".rules.yaml");
+    if own.exists() {
+        if let Ok(config) = load_rules_config(&own) {
+            return Some(config);
+        }
+    }
+    workspace_rules.cloned()
+}
+
+/// Local branches (other than the checked-out one) whose tip commit is older
+/// than `cutoff` (Unix seconds).
+pub fn stale_branch_count(project_path: &Path, cutoff: i64) -> usize {
+    let Ok(repo) = git2::Repository::open(project_path) else {
+        return 0;
+    };
+    let Ok(branches) = repo.branches(Some(git2::BranchType::Local)) else {
+        return 0;
+    };
+    branches
+        .filter_map(|b| b.ok())
+        .filter(|(branch, _)| !branch.is_head())
+        .filter_map(|(branch, _)| branch.get().peel_to_commit().ok())
+        .filter(|commit| commit.time().seconds() < cutoff)
+        .count()
+}
+
+fn inspect_project(
+    project_path: &Path,
+    workspace_rules: Option<&RulesConfig>,
+    cutoff: i64,
+) -> ProjectFindings {
+    let violations = rules_for(project_path, workspace_rules)
+        .and_then(|rules| RuleEngine::new(rules).validate(project_path).ok())
+        .map(|found| {
+            found
+                .iter()
+                .filter(|v| matches!(v.severity, Severity::Error | Severity::Warning))
+                .count()
+        })
+        .unwrap_or(0);
+    ProjectFindings {
+        violations,
+        stale_branches: stale_branch_count(project_path, cutoff),
+    }
+}
+
+/// Everything `meta doctor` found, including the names behind the counts.
+#[derive(Debug, Default)]
+pub struct HealthReport {
+    pub record: HealthRecord,
+    pub missing: Vec<String>,
+    pub violating: Vec<String>,
+    pub stale: Vec<String>,
+}
+
+/// Measure the health of the whole workspace. Directory scope does not apply:
+/// the score describes the workspace, not the current subtree.
+pub fn measure_health(
+    base_path: &Path,
+    config: &MetaConfig,
+    stale_days: u64,
+) -> Result<HealthReport> {
+    let disabled = config.disabled_project_keys();
+    let mut projects: Vec<String> = config
+        .projects
+        .keys()
+        .filter(|key| !disabled.contains(*key))
+        .cloned()
+        .collect();
+    projects.sort();
+
+    let (present, missing): (Vec<String>, Vec<String>) = projects
+        .iter()
+        .cloned()
+        .partition(|name| base_path.join(name).exists());
+
+    let drift = workspace_drift(base_path, config)?;
+    // Missing directories are scored as missing clones, not again as drift.
+    let drift_count = drift.total() - drift.missing_dirs.len();
+
+    let workspace_rules_path = base_path.join(".rules.yaml");
+    let workspace_rules = if workspace_rules_path.exists() {
+        Some(load_rules_config(&workspace_rules_path)?)
+    } else {
+        None
+    };
+    let cutoff = state::now_secs().saturating_sub(stale_days * 86_400) as i64;
+
+    let findings: Mutex<Vec<ProjectFindings>> =
+        Mutex::new(vec![ProjectFindings::default(); present.len()]);
+    let next = AtomicUsize::new(0);
+    let workers = config.worker_count().min(present.len().max(1));
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(name) = present.get(i) else {
+                    break;
+                };
+                let found =
+                    inspect_project(&base_path.join(name), workspace_rules.as_ref(), cutoff);
+                findings.lock().unwrap()[i] = found;
+            });
+        }
+    });
+    let findings = findings.into_inner().unwrap();
+
+    let violating: Vec<String> = present
+        .iter()
+        .zip(&findings)
+        .filter(|(_, f)| f.violations > 0)
+        .map(|(name, _)| name.clone())
+        .collect();
+    let stale: Vec<String> = present
+        .iter()
+        .zip(&findings)
+        .filter(|(_, f)| f.stale_branches > 0)
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    let record = HealthRecord {
+        timestamp: state::now_secs(),
+        score: 0,
+        projects: projects.len(),
+        missing_clones: missing.len(),
+        drift: drift_count,
+        violations: findings.iter().map(|f| f.violations).sum(),
+        projects_with_violations: violating.len(),
+        stale_branches: findings.iter().map(|f| f.stale_branches).sum(),
+        projects_with_stale_branches: stale.len(),
+    }
+    .with_score();
+
+    Ok(HealthReport {
+        record,
+        missing,
+        violating,
+        stale,
+    })
+}
+
+/// Append `record` to the workspace history, keeping the newest
+/// [`HISTORY_LIMIT`] records. Returns the previous record, if any.
+pub fn record_health(base_path: &Path, record: &HealthRecord) -> Result<Option<HealthRecord>> {
+    let mut history: HealthHistory = state::load(base_path, HISTORY_FILE)?;
+    let previous = history.records.last().cloned();
+    history.records.push(record.clone());
+    if history.records.len() > HISTORY_LIMIT {
+        let excess = history.records.len() - HISTORY_LIMIT;
+        history.records.drain(..excess);
+    }
+    state::save(base_path, HISTORY_FILE, &history)?;
+    Ok(previous)
+}
+
+fn colored_score(score: u32) -> ColoredString {
+    let text = format!("{}/100", score);
+    match score {
+        80.. => text.green().bold(),
+        50..=79 => text.yellow().bold(),
+        _ => text.red().bold(),
+    }
+}
+
+/// A score change, right-aligned to `width` before coloring.
+fn colored_delta(delta: i64, width: usize) -> ColoredString {
+    let text = match delta {
+        d if d > 0 => format!("{:>width$}", format!("+{}", d)),
+        d if d < 0 => format!("{:>width$}", d),
+        _ => format!("{:>width$}", "±0"),
+    };
+    match delta {
+        d if d > 0 => text.green(),
+        d if d < 0 => text.red(),
+        _ => text.bright_black(),
+    }
+}
+
+/// Print a health report, compared against `previous` when there is one.
+pub fn print_health(report: &HealthReport, previous: Option<&HealthRecord>) {
+    let record = &report.record;
+    println!("\n  {} {}", "🩺".cyan(), "Workspace health".bold());
+    println!("  {}", "═".repeat(60).bright_black());
+    match previous {
+        Some(prev) => println!(
+            "  {}  {}  ({} since {})",
+            "Score".bold(),
+            colored_score(record.score),
+            colored_delta(record.score as i64 - prev.score as i64, 0),
+            state::format_timestamp(prev.timestamp)
+        ),
+        None => println!("  {}  {}", "Score".bold(), colored_score(record.score)),
+    }
+    println!("  {}", "─".repeat(60).bright_black());
+
+    let [missing, drift, violations, stale] = record.deductions();
+    let rows = [
+        (
+            "Missing clones",
+            format!("{} of {} projects", record.missing_clones, record.projects),
+            missing,
+        ),
+        ("Drift", format!("{} issue(s)", record.drift), drift),
+        (
+            "Rule violations",
+            format!(
+                "{} in {} project(s)",
+                record.violations, record.projects_with_violations
+            ),
+            violations,
+        ),
+        (
+            "Stale branches",
+            format!(
+                "{} in {} project(s)",
+                record.stale_branches, record.projects_with_stale_branches
+            ),
+            stale,
+        ),
+    ];
+    for (label, detail, lost) in rows {
+        let mark = if lost > 0.0 {
+            "!".yellow()
+        } else {
+            "✓".green()
+        };
+        let penalty = format!("{:>6}", format!("-{:.0}", lost));
+        println!(
+            "  {} {:<16} {:<26} {}",
+            mark,
+            label,
+            detail,
+            if lost > 0.0 {
+                penalty.yellow()
+            } else {
+                penalty.bright_black()
+            }
+        );
+    }
+
+    let hints = [
+        (&report.missing, "meta git update"),
+        (&report.violating, "meta rules check"),
+        (&report.stale, "git branch -d"),
+    ];
+    let mut printed_hint = false;
+    for (names, fix) in hints {
+        if names.is_empty() {
+            continue;
+        }
+        if !printed_hint {
+            println!();
+            printed_hint = true;
+        }
+        let shown: Vec<&str> = names.iter().take(5).map(|s| s.as_str()).collect();
+        let more = names.len().saturating_sub(shown.len());
+        let mut list = shown.join(", ");
+        if more > 0 {
+            list.push_str(&format!(" and {} more", more));
+        }
+        println!(
+            "  {} {} {}",
+            "└".bright_black(),
+            list,
+            format!("({})", fix).bright_black()
+        );
+    }
+    if record.drift > 0 {
+        if !printed_hint {
+            println!();
+        }
+        println!(
+            "  {} {}",
+            "└".bright_black(),
+            "run meta project check for drift details".bright_black()
+        );
+    }
+    println!();
+}
+
+/// Print the recorded scores, oldest first, with the change between runs.
+pub fn print_history(history: &HealthHistory, limit: usize) {
+    if history.records.is_empty() {
+        println!(
+            "\n{}",
+            "No health history yet. Run meta doctor to record a score.".dimmed()
+        );
+        return;
+    }
+    let start = history.records.len().saturating_sub(limit);
+    let shown = &history.records[start..];
+
+    println!("\n  {} {}", "📈".cyan(), "Workspace health history".bold());
+    println!("  {}", "═".repeat(60).bright_black());
+    println!(
+        "  {}",
+        format!(
+            "{:<16}  {:>7}  {:>5}  {:>7}  {:>5}  {:>5}  {:>5}",
+            "Date", "Score", "Δ", "Missing", "Drift", "Rules", "Stale"
+        )
+        .bright_black()
+    );
+    let mut prev_score = start
+        .checked_sub(1)
+        .map(|i| history.records[i].score as i64);
+    for record in shown {
+        let delta = match prev_score {
+            Some(prev) => colored_delta(record.score as i64 - prev, 5),
+            None => " ".repeat(5).normal(),
+        };
+        println!(
+            "  {:<16}  {}  {}  {:>7}  {:>5}  {:>5}  {:>5}",
+            state::format_timestamp(record.timestamp),
+            format!("{:>7}", record.score).bold(),
+            delta,
+            record.missing_clones,
+            record.drift,
+            record.violations,
+            record.stale_branches
+        );
+        prev_score = Some(record.score as i64);
+    }
+
+    if let (Some(first), Some(last)) = (shown.first(), shown.last()) {
+        if shown.len() > 1 {
+            println!("  {}", "─".repeat(60).bright_black());
+            println!(
+                "  Trend: {} → {} ({}) over {} runs",
+                first.score,
+                last.score,
+                colored_delta(last.score as i64 - first.score as i64, 0),
+                shown.len()
+            );
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(projects: usize) -> HealthRecord {
+        HealthRecord {
+            projects,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn clean_workspace_scores_100() {
+        assert_eq!(record(5).with_score().score, 100);
+        // An empty workspace has nothing wrong with it either.
+        assert_eq!(record(0).with_score().score, 100);
+    }
+
+    #[test]
+    fn deductions_scale_with_affected_share() {
+        let mut r = record(10);
+        r.missing_clones = 5; // half of 30
+        r.projects_with_violations = 10; // all of 30
+        assert_eq!(r.clone().with_score().score, 55);
+
+        // Drift findings can outnumber projects; the deduction caps at its weight.
+        r.drift = 50;
+        assert_eq!(r.with_score().score, 35);
+    }
+
+    #[test]
+    fn history_keeps_newest_records() {
+        let tmp = tempfile::tempdir().unwrap();
+        for score in 0..(HISTORY_LIMIT as u32 + 3) {
+            let r = HealthRecord {
+                score,
+                ..Default::default()
+            };
+            record_health(tmp.path(), &r).unwrap();
+        }
+        let history: HealthHistory = state::load(tmp.path(), HISTORY_FILE).unwrap();
+        assert_eq!(history.records.len(), HISTORY_LIMIT);
+        assert_eq!(history.records.first().unwrap().score, 3);
+
+        let previous = record_health(tmp.path(), &HealthRecord::default()).unwrap();
+        assert_eq!(previous.unwrap().score, HISTORY_LIMIT as u32 + 2);
+    }
+
+    #[test]
+    fn measure_counts_missing_clones_and_skips_disabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("present")).unwrap();
+        let mut config = MetaConfig::default();
+        for name in ["present", "missing", "off"] {
+            config.projects.insert(
+                name.to_string(),
+                metarepo_core::ProjectEntry::Url("local:".to_string()),
+            );
+        }
+        config.disabled = vec!["off".to_string()];
+
+        let report = measure_health(tmp.path(), &config, DEFAULT_STALE_DAYS).unwrap();
+        assert_eq!(report.record.projects, 2);
+        assert_eq!(report.missing, vec!["missing".to_string()]);
+        assert_eq!(report.record.score, 85);
+    }
+}
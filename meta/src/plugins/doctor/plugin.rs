@@ -0,0 +1,154 @@
+//! Plugin wiring for `meta doctor`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{
+    measure_health, print_health, print_history, record_health, HealthHistory, DEFAULT_STALE_DAYS,
+    HISTORY_FILE,
+};
+use crate::plugins::shared::state;
+
+/// Registers the top-level `meta doctor` command.
+pub struct DoctorPlugin;
+
+impl DoctorPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DoctorPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for DoctorPlugin {
+    fn name(&self) -> &str {
+        "doctor"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("doctor")
+                .about("Score workspace health and track it over time")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Compute a 0-100 health score for the whole workspace.\n\
+                     \n\
+                     Points are deducted for missing clones (up to 30), config drift as\n\
+                     reported by `meta project check` (20), projects with rule violations\n\
+                     (30), and projects with local branches untouched for --stale-days\n\
+                     (20), each in proportion to the share of projects affected. Disabled\n\
+                     projects are not scored.\n\
+                     \n\
+                     Each run is recorded in .meta-state/doctor-history.json; --history\n\
+                     shows the recorded scores and the trend without measuring again.\n\
+                     --min-score exits non-zero below a threshold, for CI gates.\n\
+                     \n\
+                     Examples:\n  \
+                       meta doctor\n  \
+                       meta doctor --history\n  \
+                       meta doctor --min-score 80 --json\n",
+                ))
+                .arg(
+                    Arg::new("history")
+                        .long("history")
+                        .help("Show recorded scores and the trend instead of measuring")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .help("Number of history entries to show")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("20"),
+                )
+                .arg(
+                    Arg::new("stale-days")
+                        .long("stale-days")
+                        .help("Days without commits before a local branch counts as stale (default 90)")
+                        .value_parser(clap::value_parser!(u64)),
+                )
+                .arg(
+                    Arg::new("min-score")
+                        .long("min-score")
+                        .help("Exit non-zero when the score is below this value")
+                        .value_parser(clap::value_parser!(u32)),
+                )
+                .arg(
+                    Arg::new("no-record")
+                        .long("no-record")
+                        .help("Do not add this run to the history")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the result as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let meta_root = config.require_meta_root()?;
+        let json = matches.get_flag("json");
+
+        if matches.get_flag("history") {
+            let history: HealthHistory = state::load(&meta_root, HISTORY_FILE)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&history.records)?);
+            } else {
+                let limit = *matches.get_one::<usize>("limit").expect("has default");
+                print_history(&history, limit);
+            }
+            return Ok(());
+        }
+
+        let stale_days = matches
+            .get_one::<u64>("stale-days")
+            .copied()
+            .unwrap_or(DEFAULT_STALE_DAYS);
+        let report = measure_health(&meta_root, &config.meta_config, stale_days)?;
+        let previous = if matches.get_flag("no-record") {
+            let history: HealthHistory = state::load(&meta_root, HISTORY_FILE)?;
+            history.records.last().cloned()
+        } else {
+            record_health(&meta_root, &report.record)?
+        };
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report.record)?);
+        } else {
+            print_health(&report, previous.as_ref());
+        }
+
+        if let Some(min) = matches.get_one::<u32>("min-score") {
+            if report.record.score < *min {
+                anyhow::bail!(
+                    "workspace health score {} is below the minimum of {}",
+                    report.record.score,
+                    min
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BasePlugin for DoctorPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Score workspace health and track it over time")
+    }
+}
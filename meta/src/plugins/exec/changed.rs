@@ -0,0 +1,163 @@
+//! Changed-file passthrough for `meta exec --changed-files <REF>`.
+//!
+//! Each project's files changed since the ref (committed, staged, unstaged,
+//! and untracked; deletions excluded) are handed to the command in two ways:
+//! the `{changed_files}` placeholder in its arguments, and the
+//! `METAREPO_CHANGED_FILES` environment variable (one path per line). Paths are
+//! relative to the project directory. A jj repository colocated with git is
+//! diffed by jj, whose working-copy commit holds the changes git sees as
+//! untracked or stale in its index.
+
+use crate::plugins::shared::vcs;
+use anyhow::{Context, Result};
+use metarepo_core::Vcs;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+/// Argument placeholder replaced by the project's changed files.
+pub const CHANGED_FILES_PLACEHOLDER: &str = "{changed_files}";
+
+/// Environment variable holding the project's changed files, one per line.
+pub const CHANGED_FILES_ENV: &str = "METAREPO_CHANGED_FILES";
+
+/// Run `program` in `dir` and return its stdout lines.
+fn lines(program: &str, dir: &Path, args: &[&str]) -> Result<Vec<String>> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Files in `project_path` changed since `since`, sorted: everything `git
+/// diff` reports against the working tree plus untracked files, minus
+/// deletions.
+pub fn changed_files(project_path: &Path, since: &str) -> Result<Vec<String>> {
+    if vcs::detect(project_path) == Some(Vcs::Jj) {
+        return jj_changed_files(project_path, since);
+    }
+    let mut files: BTreeSet<String> = lines(
+        "git",
+        project_path,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            "--diff-filter=d",
+            since,
+            "--",
+        ],
+    )?
+    .into_iter()
+    .collect();
+    files.extend(lines(
+        "git",
+        project_path,
+        &["ls-files", "--others", "--exclude-standard"],
+    )?);
+    Ok(files.into_iter().collect())
+}
+
+/// [`changed_files`] for a colocated jj repository. `since` is still a git
+/// ref, so git resolves it to a commit jj can diff the working copy against.
+fn jj_changed_files(project_path: &Path, since: &str) -> Result<Vec<String>> {
+    let commit = lines(
+        "git",
+        project_path,
+        &["rev-parse", "--verify", &format!("{}^{{commit}}", since)],
+    )?;
+    let commit = commit
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Could not resolve '{}'", since))?;
+    let files: BTreeSet<String> = lines(
+        "jj",
+        project_path,
+        &["diff", "--name-only", "--from", commit],
+    )?
+    .into_iter()
+    // jj lists deletions too; they have nothing left to hand on.
+    .filter(|file| project_path.join(file).exists())
+    .collect();
+    Ok(files.into_iter().collect())
+}
+
+/// `args` with the changed files substituted: a bare `{changed_files}`
+/// argument expands to one argument per file, and a placeholder inside a
+/// larger argument is replaced by the files joined with spaces.
+pub fn expand_args(args: &[&str], files: &[String]) -> Vec<String> {
+    let mut expanded = Vec::with_capacity(args.len() + files.len());
+    for arg in args {
+        if *arg == CHANGED_FILES_PLACEHOLDER {
+            expanded.extend(files.iter().cloned());
+        } else {
+            expanded.push(arg.replace(CHANGED_FILES_PLACEHOLDER, &files.join(" ")));
+        }
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn placeholder_expands_to_one_argument_per_file() {
+        let files = vec!["src/a.rs".to_string(), "b c.rs".to_string()];
+        assert_eq!(
+            expand_args(&["--fix", CHANGED_FILES_PLACEHOLDER], &files),
+            ["--fix", "src/a.rs", "b c.rs"]
+        );
+        assert_eq!(
+            expand_args(&["--files={changed_files}"], &files),
+            ["--files=src/a.rs b c.rs"]
+        );
+        assert_eq!(
+            expand_args(&[CHANGED_FILES_PLACEHOLDER], &[]),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn lists_changed_and_untracked_files_but_not_deletions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        git(dir, &["init", "-q"]);
+        std::fs::write(dir.join("kept.txt"), "1").unwrap();
+        std::fs::write(dir.join("edited.txt"), "1").unwrap();
+        std::fs::write(dir.join("removed.txt"), "1").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-qm", "base"]);
+
+        std::fs::write(dir.join("edited.txt"), "2").unwrap();
+        std::fs::remove_file(dir.join("removed.txt")).unwrap();
+        std::fs::write(dir.join("new.txt"), "1").unwrap();
+
+        assert_eq!(
+            changed_files(dir, "HEAD").unwrap(),
+            ["edited.txt", "new.txt"]
+        );
+        assert!(changed_files(dir, "no-such-ref").is_err());
+    }
+}
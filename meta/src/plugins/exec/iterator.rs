@@ -1,5 +1,7 @@
+use crate::plugins::shared::vcs;
 use git2::Repository;
-use metarepo_core::MetaConfig;
+use metarepo_core::{MetaConfig, ProjectEntry, Vcs};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
@@ -8,6 +10,9 @@ pub struct ProjectInfo {
     pub path: PathBuf,
     pub repo_url: String,
     pub exists: bool,
+    /// The project's `env` map from `.meta`, as written: `enc:` values are
+    /// still sealed.
+    pub env: HashMap<String, String>,
 }
 
 impl ProjectInfo {
@@ -18,6 +23,7 @@ impl ProjectInfo {
             path,
             repo_url,
             exists,
+            env: HashMap::new(),
         }
     }
 
@@ -33,6 +39,14 @@ impl ProjectInfo {
         if !self.is_git_repo() {
             return false;
         }
+        // In a jj repository colocated with git, the git index is stale;
+        // the working-copy commit's diff is what is uncommitted.
+        if vcs::detect(&self.path) == Some(Vcs::Jj) {
+            return vcs::backend(Vcs::Jj)
+                .status(&self.path)
+                .map(|status| status.dirty > 0)
+                .unwrap_or(false);
+        }
         match Repository::open(&self.path) {
             Ok(repo) => {
                 let mut opts = git2::StatusOptions::new();
@@ -47,6 +61,7 @@ impl ProjectInfo {
     }
 }
 
+#[derive(Clone)]
 pub struct ProjectIterator {
     projects: Vec<ProjectInfo>,
     current: usize,
@@ -66,7 +81,11 @@ impl ProjectIterator {
             let repo_url = config
                 .get_project_url(path_str)
                 .unwrap_or_else(|| "local".to_string());
-            projects.push(ProjectInfo::new(name, path, repo_url));
+            let mut project = ProjectInfo::new(name, path, repo_url);
+            if let Some(ProjectEntry::Metadata(metadata)) = config.projects.get(path_str) {
+                project.env = metadata.env.clone();
+            }
+            projects.push(project);
         }
 
         Self {
@@ -104,6 +123,20 @@ impl ProjectIterator {
         self
     }
 
+    /// Drop the given project keys. Used to apply `--exclude` selectors and
+    /// the workspace `default_excludes` once they are resolved to keys.
+    pub fn without(mut self, excluded: &std::collections::HashSet<String>) -> Self {
+        self.projects.retain(|p| !excluded.contains(&p.name));
+        self
+    }
+
+    /// Keep only the given project keys. Used to apply `--tag` and
+    /// `--exclude-tag` once they are resolved to keys.
+    pub fn only(mut self, kept: &std::collections::HashSet<String>) -> Self {
+        self.projects.retain(|p| kept.contains(&p.name));
+        self
+    }
+
     pub fn filter_existing(mut self) -> Self {
         self.projects.retain(|p| p.exists);
         self
@@ -358,6 +391,19 @@ mod tests {
         assert!(!projects.iter().any(|p| p.name == "test-project"));
     }
 
+    #[test]
+    fn test_project_iterator_without_keys() {
+        let temp_dir = tempdir().unwrap();
+        let config = create_test_config();
+
+        let excluded = ["lib-core".to_string(), "project-a".to_string()].into();
+        let iterator = ProjectIterator::new(&config, temp_dir.path()).without(&excluded);
+        let projects: Vec<ProjectInfo> = iterator.collect();
+
+        assert_eq!(projects.len(), 3);
+        assert!(!projects.iter().any(|p| excluded.contains(&p.name)));
+    }
+
     #[test]
     fn test_project_iterator_filter_existing() {
         let temp_dir = tempdir().unwrap();
@@ -597,4 +643,29 @@ mod tests {
         assert_eq!(projects.len(), 1);
         assert_eq!(projects[0].name, "clean-repo");
     }
+
+    #[test]
+    fn projects_carry_their_env_as_written() {
+        let config: MetaConfig = serde_json::from_str(
+            r#"{
+                "projects": {
+                    "api": {
+                        "url": "local:api",
+                        "env": { "PORT": "8080", "TOKEN": "enc:sealed" }
+                    },
+                    "web": "local:web"
+                }
+            }"#,
+        )
+        .unwrap();
+        let projects: HashMap<String, ProjectInfo> =
+            ProjectIterator::new(&config, Path::new("/ws"))
+                .map(|p| (p.name.clone(), p))
+                .collect();
+
+        let api = &projects["api"].env;
+        assert_eq!(api["PORT"], "8080");
+        assert_eq!(api["TOKEN"], "enc:sealed");
+        assert!(projects["web"].env.is_empty());
+    }
 }
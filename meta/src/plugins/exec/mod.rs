@@ -1,30 +1,91 @@
 use anyhow::Result;
-use metarepo_core::MetaConfig;
+use metarepo_core::{MetaConfig, ProjectEntry};
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
+use std::time::Instant;
 
+pub mod changed;
 pub mod iterator;
 pub mod plugin;
+pub mod template;
 
 // Export the plugin
-use crate::plugins::shared::{OutputManager, ProgressIndicator};
+use crate::plugins::shared::{
+    parallel_map, secrets, LogMultiplexer, MuxOptions, OutputManager, ProgressIndicator, RunOutcome,
+};
 pub use iterator::{ProjectInfo, ProjectIterator};
 pub use plugin::ExecPlugin;
+use template::TemplateVars;
+
+/// How `meta exec` shows each project's output: `--output`, else `exec.output`
+/// in `.meta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// One block per project; with several jobs, each project's output is
+    /// collected and shown once it finishes.
+    #[default]
+    Buffered,
+    /// Every line live, prefixed with its project.
+    Prefixed,
+    /// No command output, only whether each project succeeded.
+    Silent,
+}
+
+impl OutputMode {
+    /// The values `--output` takes.
+    pub const NAMES: [&'static str; 3] = ["buffered", "prefixed", "silent"];
+
+    /// Parse a mode name. `sections` and `prefix`, the older `exec.output`
+    /// values, mean `buffered` and `prefixed`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "buffered" | "sections" => Some(Self::Buffered),
+            "prefixed" | "prefix" => Some(Self::Prefixed),
+            "silent" => Some(Self::Silent),
+            _ => None,
+        }
+    }
+}
 
 pub fn execute_command_in_directory<P: AsRef<Path>>(
     command: &str,
     args: &[&str],
     directory: P,
 ) -> Result<()> {
-    let dir = directory.as_ref();
+    execute_command_in_directory_with_env(command, args, directory, &[])
+}
+
+/// [`execute_command_in_directory`] with extra environment variables.
+pub fn execute_command_in_directory_with_env<P: AsRef<Path>>(
+    command: &str,
+    args: &[&str],
+    directory: P,
+    env: &[(&str, String)],
+) -> Result<()> {
+    match run_in_directory(command, args, directory.as_ref(), env)? {
+        0 => Ok(()),
+        code => Err(anyhow::anyhow!("Command failed with exit code: {}", code)),
+    }
+}
+
+/// Run the command with streamed output and return its exit code (-1 when it
+/// was killed by a signal). Errors only when it cannot be run at all.
+fn run_in_directory(
+    command: &str,
+    args: &[&str],
+    dir: &Path,
+    env: &[(&str, String)],
+) -> Result<i32> {
     println!("\n=== Executing in {} ===", dir.display());
     println!("Command: {} {}", command, args.join(" "));
 
     let mut cmd = Command::new(command);
     cmd.args(args)
         .current_dir(dir)
+        .envs(env.iter().cloned())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
@@ -49,61 +110,232 @@ pub fn execute_command_in_directory<P: AsRef<Path>>(
                 eprintln!("ERROR: {}", line?);
             }
         }
-        return Err(anyhow::anyhow!(
-            "Command failed with exit code: {}",
-            status.code().unwrap_or(-1)
-        ));
     }
 
-    Ok(())
+    Ok(status.code().unwrap_or(-1))
+}
+
+/// Run the command with its output discarded and return its exit code (-1
+/// when it was killed by a signal).
+fn run_silently(command: &str, args: &[&str], dir: &Path, env: &[(&str, String)]) -> Result<i32> {
+    let status = Command::new(command)
+        .args(args)
+        .current_dir(dir)
+        .envs(env.iter().cloned())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    Ok(status.code().unwrap_or(-1))
+}
+
+/// Run the command in `dir` for `name`, recording its exit code and duration
+/// in `outcome`. Returns the failure, if any, for the caller to report.
+fn run_and_record(
+    outcome: &mut RunOutcome,
+    name: &str,
+    command: &str,
+    args: &[&str],
+    dir: &Path,
+    env: &[(&str, String)],
+    silent: bool,
+) -> Option<anyhow::Error> {
+    let started = Instant::now();
+    let result = if silent {
+        run_silently(command, args, dir, env)
+    } else {
+        run_in_directory(command, args, dir, env)
+    };
+    outcome.record(name, result.as_ref().ok().copied(), started.elapsed());
+    match result {
+        Ok(0) => None,
+        Ok(code) => Some(anyhow::anyhow!("Command failed with exit code: {}", code)),
+        Err(e) => Some(e),
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_with_iterator(
     command: &str,
     args: &[&str],
     iterator: ProjectIterator,
     include_main: bool,
-    parallel: bool,
+    jobs: usize,
     no_progress: bool,
     streaming: bool,
-) -> Result<()> {
+    output: OutputMode,
+    mux: &MuxOptions,
+    changed_since: Option<&str>,
+    vars: Option<&TemplateVars>,
+) -> Result<RunOutcome> {
     let projects: Vec<_> = iterator.collect();
     execute_with_projects(
         command,
         args,
         projects,
         include_main,
-        parallel,
+        jobs,
         no_progress,
         streaming,
+        output,
+        mux,
+        changed_since,
+        vars,
     )
 }
 
+/// Each project's changed files since `since` (see [`changed`]). Projects
+/// with no changes, or where the ref cannot be resolved, are dropped with a
+/// note.
+fn collect_changed_files(
+    projects: &mut Vec<ProjectInfo>,
+    since: &str,
+) -> HashMap<String, Vec<String>> {
+    let mut changed = HashMap::new();
+    projects.retain(|project| {
+        if !project.exists {
+            return true;
+        }
+        match changed_files_or_note(&project.name, &project.path, since) {
+            Some(files) => {
+                changed.insert(project.name.clone(), files);
+                true
+            }
+            None => false,
+        }
+    });
+    changed
+}
+
+/// The files changed since `since` in the repository at `path`, or `None`,
+/// with a note naming `name`, when there are none or the ref cannot be
+/// resolved there.
+fn changed_files_or_note(name: &str, path: &Path, since: &str) -> Option<Vec<String>> {
+    match changed::changed_files(path, since) {
+        Ok(files) if files.is_empty() => {
+            println!("  ℹ {}: no files changed since {}, skipping", name, since);
+            None
+        }
+        Ok(files) => Some(files),
+        Err(e) => {
+            eprintln!("  ⚠️  {}: cannot diff against {}: {}", name, since, e);
+            None
+        }
+    }
+}
+
+/// Open the encrypted values in each project's `env`, so every run gets
+/// them in plain text. A value that cannot be decrypted (no key, wrong key)
+/// fails before anything runs.
+fn decrypt_project_env(projects: &mut [ProjectInfo]) -> Result<()> {
+    for project in projects.iter_mut() {
+        if project
+            .env
+            .values()
+            .any(|value| secrets::is_encrypted(value))
+        {
+            project.env = secrets::resolve_env(&project.env)
+                .map_err(|e| anyhow::anyhow!("{}: {}", project.name, e))?
+                .into_iter()
+                .collect();
+        }
+    }
+    Ok(())
+}
+
+/// The arguments and extra environment for one project's run: the project's
+/// `env` from `.meta`; with `vars`, template placeholders are filled for
+/// `project`; with changed files, the placeholder is expanded and the
+/// environment variable set.
+fn project_invocation<'a>(
+    args: &[&str],
+    project: &'a ProjectInfo,
+    vars: Option<&TemplateVars>,
+    files: Option<&Vec<String>>,
+) -> (Vec<String>, Vec<(&'a str, String)>) {
+    let filled = vars.map(|vars| vars.fill(args, project));
+    let args: Vec<&str> = match &filled {
+        Some(filled) => filled.iter().map(String::as_str).collect(),
+        None => args.to_vec(),
+    };
+    let mut env: Vec<(&str, String)> = project
+        .env
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    match files {
+        Some(files) => {
+            env.push((changed::CHANGED_FILES_ENV, files.join("\n")));
+            (changed::expand_args(&args, files), env)
+        }
+        None => (args.iter().map(|s| s.to_string()).collect(), env),
+    }
+}
+
+/// The main repository as a target, for template placeholders.
+fn main_repo_info(base_path: &Path) -> ProjectInfo {
+    let name = base_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| ".".to_string());
+    ProjectInfo::new(name, base_path.to_path_buf(), "local".to_string())
+}
+
 /// Execute a command across a pre-built list of projects.
 ///
 /// This mirrors [`execute_with_iterator`] but accepts an already-collected list
 /// of [`ProjectInfo`] entries. It is useful when callers need to transform the
 /// project set before execution (for example, expanding a bare repository into
 /// one target per worktree).
+///
+/// Up to `jobs` projects run at once (1 runs them one after another). Output
+/// follows `output`: it goes through a [`LogMultiplexer`] when prefixed, when
+/// any of `mux`'s options are set, or when several jobs and `streaming` ask
+/// for live output from several projects at once; silent runs show only each
+/// project's result. With `changed_since`, each project gets its changed files
+/// (see [`changed`]) and projects without changes are skipped. With `vars`,
+/// the arguments are a template's (see [`template`]), filled per project; a
+/// placeholder without a value in some project fails before anything runs.
+/// Each project runs with its `env` from `.meta`, encrypted values opened
+/// first (see [`secrets`]).
+///
+/// Returns how many projects succeeded and failed; the caller decides the exit
+/// code (see [`crate::plugins::shared::outcome`]).
+#[allow(clippy::too_many_arguments)]
 pub fn execute_with_projects(
     command: &str,
     args: &[&str],
-    projects: Vec<ProjectInfo>,
+    mut projects: Vec<ProjectInfo>,
     include_main: bool,
-    parallel: bool,
+    jobs: usize,
     no_progress: bool,
     streaming: bool,
-) -> Result<()> {
+    output: OutputMode,
+    mux: &MuxOptions,
+    changed_since: Option<&str>,
+    vars: Option<&TemplateVars>,
+) -> Result<RunOutcome> {
+    let changed = changed_since.map(|since| collect_changed_files(&mut projects, since));
     if projects.is_empty() && !include_main {
         println!("No projects matched the criteria");
-        return Ok(());
+        return Ok(RunOutcome::default());
+    }
+    if let Some(vars) = vars {
+        vars.check(args, &projects)?;
     }
+    decrypt_project_env(&mut projects)?;
 
+    let parallel = jobs > 1 && projects.len() > 1;
+    let silent = output == OutputMode::Silent;
+    let mut outcome = RunOutcome::default();
     let total = projects.len() + if include_main { 1 } else { 0 };
     println!("Executing command in {} project(s)", total);
     println!("Command: {} {}", command, args.join(" "));
     if parallel {
-        println!("Mode: Parallel execution");
+        println!(
+            "Mode: Parallel execution ({} at a time)",
+            jobs.min(projects.len())
+        );
     }
     println!();
 
@@ -114,13 +346,53 @@ pub fn execute_with_projects(
         let base_path = meta_file.parent().unwrap();
 
         println!("=== Main Repository ===");
-        if let Err(e) = execute_command_in_directory(command, args, base_path) {
-            eprintln!("Failed in main repository: {}", e);
+        let main_repo = main_repo_info(base_path);
+        // Like a project, the main repository is skipped with a note when
+        // nothing changed there or the ref cannot be resolved.
+        let main_files =
+            changed_since.map(|since| changed_files_or_note(&main_repo.name, base_path, since));
+        if !matches!(main_files, Some(None)) {
+            if let Some(vars) = vars {
+                vars.check(args, std::slice::from_ref(&main_repo))?;
+            }
+            let (main_args, env) =
+                project_invocation(args, &main_repo, vars, main_files.flatten().as_ref());
+            let main_args: Vec<&str> = main_args.iter().map(|s| s.as_str()).collect();
+            if let Some(e) = run_and_record(
+                &mut outcome,
+                ".",
+                command,
+                &main_args,
+                base_path,
+                &env,
+                silent,
+            ) {
+                eprintln!("Failed in main repository: {}", e);
+            }
         }
     }
 
     // Execute in projects
-    if parallel && projects.len() > 1 && !streaming {
+    if silent {
+        outcome.merge(execute_silent(
+            command,
+            args,
+            &projects,
+            jobs,
+            changed.as_ref(),
+            vars,
+        ));
+    } else if output == OutputMode::Prefixed || mux.is_active() || (parallel && streaming) {
+        outcome.merge(execute_multiplexed(
+            command,
+            args,
+            &projects,
+            jobs,
+            mux,
+            changed.as_ref(),
+            vars,
+        )?);
+    } else if parallel {
         // Use buffered output for parallel execution
         let project_names: Vec<String> = projects.iter().map(|p| p.name.clone()).collect();
         let output_manager = Arc::new(OutputManager::new(project_names));
@@ -139,57 +411,48 @@ pub fn execute_with_projects(
             progress_indicator.start();
         }
 
-        use std::thread;
-        let mut handles = vec![];
-
-        for project in projects.clone() {
-            let cmd = command.to_string();
-            let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
-            let output_manager_clone = Arc::clone(&output_manager);
-            let project_name = project.name.clone();
+        parallel_map(projects.clone(), jobs, |project| {
+            let (args, env) = project_invocation(
+                args,
+                &project,
+                vars,
+                changed.as_ref().and_then(|c| c.get(&project.name)),
+            );
+            output_manager.start_project(&project.name);
 
-            let handle = thread::spawn(move || {
-                output_manager_clone.start_project(&project_name);
+            if !project.exists {
+                let error_msg = "Directory does not exist, skipping";
+                output_manager.complete_project(
+                    &project.name,
+                    -1,
+                    Vec::new(),
+                    error_msg.as_bytes().to_vec(),
+                );
+                return;
+            }
 
-                if !project.exists {
-                    let error_msg = "Directory does not exist, skipping";
-                    output_manager_clone.complete_project(
-                        &project_name,
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            match execute_command_in_directory_buffered_with_env(
+                command,
+                &args_refs,
+                &project.path,
+                &env,
+            ) {
+                Ok((exit_code, stdout, stderr, command_str)) => {
+                    output_manager.set_project_command(&project.name, command_str);
+                    output_manager.complete_project(&project.name, exit_code, stdout, stderr);
+                }
+                Err(e) => {
+                    let error_msg = format!("Error: {}", e);
+                    output_manager.complete_project(
+                        &project.name,
                         -1,
                         Vec::new(),
-                        error_msg.as_bytes().to_vec(),
+                        error_msg.into_bytes(),
                     );
-                    return;
                 }
-
-                let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-                match execute_command_in_directory_buffered(&cmd, &args_refs, &project.path) {
-                    Ok((exit_code, stdout, stderr, command_str)) => {
-                        output_manager_clone.set_project_command(&project_name, command_str);
-                        output_manager_clone.complete_project(
-                            &project_name,
-                            exit_code,
-                            stdout,
-                            stderr,
-                        );
-                    }
-                    Err(e) => {
-                        let error_msg = format!("Error: {}", e);
-                        output_manager_clone.complete_project(
-                            &project_name,
-                            -1,
-                            Vec::new(),
-                            error_msg.into_bytes(),
-                        );
-                    }
-                }
-            });
-            handles.push(handle);
-        }
-
-        for handle in handles {
-            handle.join().unwrap();
-        }
+            }
+        });
 
         // Stop progress indicator and display results
         if !no_progress {
@@ -200,7 +463,16 @@ pub fn execute_with_projects(
         }
         output_manager.display_final_results();
 
-        return Ok(());
+        for project in projects.iter().filter(|p| p.exists) {
+            let output = output_manager.get_project_output(&project.name);
+            let exit_code = output.as_ref().and_then(|o| o.exit_code);
+            let duration = output.as_ref().and_then(|o| o.duration).unwrap_or_default();
+            match output.and_then(|o| o.start_time) {
+                Some(started) => outcome.record_span(&project.name, exit_code, started, duration),
+                None => outcome.record(&project.name, exit_code, duration),
+            }
+        }
+        return Ok(outcome);
     } else {
         for (idx, project) in projects.iter().enumerate() {
             println!("[{}/{}] {}", idx + 1, projects.len(), project.name);
@@ -210,16 +482,113 @@ pub fn execute_with_projects(
                 continue;
             }
 
-            if let Err(e) = execute_command_in_directory(command, args, &project.path) {
-                eprintln!("  ❌ Failed: {}", e);
-            } else {
-                println!("  ✅ Success");
+            let (args, env) = project_invocation(
+                args,
+                project,
+                vars,
+                changed.as_ref().and_then(|c| c.get(&project.name)),
+            );
+            let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            match run_and_record(
+                &mut outcome,
+                &project.name,
+                command,
+                &args,
+                &project.path,
+                &env,
+                false,
+            ) {
+                Some(e) => eprintln!("  ❌ Failed: {}", e),
+                None => println!("  ✅ Success"),
             }
         }
     }
 
     println!("\n=== Execution Complete ===");
-    Ok(())
+    Ok(outcome)
+}
+
+/// Run the command in each existing project with live, prefixed output from
+/// a [`LogMultiplexer`], then summarize the exit codes.
+fn execute_multiplexed(
+    command: &str,
+    args: &[&str],
+    projects: &[ProjectInfo],
+    jobs: usize,
+    options: &MuxOptions,
+    changed: Option<&HashMap<String, Vec<String>>>,
+    vars: Option<&TemplateVars>,
+) -> Result<RunOutcome> {
+    let names: Vec<String> = projects.iter().map(|p| p.name.clone()).collect();
+    let mux = Arc::new(LogMultiplexer::new(&names, options)?);
+
+    let mut commands = Vec::new();
+    for project in projects {
+        if !project.exists {
+            println!("  ⚠️  {}: directory does not exist, skipping", project.name);
+            continue;
+        }
+        let (args, env) = project_invocation(
+            args,
+            project,
+            vars,
+            changed.and_then(|c| c.get(&project.name)),
+        );
+        let mut cmd = Command::new(command);
+        cmd.args(args).envs(env).current_dir(&project.path);
+        commands.push((project.name.clone(), cmd));
+    }
+
+    let results = mux.run_all(commands, jobs);
+    let mut outcome = RunOutcome::default();
+    println!();
+    for (project, result, started, duration) in &results {
+        match result {
+            Ok(0) => println!("  ✅ {}", project),
+            Ok(code) => println!("  ❌ {} (exit code {})", project, code),
+            Err(e) => println!("  ❌ {}: {}", project, e),
+        }
+        outcome.record_span(project, result.as_ref().ok().copied(), *started, *duration);
+    }
+    Ok(outcome)
+}
+
+/// Run the command in each existing project, up to `jobs` at a time, with its
+/// output discarded, then list how each project did.
+fn execute_silent(
+    command: &str,
+    args: &[&str],
+    projects: &[ProjectInfo],
+    jobs: usize,
+    changed: Option<&HashMap<String, Vec<String>>>,
+    vars: Option<&TemplateVars>,
+) -> RunOutcome {
+    let existing: Vec<&ProjectInfo> = projects.iter().filter(|p| p.exists).collect();
+    for project in projects.iter().filter(|p| !p.exists) {
+        println!("  ⚠️  {}: directory does not exist, skipping", project.name);
+    }
+    let results = parallel_map(existing, jobs, |project| {
+        let (args, env) = project_invocation(
+            args,
+            project,
+            vars,
+            changed.and_then(|c| c.get(&project.name)),
+        );
+        let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let started = Instant::now();
+        let result = run_silently(command, &args, &project.path, &env);
+        (&project.name, result, started, started.elapsed())
+    });
+    let mut outcome = RunOutcome::default();
+    for (project, result, started, duration) in results {
+        match &result {
+            Ok(0) => println!("  ✅ {}", project),
+            Ok(code) => println!("  ❌ {} (exit code {})", project, code),
+            Err(e) => println!("  ❌ {}: {}", project, e),
+        }
+        outcome.record_span(project, result.ok(), started, duration);
+    }
+    outcome
 }
 
 /// Execute command in directory with buffered output (for parallel execution)
@@ -227,6 +596,16 @@ pub fn execute_command_in_directory_buffered<P: AsRef<Path>>(
     command: &str,
     args: &[&str],
     directory: P,
+) -> Result<(i32, Vec<u8>, Vec<u8>, String)> {
+    execute_command_in_directory_buffered_with_env(command, args, directory, &[])
+}
+
+/// [`execute_command_in_directory_buffered`] with extra environment variables.
+pub fn execute_command_in_directory_buffered_with_env<P: AsRef<Path>>(
+    command: &str,
+    args: &[&str],
+    directory: P,
+    env: &[(&str, String)],
 ) -> Result<(i32, Vec<u8>, Vec<u8>, String)> {
     let dir = directory.as_ref();
     let command_str = if args.is_empty() {
@@ -236,7 +615,7 @@ pub fn execute_command_in_directory_buffered<P: AsRef<Path>>(
     };
 
     let mut cmd = Command::new(command);
-    cmd.args(args).current_dir(dir);
+    cmd.args(args).current_dir(dir).envs(env.iter().cloned());
 
     let output = cmd.output()?;
 
@@ -248,7 +627,7 @@ pub fn execute_command_in_directory_buffered<P: AsRef<Path>>(
     ))
 }
 
-pub fn execute_in_all_projects(command: &str, args: &[&str]) -> Result<()> {
+pub fn execute_in_all_projects(command: &str, args: &[&str]) -> Result<RunOutcome> {
     let meta_file = MetaConfig::find_meta_file()
         .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
 
@@ -256,28 +635,70 @@ pub fn execute_in_all_projects(command: &str, args: &[&str]) -> Result<()> {
     let base_path = meta_file.parent().unwrap();
 
     let iterator = ProjectIterator::new(&config, base_path);
-    execute_with_iterator(command, args, iterator, true, false, false, false)
+    execute_with_iterator(
+        command,
+        args,
+        iterator,
+        true,
+        1,
+        false,
+        false,
+        OutputMode::default(),
+        &MuxOptions::default(),
+        None,
+        None,
+    )
 }
 
-pub fn execute_in_specific_projects(command: &str, args: &[&str], projects: &[&str]) -> Result<()> {
+pub fn execute_in_specific_projects(
+    command: &str,
+    args: &[&str],
+    projects: &[&str],
+) -> Result<RunOutcome> {
     let meta_file = MetaConfig::find_meta_file()
         .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
 
     let config = MetaConfig::load_from_file(&meta_file)?;
     let base_path = meta_file.parent().unwrap();
 
+    // Open encrypted env values up front, so a missing key fails before
+    // anything runs, as in execute_with_projects.
+    let mut envs = HashMap::new();
+    for project_name in projects {
+        if let Some(ProjectEntry::Metadata(metadata)) = config.projects.get(*project_name) {
+            let env = secrets::resolve_env(&metadata.env)
+                .map_err(|e| anyhow::anyhow!("{}: {}", project_name, e))?;
+            envs.insert(*project_name, env);
+        }
+    }
+
     println!(
         "Executing '{} {}' in specified projects",
         command,
         args.join(" ")
     );
 
+    let mut outcome = RunOutcome::default();
     for project_name in projects {
-        if let Some(_repo_url) = config.projects.get(*project_name) {
+        if config.projects.contains_key(*project_name) {
             let full_path = base_path.join(project_name);
 
             if full_path.exists() {
-                if let Err(e) = execute_command_in_directory(command, args, &full_path) {
+                let env: Vec<(&str, String)> = envs
+                    .get(project_name)
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, value)| (name.as_str(), value.clone()))
+                    .collect();
+                if let Some(e) = run_and_record(
+                    &mut outcome,
+                    project_name,
+                    command,
+                    args,
+                    &full_path,
+                    &env,
+                    false,
+                ) {
                     eprintln!("Failed in {}: {}", project_name, e);
                 }
             } else {
@@ -293,5 +714,5 @@ pub fn execute_in_specific_projects(command: &str, args: &[&str], projects: &[&s
     }
 
     println!("\n=== Execution Complete ===");
-    Ok(())
+    Ok(outcome)
 }
@@ -1,7 +1,12 @@
-use super::{execute_in_specific_projects, execute_with_iterator, ProjectIterator};
+use super::template::{self, TemplateVars};
+use super::{execute_in_specific_projects, execute_with_iterator, OutputMode, ProjectIterator};
+use crate::plugins::shared::{jobs_from, outcome, selection, MuxOptions};
 use anyhow::Result;
 use clap::ArgMatches;
 use metarepo_core::{arg, command, plugin, BasePlugin, MetaConfig, MetaPlugin, RuntimeConfig};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Instant;
 
 /// ExecPlugin using the new simplified plugin architecture
 pub struct ExecPlugin;
@@ -29,17 +34,74 @@ impl ExecPlugin {
                          there, inside a subdirectory it runs in the projects beneath it, and at the\n\
                          workspace root it runs everywhere.\n\
                          \n\
-                         Use -p/--project or --projects to target specific projects, -a/--all to run\n\
-                         across the whole workspace, and --include-only/--exclude to filter by name.\n\
-                         --git-only and --existing-only restrict the set further. Projects disabled\n\
-                         in the .meta config are skipped unless --include-disabled is passed.\n\
-                         --parallel runs the command concurrently and --include-main also runs it in\n\
-                         the meta repo itself.\n\
+                         Use -p/--project or --projects to target specific projects; names may be\n\
+                         globs ('services/*', 'libs/**'), and --strict fails if any selector matches\n\
+                         nothing. Use -a/--all to run across the whole workspace, and\n\
+                         --include-only/--exclude to filter by name; --exclude applies after selection\n\
+                         on every path. Projects in the workspace default_excludes list are skipped\n\
+                         unless named, and projects disabled in the .meta config are skipped unless\n\
+                         --include-disabled is passed. --git-only and --existing-only restrict the set\n\
+                         further. --parallel runs the command in several projects at once, as many as\n\
+                         the parallelism setting (default: one per CPU); -j/--jobs N runs up to N at\n\
+                         once. --include-main also runs it in the meta repo itself. --group NAME\n\
+                         (repeatable) adds the members of a project group from .meta to the\n\
+                         selection. --tag TAG (repeatable) keeps only projects with that tag in their\n\
+                         .meta entry (\"tags\": [\"backend\"]); without another selection it picks\n\
+                         the tagged projects across the workspace. --exclude-tag TAG (repeatable)\n\
+                         drops the projects carrying that tag.\n\
+                         \n\
+                         --only, --timestamps, and --log-file stream every project's output live\n\
+                         with a colored project prefix: --only shows just the named projects,\n\
+                         --timestamps adds the time to each line, and --log-file also writes all\n\
+                         output to one file. --parallel --streaming uses the same live output.\n\
+                         --prefix asks for it by name and ends each project's output with\n\
+                         its exit status; \"exec\": {\"output\": \"prefix\"} in .meta makes it\n\
+                         the default, and --sections goes back to one block per project.\n\
+                         --output MODE names the mode: buffered (one block per project; with\n\
+                         several jobs, each shown as it finishes), prefixed (as --prefix), or\n\
+                         silent (no command output, only each project's result).\n\
+                         --pty runs each command on its own pseudo-terminal (a pty on Unix,\n\
+                         ConPTY on Windows), so tools that drop colors and progress bars when\n\
+                         piped keep them;\n\
+                         stdout and stderr arrive merged, as on a terminal.\n\
+                         \n\
+                         --changed-files REF hands each project the files changed since REF (committed,\n\
+                         uncommitted, and untracked; deletions left out), relative to the project:\n\
+                         a {changed_files} argument expands to one argument per file, and\n\
+                         METAREPO_CHANGED_FILES holds them one per line. Projects with no changes\n\
+                         are skipped.\n\
+                         \n\
+                         @NAME runs a command line from the exec_templates block in .meta (or an\n\
+                         extends fragment, or the user config), with any further arguments\n\
+                         appended. In its arguments {name}, {path}, {branch}, and {url} are filled\n\
+                         per project, and any other {key} from the project's env block, then the\n\
+                         environment (as key or KEY); a missing value stops the run before it\n\
+                         starts. --list-templates shows the templates.\n\
+                         \n\
+                         Each project's env block from .meta is set in its command's environment,\n\
+                         with enc: values decrypted first (see meta config encrypt); a value that\n\
+                         cannot be decrypted stops the run before it starts. Template placeholders\n\
+                         only take plain env values, so secrets stay off the command line.\n\
+                         \n\
+                         Exit codes: 0 when every project succeeds, 1 when all fail, and 2 when some\n\
+                         fail. --min-success PCT (or min_success.exec in .meta) exits 0 as long as at\n\
+                         least PCT percent of the projects succeed. `meta last` shows the summary of\n\
+                         the most recent run.\n\
+                         \n\
+                         With notify.on_complete_secs set in .meta, a run that takes at least that\n\
+                         long rings the terminal bell when it finishes (and, with notify.desktop,\n\
+                         shows a desktop notification).\n\
                          \n\
                          Examples:\n  \
                            meta exec --all git status\n  \
                            meta exec -p doop npm install\n  \
-                           meta exec --git-only --parallel git pull",
+                           meta exec --projects 'services/*' --strict npm test\n  \
+                           meta exec --git-only --parallel git pull\n  \
+                           meta exec -j 4 --output silent cargo check\n  \
+                           meta exec --tag backend --existing-only cargo test\n  \
+                           meta exec --parallel --only api --log-file test.log npm test\n  \
+                           meta exec --changed-files origin/main npx eslint {changed_files}\n  \
+                           meta exec --all @docker-build --no-cache",
                     )
                     .aliases(vec!["e".to_string(), "x".to_string()])
                     .allow_external_subcommands(true)
@@ -57,6 +119,29 @@ impl ExecPlugin {
                             .help("Comma-separated list of specific projects")
                             .takes_value(true),
                     )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Run in the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .help("Only run in projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude-tag")
+                            .long("exclude-tag")
+                            .help("Skip projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
                     .arg(
                         arg("all")
                             .short('a')
@@ -72,7 +157,7 @@ impl ExecPlugin {
                     .arg(
                         arg("exclude")
                             .long("exclude")
-                            .help("Exclude projects matching these patterns (comma-separated)")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
                             .takes_value(true),
                     )
                     .arg(
@@ -90,6 +175,13 @@ impl ExecPlugin {
                             .long("parallel")
                             .help("Execute commands in parallel"),
                     )
+                    .arg(
+                        arg("jobs")
+                            .short('j')
+                            .long("jobs")
+                            .help("Run in up to this many projects at once (implies --parallel)")
+                            .takes_value(true),
+                    )
                     .arg(
                         arg("include-main")
                             .long("include-main")
@@ -99,6 +191,61 @@ impl ExecPlugin {
                         arg("include-disabled")
                             .long("include-disabled")
                             .help("Also run in projects disabled in the .meta config"),
+                    )
+                    .arg(
+                        arg("prefix")
+                            .long("prefix")
+                            .help("Stream output live with a project prefix, ending with each exit status"),
+                    )
+                    .arg(
+                        arg("sections")
+                            .long("sections")
+                            .help("Show one block of output per project, overriding exec.output in .meta"),
+                    )
+                    .arg(
+                        arg("output")
+                            .long("output")
+                            .help("Output mode: buffered, prefixed, or silent (default: exec.output)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("only")
+                            .long("only")
+                            .help("Only show output from these projects (comma-separated)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("timestamps")
+                            .long("timestamps")
+                            .help("Prefix each output line with the time"),
+                    )
+                    .arg(
+                        arg("log-file")
+                            .long("log-file")
+                            .help("Also write all output to this file")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("pty")
+                            .long("pty")
+                            .help("Run commands on a pseudo-terminal to keep colors and progress bars"),
+                    )
+                    .arg(
+                        arg("min-success")
+                            .long("min-success")
+                            .help("Exit 0 when at least this percentage of projects succeed")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("list-templates")
+                            .long("list-templates")
+                            .help("List the exec_templates from .meta and exit"),
+                    )
+                    .arg(
+                        arg("changed-files")
+                            .long("changed-files")
+                            .help("Pass each project's files changed since this ref ({changed_files}, METAREPO_CHANGED_FILES)")
+                            .takes_value(true),
                     ),
             )
             .handler("exec", handle_exec)
@@ -106,14 +253,189 @@ impl ExecPlugin {
     }
 }
 
+/// Print each exec template and its command line, by name.
+fn list_templates(config: &MetaConfig) -> Result<()> {
+    let mut templates: Vec<_> = config.exec_templates.iter().flatten().collect();
+    if templates.is_empty() {
+        println!("No exec templates. Add an exec_templates block to .meta, e.g.");
+        println!("  \"exec_templates\": {{ \"test\": \"cargo test --all\" }}");
+        return Ok(());
+    }
+    templates.sort();
+    let width = templates
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    for (name, command) in templates {
+        println!("  @{:<width$} {}", name, command, width = width);
+    }
+    Ok(())
+}
+
+/// The output mode: `--output`, `--prefix`, or `--sections`, else
+/// `exec.output` in .meta, else buffered. An unknown mode name is an error.
+fn output_mode(matches: &ArgMatches, config: &MetaConfig) -> Result<OutputMode> {
+    if let Some(name) = matches.get_one::<String>("output") {
+        return parse_output_mode(name, "--output");
+    }
+    if matches.get_flag("prefix") {
+        return Ok(OutputMode::Prefixed);
+    }
+    if matches.get_flag("sections") {
+        return Ok(OutputMode::Buffered);
+    }
+    match config
+        .exec_settings
+        .as_ref()
+        .and_then(|settings| settings.output.as_deref())
+    {
+        Some(name) => parse_output_mode(name, "exec.output"),
+        None => Ok(OutputMode::default()),
+    }
+}
+
+fn parse_output_mode(name: &str, source: &str) -> Result<OutputMode> {
+    OutputMode::parse(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid {} value '{}': expected one of {}",
+            source,
+            name,
+            OutputMode::NAMES.join(", ")
+        )
+    })
+}
+
+/// How many projects run at once: `--jobs`, else the `parallelism` setting
+/// with `--parallel`, else one.
+fn job_count(matches: &ArgMatches, config: &MetaConfig) -> Result<usize> {
+    Ok(match jobs_from(matches)? {
+        Some(jobs) => jobs,
+        None if matches.get_flag("parallel") => config.worker_count(),
+        None => 1,
+    })
+}
+
+/// The projects to run in when neither `--all` nor `--tag` alone picks them:
+/// those named with -p/--projects/--group, else the directory-aware scope,
+/// then narrowed by the tag filters, `--exclude`, `--existing-only`, and
+/// `--git-only`. Empty when nothing is left to run in; the reason has been
+/// printed.
+fn selected_projects(
+    matches: &ArgMatches,
+    runtime_config: &RuntimeConfig,
+    config: &MetaConfig,
+    base_path: &Path,
+    tagged: Option<&HashSet<String>>,
+) -> Result<Vec<String>> {
+    let include_disabled = matches.get_flag("include-disabled");
+
+    // Explicit selection: names, aliases, and globs from -p/--projects
+    let mut selected_projects =
+        selection::explicit_projects(matches, runtime_config)?.unwrap_or_default();
+
+    if let Some(tagged) = tagged {
+        if !selected_projects.is_empty() {
+            selected_projects.retain(|key| tagged.contains(key));
+            if selected_projects.is_empty() {
+                println!("No selected project passes the tag filters.");
+                return Ok(selected_projects);
+            }
+        }
+    }
+
+    // Drop explicitly-selected projects that are disabled, unless the
+    // user opted in with --include-disabled. Resolution already happened
+    // above, so an alias of a disabled project is caught here too.
+    if !selected_projects.is_empty() && !include_disabled {
+        let disabled = config.disabled_project_keys();
+        selected_projects.retain(|key| {
+            if disabled.contains(key) {
+                eprintln!("Skipping disabled project '{key}' (use --include-disabled to run it)");
+                false
+            } else {
+                true
+            }
+        });
+        if selected_projects.is_empty() {
+            return Ok(selected_projects);
+        }
+    }
+
+    // Exclusion runs after inclusion. Named projects only lose what
+    // --exclude names; the workspace default_excludes leave them alone.
+    if !selected_projects.is_empty() {
+        selection::apply_excludes(matches, runtime_config, &mut selected_projects, true);
+        if selected_projects.is_empty() {
+            println!("Every selected project is excluded.");
+            return Ok(selected_projects);
+        }
+    }
+
+    // If no projects specified, fall back to the directory-aware scope:
+    // inside a project -> that project; inside a subdirectory -> the
+    // projects beneath it; at the workspace root (or with --workspace)
+    // -> all. Explicit --project/--projects above override this.
+    if selected_projects.is_empty() {
+        selected_projects = runtime_config.scoped_project_keys();
+        selection::apply_excludes(matches, runtime_config, &mut selected_projects, false);
+        if let Some(tagged) = tagged {
+            selected_projects.retain(|key| tagged.contains(key));
+        }
+        if selected_projects.is_empty() {
+            println!("No projects in this directory. Use --workspace to run across the whole workspace, or --project/--projects to target specific projects.");
+            return Ok(selected_projects);
+        }
+    }
+
+    // --existing-only and --git-only narrow every selection, as they do
+    // the iterator for --all.
+    let existing_only = matches.get_flag("existing-only");
+    let git_only = matches.get_flag("git-only");
+    if existing_only || git_only {
+        let mut iterator = ProjectIterator::new(config, base_path)
+            .include_disabled(true)
+            .with_scope(&selected_projects);
+        if existing_only {
+            iterator = iterator.filter_existing();
+        }
+        if git_only {
+            iterator = iterator.filter_git_repos();
+        }
+        let kept: HashSet<String> = iterator.map(|project| project.name).collect();
+        selected_projects.retain(|key| kept.contains(key));
+        if selected_projects.is_empty() {
+            println!(
+                "No selected project is {}.",
+                if git_only {
+                    "a git repository"
+                } else {
+                    "on disk"
+                }
+            );
+        }
+    }
+
+    Ok(selected_projects)
+}
+
 /// Handler for the exec command
 fn handle_exec(matches: &ArgMatches, runtime_config: &RuntimeConfig) -> Result<()> {
+    let started = Instant::now();
     // Load meta configuration
     let meta_file = MetaConfig::find_meta_file()
         .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
-    let config = MetaConfig::load_from_file(&meta_file)?;
+    let mut config = MetaConfig::load_from_file(&meta_file)?;
+    if let Some(profile) = &runtime_config.profile {
+        config.apply_profile(profile)?;
+    }
     let base_path = meta_file.parent().unwrap();
 
+    if matches.get_flag("list-templates") {
+        return list_templates(&runtime_config.meta_config);
+    }
+
     // Get the external subcommand (the actual command to run)
     match matches.subcommand() {
         Some((command, sub_matches)) => {
@@ -123,13 +445,36 @@ fn handle_exec(matches: &ArgMatches, runtime_config: &RuntimeConfig) -> Result<(
                 None => Vec::new(),
             };
 
-            // Collect selected projects
-            let mut selected_projects = Vec::new();
+            // `@name` runs a command line from exec_templates, with the
+            // arguments given here appended and placeholders filled per project.
+            let template_words = match command.strip_prefix(template::TEMPLATE_PREFIX) {
+                Some(name) => Some(template::resolve(&runtime_config.meta_config, name, &args)?),
+                None => None,
+            };
+            let vars = template_words.as_ref().map(|_| TemplateVars::new(&config));
+            let vars = vars.as_ref();
+            let (command, args): (&str, Vec<&str>) = match &template_words {
+                Some(words) => (&words[0], words[1..].iter().map(String::as_str).collect()),
+                None => (command, args),
+            };
+
+            let output = output_mode(matches, &config)?;
+            let mut mux = MuxOptions::from_matches(matches);
+            mux.prefix = output == OutputMode::Prefixed;
+            let jobs = job_count(matches, &config)?;
+            let changed_since = matches
+                .get_one::<String>("changed-files")
+                .map(|s| s.as_str());
+            let min_success = outcome::min_success(matches, &config, "exec")?;
 
             let include_disabled = matches.get_flag("include-disabled");
 
+            // --tag and --exclude-tag narrow whatever is selected; --tag on its
+            // own selects the tagged projects across the workspace, like --all.
+            let tagged = selection::tagged_keys(matches, runtime_config)?;
+
             // Check for --all flag
-            if matches.get_flag("all") {
+            if matches.get_flag("all") || selection::selects_by_tag(matches) {
                 // Run in all projects
                 let mut iterator =
                     ProjectIterator::new(&config, base_path).include_disabled(include_disabled);
@@ -147,6 +492,12 @@ fn handle_exec(matches: &ArgMatches, runtime_config: &RuntimeConfig) -> Result<(
                     iterator = iterator.with_exclude_patterns(pattern_vec);
                 }
 
+                iterator =
+                    iterator.without(&selection::excluded_keys(matches, runtime_config, false));
+                if let Some(tagged) = &tagged {
+                    iterator = iterator.only(tagged);
+                }
+
                 if matches.get_flag("existing-only") {
                     iterator = iterator.filter_existing();
                 }
@@ -155,84 +506,73 @@ fn handle_exec(matches: &ArgMatches, runtime_config: &RuntimeConfig) -> Result<(
                     iterator = iterator.filter_git_repos();
                 }
 
-                let parallel = matches.get_flag("parallel");
+                selection::warn_deprecated(&config, iterator.clone().map(|p| p.name));
                 let include_main = matches.get_flag("include-main");
                 let no_progress = matches.get_flag("no-progress");
                 let streaming = matches.get_flag("streaming");
 
-                execute_with_iterator(
+                return execute_with_iterator(
                     command,
                     &args,
                     iterator,
                     include_main,
-                    parallel,
+                    jobs,
                     no_progress,
                     streaming,
-                )?;
-                return Ok(());
-            }
-
-            // Check for single project
-            if let Some(project_id) = matches.get_one::<String>("project") {
-                // Use resolve_project to handle aliases
-                if let Some(resolved) = runtime_config.resolve_project(project_id) {
-                    selected_projects.push(resolved);
-                } else {
-                    selected_projects.push(project_id.clone());
-                }
-            }
-
-            // Check for multiple projects
-            if let Some(projects_str) = matches.get_one::<String>("projects") {
-                for p in projects_str.split(',') {
-                    let trimmed = p.trim();
-                    // Use resolve_project to handle aliases
-                    if let Some(resolved) = runtime_config.resolve_project(trimmed) {
-                        selected_projects.push(resolved);
-                    } else {
-                        selected_projects.push(trimmed.to_string());
-                    }
-                }
-            }
-
-            // Drop explicitly-selected projects that are disabled, unless the
-            // user opted in with --include-disabled. Resolution already happened
-            // above, so an alias of a disabled project is caught here too.
-            if !selected_projects.is_empty() && !include_disabled {
-                let disabled = config.disabled_project_keys();
-                selected_projects.retain(|key| {
-                    if disabled.contains(key) {
-                        eprintln!(
-                            "Skipping disabled project '{key}' (use --include-disabled to run it)"
-                        );
-                        false
-                    } else {
-                        true
-                    }
-                });
-                if selected_projects.is_empty() {
-                    return Ok(());
-                }
+                    output,
+                    &mux,
+                    changed_since,
+                    vars,
+                )?
+                .finish(&config, base_path, "exec", started, min_success);
             }
 
-            // If no projects specified, fall back to the directory-aware scope:
-            // inside a project -> that project; inside a subdirectory -> the
-            // projects beneath it; at the workspace root (or with --workspace)
-            // -> all. Explicit --project/--projects above override this.
+            let selected_projects =
+                selected_projects(matches, runtime_config, &config, base_path, tagged.as_ref())?;
             if selected_projects.is_empty() {
-                selected_projects = runtime_config.scoped_project_keys();
-                if selected_projects.is_empty() {
-                    println!("No projects in this directory. Use --workspace to run across the whole workspace, or --project/--projects to target specific projects.");
-                    return Ok(());
-                }
+                return Ok(());
             }
 
-            // Execute in selected projects
+            selection::warn_deprecated(&config, &selected_projects);
+
+            // Execute in selected projects. Parallel, multiplexed, template,
+            // and --changed-files runs go through the shared executor;
+            // otherwise run one after another.
+            if !selected_projects.is_empty()
+                && (jobs > 1
+                    || mux.is_active()
+                    || output == OutputMode::Silent
+                    || vars.is_some()
+                    || changed_since.is_some())
+            {
+                let iterator = ProjectIterator::new(&config, base_path)
+                    .include_disabled(true)
+                    .with_scope(&selected_projects);
+                return execute_with_iterator(
+                    command,
+                    &args,
+                    iterator,
+                    matches.get_flag("include-main"),
+                    jobs,
+                    matches.get_flag("no-progress"),
+                    matches.get_flag("streaming"),
+                    output,
+                    &mux,
+                    changed_since,
+                    vars,
+                )?
+                .finish(&config, base_path, "exec", started, min_success);
+            }
             if !selected_projects.is_empty() {
                 let project_refs: Vec<&str> =
                     selected_projects.iter().map(|s| s.as_str()).collect();
-                execute_in_specific_projects(command, &args, &project_refs)?;
-                return Ok(());
+                return execute_in_specific_projects(command, &args, &project_refs)?.finish(
+                    &config,
+                    base_path,
+                    "exec",
+                    started,
+                    min_success,
+                );
             }
 
             // Build iterator with filters (for backward compatibility)
@@ -262,7 +602,6 @@ fn handle_exec(matches: &ArgMatches, runtime_config: &RuntimeConfig) -> Result<(
                 iterator = iterator.filter_git_repos();
             }
 
-            let parallel = matches.get_flag("parallel");
             let include_main = matches.get_flag("include-main");
             let no_progress = matches.get_flag("no-progress");
             let streaming = matches.get_flag("streaming");
@@ -272,12 +611,15 @@ fn handle_exec(matches: &ArgMatches, runtime_config: &RuntimeConfig) -> Result<(
                 &args,
                 iterator,
                 include_main,
-                parallel,
+                jobs,
                 no_progress,
                 streaming,
-            )?;
-
-            Ok(())
+                output,
+                &mux,
+                changed_since,
+                vars,
+            )?
+            .finish(&config, base_path, "exec", started, min_success)
         }
         None => {
             // No command specified - show error
@@ -311,15 +653,73 @@ impl MetaPlugin for ExecPlugin {
                  there, inside a subdirectory it runs in the projects beneath it, and at the\n\
                  workspace root it runs everywhere.\n\
                  \n\
-                 Use -p/--project or --projects to target specific projects, -a/--all to run\n\
-                 across the whole workspace, and --include-only/--exclude to filter by name.\n\
-                 --git-only and --existing-only restrict the set further. --parallel runs the\n\
-                 command concurrently and --include-main also runs it in the meta repo itself.\n\
+                 Use -p/--project or --projects to target specific projects; names may be\n\
+                 globs ('services/*', 'libs/**'), and --strict fails if any selector matches\n\
+                 nothing. Use -a/--all to run across the whole workspace, and\n\
+                 --include-only/--exclude to filter by name; --exclude applies after selection\n\
+                 on every path. Projects in the workspace default_excludes list are skipped\n\
+                 unless named. --git-only and --existing-only restrict the set further.\n\
+                 --parallel runs the command in several projects at once, as many as the\n\
+                 parallelism setting (default: one per CPU); -j/--jobs N runs up to N at\n\
+                 once. --include-main also runs it in the meta repo itself. --group NAME\n\
+                 (repeatable) adds the members of a project group from .meta to the\n\
+                 selection. --tag TAG (repeatable) keeps only projects with that tag in their\n\
+                 .meta entry (\"tags\": [\"backend\"]); without another selection it picks\n\
+                 the tagged projects across the workspace. --exclude-tag TAG (repeatable)\n\
+                 drops the projects carrying that tag.\n\
+                 \n\
+                 --only, --timestamps, and --log-file stream every project's output live\n\
+                 with a colored project prefix: --only shows just the named projects,\n\
+                 --timestamps adds the time to each line, and --log-file also writes all\n\
+                 output to one file. --parallel --streaming uses the same live output.\n\
+                 --prefix asks for it by name and ends each project's output with\n\
+                 its exit status; \"exec\": {\"output\": \"prefix\"} in .meta makes it\n\
+                 the default, and --sections goes back to one block per project.\n\
+                 --output MODE names the mode: buffered (one block per project; with\n\
+                 several jobs, each shown as it finishes), prefixed (as --prefix), or\n\
+                 silent (no command output, only each project's result).\n\
+                 --pty runs each command on its own pseudo-terminal (a pty on Unix,\n\
+                 ConPTY on Windows), so tools that drop colors and progress bars when\n\
+                 piped keep them;\n\
+                 stdout and stderr arrive merged, as on a terminal.\n\
+                 \n\
+                 --changed-files REF hands each project the files changed since REF (committed,\n\
+                 uncommitted, and untracked; deletions left out), relative to the project:\n\
+                 a {changed_files} argument expands to one argument per file, and\n\
+                 METAREPO_CHANGED_FILES holds them one per line. Projects with no changes\n\
+                 are skipped.\n\
+                 \n\
+                 @NAME runs a command line from the exec_templates block in .meta (or an\n\
+                 extends fragment, or the user config), with any further arguments\n\
+                 appended. In its arguments {name}, {path}, {branch}, and {url} are filled\n\
+                 per project, and any other {key} from the project's env block, then the\n\
+                 environment (as key or KEY); a missing value stops the run before it\n\
+                 starts. --list-templates shows the templates.\n\
+                 \n\
+                 Each project's env block from .meta is set in its command's environment,\n\
+                 with enc: values decrypted first (see meta config encrypt); a value that\n\
+                 cannot be decrypted stops the run before it starts. Template placeholders\n\
+                 only take plain env values, so secrets stay off the command line.\n\
+                 \n\
+                 Exit codes: 0 when every project succeeds, 1 when all fail, and 2 when some\n\
+                 fail. --min-success PCT (or min_success.exec in .meta) exits 0 as long as at\n\
+                 least PCT percent of the projects succeed. `meta last` shows the summary of\n\
+                 the most recent run.\n\
+                 \n\
+                 With notify.on_complete_secs set in .meta, a run that takes at least that\n\
+                 long rings the terminal bell when it finishes (and, with notify.desktop,\n\
+                 shows a desktop notification).\n\
                  \n\
                  Examples:\n  \
                    meta exec --all git status\n  \
                    meta exec -p doop npm install\n  \
-                   meta exec --git-only --parallel git pull",
+                   meta exec --projects 'services/*' --strict npm test\n  \
+                   meta exec --git-only --parallel git pull\n  \
+                   meta exec -j 4 --output silent cargo check\n  \
+                   meta exec --tag backend --existing-only cargo test\n  \
+                   meta exec --parallel --only api --log-file test.log npm test\n  \
+                   meta exec --changed-files origin/main npx eslint {changed_files}\n  \
+                   meta exec --all @docker-build --no-cache",
             ))
             .version(env!("CARGO_PKG_VERSION"))
             .allow_external_subcommands(true)
@@ -339,6 +739,33 @@ impl MetaPlugin for ExecPlugin {
                     .help("Comma-separated list of specific projects")
                     .value_name("PROJECTS"),
             )
+            .arg(
+                clap::Arg::new("group")
+                    .long("group")
+                    .help("Run in the members of a project group (repeatable)")
+                    .value_name("GROUP")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("tag")
+                    .long("tag")
+                    .help("Only run in projects with this tag in .meta (repeatable)")
+                    .value_name("TAG")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("exclude-tag")
+                    .long("exclude-tag")
+                    .help("Skip projects with this tag in .meta (repeatable)")
+                    .value_name("TAG")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("strict")
+                    .long("strict")
+                    .help("Fail if any project selector matches nothing")
+                    .action(clap::ArgAction::SetTrue),
+            )
             .arg(
                 clap::Arg::new("all")
                     .short('a')
@@ -355,7 +782,7 @@ impl MetaPlugin for ExecPlugin {
             .arg(
                 clap::Arg::new("exclude")
                     .long("exclude")
-                    .help("Exclude projects matching these patterns (comma-separated)")
+                    .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
                     .value_name("PATTERNS"),
             )
             .arg(
@@ -376,6 +803,13 @@ impl MetaPlugin for ExecPlugin {
                     .help("Execute commands in parallel")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                clap::Arg::new("jobs")
+                    .short('j')
+                    .long("jobs")
+                    .help("Run in up to this many projects at once (implies --parallel)")
+                    .value_name("N"),
+            )
             .arg(
                 clap::Arg::new("include-main")
                     .long("include-main")
@@ -399,6 +833,70 @@ impl MetaPlugin for ExecPlugin {
                     .long("streaming")
                     .help("Show output as it happens instead of buffered (legacy behavior)")
                     .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("prefix")
+                    .long("prefix")
+                    .help("Stream output live with a project prefix, ending with each exit status")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with("sections"),
+            )
+            .arg(
+                clap::Arg::new("sections")
+                    .long("sections")
+                    .help("Show one block of output per project, overriding exec.output in .meta")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("output")
+                    .long("output")
+                    .help("Output mode: buffered, prefixed, or silent (default: exec.output)")
+                    .value_name("MODE")
+                    .value_parser(OutputMode::NAMES)
+                    .conflicts_with_all(["prefix", "sections"]),
+            )
+            .arg(
+                clap::Arg::new("only")
+                    .long("only")
+                    .help("Only show output from these projects (comma-separated)")
+                    .action(clap::ArgAction::Append)
+                    .value_name("PROJECTS"),
+            )
+            .arg(
+                clap::Arg::new("timestamps")
+                    .long("timestamps")
+                    .help("Prefix each output line with the time")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("log-file")
+                    .long("log-file")
+                    .help("Also write all output to this file")
+                    .value_name("FILE"),
+            )
+            .arg(
+                clap::Arg::new("pty")
+                    .long("pty")
+                    .help("Run commands on a pseudo-terminal to keep colors and progress bars")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("min-success")
+                    .long("min-success")
+                    .help("Exit 0 when at least this percentage of projects succeed")
+                    .value_name("PCT"),
+            )
+            .arg(
+                clap::Arg::new("list-templates")
+                    .long("list-templates")
+                    .help("List the exec_templates from .meta and exit")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("changed-files")
+                    .long("changed-files")
+                    .help("Pass each project's files changed since this ref ({changed_files}, METAREPO_CHANGED_FILES)")
+                    .value_name("REF"),
             );
 
         app.subcommand(exec_cmd)
@@ -429,3 +927,96 @@ impl Default for ExecPlugin {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exec_matches(args: &[&str]) -> ArgMatches {
+        let app = ExecPlugin::new().register_commands(clap::Command::new("meta"));
+        let matches = app
+            .try_get_matches_from(["meta", "exec"].into_iter().chain(args.iter().copied()))
+            .unwrap();
+        matches.subcommand_matches("exec").unwrap().clone()
+    }
+
+    #[test]
+    fn output_mode_and_jobs_come_from_flags_then_config() {
+        let config: MetaConfig =
+            serde_json::from_str(r#"{"exec": {"output": "prefix"}, "parallelism": 3}"#).unwrap();
+        let mode =
+            |args: &[&str], config: &MetaConfig| output_mode(&exec_matches(args), config).unwrap();
+        assert_eq!(mode(&["pwd"], &config), OutputMode::Prefixed);
+        assert_eq!(mode(&["--sections", "pwd"], &config), OutputMode::Buffered);
+        assert_eq!(
+            mode(&["--output", "silent", "pwd"], &config),
+            OutputMode::Silent
+        );
+        assert_eq!(mode(&["pwd"], &MetaConfig::default()), OutputMode::Buffered);
+
+        let typo: MetaConfig = serde_json::from_str(r#"{"exec": {"output": "prefixd"}}"#).unwrap();
+        let err = output_mode(&exec_matches(&["pwd"]), &typo)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("'prefixd'") && err.contains("buffered, prefixed, silent"),
+            "{err}"
+        );
+
+        let jobs = |args: &[&str]| job_count(&exec_matches(args), &config).unwrap();
+        assert_eq!(jobs(&["pwd"]), 1);
+        assert_eq!(jobs(&["--parallel", "pwd"]), 3);
+        assert_eq!(jobs(&["-j", "5", "pwd"]), 5);
+        assert!(job_count(&exec_matches(&["-j", "0", "pwd"]), &config).is_err());
+    }
+
+    #[test]
+    fn group_selection_honors_git_only_and_existing_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path();
+        std::fs::create_dir_all(base.join("api/.git")).unwrap();
+        std::fs::create_dir_all(base.join("docs")).unwrap();
+        let runtime = RuntimeConfig {
+            meta_config: serde_json::from_str(
+                r#"{
+                    "projects": {
+                        "api": "local:api",
+                        "docs": "local:docs",
+                        "web": "local:web"
+                    },
+                    "groups": { "g": { "projects": ["api", "docs", "web"] } }
+                }"#,
+            )
+            .unwrap(),
+            working_dir: base.to_path_buf(),
+            meta_file_path: Some(base.join(".meta")),
+            experimental: false,
+            non_interactive: None,
+            scope_workspace: false,
+            profile: None,
+            settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
+        };
+        let selected = |args: &[&str]| {
+            selected_projects(
+                &exec_matches(args),
+                &runtime,
+                &runtime.meta_config,
+                base,
+                None,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(selected(&["--group", "g", "pwd"]), ["api", "docs", "web"]);
+        assert_eq!(
+            selected(&["--group", "g", "--existing-only", "pwd"]),
+            ["api", "docs"]
+        );
+        assert_eq!(selected(&["--group", "g", "--git-only", "pwd"]), ["api"]);
+        assert_eq!(
+            selected(&["-p", "docs", "--git-only", "pwd"]),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Named command templates for `meta exec @<name>`.
+//!
+//! The `exec_templates` block in `.meta` (or a shared `extends` fragment, or
+//! the user config) maps names to command lines:
+//!
+//! ```json
+//! "exec_templates": {
+//!   "docker-build": "docker build -t {registry}/{name}:{branch} ."
+//! }
+//! ```
+//!
+//! `meta exec @docker-build` runs the command in each selected project,
+//! with any further arguments appended. Placeholders in the arguments are
+//! filled per project: `{name}`, `{path}`, `{branch}` (the checked-out
+//! branch), and `{url}`; any other `{key}` comes from the project's `env`
+//! block in `.meta`, then from the environment, as `key` or `KEY`.
+//! `{changed_files}` is left for `--changed-files` (see [`super::changed`]).
+
+use super::changed::CHANGED_FILES_PLACEHOLDER;
+use super::ProjectInfo;
+use crate::plugins::shared::secrets;
+use anyhow::Result;
+use metarepo_core::{MetaConfig, ProjectEntry};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Prefix that makes an exec command a template name.
+pub const TEMPLATE_PREFIX: char = '@';
+
+/// The command line of template `name` split into words, with `extra`
+/// appended.
+pub fn resolve(config: &MetaConfig, name: &str, extra: &[&str]) -> Result<Vec<String>> {
+    let templates = config.exec_templates.as_ref();
+    let Some(template) = templates.and_then(|t| t.get(name)) else {
+        let mut known: Vec<&String> = templates.into_iter().flat_map(|t| t.keys()).collect();
+        known.sort();
+        if known.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No exec template '{}': there is no exec_templates block in .meta",
+                name
+            ));
+        }
+        let known: Vec<&str> = known.iter().map(|k| k.as_str()).collect();
+        return Err(anyhow::anyhow!(
+            "No exec template '{}'. Templates: {}",
+            name,
+            known.join(", ")
+        ));
+    };
+    let mut words = shlex::split(template).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Failed to parse exec template '{}' (unbalanced quotes?): {}",
+            name,
+            template
+        )
+    })?;
+    if words.is_empty() {
+        return Err(anyhow::anyhow!("Exec template '{}' is empty", name));
+    }
+    words.extend(extra.iter().map(|s| s.to_string()));
+    Ok(words)
+}
+
+/// Fills template placeholders for each project.
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    placeholder: Regex,
+    /// The plain `env` values of each project, by project name. Encrypted
+    /// values are left out so they never end up on a command line.
+    env: HashMap<String, HashMap<String, String>>,
+}
+
+impl TemplateVars {
+    pub fn new(config: &MetaConfig) -> Self {
+        let env = config
+            .projects
+            .iter()
+            .filter_map(|(name, entry)| match entry {
+                ProjectEntry::Metadata(metadata) => Some((
+                    name.clone(),
+                    metadata
+                        .env
+                        .iter()
+                        .filter(|(_, value)| !secrets::is_encrypted(value))
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .collect(),
+                )),
+                ProjectEntry::Url(_) => None,
+            })
+            .collect();
+        Self {
+            placeholder: Regex::new(r"\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap(),
+            env,
+        }
+    }
+
+    fn lookup(&self, key: &str, project: &ProjectInfo) -> Option<String> {
+        match key {
+            "name" => return Some(project.name.clone()),
+            "path" => return Some(project.path.display().to_string()),
+            "url" => return Some(project.repo_url.clone()),
+            "branch" => return current_branch(project),
+            _ => {}
+        }
+        let upper = key.to_uppercase();
+        let env = self.env.get(&project.name);
+        env.and_then(|env| env.get(key).or_else(|| env.get(&upper)).cloned())
+            .or_else(|| std::env::var(key).ok())
+            .or_else(|| std::env::var(&upper).ok())
+    }
+
+    /// Fail, before anything runs, when a placeholder in `args` has no value
+    /// in one of `projects`.
+    pub fn check(&self, args: &[&str], projects: &[ProjectInfo]) -> Result<()> {
+        for project in projects.iter().filter(|p| p.exists) {
+            for arg in args {
+                for caps in self.placeholder.captures_iter(arg) {
+                    if &caps[0] != CHANGED_FILES_PLACEHOLDER
+                        && self.lookup(&caps[1], project).is_none()
+                    {
+                        return Err(anyhow::anyhow!(
+                            "No value for {} in {}: set it in the project's env in .meta, \
+                             or in the environment",
+                            &caps[0],
+                            project.name
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `args` with the placeholders filled for `project`.
+    pub fn fill(&self, args: &[&str], project: &ProjectInfo) -> Vec<String> {
+        args.iter()
+            .map(|arg| {
+                self.placeholder
+                    .replace_all(arg, |caps: &Captures| {
+                        self.lookup(&caps[1], project)
+                            .filter(|_| &caps[0] != CHANGED_FILES_PLACEHOLDER)
+                            .unwrap_or_else(|| caps[0].to_string())
+                    })
+                    .into_owned()
+            })
+            .collect()
+    }
+}
+
+/// The branch checked out in `project`, or none on a detached HEAD.
+fn current_branch(project: &ProjectInfo) -> Option<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .current_dir(&project.path)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_and_fills_templates_per_project() {
+        let config: MetaConfig = serde_json::from_str(
+            r#"{
+                "projects": {
+                    "api": {"url": "git@h:api.git", "env": {"REGISTRY": "ghcr.io/acme"}},
+                    "web": "git@h:web.git"
+                },
+                "exec_templates": {"docker-build": "docker build -t '{registry}/{name}:{branch}' ."}
+            }"#,
+        )
+        .unwrap();
+        let words = resolve(&config, "docker-build", &["--pull"]).unwrap();
+        assert_eq!(
+            words,
+            [
+                "docker",
+                "build",
+                "-t",
+                "{registry}/{name}:{branch}",
+                ".",
+                "--pull"
+            ]
+        );
+        let err = resolve(&config, "nope", &[]).unwrap_err().to_string();
+        assert!(err.contains("Templates: docker-build"), "{}", err);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q", "-b", "main"])
+            .current_dir(tmp.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let project = |name: &str| {
+            ProjectInfo::new(
+                name.to_string(),
+                tmp.path().to_path_buf(),
+                format!("git@h:{}.git", name),
+            )
+        };
+        let vars = TemplateVars::new(&config);
+        let args: Vec<&str> = words[1..].iter().map(String::as_str).collect();
+        vars.check(&args, &[project("api")]).unwrap();
+        assert_eq!(
+            vars.fill(&args, &project("api"))[2],
+            "ghcr.io/acme/api:main"
+        );
+        assert_eq!(
+            vars.fill(&["{changed_files}", "{url}"], &project("web")),
+            ["{changed_files}", "git@h:web.git"]
+        );
+
+        let err = vars
+            .check(&["{no_such_variable_anywhere}"], &[project("web")])
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("{no_such_variable_anywhere} in web"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! `meta export monorepo` — combine the workspace's projects into a single
+//! repository, keeping each project's history.
+//!
+//! Every project is merged in under its workspace path with `git subtree add`
+//! (no squashing), so its commits stay reachable from the new repository.
+//! [`MANIFEST_FILE`] at the root of the output maps each project's old URL to
+//! its new path and the commit it was imported at.
+
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::MetaConfig;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::plugins::shared::{git, gitignore};
+
+pub use self::plugin::ExportPlugin;
+
+mod plugin;
+
+/// Manifest written at the root of an exported monorepo.
+pub const MANIFEST_FILE: &str = "monorepo-manifest.json";
+
+/// One project's place in the exported monorepo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub project: String,
+    /// The project's URL in the workspace config.
+    pub url: String,
+    /// Where its files live in the monorepo.
+    pub path: String,
+    /// The commit its history was imported at.
+    pub commit: String,
+}
+
+/// The contents of [`MANIFEST_FILE`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub projects: Vec<ManifestEntry>,
+}
+
+/// Merge the checked-out history of the repository at `source` into `out`
+/// under `prefix`, returning the imported commit.
+fn import_history(out: &Path, source: &Path, prefix: &str, message: &str) -> Result<String> {
+    let source = source.to_string_lossy();
+    git(out, &["fetch", "--quiet", "--no-tags", &source, "HEAD"])?;
+    let commit = git(out, &["rev-parse", "FETCH_HEAD"])?;
+    git(
+        out,
+        &[
+            "subtree", "add", "-q", "--prefix", prefix, &commit, "-m", message,
+        ],
+    )
+    .map_err(|e| anyhow::anyhow!("git subtree add failed (is git-subtree installed?): {}", e))?;
+    Ok(commit)
+}
+
+/// Export `projects` of the workspace at `base_path` into a new repository at
+/// `out` (see the module docs). With `include_main`, the meta repository's own
+/// history is merged in at the root first and its managed `.gitignore` block
+/// is emptied so the imported projects are tracked.
+pub fn export_monorepo(
+    base_path: &Path,
+    config: &MetaConfig,
+    projects: &[String],
+    out: &Path,
+    include_main: bool,
+) -> Result<()> {
+    if out.exists() && out.read_dir()?.next().is_some() {
+        return Err(anyhow::anyhow!(
+            "Output directory {} is not empty",
+            out.display()
+        ));
+    }
+    std::fs::create_dir_all(out).with_context(|| format!("Failed to create {}", out.display()))?;
+    git(out, &["init", "--quiet"])?;
+    export_into(base_path, config, projects, out, include_main)
+}
+
+/// [`export_monorepo`] into `out`, an empty repository just initialized.
+fn export_into(
+    base_path: &Path,
+    config: &MetaConfig,
+    projects: &[String],
+    out: &Path,
+    include_main: bool,
+) -> Result<()> {
+    git(
+        out,
+        &[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            "Create monorepo",
+        ],
+    )?;
+
+    println!(
+        "\n  {} {}",
+        "📦".bright_blue(),
+        format!("Exporting to {}", out.display()).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+
+    if include_main {
+        git(
+            out,
+            &[
+                "fetch",
+                "--quiet",
+                "--no-tags",
+                &base_path.to_string_lossy(),
+                "HEAD",
+            ],
+        )?;
+        git(
+            out,
+            &[
+                "merge",
+                "--quiet",
+                "--allow-unrelated-histories",
+                "-m",
+                "Import the meta repository",
+                "FETCH_HEAD",
+            ],
+        )?;
+        if gitignore::sync_managed_entries(out, &[])? {
+            git(
+                out,
+                &["commit", "--quiet", "-am", "Track the imported projects"],
+            )?;
+        }
+        println!(
+            "  {} {}",
+            "✓".green(),
+            "meta repository (root)".bright_white()
+        );
+    }
+
+    // Parents before nested projects, so `services` exists before
+    // `services/api` is added inside it.
+    let mut projects = projects.to_vec();
+    projects.sort();
+
+    let mut manifest = Manifest::default();
+    let mut failed = 0;
+    for name in &projects {
+        let path = base_path.join(name);
+        let url = config.get_project_url(name).unwrap_or_default();
+        let skip = if config.is_vendored(name) {
+            Some("vendored; its files are in the meta repository")
+        } else if !path.exists() {
+            Some("not cloned")
+        } else if !path.join(".git").exists() && !config.is_bare_repo(name) {
+            Some("not a git repository")
+        } else {
+            None
+        };
+        if let Some(reason) = skip {
+            println!(
+                "  {} {} {}",
+                "⏭".yellow(),
+                name.bright_white(),
+                format!("({})", reason).yellow()
+            );
+            continue;
+        }
+
+        let message = format!("Import {} from {}", name, url);
+        match import_history(out, &path, name, &message) {
+            Ok(commit) => {
+                println!(
+                    "  {} {} {}",
+                    "✓".green(),
+                    name.bright_white(),
+                    format!("@ {}", &commit[..commit.len().min(12)]).bright_black()
+                );
+                manifest.projects.push(ManifestEntry {
+                    project: name.clone(),
+                    url,
+                    path: name.clone(),
+                    commit,
+                });
+            }
+            Err(e) => {
+                println!(
+                    "  {} {} {}",
+                    "✗".red(),
+                    name.bright_white(),
+                    e.to_string().red()
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    std::fs::write(
+        out.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)? + "\n",
+    )?;
+    git(out, &["add", MANIFEST_FILE])?;
+    git(out, &["commit", "--quiet", "-m", "Add monorepo manifest"])?;
+
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} projects imported, {} failed",
+        "Summary:".bright_black(),
+        manifest.projects.len().to_string().green(),
+        if failed > 0 {
+            failed.to_string().red()
+        } else {
+            failed.to_string().bright_black()
+        }
+    );
+    println!(
+        "  {} {}",
+        "ℹ".bright_black(),
+        format!("Old URLs map to new paths in {}", MANIFEST_FILE).dimmed()
+    );
+    println!();
+
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "{} project(s) could not be imported",
+            failed
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ProjectEntry;
+    use std::process::Command;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn exports_projects_with_history_and_a_manifest() {
+        // git-subtree ships with most git installs, but not all.
+        let subtree = Command::new("git").args(["subtree", "-h"]).output();
+        if !subtree.is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains("split")) {
+            return;
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("workspace");
+        let mut config = MetaConfig::default();
+        for name in ["web", "api"] {
+            let dir = root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            run(&dir, &["init", "-q"]);
+            std::fs::write(dir.join("README.md"), name).unwrap();
+            run(&dir, &["add", "."]);
+            run(&dir, &["commit", "-qm", &format!("{} first", name)]);
+            config.projects.insert(
+                name.to_string(),
+                ProjectEntry::Url(format!("git@example.com:{}.git", name)),
+            );
+        }
+        config.projects.insert(
+            "docs".to_string(),
+            ProjectEntry::Url("git@example.com:docs.git".to_string()),
+        );
+
+        // The export commits in a fresh repository, which gets its identity
+        // from its own config here rather than the user's.
+        let out = tmp.path().join("mono");
+        std::fs::create_dir_all(&out).unwrap();
+        run(&out, &["init", "-q"]);
+        run(&out, &["config", "user.name", "t"]);
+        run(&out, &["config", "user.email", "t@t"]);
+        let projects = vec!["web".to_string(), "api".to_string(), "docs".to_string()];
+        export_into(&root, &config, &projects, &out, false).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(out.join("api/README.md")).unwrap(),
+            "api"
+        );
+        let log = git(&out, &["log", "--format=%s"]).unwrap();
+        assert!(log.contains("api first") && log.contains("web first"));
+
+        let manifest: Manifest =
+            serde_json::from_str(&std::fs::read_to_string(out.join(MANIFEST_FILE)).unwrap())
+                .unwrap();
+        let paths: Vec<_> = manifest.projects.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, ["api", "web"]);
+        assert_eq!(manifest.projects[0].url, "git@example.com:api.git");
+
+        // The output must be empty.
+        assert!(export_monorepo(&root, &config, &projects, &out, false).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use super::export_monorepo;
+use crate::plugins::shared::selection;
+use anyhow::Result;
+use clap::ArgMatches;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+use std::path::PathBuf;
+
+/// ExportPlugin for converting the workspace to other layouts
+pub struct ExportPlugin;
+
+impl ExportPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("export")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Export the workspace to other repository layouts")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Export the workspace to other repository layouts.\n\
+                 \n\
+                 `meta export monorepo` combines the projects into one repository with\n\
+                 their histories intact, for teams consolidating a polyrepo.",
+            )
+            .command(
+                command("monorepo")
+                    .about("Combine the projects into one repository, keeping history")
+                    .help_description(
+                        "Combine the workspace's projects into a new monorepo.\n\
+                         \n\
+                         Creates a repository at --out and merges each project's checked-out\n\
+                         history into it under the project's workspace path with\n\
+                         `git subtree add` (not squashed). monorepo-manifest.json at the root\n\
+                         maps each project's old URL to its new path and imported commit.\n\
+                         \n\
+                         Name projects (or globs) to export only those; --exclude and the\n\
+                         workspace default_excludes apply as elsewhere. Projects that are not\n\
+                         cloned or are vendored are skipped. --include-main also merges the\n\
+                         meta repository's own history at the root and empties its managed\n\
+                         .gitignore block so the imported projects are tracked.\n\
+                         \n\
+                         --out must not exist or be empty. Requires git-subtree.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta export monorepo --out ../mono\n\
+                           meta export monorepo --out ../mono --include-main\n\
+                           meta export monorepo 'services/*' --out ../services-mono",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to export (names, aliases, or globs)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("out")
+                            .long("out")
+                            .help("Directory for the new repository")
+                            .required(true)
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("include-main")
+                            .long("include-main")
+                            .help("Also merge the meta repository's history at the root"),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    ),
+            )
+            .handler("monorepo", handle_monorepo)
+            .build()
+    }
+}
+
+/// Handler for the monorepo command
+fn handle_monorepo(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    // Named projects, or the whole workspace as with `--all`.
+    let projects = match selection::explicit_projects(matches, config)? {
+        Some(mut selected) => {
+            selection::apply_excludes(matches, config, &mut selected, true);
+            selected
+        }
+        None => selection::all_projects(matches, config),
+    };
+    let out = config
+        .working_dir
+        .join(PathBuf::from(matches.get_one::<String>("out").unwrap()));
+    export_monorepo(
+        &base_path,
+        &config.meta_config,
+        &projects,
+        &out,
+        matches.get_flag("include-main"),
+    )
+}
+
+impl MetaPlugin for ExportPlugin {
+    fn name(&self) -> &str {
+        "export"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for ExportPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Export the workspace to other repository layouts")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for ExportPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
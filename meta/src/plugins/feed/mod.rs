@@ -0,0 +1,601 @@
+//! `meta feed` — what happened across the workspace lately, as one stream,
+//! newest first: commits that landed on each project's default branch, new
+//! tags, branches not seen before, and (with `--remote`) GitHub releases.
+//!
+//! Everything but releases is read from the local repositories, so the feed
+//! shows what the last fetch brought in. Each view leaves a mark in
+//! `.meta-state/feed.json`: the commit each default branch was at and the
+//! tags and branches each project had. The next `meta feed` lists what came
+//! after that mark, however old its commit dates, so work pushed late still
+//! shows up. Without a mark (and with `--since`) dates decide.
+//!
+//! Issue references in branch names and summaries (see `issue_trackers` in
+//! `.meta`) are shown as links and listed in the JSON output.
+
+use anyhow::{Context, Result};
+use colored::{ColoredString, Colorize};
+use metarepo_core::{api, MetaConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::plugins::project::github_slug;
+use crate::plugins::shared::issues::{IssueLinker, IssueRef};
+use crate::plugins::shared::{detect_default_branch, git, state};
+
+pub use self::plugin::FeedPlugin;
+
+mod plugin;
+
+/// State file with the last view.
+pub const FEED_FILE: &str = "feed.json";
+
+/// Window of the first feed in a workspace.
+pub const FIRST_WINDOW: &str = "2 weeks ago";
+
+/// Events shown when no `--limit` is given.
+pub const DEFAULT_LIMIT: usize = 50;
+
+/// Separates the fields of one `--format` line.
+const FIELD_SEP: char = '\x1f';
+
+/// One project as it was when the feed was last viewed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectMark {
+    /// The commit the default branch was at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub branches: BTreeSet<String>,
+}
+
+/// The contents of [`FEED_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeedState {
+    /// When the feed was last shown, Unix seconds.
+    #[serde(default)]
+    pub last_viewed: Option<u64>,
+    /// Each project at that time.
+    #[serde(default)]
+    pub projects: BTreeMap<String, ProjectMark>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Release,
+    Tag,
+    Branch,
+    Commit,
+}
+
+impl EventKind {
+    fn label(self) -> ColoredString {
+        match self {
+            EventKind::Release => "release".green().bold(),
+            EventKind::Tag => "tag    ".yellow(),
+            EventKind::Branch => "branch ".cyan(),
+            EventKind::Commit => "commit ".normal(),
+        }
+    }
+}
+
+/// One entry of the feed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FeedEvent {
+    /// Unix seconds.
+    pub timestamp: u64,
+    pub project: String,
+    pub kind: EventKind,
+    /// Short hash, tag, branch, or release tag.
+    pub name: String,
+    /// Commit subject, tag message, subject of the branch tip, or release
+    /// title.
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl FeedEvent {
+    /// The issue references in a branch's name and in the summary.
+    pub fn issues(&self, config: &MetaConfig, linker: &IssueLinker) -> Vec<IssueRef> {
+        let name = match self.kind {
+            EventKind::Branch => self.name.as_str(),
+            _ => "",
+        };
+        linker.references_in(config, &self.project, &[name, &self.summary])
+    }
+}
+
+/// A tag or branch, with the time it was made (for a branch, of its last
+/// commit), the tagger of an annotated tag, and its subject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RefTip {
+    name: String,
+    timestamp: u64,
+    author: Option<String>,
+    subject: String,
+}
+
+/// Unix seconds for `since`, any date `git log --since` accepts, as git in
+/// the repository at `dir` reads it.
+pub fn resolve_since(dir: &Path, since: &str) -> Result<u64> {
+    git(dir, &["rev-parse", &format!("--since={}", since)])?
+        .trim()
+        .strip_prefix("--max-age=")
+        .and_then(|s| s.parse().ok())
+        .with_context(|| format!("Cannot read --since '{}'", since))
+}
+
+/// The default branch's ref at `path` and the commit it is at: origin's
+/// branch when it was fetched, else the local one.
+fn default_head(path: &Path, branch: &str) -> Option<(String, String)> {
+    [
+        format!("refs/remotes/origin/{}", branch),
+        format!("refs/heads/{}", branch),
+    ]
+    .into_iter()
+    .find_map(|reference| {
+        let commit = git(path, &["rev-parse", "--verify", "--quiet", &reference]).ok()?;
+        Some((reference, commit.trim().to_string()))
+    })
+}
+
+/// Commits that landed on `reference` at `path`: those after `last_head`,
+/// else (or when history was rewritten past it) those committed since
+/// `since`. Only first-parent history counts, so a merged pull request is
+/// one entry.
+fn landed_commits(
+    path: &Path,
+    project: &str,
+    reference: &str,
+    last_head: Option<&str>,
+    since: u64,
+) -> Result<Vec<FeedEvent>> {
+    let format = format!("--format=%h{0}%ct{0}%aN{0}%s", FIELD_SEP);
+    let mut args = vec![
+        "log".to_string(),
+        "--first-parent".to_string(),
+        "--use-mailmap".to_string(),
+        format,
+    ];
+    match last_head
+        .filter(|head| git(path, &["merge-base", "--is-ancestor", head, reference]).is_ok())
+    {
+        Some(head) => args.push(format!("{}..{}", head, reference)),
+        None => {
+            args.push(format!("--max-age={}", since));
+            args.push(reference.to_string());
+        }
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    Ok(git(path, &args)?
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(4, FIELD_SEP).collect();
+            let [hash, timestamp, author, subject] = fields[..] else {
+                return None;
+            };
+            Some(FeedEvent {
+                timestamp: timestamp.parse().ok()?,
+                project: project.to_string(),
+                kind: EventKind::Commit,
+                name: hash.to_string(),
+                summary: subject.to_string(),
+                author: Some(author.to_string()),
+                url: None,
+            })
+        })
+        .collect())
+}
+
+/// The refs under `refs` at `path`, named without `prefix`. Symbolic refs,
+/// such as origin/HEAD, are left out.
+fn list_refs(path: &Path, refs: &str, prefix: &str) -> Result<Vec<RefTip>> {
+    let listed = git(
+        path,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)%1f%(symref)%1f%(creatordate:unix)%1f%(taggername)%1f%(subject)",
+            refs,
+        ],
+    )?;
+    Ok(listed
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(5, FIELD_SEP).collect();
+            let [name, symref, timestamp, tagger, subject] = fields[..] else {
+                return None;
+            };
+            if !symref.is_empty() {
+                return None;
+            }
+            Some(RefTip {
+                name: name.strip_prefix(prefix).unwrap_or(name).to_string(),
+                timestamp: timestamp.parse().ok()?,
+                author: (!tagger.is_empty()).then(|| tagger.to_string()),
+                subject: subject.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// The tags at `path`. Annotated tags are dated by their tagger, others by
+/// the commit they point at.
+fn tags(path: &Path) -> Result<Vec<RefTip>> {
+    list_refs(path, "refs/tags", "")
+}
+
+/// The branches at `path`: origin's when it has any, else the local ones.
+fn branches(path: &Path) -> Result<Vec<RefTip>> {
+    let remote = list_refs(path, "refs/remotes/origin", "origin/")?;
+    if !remote.is_empty() {
+        return Ok(remote);
+    }
+    list_refs(path, "refs/heads", "")
+}
+
+/// Events for the refs in `tips` that are new: not among those `seen` at the
+/// last view or, with no record of them, made since `since`. The ref named
+/// `skip` (the default branch) never is.
+fn new_refs(
+    project: &str,
+    kind: EventKind,
+    tips: &[RefTip],
+    seen: Option<&BTreeSet<String>>,
+    since: u64,
+    skip: Option<&str>,
+) -> Vec<FeedEvent> {
+    tips.iter()
+        .filter(|tip| Some(tip.name.as_str()) != skip)
+        .filter(|tip| match seen {
+            Some(seen) => !seen.contains(&tip.name),
+            None => tip.timestamp >= since,
+        })
+        .map(|tip| FeedEvent {
+            timestamp: tip.timestamp,
+            project: project.to_string(),
+            kind,
+            name: tip.name.clone(),
+            summary: tip.subject.clone(),
+            author: tip.author.clone(),
+            url: None,
+        })
+        .collect()
+}
+
+/// The local events of the project at `path`: what came after its `mark`
+/// from the last view, or without one what happened since `since`. Returns
+/// them with the project's new mark.
+pub fn project_feed(
+    path: &Path,
+    project: &str,
+    mark: Option<&ProjectMark>,
+    since: u64,
+) -> Result<(Vec<FeedEvent>, ProjectMark)> {
+    let default_branch = detect_default_branch(path).unwrap_or_else(|_| "main".to_string());
+    let head = default_head(path, &default_branch);
+    let mut events = match &head {
+        Some((reference, _)) => landed_commits(
+            path,
+            project,
+            reference,
+            mark.and_then(|m| m.head.as_deref()),
+            since,
+        )?,
+        None => Vec::new(),
+    };
+    let tags = tags(path)?;
+    let branches = branches(path)?;
+    events.extend(new_refs(
+        project,
+        EventKind::Tag,
+        &tags,
+        mark.map(|m| &m.tags),
+        since,
+        None,
+    ));
+    events.extend(new_refs(
+        project,
+        EventKind::Branch,
+        &branches,
+        mark.map(|m| &m.branches),
+        since,
+        Some(&default_branch),
+    ));
+    let names = |tips: Vec<RefTip>| tips.into_iter().map(|tip| tip.name).collect();
+    let mark = ProjectMark {
+        head: head.map(|(_, commit)| commit),
+        tags: names(tags),
+        branches: names(branches),
+    };
+    Ok((events, mark))
+}
+
+/// Unix seconds for a UTC time as GitHub writes it, `2024-06-01T09:30:00Z`.
+fn parse_utc(text: &str) -> Option<u64> {
+    let (date, time) = text.strip_suffix('Z')?.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let mut time = time.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    // Days-from-civil (Howard Hinnant's algorithm), the inverse of
+    // `state::format_timestamp`.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+/// Published releases in a GitHub releases listing since `since`.
+fn parse_releases(project: &str, body: &str, since: u64) -> Result<Vec<FeedEvent>> {
+    let listing: Vec<serde_json::Value> =
+        serde_json::from_str(body).context("Unexpected releases response")?;
+    Ok(listing
+        .iter()
+        .filter(|release| release["draft"] != true)
+        .filter_map(|release| {
+            let timestamp = parse_utc(release["published_at"].as_str()?)?;
+            let tag = release["tag_name"].as_str()?;
+            let title = release["name"]
+                .as_str()
+                .filter(|name| !name.is_empty())
+                .unwrap_or(tag);
+            (timestamp >= since).then(|| FeedEvent {
+                timestamp,
+                project: project.to_string(),
+                kind: EventKind::Release,
+                name: tag.to_string(),
+                summary: title.to_string(),
+                author: release["author"]["login"].as_str().map(str::to_string),
+                url: release["html_url"].as_str().map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+/// Releases of the repository at `repo_url` published since `since`, from
+/// the host's API. Only GitHub is asked; other hosts have none.
+pub fn releases(
+    project: &str,
+    repo_url: &str,
+    since: u64,
+    token: Option<&str>,
+) -> Result<Vec<FeedEvent>> {
+    let Some((owner, repo)) = github_slug(repo_url) else {
+        return Ok(Vec::new());
+    };
+    let body = api::client().get(
+        &format!("https://api.github.com/repos/{owner}/{repo}/releases?per_page=30"),
+        token,
+    )?;
+    parse_releases(project, &body, since)
+}
+
+/// All events newest first; at the same second, releases before tags before
+/// branches before commits.
+pub fn merge_feed(events: Vec<Vec<FeedEvent>>) -> Vec<FeedEvent> {
+    let mut feed: Vec<FeedEvent> = events.into_iter().flatten().collect();
+    feed.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.project.cmp(&b.project))
+    });
+    feed
+}
+
+/// Print `events`, one per line, under a header naming where the feed
+/// starts, with issue references linked.
+pub fn print_feed(
+    events: &[FeedEvent],
+    header: &str,
+    hidden: usize,
+    config: &MetaConfig,
+    linker: &IssueLinker,
+) {
+    if events.is_empty() {
+        println!("Nothing new {}.", header);
+        return;
+    }
+    println!("{}:\n", format!("New {}", header).bold());
+    let width = events.iter().map(|e| e.project.len()).max().unwrap_or(0);
+    for event in events {
+        let mut line = format!(
+            "  {}  {:<width$}  {} {}",
+            state::format_timestamp(event.timestamp).bright_black(),
+            event.project,
+            event.kind.label(),
+            match event.kind {
+                EventKind::Branch => linker.linkify(config, &event.project, &event.name),
+                _ => event.name.clone(),
+            }
+            .bold(),
+            width = width
+        );
+        if !event.summary.is_empty() && event.summary != event.name {
+            line.push_str(&format!(
+                "  {}",
+                linker.linkify(config, &event.project, &event.summary)
+            ));
+        }
+        if let Some(author) = &event.author {
+            line.push_str(&format!(" {}", format!("({})", author).bright_black()));
+        }
+        println!("{}", line);
+    }
+    if hidden > 0 {
+        println!(
+            "\n  {} older event(s) not shown; --limit shows more.",
+            hidden
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .env("GIT_COMMITTER_DATE", "2024-06-01T09:00:00Z")
+            .env("GIT_AUTHOR_NAME", "Dev")
+            .env("GIT_AUTHOR_EMAIL", "dev@example.com")
+            .env("GIT_COMMITTER_NAME", "Dev")
+            .env("GIT_COMMITTER_EMAIL", "dev@example.com")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn events_list_issues_in_branch_names_and_summaries() {
+        let linker = IssueLinker::new(&[metarepo_core::IssueTracker {
+            pattern: r"[A-Z]+-\d+".to_string(),
+            url: "https://issues.example.com/{id}".to_string(),
+        }])
+        .unwrap();
+        let config = MetaConfig::default();
+        let event = |kind, name: &str, summary: &str| FeedEvent {
+            timestamp: 0,
+            project: "api".to_string(),
+            kind,
+            name: name.to_string(),
+            summary: summary.to_string(),
+            author: None,
+            url: None,
+        };
+        let ids = |event: FeedEvent| -> Vec<String> {
+            event
+                .issues(&config, &linker)
+                .into_iter()
+                .map(|issue| issue.id)
+                .collect()
+        };
+        assert_eq!(
+            ids(event(EventKind::Branch, "feat/ENG-1", "Start ENG-2")),
+            ["ENG-1", "ENG-2"]
+        );
+        // A commit's name is its hash, never an issue.
+        assert_eq!(
+            ids(event(EventKind::Commit, "ABC-1", "Fix ENG-3")),
+            ["ENG-3"]
+        );
+    }
+
+    #[test]
+    fn reads_landed_commits_tags_and_branches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        run(dir, &["init", "-q", "-b", "main"]);
+        run(dir, &["config", "user.email", "dev@example.com"]);
+        run(dir, &["config", "user.name", "Dev"]);
+        run(dir, &["config", "tag.gpgSign", "false"]);
+        run(dir, &["commit", "-q", "--allow-empty", "-m", "first"]);
+        run(dir, &["checkout", "-q", "-b", "feature"]);
+        run(dir, &["commit", "-q", "--allow-empty", "-m", "on feature"]);
+        run(dir, &["checkout", "-q", "main"]);
+        run(
+            dir,
+            &["merge", "-q", "--no-ff", "-m", "Merge feature", "feature"],
+        );
+        run(dir, &["tag", "-a", "v1.0", "-m", "Release 1.0"]);
+        let since = parse_utc("2024-05-01T00:00:00Z").unwrap();
+
+        let (reference, head) = default_head(dir, "main").unwrap();
+        assert_eq!(reference, "refs/heads/main");
+        let commits = landed_commits(dir, "api", &reference, None, since).unwrap();
+        let subjects: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        // The feature commit arrived with the merge, not on its own.
+        assert_eq!(subjects, ["Merge feature", "first"]);
+        assert_eq!(commits[0].author.as_deref(), Some("Dev"));
+        assert!(
+            landed_commits(dir, "api", &reference, None, since + 86_400 * 60)
+                .unwrap()
+                .is_empty()
+        );
+        // After a mark, only later commits count, whatever their dates.
+        let first = git(dir, &["rev-parse", "HEAD^1"]).unwrap();
+        let after = landed_commits(dir, "api", &reference, Some(first.trim()), u64::MAX).unwrap();
+        assert_eq!(after.len(), 1);
+        assert!(landed_commits(dir, "api", &reference, Some(&head), 0)
+            .unwrap()
+            .is_empty());
+
+        let tags = tags(dir).unwrap();
+        let new = new_refs("api", EventKind::Tag, &tags, None, since, None);
+        assert_eq!(new.len(), 1);
+        assert_eq!(
+            (new[0].name.as_str(), new[0].summary.as_str()),
+            ("v1.0", "Release 1.0")
+        );
+        assert_eq!(new[0].author.as_deref(), Some("Dev"));
+
+        let branches = branches(dir).unwrap();
+        let first_view = new_refs(
+            "api",
+            EventKind::Branch,
+            &branches,
+            None,
+            since,
+            Some("main"),
+        );
+        assert_eq!(first_view.len(), 1);
+        assert_eq!(first_view[0].name, "feature");
+        let seen = BTreeSet::from(["main".to_string(), "feature".to_string()]);
+        assert!(new_refs(
+            "api",
+            EventKind::Branch,
+            &branches,
+            Some(&seen),
+            0,
+            Some("main")
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn parses_published_releases() {
+        assert_eq!(parse_utc("1970-01-02T00:00:01Z"), Some(86_401));
+        let at = parse_utc("2024-06-01T09:30:00Z").unwrap();
+        assert_eq!(state::format_timestamp(at), "2024-06-01 09:30");
+
+        let body = r#"[
+            {"tag_name": "v2.0", "name": "", "draft": false,
+             "published_at": "2024-06-03T10:00:00Z", "author": {"login": "dev"},
+             "html_url": "https://github.com/o/r/releases/tag/v2.0"},
+            {"tag_name": "v2.1-rc", "name": "RC", "draft": true, "published_at": null},
+            {"tag_name": "v1.0", "name": "One", "draft": false,
+             "published_at": "2023-01-01T00:00:00Z"}
+        ]"#;
+        let releases = parse_releases("api", body, at).unwrap();
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].summary, "v2.0");
+        assert_eq!(releases[0].author.as_deref(), Some("dev"));
+
+        let merged = merge_feed(vec![
+            releases,
+            vec![FeedEvent {
+                timestamp: at,
+                project: "web".into(),
+                kind: EventKind::Commit,
+                name: "abc1234".into(),
+                summary: "Fix".into(),
+                author: None,
+                url: None,
+            }],
+        ]);
+        assert_eq!(merged[0].kind, EventKind::Release);
+    }
+}
@@ -0,0 +1,238 @@
+//! Plugin wiring for `meta feed`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{
+    merge_feed, print_feed, project_feed, releases, resolve_since, FeedState, DEFAULT_LIMIT,
+    FEED_FILE, FIRST_WINDOW,
+};
+use crate::plugins::exec::ProjectIterator;
+use crate::plugins::project::github_token;
+use crate::plugins::shared::issues::{IssueLinker, WithIssues};
+use crate::plugins::shared::{selection, state};
+use std::collections::BTreeMap;
+
+/// Registers the top-level `meta feed` command.
+pub struct FeedPlugin;
+
+impl FeedPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for FeedPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for FeedPlugin {
+    fn name(&self) -> &str {
+        "feed"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("feed")
+                .about("Show recent activity across the workspace, newest first")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "List what happened in the projects in your directory scope (or the\n\
+                     named projects) as one stream, newest first: commits that landed on\n\
+                     each default branch (merges count once), new tags, and branches not\n\
+                     seen before. --remote adds releases from the GitHub API, using\n\
+                     GITHUB_TOKEN/GH_TOKEN or a token from 'meta auth login github'.\n\
+                     \n\
+                     The feed starts where the last one stopped (the first one covers two\n\
+                     weeks) and then marks itself viewed in .meta-state/feed.json; --since\n\
+                     picks another start, and --peek leaves the mark alone. Commits, tags,\n\
+                     and branches are read from the local repositories, so fetch first to\n\
+                     see what others pushed. Issue references in branch names and summaries\n\
+                     (see issue_trackers in .meta) are shown as links and listed under\n\
+                     \"issues\" in the JSON output.\n\
+                     \n\
+                     Examples:\n  \
+                       meta git update && meta feed\n  \
+                       meta feed --since \"1 month ago\" --remote api web\n  \
+                       meta feed --json --peek\n",
+                ))
+                .arg(
+                    Arg::new(selection::TARGETS_ARG)
+                        .help("Projects to include (names, aliases, or globs)")
+                        .num_args(1..)
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("group")
+                        .long("group")
+                        .help("Select the members of a project group (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("strict")
+                        .long("strict")
+                        .help("Fail if any project selector matches nothing")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("exclude")
+                        .long("exclude")
+                        .help(
+                            "Skip these projects (names, aliases, globs, or tags; comma-separated)",
+                        )
+                        .value_name("PROJECTS"),
+                )
+                .arg(Arg::new("since").long("since").value_name("DATE").help(
+                    "Start of the feed (any date git log --since accepts; default: last viewed)",
+                ))
+                .arg(
+                    Arg::new("remote")
+                        .long("remote")
+                        .help("Include releases from the GitHub API")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Show at most N events (default: 50)"),
+                )
+                .arg(
+                    Arg::new("peek")
+                        .long("peek")
+                        .help("Do not mark the feed as viewed")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the events as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let base_path = config
+            .meta_root()
+            .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+        let mut feed: FeedState = state::load(&base_path, FEED_FILE)?;
+        let mut marks = BTreeMap::new();
+
+        let explicit = selection::explicit_projects(matches, config)?;
+        let mut scope = match &explicit {
+            Some(selected) => selected.clone(),
+            None => config.scoped_project_keys(),
+        };
+        selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+        let projects: Vec<_> = ProjectIterator::new(&config.meta_config, &base_path)
+            .with_scope(&scope)
+            .filter_existing()
+            .filter_git_repos()
+            .collect();
+        let Some(first) = projects.first() else {
+            println!("No cloned projects in scope.");
+            return Ok(());
+        };
+
+        let (since, header) = match (matches.get_one::<String>("since"), feed.last_viewed) {
+            (Some(since), _) => (
+                resolve_since(&first.path, since)?,
+                format!("since {}", since),
+            ),
+            (None, Some(viewed)) => (
+                viewed,
+                format!(
+                    "since you last looked ({} UTC)",
+                    state::format_timestamp(viewed)
+                ),
+            ),
+            (None, None) => (
+                resolve_since(&first.path, FIRST_WINDOW)?,
+                format!("since {}", FIRST_WINDOW),
+            ),
+        };
+        let token = matches.get_flag("remote").then(github_token).flatten();
+
+        // An explicit --since replaces the marks of the last view.
+        let use_marks = matches.get_one::<String>("since").is_none();
+        let mut events = Vec::new();
+        let mut failed = 0;
+        for project in &projects {
+            let mark = feed.projects.get(&project.name).filter(|_| use_marks);
+            match project_feed(&project.path, &project.name, mark, since) {
+                Ok((found, mark)) => {
+                    events.push(found);
+                    marks.insert(project.name.clone(), mark);
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("  {} {}: {}", "✗".red(), project.name, e);
+                }
+            }
+            if matches.get_flag("remote") {
+                match releases(&project.name, &project.repo_url, since, token.as_deref()) {
+                    Ok(found) => events.push(found),
+                    Err(e) => {
+                        failed += 1;
+                        eprintln!("  {} {} releases: {}", "✗".red(), project.name, e);
+                    }
+                }
+            }
+        }
+
+        let mut events = merge_feed(events);
+        let limit = matches
+            .get_one::<usize>("limit")
+            .copied()
+            .unwrap_or(DEFAULT_LIMIT);
+        let hidden = events.len().saturating_sub(limit);
+        events.truncate(limit);
+        let meta = &config.meta_config;
+        let linker = IssueLinker::from_config(meta);
+        if matches.get_flag("json") {
+            let entries: Vec<WithIssues<_>> = events
+                .iter()
+                .map(|event| WithIssues {
+                    entry: event,
+                    issues: event.issues(meta, &linker),
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        } else {
+            print_feed(&events, &header, hidden, meta, &linker);
+        }
+
+        if !matches.get_flag("peek") {
+            feed.last_viewed = Some(state::now_secs());
+            feed.projects.extend(marks);
+            state::save(&base_path, FEED_FILE, &feed)?;
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "could not read activity in {} project(s)",
+                failed
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl BasePlugin for FeedPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Show recent activity across the workspace, newest first")
+    }
+}
@@ -0,0 +1,398 @@
+//! `meta files sync` — write the files listed in the `sync_files` block of
+//! `.meta` into projects, so shared files (CI definitions, lint and editor
+//! configs) live in one place and stay in step.
+//!
+//! Each source is a [minijinja](https://docs.rs/minijinja) template. It sees
+//! `vars` (the block's own variables), `workspace.name` (the workspace
+//! directory's name), `projects` (every project the file is written for), and,
+//! except in `root` files, `project` (the one being written). A project has
+//! `name`, `url`, `tags`, `aliases`, `default_script`, and `env`; `enc:` env
+//! values are left out so they never end up in a file. Using a variable that
+//! does not exist is an error rather than an empty string.
+//!
+//! `{{ secrets:NAME }}` marks a secret the CI system fills in. The name must
+//! be listed in `sync_files.secrets`, and the placeholder is written as
+//! `secret_ref` (by default GitHub Actions' `${{ secrets.NAME }}`); meta
+//! never looks up a value. Other `${{ … }}` expressions need a
+//! `{% raw %}…{% endraw %}` block so the template leaves them alone.
+
+use crate::plugins::shared::secrets;
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::{MetaConfig, ProjectEntry, SyncFile, SyncFileSettings};
+use minijinja::{context, AutoEscape, Environment, UndefinedBehavior};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path};
+
+pub use self::plugin::FilesPlugin;
+
+mod plugin;
+
+const DEFAULT_SECRET_REF: &str = "${{ secrets.{name} }}";
+
+/// What a template sees of a project.
+#[derive(Debug, Serialize)]
+struct ProjectVars {
+    name: String,
+    url: String,
+    tags: Vec<String>,
+    aliases: Vec<String>,
+    default_script: Option<String>,
+    env: BTreeMap<String, String>,
+}
+
+impl ProjectVars {
+    fn new(config: &MetaConfig, name: &str) -> Self {
+        let (aliases, default_script, env) = match config.projects.get(name) {
+            Some(ProjectEntry::Metadata(metadata)) => (
+                metadata.aliases.clone(),
+                metadata.default_script.clone(),
+                metadata
+                    .env
+                    .iter()
+                    .filter(|(_, value)| !secrets::is_encrypted(value))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            ),
+            _ => (Vec::new(), None, BTreeMap::new()),
+        };
+        Self {
+            name: name.to_string(),
+            url: config.get_project_url(name).unwrap_or_default(),
+            tags: config.get_project_tags(name).to_vec(),
+            aliases,
+            default_script,
+            env,
+        }
+    }
+}
+
+/// Replace each `{{ secrets:NAME }}` in `source` with the literal secret
+/// reference, failing on names not listed in `settings.secrets`.
+fn expand_secret_refs(source: &str, settings: &SyncFileSettings) -> Result<String> {
+    let placeholder = Regex::new(r"\{\{-?\s*secrets:\s*([^\s}]*)\s*-?\}\}").unwrap();
+    let valid = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+    let reference = settings.secret_ref.as_deref().unwrap_or(DEFAULT_SECRET_REF);
+    let mut error = None;
+    let expanded = placeholder.replace_all(source, |caps: &Captures| {
+        let name = &caps[1];
+        if !valid.is_match(name) {
+            error.get_or_insert_with(|| format!("'{}' is not a valid secret name", name));
+        } else if !settings.secrets.iter().any(|s| s == name) {
+            error.get_or_insert_with(|| {
+                format!("secret '{}' is not listed in sync_files.secrets", name)
+            });
+        }
+        format!(
+            "{{% raw %}}{}{{% endraw %}}",
+            reference.replace("{name}", name)
+        )
+    });
+    match error {
+        Some(error) => Err(anyhow::anyhow!(error)),
+        None => Ok(expanded.into_owned()),
+    }
+}
+
+/// Render the template `source` (read from `name`). `project` is `None` for
+/// a `root` file.
+fn render(
+    name: &str,
+    source: &str,
+    settings: &SyncFileSettings,
+    workspace: &str,
+    projects: &[ProjectVars],
+    project: Option<&ProjectVars>,
+) -> Result<String> {
+    let source = expand_secret_refs(source, settings).with_context(|| name.to_string())?;
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_keep_trailing_newline(true);
+    env.set_auto_escape_callback(|_| AutoEscape::None);
+    let template = env.template_from_named_str(name, &source)?;
+    let vars: BTreeMap<&String, &String> = settings.vars.iter().collect();
+    let workspace = context! { name => workspace };
+    let rendered = match project {
+        Some(project) => template.render(context! { vars, workspace, projects, project }),
+        None => template.render(context! { vars, workspace, projects }),
+    };
+    rendered.map_err(|e| anyhow::anyhow!("{:#}", e))
+}
+
+/// The projects `file` is written to, sorted: its `projects` selectors, or
+/// every project not left out by default. Disabled projects never get it.
+fn file_projects(config: &MetaConfig, file: &SyncFile) -> Result<Vec<String>> {
+    let mut projects = if file.projects.is_empty() {
+        let excluded = config.default_excluded_keys();
+        config
+            .projects
+            .keys()
+            .filter(|key| !excluded.contains(*key))
+            .cloned()
+            .collect()
+    } else {
+        let selection = config.select_projects(&file.projects);
+        if let Some(selector) = selection.unmatched.first() {
+            return Err(anyhow::anyhow!(
+                "sync_files entry '{}' names '{}', which matches no project",
+                file.source,
+                selector
+            ));
+        }
+        selection.projects
+    };
+    let disabled = config.disabled_project_keys();
+    projects.retain(|key| !disabled.contains(key));
+    projects.sort();
+    Ok(projects)
+}
+
+/// Fail unless `target` stays inside the directory it is written to.
+fn check_target(target: &str) -> Result<()> {
+    let path = Path::new(target);
+    let inside = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if target.is_empty() || !inside {
+        return Err(anyhow::anyhow!(
+            "sync_files target '{}' must be a relative path inside the project",
+            target
+        ));
+    }
+    Ok(())
+}
+
+/// Write every file in the `sync_files` block. `only` limits the projects
+/// written to (and skips `root` files); `excluded` projects are left alone.
+/// With `check`, nothing is written and an error is returned if any file is
+/// out of date.
+pub fn sync_files(
+    base_path: &Path,
+    config: &MetaConfig,
+    only: Option<&[String]>,
+    excluded: &HashSet<String>,
+    check: bool,
+) -> Result<()> {
+    let settings = config.sync_files.clone().unwrap_or_default();
+    if settings.files.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No files to sync. Add a \"sync_files\" block with \"files\" to .meta"
+        ));
+    }
+    let workspace = base_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    println!(
+        "\n  {} {}",
+        "📄".bright_blue(),
+        if check {
+            "Checking synced files".bold()
+        } else {
+            "Syncing files".bold()
+        }
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+
+    let (mut changed, mut current) = (0, 0);
+    for file in &settings.files {
+        check_target(&file.target)?;
+        let source_path = base_path.join(&file.source);
+        let source = std::fs::read_to_string(&source_path)
+            .with_context(|| format!("Failed to read {}", source_path.display()))?;
+        let projects = file_projects(config, file)?;
+        let vars: Vec<ProjectVars> = projects
+            .iter()
+            .map(|name| ProjectVars::new(config, name))
+            .collect();
+
+        // (label, directory written into, the project it is for)
+        let mut outputs = Vec::new();
+        if file.root.unwrap_or(false) {
+            if only.is_none() {
+                outputs.push((".".to_string(), base_path.to_path_buf(), None));
+            }
+        } else {
+            for project in &vars {
+                let named = only.is_none_or(|only| only.contains(&project.name));
+                if named && !excluded.contains(&project.name) {
+                    let dir = base_path.join(&project.name);
+                    outputs.push((project.name.clone(), dir, Some(project)));
+                }
+            }
+        }
+
+        for (label, dir, project) in outputs {
+            let line = |symbol: ColoredString, status: ColoredString| {
+                println!(
+                    "  {} {} {} {}",
+                    symbol,
+                    label.bright_white(),
+                    file.target.bright_black(),
+                    status
+                );
+            };
+            if !dir.is_dir() {
+                line("⏭".yellow(), "(not cloned)".yellow());
+                continue;
+            }
+            let rendered = render(&file.source, &source, &settings, &workspace, &vars, project)?;
+            let target = dir.join(&file.target);
+            if std::fs::read_to_string(&target).ok().as_deref() == Some(rendered.as_str()) {
+                current += 1;
+                line("✓".green(), "up to date".bright_black());
+                continue;
+            }
+            changed += 1;
+            if check {
+                line("✗".red(), "out of date".red());
+            } else {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&target, rendered)?;
+                line("✓".green(), "written".green());
+            }
+        }
+    }
+
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} {}, {} up to date",
+        "Summary:".bright_black(),
+        changed.to_string().yellow(),
+        if check { "out of date" } else { "written" },
+        current.to_string().green()
+    );
+    println!();
+
+    if check && changed > 0 {
+        return Err(anyhow::anyhow!(
+            "{} file(s) are out of date; run 'meta files sync'",
+            changed
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A workspace with projects `api` (tagged, with a plain and an
+    /// encrypted env value) and `web`, and a `ci.yml.j2` template.
+    fn workspace(root: &Path, template: &str) -> MetaConfig {
+        for project in ["api", "web"] {
+            std::fs::create_dir(root.join(project)).unwrap();
+        }
+        std::fs::write(root.join("ci.yml.j2"), template).unwrap();
+        serde_json::from_str(
+            r#"{"projects": {
+                "api": {"url": "git@h:api.git", "tags": ["backend"],
+                        "env": {"PORT": "8080", "TOKEN": "enc:abc"}},
+                "web": "git@h:web.git"
+            }}"#,
+        )
+        .unwrap()
+    }
+
+    fn settings(files: Vec<SyncFile>) -> Option<SyncFileSettings> {
+        Some(SyncFileSettings {
+            vars: [("rust".to_string(), "1.80".to_string())].into(),
+            secrets: vec!["DEPLOY_KEY".to_string()],
+            files,
+            ..Default::default()
+        })
+    }
+
+    fn file(target: &str, projects: &[&str], root: bool) -> SyncFile {
+        SyncFile {
+            source: "ci.yml.j2".to_string(),
+            target: target.to_string(),
+            projects: projects.iter().map(|p| p.to_string()).collect(),
+            root: root.then_some(true),
+        }
+    }
+
+    #[test]
+    fn templates_are_rendered_into_each_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut config = workspace(
+            root,
+            "name: {{ project.name }} ({{ project.tags | join(',') }})\n\
+             rust: {{ vars.rust }}\n\
+             env: {{ project.env | items | map('first') | join(',') }}\n\
+             key: {{ secrets:DEPLOY_KEY }}\n\
+             ref: {% raw %}${{ github.ref }}{% endraw %}\n",
+        );
+        config.sync_files = settings(vec![file(".github/workflows/ci.yml", &[], false)]);
+
+        let none = HashSet::new();
+        assert!(sync_files(root, &config, None, &none, true).is_err());
+        sync_files(root, &config, None, &none, false).unwrap();
+        let api = std::fs::read_to_string(root.join("api/.github/workflows/ci.yml")).unwrap();
+        assert_eq!(
+            api,
+            "name: api (backend)\nrust: 1.80\nenv: PORT\n\
+             key: ${{ secrets.DEPLOY_KEY }}\nref: ${{ github.ref }}\n"
+        );
+        assert!(root.join("web/.github/workflows/ci.yml").is_file());
+        sync_files(root, &config, None, &none, true).unwrap();
+    }
+
+    #[test]
+    fn root_files_see_the_selected_projects() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut config = workspace(
+            root,
+            "{% for p in projects %}{{ p.name }}@{{ p.url }}\n{% endfor %}",
+        );
+        config.sync_files = settings(vec![file("ci.yml", &[], true)]);
+        config.default_excludes = vec!["web".to_string()];
+
+        sync_files(root, &config, None, &HashSet::new(), false).unwrap();
+        let written = std::fs::read_to_string(root.join("ci.yml")).unwrap();
+        assert_eq!(written, "api@git@h:api.git\n");
+        assert!(!root.join("api/ci.yml").exists());
+    }
+
+    #[test]
+    fn unknown_variables_and_secrets_are_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let none = HashSet::new();
+        for (template, expected) in [
+            ("{{ project.nmae }}", "nmae"),
+            ("{{ secrets:NPM_TOKEN }}", "NPM_TOKEN"),
+            ("{{ secrets:npm-token }}", "npm-token"),
+            ("{{ secrets.DEPLOY_KEY }}", "secrets"),
+        ] {
+            std::fs::write(root.join("ci.yml.j2"), template).unwrap();
+            let mut config = MetaConfig::default();
+            config
+                .projects
+                .insert("api".to_string(), ProjectEntry::Url("u".to_string()));
+            std::fs::create_dir_all(root.join("api")).unwrap();
+            config.sync_files = settings(vec![file("ci.yml", &[], false)]);
+            let err = format!(
+                "{:#}",
+                sync_files(root, &config, None, &none, false).unwrap_err()
+            );
+            assert!(err.contains(expected), "{}: {}", template, err);
+            assert!(!root.join("api/ci.yml").exists());
+        }
+    }
+
+    #[test]
+    fn targets_must_stay_inside_the_project() {
+        for target in ["../ci.yml", "/etc/ci.yml", ""] {
+            assert!(check_target(target).is_err(), "{}", target);
+        }
+        assert!(check_target(".github/workflows/ci.yml").is_ok());
+    }
+}
@@ -0,0 +1,151 @@
+use super::sync_files;
+use crate::plugins::shared::selection;
+use anyhow::Result;
+use clap::ArgMatches;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+
+/// FilesPlugin for keeping shared files in step across projects
+pub struct FilesPlugin;
+
+impl FilesPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("files")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Keep shared files in step across projects")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Keep shared files in step across projects.\n\
+                 \n\
+                 `meta files sync` renders the templates listed in the sync_files block\n\
+                 of .meta into each project, so CI definitions and shared configs are\n\
+                 written in one place.",
+            )
+            .command(
+                command("sync")
+                    .about("Render the sync_files templates into the projects")
+                    .help_description(
+                        "Render the templates in the sync_files block of .meta into projects.\n\
+                         \n\
+                         Each entry names a source template (relative to the workspace root)\n\
+                         and a target path inside each project. projects limits an entry to\n\
+                         some projects (names, aliases, or globs); by default it goes to every\n\
+                         project except default_excludes. root writes it once into the\n\
+                         workspace root instead, e.g. for the workspace's own CI.\n\
+                         \n\
+                         Templates use minijinja syntax and see vars (the block's variables),\n\
+                         workspace.name, projects (each with name, url, tags, aliases,\n\
+                         default_script, and env), and project (the one being written). enc:\n\
+                         env values are left out. An unknown variable is an error.\n\
+                         \n\
+                         {{ secrets:NAME }} stands for a secret the CI system provides. NAME\n\
+                         must be listed in sync_files.secrets; it is written as secret_ref\n\
+                         (default: ${{ secrets.NAME }}) and never expanded by meta. Wrap other\n\
+                         ${{ ... }} expressions in {% raw %}...{% endraw %}.\n\
+                         \n\
+                         Name projects (or globs) to write only those; root files are then\n\
+                         skipped. --check writes nothing and exits non-zero when a file is out\n\
+                         of date, for CI.\n\
+                         \n\
+                         Example configuration:\n\
+                         \n\
+                           \"sync_files\": {\n\
+                             \"vars\": { \"rust\": \"1.80\" },\n\
+                             \"secrets\": [\"CARGO_TOKEN\"],\n\
+                             \"files\": [\n\
+                               { \"source\": \"templates/ci.yml\",\n\
+                                 \"target\": \".github/workflows/ci.yml\",\n\
+                                 \"projects\": [\"services/*\"] }\n\
+                             ]\n\
+                           }\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta files sync\n\
+                           meta files sync --check\n\
+                           meta files sync 'services/*'",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to write (names, aliases, or globs)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("check")
+                            .long("check")
+                            .help("Report out-of-date files without writing; fail if any"),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    ),
+            )
+            .handler("sync", handle_sync)
+            .build()
+    }
+}
+
+/// Handler for the sync command
+fn handle_sync(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    let only = selection::explicit_projects(matches, config)?;
+    // Each entry applies default_excludes itself; only --exclude is left.
+    let excluded = selection::excluded_keys(matches, config, true);
+    sync_files(
+        &base_path,
+        &config.meta_config,
+        only.as_deref(),
+        &excluded,
+        matches.get_flag("check"),
+    )
+}
+
+impl MetaPlugin for FilesPlugin {
+    fn name(&self) -> &str {
+        "files"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for FilesPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Keep shared files in step across projects")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for FilesPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,454 @@
+//! `meta git branch create|delete|list` and `meta git checkout`: one branch
+//! name managed across many repositories, for feature work that spans
+//! projects.
+//!
+//! Each target is `(project name, git dir)`: the project directory, or
+//! `<project>/.git` for a bare project. Branch detection is shared with the
+//! worktree commands.
+
+use crate::plugins::shared::branch_policy::check_branch_name;
+use crate::plugins::shared::{detect_default_branch, git, git_backend};
+use crate::plugins::worktree::{
+    branch_has_no_diff, branch_is_merged, check_branch_exists, list_worktrees, resolve_base_ref,
+    BranchStatus,
+};
+use anyhow::Result;
+use colored::*;
+use metarepo_core::{MetaConfig, Table};
+use std::path::{Path, PathBuf};
+
+/// The default branch of the repository at `git_dir`, as a local or
+/// remote-tracking ref that resolves there.
+fn default_base(git_dir: &Path) -> (String, String) {
+    let name = detect_default_branch(git_dir).unwrap_or_else(|_| "main".to_string());
+    let base = resolve_base_ref(git_dir, &name);
+    (name, base)
+}
+
+/// The worktree that has `branch` checked out, if any.
+fn checked_out_at(git_dir: &Path, branch: &str) -> Option<PathBuf> {
+    let full = format!("refs/heads/{}", branch);
+    list_worktrees(git_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|wt| !wt.is_bare && wt.branch == full)
+        .map(|wt| wt.path)
+}
+
+/// Create `branch` in one repository. A branch that exists only on the
+/// remote is created tracking it; otherwise it starts at `from`, or at the
+/// repository's default branch.
+fn create_one(git_dir: &Path, branch: &str, from: Option<&str>) -> Result<String> {
+    match check_branch_exists(git_dir, branch)? {
+        BranchStatus::Local => Ok("already exists".to_string()),
+        BranchStatus::Remote(remote_ref) => {
+            git(
+                git_dir,
+                &["branch", "--quiet", "--track", branch, &remote_ref],
+            )?;
+            Ok(format!("tracking {}", remote_ref))
+        }
+        BranchStatus::NotFound => {
+            let start = match from {
+                Some(from) => from.to_string(),
+                None => default_base(git_dir).1,
+            };
+            git(
+                git_dir,
+                &["branch", "--quiet", "--no-track", branch, &start],
+            )?;
+            Ok(format!("from {}", start))
+        }
+    }
+}
+
+/// Create `branch` in every target, optionally switching the primary
+/// checkout of each (non-bare) repository to it.
+pub fn create_branches(
+    config: &MetaConfig,
+    targets: &[(String, PathBuf)],
+    branch: &str,
+    from: Option<&str>,
+    checkout: bool,
+) -> Result<()> {
+    check_branch_name(config, branch)?;
+    let mut failed = 0;
+    for (name, git_dir) in targets {
+        let result = create_one(git_dir, branch, from).and_then(|note| {
+            if checkout && !config.is_bare_repo(name) {
+                git(git_dir, &["switch", "--quiet", branch])?;
+                return Ok(format!("{}, checked out", note));
+            }
+            Ok(note)
+        });
+        match result {
+            Ok(note) => println!("  {} {} {}", "✓".green(), name, note.bright_black()),
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), name, e);
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "could not create '{}' in {} project(s)",
+            branch,
+            failed
+        ));
+    }
+    Ok(())
+}
+
+/// What switching one checkout to a branch did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Switch {
+    Switched(String),
+    Skipped(String),
+}
+
+fn checkout_one(path: &Path, branch: &str, create: bool, from: Option<&str>) -> Result<Switch> {
+    if git(path, &["symbolic-ref", "--short", "-q", "HEAD"])
+        .ok()
+        .as_deref()
+        == Some(branch)
+    {
+        return Ok(Switch::Switched("already on it".to_string()));
+    }
+    // Untracked files carry over a switch; only tracked changes are at risk.
+    if !git(path, &["status", "--porcelain", "--untracked-files=no"])?.is_empty() {
+        return Ok(Switch::Skipped(
+            "uncommitted changes; commit or stash them first".to_string(),
+        ));
+    }
+    match check_branch_exists(path, branch)? {
+        BranchStatus::Local => {
+            if let Some(other) = checked_out_at(path, branch) {
+                return Ok(Switch::Skipped(format!(
+                    "checked out at {}",
+                    other.display()
+                )));
+            }
+            git(path, &["switch", "--quiet", branch])?;
+            Ok(Switch::Switched("switched".to_string()))
+        }
+        BranchStatus::Remote(remote_ref) => {
+            git(
+                path,
+                &[
+                    "switch",
+                    "--quiet",
+                    "--create",
+                    branch,
+                    "--track",
+                    &remote_ref,
+                ],
+            )?;
+            Ok(Switch::Switched(format!("tracking {}", remote_ref)))
+        }
+        BranchStatus::NotFound if create => {
+            let start = match from {
+                Some(from) => from.to_string(),
+                None => default_base(path).1,
+            };
+            git(
+                path,
+                &[
+                    "switch",
+                    "--quiet",
+                    "--no-track",
+                    "--create",
+                    branch,
+                    &start,
+                ],
+            )?;
+            Ok(Switch::Switched(format!("created from {}", start)))
+        }
+        BranchStatus::NotFound => Ok(Switch::Skipped("no such branch; pass --create".to_string())),
+    }
+}
+
+/// Switch the checkout of every target to `branch`: an existing local
+/// branch, a new one tracking the remote branch, or (with `create`) a new
+/// one started at `from` or the default branch. Checkouts with uncommitted
+/// changes, and bare projects, are skipped and reported rather than failing
+/// the rest.
+pub fn checkout_branches(
+    config: &MetaConfig,
+    targets: &[(String, PathBuf)],
+    branch: &str,
+    create: bool,
+    from: Option<&str>,
+) -> Result<()> {
+    if create {
+        check_branch_name(config, branch)?;
+    }
+    let (mut switched, mut skipped, mut failed) = (0, 0, 0);
+    for (name, path) in targets {
+        let result = if config.is_bare_repo(name) {
+            Ok(Switch::Skipped(
+                "bare; switch branches in its worktrees".to_string(),
+            ))
+        } else {
+            checkout_one(path, branch, create, from)
+        };
+        match result {
+            Ok(Switch::Switched(note)) => {
+                switched += 1;
+                println!("  {} {} {}", "✓".green(), name, note.bright_black());
+            }
+            Ok(Switch::Skipped(reason)) => {
+                skipped += 1;
+                println!("  {} {} {}", "·".yellow(), name, reason.bright_black());
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), name, e);
+            }
+        }
+    }
+    println!(
+        "\nOn '{}' in {} of {} project(s); {} skipped.",
+        branch,
+        switched,
+        targets.len(),
+        skipped
+    );
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "could not switch to '{}' in {} project(s)",
+            branch,
+            failed
+        ));
+    }
+    Ok(())
+}
+
+/// Why a branch was or was not deleted from one repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Deletion {
+    Deleted(String),
+    Skipped(String),
+}
+
+fn delete_one(git_dir: &Path, branch: &str, force: bool) -> Result<Deletion> {
+    if !matches!(check_branch_exists(git_dir, branch)?, BranchStatus::Local) {
+        return Ok(Deletion::Skipped("no local branch".to_string()));
+    }
+    if let Some(path) = checked_out_at(git_dir, branch) {
+        return Ok(Deletion::Skipped(format!(
+            "checked out at {}",
+            path.display()
+        )));
+    }
+    let (base_name, base) = default_base(git_dir);
+    if base_name == branch {
+        return Ok(Deletion::Skipped("it is the default branch".to_string()));
+    }
+    let full = format!("refs/heads/{}", branch);
+    let merged =
+        branch_is_merged(git_dir, &full, &base) || branch_has_no_diff(git_dir, &full, &base);
+    if !merged && !force {
+        return Ok(Deletion::Skipped(format!(
+            "not merged into {}; use --force",
+            base_name
+        )));
+    }
+    // Mergedness was checked against the default branch above; `-d` would
+    // check against HEAD instead.
+    git(git_dir, &["branch", "-D", branch])?;
+    Ok(Deletion::Deleted(if merged {
+        format!("merged into {}", base_name)
+    } else {
+        "unmerged, forced".to_string()
+    }))
+}
+
+/// Delete `branch` wherever it has been merged into the repository's default
+/// branch (or everywhere, with `force`). Checked-out branches are kept.
+pub fn delete_branches(targets: &[(String, PathBuf)], branch: &str, force: bool) -> Result<()> {
+    let (mut deleted, mut failed) = (0, 0);
+    for (name, git_dir) in targets {
+        match delete_one(git_dir, branch, force) {
+            Ok(Deletion::Deleted(note)) => {
+                deleted += 1;
+                println!("  {} {} {}", "✓".green(), name, note.bright_black());
+            }
+            Ok(Deletion::Skipped(reason)) => {
+                println!("  {} {} {}", "·".yellow(), name, reason.bright_black())
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), name, e);
+            }
+        }
+    }
+    println!(
+        "\nDeleted '{}' in {} of {} project(s).",
+        branch,
+        deleted,
+        targets.len()
+    );
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "could not delete '{}' in {} project(s)",
+            branch,
+            failed
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `branch` exists at `git_dir`, read through the configured git
+/// backend, which is faster across many repositories than asking the git
+/// CLI twice per target.
+fn find_branch(git_dir: &Path, branch: &str) -> Result<BranchStatus> {
+    let branches = git_backend().branches(git_dir)?;
+    if branches.local.iter().any(|name| name == branch) {
+        return Ok(BranchStatus::Local);
+    }
+    let suffix = format!("/{}", branch);
+    Ok(branches
+        .remote
+        .into_iter()
+        .find(|name| name.ends_with(&suffix))
+        .map(BranchStatus::Remote)
+        .unwrap_or(BranchStatus::NotFound))
+}
+
+/// Show which targets have `branch`, locally or only on a remote.
+pub fn list_branch(targets: &[(String, PathBuf)], branch: &str) -> Result<()> {
+    let mut table = Table::new(["Project", "Branch", "Notes"])
+        .indent(2)
+        .shrink(2);
+    let mut found = 0;
+    for (name, git_dir) in targets {
+        let (status, notes) = match find_branch(git_dir, branch)? {
+            BranchStatus::Local => {
+                found += 1;
+                let notes = checked_out_at(git_dir, branch)
+                    .map(|path| format!("checked out at {}", path.display()))
+                    .unwrap_or_default();
+                ("local".green(), notes)
+            }
+            BranchStatus::Remote(remote_ref) => {
+                found += 1;
+                ("remote".cyan(), remote_ref)
+            }
+            BranchStatus::NotFound => ("—".bright_black(), String::new()),
+        };
+        table.row([name.bold().to_string(), status.to_string(), notes]);
+    }
+    println!();
+    table.print();
+    println!(
+        "\n'{}' exists in {} of {} project(s).",
+        branch,
+        found,
+        targets.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    fn repo(root: &Path, name: &str) -> PathBuf {
+        let path = root.join(name);
+        std::fs::create_dir(&path).unwrap();
+        run(&path, &["init", "-q", "-b", "main"]);
+        run(&path, &["config", "user.email", "test@example.com"]);
+        run(&path, &["config", "user.name", "Test"]);
+        run(&path, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        path
+    }
+
+    #[test]
+    fn creates_then_deletes_only_merged_branches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let targets = vec![
+            ("api".to_string(), repo(tmp.path(), "api")),
+            ("web".to_string(), repo(tmp.path(), "web")),
+        ];
+        let config = MetaConfig::default();
+        create_branches(&config, &targets, "feature", None, false).unwrap();
+        for (_, dir) in &targets {
+            assert!(matches!(
+                check_branch_exists(dir, "feature").unwrap(),
+                BranchStatus::Local
+            ));
+            assert!(matches!(
+                find_branch(dir, "feature").unwrap(),
+                BranchStatus::Local
+            ));
+            // A longer name ending the same way is another branch.
+            assert!(matches!(
+                find_branch(dir, "ature").unwrap(),
+                BranchStatus::NotFound
+            ));
+        }
+
+        // Give web's branch a commit of its own: it is no longer merged.
+        let web = &targets[1].1;
+        run(web, &["switch", "-q", "feature"]);
+        std::fs::write(web.join("work.txt"), "work").unwrap();
+        run(web, &["add", "."]);
+        run(web, &["commit", "-q", "-m", "work"]);
+        assert!(matches!(
+            delete_one(web, "feature", false).unwrap(),
+            Deletion::Skipped(reason) if reason.starts_with("checked out")
+        ));
+        run(web, &["switch", "-q", "main"]);
+
+        delete_branches(&targets, "feature", false).unwrap();
+        assert!(matches!(
+            check_branch_exists(&targets[0].1, "feature").unwrap(),
+            BranchStatus::NotFound
+        ));
+        assert!(matches!(
+            check_branch_exists(web, "feature").unwrap(),
+            BranchStatus::Local
+        ));
+        assert_eq!(
+            delete_one(web, "feature", true).unwrap(),
+            Deletion::Deleted("unmerged, forced".to_string())
+        );
+    }
+
+    #[test]
+    fn checkout_skips_dirty_repos_and_needs_create_for_new_branches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let api = repo(tmp.path(), "api");
+        std::fs::write(api.join("a.txt"), "a").unwrap();
+        run(&api, &["add", "."]);
+        run(&api, &["commit", "-q", "-m", "a"]);
+
+        assert_eq!(
+            checkout_one(&api, "feature", false, None).unwrap(),
+            Switch::Skipped("no such branch; pass --create".to_string())
+        );
+        assert_eq!(
+            checkout_one(&api, "feature", true, None).unwrap(),
+            Switch::Switched("created from main".to_string())
+        );
+        assert_eq!(
+            checkout_one(&api, "feature", false, None).unwrap(),
+            Switch::Switched("already on it".to_string())
+        );
+
+        std::fs::write(api.join("a.txt"), "edited").unwrap();
+        assert!(matches!(
+            checkout_one(&api, "main", false, None).unwrap(),
+            Switch::Skipped(reason) if reason.starts_with("uncommitted changes")
+        ));
+        run(&api, &["checkout", "--", "a.txt"]);
+        assert_eq!(
+            checkout_one(&api, "main", false, None).unwrap(),
+            Switch::Switched("switched".to_string())
+        );
+    }
+}
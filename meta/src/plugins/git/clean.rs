@@ -0,0 +1,108 @@
+//! `meta git clean`: list, and with `--force` delete, the untracked files of
+//! every project. A dry run unless forced, and never touching what the
+//! workspace `ignore` list names.
+
+use crate::plugins::shared::git;
+use anyhow::Result;
+use std::path::Path;
+
+/// What `git clean` removes beyond untracked files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanOptions {
+    /// Delete rather than list.
+    pub force: bool,
+    /// Untracked directories too (`-d`).
+    pub directories: bool,
+    /// Ignored files too (`-x`).
+    pub ignored: bool,
+}
+
+/// The `git clean` arguments for `options`. Each of `keep` becomes an
+/// exclude pattern, which git honors even with `-x`.
+fn clean_args(options: CleanOptions, keep: &[String]) -> Vec<String> {
+    let mut args = vec![
+        "-c".to_string(),
+        "core.quotePath=false".to_string(),
+        "clean".to_string(),
+        if options.force { "-f" } else { "-n" }.to_string(),
+    ];
+    if options.directories {
+        args.push("-d".to_string());
+    }
+    if options.ignored {
+        args.push("-x".to_string());
+    }
+    for pattern in keep {
+        args.push("-e".to_string());
+        args.push(pattern.clone());
+    }
+    args
+}
+
+/// Run `git clean` at `path` and return the paths it removed, or with a dry
+/// run would remove. Nested repositories are always left alone.
+pub fn clean(path: &Path, options: CleanOptions, keep: &[String]) -> Result<Vec<String>> {
+    let args = clean_args(options, keep);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let prefix = if options.force {
+        "Removing "
+    } else {
+        "Would remove "
+    };
+    Ok(git(path, &args)?
+        .lines()
+        .filter_map(|line| line.strip_prefix(prefix))
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    #[test]
+    fn lists_before_deleting_and_keeps_excluded_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        run(dir, &["init", "-q", "-b", "main"]);
+        std::fs::write(dir.join(".gitignore"), "*.log\nnode_modules/\n").unwrap();
+        std::fs::write(dir.join("new.txt"), "").unwrap();
+        std::fs::write(dir.join("debug.log"), "").unwrap();
+        std::fs::create_dir_all(dir.join("build")).unwrap();
+        std::fs::write(dir.join("build/out.o"), "").unwrap();
+        std::fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.join("node_modules/pkg/index.js"), "").unwrap();
+        let keep = vec!["node_modules".to_string()];
+
+        let plain = CleanOptions::default();
+        assert_eq!(clean(dir, plain, &keep).unwrap(), [".gitignore", "new.txt"]);
+        let everything = CleanOptions {
+            directories: true,
+            ignored: true,
+            ..plain
+        };
+        assert_eq!(
+            clean(dir, everything, &keep).unwrap(),
+            [".gitignore", "build/", "debug.log", "new.txt"]
+        );
+        // The dry runs deleted nothing.
+        assert!(dir.join("build/out.o").exists());
+
+        let removed = clean(
+            dir,
+            CleanOptions {
+                force: true,
+                ..everything
+            },
+            &keep,
+        )
+        .unwrap();
+        assert_eq!(removed.len(), 4);
+        assert!(!dir.join("new.txt").exists() && !dir.join("build").exists());
+        assert!(dir.join("node_modules/pkg/index.js").exists());
+    }
+}
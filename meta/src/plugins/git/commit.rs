@@ -0,0 +1,80 @@
+//! `meta git commit`: one message committed in every checkout that has
+//! something to commit, for changes that span projects.
+
+use crate::plugins::shared::git;
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Replaced by the project name in a commit message.
+pub const PROJECT_PLACEHOLDER: &str = "{project}";
+
+/// `template` with the project name substituted.
+pub fn message_for(template: &str, project: &str) -> String {
+    template.replace(PROJECT_PLACEHOLDER, project)
+}
+
+/// The files a commit at `path` would take: what is staged, plus changes to
+/// tracked files when `all_staged`.
+pub fn pending_files(path: &Path, all_staged: bool) -> Result<Vec<String>> {
+    let mut files: BTreeSet<String> = git(path, &["diff", "--cached", "--name-only"])?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    if all_staged {
+        files.extend(
+            git(path, &["diff", "--name-only"])?
+                .lines()
+                .map(str::to_string),
+        );
+    }
+    Ok(files.into_iter().collect())
+}
+
+/// Commit at `path` (staging tracked changes first when `all_staged`) and
+/// return the new commit's short id. Hooks run as for a plain `git commit`.
+pub fn commit(path: &Path, message: &str, all_staged: bool) -> Result<String> {
+    if all_staged {
+        git(path, &["add", "--update"])?;
+    }
+    git(path, &["commit", "--quiet", "-m", message])?;
+    git(path, &["rev-parse", "--short", "HEAD"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    #[test]
+    fn commits_staged_or_all_tracked_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        run(dir, &["init", "-q", "-b", "main"]);
+        run(dir, &["config", "user.email", "test@example.com"]);
+        run(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        std::fs::write(dir.join("b.txt"), "b").unwrap();
+        run(dir, &["add", "."]);
+        run(dir, &["commit", "-q", "-m", "init"]);
+
+        std::fs::write(dir.join("a.txt"), "a2").unwrap();
+        std::fs::write(dir.join("b.txt"), "b2").unwrap();
+        std::fs::write(dir.join("new.txt"), "new").unwrap();
+        assert!(pending_files(dir, false).unwrap().is_empty());
+        run(dir, &["add", "a.txt"]);
+        assert_eq!(pending_files(dir, false).unwrap(), ["a.txt"]);
+        assert_eq!(pending_files(dir, true).unwrap(), ["a.txt", "b.txt"]);
+
+        let message = message_for("chore({project}): bump", "api");
+        assert_eq!(message, "chore(api): bump");
+        let id = commit(dir, &message, true).unwrap();
+        assert_eq!(git(dir, &["rev-parse", "--short", "HEAD"]).unwrap(), id);
+        assert_eq!(git(dir, &["log", "-1", "--format=%s"]).unwrap(), message);
+        // Untracked files are left for the user to add.
+        assert_eq!(git(dir, &["status", "--porcelain"]).unwrap(), "?? new.txt");
+    }
+}
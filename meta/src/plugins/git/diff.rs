@@ -0,0 +1,151 @@
+//! `meta git diff`: the uncommitted changes of every project, grouped by
+//! project, as a patch, a stat, file names, or JSON.
+
+use crate::plugins::shared::git;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+/// How much of each diff to print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    Patch,
+    Stat,
+    NameOnly,
+}
+
+impl DiffFormat {
+    fn flag(self) -> Option<&'static str> {
+        match self {
+            DiffFormat::Patch => None,
+            DiffFormat::Stat => Some("--stat"),
+            DiffFormat::NameOnly => Some("--name-only"),
+        }
+    }
+}
+
+/// One changed file, from `git diff --numstat`. Binary files have no line
+/// counts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub added: Option<u64>,
+    pub deleted: Option<u64>,
+}
+
+/// The `git diff` arguments shared by every format: unstaged changes, or
+/// with `staged` the index against HEAD. Renames show as a delete and an add
+/// so every path is a plain file name.
+fn diff_args(staged: bool) -> Vec<&'static str> {
+    let mut args = vec!["diff", "--no-renames"];
+    if staged {
+        args.push("--cached");
+    }
+    args
+}
+
+/// The files changed at `path`, with line counts.
+pub fn changed_files(path: &Path, staged: bool) -> Result<Vec<FileChange>> {
+    let mut args = diff_args(staged);
+    args.push("--numstat");
+    Ok(git(path, &args)?
+        .lines()
+        .filter_map(parse_numstat)
+        .collect())
+}
+
+/// Parse a `--numstat` line: `added<TAB>deleted<TAB>path`, with `-` counts
+/// for binary files.
+fn parse_numstat(line: &str) -> Option<FileChange> {
+    let mut fields = line.splitn(3, '\t');
+    let added = fields.next()?;
+    let deleted = fields.next()?;
+    let path = fields.next()?;
+    Some(FileChange {
+        path: path.to_string(),
+        added: added.parse().ok(),
+        deleted: deleted.parse().ok(),
+    })
+}
+
+/// The diff at `path` as git prints it in `format`, colored when `color`.
+pub fn diff_text(path: &Path, staged: bool, format: DiffFormat, color: bool) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(diff_args(staged))
+        .arg(if color {
+            "--color=always"
+        } else {
+            "--color=never"
+        })
+        .args(format.flag())
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    // Only trailing whitespace goes: `--stat` lines start indented.
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    #[test]
+    fn lists_unstaged_or_staged_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        run(dir, &["init", "-q", "-b", "main"]);
+        run(dir, &["config", "user.email", "test@example.com"]);
+        run(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("a.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(dir.join("logo.bin"), [0u8, 1, 2]).unwrap();
+        run(dir, &["add", "."]);
+        run(dir, &["commit", "-q", "-m", "init"]);
+        assert!(changed_files(dir, false).unwrap().is_empty());
+
+        std::fs::write(dir.join("a.txt"), "one\n2\nthree\n").unwrap();
+        std::fs::write(dir.join("logo.bin"), [3u8, 4, 5]).unwrap();
+        assert_eq!(
+            changed_files(dir, false).unwrap(),
+            [
+                FileChange {
+                    path: "a.txt".to_string(),
+                    added: Some(2),
+                    deleted: Some(1),
+                },
+                FileChange {
+                    path: "logo.bin".to_string(),
+                    added: None,
+                    deleted: None,
+                },
+            ]
+        );
+        assert!(changed_files(dir, true).unwrap().is_empty());
+
+        run(dir, &["add", "a.txt"]);
+        let staged = changed_files(dir, true).unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].path, "a.txt");
+        assert_eq!(
+            diff_text(dir, true, DiffFormat::NameOnly, false).unwrap(),
+            "a.txt"
+        );
+        let stat = diff_text(dir, true, DiffFormat::Stat, false).unwrap();
+        assert!(stat.starts_with(" a.txt |"), "{}", stat);
+        let patch = diff_text(dir, true, DiffFormat::Patch, false).unwrap();
+        assert!(patch.contains("+three"), "{}", patch);
+    }
+}
@@ -0,0 +1,248 @@
+//! `meta git divergence`: where each project's branches stand against their
+//! upstreams, as last fetched. Meant as a preflight check: the command fails
+//! when any branch has diverged.
+
+use crate::plugins::shared::git;
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// How a local branch compares with its upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "kebab-case")]
+pub enum Tracking {
+    UpToDate,
+    /// Local commits not on the upstream.
+    Ahead {
+        ahead: usize,
+    },
+    /// Upstream commits not on the branch; a fast-forward away.
+    Behind {
+        behind: usize,
+    },
+    Diverged {
+        ahead: usize,
+        behind: usize,
+    },
+    /// The upstream branch was deleted on the remote.
+    Gone,
+    NoUpstream,
+    /// HEAD is not on a branch.
+    Detached,
+}
+
+impl Tracking {
+    /// Read `%(upstream:track,nobracket)`: empty when in sync, otherwise
+    /// `gone`, `ahead N`, `behind N`, or `ahead N, behind M`.
+    fn parse(track: &str) -> Tracking {
+        if track == "gone" {
+            return Tracking::Gone;
+        }
+        let count = |word: &str| {
+            track
+                .split(", ")
+                .find_map(|part| part.strip_prefix(word)?.trim().parse().ok())
+                .unwrap_or(0)
+        };
+        match (count("ahead"), count("behind")) {
+            (0, 0) => Tracking::UpToDate,
+            (ahead, 0) => Tracking::Ahead { ahead },
+            (0, behind) => Tracking::Behind { behind },
+            (ahead, behind) => Tracking::Diverged { ahead, behind },
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tracking::UpToDate => "up to date",
+            Tracking::Ahead { .. } => "ahead",
+            Tracking::Behind { .. } => "behind",
+            Tracking::Diverged { .. } => "diverged",
+            Tracking::Gone => "gone upstream",
+            Tracking::NoUpstream => "no upstream",
+            Tracking::Detached => "detached",
+        }
+    }
+}
+
+/// One local branch of a project and its standing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BranchTracking {
+    /// Empty for a detached HEAD.
+    pub branch: String,
+    pub upstream: Option<String>,
+    #[serde(flatten)]
+    pub tracking: Tracking,
+}
+
+/// The branches of the repository at `path` against their upstreams: only
+/// the checked-out one unless `all_branches`.
+pub fn branch_tracking(path: &Path, all_branches: bool) -> Result<Vec<BranchTracking>> {
+    // %(HEAD) goes last: git() trims the output, which would eat a leading
+    // blank marker on the first line.
+    let refs = git(
+        path,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)%00%(upstream:short)%00%(upstream:track,nobracket)%00%(HEAD)",
+            "refs/heads",
+        ],
+    )?;
+    let mut branches = Vec::new();
+    for line in refs.lines() {
+        let fields: Vec<&str> = line.split('\0').collect();
+        let [branch, upstream, track, head] = fields[..] else {
+            continue;
+        };
+        if !all_branches && head != "*" {
+            continue;
+        }
+        let (upstream, tracking) = if upstream.is_empty() {
+            (None, Tracking::NoUpstream)
+        } else {
+            (Some(upstream.to_string()), Tracking::parse(track))
+        };
+        branches.push(BranchTracking {
+            branch: branch.to_string(),
+            upstream,
+            tracking,
+        });
+    }
+    if !all_branches && branches.is_empty() {
+        // A branch with no commits yet has no ref to list.
+        let tracking = match git(path, &["symbolic-ref", "--quiet", "--short", "HEAD"]) {
+            Ok(branch) => BranchTracking {
+                branch,
+                upstream: None,
+                tracking: Tracking::NoUpstream,
+            },
+            Err(_) => BranchTracking {
+                branch: String::new(),
+                upstream: None,
+                tracking: Tracking::Detached,
+            },
+        };
+        branches.push(tracking);
+    }
+    Ok(branches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    fn commit(dir: &Path, message: &str) {
+        run(
+            dir,
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                message,
+            ],
+        );
+    }
+
+    #[test]
+    fn classifies_branches_against_their_upstreams() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let clone = tmp.path().join("clone");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        commit(&upstream, "init");
+        for branch in ["ahead", "behind", "diverged", "gone"] {
+            run(&upstream, &["branch", branch]);
+        }
+        run(
+            tmp.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "clone"],
+        );
+        for branch in ["ahead", "behind", "diverged", "gone"] {
+            run(
+                &clone,
+                &[
+                    "branch",
+                    "-q",
+                    "--track",
+                    branch,
+                    &format!("origin/{}", branch),
+                ],
+            );
+        }
+        run(&clone, &["branch", "local"]);
+
+        for branch in ["behind", "diverged"] {
+            run(&upstream, &["checkout", "-q", branch]);
+            commit(&upstream, "upstream");
+        }
+        run(&upstream, &["checkout", "-q", "main"]);
+        run(&upstream, &["branch", "-D", "gone"]);
+        for branch in ["ahead", "diverged"] {
+            run(&clone, &["checkout", "-q", branch]);
+            commit(&clone, "local");
+        }
+        run(&clone, &["fetch", "-q", "--prune"]);
+
+        let current = branch_tracking(&clone, false).unwrap();
+        assert_eq!(
+            current,
+            [BranchTracking {
+                branch: "diverged".into(),
+                upstream: Some("origin/diverged".into()),
+                tracking: Tracking::Diverged {
+                    ahead: 1,
+                    behind: 1
+                },
+            }]
+        );
+
+        let all: Vec<(String, Tracking)> = branch_tracking(&clone, true)
+            .unwrap()
+            .into_iter()
+            .map(|b| (b.branch, b.tracking))
+            .collect();
+        assert_eq!(
+            all,
+            [
+                ("ahead".into(), Tracking::Ahead { ahead: 1 }),
+                ("behind".into(), Tracking::Behind { behind: 1 }),
+                (
+                    "diverged".into(),
+                    Tracking::Diverged {
+                        ahead: 1,
+                        behind: 1
+                    }
+                ),
+                ("gone".into(), Tracking::Gone),
+                ("local".into(), Tracking::NoUpstream),
+                ("main".into(), Tracking::UpToDate),
+            ]
+        );
+
+        run(&clone, &["checkout", "-q", "--detach"]);
+        assert_eq!(
+            branch_tracking(&clone, false).unwrap()[0].tracking,
+            Tracking::Detached
+        );
+        assert_eq!(
+            serde_json::to_value(&current[0]).unwrap(),
+            serde_json::json!({
+                "branch": "diverged",
+                "upstream": "origin/diverged",
+                "state": "diverged",
+                "ahead": 1,
+                "behind": 1,
+            })
+        );
+    }
+}
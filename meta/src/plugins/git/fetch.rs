@@ -0,0 +1,172 @@
+//! `meta git fetch`: fetch every remote of every project, leaving working
+//! trees and local branches alone, and report what arrived: new and pruned
+//! remote branches, and new tags.
+
+use crate::plugins::shared::git;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// How a fetch changed a repository's remote branches and tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefChanges {
+    /// Remote branches that were not there before, as `origin/name`.
+    pub new_branches: Vec<String>,
+    /// Remote branches that moved.
+    pub updated: usize,
+    /// Remote branches that are gone, deleted on the remote.
+    pub pruned: Vec<String>,
+    pub new_tags: Vec<String>,
+}
+
+/// Names shown in a summary before the rest are counted.
+const NAMES_SHOWN: usize = 5;
+
+/// `count noun (a, b, ...)`, or nothing for an empty list.
+fn listed(names: &[String], one: &str, many: &str) -> Option<String> {
+    if names.is_empty() {
+        return None;
+    }
+    let mut shown = names
+        .iter()
+        .take(NAMES_SHOWN)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > NAMES_SHOWN {
+        shown.push_str(&format!(", +{} more", names.len() - NAMES_SHOWN));
+    }
+    let noun = if names.len() == 1 { one } else { many };
+    Some(format!("{} {} ({})", names.len(), noun, shown))
+}
+
+impl RefChanges {
+    pub fn is_empty(&self) -> bool {
+        self == &RefChanges::default()
+    }
+
+    /// One line for the fetch report, such as `1 new branch (origin/x),
+    /// 2 updated`.
+    pub fn describe(&self) -> String {
+        [
+            listed(&self.new_branches, "new branch", "new branches"),
+            listed(&self.new_tags, "new tag", "new tags"),
+            (self.updated > 0).then(|| format!("{} updated", self.updated)),
+            listed(&self.pruned, "pruned branch", "pruned branches"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// The remote branches and tags at `path`, by full ref name, with the
+/// object each points at. Symbolic refs such as `origin/HEAD` are left out.
+fn snapshot(path: &Path) -> Result<BTreeMap<String, String>> {
+    Ok(git(
+        path,
+        &[
+            "for-each-ref",
+            "--format=%(refname) %(objectname) %(symref)",
+            "refs/remotes",
+            "refs/tags",
+        ],
+    )?
+    .lines()
+    .filter_map(|line| {
+        let mut fields = line.split(' ');
+        let (name, object) = (fields.next()?, fields.next()?);
+        fields
+            .next()
+            .is_none_or(str::is_empty)
+            .then(|| (name.to_string(), object.to_string()))
+    })
+    .collect())
+}
+
+/// What changed between two [`snapshot`]s.
+fn diff_refs(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> RefChanges {
+    let mut changes = RefChanges::default();
+    for (name, object) in after {
+        match (before.get(name), name.strip_prefix("refs/remotes/")) {
+            (None, Some(branch)) => changes.new_branches.push(branch.to_string()),
+            (None, None) => {
+                let tag = name.strip_prefix("refs/tags/").unwrap_or(name);
+                changes.new_tags.push(tag.to_string());
+            }
+            (Some(old), Some(_)) if old != object => changes.updated += 1,
+            _ => {}
+        }
+    }
+    changes.pruned = before
+        .keys()
+        .filter(|name| !after.contains_key(*name))
+        .filter_map(|name| name.strip_prefix("refs/remotes/"))
+        .map(str::to_string)
+        .collect();
+    changes
+}
+
+/// Fetch every remote of the repository at `path`, pruning remote branches
+/// deleted upstream with `prune`, and report what changed.
+pub fn fetch_all(path: &Path, prune: bool) -> Result<RefChanges> {
+    let before = snapshot(path)?;
+    let mut args = vec!["fetch", "--all", "--quiet"];
+    if prune {
+        args.push("--prune");
+    }
+    git(path, &args).map_err(|e| anyhow::anyhow!("fetch failed: {}", e))?;
+    Ok(diff_refs(&before, &snapshot(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    #[test]
+    fn reports_new_updated_and_pruned_refs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let clone = tmp.path().join("clone");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        run(&upstream, &["config", "user.email", "test@example.com"]);
+        run(&upstream, &["config", "user.name", "Test"]);
+        run(&upstream, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        run(&upstream, &["branch", "old"]);
+        run(
+            tmp.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "clone"],
+        );
+        assert!(fetch_all(&clone, false).unwrap().is_empty());
+
+        run(&upstream, &["commit", "-q", "--allow-empty", "-m", "more"]);
+        run(&upstream, &["branch", "feature"]);
+        run(&upstream, &["branch", "-D", "old"]);
+        run(&upstream, &["tag", "v1"]);
+        let head = git(&clone, &["rev-parse", "HEAD"]).unwrap();
+
+        // Without --prune the deleted branch stays.
+        let changes = fetch_all(&clone, false).unwrap();
+        assert_eq!(changes.new_branches, ["origin/feature"]);
+        assert_eq!(changes.new_tags, ["v1"]);
+        assert_eq!(changes.updated, 1);
+        assert!(changes.pruned.is_empty());
+        assert_eq!(
+            changes.describe(),
+            "1 new branch (origin/feature), 1 new tag (v1), 1 updated"
+        );
+
+        let changes = fetch_all(&clone, true).unwrap();
+        assert_eq!(changes.pruned, ["origin/old"]);
+        assert_eq!(changes.describe(), "1 pruned branch (origin/old)");
+        assert!(changes.new_branches.is_empty() && changes.updated == 0);
+        // The checkout did not move.
+        assert_eq!(git(&clone, &["rev-parse", "HEAD"]).unwrap(), head);
+    }
+}
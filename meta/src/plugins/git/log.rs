@@ -0,0 +1,222 @@
+//! `meta git log`: the commits of every project merged into one timeline,
+//! newest first, each tagged with its project.
+
+use crate::plugins::shared::git_operations::Commit;
+use crate::plugins::shared::{git, git_backend};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Which commits to list, as given on the command line: `--since` takes
+/// anything `git log --since` does, `--author` and `--grep` are regular
+/// expressions matched like `git log` matches them.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub since: Option<String>,
+    pub author: Option<String>,
+    pub grep: Option<String>,
+}
+
+impl LogFilter {
+    /// Parse the filter once for every project. Dates are read by git in
+    /// the repository at `dir`, so `--since` means what it does to `git log`.
+    pub fn resolve(&self, dir: &Path) -> Result<LogQuery> {
+        let since = match &self.since {
+            Some(since) => {
+                let parsed = git(dir, &["rev-parse", &format!("--since={}", since)])?;
+                let seconds = parsed
+                    .strip_prefix("--max-age=")
+                    .and_then(|s| s.parse().ok())
+                    .with_context(|| format!("Cannot read --since '{}'", since))?;
+                Some(seconds)
+            }
+            None => None,
+        };
+        let regex = |flag: &str, pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .map(|p| {
+                    Regex::new(p).map_err(|e| anyhow::anyhow!("Invalid {} pattern: {}", flag, e))
+                })
+                .transpose()
+        };
+        Ok(LogQuery {
+            since,
+            author: regex("--author", &self.author)?,
+            grep: regex("--grep", &self.grep)?,
+        })
+    }
+}
+
+/// A [`LogFilter`] ready to run against each project.
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    /// Unix seconds; commits made before are left out.
+    since: Option<i64>,
+    /// Matched against `Name <email>`.
+    author: Option<Regex>,
+    /// Matched against the whole message.
+    grep: Option<Regex>,
+}
+
+impl LogQuery {
+    fn matches(&self, commit: &Commit) -> bool {
+        let author = format!("{} <{}>", commit.author, commit.email);
+        self.author.as_ref().is_none_or(|re| re.is_match(&author))
+            && self
+                .grep
+                .as_ref()
+                .is_none_or(|re| re.is_match(&commit.message))
+    }
+}
+
+/// One commit in the timeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogEntry {
+    pub project: String,
+    pub hash: String,
+    pub short_hash: String,
+    /// Author date, Unix seconds.
+    pub timestamp: u64,
+    pub author: String,
+    pub email: String,
+    pub subject: String,
+}
+
+/// The commits reachable from HEAD at `path` that match `query`. An unborn
+/// branch has none.
+pub fn project_log(path: &Path, project: &str, query: &LogQuery) -> Result<Vec<LogEntry>> {
+    Ok(git_backend()
+        .log(path, query.since)?
+        .into_iter()
+        .filter(|commit| query.matches(commit))
+        .map(|commit| LogEntry {
+            project: project.to_string(),
+            hash: commit.hash,
+            short_hash: commit.short_hash,
+            timestamp: commit.timestamp.max(0) as u64,
+            author: commit.author,
+            email: commit.email,
+            subject: commit.subject,
+        })
+        .collect())
+}
+
+/// Merge per-project logs into one timeline, newest first. A commit listed
+/// more than once for the same project (by two worktrees of a bare project)
+/// is kept once.
+pub fn merge_timeline(logs: Vec<Vec<LogEntry>>) -> Vec<LogEntry> {
+    let mut seen = HashSet::new();
+    let mut timeline: Vec<LogEntry> = logs
+        .into_iter()
+        .flatten()
+        .filter(|entry| seen.insert((entry.project.clone(), entry.hash.clone())))
+        .collect();
+    timeline.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| a.project.cmp(&b.project))
+    });
+    timeline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(project: &str, hash: &str, timestamp: u64) -> LogEntry {
+        LogEntry {
+            project: project.to_string(),
+            hash: hash.to_string(),
+            short_hash: hash.to_string(),
+            timestamp,
+            author: "A".to_string(),
+            email: "a@example.com".to_string(),
+            subject: "s".to_string(),
+        }
+    }
+
+    #[test]
+    fn timeline_is_newest_first_without_duplicates() {
+        let timeline = merge_timeline(vec![
+            vec![entry("api", "a2", 30), entry("api", "a1", 10)],
+            vec![entry("web", "w1", 20)],
+            // A second worktree of api sees the same commit.
+            vec![entry("api", "a1", 10)],
+        ]);
+        let order: Vec<&str> = timeline.iter().map(|e| e.hash.as_str()).collect();
+        assert_eq!(order, ["a2", "w1", "a1"]);
+    }
+
+    #[test]
+    fn reads_and_filters_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let run = |args: &[&str]| {
+            git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+        };
+        run(&["init", "-q", "-b", "main"]);
+        let everything = LogFilter::default().resolve(dir).unwrap();
+        assert!(project_log(dir, "api", &everything).unwrap().is_empty());
+
+        run(&["config", "user.email", "test@example.com"]);
+        run(&["config", "user.name", "Test"]);
+        for (author, message) in [
+            ("Ada <ada@example.com>", "Add parser"),
+            ("Bob <bob@example.com>", "Fix parser | edge case"),
+        ] {
+            run(&[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "--author",
+                author,
+                "-m",
+                message,
+            ]);
+        }
+
+        let all = project_log(dir, "api", &everything).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].project, "api");
+        assert_eq!(all[0].author, "Bob");
+        assert_eq!(all[0].subject, "Fix parser | edge case");
+
+        let filter = LogFilter {
+            author: Some("Ada".to_string()),
+            ..Default::default()
+        };
+        let ada = project_log(dir, "api", &filter.resolve(dir).unwrap()).unwrap();
+        assert_eq!(ada.len(), 1);
+        assert_eq!(ada[0].subject, "Add parser");
+
+        let filter = LogFilter {
+            grep: Some("Fix".to_string()),
+            since: Some("1 week ago".to_string()),
+            ..Default::default()
+        };
+        let query = filter.resolve(dir).unwrap();
+        assert_eq!(project_log(dir, "api", &query).unwrap().len(), 1);
+
+        // --author matches the email too; --since stops the walk.
+        let filter = LogFilter {
+            author: Some("bob@".to_string()),
+            ..Default::default()
+        };
+        let query = filter.resolve(dir).unwrap();
+        assert_eq!(project_log(dir, "api", &query).unwrap()[0].author, "Bob");
+        let filter = LogFilter {
+            since: Some("2099-01-01".to_string()),
+            ..Default::default()
+        };
+        let query = filter.resolve(dir).unwrap();
+        assert!(project_log(dir, "api", &query).unwrap().is_empty());
+        let filter = LogFilter {
+            grep: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.resolve(dir).is_err());
+    }
+}
@@ -1,18 +1,42 @@
 use anyhow::Result;
 use colored::*;
-use metarepo_core::MetaConfig;
-use std::path::Path;
+use metarepo_core::{MetaConfig, Vcs};
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 // Export the main plugin
 pub use self::plugin::GitPlugin;
 
+mod branch;
+mod clean;
+mod commit;
+mod diff;
+mod divergence;
+mod fetch;
+mod log;
 mod operations;
 mod plugin;
+mod progress;
+mod pull;
+mod push;
+mod resolve;
+mod stash;
+mod tag;
 
 pub use operations::get_git_status;
 
 // Import shared git operations
-use crate::plugins::shared::{clone_with_auth, create_default_worktree};
+use crate::plugins::project::sizes;
+use crate::plugins::shared::outcome::RunOutcome;
+use crate::plugins::shared::parallel_map;
+use crate::plugins::shared::{
+    add_default_worktree, clone_with_auth, create_default_worktree, pin, state, submodules,
+    transfer, vcs, OutputManager, ProgressIndicator,
+};
+use progress::{CloneProgress, CloneState, CLONE_PROGRESS_FILE};
 
 pub fn clone_repository(
     repo_url: &str,
@@ -61,75 +85,659 @@ pub fn clone_repository(
     Ok(())
 }
 
-pub fn clone_missing_repos() -> Result<()> {
-    let meta_file =
-        MetaConfig::find_meta_file().ok_or_else(|| anyhow::anyhow!("No .meta file found"))?;
+/// Clone every project of the workspace in the current directory that is not
+/// on disk yet, or only those in `profile` when one is given. `only`, when
+/// given, limits the clone to those projects (as `--tag` does); projects in
+/// `excluded` (`--exclude`) and the workspace `default_excludes` are skipped.
+///
+/// Progress is saved to [`CLONE_PROGRESS_FILE`] after each project. A directory
+/// left by an interrupted or failed clone is removed and cloned again rather
+/// than taken for a finished one, but only while it holds nothing besides an
+/// empty repository (see [`leftover_kind`]). With `resume`, only the projects the last
+/// run did not finish are cloned, without looking for other missing ones.
+/// `jobs` projects are cloned at once.
+pub fn clone_missing_repos(
+    profile: Option<&str>,
+    resume: bool,
+    jobs: usize,
+    only: Option<&HashSet<String>>,
+    excluded: &HashSet<String>,
+) -> Result<RunOutcome> {
+    let (config, base_path) = workspace_to_clone(profile, only, excluded)?;
+    clone_missing(&config, &base_path, resume, jobs)
+}
 
-    let config = MetaConfig::load_from_file(&meta_file)?;
-    let base_path = meta_file.parent().unwrap();
+/// One project [`clone_missing`] clones.
+struct CloneJob {
+    project: String,
+    url: String,
+    path: PathBuf,
+    bare: bool,
+    depth: Option<i32>,
+    /// The directory is what an interrupted or failed run left behind.
+    leftover: bool,
+}
 
-    // Collect missing projects first to show count
-    let missing_projects: Vec<(String, String, std::path::PathBuf, bool, Option<i32>)> = config
-        .projects
-        .keys()
-        .filter_map(|project_path| {
-            let full_path = base_path.join(project_path);
-            if !full_path.exists() {
-                config.get_project_url(project_path).map(|url| {
-                    let is_bare = config.is_bare_repo(project_path);
-                    let depth = config.get_project_depth(project_path);
-                    (project_path.clone(), url, full_path, is_bare, depth)
-                })
-            } else {
-                None
+impl CloneJob {
+    /// The last path component, for display.
+    fn name(&self) -> &str {
+        self.project.rsplit('/').next().unwrap_or(&self.project)
+    }
+}
+
+/// Clone `repo_url` to `target_path` without printing. A bare layout gets
+/// its repository in `<project>/.git` and a worktree of the default branch.
+fn clone_checkout(
+    repo_url: &str,
+    target_path: &Path,
+    bare: bool,
+    depth: Option<i32>,
+) -> Result<()> {
+    if bare {
+        let bare_path = target_path.join(".git");
+        clone_with_auth(repo_url, &bare_path, true, depth)?;
+        std::fs::create_dir_all(target_path)?;
+        add_default_worktree(&bare_path, target_path)?;
+    } else {
+        clone_with_auth(repo_url, target_path, false, depth)?;
+    }
+    Ok(())
+}
+
+/// What a directory an interrupted or failed clone run left behind holds now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Leftover {
+    /// Nothing, or only a `.git` without a commit: what the clone wrote
+    /// before it stopped. Safe to remove.
+    Interrupted,
+    /// A repository with a checked-out commit, cloned some other way since.
+    Clone,
+    /// Anything else, which may be the user's work and is left alone.
+    Unknown,
+}
+
+/// Classify the leftover directory at `path`. Only an empty directory or a
+/// lone `.git` whose `HEAD` resolves to no commit counts as interrupted.
+fn leftover_kind(path: &Path) -> Leftover {
+    let git_dir = path.join(".git");
+    let has_commit = git_dir.exists()
+        && std::process::Command::new("git")
+            .arg("--git-dir")
+            .arg(&git_dir)
+            .args(["rev-parse", "--verify", "-q", "HEAD^{commit}"])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+    if has_commit {
+        return Leftover::Clone;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return Leftover::Unknown;
+    };
+    let only_git = entries
+        .filter_map(|entry| entry.ok())
+        .all(|entry| entry.file_name() == ".git");
+    if only_git {
+        Leftover::Interrupted
+    } else {
+        Leftover::Unknown
+    }
+}
+
+/// Set `project`'s state and save the progress file.
+fn record(
+    progress: &Mutex<CloneProgress>,
+    base_path: &Path,
+    project: &str,
+    clone_state: CloneState,
+    error: Option<String>,
+) -> Result<()> {
+    let mut progress = progress.lock().unwrap();
+    progress.set(project, clone_state, error);
+    state::save(base_path, CLONE_PROGRESS_FILE, &*progress)
+}
+
+/// Clone one project, recording its progress. Anything worth telling the
+/// user besides the outcome is pushed to `notes`, so parallel clones can
+/// print it together per project.
+fn clone_job(
+    config: &MetaConfig,
+    base_path: &Path,
+    job: &CloneJob,
+    progress: &Mutex<CloneProgress>,
+    notes: &mut Vec<String>,
+) -> Result<()> {
+    if job.path.exists() && job.leftover {
+        if leftover_kind(&job.path) != Leftover::Interrupted {
+            let message = format!(
+                "{} is not empty and is not the clone the last run left unfinished; \
+                 move it aside and run again",
+                job.path.display()
+            );
+            record(
+                progress,
+                base_path,
+                &job.project,
+                CloneState::Failed,
+                Some(message.clone()),
+            )?;
+            return Err(anyhow::anyhow!(message));
+        }
+        notes.push("Removing the incomplete clone left by the last run".to_string());
+        if let Err(e) = std::fs::remove_dir_all(&job.path) {
+            record(
+                progress,
+                base_path,
+                &job.project,
+                CloneState::Failed,
+                Some(e.to_string()),
+            )?;
+            return Err(e.into());
+        }
+    }
+    record(progress, base_path, &job.project, CloneState::Cloning, None)?;
+
+    // Projects under another version-control system clone through its
+    // backend; bare layouts, depth, and pins are git-only.
+    let kind = config.get_project_vcs(&job.project);
+    let cloned = if kind == Vcs::Git {
+        clone_checkout(&job.url, &job.path, job.bare, job.depth)
+    } else {
+        vcs::backend(kind).clone_repo(&job.url, &job.path)
+    };
+    if let Err(e) = cloned {
+        // The directory did not exist before; drop what the clone wrote so
+        // the next run starts clean.
+        if job.path.exists() {
+            std::fs::remove_dir_all(&job.path).ok();
+        }
+        record(
+            progress,
+            base_path,
+            &job.project,
+            CloneState::Failed,
+            Some(e.to_string()),
+        )?;
+        return Err(e);
+    }
+    record(progress, base_path, &job.project, CloneState::Done, None)?;
+
+    // A pinned project starts out at its pin rather than the default branch.
+    let pinned = config
+        .get_project_pin(&job.project)
+        .filter(|_| kind == Vcs::Git);
+    if let Some(reference) = pinned {
+        if job.bare {
+            notes.push(format!(
+                "{} is pinned at {}; check it out in its worktrees",
+                job.name(),
+                reference
+            ));
+        } else if let Err(e) = pin::check_out(&job.path, reference) {
+            notes.push(format!("Pin {}: {}", reference, e));
+        } else if submodules::enabled() {
+            // The pin may record other submodule commits than the branch did.
+            if let Err(e) = submodules::update(&job.path) {
+                notes.push(format!("Submodules at {}: {}", reference, e));
             }
+        }
+    }
+    Ok(())
+}
+
+/// Clone the projects of `config` missing under `base_path` (or, with
+/// `resume`, those the last run left unfinished), recording progress for
+/// `--resume`. `jobs` projects are cloned at once; with more than one, each
+/// project's output is held back and printed when all are done, under a
+/// single progress line. Returns each project's result.
+pub fn clone_missing(
+    config: &MetaConfig,
+    base_path: &Path,
+    resume: bool,
+    jobs: usize,
+) -> Result<RunOutcome> {
+    let mut previous: CloneProgress = state::load(base_path, CLONE_PROGRESS_FILE)?;
+
+    let mut candidates: Vec<String> = if resume {
+        let incomplete: Vec<String> = previous
+            .incomplete()
+            .into_iter()
+            .filter(|project| config.projects.contains_key(project))
+            .collect();
+        if incomplete.is_empty() {
+            println!("Nothing to resume: the last clone run finished every project");
+            return Ok(RunOutcome::default());
+        }
+        incomplete
+    } else {
+        config
+            .projects
+            .keys()
+            .filter(|project| !base_path.join(project).exists() || previous.partial(project))
+            .cloned()
+            .collect()
+    };
+    candidates.sort();
+
+    // A leftover directory that holds a working clone by now was finished
+    // some other way (by hand, say): forget its stale entry instead of
+    // cloning over it.
+    let finished: Vec<String> = candidates
+        .iter()
+        .filter(|project| {
+            previous.partial(project) && leftover_kind(&base_path.join(project)) == Leftover::Clone
+        })
+        .cloned()
+        .collect();
+    if !finished.is_empty() {
+        for project in &finished {
+            previous.projects.remove(project);
+            println!(
+                "{} {} is already cloned; clearing its unfinished clone record",
+                "ℹ".bright_black(),
+                project
+            );
+        }
+        state::save(base_path, CLONE_PROGRESS_FILE, &previous)?;
+        candidates.retain(|project| !finished.contains(project));
+    }
+
+    // Collect missing projects first to show count
+    let missing_projects: Vec<CloneJob> = candidates
+        .into_iter()
+        .filter_map(|project| {
+            config.get_project_url(&project).map(|url| CloneJob {
+                path: base_path.join(&project),
+                bare: config.is_bare_repo(&project),
+                depth: config.get_project_depth(&project),
+                leftover: previous.partial(&project),
+                url,
+                project,
+            })
         })
         .collect();
 
     if missing_projects.is_empty() {
         println!("All projects already exist");
-        return Ok(());
+        return Ok(RunOutcome::default());
     }
 
     let total = missing_projects.len();
+    let jobs = jobs.clamp(1, total);
     println!(
-        "Cloning {} missing project{}\n",
+        "{} {} project{}{}\n",
+        if resume { "Resuming" } else { "Cloning" },
         total,
-        if total == 1 { "" } else { "s" }
+        if total == 1 { "" } else { "s" },
+        if jobs > 1 {
+            format!(", {} at a time", jobs)
+        } else {
+            String::new()
+        }
     );
 
-    let mut success_count = 0;
-    let mut failed_count = 0;
+    let mut progress = CloneProgress {
+        started: state::now_secs(),
+        ..Default::default()
+    };
+    for job in &missing_projects {
+        progress.set(&job.project, CloneState::Pending, None);
+    }
+    state::save(base_path, CLONE_PROGRESS_FILE, &progress)?;
+    let progress = Mutex::new(progress);
+
+    let outcome = if jobs > 1 {
+        clone_in_parallel(config, base_path, missing_projects, &progress, jobs)
+    } else {
+        let mut outcome = RunOutcome::default();
+        for (i, job) in missing_projects.iter().enumerate() {
+            println!(
+                "[{}/{}] Cloning {}",
+                (i + 1).to_string().cyan(),
+                total.to_string().cyan(),
+                job.name().bright_white()
+            );
+            let mut notes = Vec::new();
+            let job_started = Instant::now();
+            let result = clone_job(config, base_path, job, &progress, &mut notes);
+            for note in notes {
+                println!("{} {}", "ℹ".bright_black(), note);
+            }
+            let exit_code = match result {
+                Ok(()) => {
+                    println!("{} Complete\n", "✓".green());
+                    0
+                }
+                Err(e) => {
+                    eprintln!("{} Failed: {}\n", "✗".red(), e);
+                    1
+                }
+            };
+            outcome.record_span(
+                &job.project,
+                Some(exit_code),
+                job_started,
+                job_started.elapsed(),
+            );
+        }
+        println!(
+            "Summary: {} cloned, {} failed",
+            outcome.succeeded.to_string().green(),
+            if outcome.failed > 0 {
+                outcome.failed.to_string().red()
+            } else {
+                "0".bright_black()
+            }
+        );
+        outcome
+    };
 
-    for (i, (project_path, repo_url, full_path, is_bare, depth)) in
-        missing_projects.iter().enumerate()
-    {
-        let project_name = project_path.rsplit('/').next().unwrap_or(project_path);
+    if outcome.failed > 0 {
         println!(
-            "[{}/{}] Cloning {}",
-            (i + 1).to_string().cyan(),
-            total.to_string().cyan(),
-            project_name.bright_white()
+            "{} Run `meta git update --resume` to retry only the failed project(s)",
+            "→".bright_black()
         );
+    }
+
+    Ok(outcome)
+}
+
+/// Clone `jobs` across a pool of `workers` threads, buffering each
+/// project's output in an [`OutputManager`] and showing one aggregate
+/// progress line on a terminal. Returns each project's result.
+fn clone_in_parallel(
+    config: &MetaConfig,
+    base_path: &Path,
+    jobs: Vec<CloneJob>,
+    progress: &Mutex<CloneProgress>,
+    workers: usize,
+) -> RunOutcome {
+    let names: Vec<String> = jobs.iter().map(|job| job.project.clone()).collect();
+    let manager = Arc::new(OutputManager::new(names));
+    let mut indicator = ProgressIndicator::new(Arc::clone(&manager), "clone".to_string());
+    let live = std::io::stdout().is_terminal();
+    if live {
+        indicator.start();
+    }
 
-        match clone_repository(repo_url, full_path, *is_bare, *depth) {
-            Ok(_) => success_count += 1,
-            Err(e) => {
-                eprintln!("{} Failed: {}\n", "✗".red(), e);
-                failed_count += 1;
+    // The aggregate line stands in for each transfer's own.
+    let results = transfer::without_bars(|| {
+        parallel_map(jobs, workers, |job| {
+            manager.start_project(&job.project);
+            manager.set_project_command(&job.project, format!("clone {}", job.url));
+            let mut notes = Vec::new();
+            let started = Instant::now();
+            let result = clone_job(config, base_path, &job, progress, &mut notes);
+            let duration = started.elapsed();
+            let stdout = notes.join("\n").into_bytes();
+            match &result {
+                Ok(()) => manager.complete_project(&job.project, 0, stdout, Vec::new()),
+                Err(e) => {
+                    manager.complete_project(&job.project, 1, stdout, e.to_string().into_bytes())
+                }
             }
+            let exit_code = if result.is_ok() { 0 } else { 1 };
+            (job.project, exit_code, started, duration)
+        })
+    });
+
+    if live {
+        indicator.stop();
+    }
+    manager.display_final_results();
+    let mut outcome = RunOutcome::default();
+    for (project, exit_code, started, duration) in results {
+        outcome.record_span(&project, Some(exit_code), started, duration);
+    }
+    outcome
+}
+
+/// The workspace in the current directory and its root, with its projects
+/// narrowed as in [`clone_missing_repos`].
+fn workspace_to_clone(
+    profile: Option<&str>,
+    only: Option<&HashSet<String>>,
+    excluded: &HashSet<String>,
+) -> Result<(MetaConfig, PathBuf)> {
+    let meta_file =
+        MetaConfig::find_meta_file().ok_or_else(|| anyhow::anyhow!("No .meta file found"))?;
+
+    let mut config = MetaConfig::load_from_file(&meta_file)?;
+    narrow_to_clone(&mut config, profile, only, excluded)?;
+    let base_path = meta_file.parent().unwrap().to_path_buf();
+    Ok((config, base_path))
+}
+
+/// Narrow `config` to the projects a clone run covers: those in `profile` and
+/// `only`, less `excluded` and the workspace `default_excludes`.
+fn narrow_to_clone(
+    config: &mut MetaConfig,
+    profile: Option<&str>,
+    only: Option<&HashSet<String>>,
+    excluded: &HashSet<String>,
+) -> Result<()> {
+    if let Some(name) = profile {
+        config.apply_profile(name)?;
+    }
+    if let Some(only) = only {
+        config.projects.retain(|project, _| only.contains(project));
+    }
+    let default_excluded = config.default_excluded_keys();
+    config
+        .projects
+        .retain(|project, _| !excluded.contains(project) && !default_excluded.contains(project));
+    Ok(())
+}
+
+/// Preview what `meta git update` would clone without cloning: each missing
+/// project with its host-reported size, flagging clones above the
+/// `clone.size-warn-mb` threshold. `profile`, `only`, and `excluded` limit it
+/// as in [`clone_missing_repos`].
+pub fn plan_missing_clones(
+    profile: Option<&str>,
+    only: Option<&HashSet<String>>,
+    excluded: &HashSet<String>,
+) -> Result<()> {
+    let (config, base_path) = workspace_to_clone(profile, only, excluded)?;
+
+    let mut missing: Vec<(String, String)> = config
+        .projects
+        .keys()
+        .filter(|project_path| !base_path.join(project_path).exists())
+        .filter_map(|project_path| {
+            config
+                .get_project_url(project_path)
+                .map(|url| (project_path.clone(), url))
+        })
+        .collect();
+    missing.sort();
+
+    if missing.is_empty() {
+        println!("All projects already exist");
+        return Ok(());
+    }
+
+    let threshold_kb = sizes::clone_warn_threshold_kb(&config);
+    let width = missing
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut known_kb = 0;
+    let mut unknown = 0;
+    let mut large = 0;
+
+    println!(
+        "Would clone {} missing project{}\n",
+        missing.len(),
+        if missing.len() == 1 { "" } else { "s" }
+    );
+    for (project_path, url) in &missing {
+        let size_kb = sizes::remote_size_kb(url);
+        match size_kb {
+            Some(kb) => known_kb += kb,
+            None => unknown += 1,
         }
+        let size = size_kb
+            .map(sizes::format_size_kb)
+            .unwrap_or_else(|| "size unknown".to_string());
+        let flag = if sizes::exceeds_threshold(size_kb, threshold_kb) {
+            large += 1;
+            format!(" {}", "⚠ large clone".yellow())
+        } else {
+            String::new()
+        };
+        println!(
+            "  {}  {}  {}{}",
+            format!("{:<width$}", project_path).bright_white(),
+            url.bright_cyan(),
+            size.bright_black(),
+            flag
+        );
     }
 
     println!(
-        "Summary: {} cloned, {} failed",
-        success_count.to_string().green(),
-        if failed_count > 0 {
-            failed_count.to_string().red()
+        "\nSummary: {} to clone, {} known{}",
+        missing.len().to_string().green(),
+        sizes::format_size_kb(known_kb),
+        if unknown > 0 {
+            format!(" ({} of unknown size)", unknown)
         } else {
-            "0".bright_black()
+            String::new()
         }
     );
+    if let Some(limit) = threshold_kb.filter(|_| large > 0) {
+        println!(
+            "{} {} project(s) above the {} clone warning; consider a shallow clone (depth in .meta)",
+            "⚠".yellow(),
+            large,
+            sizes::format_size_kb(limit)
+        );
+    }
+    println!("Nothing was cloned. Re-run without --plan to clone.");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::shared::outcome::EXIT_PARTIAL;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn parallel_clones_record_every_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "main"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        let url = origin.to_string_lossy().into_owned();
+
+        let base = tmp.path().join("ws");
+        std::fs::create_dir_all(&base).unwrap();
+        let mut config = MetaConfig::default();
+        for name in ["api", "web", "libs/core"] {
+            config.projects.insert(
+                name.to_string(),
+                metarepo_core::ProjectEntry::Url(url.clone()),
+            );
+        }
+        config.projects.insert(
+            "broken".to_string(),
+            metarepo_core::ProjectEntry::Url(tmp.path().join("missing").display().to_string()),
+        );
+
+        let outcome = clone_missing(&config, &base, false, 3).unwrap();
+        assert_eq!((outcome.succeeded, outcome.failed), (3, 1));
+        assert_eq!(outcome.exit_code(None), EXIT_PARTIAL);
+        assert!(base.join("libs/core/.git").exists());
+        assert!(!base.join("broken").exists());
+        let progress: CloneProgress = state::load(&base, CLONE_PROGRESS_FILE).unwrap();
+        assert_eq!(progress.incomplete(), ["broken"]);
+        assert_eq!(progress.projects["web"].state, CloneState::Done);
+    }
+
+    #[test]
+    fn leftover_clone_with_user_files_is_kept() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "main"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        let url = origin.to_string_lossy().into_owned();
+
+        let base = tmp.path().join("ws");
+        std::fs::create_dir_all(&base).unwrap();
+        let mut config = MetaConfig::default();
+        for name in ["old", "half"] {
+            config.projects.insert(
+                name.to_string(),
+                metarepo_core::ProjectEntry::Url(url.clone()),
+            );
+        }
+
+        // The last run failed on both; since then `old` was cloned by hand
+        // and has work in it, while `half` is an empty repository.
+        let mut previous = CloneProgress::default();
+        previous.set("old", CloneState::Failed, Some("timed out".to_string()));
+        previous.set("half", CloneState::Cloning, None);
+        state::save(&base, CLONE_PROGRESS_FILE, &previous).unwrap();
+        git(&base, &["clone", "-q", &url, "old"]);
+        std::fs::write(base.join("old/WORK.txt"), "unsaved").unwrap();
+        std::fs::create_dir_all(base.join("half")).unwrap();
+        git(&base.join("half"), &["init", "-q"]);
+
+        assert_eq!(clone_missing(&config, &base, false, 1).unwrap().failed, 0);
+        assert_eq!(
+            std::fs::read_to_string(base.join("old/WORK.txt")).unwrap(),
+            "unsaved"
+        );
+        assert_eq!(leftover_kind(&base.join("half")), Leftover::Clone);
+        let progress: CloneProgress = state::load(&base, CLONE_PROGRESS_FILE).unwrap();
+        assert!(!progress.projects.contains_key("old"));
+        assert_eq!(progress.projects["half"].state, CloneState::Done);
+    }
+
+    #[test]
+    fn clone_skips_default_excludes_and_excluded_projects() {
+        let mut config: MetaConfig = serde_json::from_str(
+            r#"{
+                "default_excludes": ["old"],
+                "projects": {
+                    "api": { "url": "local:api", "tags": ["backend"] },
+                    "web": "local:web",
+                    "old": { "url": "local:old", "tags": ["backend"] }
+                }
+            }"#,
+        )
+        .unwrap();
+        let excluded: HashSet<String> = ["web".to_string()].into();
+        narrow_to_clone(&mut config, None, None, &excluded).unwrap();
+        let mut kept: Vec<&String> = config.projects.keys().collect();
+        kept.sort();
+        assert_eq!(kept, ["api"]);
+    }
+
+    #[test]
+    fn unknown_leftover_is_not_removed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("notes");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(leftover_kind(&dir), Leftover::Interrupted);
+        std::fs::write(dir.join("WORK.txt"), "unsaved").unwrap();
+        assert_eq!(leftover_kind(&dir), Leftover::Unknown);
+    }
+}
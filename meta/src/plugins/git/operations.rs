@@ -1,56 +1,39 @@
 use anyhow::Result;
-use git2::Repository;
 use std::path::Path;
 
-pub fn get_git_status(repo_path: &Path) -> Result<String> {
-    let repo = Repository::open(repo_path)?;
-    let statuses = repo.statuses(None)?;
-
-    if statuses.is_empty() {
-        Ok("Clean working directory".to_string())
-    } else {
-        let mut status_lines = Vec::new();
+use crate::plugins::shared::{git_backend, submodules};
 
-        for entry in statuses.iter() {
-            if let Ok(path) = entry.path() {
-                let status = entry.status();
-                let mut status_str = String::new();
+pub fn get_git_status(repo_path: &Path) -> Result<String> {
+    let entries = git_backend().status(repo_path)?;
+    let submodules = submodules::changes(repo_path);
 
-                if status.is_wt_new() {
-                    status_str.push('?');
-                } else {
-                    status_str.push(' ');
-                }
-                if status.is_wt_modified() {
-                    status_str.push('M');
-                } else {
-                    status_str.push(' ');
-                }
-                if status.is_wt_deleted() {
-                    status_str.push('D');
-                } else {
-                    status_str.push(' ');
-                }
-                if status.is_index_new() {
-                    status_str.push('A');
-                } else {
-                    status_str.push(' ');
-                }
-                if status.is_index_modified() {
-                    status_str.push('M');
-                } else {
-                    status_str.push(' ');
-                }
-                if status.is_index_deleted() {
-                    status_str.push('D');
-                } else {
-                    status_str.push(' ');
-                }
+    if entries.is_empty() && submodules.is_empty() {
+        return Ok("Clean working directory".to_string());
+    }
 
-                status_lines.push(format!("{} {}", status_str, path));
-            }
-        }
+    let flag = |set: bool, c: char| if set { c } else { ' ' };
+    let mut status_lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let status = &entry.status;
+            let status_str: String = [
+                flag(status.wt_new, '?'),
+                flag(status.wt_modified, 'M'),
+                flag(status.wt_deleted, 'D'),
+                flag(status.index_new, 'A'),
+                flag(status.index_modified, 'M'),
+                flag(status.index_deleted, 'D'),
+            ]
+            .iter()
+            .collect();
+            format!("{} {}", status_str, entry.path)
+        })
+        .collect();
+    status_lines.extend(
+        submodules
+            .iter()
+            .map(|change| format!("submodule {} ({})", change.path, change.describe())),
+    );
 
-        Ok(status_lines.join("\n"))
-    }
+    Ok(status_lines.join("\n"))
 }
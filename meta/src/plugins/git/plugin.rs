@@ -1,12 +1,29 @@
-use super::{clone_missing_repos, clone_repository, get_git_status};
-use crate::plugins::exec::{execute_with_projects, ProjectInfo, ProjectIterator};
-use crate::plugins::shared::{detect_default_branch, parse_depth_arg};
+use super::{
+    branch, clean, clone_missing_repos, clone_repository, commit, diff, divergence, fetch,
+    get_git_status, log, plan_missing_clones, pull, push, resolve, stash, tag,
+};
+use crate::plugins::exec::{ProjectInfo, ProjectIterator};
+use crate::plugins::lock::{check_out_locked, Lockfile, LOCK_FILE};
+use crate::plugins::shared::issues::{IssueLinker, WithIssues};
+use crate::plugins::shared::{
+    confirm, detect_default_branch, git, jobs_from, notify, outcome, parallel_map, parse_depth_arg,
+    pin, selection, state, submodules, throttle, transfer,
+};
 use crate::plugins::worktree::list_worktrees;
 use anyhow::Result;
 use clap::ArgMatches;
-use metarepo_core::{arg, command, plugin, BasePlugin, MetaConfig, MetaPlugin, RuntimeConfig};
-use std::path::Path;
+use colored::*;
+use metarepo_core::{
+    arg, command, plugin, prompt_confirm, BasePlugin, CommandBuilder, MetaConfig, MetaPlugin,
+    NonInteractiveMode, RuntimeConfig, Table,
+};
+use pull::PullOutcome;
+use push::PushPlan;
+use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Instant;
 
 /// GitPlugin using the new simplified plugin architecture
 pub struct GitPlugin;
@@ -49,13 +66,17 @@ impl GitPlugin {
                          config (.meta), metarepo switches into it and clones each missing\n\
                          child project so the whole workspace is checked out in one step.\n\
                          Use --depth to perform a shallow clone; the depth is recorded so\n\
-                         later re-clones (meta git update) stay shallow.\n\
+                         later re-clones (meta git update) stay shallow. -j/--jobs clones\n\
+                         that many child projects at once, as in meta git update.\n\
+                         --recurse-submodules also initializes the git submodules of every\n\
+                         clone, recursively (or set clone.recurse-submodules in .meta).\n\
                          \n\
                          Examples:\n\
                          \n\
                            meta git clone git@github.com:org/workspace.git\n\
                            meta git c https://github.com/org/workspace.git\n\
-                           meta git clone --depth 1 https://github.com/org/workspace.git",
+                           meta git clone --depth 1 https://github.com/org/workspace.git\n\
+                           meta git clone --recurse-submodules git@github.com:org/workspace.git",
                     )
                     .aliases(vec!["c".to_string()])
                     .with_help_formatting()
@@ -70,6 +91,24 @@ impl GitPlugin {
                             .long("depth")
                             .help("Create a shallow clone with the given history depth")
                             .takes_value(true),
+                    )
+                    .arg(
+                        arg("recurse-submodules")
+                            .long("recurse-submodules")
+                            .help("Also initialize and update git submodules, recursively"),
+                    )
+                    .arg(
+                        arg("limit-rate")
+                            .long("limit-rate")
+                            .help("Limit network transfer to this many KB/s (default: clone.limit-rate)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("jobs")
+                            .long("jobs")
+                            .short('j')
+                            .help("Clone this many repositories at once (default: 1)")
+                            .takes_value(true),
                     ),
             )
             .command(
@@ -85,13 +124,58 @@ impl GitPlugin {
                          subdirectory, only the in-scope projects are reported. Projects\n\
                          listed in .meta that are not yet cloned are flagged as not cloned.\n\
                          \n\
+                         Name projects (or globs such as 'libs/**'), or a project group with\n\
+                         --group, to report only those; the main repository is then left\n\
+                         out. A name that matches nothing is a warning, or an error with\n\
+                         --strict. --exclude drops projects after selection, and projects in\n\
+                         the workspace default_excludes list are skipped unless named.\n\
+                         --tag TAG keeps only projects with that tag in .meta (across the\n\
+                         workspace when nothing else is selected) and --exclude-tag TAG drops\n\
+                         them; both are repeatable and leave out the main repository.\n\
+                         \n\
                          Examples:\n\
                          \n\
-                           meta git status   status for the whole workspace\n\
-                           meta git st       same, using an alias",
+                           meta git status             status for the whole workspace\n\
+                           meta git st                 same, using an alias\n\
+                           meta git status 'libs/**'   status for every project under libs/\n\
+                           meta git status --tag web   status for the projects tagged web",
                     )
                     .aliases(vec!["st".to_string(), "s".to_string()])
-                    .with_help_formatting(),
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to report (names, aliases, or globs such as 'libs/**')")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .help("Only report projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude-tag")
+                            .long("exclude-tag")
+                            .help("Skip projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    ),
             )
             .command(
                 command("update")
@@ -105,13 +189,238 @@ impl GitPlugin {
                          project is marked bare). Existing repositories are left untouched,\n\
                          so this is the command to run after pulling new entries into .meta.\n\
                          \n\
+                         --plan previews the clone without touching the disk: each missing\n\
+                         project with its size from the host API, flagging repositories\n\
+                         above the clone.size-warn-mb threshold (default 500 MB).\n\
+                         \n\
+                         Progress is saved after every project, so a run that dies partway\n\
+                         (a flaky network, Ctrl-C) can be picked up again: a plain re-run\n\
+                         replaces any half-written clone it left, and --resume retries only\n\
+                         the projects the last run did not finish, without scanning the rest.\n\
+                         \n\
+                         -j/--jobs N clones N projects at once. Each project's output is held\n\
+                         back and printed together when all are done, with a single progress\n\
+                         line in the meantime.\n\
+                         \n\
+                         --frozen reproduces the workspace recorded by `meta lock`: after\n\
+                         cloning, every locked project is checked out (detached) at its\n\
+                         commit in meta.lock, fetching it when needed. It fails when there\n\
+                         is no lockfile or a commit cannot be checked out.\n\
+                         \n\
+                         --tag TAG clones only the missing projects with that tag in .meta,\n\
+                         and --exclude-tag TAG skips them; both are repeatable. Projects in\n\
+                         the workspace default_excludes list are never cloned here, and\n\
+                         --exclude skips more by name, alias, or glob.\n\
+                         \n\
+                         --recurse-submodules (or clone.recurse-submodules in .meta) also\n\
+                         initializes and updates the git submodules of each new clone.\n\
+                         \n\
+                         Exit codes: 0 when every clone succeeds, 1 when all fail, and 2\n\
+                         when some fail. --min-success PCT (or min_success.update in .meta)\n\
+                         exits 0 as long as at least PCT percent of the clones succeed.\n\
+                         `meta last` shows the summary of the most recent run.\n\
+                         \n\
                          Examples:\n\
                          \n\
-                           meta git update   clone all missing projects\n\
-                           meta git u        same, using an alias",
+                           meta git update            clone all missing projects\n\
+                           meta git u                 same, using an alias\n\
+                           meta git update --plan     list what would be cloned, with sizes\n\
+                           meta git update -j 8       clone eight projects at a time\n\
+                           meta git update --resume   retry what the last run did not finish\n\
+                           meta git update --tag web  clone only the projects tagged web\n\
+                           meta git update --frozen   check out the commits in meta.lock",
                     )
                     .aliases(vec!["up".to_string(), "u".to_string()])
-                    .with_help_formatting(),
+                    .with_help_formatting()
+                    .arg(arg("plan").long("plan").help(
+                        "List the missing projects with their host-reported sizes \
+                         instead of cloning them",
+                    ))
+                    .arg(
+                        arg("frozen")
+                            .long("frozen")
+                            .help("Check every project out at its commit in meta.lock"),
+                    )
+                    .arg(
+                        arg("resume")
+                            .long("resume")
+                            .help("Retry only the projects the last clone run did not finish"),
+                    )
+                    .arg(
+                        arg("recurse-submodules")
+                            .long("recurse-submodules")
+                            .help("Also initialize and update git submodules, recursively"),
+                    )
+                    .arg(
+                        arg("limit-rate")
+                            .long("limit-rate")
+                            .help("Limit network transfer to this many KB/s (default: clone.limit-rate)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("jobs")
+                            .long("jobs")
+                            .short('j')
+                            .help("Clone this many repositories at once (default: 1)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("min-success")
+                            .long("min-success")
+                            .help("Exit 0 when at least this percentage of clones succeed")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .help("Only clone projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("exclude-tag")
+                            .long("exclude-tag")
+                            .help("Skip projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    ),
+            )
+            .command(
+                command("fetch")
+                    .about("Fetch every remote of every repository, leaving checkouts alone")
+                    .help_description(
+                        "Fetch all remotes of every repository in scope (or the projects\n\
+                         named, or --group), concurrently (-j sets how many at once;\n\
+                         --sequential fetches one at a time). Only remote-tracking branches\n\
+                         and tags change: working trees and local branches stay as they are,\n\
+                         so this is safe with uncommitted work. --prune also drops\n\
+                         remote-tracking branches deleted on the remote.\n\
+                         \n\
+                         Each repository that changed is listed with its new branches and\n\
+                         tags, how many branches moved, and what was pruned. The main\n\
+                         repository is included unless --skip-main; worktrees of a bare\n\
+                         project share one fetch.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git fetch --prune\n\
+                           meta git fetch -j 16 --group backend",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("prune")
+                            .long("prune")
+                            .short('p')
+                            .help("Remove remote-tracking branches deleted on the remote"),
+                    )
+                    .arg(
+                        arg("sequential")
+                            .long("sequential")
+                            .help("Fetch repositories one at a time instead of concurrently"),
+                    )
+                    .arg(
+                        arg("jobs")
+                            .long("jobs")
+                            .short('j')
+                            .help("Fetch this many repositories at once (default: parallelism)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    ),
+            )
+            .command(
+                command("divergence")
+                    .about("Report branches that are ahead of, behind, or diverged from upstream")
+                    .help_description(
+                        "Compare the checked-out branch of every repository in scope (or the\n\
+                         projects named, or --group) with its upstream and report it as up\n\
+                         to date, ahead, behind, diverged, gone upstream (deleted on the\n\
+                         remote), without an upstream, or detached. --all-branches checks\n\
+                         every local branch instead.\n\
+                         \n\
+                         Upstreams are compared as last fetched; --fetch fetches every\n\
+                         repository first. Nothing is changed. Exits non-zero when any\n\
+                         branch has diverged, so it can gate CI. --json prints one entry per\n\
+                         branch. The main repository is included unless --skip-main.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git divergence --fetch\n\
+                           meta git divergence --all-branches api web\n\
+                           meta git divergence --json --skip-main",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("all-branches")
+                            .long("all-branches")
+                            .help("Check every local branch, not just the checked-out one"),
+                    )
+                    .arg(
+                        arg("fetch")
+                            .long("fetch")
+                            .help("Fetch every repository before comparing"),
+                    )
+                    .arg(
+                        arg("json")
+                            .long("json")
+                            .help("Print the branches and their state as JSON"),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    ),
             )
             .command(
                 command("pull")
@@ -119,31 +428,66 @@ impl GitPlugin {
                     .help_description(
                         "Pull the latest changes into every repository in scope.\n\
                          \n\
-                         Pulls run concurrently by default since they are network-bound;\n\
-                         use --sequential to pull one repo at a time. Each repo is\n\
-                         preflighted first: repositories with uncommitted changes or no\n\
+                         Every repository is fetched first, concurrently (-j sets how\n\
+                         many at once; --sequential fetches one at a time), and each\n\
+                         branch is then brought up to its upstream: fast-forwarded when\n\
+                         possible, rebased with --rebase when it has diverged, and\n\
+                         otherwise left alone and reported as diverged. A rebase that hits\n\
+                         conflicts is undone and the project queued for `meta git resolve`.\n\
+                         A table of per-project results and a summary follow. Each repo\n\
+                         is preflighted first: repositories with uncommitted changes or no\n\
                          upstream tracking branch are skipped with a note instead of\n\
                          failing the run. Bare repositories are expanded so each managed\n\
                          worktree is pulled in place. The main repo is pulled in the\n\
                          full-workspace view unless --skip-main is given.\n\
+                         Projects pinned with `meta project pin` are fetched but never\n\
+                         moved; a warning names any whose remote has moved past the pin.\n\
                          \n\
-                         Use --include-only and --exclude with comma-separated patterns to\n\
-                         narrow which projects are pulled.\n\
+                         Name projects (or globs such as 'services/*'), or a project group\n\
+                         with --group, to pull only those; the main repo is then skipped,\n\
+                         and --strict fails if any name matches nothing. Use --include-only\n\
+                         and --exclude with comma-separated patterns to narrow which\n\
+                         projects are pulled; projects in the workspace default_excludes\n\
+                         list are skipped unless named.\n\
                          \n\
                          Shallow projects (cloned with --depth) accumulate history on a\n\
                          plain pull. Pass --shallow to re-truncate each project with a\n\
                          stored depth in .meta (git fetch --depth N) after pulling so\n\
                          history shrinks back to the configured depth.\n\
                          \n\
+                         Exit codes: 0 when every target pulls, 1 when all fail, and 2 when\n\
+                         some fail; conflicts count as failures, diverged branches do not.\n\
+                         --min-success PCT (or min_success.pull in .meta) exits 0 as long as\n\
+                         at least PCT percent of the targets succeed. `meta last` shows the\n\
+                         summary of the most recent run.\n\
+                         \n\
                          Examples:\n\
                          \n\
                            meta git pull                       pull everything\n\
                            meta git pull --skip-main           pull child repos only\n\
+                           meta git pull --rebase -j 16        rebase diverged branches\n\
+                           meta git pull 'services/*'          pull matching projects only\n\
                            meta git pull --exclude vendor,docs  pull all but matches\n\
                            meta git pull --shallow             re-truncate shallow repos",
                     )
                     .aliases(vec!["p".to_string()])
                     .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to pull (names, aliases, or globs such as 'libs/**')")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
                     .arg(
                         arg("parallel")
                             .long("parallel")
@@ -154,6 +498,24 @@ impl GitPlugin {
                             .long("sequential")
                             .help("Pull repositories one at a time instead of concurrently"),
                     )
+                    .arg(
+                        arg("jobs")
+                            .long("jobs")
+                            .short('j')
+                            .help("Fetch this many repositories at once (default: parallelism)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("rebase")
+                            .long("rebase")
+                            .help("Rebase branches that have diverged from their upstream"),
+                    )
+                    .arg(
+                        arg("min-success")
+                            .long("min-success")
+                            .help("Exit 0 when at least this percentage of targets pull")
+                            .takes_value(true),
+                    )
                     .arg(
                         arg("skip-main")
                             .long("skip-main")
@@ -168,267 +530,2512 @@ impl GitPlugin {
                     .arg(
                         arg("exclude")
                             .long("exclude")
-                            .help("Exclude projects matching patterns (comma-separated)")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
                             .takes_value(true),
                     )
                     .arg(arg("shallow").long("shallow").help(
                         "Re-truncate history after pulling for projects with a stored \
                          shallow clone depth in .meta (fetch --depth N), so shallow \
                          repos do not accumulate history over time",
-                    )),
+                    ))
+                    .arg(
+                        arg("limit-rate")
+                            .long("limit-rate")
+                            .help("Limit network transfer to this many KB/s (default: clone.limit-rate)")
+                            .takes_value(true),
+                    ),
             )
-            .handler("clone", handle_clone)
-            .handler("status", handle_status)
-            .handler("update", handle_update)
-            .handler("pull", handle_pull)
-            .build()
-    }
-}
-
-/// Handler for the clone command
-fn handle_clone(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
-    let url = matches.get_one::<String>("url").unwrap();
-
-    let depth = parse_depth_arg(matches.get_one::<String>("depth"))?;
-
-    println!("Cloning meta repository from: {}", url);
-
-    // Extract repo name from URL for directory name
-    let repo_name = url
-        .split('/')
-        .next_back()
-        .unwrap_or("meta-repo")
-        .trim_end_matches(".git");
-
-    let target_path = config.working_dir.join(repo_name);
-    clone_repository(url, &target_path, false, depth)?;
+            .command(
+                command("push")
+                    .about("Push the current branch of each repository")
+                    .help_description(
+                        "Push the checked-out branch of every repository in scope to its\n\
+                         upstream, for feature branches that span several projects.\n\
+                         \n\
+                         A summary of what will be pushed where comes first; --dry-run\n\
+                         stops there. Branches without an upstream are skipped unless\n\
+                         --set-upstream is given, which pushes them to origin under the\n\
+                         same name and tracks them. Branches that have diverged from\n\
+                         their upstream, and detached HEADs, are skipped with a note;\n\
+                         --force pushes them with --force-with-lease instead.\n\
+                         --tags pushes tags along with each branch. Bare repositories are\n\
+                         expanded so each worktree's branch is pushed. The main repo is\n\
+                         included in the full-workspace view unless --skip-main is given.\n\
+                         \n\
+                         Name projects (or globs), or a project group with --group, to\n\
+                         push only those; --strict fails if any name matches nothing.\n\
+                         --tag TAG keeps only projects with that tag in .meta (across the\n\
+                         workspace when nothing else is selected) and --exclude-tag TAG drops\n\
+                         them; both are repeatable and leave out the main repository.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git push --dry-run              show what would be pushed\n\
+                           meta git push --set-upstream api web  publish a feature branch\n\
+                           meta git push --group backend --tags  push a group with tags\n\
+                           meta git push --tag backend -u        publish the backend projects\n\
+                           meta git push --force api             overwrite a rewritten branch",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to push (names, aliases, or globs such as 'libs/**')")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .help("Only push projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude-tag")
+                            .long("exclude-tag")
+                            .help("Skip projects with this tag in .meta (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("set-upstream")
+                            .long("set-upstream")
+                            .short('u')
+                            .help("Push branches without an upstream to origin and track them"),
+                    )
+                    .arg(arg("tags").long("tags").help("Push tags along with each branch"))
+                    .arg(
+                        arg("force")
+                            .long("force")
+                            .help("Push diverged branches with --force-with-lease"),
+                    )
+                    .arg(
+                        arg(confirm::YES_FLAG)
+                            .long(confirm::YES_FLAG)
+                            .help("Confirm a protected force push without typing the workspace name"),
+                    )
+                    .arg(
+                        arg("dry-run")
+                            .long("dry-run")
+                            .help("Show what would be pushed without pushing"),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    ),
+            )
+            .command(
+                command("branch")
+                    .about("Create, delete, or find a branch across repositories")
+                    .help_description(
+                        "Manage one branch name across every repository in scope, or the\n\
+                         projects named (names, globs, or --group).\n\
+                         \n\
+                         create makes the branch in each repository: tracking the remote\n\
+                         branch when only the remote has it, otherwise starting at --from\n\
+                         (default: the project's default branch). --checkout also switches\n\
+                         each checkout to it. The workspace branch_policy applies.\n\
+                         \n\
+                         delete removes the branch wherever it is merged into the project's\n\
+                         default branch (squash and rebase merges count), leaving unmerged\n\
+                         and checked-out copies alone; --force deletes unmerged ones too.\n\
+                         \n\
+                         list shows which projects have the branch, locally or only on a\n\
+                         remote, and where it is checked out.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git branch create feat/login api web --checkout\n\
+                           meta git branch create release/2.0 --from v1.9.0 --group backend\n\
+                           meta git branch list feat/login\n\
+                           meta git branch delete feat/login",
+                    )
+                    .with_help_formatting()
+                    .subcommand(
+                        branch_subcommand("create")
+                            .about("Create the branch in each project")
+                            .arg(
+                                arg("from")
+                                    .long("from")
+                                    .help("Start point (default: each project's default branch)")
+                                    .takes_value(true),
+                            )
+                            .arg(
+                                arg("checkout")
+                                    .long("checkout")
+                                    .help("Switch each checkout to the new branch"),
+                            ),
+                    )
+                    .subcommand(
+                        branch_subcommand("delete")
+                            .about("Delete the branch where it has been merged")
+                            .arg(
+                                arg("force")
+                                    .long("force")
+                                    .help("Delete the branch even where it is not merged"),
+                            ),
+                    )
+                    .subcommand(
+                        branch_subcommand("list").about("Show which projects have the branch"),
+                    ),
+            )
+            .command(
+                branch_subcommand("checkout")
+                    .about("Switch projects to a branch")
+                    .help_description(
+                        "Switch the checkout of every repository in scope, or the projects\n\
+                         named, to one branch. An existing local branch is checked out; a\n\
+                         branch only the remote has is created tracking it; with --create a\n\
+                         missing branch is started at --from (default: the project's default\n\
+                         branch), subject to the workspace branch_policy.\n\
+                         \n\
+                         Repositories with uncommitted changes to tracked files, bare\n\
+                         projects, and branches checked out in another worktree are skipped\n\
+                         and listed; the rest are still switched.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git checkout feat/login\n\
+                           meta git checkout feat/login api web --create\n\
+                           meta git checkout main --all",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("create")
+                            .long("create")
+                            .help("Create the branch where neither the repository nor its remote has it"),
+                    )
+                    .arg(
+                        arg("from")
+                            .long("from")
+                            .help("Start point for created branches (default: each project's default branch)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("all")
+                            .long("all")
+                            .short('a')
+                            .help("Every project in the workspace, not just those in scope"),
+                    ),
+            )
+            .command(
+                command("commit")
+                    .about("Commit staged changes in every project with one message")
+                    .help_description(
+                        "Commit in every repository in scope (or the projects named, or\n\
+                         --group) that has staged changes, all with the same message. A\n\
+                         {project} placeholder in the message becomes each project's name.\n\
+                         \n\
+                         --all-staged stages changes to tracked files first, like 'git commit\n\
+                         -a'; untracked files are never added. --interactive lists what each\n\
+                         repository would commit and asks before committing it. Repositories\n\
+                         with nothing to commit are skipped and listed. The main repository is\n\
+                         included unless --skip-main; bare projects commit in their worktrees.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git commit -m \"Bump shared schema to v3\"\n\
+                           meta git commit -m \"chore({project}): update lockfile\" --all-staged\n\
+                           meta git commit -m \"Rename API client\" api web --interactive",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("message")
+                            .long("message")
+                            .short('m')
+                            .help("Commit message; {project} is replaced by each project's name")
+                            .required(true)
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("all-staged")
+                            .long("all-staged")
+                            .short('a')
+                            .help("Stage changes to tracked files before committing"),
+                    )
+                    .arg(
+                        arg("interactive")
+                            .long("interactive")
+                            .short('i')
+                            .help("Show each repository's changes and confirm before committing"),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    ),
+            )
+            .command(
+                command("diff")
+                    .about("Show uncommitted changes across projects, grouped by project")
+                    .help_description(
+                        "Show the uncommitted changes of every repository in scope (or the\n\
+                         projects named, or --group), each under a header with the project\n\
+                         name. Repositories without changes are left out. Like 'git diff',\n\
+                         this is the working tree against the index; --staged shows what the\n\
+                         next commit would take instead. Untracked files are not diffs.\n\
+                         \n\
+                         --stat and --name-only shorten each diff as in git. --json prints\n\
+                         the changed files of each project with their added and deleted\n\
+                         line counts. The main repository is included unless --skip-main;\n\
+                         bare projects show each worktree. Issue references in each branch\n\
+                         name (see issue_trackers in .meta) are shown as links and listed\n\
+                         under \"issues\" in the JSON output.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git diff --stat\n\
+                           meta git diff --staged api web\n\
+                           meta git diff --json --skip-main",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("staged")
+                            .long("staged")
+                            .help("Show staged changes (the index against HEAD)"),
+                    )
+                    .arg(
+                        arg("stat")
+                            .long("stat")
+                            .help("Show a diffstat per project instead of the patch"),
+                    )
+                    .arg(
+                        arg("name-only")
+                            .long("name-only")
+                            .help("Show only the names of changed files"),
+                    )
+                    .arg(
+                        arg("json")
+                            .long("json")
+                            .help("Print the changed files per project as JSON"),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    ),
+            )
+            .command(
+                command("log")
+                    .about("Show the commits of all projects as one timeline")
+                    .help_description(
+                        "Merge the history of every repository in scope (or the projects\n\
+                         named, or --group) into one timeline, newest first, each commit\n\
+                         prefixed with its project. Each repository contributes the commits\n\
+                         reachable from its checked-out HEAD.\n\
+                         \n\
+                         --since, --author, and --grep filter as in 'git log' and take the\n\
+                         same values. --json prints the commits with full hashes, author\n\
+                         emails, and Unix timestamps. The main repository is included unless\n\
+                         --skip-main. Issue references in subjects (see issue_trackers in\n\
+                         .meta) are shown as links and listed under \"issues\" in the JSON.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git log --since \"1 week ago\"\n\
+                           meta git log --since 2024-06-01 --author alice --skip-main\n\
+                           meta git log --grep \"^fix\" --json api web",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("since")
+                            .long("since")
+                            .help("Only commits newer than this date (e.g. \"2 weeks ago\", 2024-06-01)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("author")
+                            .long("author")
+                            .help("Only commits whose author matches this pattern")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("grep")
+                            .long("grep")
+                            .help("Only commits whose message matches this pattern")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("json")
+                            .long("json")
+                            .help("Print the timeline as JSON"),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    ),
+            )
+            .command(
+                command("resolve")
+                    .about("Step through the projects a pull left with conflicts")
+                    .help_description(
+                        "Work through the projects whose `meta git pull --rebase` hit\n\
+                         conflicts. The pull undoes those rebases and queues the projects;\n\
+                         for each queued project this redoes the rebase, lists the\n\
+                         conflicted files, and opens the merge tool (--tool, else git's\n\
+                         merge.tool) or, with none configured or --shell, a shell in the\n\
+                         project. When it returns, the rebase is continued with the\n\
+                         original commit messages.\n\
+                         \n\
+                         A project whose branch ends up on top of its upstream leaves the\n\
+                         queue. One still conflicted stays queued and mid-rebase; the next\n\
+                         run picks it up where it stopped, or run `git rebase --abort` in\n\
+                         it to give up. A later pull that brings a queued project up to\n\
+                         date also drops it. Name projects to resolve only those; --list\n\
+                         shows the queue.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git pull --rebase && meta git resolve\n\
+                           meta git resolve --list\n\
+                           meta git resolve api --tool vimdiff\n\
+                           meta git resolve --shell",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Queued projects to resolve (default: all)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("list")
+                            .long("list")
+                            .help("Show the queued projects without resolving anything"),
+                    )
+                    .arg(
+                        arg("tool")
+                            .long("tool")
+                            .short('t')
+                            .help("Merge tool to run (git mergetool --tool)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("shell")
+                            .long("shell")
+                            .help("Open a shell in each project instead of the merge tool"),
+                    ),
+            )
+            .command(
+                command("stash")
+                    .about("Stash changes across projects and restore exactly those")
+                    .help_description(
+                        "Stash the changes of every repository in scope (or the projects\n\
+                         named, or --group) that has any, and record which repositories\n\
+                         were stashed. 'meta git stash pop' restores the last such stash in\n\
+                         exactly those repositories, even if other stashes were pushed there\n\
+                         since. Untracked files stay unless --include-untracked.\n\
+                         \n\
+                         'meta git stash list' shows each repository's stash entries, grouped\n\
+                         by project, and marks the ones made by 'meta git stash'. The main\n\
+                         repository is included unless --skip-main; bare projects stash in\n\
+                         their worktrees.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git stash -m \"wip: before rebase\"\n\
+                           meta git pull && meta git stash pop\n\
+                           meta git stash list",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("message")
+                            .long("message")
+                            .short('m')
+                            .help("Stash message (default: \"meta git stash\")")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("include-untracked")
+                            .long("include-untracked")
+                            .short('u')
+                            .help("Stash untracked files too"),
+                    )
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    )
+                    .subcommand(
+                        command("pop").about("Restore the last workspace stash where it was made"),
+                    )
+                    .subcommand(
+                        command("list")
+                            .about("Show stash entries, grouped by project")
+                            .arg(
+                                arg("targets")
+                                    .help("Projects (names, aliases, or globs); default: all in scope")
+                                    .multiple(true),
+                            )
+                            .arg(
+                                arg("group")
+                                    .long("group")
+                                    .help("Select the members of a project group (repeatable)")
+                                    .multiple(true),
+                            )
+                            .arg(
+                                arg("exclude")
+                                    .long("exclude")
+                                    .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                                    .takes_value(true),
+                            )
+                            .arg(
+                                arg("strict")
+                                    .long("strict")
+                                    .help("Fail if any project selector matches nothing"),
+                            )
+                            .arg(
+                                arg("skip-main")
+                                    .long("skip-main")
+                                    .help("Skip the main meta repository"),
+                            ),
+                    ),
+            )
+            .command(
+                command("clean")
+                    .about("List or delete untracked files across projects")
+                    .help_description(
+                        "Show the untracked files 'git clean' would remove in every\n\
+                         repository in scope (or the projects named, or --group), grouped by\n\
+                         project. Nothing is deleted unless -f is given. -d takes untracked\n\
+                         directories too, and --ignored the files git ignores (build output,\n\
+                         caches) as 'git clean -x' does; meta's own -x is --experimental.\n\
+                         \n\
+                         Paths matching the workspace 'ignore' list are always kept, and so\n\
+                         are nested repositories. In the main repository the project\n\
+                         checkouts, .meta-state, and the workspace config are kept as well.\n\
+                         The main repository is included unless --skip-main; bare projects\n\
+                         clean each worktree. List 'clean' under 'protected' to have -f ask\n\
+                         for the workspace name first.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git clean -d --ignored\n\
+                           meta git clean -f -d api web\n\
+                           meta git clean -fd --ignored --skip-main",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("dry-run")
+                            .long("dry-run")
+                            .short('n')
+                            .help("Only list what would be removed (the default)"),
+                    )
+                    .arg(
+                        arg("force")
+                            .long("force")
+                            .short('f')
+                            .help("Delete the files"),
+                    )
+                    .arg(
+                        arg("directories")
+                            .long("directories")
+                            .short('d')
+                            .help("Remove untracked directories too"),
+                    )
+                    .arg(
+                        arg("ignored")
+                            .long("ignored")
+                            .help("Remove ignored files too (git clean -x)"),
+                    )
+                    .arg(
+                        arg(confirm::YES_FLAG)
+                            .long(confirm::YES_FLAG)
+                            .help("Confirm a protected clean without typing the workspace name"),
+                    )
+                    .arg(
+                        arg("skip-main")
+                            .long("skip-main")
+                            .help("Skip the main meta repository"),
+                    ),
+            )
+            .command(
+                command("tag")
+                    .about("Create the same tag in every project, all or nothing")
+                    .help_description(
+                        "Create a tag at HEAD of every repository in scope (or the projects\n\
+                         named, or --group). If any repository already has the tag (or, with\n\
+                         --push, its origin does), the conflicts are listed and no tag is\n\
+                         created; if creating one fails, the tags made so far are deleted.\n\
+                         \n\
+                         --annotate (or a --message) makes annotated tags. --push then pushes\n\
+                         the tag to each repository's origin; a failed push is reported and\n\
+                         the local tag kept, so the push can be retried.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta git tag v2.4.0 --annotate -m \"Release 2.4.0\" --push\n\
+                           meta git tag qa-2024-06-01 --group backend",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("name")
+                            .help("Tag name")
+                            .required(true)
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("targets")
+                            .help("Projects (names, aliases, or globs); default: all in scope")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("annotate")
+                            .long("annotate")
+                            .short('a')
+                            .help("Make an annotated tag (the message defaults to the tag name)"),
+                    )
+                    .arg(
+                        arg("message")
+                            .long("message")
+                            .short('m')
+                            .help("Tag message; implies --annotate")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("push")
+                            .long("push")
+                            .help("Push the tag to each project's origin"),
+                    ),
+            )
+            .handler("clone", handle_clone)
+            .handler("status", handle_status)
+            .handler("update", handle_update)
+            .handler("fetch", handle_fetch)
+            .handler("divergence", handle_divergence)
+            .handler("pull", handle_pull)
+            .handler("push", handle_push)
+            .handler("branch", handle_branch)
+            .handler("checkout", handle_checkout)
+            .handler("commit", handle_commit)
+            .handler("diff", handle_diff)
+            .handler("log", handle_log)
+            .handler("resolve", handle_resolve)
+            .handler("stash", handle_stash)
+            .handler("clean", handle_clean)
+            .handler("tag", handle_tag)
+            .build()
+    }
+}
+
+/// Handler for the clone command
+fn handle_clone(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let url = matches.get_one::<String>("url").unwrap();
+
+    let depth = parse_depth_arg(matches.get_one::<String>("depth"))?.or_else(|| {
+        config
+            .meta_config
+            .clone_settings
+            .as_ref()
+            .and_then(|c| c.depth)
+    });
+
+    throttle::configure(matches, &config.meta_config)?;
+    submodules::configure(matches, &config.meta_config);
+    let jobs = jobs_from(matches)?.unwrap_or(1);
+    println!("Cloning meta repository from: {}", url);
+
+    // Extract repo name from URL for directory name
+    let repo_name = url
+        .split('/')
+        .next_back()
+        .unwrap_or("meta-repo")
+        .trim_end_matches(".git");
+
+    let started = Instant::now();
+    let target_path = config.working_dir.join(repo_name);
+    clone_repository(url, &target_path, false, depth)?;
+
+    // After cloning, look for a workspace config and clone child repos. The
+    // cloned workspace's notify settings apply to the rest of its clone.
+    let mut notify_config = config.meta_config.clone();
+    if let Some(found) = MetaConfig::config_in_dir(&target_path) {
+        if let Ok(cloned) = MetaConfig::load_from_file_with_format(&found.path, found.format) {
+            notify_config = cloned;
+            submodules::configure(matches, &notify_config);
+        }
+        std::env::set_current_dir(&target_path)?;
+        // `--profile` names a profile of the workspace being cloned.
+        clone_missing_repos(
+            config.profile.as_deref(),
+            false,
+            jobs,
+            None,
+            &HashSet::new(),
+        )?;
+    }
+
+    notify::on_complete(&notify_config, "meta git clone", started.elapsed(), true);
+    Ok(())
+}
+
+/// Handler for the status command
+fn handle_status(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut scope = match &explicit {
+        Some(selected) => selected.clone(),
+        None => selection::implicit_scope(matches, config),
+    };
+    // Only show the workspace's main repository in the full-workspace view, not
+    // when scoped to a project or subdirectory, when projects were named, or
+    // when a tag filter picks them.
+    let show_main = explicit.is_none()
+        && !selection::has_tag_filter(matches)
+        && scope.len() == config.meta_config.projects.len();
+    selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+    selection::apply_tags(matches, config, &mut scope)?;
+    if scope.is_empty() {
+        println!("No projects in this directory.");
+        return Ok(());
+    }
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+
+    println!("Git status:");
+    println!("===========");
+
+    if show_main {
+        println!("\nMain repository:");
+        match get_git_status(&base_path) {
+            Ok(status) => println!("{}", status),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    for project_path in &scope {
+        let full_path = base_path.join(project_path);
+        if full_path.exists() {
+            println!("\n{}:", project_path);
+            match get_git_status(&full_path) {
+                Ok(status) => println!("{}", status),
+                Err(e) => println!("Error: {}", e),
+            }
+        } else {
+            println!("\n{}: (not cloned)", project_path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handler for the update command
+fn handle_update(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let only = selection::tagged_keys(matches, config)?;
+    let excluded = selection::excluded_keys(matches, config, false);
+    if matches.get_flag("plan") {
+        return plan_missing_clones(config.profile.as_deref(), only.as_ref(), &excluded);
+    }
+    throttle::configure(matches, &config.meta_config)?;
+    submodules::configure(matches, &config.meta_config);
+    let min_success = outcome::min_success(matches, &config.meta_config, "update")?;
+    let started = Instant::now();
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    // Read the lockfile before cloning so a missing one fails fast.
+    let frozen = if matches.get_flag("frozen") {
+        Some(Lockfile::require(&base_path)?)
+    } else {
+        None
+    };
+    let resume = matches.get_flag("resume");
+    let jobs = jobs_from(matches)?.unwrap_or(1);
+    if !resume {
+        println!("Cloning missing repositories...");
+    }
+    let cloned = clone_missing_repos(
+        config.profile.as_deref(),
+        resume,
+        jobs,
+        only.as_ref(),
+        &excluded,
+    )?;
+    let unlocked = match frozen {
+        Some(lock) => {
+            println!("\nChecking out the commits in {}...", LOCK_FILE);
+            check_out_locked(&base_path, &config.meta_config, &lock)
+        }
+        None => 0,
+    };
+    cloned.finish(
+        &config.meta_config,
+        &base_path,
+        "git update",
+        started,
+        min_success,
+    )?;
+    // A workspace that does not match meta.lock is not reproduced, however
+    // many clones succeeded.
+    if unlocked > 0 {
+        return Err(anyhow::anyhow!(
+            "{} project(s) could not be checked out at their locked commit",
+            unlocked
+        ));
+    }
+    Ok(())
+}
+
+/// Handler for the fetch command
+fn handle_fetch(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let jobs = if matches.get_flag("sequential") {
+        1
+    } else {
+        jobs_from(matches)?.unwrap_or_else(|| config.meta_config.worker_count())
+    };
+    let prune = matches.get_flag("prune");
+
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut scope = match &explicit {
+        Some(selected) => selected.clone(),
+        None => config.scoped_project_keys(),
+    };
+    let full_scope = explicit.is_none() && scope.len() == config.meta_config.projects.len();
+    selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+    let mut targets: Vec<ProjectInfo> = ProjectIterator::new(&config.meta_config, &base_path)
+        .with_scope(&scope)
+        .filter_existing()
+        .filter_git_repos()
+        .collect();
+    if full_scope && !matches.get_flag("skip-main") && base_path.join(".git").exists() {
+        targets.insert(0, main_repo_target(&base_path));
+    }
+    if targets.is_empty() {
+        println!("No repositories in scope.");
+        return Ok(());
+    }
+
+    let paths: Vec<PathBuf> = targets.iter().map(|p| p.path.clone()).collect();
+    let roots = pull::fetch_roots(&paths);
+    let total = roots.len();
+    println!(
+        "Fetching {} repositories{}...\n",
+        total,
+        if prune { " with --prune" } else { "" }
+    );
+    let results = parallel_map(roots, jobs, |i| {
+        (targets[i].name.clone(), fetch::fetch_all(&paths[i], prune))
+    });
+
+    let mut changed = 0;
+    let mut failed = 0;
+    for (name, result) in results {
+        match result {
+            Ok(changes) if changes.is_empty() => {}
+            Ok(changes) => {
+                changed += 1;
+                println!("  {} {}: {}", "✓".green(), name.bold(), changes.describe());
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), name, e);
+            }
+        }
+    }
+    let unchanged = total - changed - failed;
+    if changed > 0 || failed > 0 {
+        println!();
+    }
+    println!(
+        "Fetched {} repositories: {} with changes, {} up to date{}.",
+        total - failed,
+        changed,
+        unchanged,
+        if failed > 0 {
+            format!(", {} failed", failed)
+        } else {
+            String::new()
+        }
+    );
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} repositories failed to fetch", failed));
+    }
+    Ok(())
+}
+
+/// Handler for the divergence command
+fn handle_divergence(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let all_branches = matches.get_flag("all-branches");
+    let json = matches.get_flag("json");
+    let mut targets = selected_checkouts(matches, config, &base_path)?;
+    if all_branches {
+        // Worktrees of a bare project share its branches; list them once,
+        // under the project.
+        let paths: Vec<PathBuf> = targets.iter().map(|(_, p)| p.path.clone()).collect();
+        let roots: HashSet<usize> = pull::fetch_roots(&paths).into_iter().collect();
+        targets = targets
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| roots.contains(i))
+            .map(|(_, (key, mut project))| {
+                if project.name.starts_with(&format!("{} [", key)) {
+                    project.name = key.clone();
+                }
+                (key, project)
+            })
+            .collect();
+    }
+    if targets.is_empty() {
+        println!("No repositories in scope.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    if matches.get_flag("fetch") {
+        let paths: Vec<PathBuf> = targets.iter().map(|(_, p)| p.path.clone()).collect();
+        let roots = pull::fetch_roots(&paths);
+        if !json {
+            println!("Fetching {} repositories...", roots.len());
+        }
+        let fetched = parallel_map(roots, config.meta_config.worker_count(), |i| {
+            (targets[i].1.name.clone(), pull::fetch(&paths[i]))
+        });
+        for (name, result) in fetched {
+            if let Err(e) = result {
+                failed += 1;
+                eprintln!("  {} {}: {}", "✗".red(), name, e);
+            }
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (_, project) in &targets {
+        match divergence::branch_tracking(&project.path, all_branches) {
+            Ok(branches) => rows.extend(branches.into_iter().map(|b| (project, b))),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {} {}: {}", "✗".red(), project.name, e);
+            }
+        }
+    }
+    let diverged = rows
+        .iter()
+        .filter(|(_, b)| matches!(b.tracking, divergence::Tracking::Diverged { .. }))
+        .count();
+
+    if json {
+        let entries: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|(project, branch)| {
+                let mut entry = serde_json::json!({
+                    "project": project.name,
+                    "path": project.path,
+                });
+                if let (Some(entry), serde_json::Value::Object(fields)) =
+                    (entry.as_object_mut(), serde_json::to_value(branch)?)
+                {
+                    entry.extend(fields);
+                }
+                Ok(entry)
+            })
+            .collect::<Result<_>>()?;
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        let mut table = Table::new(["Project", "Branch", "Upstream", "State", "Details"])
+            .indent(2)
+            .shrink(4);
+        for (project, branch) in &rows {
+            use divergence::Tracking;
+            let label = branch.tracking.label();
+            match counts.iter_mut().find(|(l, _)| *l == label) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((label, 1)),
+            }
+            let (state, details) = match branch.tracking {
+                Tracking::UpToDate => (label.bright_black(), String::new()),
+                Tracking::Ahead { ahead } => {
+                    (label.cyan(), format!("{} commit(s) not pushed", ahead))
+                }
+                Tracking::Behind { behind } => {
+                    (label.cyan(), format!("{} commit(s) to pull", behind))
+                }
+                Tracking::Diverged { ahead, behind } => {
+                    (label.red(), format!("{} ahead, {} behind", ahead, behind))
+                }
+                Tracking::Gone => (label.yellow(), "deleted on the remote".to_string()),
+                Tracking::NoUpstream | Tracking::Detached => (label.yellow(), String::new()),
+            };
+            table.row([
+                project.name.bold().to_string(),
+                branch.branch.clone(),
+                branch.upstream.clone().unwrap_or_default(),
+                state.to_string(),
+                details,
+            ]);
+        }
+        if !rows.is_empty() {
+            println!();
+            table.print();
+            println!();
+        }
+        let summary: Vec<String> = counts
+            .iter()
+            .map(|(label, n)| format!("{} {}", n, label))
+            .collect();
+        println!("{} branch(es): {}", rows.len(), summary.join(", "));
+        if diverged > 0 {
+            println!(
+                "Diverged branches have commits on both sides; 'meta git pull --rebase' \
+                 replays local commits on top."
+            );
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow::anyhow!("could not check {} repositories", failed));
+    }
+    if diverged > 0 {
+        return Err(anyhow::anyhow!(
+            "{} branch(es) have diverged from their upstream",
+            diverged
+        ));
+    }
+    Ok(())
+}
+
+/// Handler for the pull command
+fn handle_pull(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    throttle::configure(matches, &config.meta_config)?;
+    let min_success = outcome::min_success(matches, &config.meta_config, "pull")?;
+    let started = Instant::now();
+
+    // Named projects win; otherwise the directory-aware scope decides which
+    // projects are pulled.
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut scope = match &explicit {
+        Some(selected) => selected.clone(),
+        None => config.scoped_project_keys(),
+    };
+    let full_scope = explicit.is_none() && scope.len() == config.meta_config.projects.len();
+    // Exclusion runs after inclusion: --exclude selectors always, and the
+    // workspace default_excludes unless projects were named.
+    selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+    if scope.is_empty() {
+        println!("No projects in this directory.");
+        return Ok(());
+    }
+
+    // Fetches are network-bound, so run them concurrently by default.
+    // `--sequential` restores one-at-a-time behavior; `--parallel` is kept for
+    // back-compat.
+    let jobs = if matches.get_flag("sequential") {
+        1
+    } else {
+        jobs_from(matches)?.unwrap_or_else(|| config.meta_config.worker_count())
+    };
+    let rebase = matches.get_flag("rebase");
+    // Pull the main repo only in the full-workspace view (or when not skipped).
+    let skip_main = matches.get_flag("skip-main") || !full_scope;
+    let shallow = matches.get_flag("shallow");
+
+    // Build iterator scoped to the in-scope projects, filtered to existing repos.
+    let mut iterator = ProjectIterator::new(&config.meta_config, &base_path)
+        .with_scope(&scope)
+        .filter_existing()
+        .filter_git_repos();
+
+    if let Some(patterns_str) = matches.get_one::<String>("include-only") {
+        let pattern_vec: Vec<String> = patterns_str.split(',').map(|s| s.to_string()).collect();
+        iterator = iterator.with_include_patterns(pattern_vec);
+    }
+
+    if let Some(patterns_str) = matches.get_one::<String>("exclude") {
+        let pattern_vec: Vec<String> = patterns_str.split(',').map(|s| s.to_string()).collect();
+        iterator = iterator.with_exclude_patterns(pattern_vec);
+    }
+
+    // Collect every candidate up front so the independent per-repo preflight
+    // checks (bare detection, uncommitted-change and upstream probes, worktree
+    // listing) can run concurrently rather than one repo at a time.
+    // Each candidate carries the project's stored shallow-clone depth (if any)
+    // so `--shallow` can re-truncate it after pulling; expanded bare-repo
+    // worktrees inherit the depth of the project they belong to.
+    let candidates: Vec<(ProjectInfo, Option<i32>)> = iterator
+        .map(|p| {
+            let depth = config.meta_config.get_project_depth(&p.name);
+            (p, depth)
+        })
+        .collect();
+
+    // Pinned projects are never pulled; they are only fetched so a remote
+    // that has moved past the pin can be reported.
+    let (pinned, mut candidates): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .partition(|(p, _)| config.meta_config.get_project_pin(&p.name).is_some());
+
+    // Treat the main meta repository as just another candidate so it goes
+    // through the same graceful skipping (uncommitted changes / no upstream)
+    // instead of aborting the whole run, and so it is pulled alongside the rest.
+    if !skip_main {
+        candidates.insert(0, (main_repo_target(&base_path), None));
+    }
+
+    // Expand each candidate into the directories that can actually be pulled.
+    // Regular repos pull in place; bare repos (whose top-level git dir is bare)
+    // pull in each managed worktree so we never hit
+    // "fatal: this operation must be run in a work tree". Worktrees with
+    // uncommitted changes are skipped to avoid conflicts.
+    let workers = config.meta_config.worker_count();
+    let classifications = parallel_map(candidates, workers, |(project, depth)| {
+        (classify_pull_target(project), depth)
+    });
+
+    let mut targets: Vec<(ProjectInfo, Option<i32>)> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut no_upstream: Vec<String> = Vec::new();
+
+    for (classification, depth) in classifications {
+        match classification {
+            PullTarget::Pull(project) => targets.push((project, depth)),
+            PullTarget::Skip(name) => skipped.push(name),
+            PullTarget::NoUpstream(name) => no_upstream.push(name),
+            PullTarget::Bare {
+                targets: t,
+                skipped: s,
+                no_upstream: u,
+            } => {
+                targets.extend(t.into_iter().map(|p| (p, depth)));
+                skipped.extend(s);
+                no_upstream.extend(u);
+            }
+        }
+    }
+
+    if !skipped.is_empty() {
+        println!(
+            "⚠️  Skipping {} target(s) with uncommitted changes:",
+            skipped.len()
+        );
+        for name in &skipped {
+            println!("   - {}", name);
+        }
+        println!();
+    }
+
+    if !pinned.is_empty() {
+        println!(
+            "📌 Leaving {} pinned project(s) at their pin:",
+            pinned.len()
+        );
+        let warnings = parallel_map(pinned, workers, |(project, _)| {
+            let reference = config
+                .meta_config
+                .get_project_pin(&project.name)
+                .unwrap_or_default();
+            let warning = pin::check(&project.name, &project.path, reference);
+            (project.name, reference.to_string(), warning)
+        });
+        for (name, reference, warning) in warnings {
+            println!("   - {} ({})", name, reference);
+            if let Some(warning) = warning {
+                println!("     ⚠️  {}", warning);
+            }
+        }
+        println!();
+    }
+
+    if !no_upstream.is_empty() {
+        println!(
+            "ℹ️  Skipping {} target(s) with no upstream tracking branch:",
+            no_upstream.len()
+        );
+        for name in &no_upstream {
+            println!("   - {}", name);
+        }
+        println!("   Set one with: git branch --set-upstream-to=origin/<branch>");
+        println!();
+    }
+
+    let refetch_targets: Vec<(ProjectInfo, i32)> = targets
+        .iter()
+        .filter_map(|(p, d)| d.map(|d| (p.clone(), d)))
+        .collect();
+    let pull_targets: Vec<ProjectInfo> = targets.into_iter().map(|(p, _)| p).collect();
+
+    // Phase one fetches concurrently, once per repository: worktrees of a
+    // bare project share its refs. Phase two updates each target from the
+    // fetched upstream, which is local work and cheap.
+    let paths: Vec<PathBuf> = pull_targets.iter().map(|p| p.path.clone()).collect();
+    let roots = pull::fetch_roots(&paths);
+    match throttle::limit() {
+        _ if roots.is_empty() => {}
+        Some(kbps) => println!(
+            "Fetching {} repositories at up to {} KB/s...",
+            roots.len(),
+            kbps
+        ),
+        None => println!("Fetching {} repositories...", roots.len()),
+    }
+    let fetch_all = || {
+        parallel_map(roots, jobs, |i| {
+            let result = pull::fetch(&paths[i]);
+            (pull::common_dir(&paths[i]), result)
+        })
+    };
+    // Concurrent fetches would draw over each other's progress line.
+    let fetched = if jobs > 1 {
+        transfer::without_bars(fetch_all)
+    } else {
+        fetch_all()
+    };
+    let fetch_errors: HashMap<PathBuf, String> = fetched
+        .into_iter()
+        .filter_map(|(dir, result)| result.err().map(|e| (dir, e.to_string())))
+        .collect();
+
+    let (outcomes, spans): (Vec<_>, Vec<_>) = parallel_map(pull_targets, jobs, |project| {
+        let project_started = Instant::now();
+        let outcome = match fetch_errors.get(&pull::common_dir(&project.path)) {
+            Some(e) => PullOutcome::Failed(e.clone()),
+            None => pull::update_from_upstream(&project.path, rebase),
+        };
+        (
+            (project.name, project.path, outcome),
+            (project_started, project_started.elapsed()),
+        )
+    })
+    .into_iter()
+    .unzip();
+    queue_conflicts(&base_path, &outcomes)?;
+    let outcomes: Vec<(String, PullOutcome)> = outcomes
+        .into_iter()
+        .map(|(name, _, outcome)| (name, outcome))
+        .collect();
+    print_pull_results(&outcomes);
+    let mut pulled = outcome::RunOutcome::default();
+    for ((name, result), (project_started, duration)) in outcomes.iter().zip(spans) {
+        let exit_code = if result.is_failure() { 1 } else { 0 };
+        pulled.record_span(name, Some(exit_code), project_started, duration);
+    }
+
+    // With --shallow, re-truncate each depth-tracked repository after the
+    // pull so its history shrinks back to the stored depth. This must run
+    // after (not before) pulling: a `fetch --depth` that moves the shallow
+    // boundary past the local HEAD leaves the local and remote branches with
+    // no visible common ancestor, and a subsequent `git pull` then fails
+    // with a divergent-branches error under default git configuration.
+    if shallow {
+        if refetch_targets.is_empty() {
+            println!("\nℹ️  --shallow: no projects in scope have a stored clone depth in .meta");
+        } else {
+            println!(
+                "\nRe-truncating {} shallow target(s) to their stored depth...",
+                refetch_targets.len()
+            );
+            let results = parallel_map(refetch_targets, workers, |(project, depth)| {
+                let result = crate::plugins::shared::refetch_shallow(&project.path, depth);
+                (project.name, depth, result)
+            });
+            for (name, depth, result) in results {
+                if let Err(e) = result {
+                    eprintln!("⚠️  {} (depth {}): {}", name, depth, e);
+                }
+            }
+        }
+    }
+
+    pulled.finish(
+        &config.meta_config,
+        &base_path,
+        "git pull",
+        started,
+        min_success,
+    )
+}
+
+/// Update the conflict queue with a pull's results, writing it only when
+/// something changed.
+fn queue_conflicts(base_path: &Path, outcomes: &[(String, PathBuf, PullOutcome)]) -> Result<()> {
+    let mut queue: resolve::ConflictQueue = state::load(base_path, resolve::CONFLICTS_FILE)?;
+    let before = queue.clone();
+    let now = state::now_secs();
+    for (name, path, outcome) in outcomes {
+        queue.record(name, path, outcome, now);
+    }
+    if queue != before {
+        state::save(base_path, resolve::CONFLICTS_FILE, &queue)?;
+    }
+    Ok(())
+}
+
+/// Handler for the resolve command
+fn handle_resolve(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let mut queue: resolve::ConflictQueue =
+        state::load(base_path.as_path(), resolve::CONFLICTS_FILE)?;
+    if queue.projects.is_empty() {
+        println!("No conflicts queued. 'meta git pull --rebase' queues projects whose rebase hits conflicts.");
+        return Ok(());
+    }
+
+    let names: Vec<String> = match matches.get_many::<String>("targets") {
+        Some(targets) => {
+            let targets: Vec<&String> = targets.collect();
+            let names: Vec<String> = queue
+                .projects
+                .keys()
+                .filter(|key| {
+                    targets.iter().any(|t| {
+                        *key == *t
+                            || key
+                                .strip_prefix(t.as_str())
+                                .is_some_and(|r| r.starts_with(" ["))
+                    })
+                })
+                .cloned()
+                .collect();
+            if names.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "None of the named projects are queued; 'meta git resolve --list' shows the queue"
+                ));
+            }
+            names
+        }
+        None => queue.projects.keys().cloned().collect(),
+    };
+
+    if matches.get_flag("list") {
+        let mut table = Table::new(["Project", "Queued", "Conflicted files"])
+            .indent(2)
+            .shrink(2);
+        for name in &names {
+            let entry = &queue.projects[name];
+            let state = if resolve::rebase_in_progress(&entry.path) {
+                " (mid-rebase)".yellow().to_string()
+            } else {
+                String::new()
+            };
+            table.row([
+                format!("{}{}", name.bold(), state),
+                state::format_timestamp(entry.queued),
+                entry.files.join(", "),
+            ]);
+        }
+        println!();
+        table.print();
+        println!();
+        return Ok(());
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow::anyhow!(
+            "meta git resolve opens a merge tool or shell and needs a terminal; use --list to see the queue"
+        ));
+    }
+
+    let tool = matches.get_one::<String>("tool").map(String::as_str);
+    let (mut resolved, total) = (0, names.len());
+    for (i, name) in names.iter().enumerate() {
+        let path = queue.projects[name].path.clone();
+        println!("\n{} {} ({}/{})", "▶".cyan(), name.bold(), i + 1, total);
+        if !path.exists() {
+            println!(
+                "  {} {} no longer exists; dropped from the queue",
+                "ℹ".bright_black(),
+                path.display()
+            );
+            queue.projects.remove(name);
+            state::save(&base_path, resolve::CONFLICTS_FILE, &queue)?;
+            continue;
+        }
+        let resolver = resolve::Resolver::pick(&path, tool, matches.get_flag("shell"));
+        let mut step = resolve::start(&path);
+        loop {
+            match step {
+                Ok(resolve::Step::Done) => {
+                    println!("  {} on top of its upstream", "✓".green());
+                    queue.projects.remove(name);
+                    resolved += 1;
+                    break;
+                }
+                Ok(resolve::Step::Conflicts(files)) => {
+                    println!("  Conflicts in:");
+                    for file in &files {
+                        println!("    {}", file.red());
+                    }
+                    if resolver == resolve::Resolver::Shell {
+                        println!(
+                            "  {}",
+                            "Opening a shell: resolve and `git add` the files, then exit."
+                                .bright_black()
+                        );
+                    }
+                    if let Err(e) = resolver.run(&path) {
+                        println!("  {} {}", "✗".red(), e);
+                        break;
+                    }
+                    step = resolve::continue_rebase(&path);
+                    if let Ok(resolve::Step::Conflicts(left)) = &step {
+                        if left == &files {
+                            let again = prompt_confirm(
+                                "Conflicts remain. Open it again?",
+                                true,
+                                NonInteractiveMode::Defaults,
+                            )?;
+                            if !again {
+                                println!("  {} still mid-rebase; stays queued", "⚠".yellow());
+                                if let Some(entry) = queue.projects.get_mut(name) {
+                                    entry.files = left.clone();
+                                }
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!(
+                        "  {} {}",
+                        "✗".red(),
+                        e.to_string().lines().next().unwrap_or_default()
+                    );
+                    println!(
+                        "  {}",
+                        "Stays queued; finish it in the project, or `git rebase --abort`."
+                            .bright_black()
+                    );
+                    break;
+                }
+            }
+        }
+        state::save(&base_path, resolve::CONFLICTS_FILE, &queue)?;
+    }
+
+    println!(
+        "\n{} resolved, {} still queued",
+        resolved,
+        queue.projects.len()
+    );
+    Ok(())
+}
+
+/// The main meta repository as a pull or push target.
+fn main_repo_target(base_path: &Path) -> ProjectInfo {
+    let main_name = base_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| format!("{} (main)", n))
+        .unwrap_or_else(|| "main repository".to_string());
+    ProjectInfo::new(main_name, base_path.to_path_buf(), "local".to_string())
+}
+
+/// The checkouts of the git projects in `scope` (and of the main repository,
+/// with `include_main`), each with the project key it belongs to. Bare
+/// repositories have nothing checked out at the top, so each of their
+/// worktrees stands in, named after its branch.
+fn checkout_targets(
+    config: &RuntimeConfig,
+    base_path: &Path,
+    scope: &[String],
+    include_main: bool,
+) -> Vec<(String, ProjectInfo)> {
+    let mut candidates: Vec<(String, ProjectInfo)> =
+        ProjectIterator::new(&config.meta_config, base_path)
+            .with_scope(scope)
+            .filter_existing()
+            .filter_git_repos()
+            .map(|project| (project.name.clone(), project))
+            .collect();
+    if include_main {
+        let key = base_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "main".to_string());
+        candidates.insert(0, (key, main_repo_target(base_path)));
+    }
+
+    let mut targets = Vec::new();
+    for (key, project) in candidates {
+        if !is_bare_repository(&project.path) {
+            targets.push((key, project));
+            continue;
+        }
+        for wt in list_worktrees(&project.path).unwrap_or_default() {
+            if wt.is_bare || !wt.path.exists() {
+                continue;
+            }
+            let branch = wt.branch.strip_prefix("refs/heads/").unwrap_or(&wt.branch);
+            let name = format!("{} [{}]", project.name, branch);
+            let info = ProjectInfo::new(name, wt.path, project.repo_url.clone());
+            targets.push((key.clone(), info));
+        }
+    }
+    targets
+}
+
+fn handle_push(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut scope = match &explicit {
+        Some(selected) => selected.clone(),
+        None => selection::implicit_scope(matches, config),
+    };
+    let full_scope = explicit.is_none()
+        && !selection::has_tag_filter(matches)
+        && scope.len() == config.meta_config.projects.len();
+    selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+    selection::apply_tags(matches, config, &mut scope)?;
+    if scope.is_empty() {
+        println!("No projects in this directory.");
+        return Ok(());
+    }
+    let set_upstream = matches.get_flag("set-upstream");
+    let tags = matches.get_flag("tags");
+    let force = matches.get_flag("force");
+
+    let targets: Vec<ProjectInfo> = checkout_targets(
+        config,
+        &base_path,
+        &scope,
+        !matches.get_flag("skip-main") && full_scope,
+    )
+    .into_iter()
+    .map(|(_, project)| project)
+    .collect();
+
+    let workers = config.meta_config.worker_count();
+    let plans = parallel_map(targets, workers, |project| {
+        let plan = push::plan(&project.path, set_upstream, tags, force);
+        (project, plan)
+    });
+
+    let mut table = Table::new(["Project", "Branch", "Destination", "Pushes"])
+        .indent(2)
+        .shrink(3);
+    let mut to_push = Vec::new();
+    let (mut current, mut skipped) = (0, 0);
+    for (project, plan) in plans {
+        match &plan {
+            PushPlan::Push {
+                branch,
+                remote,
+                target,
+                ahead,
+                set_upstream,
+                force,
+            } => {
+                let mut pushes = match ahead {
+                    Some(n) => format!("{} commit(s)", n),
+                    None => "new branch".to_string(),
+                };
+                if *force {
+                    pushes.push_str(", forced");
+                }
+                if *set_upstream {
+                    pushes.push_str(", sets upstream");
+                }
+                if tags {
+                    pushes.push_str(", tags");
+                }
+                table.row([
+                    project.name.bold().to_string(),
+                    branch.clone(),
+                    format!("{}/{}", remote, target).cyan().to_string(),
+                    pushes,
+                ]);
+                to_push.push((project, plan));
+            }
+            PushPlan::UpToDate { branch } => {
+                current += 1;
+                table.row([
+                    project.name.bold().to_string(),
+                    branch.clone(),
+                    String::new(),
+                    "up to date".bright_black().to_string(),
+                ]);
+            }
+            PushPlan::Skip(reason) => {
+                skipped += 1;
+                table.row([
+                    project.name.bold().to_string(),
+                    String::new(),
+                    String::new(),
+                    format!("skipped: {}", reason).yellow().to_string(),
+                ]);
+            }
+        }
+    }
+    if table.is_empty() {
+        println!("No repositories to push.");
+        return Ok(());
+    }
+    println!();
+    table.print();
+    println!();
+    println!(
+        "{} to push, {} up to date, {} skipped",
+        to_push.len(),
+        current,
+        skipped
+    );
+
+    if matches.get_flag("dry-run") {
+        println!("Dry run: nothing was pushed.");
+        return Ok(());
+    }
+    if to_push.is_empty() {
+        return Ok(());
+    }
+    let forced = to_push
+        .iter()
+        .filter(|(_, plan)| matches!(plan, PushPlan::Push { force: true, .. }))
+        .count();
+    if forced > 0 {
+        confirm::require(
+            confirm::FORCE_PUSH,
+            &format!("Force-pushing {} branch(es)", forced),
+            matches,
+            config,
+        )?;
+    }
+
+    println!("\nPushing {} repositories...", to_push.len());
+    let results = parallel_map(to_push, workers, |(project, plan)| {
+        let result = push::push(&project.path, &plan, tags);
+        (project.name, result)
+    });
+    let mut failed = 0;
+    for (name, result) in results {
+        match result {
+            Ok(()) => println!("  {} {}", "✓".green(), name),
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), name, e);
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} repositories failed to push", failed));
+    }
+    Ok(())
+}
+
+/// A `meta git branch` subcommand with the branch name and the project
+/// selection arguments every action shares.
+fn branch_subcommand(name: &str) -> CommandBuilder {
+    command(name)
+        .arg(
+            arg("name")
+                .help("Branch name")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            arg("targets")
+                .help("Projects (names, aliases, or globs); default: all in scope")
+                .multiple(true),
+        )
+        .arg(
+            arg("group")
+                .long("group")
+                .help("Select the members of a project group (repeatable)")
+                .multiple(true),
+        )
+        .arg(
+            arg("exclude")
+                .long("exclude")
+                .help("Skip projects matching these names, aliases, patterns, or tags (comma-separated)")
+                .takes_value(true),
+        )
+        .arg(
+            arg("strict")
+                .long("strict")
+                .help("Fail if any project selector matches nothing"),
+        )
+}
+
+fn handle_branch(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let Some((action, sub)) = matches.subcommand() else {
+        return Err(anyhow::anyhow!(
+            "Specify create, delete, or list. See 'meta git branch --help'."
+        ));
+    };
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let name = sub.get_one::<String>("name").unwrap();
+    let targets = branch_targets(sub, config, &base_path, false)?
+        .into_iter()
+        .map(|(project, path)| {
+            // Bare projects keep their branches in <project>/.git.
+            if config.meta_config.is_bare_repo(&project) {
+                (project, path.join(".git"))
+            } else {
+                (project, path)
+            }
+        })
+        .collect::<Vec<_>>();
+    if targets.is_empty() {
+        println!("No repositories in scope.");
+        return Ok(());
+    }
+
+    match action {
+        "create" => branch::create_branches(
+            &config.meta_config,
+            &targets,
+            name,
+            sub.get_one::<String>("from").map(String::as_str),
+            sub.get_flag("checkout"),
+        ),
+        "delete" => branch::delete_branches(&targets, name, sub.get_flag("force")),
+        _ => branch::list_branch(&targets, name),
+    }
+}
+
+fn handle_commit(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let template = matches.get_one::<String>("message").unwrap();
+    if template.trim().is_empty() {
+        return Err(anyhow::anyhow!("The commit message is empty."));
+    }
+    let all_staged = matches.get_flag("all-staged");
+    let interactive = matches.get_flag("interactive");
+    let non_interactive = config.non_interactive.unwrap_or(NonInteractiveMode::Fail);
+
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut scope = match &explicit {
+        Some(selected) => selected.clone(),
+        None => config.scoped_project_keys(),
+    };
+    let full_scope = explicit.is_none() && scope.len() == config.meta_config.projects.len();
+    selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+    let include_main = !matches.get_flag("skip-main") && full_scope;
+    let targets = checkout_targets(config, &base_path, &scope, include_main);
+    if targets.is_empty() {
+        println!("No repositories in scope.");
+        return Ok(());
+    }
+
+    let nothing = if all_staged {
+        "no changes to tracked files"
+    } else {
+        "nothing staged"
+    };
+    let (mut committed, mut skipped, mut failed) = (0, 0, 0);
+    for (key, project) in &targets {
+        let files = match commit::pending_files(&project.path, all_staged) {
+            Ok(files) => files,
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), project.name, e);
+                continue;
+            }
+        };
+        if files.is_empty() {
+            skipped += 1;
+            println!(
+                "  {} {} {}",
+                "·".yellow(),
+                project.name,
+                nothing.bright_black()
+            );
+            continue;
+        }
+        if interactive {
+            println!("\n  {}", project.name.bold());
+            for file in &files {
+                println!("      {}", file);
+            }
+            if !prompt_confirm(&format!("Commit {}?", project.name), true, non_interactive)? {
+                skipped += 1;
+                println!(
+                    "  {} {} {}",
+                    "·".yellow(),
+                    project.name,
+                    "declined".bright_black()
+                );
+                continue;
+            }
+        }
+        let message = commit::message_for(template, key);
+        match commit::commit(&project.path, &message, all_staged) {
+            Ok(id) => {
+                committed += 1;
+                let note = format!("{} ({} file(s))", id, files.len());
+                println!("  {} {} {}", "✓".green(), project.name, note.bright_black());
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), project.name, e);
+            }
+        }
+    }
+    println!(
+        "\nCommitted in {} of {} repositories; {} skipped.",
+        committed,
+        targets.len(),
+        skipped
+    );
+    if failed > 0 {
+        return Err(anyhow::anyhow!("commit failed in {} repositories", failed));
+    }
+    Ok(())
+}
+
+fn handle_diff(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let staged = matches.get_flag("staged");
+    let format = match (matches.get_flag("stat"), matches.get_flag("name-only")) {
+        (true, true) => {
+            return Err(anyhow::anyhow!(
+                "--stat and --name-only cannot be used together"
+            ))
+        }
+        (true, false) => diff::DiffFormat::Stat,
+        (false, true) => diff::DiffFormat::NameOnly,
+        (false, false) => diff::DiffFormat::Patch,
+    };
+    let json = matches.get_flag("json");
+    let targets = selected_checkouts(matches, config, &base_path)?;
 
-    // After cloning, look for a workspace config and clone child repos
-    if MetaConfig::config_in_dir(&target_path).is_some() {
-        std::env::set_current_dir(&target_path)?;
-        clone_missing_repos()?;
+    let mut changed = Vec::new();
+    let mut failed = 0;
+    for (key, project) in &targets {
+        match diff::changed_files(&project.path, staged) {
+            Ok(files) if files.is_empty() => {}
+            Ok(files) => {
+                // None on a detached HEAD.
+                let branch = git(
+                    &project.path,
+                    &["symbolic-ref", "--quiet", "--short", "HEAD"],
+                )
+                .ok();
+                changed.push((key, project, branch, files));
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {} {}: {}", "✗".red(), project.name, e);
+            }
+        }
     }
 
+    let meta = &config.meta_config;
+    let linker = IssueLinker::from_config(meta);
+    if json {
+        let entries: Vec<serde_json::Value> = changed
+            .iter()
+            .map(|(key, project, branch, files)| {
+                let issues = branch
+                    .as_deref()
+                    .map(|branch| linker.references(meta, key, branch))
+                    .unwrap_or_default();
+                serde_json::json!({
+                    "project": project.name,
+                    "path": project.path,
+                    "branch": branch,
+                    "issues": issues,
+                    "files": files,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if changed.is_empty() {
+        if failed == 0 {
+            println!("No uncommitted changes in {} repositories.", targets.len());
+        }
+    } else {
+        let color = colored::control::SHOULD_COLORIZE.should_colorize();
+        for (key, project, branch, files) in &changed {
+            let (added, deleted) = files.iter().fold((0, 0), |(a, d), f| {
+                (a + f.added.unwrap_or(0), d + f.deleted.unwrap_or(0))
+            });
+            let counts = format!("{} file(s), +{} -{}", files.len(), added, deleted);
+            let branch = branch
+                .as_deref()
+                .map(|branch| format!(" {}", linker.linkify(meta, key, branch).cyan()))
+                .unwrap_or_default();
+            println!(
+                "\n{}{} {}",
+                project.name.bold(),
+                branch,
+                counts.bright_black()
+            );
+            println!("{}", "─".repeat(60).bright_black());
+            match diff::diff_text(&project.path, staged, format, color) {
+                Ok(text) => println!("{}", text),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("  {} {}: {}", "✗".red(), project.name, e);
+                }
+            }
+        }
+        println!(
+            "\n{} of {} repositories have {} changes.",
+            changed.len(),
+            targets.len(),
+            if staged { "staged" } else { "unstaged" }
+        );
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!("diff failed in {} repositories", failed));
+    }
     Ok(())
 }
 
-/// Handler for the status command
-fn handle_status(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
-    let scope = config.scoped_project_keys();
-    if scope.is_empty() {
-        println!("No projects in this directory.");
-        return Ok(());
-    }
-    // Only show the workspace's main repository in the full-workspace view, not
-    // when scoped to a project or subdirectory.
-    let show_main = scope.len() == config.meta_config.projects.len();
+fn handle_log(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let base_path = config
         .meta_root()
-        .unwrap_or_else(|| config.working_dir.clone());
-
-    println!("Git status:");
-    println!("===========");
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let filter = log::LogFilter {
+        since: matches.get_one::<String>("since").cloned(),
+        author: matches.get_one::<String>("author").cloned(),
+        grep: matches.get_one::<String>("grep").cloned(),
+    };
+    let targets = selected_checkouts(matches, config, &base_path)?;
+    // Any checkout will do to read --since with.
+    let date_dir = targets
+        .iter()
+        .map(|(_, project)| project.path.as_path())
+        .find(|path| path.exists())
+        .unwrap_or(&base_path);
+    let query = filter.resolve(date_dir)?;
 
-    if show_main {
-        println!("\nMain repository:");
-        match get_git_status(&base_path) {
-            Ok(status) => println!("{}", status),
-            Err(e) => println!("Error: {}", e),
+    let mut logs = Vec::new();
+    let mut failed = 0;
+    for (key, project) in &targets {
+        match log::project_log(&project.path, key, &query) {
+            Ok(entries) => logs.push(entries),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {} {}: {}", "✗".red(), project.name, e);
+            }
         }
     }
+    let timeline = log::merge_timeline(logs);
+    let meta = &config.meta_config;
+    let linker = IssueLinker::from_config(meta);
 
-    for project_path in &scope {
-        let full_path = base_path.join(project_path);
-        if full_path.exists() {
-            println!("\n{}:", project_path);
-            match get_git_status(&full_path) {
-                Ok(status) => println!("{}", status),
-                Err(e) => println!("Error: {}", e),
-            }
-        } else {
-            println!("\n{}: (not cloned)", project_path);
+    if matches.get_flag("json") {
+        let entries: Vec<WithIssues<log::LogEntry>> = timeline
+            .iter()
+            .map(|entry| WithIssues {
+                entry,
+                issues: linker.references(meta, &entry.project, &entry.subject),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if timeline.is_empty() {
+        if failed == 0 {
+            println!("No matching commits in {} repositories.", targets.len());
+        }
+    } else {
+        let width = timeline
+            .iter()
+            .map(|entry| entry.project.chars().count())
+            .max()
+            .unwrap_or(0);
+        for entry in &timeline {
+            println!(
+                "{}  {}  {}  {} {}",
+                state::format_timestamp(entry.timestamp).bright_black(),
+                format!("{:<width$}", entry.project).cyan(),
+                entry.short_hash.yellow(),
+                linker.linkify(meta, &entry.project, &entry.subject),
+                format!("({})", entry.author).bright_black()
+            );
         }
+        let projects: HashSet<&str> = timeline.iter().map(|e| e.project.as_str()).collect();
+        println!(
+            "\n{} commit(s) in {} of {} repositories.",
+            timeline.len(),
+            projects.len(),
+            targets.len()
+        );
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!("log failed in {} repositories", failed));
     }
-
     Ok(())
 }
 
-/// Handler for the update command
-fn handle_update(_matches: &ArgMatches, _config: &RuntimeConfig) -> Result<()> {
-    println!("Cloning missing repositories...");
-    clone_missing_repos()?;
-    Ok(())
+/// The checkouts a diff, log, stash, or clean command acts on: the projects selected by `matches`
+/// (else those in scope), plus the main repository for a full-scope run
+/// without --skip-main.
+fn selected_checkouts(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+    base_path: &Path,
+) -> Result<Vec<(String, ProjectInfo)>> {
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut scope = match &explicit {
+        Some(selected) => selected.clone(),
+        None => config.scoped_project_keys(),
+    };
+    let full_scope = explicit.is_none() && scope.len() == config.meta_config.projects.len();
+    selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+    let include_main = !matches.get_flag("skip-main") && full_scope;
+    Ok(checkout_targets(config, base_path, &scope, include_main))
 }
 
-/// Handler for the pull command
-fn handle_pull(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+fn handle_stash(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let base_path = config
         .meta_root()
         .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    match matches.subcommand() {
+        Some(("pop", _)) => return pop_workspace_stash(&base_path),
+        Some(("list", sub)) => return list_stashes(sub, config, &base_path),
+        _ => {}
+    }
 
-    // Directory-aware scope: only the in-scope projects are pulled.
-    let scope = config.scoped_project_keys();
-    if scope.is_empty() {
-        println!("No projects in this directory.");
+    let message = matches
+        .get_one::<String>("message")
+        .map(String::as_str)
+        .unwrap_or("meta git stash");
+    let untracked = matches.get_flag("include-untracked");
+    let targets = selected_checkouts(matches, config, &base_path)?;
+    if targets.is_empty() {
+        println!("No repositories in scope.");
         return Ok(());
     }
-    let full_scope = scope.len() == config.meta_config.projects.len();
 
-    // Pulls are network-bound, so run them concurrently by default. `--sequential`
-    // restores one-at-a-time behavior; `--parallel` is kept for back-compat.
-    let parallel = !matches.get_flag("sequential");
-    // Pull the main repo only in the full-workspace view (or when not skipped).
-    let skip_main = matches.get_flag("skip-main") || !full_scope;
-    let shallow = matches.get_flag("shallow");
+    let mut stashed = Vec::new();
+    let mut failed = 0;
+    for (_, project) in &targets {
+        let result = stash::has_changes(&project.path, untracked).and_then(|dirty| {
+            dirty
+                .then(|| stash::push(&project.path, message, untracked))
+                .transpose()
+        });
+        match result {
+            Ok(Some(commit)) => {
+                println!("  {} {}", "✓".green(), project.name);
+                stashed.push(stash::StashedProject {
+                    name: project.name.clone(),
+                    path: project.path.clone(),
+                    commit,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}: {}", "✗".red(), project.name, e);
+            }
+        }
+    }
 
-    // Build iterator scoped to the in-scope projects, filtered to existing repos.
-    let mut iterator = ProjectIterator::new(&config.meta_config, &base_path)
-        .with_scope(&scope)
-        .filter_existing()
-        .filter_git_repos();
+    if stashed.is_empty() {
+        if failed == 0 {
+            println!("Nothing to stash in {} repositories.", targets.len());
+        }
+    } else {
+        let count = stashed.len();
+        let mut log: stash::StashLog = state::load(&base_path, stash::STASH_FILE)?;
+        log.stashes.push(stash::WorkspaceStash {
+            created: state::now_secs(),
+            message: message.to_string(),
+            projects: stashed,
+        });
+        state::save(&base_path, stash::STASH_FILE, &log)?;
+        println!(
+            "\nStashed {} of {} repositories; 'meta git stash pop' restores them.",
+            count,
+            targets.len()
+        );
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!("stash failed in {} repositories", failed));
+    }
+    Ok(())
+}
 
-    if let Some(patterns_str) = matches.get_one::<String>("include-only") {
-        let pattern_vec: Vec<String> = patterns_str.split(',').map(|s| s.to_string()).collect();
-        iterator = iterator.with_include_patterns(pattern_vec);
+/// Pop the most recent workspace stash in the repositories it was made in.
+/// Repositories that fail to restore stay recorded for another pop.
+fn pop_workspace_stash(base_path: &Path) -> Result<()> {
+    let mut log: stash::StashLog = state::load(base_path, stash::STASH_FILE)?;
+    let Some(mut last) = log.stashes.pop() else {
+        println!("No workspace stash to pop.");
+        return Ok(());
+    };
+    println!(
+        "Restoring \"{}\" from {}\n",
+        last.message,
+        state::format_timestamp(last.created)
+    );
+
+    let mut failed = Vec::new();
+    for project in last.projects.drain(..) {
+        match stash::pop(&project.path, &project.commit) {
+            Ok(true) => println!("  {} {}", "✓".green(), project.name),
+            Ok(false) => println!(
+                "  {} {} {}",
+                "·".yellow(),
+                project.name,
+                "stash already gone".bright_black()
+            ),
+            Err(e) => {
+                println!("  {} {}: {}", "✗".red(), project.name, e);
+                failed.push(project);
+            }
+        }
     }
 
-    if let Some(patterns_str) = matches.get_one::<String>("exclude") {
-        let pattern_vec: Vec<String> = patterns_str.split(',').map(|s| s.to_string()).collect();
-        iterator = iterator.with_exclude_patterns(pattern_vec);
+    let count = failed.len();
+    if !failed.is_empty() {
+        last.projects = failed;
+        log.stashes.push(last);
     }
+    state::save(base_path, stash::STASH_FILE, &log)?;
+    if count > 0 {
+        return Err(anyhow::anyhow!(
+            "{} repositories could not be restored; fix them and run 'meta git stash pop' again",
+            count
+        ));
+    }
+    Ok(())
+}
 
-    // Collect every candidate up front so the independent per-repo preflight
-    // checks (bare detection, uncommitted-change and upstream probes, worktree
-    // listing) can run concurrently rather than one repo at a time.
-    // Each candidate carries the project's stored shallow-clone depth (if any)
-    // so `--shallow` can re-truncate it after pulling; expanded bare-repo
-    // worktrees inherit the depth of the project they belong to.
-    let mut candidates: Vec<(ProjectInfo, Option<i32>)> = iterator
-        .map(|p| {
-            let depth = config.meta_config.get_project_depth(&p.name);
-            (p, depth)
+/// Print the stash entries of each checkout in scope, marking those made by
+/// `meta git stash`.
+fn list_stashes(matches: &ArgMatches, config: &RuntimeConfig, base_path: &Path) -> Result<()> {
+    let log: stash::StashLog = state::load(base_path, stash::STASH_FILE)?;
+    let ours: HashMap<&str, &str> = log
+        .stashes
+        .iter()
+        .flat_map(|s| {
+            s.projects
+                .iter()
+                .map(|p| (p.commit.as_str(), s.message.as_str()))
         })
         .collect();
 
-    // Treat the main meta repository as just another candidate so it goes
-    // through the same graceful skipping (uncommitted changes / no upstream)
-    // instead of aborting the whole run, and so it is pulled alongside the rest.
-    if !skip_main {
-        let main_name = base_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|n| format!("{} (main)", n))
-            .unwrap_or_else(|| "main repository".to_string());
-        candidates.insert(
-            0,
-            (
-                ProjectInfo::new(main_name, base_path.to_path_buf(), "local".to_string()),
-                None,
-            ),
+    let mut shown = 0;
+    for (_, project) in selected_checkouts(matches, config, base_path)? {
+        let entries = match stash::list(&project.path) {
+            Ok(entries) if entries.is_empty() => continue,
+            Ok(entries) => entries,
+            Err(e) => {
+                println!("  {} {}: {}", "✗".red(), project.name, e);
+                continue;
+            }
+        };
+        shown += 1;
+        println!("\n  {}", project.name.bold());
+        for entry in entries {
+            if ours.contains_key(entry.commit.as_str()) {
+                println!("      {} {}", entry.line, "(meta)".cyan());
+            } else {
+                println!("      {}", entry.line);
+            }
+        }
+    }
+    if shown == 0 {
+        println!("No stashes.");
+    } else if !log.stashes.is_empty() {
+        println!(
+            "\n{} workspace stash(es); 'meta git stash pop' restores the newest.",
+            log.stashes.len()
         );
     }
+    Ok(())
+}
 
-    // Expand each candidate into the directories that can actually be pulled.
-    // Regular repos pull in place; bare repos (whose top-level git dir is bare)
-    // pull in each managed worktree so we never hit
-    // "fatal: this operation must be run in a work tree". Worktrees with
-    // uncommitted changes are skipped to avoid conflicts.
-    let workers = std::thread::available_parallelism()
-        .map(|n| n.get())
-        .unwrap_or(4);
-    let classifications = parallel_map(candidates, workers, |(project, depth)| {
-        (classify_pull_target(project), depth)
-    });
+/// Paths `meta git clean` keeps at `path`: the workspace `ignore` list, and
+/// in the main repository everything meta keeps there itself.
+fn clean_keep(config: &RuntimeConfig, base_path: &Path, path: &Path) -> Vec<String> {
+    let mut keep = config.meta_config.ignore.clone();
+    if path != base_path {
+        return keep;
+    }
+    keep.push(format!("/{}", state::STATE_DIR));
+    if let Some(file) = config
+        .meta_file_path
+        .as_ref()
+        .and_then(|file| file.file_name())
+    {
+        keep.push(format!("/{}", file.to_string_lossy()));
+    }
+    for project in ProjectIterator::new(&config.meta_config, base_path) {
+        if let Ok(relative) = project.path.strip_prefix(base_path) {
+            keep.push(format!("/{}", relative.to_string_lossy()));
+        }
+    }
+    keep
+}
 
-    let mut targets: Vec<(ProjectInfo, Option<i32>)> = Vec::new();
-    let mut skipped: Vec<String> = Vec::new();
-    let mut no_upstream: Vec<String> = Vec::new();
+fn handle_clean(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let force = matches.get_flag("force");
+    if force && matches.get_flag("dry-run") {
+        return Err(anyhow::anyhow!("-n and -f cannot be used together"));
+    }
+    let options = clean::CleanOptions {
+        force,
+        directories: matches.get_flag("directories"),
+        ignored: matches.get_flag("ignored"),
+    };
+    let targets = selected_checkouts(matches, config, &base_path)?;
+    if targets.is_empty() {
+        println!("No repositories in scope.");
+        return Ok(());
+    }
+    if force {
+        confirm::require(
+            confirm::CLEAN,
+            &format!("Deleting untracked files in {} repositories", targets.len()),
+            matches,
+            config,
+        )?;
+    }
 
-    for (classification, depth) in classifications {
-        match classification {
-            PullTarget::Pull(project) => targets.push((project, depth)),
-            PullTarget::Skip(name) => skipped.push(name),
-            PullTarget::NoUpstream(name) => no_upstream.push(name),
-            PullTarget::Bare {
-                targets: t,
-                skipped: s,
-                no_upstream: u,
-            } => {
-                targets.extend(t.into_iter().map(|p| (p, depth)));
-                skipped.extend(s);
-                no_upstream.extend(u);
+    let mut cleaned = 0;
+    let mut paths = 0;
+    let mut failed = 0;
+    for (_, project) in &targets {
+        let keep = clean_keep(config, &base_path, &project.path);
+        match clean::clean(&project.path, options, &keep) {
+            Ok(removed) if removed.is_empty() => {}
+            Ok(removed) => {
+                cleaned += 1;
+                paths += removed.len();
+                println!("\n  {}", project.name.bold());
+                for path in removed {
+                    println!("      {}", path);
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {} {}: {}", "✗".red(), project.name, e);
             }
         }
     }
 
-    if !skipped.is_empty() {
+    if cleaned == 0 {
+        if failed == 0 {
+            println!("Nothing to clean in {} repositories.", targets.len());
+        }
+    } else if force {
         println!(
-            "⚠️  Skipping {} target(s) with uncommitted changes:",
-            skipped.len()
+            "\nRemoved {} path(s) in {} of {} repositories.",
+            paths,
+            cleaned,
+            targets.len()
         );
-        for name in &skipped {
-            println!("   - {}", name);
-        }
-        println!();
-    }
-
-    if !no_upstream.is_empty() {
+    } else {
         println!(
-            "ℹ️  Skipping {} target(s) with no upstream tracking branch:",
-            no_upstream.len()
+            "\n{} path(s) in {} of {} repositories would be removed; rerun with -f to delete them.",
+            paths,
+            cleaned,
+            targets.len()
         );
-        for name in &no_upstream {
-            println!("   - {}", name);
-        }
-        println!("   Set one with: git branch --set-upstream-to=origin/<branch>");
-        println!();
     }
+    if failed > 0 {
+        return Err(anyhow::anyhow!("clean failed in {} repositories", failed));
+    }
+    Ok(())
+}
 
-    let refetch_targets: Vec<(ProjectInfo, i32)> = targets
-        .iter()
-        .filter_map(|(p, d)| d.map(|d| (p.clone(), d)))
-        .collect();
-    let pull_targets: Vec<ProjectInfo> = targets.into_iter().map(|(p, _)| p).collect();
-
-    // `include_main` is false here: the main repo, when not skipped, is already
-    // part of `targets` so it is filtered and pulled like any other repository.
-    execute_with_projects(
-        "git",
-        &["pull"],
-        pull_targets,
-        false,
-        parallel,
-        false,
-        false,
-    )?;
+/// The existing git repositories a branch command acts on: the projects
+/// named, else every one in scope (or, with `all`, in the workspace), less
+/// any excludes.
+fn branch_targets(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+    base_path: &Path,
+    all: bool,
+) -> Result<Vec<(String, PathBuf)>> {
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut scope = match &explicit {
+        Some(projects) => projects.clone(),
+        None if all => config.meta_config.projects.keys().cloned().collect(),
+        None => config.scoped_project_keys(),
+    };
+    selection::apply_excludes(matches, config, &mut scope, explicit.is_some());
+    Ok(ProjectIterator::new(&config.meta_config, base_path)
+        .with_scope(&scope)
+        .filter_existing()
+        .filter_git_repos()
+        .map(|p| (p.name, p.path))
+        .collect())
+}
 
-    // With --shallow, re-truncate each depth-tracked repository after the
-    // pull so its history shrinks back to the stored depth. This must run
-    // after (not before) pulling: a `fetch --depth` that moves the shallow
-    // boundary past the local HEAD leaves the local and remote branches with
-    // no visible common ancestor, and a subsequent `git pull` then fails
-    // with a divergent-branches error under default git configuration.
-    if shallow {
-        if refetch_targets.is_empty() {
-            println!("\nℹ️  --shallow: no projects in scope have a stored clone depth in .meta");
-        } else {
-            println!(
-                "\nRe-truncating {} shallow target(s) to their stored depth...",
-                refetch_targets.len()
-            );
-            let results = parallel_map(refetch_targets, workers, |(project, depth)| {
-                let result = crate::plugins::shared::refetch_shallow(&project.path, depth);
-                (project.name, depth, result)
-            });
-            for (name, depth, result) in results {
-                if let Err(e) = result {
-                    eprintln!("⚠️  {} (depth {}): {}", name, depth, e);
-                }
+fn handle_tag(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let name = matches.get_one::<String>("name").unwrap();
+    let message = match matches.get_one::<String>("message") {
+        Some(message) => Some(message.as_str()),
+        None if matches.get_flag("annotate") => Some(name.as_str()),
+        None => None,
+    };
+    let targets = branch_targets(matches, config, &base_path, false)?
+        .into_iter()
+        .map(|(project, path)| {
+            // Bare projects keep their tags in <project>/.git.
+            if config.meta_config.is_bare_repo(&project) {
+                (project, path.join(".git"))
+            } else {
+                (project, path)
             }
-        }
+        })
+        .collect::<Vec<_>>();
+    if targets.is_empty() {
+        println!("No repositories in scope.");
+        return Ok(());
     }
+    tag::create_tags(&targets, name, message, matches.get_flag("push"))
+}
 
-    Ok(())
+fn handle_checkout(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let name = matches.get_one::<String>("name").unwrap();
+    let all = matches.get_flag("all");
+    if all && matches.get_many::<String>("targets").is_some() {
+        return Err(anyhow::anyhow!("Name projects or pass --all, not both."));
+    }
+    let targets = branch_targets(matches, config, &base_path, all)?;
+    if targets.is_empty() {
+        println!("No repositories in scope.");
+        return Ok(());
+    }
+    branch::checkout_branches(
+        &config.meta_config,
+        &targets,
+        name,
+        matches.get_flag("create"),
+        matches.get_one::<String>("from").map(String::as_str),
+    )
+}
+
+/// Print one row per pulled target and a summary line.
+fn print_pull_results(outcomes: &[(String, PullOutcome)]) {
+    if outcomes.is_empty() {
+        println!("Nothing to pull.");
+        return;
+    }
+    let (mut updated, mut current, mut diverged, mut conflicted, mut failed) = (0, 0, 0, 0, 0);
+    let mut table = Table::new(["Project", "Result", "Details"])
+        .indent(2)
+        .shrink(2);
+    for (name, outcome) in outcomes {
+        let (result, details) = match outcome {
+            PullOutcome::UpToDate { ahead } => {
+                current += 1;
+                let details = match ahead {
+                    0 => String::new(),
+                    n => format!("{} local commit(s) not pushed", n),
+                };
+                ("up to date".bright_black(), details)
+            }
+            PullOutcome::Updated(n) => {
+                updated += 1;
+                ("updated".green(), format!("fast-forwarded {} commit(s)", n))
+            }
+            PullOutcome::Rebased { ahead, behind } => {
+                updated += 1;
+                (
+                    "rebased".green(),
+                    format!("{} local commit(s) onto {} upstream", ahead, behind),
+                )
+            }
+            PullOutcome::Diverged { ahead, behind } => {
+                diverged += 1;
+                (
+                    "diverged".yellow(),
+                    format!("{} ahead, {} behind", ahead, behind),
+                )
+            }
+            PullOutcome::Conflicted(files) => {
+                conflicted += 1;
+                (
+                    "conflicts".red(),
+                    format!("{} file(s); rebase undone", files.len()),
+                )
+            }
+            PullOutcome::Failed(e) => {
+                failed += 1;
+                (
+                    "failed".red(),
+                    e.lines().next().unwrap_or_default().to_string(),
+                )
+            }
+        };
+        table.row([name.bold().to_string(), result.to_string(), details]);
+    }
+    println!();
+    table.print();
+    println!();
+    println!(
+        "{} updated, {} up to date, {} diverged, {} conflicted, {} failed",
+        updated, current, diverged, conflicted, failed
+    );
+    if diverged > 0 {
+        println!(
+            "Diverged branches were left as they were; rerun with --rebase to replay local \
+             commits on top, or merge them by hand."
+        );
+    }
+    if conflicted > 0 {
+        println!(
+            "{} project(s) hit conflicts and were left unchanged; run 'meta git resolve' to \
+             step through them.",
+            conflicted
+        );
+    }
 }
 
 /// Outcome of inspecting a single candidate before pulling.
@@ -471,48 +3078,6 @@ fn classify_pull_target(project: ProjectInfo) -> PullTarget {
     }
 }
 
-/// Apply `f` to every item across a bounded pool of worker threads, preserving
-/// input order in the returned vector.
-///
-/// Used to run the independent, per-repository preflight checks concurrently.
-/// Falls back to a plain sequential map when there is nothing to gain.
-fn parallel_map<T, R>(items: Vec<T>, workers: usize, f: impl Fn(T) -> R + Sync) -> Vec<R>
-where
-    T: Send,
-    R: Send,
-{
-    let len = items.len();
-    if len <= 1 || workers <= 1 {
-        return items.into_iter().map(f).collect();
-    }
-
-    let workers = workers.min(len);
-    let queue: std::sync::Mutex<std::collections::VecDeque<(usize, T)>> =
-        std::sync::Mutex::new(items.into_iter().enumerate().collect());
-    let slots: Vec<std::sync::Mutex<Option<R>>> =
-        (0..len).map(|_| std::sync::Mutex::new(None)).collect();
-
-    std::thread::scope(|scope| {
-        for _ in 0..workers {
-            scope.spawn(|| loop {
-                let next = queue.lock().unwrap().pop_front();
-                match next {
-                    Some((index, item)) => {
-                        let result = f(item);
-                        *slots[index].lock().unwrap() = Some(result);
-                    }
-                    None => break,
-                }
-            });
-        }
-    });
-
-    slots
-        .into_iter()
-        .map(|slot| slot.into_inner().unwrap().expect("worker filled slot"))
-        .collect()
-}
-
 /// Determine whether the git repository discovered at `path` is bare.
 ///
 /// Metarepo clones bare repositories into `<project>/.git` and checks branches
@@ -0,0 +1,91 @@
+//! Per-project progress of `meta git update` and `meta git clone`, saved in the
+//! workspace state directory after every project. A run that dies partway
+//! (a dropped network, Ctrl-C) leaves behind the projects it never finished,
+//! so the next run can redo exactly those: including a half-written
+//! directory, which would otherwise look like a finished clone.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// State file holding the progress of the most recent clone run.
+pub const CLONE_PROGRESS_FILE: &str = "clone-progress.json";
+
+/// Where one project's clone got to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloneState {
+    /// Queued, not started yet.
+    Pending,
+    /// Started but not finished: the run was interrupted mid-clone.
+    Cloning,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectProgress {
+    pub state: CloneState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The saved progress of a clone run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloneProgress {
+    /// Unix seconds when the run started.
+    #[serde(default)]
+    pub started: u64,
+    #[serde(default)]
+    pub projects: BTreeMap<String, ProjectProgress>,
+}
+
+impl CloneProgress {
+    /// Projects the run did not finish: failed, interrupted, or never reached.
+    pub fn incomplete(&self) -> Vec<String> {
+        self.projects
+            .iter()
+            .filter(|(_, p)| p.state != CloneState::Done)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Whether a directory for `project` is a leftover of a clone that was
+    /// interrupted or failed, rather than a finished checkout.
+    pub fn partial(&self, project: &str) -> bool {
+        self.projects
+            .get(project)
+            .is_some_and(|p| matches!(p.state, CloneState::Cloning | CloneState::Failed))
+    }
+
+    pub fn set(&mut self, project: &str, state: CloneState, error: Option<String>) {
+        self.projects
+            .insert(project.to_string(), ProjectProgress { state, error });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_finished_projects_are_complete() {
+        let mut progress = CloneProgress::default();
+        progress.set("api", CloneState::Done, None);
+        progress.set("web", CloneState::Cloning, None);
+        progress.set("docs", CloneState::Failed, Some("timed out".to_string()));
+        progress.set("cli", CloneState::Pending, None);
+
+        assert_eq!(progress.incomplete(), ["cli", "docs", "web"]);
+        assert!(progress.partial("web"));
+        assert!(progress.partial("docs"));
+        assert!(!progress.partial("api"));
+        assert!(!progress.partial("cli"));
+
+        let json = serde_json::to_string(&progress).unwrap();
+        assert!(json.contains(r#""state":"cloning""#));
+        assert_eq!(
+            serde_json::from_str::<CloneProgress>(&json).unwrap(),
+            progress
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! The two phases of `meta git pull`: fetch every repository concurrently,
+//! then bring each checked-out branch up to its upstream locally.
+//!
+//! Splitting the network work from the update keeps the slow part parallel
+//! and lets each project's result be classified instead of passed through
+//! from `git pull`: a branch that has diverged from its upstream is reported
+//! (or rebased with `--rebase`) rather than failing the run. A rebase that
+//! hits conflicts is undone and the project queued for `meta git resolve`.
+
+use crate::plugins::shared::{fetch_origin, git, throttle};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What pulling one target did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PullOutcome {
+    /// Nothing new upstream; `ahead` counts local commits not yet pushed.
+    UpToDate {
+        ahead: usize,
+    },
+    /// Fast-forwarded by this many commits.
+    Updated(usize),
+    /// Local commits replayed on top of the upstream with `--rebase`.
+    Rebased {
+        ahead: usize,
+        behind: usize,
+    },
+    /// Both sides have new commits and `--rebase` was not given; left as is.
+    Diverged {
+        ahead: usize,
+        behind: usize,
+    },
+    /// The `--rebase` stopped on conflicts in these files and was aborted,
+    /// leaving the branch unchanged.
+    Conflicted(Vec<String>),
+    Failed(String),
+}
+
+impl PullOutcome {
+    /// Whether the target counts as failed in the run's exit code. Diverged
+    /// branches are reported, not failed.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, PullOutcome::Conflicted(_) | PullOutcome::Failed(_))
+    }
+}
+
+/// The repository whose refs a fetch in `path` updates. Worktrees of one
+/// bare project share it, so fetching once per common dir is enough.
+pub fn common_dir(path: &Path) -> PathBuf {
+    git(path, &["rev-parse", "--git-common-dir"])
+        .ok()
+        .and_then(|dir| path.join(dir).canonicalize().ok())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Indices of the first target per common dir: the ones worth fetching.
+pub fn fetch_roots(paths: &[PathBuf]) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    (0..paths.len())
+        .filter(|&i| seen.insert(common_dir(&paths[i])))
+        .collect()
+}
+
+/// Fetch every remote of the repository at `path`. With a `--limit-rate`
+/// set, fetch `origin` through the throttled libgit2 transport instead, as
+/// the git CLI cannot be rate-limited.
+pub fn fetch(path: &Path) -> Result<()> {
+    if throttle::limit().is_some() {
+        return fetch_origin(path);
+    }
+    git(path, &["fetch", "--all", "--quiet"])
+        .map(|_| ())
+        .map_err(|e| anyhow::anyhow!("fetch failed: {}", e))
+}
+
+/// Commits on HEAD but not its upstream, and on the upstream but not HEAD.
+pub(super) fn ahead_behind(path: &Path) -> Result<(usize, usize)> {
+    let counts = git(
+        path,
+        &["rev-list", "--left-right", "--count", "HEAD...@{u}"],
+    )?;
+    let mut parts = counts.split_whitespace().map(str::parse::<usize>);
+    match (parts.next(), parts.next()) {
+        (Some(Ok(ahead)), Some(Ok(behind))) => Ok((ahead, behind)),
+        _ => Err(anyhow::anyhow!("unexpected rev-list output '{}'", counts)),
+    }
+}
+
+/// Paths with unresolved conflicts in the checkout at `path`.
+pub fn conflicted_files(path: &Path) -> Result<Vec<String>> {
+    Ok(git(path, &["diff", "--name-only", "--diff-filter=U"])?
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+/// Bring the branch checked out at `path` up to its already-fetched
+/// upstream: fast-forward when possible, rebase a diverged branch when
+/// `rebase` is set, and otherwise leave a diverged branch untouched.
+pub fn update_from_upstream(path: &Path, rebase: bool) -> PullOutcome {
+    let (ahead, behind) = match ahead_behind(path) {
+        Ok(counts) => counts,
+        Err(e) => return PullOutcome::Failed(e.to_string()),
+    };
+    if behind == 0 {
+        return PullOutcome::UpToDate { ahead };
+    }
+    if ahead == 0 {
+        return match git(path, &["merge", "--ff-only", "--quiet", "@{u}"]) {
+            Ok(_) => PullOutcome::Updated(behind),
+            Err(e) => PullOutcome::Failed(e.to_string()),
+        };
+    }
+    if !rebase {
+        return PullOutcome::Diverged { ahead, behind };
+    }
+    match git(path, &["rebase", "--quiet", "@{u}"]) {
+        Ok(_) => PullOutcome::Rebased { ahead, behind },
+        Err(e) => {
+            let files = conflicted_files(path).unwrap_or_default();
+            // Put the branch back as it was rather than leave a half-done
+            // rebase behind in a repository nobody is looking at; `meta git
+            // resolve` redoes it with someone watching.
+            let _ = git(path, &["rebase", "--abort"]);
+            if files.is_empty() {
+                PullOutcome::Failed(format!("rebase failed; aborted, branch unchanged: {}", e))
+            } else {
+                PullOutcome::Conflicted(files)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    fn commit(repo: &Path, file: &str, text: &str) {
+        std::fs::write(repo.join(file), text).unwrap();
+        run(repo, &["add", "."]);
+        run(repo, &["commit", "-q", "-m", text]);
+    }
+
+    fn identify(repo: &Path) {
+        run(repo, &["config", "user.email", "test@example.com"]);
+        run(repo, &["config", "user.name", "Test"]);
+    }
+
+    /// An upstream repository with one commit and a clone of it.
+    fn setup() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let clone = tmp.path().join("clone");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        identify(&upstream);
+        commit(&upstream, "a.txt", "one");
+        run(
+            tmp.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "clone"],
+        );
+        identify(&clone);
+        (tmp, upstream, clone)
+    }
+
+    #[test]
+    fn fast_forwards_and_reports_divergence() {
+        let (_tmp, upstream, clone) = setup();
+        assert_eq!(
+            update_from_upstream(&clone, false),
+            PullOutcome::UpToDate { ahead: 0 }
+        );
+
+        commit(&upstream, "a.txt", "two");
+        commit(&upstream, "a.txt", "three");
+        fetch(&clone).unwrap();
+        assert_eq!(update_from_upstream(&clone, false), PullOutcome::Updated(2));
+
+        commit(&upstream, "a.txt", "four");
+        commit(&clone, "b.txt", "local");
+        fetch(&clone).unwrap();
+        let diverged = PullOutcome::Diverged {
+            ahead: 1,
+            behind: 1,
+        };
+        assert_eq!(update_from_upstream(&clone, false), diverged);
+        assert!(!diverged.is_failure());
+        assert_eq!(
+            update_from_upstream(&clone, true),
+            PullOutcome::Rebased {
+                ahead: 1,
+                behind: 1
+            }
+        );
+        assert_eq!(
+            update_from_upstream(&clone, false),
+            PullOutcome::UpToDate { ahead: 1 }
+        );
+    }
+
+    #[test]
+    fn conflicting_rebase_is_aborted() {
+        let (_tmp, upstream, clone) = setup();
+        commit(&upstream, "a.txt", "theirs");
+        commit(&clone, "a.txt", "ours");
+        fetch(&clone).unwrap();
+        let head = git(&clone, &["rev-parse", "HEAD"]).unwrap();
+
+        let outcome = update_from_upstream(&clone, true);
+        assert_eq!(outcome, PullOutcome::Conflicted(vec!["a.txt".to_string()]));
+        assert!(outcome.is_failure());
+        assert_eq!(git(&clone, &["rev-parse", "HEAD"]).unwrap(), head);
+        assert!(!clone.join(".git/rebase-merge").exists());
+        assert_eq!(fetch_roots(&[clone.clone(), clone]), [0]);
+    }
+}
@@ -0,0 +1,236 @@
+//! `meta git push`: work out what each checkout's current branch would push,
+//! and where, so the whole set can be shown (or only shown, with
+//! `--dry-run`) before anything leaves the machine.
+
+use super::pull::ahead_behind;
+use crate::plugins::shared::git;
+use anyhow::Result;
+use std::path::Path;
+
+/// What pushing one checkout would do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushPlan {
+    /// Push `branch` to `target` on `remote`. `ahead` is `None` when the
+    /// remote has no such branch yet. `force` overwrites a diverged upstream
+    /// (with a lease, so commits pushed since the last fetch are not lost).
+    Push {
+        branch: String,
+        remote: String,
+        target: String,
+        ahead: Option<usize>,
+        set_upstream: bool,
+        force: bool,
+    },
+    /// The upstream already has every commit.
+    UpToDate { branch: String },
+    /// Cannot be pushed as it stands; carries the reason.
+    Skip(String),
+}
+
+/// Plan the push of the branch checked out at `path`. A branch without an
+/// upstream is skipped unless `set_upstream`, which pushes it to `origin`
+/// under the same name. With `tags`, an up-to-date branch is still pushed so
+/// its tags go along. A branch that has diverged from its upstream is skipped
+/// unless `force`.
+pub fn plan(path: &Path, set_upstream: bool, tags: bool, force: bool) -> PushPlan {
+    let Ok(branch) = git(path, &["symbolic-ref", "--short", "-q", "HEAD"]) else {
+        return PushPlan::Skip("detached HEAD".to_string());
+    };
+    let remote = git(path, &["config", &format!("branch.{branch}.remote")]);
+    let merge = git(path, &["config", &format!("branch.{branch}.merge")]);
+    let (remote, target) = match (remote, merge) {
+        (Ok(remote), Ok(merge)) => {
+            let target = merge.strip_prefix("refs/heads/").unwrap_or(&merge);
+            (remote, target.to_string())
+        }
+        _ if !set_upstream => {
+            return PushPlan::Skip("no upstream branch; pass --set-upstream".to_string())
+        }
+        _ if git(path, &["remote", "get-url", "origin"]).is_err() => {
+            return PushPlan::Skip("no origin remote".to_string())
+        }
+        _ => {
+            return PushPlan::Push {
+                remote: "origin".to_string(),
+                target: branch.clone(),
+                branch,
+                ahead: None,
+                set_upstream: true,
+                force: false,
+            }
+        }
+    };
+
+    // An upstream whose remote branch is gone reads as a new branch.
+    let mut diverged = false;
+    let ahead = match ahead_behind(path) {
+        Ok((ahead, behind)) if ahead > 0 && behind > 0 && force => {
+            diverged = true;
+            Some(ahead)
+        }
+        Ok((ahead, behind)) if ahead > 0 && behind > 0 => {
+            return PushPlan::Skip(format!(
+                "diverged from {}/{} ({} ahead, {} behind); pull first, or pass --force",
+                remote, target, ahead, behind
+            ));
+        }
+        Ok((0, _)) if !tags => return PushPlan::UpToDate { branch },
+        Ok((ahead, _)) => Some(ahead),
+        Err(_) => None,
+    };
+    PushPlan::Push {
+        branch,
+        remote,
+        target,
+        ahead,
+        set_upstream: false,
+        force: diverged,
+    }
+}
+
+/// Carry out `plan` in the checkout at `path`.
+pub fn push(path: &Path, plan: &PushPlan, tags: bool) -> Result<()> {
+    let PushPlan::Push {
+        branch,
+        remote,
+        target,
+        set_upstream,
+        force,
+        ..
+    } = plan
+    else {
+        return Ok(());
+    };
+    let refspec = format!("refs/heads/{}:refs/heads/{}", branch, target);
+    let mut args = vec!["push", "--quiet"];
+    if *set_upstream {
+        args.push("--set-upstream");
+    }
+    if tags {
+        args.push("--tags");
+    }
+    if *force {
+        args.push("--force-with-lease");
+    }
+    args.extend([remote.as_str(), refspec.as_str()]);
+    git(path, &args).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    fn commit(repo: &Path, text: &str) {
+        std::fs::write(repo.join("a.txt"), text).unwrap();
+        run(repo, &["add", "."]);
+        run(repo, &["commit", "-q", "-m", text]);
+    }
+
+    /// A bare remote and a clone of it with `main` pushed and tracked.
+    fn setup() -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let remote = tmp.path().join("remote.git");
+        let clone = tmp.path().join("clone");
+        std::fs::create_dir(&remote).unwrap();
+        std::fs::create_dir(&clone).unwrap();
+        run(&remote, &["init", "-q", "--bare", "-b", "main"]);
+        run(&clone, &["init", "-q", "-b", "main"]);
+        run(&clone, &["config", "user.email", "test@example.com"]);
+        run(&clone, &["config", "user.name", "Test"]);
+        run(
+            &clone,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        );
+        commit(&clone, "one");
+        run(&clone, &["push", "-q", "-u", "origin", "main"]);
+        (tmp, clone)
+    }
+
+    #[test]
+    fn plans_and_pushes_tracked_branches() {
+        let (_tmp, clone) = setup();
+        let up_to_date = PushPlan::UpToDate {
+            branch: "main".to_string(),
+        };
+        assert_eq!(plan(&clone, false, false, false), up_to_date);
+
+        commit(&clone, "two");
+        let planned = plan(&clone, false, false, false);
+        assert_eq!(
+            planned,
+            PushPlan::Push {
+                branch: "main".to_string(),
+                remote: "origin".to_string(),
+                target: "main".to_string(),
+                ahead: Some(1),
+                set_upstream: false,
+                force: false,
+            }
+        );
+        push(&clone, &planned, false).unwrap();
+        assert_eq!(plan(&clone, false, false, false), up_to_date);
+    }
+
+    #[test]
+    fn new_branches_need_set_upstream() {
+        let (_tmp, clone) = setup();
+        run(&clone, &["switch", "-q", "-c", "feature"]);
+        commit(&clone, "feature work");
+        assert!(matches!(
+            plan(&clone, false, false, false),
+            PushPlan::Skip(_)
+        ));
+
+        let planned = plan(&clone, true, false, false);
+        assert!(matches!(
+            planned,
+            PushPlan::Push {
+                ahead: None,
+                set_upstream: true,
+                ..
+            }
+        ));
+        push(&clone, &planned, false).unwrap();
+        assert_eq!(
+            plan(&clone, false, false, false),
+            PushPlan::UpToDate {
+                branch: "feature".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn diverged_branches_need_force() {
+        let (_tmp, clone) = setup();
+        commit(&clone, "two");
+        run(&clone, &["push", "-q"]);
+        run(&clone, &["reset", "-q", "--hard", "HEAD~1"]);
+        commit(&clone, "rewritten");
+        assert!(matches!(
+            plan(&clone, false, false, false),
+            PushPlan::Skip(_)
+        ));
+
+        let planned = plan(&clone, false, false, true);
+        assert!(matches!(
+            planned,
+            PushPlan::Push {
+                ahead: Some(1),
+                force: true,
+                ..
+            }
+        ));
+        push(&clone, &planned, false).unwrap();
+        assert_eq!(
+            plan(&clone, false, false, false),
+            PushPlan::UpToDate {
+                branch: "main".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! `meta git resolve`: step through the projects a pull left conflicted.
+//!
+//! When `meta git pull --rebase` cannot replay a project's commits cleanly it
+//! aborts the rebase, so the branch stays as it was, and queues the project
+//! in `.meta-state/git-conflicts.json`. `meta git resolve` works through the
+//! queue one project at a time: it redoes the rebase, hands the conflicts to
+//! the merge tool or a shell, continues the rebase once they are resolved,
+//! and drops the project from the queue when its branch sits on top of its
+//! upstream. A project still conflicted stays queued, mid-rebase, for the
+//! next run; a later pull that brings a project up to date drops it too.
+
+use super::pull::{ahead_behind, conflicted_files, PullOutcome};
+use crate::plugins::shared::git;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// State file holding the conflict queue.
+pub const CONFLICTS_FILE: &str = "git-conflicts.json";
+
+/// A project waiting for its conflicts to be resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedConflict {
+    pub path: PathBuf,
+    /// Unix seconds when a pull last hit the conflicts.
+    pub queued: u64,
+    /// The conflicted files as of then.
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// The contents of [`CONFLICTS_FILE`], keyed by target name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictQueue {
+    #[serde(default)]
+    pub projects: BTreeMap<String, QueuedConflict>,
+}
+
+impl ConflictQueue {
+    /// Fold one pull result in: a conflicted target is queued with its
+    /// files, and a target the pull brought up to date leaves the queue.
+    /// Diverged and failed targets keep whatever entry they had.
+    pub fn record(&mut self, name: &str, path: &Path, outcome: &PullOutcome, now: u64) {
+        match outcome {
+            PullOutcome::Conflicted(files) => {
+                self.projects.insert(
+                    name.to_string(),
+                    QueuedConflict {
+                        path: path.to_path_buf(),
+                        queued: now,
+                        files: files.clone(),
+                    },
+                );
+            }
+            PullOutcome::UpToDate { .. }
+            | PullOutcome::Updated(_)
+            | PullOutcome::Rebased { .. } => {
+                self.projects.remove(name);
+            }
+            PullOutcome::Diverged { .. } | PullOutcome::Failed(_) => {}
+        }
+    }
+}
+
+/// Where a project's rebase stands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// The branch is on top of its upstream: nothing left to resolve.
+    Done,
+    /// The rebase is stopped on conflicts in these files.
+    Conflicts(Vec<String>),
+}
+
+/// Whether a rebase is stopped in the checkout at `path`.
+pub fn rebase_in_progress(path: &Path) -> bool {
+    ["rebase-merge", "rebase-apply"].iter().any(|dir| {
+        git(path, &["rev-parse", "--git-path", dir])
+            .map(|p| path.join(p).exists())
+            .unwrap_or(false)
+    })
+}
+
+/// The conflicts of a stopped rebase, or `Done` once it has finished.
+fn stopped(path: &Path) -> Result<Step> {
+    if !rebase_in_progress(path) {
+        return Ok(Step::Done);
+    }
+    Ok(Step::Conflicts(conflicted_files(path)?))
+}
+
+/// Start rebasing the branch at `path` onto its upstream, or pick up a
+/// rebase an earlier run left stopped, continuing it if its conflicts have
+/// been resolved since.
+pub fn start(path: &Path) -> Result<Step> {
+    if rebase_in_progress(path) {
+        return continue_rebase(path);
+    }
+    let (_, behind) = ahead_behind(path)?;
+    if behind == 0 {
+        return Ok(Step::Done);
+    }
+    match git(path, &["rebase", "--quiet", "@{u}"]) {
+        Ok(_) => Ok(Step::Done),
+        Err(e) if rebase_in_progress(path) => stopped(path).or(Err(e)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Continue a stopped rebase whose conflicts have all been resolved, for as
+/// long as it goes without new ones. Commit messages are kept as they were.
+pub fn continue_rebase(path: &Path) -> Result<Step> {
+    loop {
+        let step = stopped(path)?;
+        match &step {
+            Step::Done => return Ok(step),
+            Step::Conflicts(files) if !files.is_empty() => return Ok(step),
+            Step::Conflicts(_) => {}
+        }
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["rebase", "--continue"])
+            .env("GIT_EDITOR", "true")
+            .output()
+            .context("Failed to run git")?;
+        if !output.status.success() && conflicted_files(path)?.is_empty() {
+            // Stopped for something other than conflicts, such as a commit
+            // the resolution emptied: leave that to a person.
+            return Err(anyhow::anyhow!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+}
+
+/// How conflicts are handed to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolver {
+    /// `git mergetool`, with this tool when named.
+    MergeTool(Option<String>),
+    /// An interactive shell in the project.
+    Shell,
+}
+
+impl Resolver {
+    /// `--tool` when given, else the merge tool git is configured with at
+    /// `path`, else a shell.
+    pub fn pick(path: &Path, tool: Option<&str>, shell: bool) -> Self {
+        if shell {
+            return Resolver::Shell;
+        }
+        if let Some(tool) = tool {
+            return Resolver::MergeTool(Some(tool.to_string()));
+        }
+        match git(path, &["config", "merge.tool"]) {
+            Ok(tool) if !tool.is_empty() => Resolver::MergeTool(None),
+            _ => Resolver::Shell,
+        }
+    }
+
+    /// Run the resolver in `path` and wait for it.
+    pub fn run(&self, path: &Path) -> Result<()> {
+        let mut cmd = match self {
+            Resolver::MergeTool(tool) => {
+                let mut cmd = Command::new("git");
+                cmd.arg("mergetool");
+                if let Some(tool) = tool {
+                    cmd.args(["--tool", tool]);
+                }
+                cmd
+            }
+            Resolver::Shell => Command::new(shell_program()),
+        };
+        cmd.current_dir(path)
+            .status()
+            .with_context(|| format!("Failed to start {}", self.describe()))?;
+        Ok(())
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Resolver::MergeTool(Some(tool)) => format!("git mergetool --tool {}", tool),
+            Resolver::MergeTool(None) => "git mergetool".to_string(),
+            Resolver::Shell => shell_program(),
+        }
+    }
+}
+
+/// The user's shell.
+fn shell_program() -> String {
+    let (var, fallback) = if cfg!(windows) {
+        ("COMSPEC", "cmd.exe")
+    } else {
+        ("SHELL", "sh")
+    };
+    std::env::var(var)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::git::pull::update_from_upstream;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    fn commit(repo: &Path, file: &str, text: &str) {
+        std::fs::write(repo.join(file), text).unwrap();
+        run(repo, &["add", "."]);
+        run(repo, &["commit", "-q", "-m", text]);
+    }
+
+    fn identify(repo: &Path) {
+        run(repo, &["config", "user.email", "test@example.com"]);
+        run(repo, &["config", "user.name", "Test"]);
+    }
+
+    #[test]
+    fn rebase_stops_on_conflicts_and_continues_once_resolved() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let clone = tmp.path().join("clone");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        identify(&upstream);
+        commit(&upstream, "a.txt", "one");
+        run(
+            tmp.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "clone"],
+        );
+        identify(&clone);
+        commit(&upstream, "a.txt", "theirs");
+        commit(&clone, "a.txt", "ours");
+        run(&clone, &["fetch", "-q"]);
+
+        let mut queue = ConflictQueue::default();
+        let outcome = update_from_upstream(&clone, true);
+        queue.record("api", &clone, &outcome, 1);
+        assert_eq!(queue.projects["api"].files, ["a.txt"]);
+
+        let conflicts = Step::Conflicts(vec!["a.txt".to_string()]);
+        assert_eq!(start(&clone).unwrap(), conflicts);
+        // Nothing resolved yet: continuing stops where it was.
+        assert_eq!(continue_rebase(&clone).unwrap(), conflicts);
+        // A second run picks the stopped rebase up instead of starting over.
+        assert_eq!(start(&clone).unwrap(), conflicts);
+
+        std::fs::write(clone.join("a.txt"), "both").unwrap();
+        run(&clone, &["add", "a.txt"]);
+        // Resolved outside a run: the next one just finishes the rebase.
+        assert_eq!(start(&clone).unwrap(), Step::Done);
+        assert!(!rebase_in_progress(&clone));
+        assert_eq!(ahead_behind(&clone).unwrap(), (1, 0));
+        assert_eq!(start(&clone).unwrap(), Step::Done);
+
+        queue.record("api", &clone, &PullOutcome::UpToDate { ahead: 1 }, 2);
+        assert!(queue.projects.is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+//! `meta git stash`: stash the changes of every dirty checkout at once, and
+//! remember which ones, so `meta git stash pop` restores exactly those.
+//!
+//! Each `meta git stash` pushes a [`WorkspaceStash`] onto the list kept in
+//! `.meta-state/git-stash.json`. Every project's entry holds the id of the
+//! stash commit it made, so a pop finds the right entry even when other
+//! stashes were pushed in that repository since.
+
+use crate::plugins::shared::git;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// State file holding the workspace stashes, oldest first.
+pub const STASH_FILE: &str = "git-stash.json";
+
+/// One project's part of a workspace stash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StashedProject {
+    pub name: String,
+    pub path: PathBuf,
+    /// The stash commit, as `git rev-parse stash@{0}` gave it.
+    pub commit: String,
+}
+
+/// The projects one `meta git stash` stashed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceStash {
+    pub created: u64,
+    pub message: String,
+    pub projects: Vec<StashedProject>,
+}
+
+/// The contents of [`STASH_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StashLog {
+    #[serde(default)]
+    pub stashes: Vec<WorkspaceStash>,
+}
+
+/// Whether the checkout at `path` has anything `git stash` would take.
+pub fn has_changes(path: &Path, include_untracked: bool) -> Result<bool> {
+    let untracked = if include_untracked {
+        "--untracked-files=normal"
+    } else {
+        "--untracked-files=no"
+    };
+    Ok(!git(path, &["status", "--porcelain", untracked])?.is_empty())
+}
+
+/// Stash the changes at `path` and return the stash commit's id.
+pub fn push(path: &Path, message: &str, include_untracked: bool) -> Result<String> {
+    let mut args = vec!["stash", "push", "--quiet", "--message", message];
+    if include_untracked {
+        args.push("--include-untracked");
+    }
+    git(path, &args)?;
+    git(path, &["rev-parse", "stash@{0}"])
+}
+
+/// The `stash@{n}` reference of stash commit `commit` at `path`, if the
+/// stash is still there.
+fn stash_ref(path: &Path, commit: &str) -> Result<Option<String>> {
+    let ids = git(path, &["stash", "list", "--format=%H"])?;
+    Ok(ids
+        .lines()
+        .position(|id| id == commit)
+        .map(|n| format!("stash@{{{}}}", n)))
+}
+
+/// Apply and drop stash commit `commit` at `path`. Returns false, doing
+/// nothing, when that stash is no longer there. On a conflict git keeps the
+/// stash and this fails.
+pub fn pop(path: &Path, commit: &str) -> Result<bool> {
+    match stash_ref(path, commit)? {
+        Some(reference) => git(path, &["stash", "pop", "--quiet", &reference]).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// One entry of a repository's stash list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StashEntry {
+    pub commit: String,
+    /// `stash@{n}: message`, as `git stash list` shows it.
+    pub line: String,
+}
+
+/// The stash entries at `path`, newest first.
+pub fn list(path: &Path) -> Result<Vec<StashEntry>> {
+    Ok(git(path, &["stash", "list", "--format=%H %gd: %gs"])?
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(commit, line)| StashEntry {
+            commit: commit.to_string(),
+            line: line.to_string(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    #[test]
+    fn pops_its_own_stash_past_newer_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        run(dir, &["init", "-q", "-b", "main"]);
+        run(dir, &["config", "user.email", "test@example.com"]);
+        run(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        run(dir, &["add", "."]);
+        run(dir, &["commit", "-q", "-m", "init"]);
+        assert!(!has_changes(dir, true).unwrap());
+
+        std::fs::write(dir.join("a.txt"), "mine").unwrap();
+        std::fs::write(dir.join("new.txt"), "new").unwrap();
+        assert!(has_changes(dir, false).unwrap());
+        let mine = push(dir, "meta: mine", false).unwrap();
+        // Untracked files stay unless asked for.
+        assert!(!has_changes(dir, false).unwrap());
+        assert!(has_changes(dir, true).unwrap());
+        let theirs = push(dir, "theirs", true).unwrap();
+        assert_ne!(mine, theirs);
+        let entries = list(dir).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].commit, mine);
+        assert_eq!(entries[1].line, "stash@{1}: On main: meta: mine");
+
+        assert!(pop(dir, &mine).unwrap());
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "mine");
+        assert_eq!(list(dir).unwrap().len(), 1);
+        assert!(!pop(dir, &mine).unwrap());
+    }
+}
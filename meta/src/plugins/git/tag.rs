@@ -0,0 +1,187 @@
+//! `meta git tag`: one tag across projects, all or nothing. A tag that any
+//! project already has (locally, or on origin when pushing) stops the run
+//! before anything is created, and a failed creation removes the tags made
+//! so far.
+
+use crate::plugins::shared::git;
+use anyhow::Result;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// Whether the repository at `path` has tag `name`.
+fn has_tag(path: &Path, name: &str) -> Result<bool> {
+    Ok(!git(path, &["tag", "--list", name])?.is_empty())
+}
+
+/// Whether origin has tag `name`.
+fn origin_has_tag(path: &Path, name: &str) -> Result<bool> {
+    let reference = format!("refs/tags/{}", name);
+    Ok(!git(path, &["ls-remote", "--tags", "origin", &reference])?.is_empty())
+}
+
+/// Why `name` cannot be created at `path`, if it cannot.
+fn conflict(path: &Path, name: &str, push: bool) -> Result<Option<&'static str>> {
+    if has_tag(path, name)? {
+        return Ok(Some("already has the tag"));
+    }
+    if push && origin_has_tag(path, name)? {
+        return Ok(Some("origin already has the tag"));
+    }
+    Ok(None)
+}
+
+fn create_one(path: &Path, name: &str, message: Option<&str>) -> Result<String> {
+    match message {
+        Some(message) => git(path, &["tag", "--annotate", name, "--message", message])?,
+        None => git(path, &["tag", name])?,
+    };
+    git(path, &["rev-parse", "--short", "HEAD"])
+}
+
+/// Create tag `name` at HEAD of every repository in `targets`, annotated
+/// when `message` is given, then push it to origin when `push`. Nothing is
+/// created unless every repository can take the tag. Push failures are
+/// reported; the local tags stay so the push can be retried.
+pub fn create_tags(
+    targets: &[(String, PathBuf)],
+    name: &str,
+    message: Option<&str>,
+    push: bool,
+) -> Result<()> {
+    if let Some(first) = targets.first() {
+        git(
+            &first.1,
+            &["check-ref-format", &format!("refs/tags/{}", name)],
+        )
+        .map_err(|_| anyhow::anyhow!("'{}' is not a valid tag name", name))?;
+    }
+
+    let mut conflicts = 0;
+    for (project, path) in targets {
+        match conflict(path, name, push) {
+            Ok(None) => {}
+            Ok(Some(reason)) => {
+                conflicts += 1;
+                println!("  {} {}: {}", "✗".red(), project, reason);
+            }
+            Err(e) => {
+                conflicts += 1;
+                println!("  {} {}: {}", "✗".red(), project, e);
+            }
+        }
+    }
+    if conflicts > 0 {
+        return Err(anyhow::anyhow!(
+            "'{}' cannot be created in {} project(s); no tags were created",
+            name,
+            conflicts
+        ));
+    }
+
+    let mut created: Vec<&(String, PathBuf)> = Vec::new();
+    for target in targets {
+        let (project, path) = target;
+        match create_one(path, name, message) {
+            Ok(commit) => {
+                println!("  {} {} {}", "✓".green(), project, commit.bright_black());
+                created.push(target);
+            }
+            Err(e) => {
+                println!("  {} {}: {}", "✗".red(), project, e);
+                for (project, path) in created {
+                    match git(path, &["tag", "--delete", name]) {
+                        Ok(_) => println!("  {} {} rolled back", "↺".yellow(), project),
+                        Err(e) => println!("  {} {}: rollback failed: {}", "✗".red(), project, e),
+                    }
+                }
+                return Err(anyhow::anyhow!(
+                    "could not create '{}' in {}; no tags were kept",
+                    name,
+                    project
+                ));
+            }
+        }
+    }
+
+    if push {
+        let mut failed = 0;
+        for (project, path) in targets {
+            let reference = format!("refs/tags/{}", name);
+            match git(path, &["push", "--quiet", "origin", &reference]) {
+                Ok(_) => println!("  {} {} pushed", "↑".green(), project),
+                Err(e) => {
+                    failed += 1;
+                    println!("  {} {}: push failed: {}", "✗".red(), project, e);
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "'{}' was created everywhere but not pushed from {} project(s)",
+                name,
+                failed
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap_or_else(|e| panic!("git {:?} failed: {}", args, e));
+    }
+
+    fn repo(dir: &Path) {
+        std::fs::create_dir_all(dir).unwrap();
+        run(dir, &["init", "-q", "-b", "main"]);
+        run(dir, &["config", "user.email", "test@example.com"]);
+        run(dir, &["config", "user.name", "Test"]);
+        run(dir, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    }
+
+    #[test]
+    fn creates_everywhere_or_nowhere() {
+        let tmp = tempfile::tempdir().unwrap();
+        let targets: Vec<(String, PathBuf)> = ["api", "web"]
+            .iter()
+            .map(|name| (name.to_string(), tmp.path().join(name)))
+            .collect();
+        for (_, path) in &targets {
+            repo(path);
+        }
+
+        run(&targets[1].1, &["tag", "v1.0"]);
+        assert!(create_tags(&targets, "v1.0", None, false).is_err());
+        assert!(!has_tag(&targets[0].1, "v1.0").unwrap());
+        assert!(create_tags(&targets, "bad..name", None, false).is_err());
+
+        create_tags(&targets, "v2.0", Some("Release 2.0"), false).unwrap();
+        for (_, path) in &targets {
+            assert_eq!(git(path, &["cat-file", "-t", "v2.0"]).unwrap(), "tag");
+        }
+    }
+
+    #[test]
+    fn pushes_to_origin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin.git");
+        run(
+            tmp.path(),
+            &["init", "-q", "--bare", origin.to_str().unwrap()],
+        );
+        let api = tmp.path().join("api");
+        repo(&api);
+        run(&api, &["remote", "add", "origin", origin.to_str().unwrap()]);
+        let targets = vec![("api".to_string(), api.clone())];
+
+        create_tags(&targets, "v1.0", None, true).unwrap();
+        assert!(has_tag(&origin, "v1.0").unwrap());
+        // Origin's copy blocks the tag even once the local one is gone.
+        run(&api, &["tag", "--delete", "v1.0"]);
+        assert!(create_tags(&targets, "v1.0", None, true).is_err());
+        assert!(!has_tag(&api, "v1.0").unwrap());
+    }
+}
@@ -0,0 +1,105 @@
+//! `meta gitignore` — maintain the metarepo-managed block of the workspace
+//! `.gitignore` (see [`crate::plugins::shared::gitignore`]).
+
+use anyhow::Result;
+use colored::Colorize;
+use metarepo_core::MetaConfig;
+use std::path::Path;
+
+use crate::plugins::shared::gitignore;
+
+pub use self::plugin::GitignorePlugin;
+
+mod plugin;
+
+/// Rebuild the managed block from the workspace config so it lists exactly the
+/// remote-backed projects. Lines outside the block are left as-is. With
+/// `check`, nothing is written and an out-of-date block is reported as an
+/// error (non-zero exit), so it can gate CI.
+pub fn sync_gitignore(base_path: &Path, check: bool) -> Result<()> {
+    let meta_file_path = MetaConfig::locate_in(base_path)?.path;
+    let config = MetaConfig::load_from_file(&meta_file_path)?;
+
+    let expected = gitignore::expected_entries(&config);
+    let gitignore_path = base_path.join(".gitignore");
+    let content = if gitignore_path.exists() {
+        std::fs::read_to_string(&gitignore_path)?
+    } else {
+        String::new()
+    };
+    let current = gitignore::managed_entries(&content);
+
+    let added: Vec<&String> = expected.iter().filter(|e| !current.contains(e)).collect();
+    let removed: Vec<&String> = current.iter().filter(|e| !expected.contains(e)).collect();
+    let in_sync = gitignore::render_with_managed_entries(&content, &expected) == content;
+
+    for entry in &added {
+        println!("  {} {}", "+".green(), entry);
+    }
+    for entry in &removed {
+        println!("  {} {}", "-".red(), entry);
+    }
+
+    if in_sync {
+        println!(
+            "  {} .gitignore managed block is current ({} entries)",
+            "·".bright_black(),
+            expected.len()
+        );
+        return Ok(());
+    }
+
+    if check {
+        return Err(anyhow::anyhow!(
+            ".gitignore managed block is out of date; run 'meta gitignore sync'"
+        ));
+    }
+
+    gitignore::sync_managed_entries(base_path, &expected)?;
+    println!(
+        "  {} Synced .gitignore managed block ({} entries)",
+        "✓".green(),
+        expected.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn sync_rebuilds_block_and_keeps_user_lines() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::write(
+            root.join(".metarepo"),
+            r#"{"projects":{"web":"https://example.com/web.git","docs":"local:docs"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join(".gitignore"),
+            format!(
+                "*.log\n{}\nstale\n{}\nbuild/\n",
+                gitignore::MANAGED_BEGIN,
+                gitignore::MANAGED_END
+            ),
+        )
+        .unwrap();
+
+        assert!(sync_gitignore(root, true).is_err());
+        sync_gitignore(root, false).unwrap();
+
+        let content = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert_eq!(
+            content,
+            format!(
+                "*.log\n{}\nweb\n{}\nbuild/\n",
+                gitignore::MANAGED_BEGIN,
+                gitignore::MANAGED_END
+            )
+        );
+        assert!(sync_gitignore(root, true).is_ok());
+    }
+}
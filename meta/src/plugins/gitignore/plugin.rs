@@ -0,0 +1,103 @@
+use super::sync_gitignore;
+use anyhow::Result;
+use clap::ArgMatches;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+
+/// GitignorePlugin for maintaining the managed .gitignore block
+pub struct GitignorePlugin;
+
+impl GitignorePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("gitignore")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Maintain the metarepo-managed .gitignore block")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Maintain the section of the workspace .gitignore that metarepo owns.\n\
+                 \n\
+                 Project entries live between the markers\n\
+                 \n\
+                   # --- managed by metarepo ---\n\
+                   # --- end managed by metarepo ---\n\
+                 \n\
+                 `meta init` inserts the block and `meta project add/remove/rename` edit\n\
+                 only inside it, so hand-written ignores elsewhere in the file keep their\n\
+                 content and order.",
+            )
+            .command(
+                command("sync")
+                    .about("Rebuild the managed block from the workspace config")
+                    .help_description(
+                        "Rebuild the managed .gitignore block from the workspace config.\n\
+                         \n\
+                         The block is rewritten to list exactly the projects backed by a remote\n\
+                         (URL not local:), sorted. The block is created at the end of the file if\n\
+                         it is missing. Lines outside the block are never changed. Pass --check\n\
+                         to report drift without writing; it exits non-zero when the block is\n\
+                         out of date.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta gitignore sync           rewrite the block from .meta\n\
+                           meta gitignore sync --check    fail if the block is stale",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("check")
+                            .long("check")
+                            .help("Report drift without writing; non-zero exit if stale"),
+                    ),
+            )
+            .handler("sync", handle_sync)
+            .build()
+    }
+}
+
+/// Handler for the sync command
+fn handle_sync(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.meta_root().unwrap_or(config.working_dir.clone());
+    sync_gitignore(&base_path, matches.get_flag("check"))
+}
+
+impl MetaPlugin for GitignorePlugin {
+    fn name(&self) -> &str {
+        "gitignore"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for GitignorePlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Maintain the metarepo-managed .gitignore block")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for GitignorePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
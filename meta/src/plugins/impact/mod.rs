@@ -0,0 +1,322 @@
+//! `meta impact --base <ref>` — what a change to the meta repository means for
+//! the workspace, for reviewing PRs against it.
+//!
+//! The working tree's `.meta` is compared with the one at `<ref>` (see
+//! [`crate::plugins::config::diff`]) and the differences are restated as
+//! consequences: projects that need cloning, remotes and pins that move,
+//! scripts that run differently, and which projects a change to the
+//! workspace `.rules.yaml` applies to. Markdown output is meant for CI to post
+//! as a PR comment.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use metarepo_core::MetaConfig;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+use crate::plugins::config::diff::{diff_configs, load_baseline, ConfigDiff};
+
+pub use self::plugin::ImpactPlugin;
+
+mod plugin;
+
+/// The workspace-wide rules file.
+const RULES_FILE: &str = ".rules.yaml";
+
+/// A project whose pin was set, moved, or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PinChange {
+    pub project: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Everything `meta impact` reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImpactReport {
+    /// The ref compared against.
+    pub base: String,
+    /// Projects, URLs, and scripts that changed in `.meta`.
+    pub config: ConfigDiff,
+    pub pins: Vec<PinChange>,
+    /// Whether the workspace `.rules.yaml` changed.
+    pub rules_changed: bool,
+    /// Projects that use the workspace rules, when they changed.
+    pub rules_apply_to: Vec<String>,
+}
+
+impl ImpactReport {
+    pub fn is_empty(&self) -> bool {
+        self.config.is_empty() && self.pins.is_empty() && !self.rules_changed
+    }
+}
+
+fn pin_changes(old: &MetaConfig, new: &MetaConfig) -> Vec<PinChange> {
+    let names: BTreeSet<&String> = old
+        .projects
+        .keys()
+        .filter(|n| new.projects.contains_key(*n))
+        .collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let from = old.get_project_pin(name);
+            let to = new.get_project_pin(name);
+            (from != to).then(|| PinChange {
+                project: name.clone(),
+                from: from.map(str::to_string),
+                to: to.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// `file` in `dir` as of git revision `base`, or `None` if it did not exist.
+fn file_at(dir: &Path, base: &str, file: &str) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("{}:./{}", base, file))
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git")?;
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
+/// Compare the workspace at `meta_root` (config file `config_path`) with
+/// revision `base`.
+pub fn analyze(meta_root: &Path, config_path: &Path, base: &str) -> Result<ImpactReport> {
+    let (old, _) = load_baseline(config_path, base)?;
+    let new = MetaConfig::load_from_file(config_path)?;
+
+    let old_rules = file_at(meta_root, base, RULES_FILE)?;
+    let new_rules = std::fs::read_to_string(meta_root.join(RULES_FILE)).ok();
+    let rules_changed = old_rules != new_rules;
+    // A project's own .rules.yaml takes priority over the workspace one.
+    let mut rules_apply_to: Vec<String> = if rules_changed {
+        new.projects
+            .keys()
+            .filter(|p| !meta_root.join(p).join(RULES_FILE).exists())
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    rules_apply_to.sort();
+
+    Ok(ImpactReport {
+        base: base.to_string(),
+        config: diff_configs(&old, &new),
+        pins: pin_changes(&old, &new),
+        rules_changed,
+        rules_apply_to,
+    })
+}
+
+fn or_none(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("(none)")
+}
+
+/// The report as titled sections of lines, with `code` wrapping identifiers
+/// and values.
+fn sections(report: &ImpactReport, code: impl Fn(&str) -> String) -> Vec<(String, Vec<String>)> {
+    let diff = &report.config;
+    let mut sections = Vec::new();
+    let mut push = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            sections.push((title.to_string(), lines));
+        }
+    };
+
+    push(
+        "New clones needed (meta git update)",
+        diff.added
+            .iter()
+            .map(|p| format!("{} from {}", code(&p.project), code(&p.url)))
+            .collect(),
+    );
+    push(
+        "Removed from the workspace (existing clones are left in place)",
+        diff.removed
+            .iter()
+            .map(|p| format!("{} ({})", code(&p.project), code(&p.url)))
+            .collect(),
+    );
+    push(
+        "Remote URL changes (existing clones keep their old origin)",
+        diff.url_changes
+            .iter()
+            .map(|c| format!("{}: {} → {}", code(&c.project), code(&c.from), code(&c.to)))
+            .collect(),
+    );
+    push(
+        "Pin changes",
+        report
+            .pins
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}: {} → {}",
+                    code(&p.project),
+                    code(or_none(&p.from)),
+                    code(or_none(&p.to))
+                )
+            })
+            .collect(),
+    );
+    push(
+        "Script changes (meta run)",
+        diff.script_changes
+            .iter()
+            .map(|c| {
+                let name = match &c.project {
+                    Some(project) => format!("{} in {}", code(&c.script), code(project)),
+                    None => format!("{} (workspace)", code(&c.script)),
+                };
+                match (&c.from, &c.to) {
+                    (None, Some(to)) => format!("{} added: {}", name, code(to)),
+                    (Some(_), None) => format!("{} removed", name),
+                    (from, to) => {
+                        format!("{}: {} → {}", name, code(or_none(from)), code(or_none(to)))
+                    }
+                }
+            })
+            .collect(),
+    );
+    if report.rules_changed {
+        push(
+            "Workspace .rules.yaml changed",
+            vec![if report.rules_apply_to.is_empty() {
+                "No project uses the workspace rules".to_string()
+            } else {
+                format!(
+                    "Applies to: {}",
+                    report
+                        .rules_apply_to
+                        .iter()
+                        .map(|p| code(p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }],
+        );
+    }
+    sections
+}
+
+/// The report as Markdown, for a PR comment.
+pub fn render_markdown(report: &ImpactReport) -> String {
+    let mut out = format!("### Workspace impact (vs `{}`)\n\n", report.base);
+    if report.is_empty() {
+        out.push_str("_No changes to projects, pins, scripts, or rules._\n");
+        return out;
+    }
+    for (title, lines) in sections(report, |s| format!("`{}`", s)) {
+        out.push_str(&format!("**{}**\n\n", title));
+        for line in lines {
+            out.push_str(&format!("- {}\n", line));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Print the report for a terminal.
+pub fn print_report(report: &ImpactReport) {
+    println!(
+        "\n  {} {}",
+        "🔎".bright_blue(),
+        format!("Workspace impact vs {}", report.base).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    if report.is_empty() {
+        println!(
+            "  {} {}",
+            "✓".green(),
+            "No changes to projects, pins, scripts, or rules".green()
+        );
+        println!();
+        return;
+    }
+    for (title, lines) in sections(report, |s| s.bright_white().to_string()) {
+        println!("\n  {}", title.bold());
+        for line in lines {
+            println!("    {} {}", "•".bright_black(), line);
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn reports_clones_pins_and_rules_against_a_base() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        run(root, &["init", "-q"]);
+        let meta = root.join(".meta");
+        std::fs::write(
+            &meta,
+            r#"{"projects": {
+                "web": "git@h:web.git",
+                "lib": {"url": "git@h:lib.git", "pinned": "v1"}
+            }}"#,
+        )
+        .unwrap();
+        run(root, &["add", "."]);
+        run(root, &["commit", "-qm", "init"]);
+
+        let report = analyze(root, &meta, "HEAD").unwrap();
+        assert!(report.is_empty());
+        assert!(render_markdown(&report).contains("_No changes"));
+
+        std::fs::write(
+            &meta,
+            r#"{"projects": {
+                "web": "git@h:web.git",
+                "api": "git@h:api.git",
+                "lib": {"url": "git@h:lib.git", "pinned": "v2"}
+            }}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join(RULES_FILE), "directories: []\n").unwrap();
+        // web keeps its own rules.
+        std::fs::create_dir_all(root.join("web")).unwrap();
+        std::fs::write(root.join("web").join(RULES_FILE), "").unwrap();
+
+        let report = analyze(root, &meta, "HEAD").unwrap();
+        assert_eq!(report.config.added[0].project, "api");
+        assert_eq!(
+            report.pins,
+            [PinChange {
+                project: "lib".into(),
+                from: Some("v1".into()),
+                to: Some("v2".into())
+            }]
+        );
+        assert!(report.rules_changed);
+        assert_eq!(report.rules_apply_to, ["api", "lib"]);
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.starts_with("### Workspace impact (vs `HEAD`)"));
+        assert!(markdown.contains("- `api` from `git@h:api.git`"));
+        assert!(markdown.contains("- `lib`: `v1` → `v2`"));
+        assert!(markdown.contains("Applies to: `api`, `lib`"));
+    }
+}
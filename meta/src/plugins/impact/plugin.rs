@@ -0,0 +1,99 @@
+//! Plugin wiring for `meta impact`.
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{analyze, print_report, render_markdown};
+
+/// Registers the top-level `meta impact` command.
+pub struct ImpactPlugin;
+
+impl ImpactPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ImpactPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for ImpactPlugin {
+    fn name(&self) -> &str {
+        "impact"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("impact")
+                .about("Report how changes to the meta repository affect the workspace")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Summarize what a change to the meta repository means for the workspace.\n\
+                     \n\
+                     Compares the working tree with --base (a git ref, default HEAD) and reports\n\
+                     projects that need cloning or were removed, remote URL and pin changes,\n\
+                     scripts added, removed, or changed, and, when the workspace .rules.yaml\n\
+                     changed, the projects it applies to (those without their own rules).\n\
+                     \n\
+                     --format markdown prints a section for a PR comment; --format json is\n\
+                     for other tooling.\n\
+                     \n\
+                     Examples:\n  \
+                       meta impact --base origin/main\n  \
+                       meta impact --base origin/main --format markdown > impact.md\n",
+                ))
+                .arg(
+                    Arg::new("base")
+                        .long("base")
+                        .value_name("REF")
+                        .default_value("HEAD")
+                        .help("Git ref to compare against"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .default_value("text")
+                        .value_parser(["text", "markdown", "json"])
+                        .help("Output format"),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let meta_root = config.require_meta_root()?;
+        let config_path = config
+            .meta_file_path
+            .clone()
+            .expect("a workspace root comes from its config file");
+        let base = matches
+            .get_one::<String>("base")
+            .expect("base has a default");
+        let report = analyze(&meta_root, &config_path, base)?;
+
+        match matches.get_one::<String>("format").map(String::as_str) {
+            Some("markdown") => print!("{}", render_markdown(&report)),
+            Some("json") => println!("{}", serde_json::to_string_pretty(&report)?),
+            _ => print_report(&report),
+        }
+        Ok(())
+    }
+}
+
+impl BasePlugin for ImpactPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Report how changes to the meta repository affect the workspace")
+    }
+}
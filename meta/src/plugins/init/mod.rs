@@ -10,6 +10,7 @@ pub use self::plugin::InitPlugin;
 
 mod plugin;
 
+use crate::plugins::shared::gitignore;
 use crate::plugins::skill;
 
 /// User-selected options for `meta init`.
@@ -77,6 +78,7 @@ pub struct InitReport {
 
 fn create_default_config() -> MetaConfig {
     MetaConfig {
+        extends: Vec::new(),
         ignore: vec![
             ".git".to_string(),
             ".vscode".to_string(),
@@ -86,19 +88,47 @@ fn create_default_config() -> MetaConfig {
         ],
         projects: HashMap::new(),
         disabled: Vec::new(),
+        default_excludes: Vec::new(),
+        profiles: None,
+        groups: None,
         plugins: None,
+        plugin_priority: None,
+        disabled_plugins: Vec::new(),
+        plugin_overrides: None,
         modules: None,
         nested: None,
         aliases: None,
         scripts: None,
         worktree_init: None,
+        branch_policy: None,
+        templates: None,
+        issue_trackers: Vec::new(),
+        default_script: None,
         default_bare: None,
         plugins_integrity: None,
         allow_version_mismatch: None,
         plugin_allow_any_path: None,
         skill: None,
         mcp: None,
+        clone_settings: None,
+        exec_settings: None,
+        exec_templates: None,
+        run_settings: None,
+        onboard: None,
+        services: None,
+        min_success: None,
+        protected: Vec::new(),
+        notify: None,
+        badges: None,
+        todos: None,
+        sync_files: None,
         help_descriptions: None,
+        parallelism: None,
+        color: None,
+        hints: None,
+        plugin_dirs: Vec::new(),
+        credentials: None,
+        hosts: None,
     }
 }
 
@@ -219,7 +249,9 @@ fn print_report(report: &InitReport) {
     }
 }
 
-/// Append meta-specific ignore patterns if missing. Returns true if any change
+/// Append meta-specific ignore patterns if missing and insert the managed
+/// project block (see [`gitignore`]) when the file has none yet, seeded from
+/// the remote-backed projects already in the config. Returns true if any change
 /// was written so the caller can report it.
 fn update_gitignore<P: AsRef<Path>>(path: P) -> Result<bool> {
     let gitignore_path: PathBuf = path.as_ref().join(".gitignore");
@@ -236,6 +268,7 @@ fn update_gitignore<P: AsRef<Path>>(path: P) -> Result<bool> {
         "*.log",
         "node_modules/",
         "target/",
+        ".meta-state/",
     ];
 
     let mut updated = false;
@@ -250,6 +283,21 @@ fn update_gitignore<P: AsRef<Path>>(path: P) -> Result<bool> {
         }
     }
 
+    if !gitignore::has_managed_block(&existing_content) {
+        // Entries a pre-block workspace already lists by hand stay where they
+        // are; only the missing ones are seeded into the new block.
+        let entries: Vec<String> = MetaConfig::locate_in(path.as_ref())
+            .ok()
+            .and_then(|found| MetaConfig::load_from_file(&found.path).ok())
+            .map(|config| gitignore::expected_entries(&config))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| !existing_content.lines().any(|l| l.trim() == entry))
+            .collect();
+        existing_content = gitignore::render_with_managed_entries(&existing_content, &entries);
+        updated = true;
+    }
+
     if updated {
         fs::write(&gitignore_path, existing_content)?;
     }
@@ -433,9 +481,32 @@ mod tests {
         assert!(content.contains("*.tmp"));
         assert!(content.contains(".DS_Store"));
         assert!(content.contains("node_modules/"));
+        assert!(content.contains(gitignore::MANAGED_BEGIN));
 
         // Second call is a no-op.
         let changed_again = update_gitignore(path).unwrap();
         assert!(!changed_again);
     }
+
+    #[test]
+    fn update_gitignore_seeds_managed_block_from_config() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path();
+        fs::write(
+            path.join(".metarepo"),
+            r#"{"projects":{"web":"https://example.com/web.git","docs":"local:docs","api":"https://example.com/api.git"}}"#,
+        )
+        .unwrap();
+        // A hand-written entry from before the block existed stays outside it.
+        fs::write(path.join(".gitignore"), "api\n").unwrap();
+
+        assert!(update_gitignore(path).unwrap());
+
+        let content = fs::read_to_string(path.join(".gitignore")).unwrap();
+        assert!(content.starts_with("api\n"));
+        assert_eq!(
+            gitignore::managed_entries(&content),
+            vec!["web".to_string()]
+        );
+    }
 }
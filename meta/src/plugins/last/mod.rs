@@ -0,0 +1,111 @@
+//! `meta last` — show the summary of the most recent multi-project run
+//! (`meta exec`, `meta run`, `meta start`, `meta dev`), read from the
+//! workspace state directory.
+
+use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+
+use crate::plugins::shared::outcome::{format_duration, results_table, LAST_RUN_FILE};
+use crate::plugins::shared::{state, LastRun};
+
+pub use self::plugin::LastPlugin;
+
+mod plugin;
+
+/// How `meta last` prints the summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastFormat {
+    /// The human-readable report.
+    Report,
+    /// The saved summary as JSON.
+    Json,
+    /// Only the failed project names, one per line.
+    Failed,
+}
+
+/// Print the last run's summary for the workspace at `base_path`.
+pub fn show_last_run(base_path: &Path, format: LastFormat) -> Result<()> {
+    if !state::state_path(base_path, LAST_RUN_FILE).exists() {
+        return Err(anyhow::anyhow!(
+            "No run recorded yet. `meta exec` and `meta run` save a summary when they finish."
+        ));
+    }
+    let last: LastRun = state::load(base_path, LAST_RUN_FILE)?;
+    match format {
+        LastFormat::Json => println!("{}", serde_json::to_string_pretty(&last)?),
+        LastFormat::Failed => {
+            for project in last.failed_projects() {
+                println!("{}", project);
+            }
+        }
+        LastFormat::Report => print_last_run(&last),
+    }
+    Ok(())
+}
+
+fn print_last_run(last: &LastRun) {
+    println!(
+        "\n  {} {} {}",
+        "📋".cyan(),
+        "Last run:".bold(),
+        format!("meta {}", last.args.join(" ")).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    let exit = match last.exit_code {
+        0 => "exit 0".green(),
+        code => format!("exit {}", code).red(),
+    };
+    println!(
+        "  {} {} UTC · {} · {}",
+        "Started".bright_black(),
+        state::format_timestamp(last.started),
+        format_duration(last.duration_ms),
+        exit
+    );
+    println!();
+
+    results_table(&last.projects).indent(2).print();
+
+    let failed = last.failed_projects().len();
+    println!("  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} succeeded, {} failed",
+        "Summary:".bright_black(),
+        (last.projects.len() - failed).to_string().green(),
+        if failed > 0 {
+            failed.to_string().red()
+        } else {
+            "0".bright_black()
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::shared::outcome::ProjectResult;
+
+    #[test]
+    fn reads_the_saved_summary() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(show_last_run(tmp.path(), LastFormat::Report).is_err());
+
+        let last = LastRun {
+            command: "run".to_string(),
+            args: vec!["run".to_string(), "test".to_string()],
+            exit_code: 1,
+            projects: vec![ProjectResult {
+                project: "api".to_string(),
+                success: false,
+                exit_code: Some(1),
+                duration_ms: 40,
+                start_ms: None,
+            }],
+            ..Default::default()
+        };
+        state::save(tmp.path(), LAST_RUN_FILE, &last).unwrap();
+        assert!(show_last_run(tmp.path(), LastFormat::Failed).is_ok());
+        assert!(show_last_run(tmp.path(), LastFormat::Json).is_ok());
+    }
+}
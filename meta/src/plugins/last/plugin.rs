@@ -0,0 +1,90 @@
+//! Plugin wiring for `meta last`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{show_last_run, LastFormat};
+
+/// Registers the top-level `meta last` command.
+pub struct LastPlugin;
+
+impl LastPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LastPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for LastPlugin {
+    fn name(&self) -> &str {
+        "last"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("last")
+                .about("Show the summary of the last multi-project run")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Show how the most recent `meta exec`, `meta run`, `meta start`, or\n\
+                     `meta dev` went: the command line, when it started, how long it took,\n\
+                     the exit code, and each project's status, exit code, and duration.\n\
+                     \n\
+                     The summary is saved as last-run.json in the workspace state directory\n\
+                     (.meta-state) whenever one of those commands finishes. Pass --json for\n\
+                     the saved summary as-is, or --failed to print only the projects that\n\
+                     failed, one per line, for feeding into a follow-up command.\n\
+                     \n\
+                     Examples:\n  \
+                       meta last\n  \
+                       meta last --json\n  \
+                       meta exec --projects \"$(meta last --failed | paste -sd,)\" npm test\n",
+                ))
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the saved summary as JSON")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("failed"),
+                )
+                .arg(
+                    Arg::new("failed")
+                        .long("failed")
+                        .help("Print only the failed projects, one per line")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let meta_root = config.require_meta_root()?;
+        let format = if matches.get_flag("json") {
+            LastFormat::Json
+        } else if matches.get_flag("failed") {
+            LastFormat::Failed
+        } else {
+            LastFormat::Report
+        };
+        show_last_run(&meta_root, format)
+    }
+}
+
+impl BasePlugin for LastPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Show the summary of the last multi-project run")
+    }
+}
@@ -0,0 +1,479 @@
+//! `meta lock` and `meta verify` — a lockfile recording the exact commit each
+//! project is at, so a workspace can be reproduced: `meta lock` writes
+//! [`LOCK_FILE`] next to `.meta`, `meta git update --frozen` checks every
+//! project out at its locked commit, and `meta verify` reports projects that
+//! have drifted from it.
+
+use crate::plugins::shared::pin;
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::MetaConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub use self::plugin::{LockPlugin, VerifyPlugin};
+
+mod plugin;
+
+/// The lockfile, at the workspace root. Unlike workspace state it is meant to
+/// be committed.
+pub const LOCK_FILE: &str = "meta.lock";
+
+/// Lockfile format version written by this build.
+const LOCK_VERSION: u32 = 1;
+
+/// One project's locked state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedProject {
+    /// The remote the commit came from, as configured when locking.
+    pub url: String,
+    /// The full commit SHA.
+    pub commit: String,
+}
+
+/// The contents of [`LOCK_FILE`]. Projects are kept sorted so the file diffs
+/// cleanly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default)]
+    pub projects: BTreeMap<String, LockedProject>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCK_VERSION,
+            projects: BTreeMap::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Load the lockfile of the workspace at `base_path`, if it has one.
+    pub fn load(base_path: &Path) -> Result<Option<Self>> {
+        let path = base_path.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let lock: Lockfile = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        if lock.version > LOCK_VERSION {
+            return Err(anyhow::anyhow!(
+                "{} is version {}; this meta only understands version {}. Upgrade meta.",
+                LOCK_FILE,
+                lock.version,
+                LOCK_VERSION
+            ));
+        }
+        Ok(Some(lock))
+    }
+
+    /// The lockfile, or an error telling the user to create one.
+    pub fn require(base_path: &Path) -> Result<Self> {
+        Self::load(base_path)?.ok_or_else(|| {
+            anyhow::anyhow!("No {} found. Run 'meta lock' to create one.", LOCK_FILE)
+        })
+    }
+
+    pub fn save(&self, base_path: &Path) -> Result<()> {
+        let path = base_path.join(LOCK_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Whether `project` has a non-bare git checkout under `base_path`.
+fn is_checked_out(base_path: &Path, config: &MetaConfig, project: &str) -> bool {
+    base_path.join(project).join(".git").exists() && !config.is_bare_repo(project)
+}
+
+/// What happened to one project when locking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockResult {
+    /// Locked at this commit, which differs from the previous lock (if any).
+    Locked(String),
+    /// Already locked at its current commit.
+    Unchanged,
+    /// Not locked, for the given reason.
+    Skipped(String),
+}
+
+/// Record the HEAD commit of each of `projects` in the lockfile, keeping the
+/// entries of projects not named. Entries for projects no longer in `.meta`,
+/// or now vendored, are dropped.
+pub fn lock_projects(
+    base_path: &Path,
+    config: &MetaConfig,
+    projects: &[String],
+) -> Result<Vec<(String, LockResult)>> {
+    let mut lock = Lockfile::load(base_path)?.unwrap_or_default();
+    lock.version = LOCK_VERSION;
+    lock.projects
+        .retain(|name, _| config.projects.contains_key(name) && !config.is_vendored(name));
+
+    let mut results = Vec::new();
+    let mut projects = projects.to_vec();
+    projects.sort();
+    for project in projects {
+        let result = if config.is_bare_repo(&project) {
+            LockResult::Skipped("bare repository".to_string())
+        } else if config.is_vendored(&project) {
+            // Its files are committed in the workspace repository itself.
+            LockResult::Skipped("vendored".to_string())
+        } else if !is_checked_out(base_path, config, &project) {
+            LockResult::Skipped("not cloned".to_string())
+        } else {
+            let commit = pin::resolve(&base_path.join(&project), "HEAD")?;
+            let locked = LockedProject {
+                url: config.get_project_url(&project).unwrap_or_default(),
+                commit: commit.clone(),
+            };
+            if lock.projects.get(&project) == Some(&locked) {
+                LockResult::Unchanged
+            } else {
+                lock.projects.insert(project.clone(), locked);
+                LockResult::Locked(commit)
+            }
+        };
+        results.push((project, result));
+    }
+    lock.save(base_path)?;
+    Ok(results)
+}
+
+/// How a project differs from the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Drift {
+    /// Checked out at a different commit than the locked one.
+    Moved { locked: String, actual: String },
+    /// Its URL in `.meta` no longer matches the locked one.
+    UrlChanged { locked: String, actual: String },
+    /// Locked but not cloned.
+    NotCloned,
+    /// In `.meta` but missing from the lockfile.
+    NotLocked,
+    /// In the lockfile but no longer in `.meta`.
+    Removed,
+}
+
+impl Drift {
+    pub fn describe(&self) -> String {
+        match self {
+            Drift::Moved { locked, actual } => format!(
+                "at {} but locked at {}",
+                short_sha(actual),
+                short_sha(locked)
+            ),
+            Drift::UrlChanged { locked, actual } => {
+                format!("URL is {} but was locked from {}", actual, locked)
+            }
+            Drift::NotCloned => "locked but not cloned".to_string(),
+            Drift::NotLocked => "not in the lockfile".to_string(),
+            Drift::Removed => "in the lockfile but no longer in .meta".to_string(),
+        }
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(10)]
+}
+
+/// Every way the workspace at `base_path` differs from `lock`, by project.
+/// Bare repositories and vendored projects are not locked and never drift.
+pub fn find_drift(base_path: &Path, config: &MetaConfig, lock: &Lockfile) -> Vec<(String, Drift)> {
+    let mut drift = Vec::new();
+    let mut names: Vec<&String> = config.projects.keys().collect();
+    names.sort();
+    for name in names {
+        if config.is_bare_repo(name) || config.is_vendored(name) {
+            continue;
+        }
+        let Some(locked) = lock.projects.get(name) else {
+            drift.push((name.clone(), Drift::NotLocked));
+            continue;
+        };
+        let url = config.get_project_url(name).unwrap_or_default();
+        if url != locked.url {
+            drift.push((
+                name.clone(),
+                Drift::UrlChanged {
+                    locked: locked.url.clone(),
+                    actual: url,
+                },
+            ));
+        }
+        if !is_checked_out(base_path, config, name) {
+            drift.push((name.clone(), Drift::NotCloned));
+            continue;
+        }
+        match pin::resolve(&base_path.join(name), "HEAD") {
+            Ok(actual) if actual == locked.commit => {}
+            Ok(actual) => drift.push((
+                name.clone(),
+                Drift::Moved {
+                    locked: locked.commit.clone(),
+                    actual,
+                },
+            )),
+            // A repository without commits cannot be at the locked one.
+            Err(_) => drift.push((
+                name.clone(),
+                Drift::Moved {
+                    locked: locked.commit.clone(),
+                    actual: String::new(),
+                },
+            )),
+        }
+    }
+    for name in lock.projects.keys() {
+        if !config.projects.contains_key(name) {
+            drift.push((name.clone(), Drift::Removed));
+        }
+    }
+    drift
+}
+
+/// Check every cloned, locked project out at its locked commit (detached),
+/// fetching first when the commit is not present locally. Returns how many
+/// projects could not be checked out; each failure is reported as it happens.
+pub fn check_out_locked(base_path: &Path, config: &MetaConfig, lock: &Lockfile) -> usize {
+    let mut failed = 0;
+    for (name, locked) in &lock.projects {
+        if !config.projects.contains_key(name) || !is_checked_out(base_path, config, name) {
+            continue;
+        }
+        let repo = base_path.join(name);
+        if pin::resolve(&repo, "HEAD").ok().as_deref() == Some(locked.commit.as_str()) {
+            continue;
+        }
+        if pin::resolve(&repo, &locked.commit).is_err() {
+            let _ = pin::fetch(&repo);
+        }
+        let result =
+            pin::resolve(&repo, &locked.commit).and_then(|_| pin::check_out(&repo, &locked.commit));
+        match result {
+            Ok(()) => println!(
+                "  {} {} {}",
+                "✓".green(),
+                name.bright_white(),
+                format!("checked out {}", short_sha(&locked.commit)).bright_black()
+            ),
+            Err(e) => {
+                eprintln!("  {} {}: {}", "✗".red(), name, e);
+                failed += 1;
+            }
+        }
+    }
+    failed
+}
+
+/// Print the drift report for `meta verify`.
+pub fn print_drift(drift: &[(String, Drift)]) {
+    println!("\n  {} {}", "🔒".cyan(), "Lockfile verification".bold());
+    println!("  {}", "═".repeat(60).bright_black());
+    if drift.is_empty() {
+        println!(
+            "  {} {}",
+            "✓".green(),
+            format!("Every project matches {}", LOCK_FILE).green()
+        );
+        println!();
+        return;
+    }
+    for (name, d) in drift {
+        let mark = match d {
+            Drift::NotLocked => "⚠️".yellow(),
+            _ => "✗".red(),
+        };
+        println!(
+            "  {} {} {}",
+            mark,
+            name.bright_white(),
+            d.describe().bright_black()
+        );
+    }
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} project(s) differ from {}. Run 'meta git update --frozen' to restore \
+         them, or 'meta lock' to accept the current state.",
+        "Summary:".bright_black(),
+        drift.len().to_string().red(),
+        LOCK_FILE
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ProjectEntry;
+    use std::process::Command;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    fn commit(dir: &Path, message: &str) {
+        run(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+
+    fn head(dir: &Path) -> String {
+        pin::resolve(dir, "HEAD").unwrap()
+    }
+
+    /// A workspace with one cloned project, `lib`, and one that is not, `app`.
+    fn workspace(root: &Path) -> MetaConfig {
+        let upstream = root.join("upstream");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        commit(&upstream, "one");
+        let ws = root.join("ws");
+        std::fs::create_dir(&ws).unwrap();
+        run(&ws, &["clone", "-q", upstream.to_str().unwrap(), "lib"]);
+
+        let mut config = MetaConfig::default();
+        let url = format!("file://{}", upstream.display());
+        config
+            .projects
+            .insert("lib".to_string(), ProjectEntry::Url(url.clone()));
+        config
+            .projects
+            .insert("app".to_string(), ProjectEntry::Url(url));
+        config
+    }
+
+    #[test]
+    fn locks_heads_and_reports_drift() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = workspace(tmp.path());
+        let ws = tmp.path().join("ws");
+        let lib = ws.join("lib");
+        let first = head(&lib);
+
+        let names = ["app", "lib"].map(String::from);
+        let results = lock_projects(&ws, &config, &names).unwrap();
+        assert_eq!(
+            results,
+            [
+                (
+                    "app".to_string(),
+                    LockResult::Skipped("not cloned".to_string())
+                ),
+                ("lib".to_string(), LockResult::Locked(first.clone())),
+            ]
+        );
+        let lock = Lockfile::require(&ws).unwrap();
+        assert_eq!(lock.projects["lib"].commit, first);
+        assert!(!lock.projects.contains_key("app"));
+        assert_eq!(
+            lock_projects(&ws, &config, &names[1..]).unwrap()[0].1,
+            LockResult::Unchanged
+        );
+
+        commit(&lib, "two");
+        let drift = find_drift(&ws, &config, &lock);
+        assert_eq!(
+            drift,
+            [
+                ("app".to_string(), Drift::NotLocked),
+                (
+                    "lib".to_string(),
+                    Drift::Moved {
+                        locked: first.clone(),
+                        actual: head(&lib),
+                    }
+                ),
+            ]
+        );
+
+        let mut removed = config.clone();
+        removed.projects.remove("lib");
+        assert_eq!(
+            find_drift(&ws, &removed, &lock),
+            [
+                ("app".to_string(), Drift::NotLocked),
+                ("lib".to_string(), Drift::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn vendored_projects_are_not_locked_and_never_drift() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = workspace(tmp.path());
+        let ws = tmp.path().join("ws");
+        let names = ["app", "lib"].map(String::from);
+        lock_projects(&ws, &config, &names[1..]).unwrap();
+
+        // `lib` is vendored after it was locked: its .git is gone.
+        std::fs::remove_dir_all(ws.join("lib/.git")).unwrap();
+        config.project_metadata_mut("lib").unwrap().vendored = Some("abc".to_string());
+        let results = lock_projects(&ws, &config, &names).unwrap();
+        assert_eq!(
+            results[1],
+            (
+                "lib".to_string(),
+                LockResult::Skipped("vendored".to_string())
+            )
+        );
+        let lock = Lockfile::require(&ws).unwrap();
+        assert!(lock.projects.is_empty());
+        assert_eq!(
+            find_drift(&ws, &config, &lock),
+            [("app".to_string(), Drift::NotLocked)]
+        );
+    }
+
+    #[test]
+    fn frozen_checkout_restores_locked_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = workspace(tmp.path());
+        let ws = tmp.path().join("ws");
+        let lib = ws.join("lib");
+        let upstream = tmp.path().join("upstream");
+
+        // Lock a commit the clone has not fetched yet.
+        commit(&upstream, "two");
+        let mut lock = Lockfile::default();
+        lock.projects.insert(
+            "lib".to_string(),
+            LockedProject {
+                url: config.get_project_url("lib").unwrap(),
+                commit: head(&upstream),
+            },
+        );
+        assert_ne!(head(&lib), head(&upstream));
+        assert_eq!(check_out_locked(&ws, &config, &lock), 0);
+        assert_eq!(head(&lib), head(&upstream));
+        assert!(find_drift(&ws, &config, &lock)
+            .iter()
+            .all(|(name, _)| name == "app"));
+
+        lock.projects.get_mut("lib").unwrap().commit = "0".repeat(40);
+        assert_eq!(check_out_locked(&ws, &config, &lock), 1);
+    }
+
+    #[test]
+    fn newer_lockfile_versions_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(Lockfile::load(tmp.path()).unwrap().is_none());
+        assert!(Lockfile::require(tmp.path()).is_err());
+        std::fs::write(
+            tmp.path().join(LOCK_FILE),
+            r#"{"version": 99, "projects": {}}"#,
+        )
+        .unwrap();
+        assert!(Lockfile::load(tmp.path()).is_err());
+    }
+}
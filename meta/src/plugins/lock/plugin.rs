@@ -0,0 +1,223 @@
+//! Plugin wiring for `meta lock` and `meta verify`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::*;
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{find_drift, lock_projects, print_drift, LockResult, Lockfile, LOCK_FILE};
+use crate::plugins::shared::selection;
+
+/// Registers the top-level `meta lock` command.
+pub struct LockPlugin;
+
+impl LockPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LockPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for LockPlugin {
+    fn name(&self) -> &str {
+        "lock"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("lock")
+                .about("Record the exact commit of every project in meta.lock")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Record the commit each project is checked out at in meta.lock, next to\n\
+                     .meta. Commit the lockfile; `meta git update --frozen` then checks\n\
+                     every project out at exactly those commits, so CI and new checkouts\n\
+                     get the same workspace, and `meta verify` reports projects that have\n\
+                     drifted from it.\n\
+                     \n\
+                     Name projects (or globs) to re-lock only those; the other entries are\n\
+                     kept. Projects that are not cloned and bare repositories are skipped.\n\
+                     \n\
+                     Examples:\n  \
+                       meta lock\n  \
+                       meta lock api 'services/*'\n",
+                ))
+                .arg(
+                    Arg::new("targets")
+                        .help("Projects to lock (names, aliases, or globs)")
+                        .num_args(1..),
+                )
+                .arg(
+                    Arg::new("strict")
+                        .long("strict")
+                        .help("Fail if any project selector matches nothing")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let base_path = config.require_meta_root()?;
+        let projects = match selection::explicit_projects(matches, config)? {
+            Some(selected) => selected,
+            None => config.meta_config.projects.keys().cloned().collect(),
+        };
+        let results = lock_projects(&base_path, &config.meta_config, &projects)?;
+
+        println!(
+            "\n  {} {}",
+            "🔒".cyan(),
+            format!("Locking {}", LOCK_FILE).bold()
+        );
+        println!("  {}", "═".repeat(60).bright_black());
+        let mut changed = 0;
+        for (project, result) in &results {
+            match result {
+                LockResult::Locked(commit) => {
+                    changed += 1;
+                    println!(
+                        "  {} {} {}",
+                        "✓".green(),
+                        project.bright_white(),
+                        commit[..commit.len().min(10)].bright_black()
+                    );
+                }
+                LockResult::Unchanged => {
+                    println!(
+                        "  {} {} {}",
+                        "✓".green(),
+                        project.bright_white(),
+                        "(unchanged)".bright_black()
+                    );
+                }
+                LockResult::Skipped(reason) => println!(
+                    "  {} {} {}",
+                    "⏭".yellow(),
+                    project.bright_white(),
+                    format!("({})", reason).yellow()
+                ),
+            }
+        }
+        println!("\n  {}", "─".repeat(60).bright_black());
+        println!(
+            "  {} {} project(s) locked, {} updated",
+            "Summary:".bright_black(),
+            results
+                .iter()
+                .filter(|(_, r)| !matches!(r, LockResult::Skipped(_)))
+                .count(),
+            changed
+        );
+        println!();
+        Ok(())
+    }
+}
+
+impl BasePlugin for LockPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Record the exact commit of every project in meta.lock")
+    }
+}
+
+/// Registers the top-level `meta verify` command.
+pub struct VerifyPlugin;
+
+impl VerifyPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for VerifyPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for VerifyPlugin {
+    fn name(&self) -> &str {
+        "verify"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("verify")
+                .about("Report projects that have drifted from meta.lock")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Compare the workspace with meta.lock and report every difference:\n\
+                     projects checked out at another commit, locked projects that are not\n\
+                     cloned, projects whose URL changed since locking, projects missing\n\
+                     from the lockfile, and locked projects no longer in .meta.\n\
+                     \n\
+                     Exits non-zero when anything differs, for CI. Fix drift with\n\
+                     `meta git update --frozen`, or accept it with `meta lock`.\n\
+                     \n\
+                     Examples:\n  \
+                       meta verify\n  \
+                       meta verify --json\n",
+                ))
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the differences as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let base_path = config.require_meta_root()?;
+        let lock = Lockfile::require(&base_path)?;
+        let drift = find_drift(&base_path, &config.meta_config, &lock);
+        if matches.get_flag("json") {
+            let entries: Vec<serde_json::Value> = drift
+                .iter()
+                .map(|(project, d)| {
+                    let mut entry = serde_json::to_value(d).unwrap_or_default();
+                    entry["project"] = serde_json::Value::String(project.clone());
+                    entry
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        } else {
+            print_drift(&drift);
+        }
+        if drift.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "{} project(s) differ from {}",
+                drift.len(),
+                LOCK_FILE
+            ))
+        }
+    }
+}
+
+impl BasePlugin for VerifyPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Report projects that have drifted from meta.lock")
+    }
+}
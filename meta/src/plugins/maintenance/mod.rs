@@ -0,0 +1,401 @@
+//! `meta maintenance` — keep the object stores of every project clone healthy.
+//!
+//! `enable` registers each clone with `git maintenance` (git 2.29+) and starts
+//! the background scheduler, so git itself repacks, prefetches, and refreshes
+//! the commit-graph on its own timetable. `run` does the work now, across
+//! projects in parallel. On older git, `run` falls back to `git gc --auto`
+//! plus `git commit-graph write` and `enable` prints a cron line instead.
+
+use anyhow::Result;
+use colored::*;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::plugins::exec::{ProjectInfo, ProjectIterator};
+use metarepo_core::MetaConfig;
+
+pub use self::plugin::MaintenancePlugin;
+
+mod plugin;
+
+/// First git release with `git maintenance`.
+const MAINTENANCE_MIN_VERSION: (u32, u32) = (2, 29);
+
+/// Parse the `(major, minor)` version from `git --version` output such as
+/// `git version 2.39.2 (Apple Git-143)` or `git version 2.45.1.windows.1`.
+pub fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.trim().strip_prefix("git version ")?;
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// The installed git version, if git runs and reports one.
+pub fn git_version() -> Option<(u32, u32)> {
+    let output = Command::new("git").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_git_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Whether `version` has the `git maintenance` command.
+pub fn supports_maintenance(version: Option<(u32, u32)>) -> bool {
+    version.is_some_and(|v| v >= MAINTENANCE_MIN_VERSION)
+}
+
+/// The cloned git repositories among `projects`, in the given order.
+fn cloned_repos(config: &MetaConfig, base_path: &Path, projects: &[String]) -> Vec<ProjectInfo> {
+    let mut repos: Vec<ProjectInfo> = ProjectIterator::new(config, base_path)
+        .with_scope(projects)
+        .filter_existing()
+        .filter_git_repos()
+        .collect();
+    repos.sort_by_key(|p| projects.iter().position(|name| *name == p.name));
+    repos
+}
+
+/// Run `git -C <path> <args>`, returning stderr (or stdout) as the error.
+fn git_in(path: &Path, args: &[&str]) -> std::result::Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let message = if stderr.trim().is_empty() {
+        stdout.trim()
+    } else {
+        stderr.trim()
+    };
+    Err(message.lines().last().unwrap_or("git failed").to_string())
+}
+
+/// The crontab line suggested when git is too old for `git maintenance`.
+pub fn cron_line(base_path: &Path) -> String {
+    format!(
+        "0 3 * * * cd '{}' && meta maintenance run --workspace",
+        base_path.display()
+    )
+}
+
+/// Register every cloned project with `git maintenance` and start the
+/// scheduler. Registration only adds each repository to the user's global
+/// `maintenance.repo` list, so it is safe to repeat.
+pub fn enable_maintenance(
+    config: &MetaConfig,
+    base_path: &Path,
+    projects: &[String],
+) -> Result<()> {
+    let version = git_version();
+    if !supports_maintenance(version) {
+        let found = version
+            .map(|(major, minor)| format!("{}.{}", major, minor))
+            .unwrap_or_else(|| "unknown".to_string());
+        println!(
+            "  {} git maintenance needs git 2.29 or newer (found {})",
+            "⚠️".yellow(),
+            found
+        );
+        println!(
+            "  {} Schedule gc and commit-graph updates with cron instead:",
+            "└".bright_black()
+        );
+        println!("\n    {}\n", cron_line(base_path).cyan());
+        anyhow::bail!("git maintenance is not available");
+    }
+
+    let repos = cloned_repos(config, base_path, projects);
+    if repos.is_empty() {
+        println!("\n{}", "No cloned projects in scope".dimmed());
+        return Ok(());
+    }
+
+    println!("\n  {} {}", "🧰".cyan(), "Enabling git maintenance".bold());
+    println!("  {}", "═".repeat(60).bright_black());
+
+    let mut registered = Vec::new();
+    let mut failed = 0;
+    for repo in &repos {
+        match git_in(&repo.path, &["maintenance", "register"]) {
+            Ok(()) => {
+                println!("  {} {}", "✓".green(), repo.name);
+                registered.push(repo);
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  {} {} {}", "✗".red(), repo.name, e.bright_black());
+            }
+        }
+    }
+
+    // `start` installs the OS scheduler (cron, launchd, or Task Scheduler)
+    // once for the user; it must run inside a repository, which it also
+    // registers, so use one that was just registered.
+    if let Some(first) = registered.first() {
+        if let Err(e) = git_in(&first.path, &["maintenance", "start"]) {
+            println!("  {}", "─".repeat(60).bright_black());
+            println!(
+                "  {} Registered {} project(s), but the scheduler did not start: {}",
+                "⚠️".yellow(),
+                registered.len(),
+                e
+            );
+            println!(
+                "  {} Run {} periodically instead",
+                "└".bright_black(),
+                "meta maintenance run".cyan()
+            );
+            anyhow::bail!("failed to start the git maintenance scheduler");
+        }
+    }
+
+    println!("  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} project(s) registered; git will maintain them in the background",
+        "✓".green(),
+        registered.len()
+    );
+    if failed > 0 {
+        anyhow::bail!("{} project(s) could not be registered", failed);
+    }
+    Ok(())
+}
+
+/// Remove every cloned project from the scheduled `git maintenance` list. The
+/// scheduler itself keeps running for any repositories outside the workspace;
+/// `git maintenance stop` turns it off entirely.
+pub fn disable_maintenance(
+    config: &MetaConfig,
+    base_path: &Path,
+    projects: &[String],
+) -> Result<()> {
+    if !supports_maintenance(git_version()) {
+        anyhow::bail!("git maintenance needs git 2.29 or newer");
+    }
+    let repos = cloned_repos(config, base_path, projects);
+    if repos.is_empty() {
+        println!("\n{}", "No cloned projects in scope".dimmed());
+        return Ok(());
+    }
+
+    let mut removed = 0;
+    for repo in &repos {
+        // Unregistering a repository that was never registered fails on some
+        // git versions; that is the state we want, so only report it.
+        match git_in(&repo.path, &["maintenance", "unregister"]) {
+            Ok(()) => {
+                removed += 1;
+                println!("  {} {}", "✓".green(), repo.name);
+            }
+            Err(e) => println!(
+                "  {} {} {}",
+                "-".bright_black(),
+                repo.name,
+                e.bright_black()
+            ),
+        }
+    }
+    println!(
+        "\n  {} {} project(s) unregistered (use {} to stop the scheduler)",
+        "✓".green(),
+        removed,
+        "git maintenance stop".cyan()
+    );
+    Ok(())
+}
+
+/// Outcome of maintaining one repository.
+#[derive(Debug)]
+pub struct MaintenanceOutcome {
+    pub name: String,
+    pub result: std::result::Result<(), String>,
+    pub elapsed: Duration,
+}
+
+/// Collect garbage and refresh the commit-graph in one repository.
+fn maintain_repo(repo: &ProjectInfo, native: bool) -> MaintenanceOutcome {
+    let start = Instant::now();
+    let result = if native {
+        git_in(
+            &repo.path,
+            &[
+                "maintenance",
+                "run",
+                "--task=gc",
+                "--task=commit-graph",
+                "--quiet",
+            ],
+        )
+    } else {
+        git_in(&repo.path, &["gc", "--auto", "--quiet"])
+            .and_then(|_| git_in(&repo.path, &["commit-graph", "write", "--reachable"]))
+    };
+    MaintenanceOutcome {
+        name: repo.name.clone(),
+        result,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Maintain `repos` across a bounded pool of threads (or one at a time with
+/// `workers == 1`), returning outcomes in input order.
+fn maintain_all(repos: &[ProjectInfo], native: bool, workers: usize) -> Vec<MaintenanceOutcome> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    let slots: Vec<Mutex<Option<MaintenanceOutcome>>> =
+        repos.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let workers = workers.clamp(1, repos.len().max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(repo) = repos.get(i) else {
+                    break;
+                };
+                *slots[i].lock().unwrap() = Some(maintain_repo(repo, native));
+            });
+        }
+    });
+
+    slots
+        .into_iter()
+        .filter_map(|slot| slot.into_inner().unwrap())
+        .collect()
+}
+
+/// Run gc and commit-graph maintenance now for every cloned project in
+/// `projects`, in parallel unless `sequential`.
+pub fn run_maintenance(
+    config: &MetaConfig,
+    base_path: &Path,
+    projects: &[String],
+    sequential: bool,
+) -> Result<()> {
+    let repos = cloned_repos(config, base_path, projects);
+    if repos.is_empty() {
+        println!("\n{}", "No cloned projects in scope".dimmed());
+        return Ok(());
+    }
+
+    let native = supports_maintenance(git_version());
+    let workers = if sequential { 1 } else { config.worker_count() };
+
+    println!(
+        "\n  {} {} {}",
+        "🧰".cyan(),
+        "Running maintenance for".bold(),
+        format!("{} project(s)", repos.len()).bold()
+    );
+    if !native {
+        println!(
+            "  {} {}",
+            "└".bright_black(),
+            "git maintenance unavailable; using gc --auto and commit-graph write".bright_black()
+        );
+    }
+    println!("  {}", "═".repeat(60).bright_black());
+
+    let started = Instant::now();
+    let outcomes = maintain_all(&repos, native, workers);
+    let width = outcomes.iter().map(|o| o.name.len()).max().unwrap_or(0);
+    let mut failed = 0;
+    for outcome in &outcomes {
+        let took = format!("{:.1}s", outcome.elapsed.as_secs_f64());
+        match &outcome.result {
+            Ok(()) => println!(
+                "  {} {:<width$}  {}",
+                "✓".green(),
+                outcome.name,
+                took.bright_black()
+            ),
+            Err(e) => {
+                failed += 1;
+                println!("  {} {:<width$}  {}", "✗".red(), outcome.name, e.red());
+            }
+        }
+    }
+
+    println!("  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} maintained, {} failed in {:.1}s",
+        if failed == 0 {
+            "✓".green()
+        } else {
+            "⚠️".yellow()
+        },
+        outcomes.len() - failed,
+        failed,
+        started.elapsed().as_secs_f64()
+    );
+    if failed > 0 {
+        anyhow::bail!("maintenance failed for {} project(s)", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_git_version_variants() {
+        assert_eq!(parse_git_version("git version 2.39.2\n"), Some((2, 39)));
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-146)"),
+            Some((2, 39))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.45.1.windows.1"),
+            Some((2, 45))
+        );
+        assert_eq!(parse_git_version("not git"), None);
+    }
+
+    #[test]
+    fn maintenance_requires_2_29() {
+        assert!(!supports_maintenance(None));
+        assert!(!supports_maintenance(Some((2, 28))));
+        assert!(supports_maintenance(Some((2, 29))));
+        assert!(supports_maintenance(Some((3, 0))));
+    }
+
+    #[test]
+    fn run_maintains_cloned_projects_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("app");
+        let status = Command::new("git").args(["init", "-q"]).arg(&repo).status();
+        if !status.map(|s| s.success()).unwrap_or(false) {
+            return; // git unavailable
+        }
+
+        let mut config = MetaConfig::default();
+        config.projects.insert(
+            "app".to_string(),
+            metarepo_core::ProjectEntry::Url("x".into()),
+        );
+        config.projects.insert(
+            "missing".to_string(),
+            metarepo_core::ProjectEntry::Url("y".into()),
+        );
+
+        let names = vec!["missing".to_string(), "app".to_string()];
+        let repos = cloned_repos(&config, tmp.path(), &names);
+        assert_eq!(repos.len(), 1);
+
+        let outcomes = maintain_all(&repos, supports_maintenance(git_version()), 2);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].name, "app");
+        assert!(outcomes[0].result.is_ok(), "{:?}", outcomes[0].result);
+    }
+}
@@ -0,0 +1,211 @@
+use super::{disable_maintenance, enable_maintenance, run_maintenance};
+use crate::plugins::shared::selection;
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::Colorize;
+use metarepo_core::{arg, command, plugin, BasePlugin, CommandBuilder, MetaPlugin, RuntimeConfig};
+
+/// MaintenancePlugin for scheduling and running git object maintenance
+pub struct MaintenancePlugin;
+
+impl MaintenancePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("maintenance")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Schedule and run git object maintenance across projects")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Keep the object stores of project clones fast.\n\
+                 \n\
+                 Large workspaces accumulate loose objects and stale commit-graphs,\n\
+                 which slows status, log, and fetch. `enable` hands each clone to\n\
+                 git's own scheduler (git maintenance, git 2.29+); `run` collects\n\
+                 garbage and rewrites the commit-graph right now, in parallel.\n\
+                 \n\
+                 Commands act on the projects in your directory scope; name projects\n\
+                 (or globs) to narrow that, and use --exclude to skip some.",
+            )
+            .command(selecting(
+                command("enable")
+                    .about("Register clones with git maintenance and start the scheduler")
+                    .help_description(
+                        "Register every cloned project with `git maintenance` and start the\n\
+                         background scheduler (cron, launchd, or Task Scheduler).\n\
+                         \n\
+                         git then runs hourly prefetch and commit-graph updates, daily\n\
+                         loose-object cleanup, and weekly incremental repacks for each\n\
+                         registered clone. Registration is recorded in your global git\n\
+                         config and is safe to repeat. On git older than 2.29 a cron line\n\
+                         running `meta maintenance run` is printed instead.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta maintenance enable                 clones in the current scope\n\
+                           meta maintenance enable -w              every clone in the workspace\n\
+                           meta maintenance enable 'services/*'    only matching projects",
+                    )
+                    .with_help_formatting(),
+            ))
+            .command(selecting(
+                command("disable")
+                    .about("Unregister clones from scheduled git maintenance")
+                    .help_description(
+                        "Unregister cloned projects from `git maintenance`.\n\
+                         \n\
+                         The scheduler keeps running for repositories outside the workspace;\n\
+                         run `git maintenance stop` to turn it off entirely.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta maintenance disable -w             unregister every clone",
+                    )
+                    .with_help_formatting(),
+            ))
+            .command(selecting(
+                command("run")
+                    .about("Run gc and commit-graph maintenance now")
+                    .help_description(
+                        "Collect garbage and rewrite the commit-graph in every cloned project,\n\
+                         several projects at a time.\n\
+                         \n\
+                         Uses `git maintenance run --task=gc --task=commit-graph` when\n\
+                         available, and `git gc --auto` plus `git commit-graph write\n\
+                         --reachable` on older git. Exits non-zero if any project fails.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta maintenance run                    clones in the current scope\n\
+                           meta maintenance run -w --sequential    whole workspace, one at a time\n\
+                           meta maintenance run --exclude vendor/*  skip vendored projects",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("sequential")
+                            .long("sequential")
+                            .help("Maintain one project at a time instead of concurrently"),
+                    ),
+            ))
+            .handler("enable", handle_enable)
+            .handler("disable", handle_disable)
+            .handler("run", handle_run)
+            .build()
+    }
+}
+
+/// Add the shared project-selection arguments to a subcommand.
+fn selecting(cmd: CommandBuilder) -> CommandBuilder {
+    cmd.arg(
+        arg("targets")
+            .help("Projects to maintain (names, aliases, or globs)")
+            .multiple(true),
+    )
+    .arg(
+        arg("strict")
+            .long("strict")
+            .help("Fail if any project selector matches nothing"),
+    )
+    .arg(
+        arg("exclude")
+            .long("exclude")
+            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+            .takes_value(true),
+    )
+}
+
+/// Resolve the projects a maintenance command acts on: the named ones, or the
+/// directory scope, minus excludes.
+fn selected_projects(matches: &ArgMatches, config: &RuntimeConfig) -> Result<Vec<String>> {
+    let (mut projects, explicit) = match selection::explicit_projects(matches, config)? {
+        Some(selected) => (selected, true),
+        None => (config.scoped_project_keys(), false),
+    };
+    selection::apply_excludes(matches, config, &mut projects, explicit);
+    Ok(projects)
+}
+
+/// Handler for the enable command
+fn handle_enable(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let projects = selected_projects(matches, config)?;
+    if projects.is_empty() {
+        println!("\n{}", "No projects in this directory".dimmed());
+        return Ok(());
+    }
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    enable_maintenance(&config.meta_config, &base_path, &projects)
+}
+
+/// Handler for the disable command
+fn handle_disable(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let projects = selected_projects(matches, config)?;
+    if projects.is_empty() {
+        println!("\n{}", "No projects in this directory".dimmed());
+        return Ok(());
+    }
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    disable_maintenance(&config.meta_config, &base_path, &projects)
+}
+
+/// Handler for the run command
+fn handle_run(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let projects = selected_projects(matches, config)?;
+    if projects.is_empty() {
+        println!("\n{}", "No projects in this directory".dimmed());
+        return Ok(());
+    }
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    run_maintenance(
+        &config.meta_config,
+        &base_path,
+        &projects,
+        matches.get_flag("sequential"),
+    )
+}
+
+impl MetaPlugin for MaintenancePlugin {
+    fn name(&self) -> &str {
+        "maintenance"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for MaintenancePlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Schedule and run git object maintenance across projects")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for MaintenancePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
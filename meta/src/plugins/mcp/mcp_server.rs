@@ -2,10 +2,11 @@ use super::client::McpClient;
 use super::config::McpConfig;
 use super::server::McpServerConfig;
 use anyhow::{Context, Result};
+use metarepo_core::MetaConfig;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, BufRead, Write};
 use std::path::PathBuf;
 use std::process::Command;
@@ -237,6 +238,65 @@ impl WorkspaceTarget {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| "default".to_string())
     }
+
+    /// This workspace's config, as the tool subprocesses will find it.
+    fn load_config(&self) -> Option<MetaConfig> {
+        let path = self
+            .config
+            .clone()
+            .or_else(|| {
+                self.root
+                    .as_deref()
+                    .and_then(MetaConfig::config_in_dir)
+                    .map(|found| found.path)
+            })
+            .or_else(MetaConfig::find_meta_file)?;
+        MetaConfig::load_resolved(path).ok()
+    }
+}
+
+/// The `--projects` list for an `exec` call: `requested` (or, when empty,
+/// every project a bulk run would pick) narrowed by the policy's allowlist,
+/// without restricted projects. Naming a restricted project is an error.
+/// Empty means no `--projects` flag.
+fn exec_targets(
+    config: Option<&MetaConfig>,
+    policy: &ServePolicy,
+    requested: &[String],
+) -> Result<Vec<String>> {
+    let mut targets = match policy.exec_projects(requested)? {
+        Some(list) if !list.is_empty() => list,
+        _ => requested.to_vec(),
+    };
+    let Some(config) = config else {
+        return Ok(targets);
+    };
+    let restricted = config.restricted_project_keys();
+    if restricted.is_empty() {
+        return Ok(targets);
+    }
+    for selector in &targets {
+        let selected = config.select_projects(&[selector]).projects;
+        if selected.iter().any(|key| restricted.contains(key)) {
+            return Err(anyhow::anyhow!(
+                "Project '{}' is restricted and not available over MCP",
+                selector
+            ));
+        }
+    }
+    if targets.is_empty() {
+        let mut hidden = config.disabled_project_keys();
+        hidden.extend(config.default_excluded_keys());
+        hidden.extend(restricted);
+        targets = config
+            .projects
+            .keys()
+            .filter(|key| !hidden.contains(*key))
+            .cloned()
+            .collect();
+        targets.sort();
+    }
+    Ok(targets)
 }
 
 pub struct MetarepoMcpServer {
@@ -951,6 +1011,36 @@ impl MetarepoMcpServer {
             ));
         }
 
+        // Projects marked restricted never reach the client: they are left
+        // out of listings and status and refused as exec targets.
+        let config = target.load_config();
+        let restricted: HashSet<String> = config
+            .as_ref()
+            .map(|c| c.restricted_project_keys())
+            .unwrap_or_default();
+        let mut restricted_keys: Vec<&String> = restricted.iter().collect();
+        restricted_keys.sort();
+        let exclude_restricted = |cmd: &mut Command| {
+            if !restricted_keys.is_empty() {
+                let keys: Vec<&str> = restricted_keys.iter().map(|k| k.as_str()).collect();
+                cmd.arg("--exclude").arg(keys.join(","));
+            }
+        };
+
+        // A running `meta daemon` answers git_status from its cache, so the
+        // tool shares the daemon's view of the workspace instead of spawning a
+        // fresh scan. The answer is the daemon's JSON rather than CLI text.
+        if name == "git_status" {
+            if let Some(mut statuses) = target
+                .root
+                .as_deref()
+                .and_then(|root| crate::plugins::daemon::cached_status(root, None, false))
+            {
+                statuses.retain(|status| !restricted.contains(&status.name));
+                return Ok(serde_json::to_string_pretty(&statuses)?);
+            }
+        }
+
         let mut cmd = Command::new(&self.metarepo_path);
 
         // Pin spawned subprocesses to the resolved workspace and enable
@@ -974,6 +1064,7 @@ impl MetarepoMcpServer {
             }
             "git_status" => {
                 cmd.args(["git", "status"]);
+                exclude_restricted(&mut cmd);
                 if arguments
                     .get("verbose")
                     .and_then(|v| v.as_bool())
@@ -984,6 +1075,7 @@ impl MetarepoMcpServer {
             }
             "git_diff" => {
                 cmd.args(["git", "diff"]);
+                exclude_restricted(&mut cmd);
                 if arguments
                     .get("staged")
                     .and_then(|v| v.as_bool())
@@ -1013,6 +1105,7 @@ impl MetarepoMcpServer {
             }
             "project_list" => {
                 cmd.args(["project", "list"]);
+                exclude_restricted(&mut cmd);
             }
             "project_add" => {
                 cmd.args(["project", "add"]);
@@ -1043,17 +1136,11 @@ impl MetarepoMcpServer {
                             .collect()
                     })
                     .unwrap_or_default();
-                // Apply the workspace's project allowlist (if any): defaults to the
-                // allowed set and rejects anything outside it.
-                match target.policy.exec_projects(&requested)? {
-                    Some(list) if !list.is_empty() => {
-                        cmd.arg("--projects").arg(list.join(","));
-                    }
-                    _ => {
-                        if !requested.is_empty() {
-                            cmd.arg("--projects").arg(requested.join(","));
-                        }
-                    }
+                // Apply the workspace's project allowlist (if any) and keep
+                // restricted projects out.
+                let targets = exec_targets(config.as_ref(), &target.policy, &requested)?;
+                if !targets.is_empty() {
+                    cmd.arg("--projects").arg(targets.join(","));
                 }
             }
             "mcp_add_server" => {
@@ -1521,6 +1608,41 @@ mod tests {
         assert!(p.exec_projects(&["secret".into()]).is_err());
     }
 
+    #[test]
+    fn exec_targets_keep_restricted_projects_out() {
+        let config: MetaConfig = serde_json::from_str(
+            r#"{
+                "projects": {
+                    "billing": { "url": "u", "visibility": "restricted" },
+                    "old": { "url": "u", "enabled": false },
+                    "web": "u",
+                    "api": "u"
+                }
+            }"#,
+        )
+        .unwrap();
+        let mut p = ServePolicy::default();
+        // No list: every runnable project except the restricted one.
+        assert_eq!(
+            exec_targets(Some(&config), &p, &[]).unwrap(),
+            vec!["api".to_string(), "web".to_string()]
+        );
+        // Naming it, directly or through a glob, is refused.
+        assert!(exec_targets(Some(&config), &p, &["billing".into()]).is_err());
+        assert!(exec_targets(Some(&config), &p, &["b*".into()]).is_err());
+        assert_eq!(
+            exec_targets(Some(&config), &p, &["web".into()]).unwrap(),
+            vec!["web".to_string()]
+        );
+        // An allowlist that includes it does not let it through.
+        p.projects = Some(vec!["web".into(), "billing".into()]);
+        assert!(exec_targets(Some(&config), &p, &[]).is_err());
+        // Without a readable config the request passes through unchanged.
+        assert!(exec_targets(None, &ServePolicy::default(), &[])
+            .unwrap()
+            .is_empty());
+    }
+
     fn target(name: &str, mode: ServeMode) -> WorkspaceTarget {
         WorkspaceTarget {
             name: name.to_string(),
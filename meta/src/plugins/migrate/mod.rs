@@ -0,0 +1,406 @@
+//! `meta migrate from-meta-js` — convert a workspace managed by the original
+//! JavaScript `meta` tool.
+//!
+//! Its `.meta` is close to ours (a `projects` map of path to git URL, plus an
+//! optional `ignore` list), but it is looser: project paths may carry `./` or
+//! a trailing slash, loop ignores live in a separate `.looprc`, and each
+//! project is appended to `.gitignore` as a bare line rather than inside our
+//! managed block. Migration normalizes all of that and reports whatever it
+//! could not carry over.
+
+use crate::plugins::shared::gitignore;
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::{
+    is_supported_git_url, validate_path_segment, validate_project_url, MetaConfig, ProjectEntry,
+};
+use serde_json::Value;
+use std::path::Path;
+
+pub use self::plugin::MigratePlugin;
+
+mod plugin;
+
+/// The JavaScript tool's loop configuration, read for its `ignore` list.
+pub const LOOPRC_FILE: &str = ".looprc";
+
+/// Suffix appended to the original file's name for the backup.
+const BACKUP_SUFFIX: &str = ".js.bak";
+
+/// Something migration changed or dropped, for the report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Note {
+    /// Carried over in a different form.
+    Changed(String),
+    /// Left out; needs the user's attention.
+    Untranslated(String),
+}
+
+/// The result of translating a JavaScript `meta` config.
+#[derive(Debug, Clone)]
+pub struct Translation {
+    pub config: MetaConfig,
+    pub notes: Vec<Note>,
+}
+
+/// A project path the way we key projects: forward slashes, no `./` prefix,
+/// no trailing slash.
+fn normalize_path(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let mut path = path.as_str();
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest;
+    }
+    path.trim_end_matches('/').to_string()
+}
+
+fn push_ignores(config: &mut MetaConfig, value: &Value, source: &str, notes: &mut Vec<Note>) {
+    let Some(entries) = value.as_array() else {
+        notes.push(Note::Untranslated(format!(
+            "'ignore' in {} is not a list",
+            source
+        )));
+        return;
+    };
+    for entry in entries {
+        match entry.as_str() {
+            Some(pattern) if !config.ignore.iter().any(|p| p == pattern) => {
+                config.ignore.push(pattern.to_string());
+            }
+            Some(_) => {}
+            None => notes.push(Note::Untranslated(format!(
+                "ignore entry {} in {} is not a string",
+                entry, source
+            ))),
+        }
+    }
+}
+
+/// Translate the contents of a JavaScript `meta` `.meta` file, and of its
+/// `.looprc` when there is one.
+pub fn translate(meta: &Value, looprc: Option<&Value>) -> Result<Translation> {
+    let object = meta
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("Expected a JSON object at the top of the file"))?;
+    let mut config = MetaConfig::default();
+    let mut notes = Vec::new();
+
+    for (key, value) in object {
+        match key.as_str() {
+            "projects" => {
+                let Some(projects) = value.as_object() else {
+                    notes.push(Note::Untranslated("'projects' is not a map".to_string()));
+                    continue;
+                };
+                for (raw_path, url) in projects {
+                    let path = normalize_path(raw_path);
+                    if let Err(e) = validate_path_segment("project path", &path) {
+                        notes.push(Note::Untranslated(format!("project '{}': {}", raw_path, e)));
+                        continue;
+                    }
+                    let Some(url) = url.as_str() else {
+                        notes.push(Note::Untranslated(format!(
+                            "project '{}': the URL is not a string",
+                            raw_path
+                        )));
+                        continue;
+                    };
+                    if let Err(e) = validate_project_url(url) {
+                        notes.push(Note::Untranslated(format!("project '{}': {}", raw_path, e)));
+                        continue;
+                    }
+                    if config.projects.contains_key(&path) {
+                        notes.push(Note::Untranslated(format!(
+                            "project '{}' duplicates '{}'",
+                            raw_path, path
+                        )));
+                        continue;
+                    }
+                    if path != *raw_path {
+                        notes.push(Note::Changed(format!(
+                            "project '{}' is now '{}'",
+                            raw_path, path
+                        )));
+                    }
+                    if !is_supported_git_url(url) {
+                        notes.push(Note::Changed(format!(
+                            "project '{}': '{}' is not a URL meta recognizes; \
+                             check it clones",
+                            path, url
+                        )));
+                    }
+                    config
+                        .projects
+                        .insert(path, ProjectEntry::Url(url.to_string()));
+                }
+            }
+            "ignore" => push_ignores(&mut config, value, ".meta", &mut notes),
+            other => notes.push(Note::Untranslated(format!(
+                "key '{}' has no equivalent",
+                other
+            ))),
+        }
+    }
+
+    if let Some(looprc) = looprc {
+        match looprc.get("ignore") {
+            Some(ignore) => {
+                push_ignores(&mut config, ignore, LOOPRC_FILE, &mut notes);
+                notes.push(Note::Changed(format!(
+                    "ignore entries from {} moved into .meta; {} is no longer read",
+                    LOOPRC_FILE, LOOPRC_FILE
+                )));
+            }
+            None => notes.push(Note::Untranslated(format!(
+                "{} has no 'ignore' list; nothing was taken from it",
+                LOOPRC_FILE
+            ))),
+        }
+    }
+    Ok(Translation { config, notes })
+}
+
+/// Rewrite a `.gitignore` so every project is listed in the managed block:
+/// bare project lines the JavaScript tool appended (`api`, `/api`, `api/`)
+/// are moved into it. Returns the new content and the lines moved.
+pub fn fix_gitignore(content: &str, config: &MetaConfig) -> (String, Vec<String>) {
+    let is_project = |line: &str| {
+        let trimmed = line.trim();
+        let bare = trimmed.trim_start_matches('/');
+        !trimmed.starts_with('#') && config.projects.contains_key(&normalize_path(bare))
+    };
+    let mut moved = Vec::new();
+    let mut kept = String::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed == gitignore::MANAGED_BEGIN {
+            in_block = true;
+        } else if trimmed == gitignore::MANAGED_END {
+            in_block = false;
+        } else if !in_block && is_project(line) {
+            moved.push(trimmed.to_string());
+            continue;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+    }
+
+    let mut entries = gitignore::managed_entries(&kept);
+    entries.extend(gitignore::expected_entries(config));
+    (
+        gitignore::render_with_managed_entries(&kept, &entries),
+        moved,
+    )
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| {
+        format!(
+            "Failed to parse {} as JSON; the JavaScript meta tool only writes JSON",
+            path.display()
+        )
+    })
+}
+
+/// Migrate the JavaScript `meta` config at `source`, in place. The original
+/// is kept next to it with a `.js.bak` suffix. With `dry_run` nothing is
+/// written; the translated config is printed instead.
+pub fn migrate_from_meta_js(source: &Path, dry_run: bool) -> Result<Translation> {
+    let base = source.parent().unwrap_or(Path::new("."));
+    let meta = read_json(source)?;
+    let looprc_path = base.join(LOOPRC_FILE);
+    let looprc = if looprc_path.exists() {
+        Some(read_json(&looprc_path)?)
+    } else {
+        None
+    };
+    let translation = translate(&meta, looprc.as_ref())?;
+
+    let gitignore_path = base.join(".gitignore");
+    let old_gitignore = std::fs::read_to_string(&gitignore_path).unwrap_or_default();
+    let (new_gitignore, moved) = fix_gitignore(&old_gitignore, &translation.config);
+
+    print_report(source, &translation, &moved, dry_run);
+    if dry_run {
+        println!("{}", serde_json::to_string_pretty(&translation.config)?);
+        return Ok(translation);
+    }
+
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| ".meta".to_string());
+    let backup = base.join(format!("{}{}", file_name, BACKUP_SUFFIX));
+    std::fs::copy(source, &backup)
+        .with_context(|| format!("Failed to back up {}", source.display()))?;
+    translation
+        .config
+        .save_to_file_with_format(source, metarepo_core::ConfigFormat::Json)?;
+    if new_gitignore != old_gitignore {
+        std::fs::write(&gitignore_path, new_gitignore)
+            .with_context(|| format!("Failed to write {}", gitignore_path.display()))?;
+    }
+    println!(
+        "  {} Wrote {} (original kept as {})",
+        "✓".green(),
+        source.display(),
+        backup.display()
+    );
+    println!();
+    Ok(translation)
+}
+
+fn print_report(source: &Path, translation: &Translation, moved: &[String], dry_run: bool) {
+    println!(
+        "\n  {} {}",
+        "🚚".cyan(),
+        format!(
+            "Migrating {} from the JavaScript meta tool",
+            source.display()
+        )
+        .bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    println!(
+        "  {} {} project(s), {} ignore pattern(s)",
+        "✓".green(),
+        translation.config.projects.len(),
+        translation.config.ignore.len()
+    );
+    if !moved.is_empty() {
+        println!(
+            "  {} {} .gitignore line(s) moved into the managed block: {}",
+            "✓".green(),
+            moved.len(),
+            moved.join(", ").bright_black()
+        );
+    }
+    for note in &translation.notes {
+        match note {
+            Note::Changed(message) => println!("  {} {}", "ℹ".bright_black(), message),
+            Note::Untranslated(message) => println!("  {} {}", "⚠️".yellow(), message),
+        }
+    }
+    let untranslated = translation
+        .notes
+        .iter()
+        .filter(|n| matches!(n, Note::Untranslated(_)))
+        .count();
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} item(s) could not be translated{}",
+        "Summary:".bright_black(),
+        if untranslated > 0 {
+            untranslated.to_string().yellow()
+        } else {
+            "0".green()
+        },
+        if dry_run {
+            " (dry run, nothing written)"
+        } else {
+            ""
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn translates_projects_and_reports_leftovers() {
+        let meta = json!({
+            "projects": {
+                "./api/": "git@github.com:acme/api.git",
+                "api": "git@github.com:acme/api-dup.git",
+                "web": "https://github.com/acme/web.git",
+                "../outside": "git@github.com:acme/outside.git",
+                "tools": 42,
+                "vendor/lib": "lib-mirror"
+            },
+            "ignore": ["dist"],
+            "plugins": ["meta-npm"]
+        });
+        let looprc = json!({ "ignore": ["dist", "coverage"] });
+        let t = translate(&meta, Some(&looprc)).unwrap();
+
+        let mut keys: Vec<&String> = t.config.projects.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["api", "vendor/lib", "web"]);
+        assert!(t
+            .config
+            .ignore
+            .ends_with(&["dist".to_string(), "coverage".to_string()]));
+
+        let untranslated: Vec<&str> = t
+            .notes
+            .iter()
+            .filter_map(|n| match n {
+                Note::Untranslated(m) => Some(m.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(untranslated.len(), 4, "{:?}", untranslated);
+        assert!(untranslated.iter().any(|m| m.contains("'plugins'")));
+        assert!(untranslated.iter().any(|m| m.contains("'../outside'")));
+        assert!(untranslated.iter().any(|m| m.contains("'tools'")));
+        assert!(untranslated.iter().any(|m| m.contains("duplicates")));
+        assert!(t
+            .notes
+            .contains(&Note::Changed("project './api/' is now 'api'".to_string())));
+        assert!(t.notes.iter().any(|n| matches!(n,
+            Note::Changed(m) if m.contains("'lib-mirror' is not a URL"))));
+    }
+
+    #[test]
+    fn moves_project_lines_into_the_managed_block() {
+        let t = translate(
+            &json!({ "projects": { "api": "git@h:a.git", "web": "git@h:w.git" } }),
+            None,
+        )
+        .unwrap();
+        let (content, moved) = fix_gitignore("# deps\nnode_modules\n/api\nweb/\n", &t.config);
+        assert_eq!(moved, ["/api", "web/"]);
+        assert_eq!(
+            content,
+            format!(
+                "# deps\nnode_modules\n\n{}\napi\nweb\n{}\n",
+                gitignore::MANAGED_BEGIN,
+                gitignore::MANAGED_END
+            )
+        );
+        // Already migrated: nothing moves and the file is stable.
+        assert_eq!(
+            fix_gitignore(&content, &t.config),
+            (content.clone(), vec![])
+        );
+    }
+
+    #[test]
+    fn migrates_in_place_with_a_backup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join(".meta");
+        let original = r#"{ "projects": { "./api": "git@github.com:acme/api.git" } }"#;
+        std::fs::write(&source, original).unwrap();
+        std::fs::write(tmp.path().join(LOOPRC_FILE), r#"{ "ignore": ["dist"] }"#).unwrap();
+        std::fs::write(tmp.path().join(".gitignore"), "api\n").unwrap();
+
+        migrate_from_meta_js(&source, true).unwrap();
+        assert_eq!(std::fs::read_to_string(&source).unwrap(), original);
+
+        migrate_from_meta_js(&source, false).unwrap();
+        let config = MetaConfig::load_from_file(&source).unwrap();
+        assert!(config.projects.contains_key("api"));
+        assert!(config.ignore.contains(&"dist".to_string()));
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join(".meta.js.bak")).unwrap(),
+            original
+        );
+        let gitignore = std::fs::read_to_string(tmp.path().join(".gitignore")).unwrap();
+        assert!(gitignore.starts_with(gitignore::MANAGED_BEGIN));
+    }
+}
@@ -0,0 +1,119 @@
+use super::migrate_from_meta_js;
+use anyhow::Result;
+use clap::ArgMatches;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+
+/// MigratePlugin for converting workspaces from other tools
+pub struct MigratePlugin;
+
+impl MigratePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("migrate")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Convert a workspace from another multi-repo tool")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Convert a workspace from another multi-repo tool.\n\
+                 \n\
+                 `meta migrate from-meta-js` converts a workspace managed by the\n\
+                 original JavaScript meta tool.",
+            )
+            .command(
+                command("from-meta-js")
+                    .about("Convert a .meta written by the JavaScript meta tool")
+                    .help_description(
+                        "Convert a workspace managed by the original JavaScript meta tool.\n\
+                         \n\
+                         Reads its .meta (and .looprc, if present) and rewrites .meta in\n\
+                         place, keeping the original as .meta.js.bak:\n\
+                         \n\
+                           - project paths are normalized ('./api/' becomes 'api')\n\
+                           - ignore entries from .meta and .looprc are merged into ignore\n\
+                           - project lines the JavaScript tool appended to .gitignore are\n\
+                             moved into meta's managed block, and missing ones added\n\
+                         \n\
+                         Anything without an equivalent (other top-level keys, project\n\
+                         paths outside the workspace, non-string URLs) is left out and\n\
+                         listed in the report. --dry-run prints the report and the\n\
+                         translated config without writing anything.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta migrate from-meta-js --dry-run\n\
+                           meta migrate from-meta-js\n\
+                           meta migrate from-meta-js --file ../old-workspace/.meta",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("file")
+                            .long("file")
+                            .help("The JavaScript meta tool's .meta file")
+                            .takes_value(true)
+                            .default_value(".meta"),
+                    )
+                    .arg(
+                        arg("dry-run")
+                            .long("dry-run")
+                            .help("Show the translation without writing anything"),
+                    ),
+            )
+            .handler("from-meta-js", handle_from_meta_js)
+            .build()
+    }
+}
+
+/// Handler for the from-meta-js command
+fn handle_from_meta_js(matches: &ArgMatches, _config: &RuntimeConfig) -> Result<()> {
+    let file = matches
+        .get_one::<String>("file")
+        .map(String::as_str)
+        .unwrap_or(".meta");
+    let source = std::env::current_dir()?.join(file);
+    if !source.exists() {
+        return Err(anyhow::anyhow!("{} not found", source.display()));
+    }
+    migrate_from_meta_js(&source, matches.get_flag("dry-run")).map(|_| ())
+}
+
+impl MetaPlugin for MigratePlugin {
+    fn name(&self) -> &str {
+        "migrate"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for MigratePlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Convert a workspace from another multi-repo tool")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for MigratePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
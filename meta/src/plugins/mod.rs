@@ -1,35 +1,83 @@
 // Built-in plugins for metarepo
 // These are compiled directly into the binary rather than as separate crates
 
+pub mod auth;
 pub mod config;
+pub mod daemon;
+pub mod docs;
+pub mod doctor;
 pub mod exec;
+pub mod export;
+pub mod feed;
+pub mod files;
 pub mod git;
+pub mod gitignore;
+pub mod impact;
 pub mod init;
+pub mod last;
+pub mod lock;
+pub mod maintenance;
 pub mod manifest_plugin;
 pub mod mcp;
+pub mod migrate;
 pub mod module;
+pub mod onboard;
 pub mod plugin_loader;
 pub mod plugin_manager;
+pub mod profile;
 pub mod project;
+pub mod reconstruct;
 pub mod rules;
 pub mod run;
+pub mod schedule;
+pub mod services;
 pub mod shared;
 pub mod skill;
+pub mod stats;
 pub mod status;
+pub mod test_report;
+pub mod tips;
+pub mod todos;
+pub mod track;
+pub mod which;
 pub mod worktree;
 
 // Re-export plugin structs for convenience
+pub use auth::AuthPlugin;
 pub use config::ConfigPlugin;
+pub use daemon::DaemonPlugin;
+pub use docs::DocsPlugin;
+pub use doctor::DoctorPlugin;
 pub use exec::ExecPlugin;
+pub use export::ExportPlugin;
+pub use feed::FeedPlugin;
+pub use files::FilesPlugin;
 pub use git::GitPlugin;
+pub use gitignore::GitignorePlugin;
+pub use impact::ImpactPlugin;
 pub use init::InitPlugin;
+pub use last::LastPlugin;
+pub use lock::{LockPlugin, VerifyPlugin};
+pub use maintenance::MaintenancePlugin;
 pub use mcp::McpPlugin;
+pub use migrate::MigratePlugin;
 pub use module::ModulePlugin;
+pub use onboard::OnboardPlugin;
 pub use plugin_manager::PluginManagerPlugin;
+pub use profile::ProfilePlugin;
 pub use project::ProjectPlugin;
+pub use reconstruct::ReconstructPlugin;
 pub use rules::RulesPlugin;
-pub use run::RunPlugin;
+pub use run::{EntryPointPlugin, RunPlugin, VerifySetupPlugin};
+pub use schedule::SchedulePlugin;
+pub use services::ServicesPlugin;
 pub use skill::SkillPlugin;
+pub use stats::StatsPlugin;
+pub use test_report::TestPlugin;
+pub use tips::TipsPlugin;
+pub use todos::TodosPlugin;
+pub use track::TrackPlugin;
+pub use which::WhichPlugin;
 pub use worktree::WorktreePlugin;
 
 // Re-export plugin loader
@@ -0,0 +1,217 @@
+//! `meta onboard` — the new-developer flow in one command.
+//!
+//! The steps run in order, each only once the one before has passed:
+//!
+//! 1. clone the projects (of the `onboard.group`, or all of them) missing here;
+//! 2. run each project's `post_clone` script;
+//! 3. check that the `onboard.tools` are on `PATH`;
+//! 4. run each project's `verify` script, as `meta verify-setup` does;
+//!
+//! then list the `onboard.manual_steps` still to do. Progress is kept in
+//! `.meta-state/onboard.json`, so after fixing whatever stopped a run, running
+//! `meta onboard` again continues at the step that failed.
+
+use anyhow::Result;
+use colored::*;
+use metarepo_core::MetaConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::plugins::run::{build_script_command, project_has_script};
+use crate::plugins::skill::adapt::which;
+
+pub use self::plugin::OnboardPlugin;
+
+mod plugin;
+
+/// State file holding onboarding progress.
+pub const PROGRESS_FILE: &str = "onboard.json";
+/// The script a project declares to finish its setup after a clone
+/// (installing dependencies, generating files).
+pub const POST_CLONE_SCRIPT: &str = "post_clone";
+
+/// An automated onboarding step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Step {
+    Clone,
+    PostClone,
+    Tools,
+    Verify,
+}
+
+impl Step {
+    /// Every step, in the order they run.
+    pub const ALL: [Step; 4] = [Step::Clone, Step::PostClone, Step::Tools, Step::Verify];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Step::Clone => "Clone projects",
+            Step::PostClone => "Run post_clone scripts",
+            Step::Tools => "Check toolchain",
+            Step::Verify => "Verify setup",
+        }
+    }
+}
+
+/// What a workspace's onboarding has got through so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnboardProgress {
+    /// Steps that have passed.
+    #[serde(default)]
+    pub completed: Vec<Step>,
+    /// Projects whose `post_clone` script has succeeded, so a rerun of that
+    /// step skips them.
+    #[serde(default)]
+    pub post_clone_done: Vec<String>,
+    /// Manual steps ticked off with `--done`, by their text.
+    #[serde(default)]
+    pub manual_done: Vec<String>,
+}
+
+impl OnboardProgress {
+    pub fn is_done(&self, step: Step) -> bool {
+        self.completed.contains(&step)
+    }
+
+    pub fn complete(&mut self, step: Step) {
+        if !self.is_done(step) {
+            self.completed.push(step);
+        }
+    }
+
+    /// Mark manual step `number` (1-based) of `steps` done and return its
+    /// text.
+    pub fn tick_manual<'a>(&mut self, steps: &'a [String], number: usize) -> Result<&'a str> {
+        let step = number
+            .checked_sub(1)
+            .and_then(|i| steps.get(i))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No manual step {}; .meta lists {} (see `meta onboard`)",
+                    number,
+                    steps.len()
+                )
+            })?;
+        if !self.manual_done.contains(step) {
+            self.manual_done.push(step.clone());
+        }
+        Ok(step)
+    }
+
+    /// The manual steps of `steps` not yet ticked off.
+    pub fn manual_remaining<'a>(&self, steps: &'a [String]) -> Vec<&'a String> {
+        steps
+            .iter()
+            .filter(|step| !self.manual_done.contains(step))
+            .collect()
+    }
+}
+
+/// The `tools` that are not on `PATH`.
+pub fn missing_tools(tools: &[String]) -> Vec<String> {
+    tools
+        .iter()
+        .filter(|tool| which(tool).is_none())
+        .cloned()
+        .collect()
+}
+
+/// Run the `post_clone` script of each cloned project in `projects` that has
+/// one and has not run it successfully yet, with its output shown live.
+/// Returns whether every script succeeded.
+pub fn run_post_clone(
+    config: &MetaConfig,
+    base_path: &Path,
+    projects: &[String],
+    progress: &mut OnboardProgress,
+    save: impl Fn(&OnboardProgress) -> Result<()>,
+) -> Result<bool> {
+    let pending: Vec<&String> = projects
+        .iter()
+        .filter(|project| !progress.post_clone_done.contains(project))
+        .filter(|project| base_path.join(project).exists())
+        .filter(|project| project_has_script(config, POST_CLONE_SCRIPT, project, base_path))
+        .collect();
+    if pending.is_empty() {
+        println!("  No project has a post_clone script left to run");
+        return Ok(true);
+    }
+
+    let mut all_ok = true;
+    for project in pending {
+        println!("\n  {} {}", "→".cyan(), project.bold());
+        let status = build_script_command(
+            config,
+            POST_CLONE_SCRIPT,
+            project,
+            base_path,
+            &HashMap::new(),
+        )
+        .and_then(|(mut cmd, _)| Ok(cmd.status()?));
+        match status {
+            Ok(status) if status.success() => {
+                println!("  {} {}", "✓".green(), project);
+                progress.post_clone_done.push(project.clone());
+                save(progress)?;
+            }
+            Ok(status) => {
+                all_ok = false;
+                let code = status
+                    .code()
+                    .map(|c| format!("exit {}", c))
+                    .unwrap_or_else(|| "killed".to_string());
+                println!("  {} {}: post_clone failed ({})", "✗".red(), project, code);
+            }
+            Err(e) => {
+                all_ok = false;
+                println!("  {} {}: {}", "✗".red(), project, e);
+            }
+        }
+    }
+    Ok(all_ok)
+}
+
+/// Print the manual steps as a numbered checklist.
+pub fn print_manual_steps(steps: &[String], progress: &OnboardProgress) {
+    println!("\n  {}\n", "Manual steps".bold());
+    for (i, step) in steps.iter().enumerate() {
+        if progress.manual_done.contains(step) {
+            println!("  {} {}. {}", "✓".green(), i + 1, step.bright_black());
+        } else {
+            println!("  {} {}. {}", "☐".yellow(), i + 1, step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_completed_and_manual_steps() {
+        let mut progress = OnboardProgress::default();
+        progress.complete(Step::Clone);
+        progress.complete(Step::Clone);
+        assert_eq!(progress.completed, [Step::Clone]);
+        assert!(!progress.is_done(Step::PostClone));
+
+        let manual = vec!["Get VPN access".to_string(), "Join #dev".to_string()];
+        assert_eq!(progress.tick_manual(&manual, 2).unwrap(), "Join #dev");
+        assert!(progress.tick_manual(&manual, 0).is_err());
+        assert!(progress.tick_manual(&manual, 3).is_err());
+        assert_eq!(progress.manual_remaining(&manual), [&manual[0]]);
+
+        let json = serde_json::to_string(&progress).unwrap();
+        assert!(json.contains("\"clone\""), "{}", json);
+        let loaded: OnboardProgress = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, progress);
+    }
+
+    #[test]
+    fn reports_tools_missing_from_path() {
+        let tools = vec!["sh".to_string(), "no-such-tool-for-meta".to_string()];
+        assert_eq!(missing_tools(&tools), ["no-such-tool-for-meta"]);
+    }
+}
@@ -0,0 +1,202 @@
+//! Plugin wiring for `meta onboard`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::*;
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{
+    missing_tools, print_manual_steps, run_post_clone, OnboardProgress, Step, PROGRESS_FILE,
+};
+use crate::plugins::git::clone_missing;
+use crate::plugins::run::load_config_with_script_cascade;
+use crate::plugins::run::verify::{check_project, print_checklist};
+use crate::plugins::shared::state;
+
+/// Registers the top-level `meta onboard` command.
+pub struct OnboardPlugin;
+
+impl OnboardPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for OnboardPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for OnboardPlugin {
+    fn name(&self) -> &str {
+        "onboard"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("onboard")
+                .about("Set up a new checkout of the workspace, step by step")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Walk a new developer through setting up the workspace:\n\
+                     \n\
+                     1. clone the missing projects (of onboard.group, or all of them)\n\
+                     2. run each project's post_clone script\n\
+                     3. check that the onboard.tools are on PATH\n\
+                     4. run each project's verify script, as meta verify-setup does\n\
+                     \n\
+                     and then list the onboard.manual_steps from .meta. A step runs only\n\
+                     once the one before has passed. Progress is kept in\n\
+                     .meta-state/onboard.json: after fixing what stopped a run, run\n\
+                     `meta onboard` again to continue where it left off. --done N ticks\n\
+                     off manual step N; --restart forgets all progress.\n\
+                     \n\
+                     Examples:\n  \
+                       meta onboard\n  \
+                       meta onboard --group frontend\n  \
+                       meta onboard --done 2\n",
+                ))
+                .arg(
+                    Arg::new("group")
+                        .long("group")
+                        .help("Onboard onto this project group instead of onboard.group")
+                        .value_name("GROUP"),
+                )
+                .arg(
+                    Arg::new("done")
+                        .long("done")
+                        .help("Mark manual step N as done")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("restart")
+                        .long("restart")
+                        .help("Forget recorded progress and start from the first step")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let base_path = config
+            .meta_root()
+            .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+        let meta_config = load_config_with_script_cascade(&base_path)?;
+        let settings = meta_config.onboard.clone().unwrap_or_default();
+        let save = |progress: &OnboardProgress| state::save(&base_path, PROGRESS_FILE, progress);
+
+        let mut progress: OnboardProgress = if matches.get_flag("restart") {
+            OnboardProgress::default()
+        } else {
+            state::load(&base_path, PROGRESS_FILE)?
+        };
+
+        if let Some(number) = matches.get_one::<usize>("done") {
+            let step = progress.tick_manual(&settings.manual_steps, *number)?;
+            println!("{} {}", "✓".green(), step);
+            save(&progress)?;
+            print_manual_steps(&settings.manual_steps, &progress);
+            return Ok(());
+        }
+
+        let group = matches
+            .get_one::<String>("group")
+            .or(settings.group.as_ref());
+        let projects = match group {
+            Some(name) => meta_config.group_projects(name)?,
+            None => {
+                let excluded = meta_config.default_excluded_keys();
+                let mut all: Vec<String> = meta_config
+                    .projects
+                    .keys()
+                    .filter(|key| !excluded.contains(*key))
+                    .cloned()
+                    .collect();
+                all.sort();
+                all
+            }
+        };
+        save(&progress)?;
+
+        for (i, step) in Step::ALL.into_iter().enumerate() {
+            let heading = format!("[{}/{}] {}", i + 1, Step::ALL.len(), step.title());
+            if progress.is_done(step) {
+                println!("{} {}", "✓".green(), heading.bright_black());
+                continue;
+            }
+            println!("\n{}", heading.bold());
+            let passed = match step {
+                Step::Clone => {
+                    let mut scoped = meta_config.clone();
+                    scoped.projects.retain(|key, _| projects.contains(key));
+                    clone_missing(&scoped, &base_path, false, 1)?.failed == 0
+                }
+                Step::PostClone => {
+                    run_post_clone(&meta_config, &base_path, &projects, &mut progress, save)?
+                }
+                Step::Tools => {
+                    let missing = missing_tools(&settings.tools);
+                    for tool in &settings.tools {
+                        if missing.contains(tool) {
+                            println!("  {} {} is not on PATH", "✗".red(), tool);
+                        } else {
+                            println!("  {} {}", "✓".green(), tool);
+                        }
+                    }
+                    if settings.tools.is_empty() {
+                        println!("  No tools listed in onboard.tools");
+                    }
+                    missing.is_empty()
+                }
+                Step::Verify => {
+                    let results: Vec<_> = projects
+                        .iter()
+                        .map(|p| (p.clone(), check_project(&meta_config, p, &base_path)))
+                        .collect();
+                    print_checklist(&results) == 0
+                }
+            };
+            if !passed {
+                save(&progress)?;
+                println!(
+                    "\n{} Fix the problems above, then run `meta onboard` again to continue.",
+                    "→".bright_black()
+                );
+                return Err(anyhow::anyhow!("onboarding stopped at: {}", step.title()));
+            }
+            progress.complete(step);
+            save(&progress)?;
+        }
+
+        let remaining = progress.manual_remaining(&settings.manual_steps);
+        if !settings.manual_steps.is_empty() {
+            print_manual_steps(&settings.manual_steps, &progress);
+        }
+        if remaining.is_empty() {
+            println!("\n{} Onboarding complete.", "✓".green());
+        } else {
+            println!(
+                "\n{} {} manual step(s) left; tick each off with `meta onboard --done <N>`.",
+                "→".bright_black(),
+                remaining.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl BasePlugin for OnboardPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Guided setup of a new workspace checkout")
+    }
+}
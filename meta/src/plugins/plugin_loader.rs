@@ -34,6 +34,7 @@ impl ExternalPlugin {
     ///
     /// Allowed roots:
     ///   - `$HOME/.config/metarepo/plugins`
+    ///   - the `plugin_dirs` listed in the user config
     ///   - `$HOME/.cargo/bin` (where `cargo install metarepo-plugin-*` lands)
     ///   - `<workspace>/.metarepo/plugins` (per-repo plugins, if used)
     ///   - `<workspace>/.meta-modules` (plugins staged from enabled meta modules)
@@ -67,6 +68,7 @@ impl ExternalPlugin {
             allowed.push(PathBuf::from(&home).join(".config/metarepo/plugins"));
             allowed.push(PathBuf::from(&home).join(".cargo/bin"));
         }
+        allowed.extend(user_plugin_dirs());
         if let Ok(cwd) = std::env::current_dir() {
             allowed.push(cwd.join(".metarepo/plugins"));
             allowed.push(cwd.join(".meta-modules"));
@@ -595,7 +597,7 @@ impl PluginLoader {
         }
     }
 
-    /// Discover ambient plugins in the user's plugin directory: ones dropped in
+    /// Discover ambient plugins in the user's plugin directories: ones dropped in
     /// but not declared in `.metarepo`. Top-level executables load as protocol
     /// plugins; a `plugin.manifest.*` (top-level or inside a per-plugin
     /// subdirectory, how `meta plugin install` lays out manifest plugins) loads
@@ -612,54 +614,54 @@ impl PluginLoader {
     ) -> Vec<Box<dyn MetaPlugin>> {
         let mut plugins = Vec::new();
 
-        let Ok(plugin_dir) = Self::plugin_dir() else {
-            return plugins;
-        };
-        if !plugin_dir.exists() {
-            return plugins;
-        }
-        let Ok(entries) = std::fs::read_dir(&plugin_dir) else {
-            return plugins;
-        };
-
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                // Per-plugin subdirectory: its name is the install name.
-                if path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .is_some_and(|n| skip.contains(n))
-                {
-                    continue;
-                }
-                if let Some(manifest) = PluginManifest::find_in_dir(&path) {
-                    if let Ok(plugin) = Self::load_from_path(&manifest, allow_any_path) {
+        let dirs = Self::plugin_dir().into_iter().chain(user_plugin_dirs());
+        for plugin_dir in dirs {
+            let Ok(entries) = std::fs::read_dir(&plugin_dir) else {
+                continue;
+            };
+            // Directory order varies by filesystem; plugins with the same name
+            // must load in the same order every run.
+            let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+            paths.sort();
+
+            for path in paths {
+                if path.is_dir() {
+                    // Per-plugin subdirectory: its name is the install name.
+                    if path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| skip.contains(n))
+                    {
+                        continue;
+                    }
+                    if let Some(manifest) = PluginManifest::find_in_dir(&path) {
+                        if let Ok(plugin) = Self::load_from_path(&manifest, allow_any_path) {
+                            if !skip.contains(plugin.name()) {
+                                plugins.push(plugin);
+                            }
+                        }
+                    }
+                } else if path.is_file() && !PluginManifest::is_manifest_path(&path) {
+                    // Loose executable: `metarepo-plugin-<name>` encodes the name.
+                    if path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .and_then(|n| n.strip_prefix("metarepo-plugin-"))
+                        .is_some_and(|n| skip.contains(n))
+                    {
+                        continue;
+                    }
+                    if let Ok(plugin) = Self::load_from_path(&path, allow_any_path) {
                         if !skip.contains(plugin.name()) {
                             plugins.push(plugin);
                         }
                     }
-                }
-            } else if path.is_file() && !PluginManifest::is_manifest_path(&path) {
-                // Loose executable: `metarepo-plugin-<name>` encodes the name.
-                if path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .and_then(|n| n.strip_prefix("metarepo-plugin-"))
-                    .is_some_and(|n| skip.contains(n))
-                {
-                    continue;
-                }
-                if let Ok(plugin) = Self::load_from_path(&path, allow_any_path) {
-                    if !skip.contains(plugin.name()) {
-                        plugins.push(plugin);
-                    }
-                }
-            } else if PluginManifest::is_manifest_path(&path) {
-                // A manifest sitting directly in the plugins dir.
-                if let Ok(plugin) = Self::load_from_path(&path, allow_any_path) {
-                    if !skip.contains(plugin.name()) {
-                        plugins.push(plugin);
+                } else if PluginManifest::is_manifest_path(&path) {
+                    // A manifest sitting directly in the plugins dir.
+                    if let Ok(plugin) = Self::load_from_path(&path, allow_any_path) {
+                        if !skip.contains(plugin.name()) {
+                            plugins.push(plugin);
+                        }
                     }
                 }
             }
@@ -728,8 +730,18 @@ pub(crate) fn plugin_allow_any_path(config: Option<&MetaConfig>) -> bool {
     )
 }
 
+/// The extra plugin directories listed in the user config. Workspace configs
+/// cannot add to these, so cloning a workspace never makes meta discover and
+/// run binaries it ships.
+fn user_plugin_dirs() -> Vec<PathBuf> {
+    metarepo_core::user_config_path()
+        .and_then(|path| MetaConfig::load_user_config(&path).ok())
+        .map(|user| user.plugin_dirs.iter().map(|d| expand_tilde(d)).collect())
+        .unwrap_or_default()
+}
+
 /// Expand a leading `~/` to the user's home directory.
-fn expand_tilde(path: &str) -> PathBuf {
+pub(crate) fn expand_tilde(path: &str) -> PathBuf {
     if let Some(rest) = path.strip_prefix("~/") {
         if let Ok(home) = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
             return PathBuf::from(home).join(rest);
@@ -3,7 +3,7 @@ use clap::ArgMatches;
 use colored::Colorize;
 use metarepo_core::{
     arg, command, plugin, BasePlugin, ConfigSetting, ConfigValueType, MetaConfig, MetaPlugin,
-    PluginManifest, RuntimeConfig,
+    PluginConflict, PluginManifest, RuntimeConfig,
 };
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -105,6 +105,16 @@ impl PluginManagerPlugin {
                          other integrity states are only shown when the workspace requires\n\
                          integrity.\n\
                          \n\
+                         Loaded plugins that declare the same top-level command are listed\n\
+                         under Command conflicts with the plugin that kept it: built-in\n\
+                         plugins always do, and between external plugins the one named in\n\
+                         plugin_priority in .meta, else the first loaded. The others run as\n\
+                         meta ext <plugin> <command>.\n\
+                         \n\
+                         Built-in plugins left out with disabled_plugins or replaced with\n\
+                         override (in .meta or the user config) are listed under Built-in\n\
+                         plugins.\n\
+                         \n\
                          Examples:\n\
                          \n\
                            meta plugin list\n\
@@ -275,6 +285,67 @@ fn handle_install(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
 }
 
 fn handle_list(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    list_registered(config)?;
+    print_replaced_builtins(&config.meta_config);
+    print_conflicts(&config.plugin_conflicts);
+    Ok(())
+}
+
+/// Print the built-in plugins the config disables or replaces.
+fn print_replaced_builtins(config: &MetaConfig) {
+    let mut overrides: Vec<(&String, &String)> = config.plugin_overrides.iter().flatten().collect();
+    overrides.sort();
+    if config.disabled_plugins.is_empty() && overrides.is_empty() {
+        return;
+    }
+    println!("\n  {}", "Built-in plugins".bold());
+    for name in &config.disabled_plugins {
+        println!("  {} {}  disabled", "-".bright_black(), name.bold());
+    }
+    for (builtin, plugin) in overrides {
+        println!(
+            "  {} {}  replaced by plugin {}",
+            "→".cyan(),
+            builtin.bold(),
+            plugin
+        );
+    }
+}
+
+/// Print the command conflicts between loaded plugins and how each was
+/// resolved.
+fn print_conflicts(conflicts: &[PluginConflict]) {
+    if conflicts.is_empty() {
+        return;
+    }
+    println!("\n  {}", "Command conflicts".bold());
+    for conflict in conflicts {
+        let kept_by = if conflict.winner_builtin {
+            format!("{} (built-in)", conflict.winner)
+        } else if conflict.pinned {
+            format!("{} (plugin_priority)", conflict.winner)
+        } else {
+            conflict.winner.clone()
+        };
+        let loser = if conflict.namespaced {
+            format!(
+                "plugin {} runs as 'meta ext {} ...'",
+                conflict.loser, conflict.loser
+            )
+        } else {
+            format!("a second plugin named {} was not loaded", conflict.loser)
+        };
+        println!(
+            "  {} {}  kept by {}; {}",
+            "!".yellow(),
+            conflict.command.bold(),
+            kept_by,
+            loser
+        );
+    }
+}
+
+fn list_registered(config: &RuntimeConfig) -> Result<()> {
     let meta_file = match active_meta_file(config) {
         Some(p) => p,
         None => {
@@ -0,0 +1,420 @@
+//! `meta profile` — a Gantt-style timeline of the most recent multi-project
+//! run (`meta exec`, `meta run`, `meta start`, `meta dev`), built from the
+//! start times and durations saved in the run summary, with the critical path
+//! — the chain of projects that determined the run's wall time — highlighted.
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::plugins::shared::outcome::{format_duration, LAST_RUN_FILE};
+use crate::plugins::shared::{state, LastRun};
+
+pub use self::plugin::ProfilePlugin;
+
+mod plugin;
+
+/// Width of the ASCII timeline, in columns.
+const TIMELINE_WIDTH: usize = 50;
+
+/// Slack allowed between one project finishing and the next one on the
+/// critical path starting, for scheduling and process start-up overhead.
+const HANDOFF_SLACK_MS: u64 = 25;
+
+/// One project's bar on the timeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub project: String,
+    /// Milliseconds after the run began.
+    pub start_ms: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// Whether the project is on the critical path.
+    pub critical: bool,
+}
+
+impl Span {
+    pub fn end_ms(&self) -> u64 {
+        self.start_ms + self.duration_ms
+    }
+}
+
+/// The timeline of one run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Timeline {
+    /// The command line after `meta`.
+    pub command: String,
+    /// Wall time of the whole run.
+    pub total_ms: u64,
+    /// Projects in start order.
+    pub spans: Vec<Span>,
+    /// The critical path, first project first.
+    pub critical_path: Vec<String>,
+}
+
+impl Timeline {
+    /// Build the timeline for a saved run summary. Fails for summaries saved
+    /// before start times were recorded.
+    pub fn from_last_run(last: &LastRun) -> Result<Self> {
+        let mut spans = Vec::new();
+        for project in &last.projects {
+            let start_ms = project.start_ms.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "The last run has no timing data. Run the command again to profile it."
+                )
+            })?;
+            spans.push(Span {
+                project: project.project.clone(),
+                start_ms,
+                duration_ms: project.duration_ms,
+                success: project.success,
+                critical: false,
+            });
+        }
+        spans.sort_by(|a, b| (a.start_ms, &a.project).cmp(&(b.start_ms, &b.project)));
+
+        let path = critical_path(&spans);
+        for &i in &path {
+            spans[i].critical = true;
+        }
+        let total_ms = spans
+            .iter()
+            .map(Span::end_ms)
+            .max()
+            .unwrap_or(0)
+            .max(last.duration_ms);
+        Ok(Self {
+            command: last.args.join(" "),
+            total_ms,
+            critical_path: path.iter().map(|&i| spans[i].project.clone()).collect(),
+            spans,
+        })
+    }
+
+    /// Time spent in all projects together, which exceeds the wall time when
+    /// they ran in parallel.
+    pub fn work_ms(&self) -> u64 {
+        self.spans.iter().map(|s| s.duration_ms).sum()
+    }
+}
+
+/// Indices of the spans on the critical path, first to last. It ends at the
+/// project that finished last and walks back through the project each one
+/// waited on: the latest to finish before it started, having itself started
+/// earlier. Projects that never ran are not on it.
+fn critical_path(spans: &[Span]) -> Vec<usize> {
+    let ran = |s: &&Span| s.duration_ms > 0;
+    let Some(mut current) = spans
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| ran(s))
+        .max_by_key(|(_, s)| (s.end_ms(), s.duration_ms))
+        .map(|(i, _)| i)
+    else {
+        return Vec::new();
+    };
+
+    let mut path = vec![current];
+    loop {
+        let cur = &spans[current];
+        let previous = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| ran(s))
+            .filter(|(_, s)| {
+                s.start_ms + HANDOFF_SLACK_MS < cur.start_ms
+                    && s.end_ms() <= cur.start_ms + HANDOFF_SLACK_MS
+            })
+            .max_by_key(|(_, s)| s.end_ms())
+            .map(|(i, _)| i);
+        match previous {
+            Some(i) => {
+                path.push(i);
+                current = i;
+            }
+            None => break,
+        }
+    }
+    path.reverse();
+    path
+}
+
+/// Column offset and length of a span's bar on a timeline `width` columns
+/// wide. Every project that ran gets at least one column.
+fn bar_extent(span: &Span, total_ms: u64, width: usize) -> (usize, usize) {
+    if total_ms == 0 {
+        return (0, 0);
+    }
+    let scale = |ms: u64| (ms as u128 * width as u128 / total_ms as u128) as usize;
+    let offset = scale(span.start_ms).min(width.saturating_sub(1));
+    let mut len = scale(span.end_ms()).saturating_sub(offset);
+    if span.duration_ms > 0 {
+        len = len.max(1);
+    }
+    (offset, len.min(width - offset))
+}
+
+/// How `meta profile` presents the timeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// The ASCII timeline.
+    Text,
+    /// The timeline as JSON.
+    Json,
+    /// A standalone HTML page written to the given file.
+    Html(std::path::PathBuf),
+}
+
+/// Profile the last run of the workspace at `base_path`.
+pub fn show_profile(base_path: &Path, format: ProfileFormat) -> Result<()> {
+    if !state::state_path(base_path, LAST_RUN_FILE).exists() {
+        return Err(anyhow::anyhow!(
+            "No run recorded yet. `meta exec` and `meta run` save their timings when they finish."
+        ));
+    }
+    let last: LastRun = state::load(base_path, LAST_RUN_FILE)?;
+    let timeline = Timeline::from_last_run(&last)?;
+    match format {
+        ProfileFormat::Text => print_timeline(&timeline),
+        ProfileFormat::Json => println!("{}", serde_json::to_string_pretty(&timeline)?),
+        ProfileFormat::Html(path) => {
+            std::fs::write(&path, render_html(&timeline))?;
+            println!("{} Wrote {}", "✓".green(), path.display());
+        }
+    }
+    Ok(())
+}
+
+fn print_timeline(timeline: &Timeline) {
+    println!(
+        "\n  {} {} {}",
+        "⏱".cyan(),
+        "Profile:".bold(),
+        format!("meta {}", timeline.command).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+
+    let name_width = timeline
+        .spans
+        .iter()
+        .map(|s| s.project.len())
+        .max()
+        .unwrap_or(0);
+    for span in &timeline.spans {
+        let (offset, len) = bar_extent(span, timeline.total_ms, TIMELINE_WIDTH);
+        let bar = "█".repeat(len);
+        let bar = if !span.success {
+            bar.red()
+        } else if span.critical {
+            bar.yellow()
+        } else {
+            bar.blue()
+        };
+        let mark = if span.success {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        println!(
+            "  {} {:<name_width$} {}{}{}{} {}",
+            mark,
+            span.project,
+            "│".bright_black(),
+            " ".repeat(offset),
+            bar,
+            " ".repeat(TIMELINE_WIDTH - offset - len),
+            format_duration(span.duration_ms).bright_black(),
+            name_width = name_width
+        );
+    }
+    let end_label = format_duration(timeline.total_ms);
+    println!(
+        "    {:<name_width$} {}{:>width$}",
+        "",
+        "0".bright_black(),
+        end_label.bright_black(),
+        name_width = name_width,
+        width = TIMELINE_WIDTH
+    );
+
+    println!("\n  {}", "─".repeat(60).bright_black());
+    let critical_ms: u64 = timeline
+        .spans
+        .iter()
+        .filter(|s| s.critical)
+        .map(|s| s.duration_ms)
+        .sum();
+    println!(
+        "  {} {} {}",
+        "Critical path:".yellow(),
+        timeline.critical_path.join(" → "),
+        format!(
+            "({} of {} wall time)",
+            format_duration(critical_ms),
+            format_duration(timeline.total_ms)
+        )
+        .bright_black()
+    );
+    let parallelism = if timeline.total_ms > 0 {
+        timeline.work_ms() as f64 / timeline.total_ms as f64
+    } else {
+        1.0
+    };
+    println!(
+        "  {} {} project(s), {} of work in {} ({:.1}× parallelism)",
+        "Summary:".bright_black(),
+        timeline.spans.len(),
+        format_duration(timeline.work_ms()),
+        format_duration(timeline.total_ms),
+        parallelism
+    );
+    println!();
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A standalone HTML page showing the timeline.
+pub fn render_html(timeline: &Timeline) -> String {
+    let total = timeline.total_ms.max(1) as f64;
+    let mut rows = String::new();
+    for span in &timeline.spans {
+        let class = if !span.success {
+            "failed"
+        } else if span.critical {
+            "critical"
+        } else {
+            "normal"
+        };
+        rows.push_str(&format!(
+            "<div class=\"row\"><span class=\"name\">{name}</span>\
+             <span class=\"track\"><span class=\"bar {class}\" \
+             style=\"left:{left:.3}%;width:{width:.3}%\" title=\"{name}: {duration}\">\
+             </span></span><span class=\"time\">{duration}</span></div>\n",
+            name = escape_html(&span.project),
+            class = class,
+            left = span.start_ms as f64 * 100.0 / total,
+            width = (span.duration_ms as f64 * 100.0 / total).max(0.2),
+            duration = format_duration(span.duration_ms),
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>meta profile: meta {command}</title>\n<style>\n\
+         body {{ font-family: system-ui, sans-serif; margin: 2em; }}\n\
+         .row {{ display: flex; align-items: center; margin: 2px 0; }}\n\
+         .name {{ width: 16em; font-family: monospace; overflow: hidden; }}\n\
+         .track {{ position: relative; flex: 1; height: 1.2em; background: #f3f3f3; }}\n\
+         .bar {{ position: absolute; top: 0; bottom: 0; border-radius: 2px; }}\n\
+         .normal {{ background: #5b8def; }}\n\
+         .critical {{ background: #f0a500; }}\n\
+         .failed {{ background: #d9534f; }}\n\
+         .time {{ width: 6em; text-align: right; color: #666; font-family: monospace; }}\n\
+         </style>\n</head>\n<body>\n\
+         <h1>meta {command}</h1>\n\
+         <p>Wall time {total}. Critical path: {path}.</p>\n\
+         {rows}</body>\n</html>\n",
+        command = escape_html(&timeline.command),
+        total = format_duration(timeline.total_ms),
+        path = escape_html(&timeline.critical_path.join(" → ")),
+        rows = rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::shared::outcome::ProjectResult;
+
+    fn result(project: &str, start_ms: u64, duration_ms: u64) -> ProjectResult {
+        ProjectResult {
+            project: project.to_string(),
+            success: true,
+            exit_code: Some(0),
+            duration_ms,
+            start_ms: Some(start_ms),
+        }
+    }
+
+    fn run(projects: Vec<ProjectResult>) -> LastRun {
+        LastRun {
+            command: "exec".to_string(),
+            args: vec!["exec".to_string(), "make".to_string()],
+            projects,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sequential_runs_are_all_critical() {
+        let timeline = Timeline::from_last_run(&run(vec![
+            result("web", 1_002, 3_000),
+            result("api", 0, 1_000),
+            result("docs", 4_004, 500),
+        ]))
+        .unwrap();
+        assert_eq!(timeline.critical_path, ["api", "web", "docs"]);
+        assert_eq!(timeline.total_ms, 4_504);
+        assert_eq!(timeline.spans[0].project, "api");
+    }
+
+    #[test]
+    fn parallel_runs_follow_the_longest_chain() {
+        // api and docs run together; web starts once api is done.
+        let timeline = Timeline::from_last_run(&run(vec![
+            result("api", 0, 2_000),
+            result("docs", 1, 500),
+            result("web", 2_010, 1_000),
+            result("cli", 5, 2_900),
+        ]))
+        .unwrap();
+        assert_eq!(timeline.critical_path, ["api", "web"]);
+        assert_eq!(timeline.work_ms(), 6_400);
+
+        let mut skipped = result("gone", 0, 0);
+        skipped.success = false;
+        let parallel = Timeline::from_last_run(&run(vec![
+            result("a", 0, 900),
+            result("b", 3, 100),
+            skipped,
+        ]))
+        .unwrap();
+        assert_eq!(parallel.critical_path, ["a"]);
+    }
+
+    #[test]
+    fn runs_without_start_times_cannot_be_profiled() {
+        let mut old = result("api", 0, 10);
+        old.start_ms = None;
+        assert!(Timeline::from_last_run(&run(vec![old])).is_err());
+    }
+
+    #[test]
+    fn bars_scale_to_the_timeline() {
+        let span = |start_ms, duration_ms| Span {
+            project: "p".to_string(),
+            start_ms,
+            duration_ms,
+            success: true,
+            critical: false,
+        };
+        assert_eq!(bar_extent(&span(0, 1_000), 2_000, 50), (0, 25));
+        assert_eq!(bar_extent(&span(1_000, 1_000), 2_000, 50), (25, 25));
+        // Short projects still show up.
+        assert_eq!(bar_extent(&span(1_990, 1), 2_000, 50), (49, 1));
+        assert_eq!(bar_extent(&span(0, 0), 2_000, 50), (0, 0));
+    }
+
+    #[test]
+    fn html_escapes_project_names() {
+        let timeline = Timeline::from_last_run(&run(vec![result("<web>", 0, 10)])).unwrap();
+        let html = render_html(&timeline);
+        assert!(html.contains("&lt;web&gt;"));
+        assert!(html.contains("class=\"bar critical\""));
+    }
+}
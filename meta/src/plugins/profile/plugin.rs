@@ -0,0 +1,95 @@
+//! Plugin wiring for `meta profile`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+use std::path::PathBuf;
+
+use super::{show_profile, ProfileFormat};
+
+/// Registers the top-level `meta profile` command.
+pub struct ProfilePlugin;
+
+impl ProfilePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ProfilePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for ProfilePlugin {
+    fn name(&self) -> &str {
+        "profile"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("profile")
+                .about("Show a timeline of the last multi-project run")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Show where the time went in the most recent `meta exec`, `meta run`,\n\
+                     `meta start`, or `meta dev`: a Gantt-style timeline with one bar per\n\
+                     project, placed by when it started and sized by how long it ran.\n\
+                     \n\
+                     The critical path is highlighted: the chain of projects that set the\n\
+                     run's wall time, ending at the project that finished last. In a\n\
+                     sequential run that is every project; in a parallel run it is usually\n\
+                     the slowest one. Speeding up anything else does not shorten the run.\n\
+                     \n\
+                     Timings come from last-run.json in the workspace state directory\n\
+                     (.meta-state), saved whenever one of those commands finishes. Pass\n\
+                     --html <file> for a standalone page to share, or --json for the\n\
+                     timeline data.\n\
+                     \n\
+                     Examples:\n  \
+                       meta exec --parallel cargo build && meta profile\n  \
+                       meta profile --html profile.html\n",
+                ))
+                .arg(
+                    Arg::new("html")
+                        .long("html")
+                        .value_name("FILE")
+                        .help("Write the timeline as a standalone HTML page")
+                        .conflicts_with("json"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the timeline as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let meta_root = config.require_meta_root()?;
+        let format = if let Some(path) = matches.get_one::<String>("html") {
+            ProfileFormat::Html(PathBuf::from(path))
+        } else if matches.get_flag("json") {
+            ProfileFormat::Json
+        } else {
+            ProfileFormat::Text
+        };
+        show_profile(&meta_root, format)
+    }
+}
+
+impl BasePlugin for ProfilePlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Show a timeline of the last multi-project run")
+    }
+}
@@ -251,12 +251,19 @@ pub fn convert_to_bare(project_name: &str, base_path: &Path) -> Result<()> {
         ProjectEntry::Metadata(ProjectMetadata {
             url: project_url,
             aliases: Vec::new(),
+            tags: Vec::new(),
             scripts: HashMap::new(),
             env: HashMap::new(),
             worktree_init: None,
             bare: Some(true),
+            default_script: None,
             enabled: None,
             depth: None,
+            pinned: None,
+            vendored: None,
+            visibility: None,
+            vcs: None,
+            deprecated: None,
         }),
     );
 
@@ -0,0 +1,396 @@
+//! `meta project doctor`: per-project health checks, with fixes for the
+//! problems meta can repair itself.
+//!
+//! Where `meta project check` looks at the workspace as a whole, the doctor
+//! looks inside each project: its directory (or symlink target) exists, it is
+//! a git repository whose `origin` matches the configured URL, the workspace
+//! `.gitignore` lists it, the worktrees git knows about are all on disk (and a
+//! bare project has one checked out), no checkout sits on a detached HEAD,
+//! and a deprecated project has not outlived its `remove_after` date.
+
+use super::{ignored_lines, locate_workspace_config};
+use crate::plugins::shared::{create_default_worktree, git, gitignore, state};
+use crate::plugins::worktree::list_worktrees;
+use anyhow::Result;
+use colored::*;
+use git2::Repository;
+use metarepo_core::{canonical_repo_url, MetaConfig};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A repair the doctor can make.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Fix {
+    /// Add the project to the managed `.gitignore` block.
+    Ignore,
+    /// Point `origin` at the configured URL, adding the remote if missing.
+    SetOrigin { url: String, add: bool },
+    /// Drop git's records of worktrees whose directories are gone.
+    PruneWorktrees,
+    /// Check out the default branch worktree of a bare project.
+    DefaultWorktree,
+}
+
+/// One problem found in a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    problem: String,
+    fix: Option<Fix>,
+    /// What to do about it, for problems without a fix.
+    hint: Option<String>,
+}
+
+impl Finding {
+    fn fixable(problem: impl Into<String>, fix: Fix) -> Self {
+        Self {
+            problem: problem.into(),
+            fix: Some(fix),
+            hint: None,
+        }
+    }
+
+    fn manual(problem: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            problem: problem.into(),
+            fix: None,
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Run every check on `name`. Checks that need the directory or the
+/// repository stop at the first one that is missing.
+fn diagnose(
+    base_path: &Path,
+    config: &MetaConfig,
+    ignored: &HashSet<String>,
+    name: &str,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let path = base_path.join(name);
+    let url = config.get_project_url(name).unwrap_or_default();
+    let is_local = url.is_empty() || url.starts_with("local:");
+    let vendored = config.is_vendored(name);
+
+    if let Some(deprecation) = config.get_project_deprecation(name) {
+        if deprecation.is_overdue(&state::today()) {
+            findings.push(Finding::manual(
+                format!(
+                    "deprecated and past its remove_after date ({})",
+                    deprecation.remove_after.as_deref().unwrap_or_default()
+                ),
+                "remove it with `meta project remove`, or extend remove_after",
+            ));
+        }
+    }
+
+    if !is_local && !vendored && !ignored.contains(name) {
+        findings.push(Finding::fixable(
+            ".gitignore has no entry for it",
+            Fix::Ignore,
+        ));
+    }
+
+    let is_symlink = path
+        .symlink_metadata()
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false);
+    if is_symlink && !path.exists() {
+        let target = std::fs::read_link(&path)
+            .map(|t| t.display().to_string())
+            .unwrap_or_default();
+        findings.push(Finding::manual(
+            format!("symlink points to {}, which does not exist", target),
+            "restore the target, or remove the link and re-add the project",
+        ));
+        return findings;
+    }
+    if !path.exists() {
+        findings.push(Finding::manual(
+            "directory is missing",
+            "run `meta git update` to clone it",
+        ));
+        return findings;
+    }
+    // Vendored files live in the workspace repository itself.
+    if vendored {
+        return findings;
+    }
+
+    let git_dir = path.join(".git");
+    if !git_dir.exists() {
+        if !is_local {
+            findings.push(Finding::manual(
+                "not a git repository",
+                "move the directory aside and run `meta git update`",
+            ));
+        }
+        return findings;
+    }
+    let bare = config.is_bare_repo(name);
+    let repo = match Repository::open(if bare { &git_dir } else { &path }) {
+        Ok(repo) => repo,
+        Err(e) => {
+            findings.push(Finding::manual(
+                format!("git repository cannot be opened: {}", e.message()),
+                "check .git for damage, or re-clone the project",
+            ));
+            return findings;
+        }
+    };
+
+    if !is_local {
+        let expected = url.strip_prefix("external:").unwrap_or(&url);
+        let origin = repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().ok().map(String::from));
+        match origin {
+            None => findings.push(Finding::fixable(
+                "no origin remote",
+                Fix::SetOrigin {
+                    url: expected.to_string(),
+                    add: true,
+                },
+            )),
+            Some(origin) if canonical_repo_url(&origin) != canonical_repo_url(expected) => findings
+                .push(Finding::fixable(
+                    format!("origin is {}, but the config says {}", origin, expected),
+                    Fix::SetOrigin {
+                        url: expected.to_string(),
+                        add: false,
+                    },
+                )),
+            Some(_) => {}
+        }
+    }
+
+    if bare && !repo.is_bare() {
+        findings.push(Finding::manual(
+            "configured as bare, but .git is a regular repository",
+            format!("run `meta project convert-to-bare {}`", name),
+        ));
+    } else if !bare && repo.is_bare() {
+        findings.push(Finding::manual(
+            ".git is a bare repository, but the project is not configured as bare",
+            "set \"bare\": true for the project in the config",
+        ));
+    }
+
+    let worktrees = list_worktrees(&path).unwrap_or_default();
+    let missing: Vec<String> = worktrees
+        .iter()
+        .filter(|wt| !wt.is_bare && !wt.path.exists())
+        .map(|wt| wt.path.display().to_string())
+        .collect();
+    if !missing.is_empty() {
+        findings.push(Finding::fixable(
+            format!("worktree(s) missing on disk: {}", missing.join(", ")),
+            Fix::PruneWorktrees,
+        ));
+    }
+    if bare && repo.is_bare() && !worktrees.iter().any(|wt| !wt.is_bare && wt.path.exists()) {
+        findings.push(Finding::fixable(
+            "no worktree is checked out",
+            Fix::DefaultWorktree,
+        ));
+    }
+
+    // A pinned project is detached on purpose.
+    if config.get_project_pin(name).is_none() {
+        for wt in &worktrees {
+            if wt.is_detached && wt.path.exists() {
+                let at = if wt.path == path {
+                    "HEAD is detached".to_string()
+                } else {
+                    format!("worktree {} has a detached HEAD", wt.path.display())
+                };
+                findings.push(Finding::manual(at, "check out a branch with `git switch`"));
+            }
+        }
+    }
+
+    findings
+}
+
+/// Apply `fix` to project `name`.
+fn apply(fix: &Fix, base_path: &Path, name: &str) -> Result<()> {
+    let path = base_path.join(name);
+    match fix {
+        Fix::Ignore => {
+            gitignore::add_managed_entry(base_path, name)?;
+        }
+        Fix::SetOrigin { url, add } => {
+            let action = if *add { "add" } else { "set-url" };
+            git(&path, &["remote", action, "origin", url])?;
+        }
+        Fix::PruneWorktrees => {
+            git(&path, &["worktree", "prune"])?;
+        }
+        Fix::DefaultWorktree => create_default_worktree(&path.join(".git"), &path)?,
+    }
+    Ok(())
+}
+
+/// Check each of `projects`, print what is wrong, and with `fix` repair what
+/// can be repaired. Fails when problems remain, so it works as a CI gate.
+pub fn doctor_projects(base_path: &Path, projects: &[String], fix: bool) -> Result<()> {
+    let config = MetaConfig::load_from_file(locate_workspace_config(base_path)?)?;
+    let ignored = ignored_lines(base_path)?;
+
+    println!("\n  {} {}", "🩺".cyan(), "Project doctor".bold());
+    println!("  {}", "═".repeat(60).bright_black());
+
+    let (mut found, mut fixable, mut fixed) = (0usize, 0usize, 0usize);
+    for name in projects {
+        let findings = diagnose(base_path, &config, &ignored, name);
+        if findings.is_empty() {
+            println!("  {} {}", "✓".green(), name);
+            continue;
+        }
+        println!("  {} {}", "!".yellow(), name.bold());
+        for finding in &findings {
+            found += 1;
+            match (&finding.fix, fix) {
+                (Some(repair), true) => match apply(repair, base_path, name) {
+                    Ok(()) => {
+                        fixed += 1;
+                        println!("      {} fixed: {}", "✓".green(), finding.problem);
+                    }
+                    Err(e) => println!(
+                        "      {} {} (fix failed: {})",
+                        "✗".red(),
+                        finding.problem,
+                        e
+                    ),
+                },
+                (Some(_), false) => {
+                    fixable += 1;
+                    println!(
+                        "      {} {} {}",
+                        "·".yellow(),
+                        finding.problem,
+                        "(fixable)".bright_black()
+                    );
+                }
+                (None, _) => {
+                    println!("      {} {}", "·".yellow(), finding.problem);
+                    if let Some(hint) = &finding.hint {
+                        println!("        {} {}", "→".bright_black(), hint.bright_black());
+                    }
+                }
+            }
+        }
+    }
+
+    println!("  {}", "─".repeat(60).bright_black());
+    let remaining = found - fixed;
+    if found == 0 {
+        println!(
+            "  {} All {} project(s) are healthy.",
+            "✓".green(),
+            projects.len()
+        );
+        return Ok(());
+    }
+    if fix {
+        println!(
+            "  {} Fixed {} issue(s); {} remaining.",
+            "·".bright_black(),
+            fixed,
+            remaining
+        );
+    } else {
+        println!(
+            "  {} {} issue(s) found, {} fixable. Run with --fix to repair them.",
+            "·".bright_black(),
+            found,
+            fixable
+        );
+    }
+    if remaining == 0 {
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "project doctor found {} issue(s)",
+        remaining
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::{Deprecation, ProjectEntry};
+
+    fn config(projects: &[(&str, &str)]) -> MetaConfig {
+        let mut config = MetaConfig::default();
+        for (name, url) in projects {
+            config
+                .projects
+                .insert(name.to_string(), ProjectEntry::Url(url.to_string()));
+        }
+        config
+    }
+
+    #[test]
+    fn reports_missing_directories_and_ignore_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config(&[
+            ("web", "https://example.com/web.git"),
+            ("docs", "local:docs"),
+        ]);
+        let findings = diagnose(tmp.path(), &config, &HashSet::new(), "web");
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].fix, Some(Fix::Ignore));
+        assert_eq!(findings[1].problem, "directory is missing");
+
+        // A plain local directory without git is fine.
+        std::fs::create_dir(tmp.path().join("docs")).unwrap();
+        assert!(diagnose(tmp.path(), &config, &HashSet::new(), "docs").is_empty());
+    }
+
+    #[test]
+    fn flags_deprecated_projects_past_remove_after() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("old")).unwrap();
+        let mut config = config(&[("old", "local:old")]);
+        config.project_metadata_mut("old").unwrap().deprecated = Some(Deprecation {
+            remove_after: Some("2000-01-01".to_string()),
+            ..Default::default()
+        });
+        let findings = diagnose(tmp.path(), &config, &HashSet::new(), "old");
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].problem.contains("2000-01-01"));
+
+        config.project_metadata_mut("old").unwrap().deprecated = Some(Deprecation {
+            remove_after: Some("9999-12-31".to_string()),
+            ..Default::default()
+        });
+        assert!(diagnose(tmp.path(), &config, &HashSet::new(), "old").is_empty());
+    }
+
+    #[test]
+    fn origin_mismatch_is_fixed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let repo = Repository::init(root.join("web")).unwrap();
+        repo.remote("origin", "git@github.com:acme/old.git")
+            .unwrap();
+        std::fs::write(
+            root.join(".metarepo"),
+            r#"{"projects":{"web":"https://github.com/acme/web.git"}}"#,
+        )
+        .unwrap();
+        let projects = vec!["web".to_string()];
+
+        assert!(doctor_projects(root, &projects, false).is_err());
+        // An unborn branch is not detached, so both problems are fixable.
+        doctor_projects(root, &projects, true).unwrap();
+        let repo = Repository::open(root.join("web")).unwrap();
+        assert_eq!(
+            repo.find_remote("origin").unwrap().url().ok(),
+            Some("https://github.com/acme/web.git")
+        );
+        assert!(doctor_projects(root, &projects, false).is_ok());
+    }
+}
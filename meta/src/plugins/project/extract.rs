@@ -0,0 +1,248 @@
+//! `meta project extract <path> --to <url>`: graduate a directory into its own
+//! repository.
+//!
+//! The directory's history is split out with `git subtree split`, pushed to
+//! the new repository, and registered as a project. A directory of the meta
+//! repository becomes a project in place: the workspace stops tracking it and
+//! a clone of the new repository takes over the same files. A directory
+//! inside a project is cloned as a new top-level project instead, and the
+//! original is left for you to remove from its project.
+
+use super::locate_workspace_config;
+use super::vendor::attach_clone;
+use crate::plugins::shared::{clone_with_auth, git, gitignore};
+use anyhow::Result;
+use colored::*;
+use metarepo_core::{MetaConfig, ProjectEntry};
+use std::path::{Path, PathBuf};
+
+/// Split `dir` into a new repository at `url` (pushed as `branch`) and register
+/// it as a project (see the module docs). `name` overrides the project name
+/// for directories inside a project.
+pub fn extract_project(
+    dir: &Path,
+    url: &str,
+    name: Option<&str>,
+    branch: &str,
+    base_path: &Path,
+) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+
+    let base = base_path.canonicalize()?;
+    let dir = dir
+        .canonicalize()
+        .map_err(|_| anyhow::anyhow!("Directory '{}' not found", dir.display()))?;
+    if !dir.is_dir() || !dir.starts_with(&base) || dir == base {
+        return Err(anyhow::anyhow!(
+            "'{}' must be a directory inside the workspace",
+            dir.display()
+        ));
+    }
+    let repo_root = PathBuf::from(git(&dir, &["rev-parse", "--show-toplevel"])?).canonicalize()?;
+    let prefix = git(&dir, &["rev-parse", "--show-prefix"])?
+        .trim_end_matches('/')
+        .to_string();
+    if prefix.is_empty() {
+        return Err(anyhow::anyhow!(
+            "'{}' is already the root of a repository",
+            dir.display()
+        ));
+    }
+    if !git(&dir, &["status", "--porcelain", "--", "."])?.is_empty() {
+        return Err(anyhow::anyhow!(
+            "'{}' has uncommitted changes. Please commit or stash them first.",
+            prefix
+        ));
+    }
+
+    // In the meta repo the project keeps the directory's path; inside a
+    // project it is cloned at the top level under its own name.
+    let in_place = repo_root == base;
+    let project_name = if in_place {
+        if name.is_some_and(|n| n != prefix) {
+            return Err(anyhow::anyhow!(
+                "A directory of the meta repository is extracted in place as '{}'; --name does not apply",
+                prefix
+            ));
+        }
+        prefix.clone()
+    } else {
+        name.map(str::to_string).unwrap_or_else(|| {
+            dir.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| prefix.clone())
+        })
+    };
+    if config.projects.contains_key(&project_name) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' already exists in workspace config",
+            project_name
+        ));
+    }
+    if !in_place && base.join(&project_name).exists() {
+        return Err(anyhow::anyhow!(
+            "Directory '{}' already exists",
+            project_name
+        ));
+    }
+
+    println!(
+        "\n  {} {}",
+        "✂️".cyan(),
+        format!("Extracting '{}' into {}", prefix, url).bold()
+    );
+    let commit = git(&repo_root, &["subtree", "split", "-q", "--prefix", &prefix])
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "git subtree split failed (is git-subtree installed?): {}",
+                e
+            )
+        })?
+        .lines()
+        .last()
+        .unwrap_or_default()
+        .to_string();
+    println!(
+        "     {} {}",
+        "│".bright_black(),
+        format!("Split {} into {}", prefix, &commit[..commit.len().min(12)]).bright_black()
+    );
+    git(
+        &repo_root,
+        &[
+            "push",
+            "--quiet",
+            url,
+            &format!("{}:refs/heads/{}", commit, branch),
+        ],
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to push to {}: {}", url, e))?;
+    println!(
+        "     {} {}",
+        "│".bright_black(),
+        format!("Pushed to {} ({})", url, branch).bright_black()
+    );
+
+    if in_place {
+        git(&base, &["rm", "-r", "--cached", "--quiet", "--", &prefix])?;
+        attach_clone(url, &dir)?;
+        // Point the clone at the pushed branch without touching the files,
+        // which already match it.
+        git(
+            &dir,
+            &["symbolic-ref", "HEAD", &format!("refs/heads/{}", branch)],
+        )?;
+        git(&dir, &["reset", "--quiet", &commit])?;
+        git(
+            &dir,
+            &[
+                "branch",
+                "--quiet",
+                &format!("--set-upstream-to=origin/{}", branch),
+            ],
+        )?;
+    } else {
+        clone_with_auth(url, &base.join(&project_name), false, None)?;
+    }
+
+    config
+        .projects
+        .insert(project_name.clone(), ProjectEntry::Url(url.to_string()));
+    config.save_to_file(&meta_file_path)?;
+    gitignore::add_managed_entry(base_path, &project_name)?;
+
+    println!(
+        "\n  {} {}",
+        "✅".green(),
+        format!("Registered '{}' as a project", project_name)
+            .bold()
+            .green()
+    );
+    let hint = if in_place {
+        "Commit the workspace to record the removal and the .meta entry".to_string()
+    } else {
+        let project = repo_root
+            .strip_prefix(&base)
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        format!(
+            "The original is still in {}: remove it there with git rm -r {}",
+            project, prefix
+        )
+    };
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        hint.italic().bright_black()
+    );
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn extracts_a_meta_repo_directory_in_place() {
+        // git-subtree ships with most git installs, but not all.
+        let subtree = Command::new("git").args(["subtree", "-h"]).output();
+        if !subtree.is_ok_and(|o| {
+            String::from_utf8_lossy(&o.stdout).contains("split")
+                || String::from_utf8_lossy(&o.stderr).contains("split")
+        }) {
+            return;
+        }
+
+        let tmp = tempfile::tempdir().unwrap();
+        let remote = tmp.path().join("cli.git");
+        std::fs::create_dir(&remote).unwrap();
+        run(&remote, &["init", "-q", "--bare", "-b", "main"]);
+
+        let root = tmp.path().join("workspace");
+        std::fs::create_dir_all(root.join("tools/cli")).unwrap();
+        run(&root, &["init", "-q"]);
+        MetaConfig::default()
+            .save_to_file(root.join(".meta"))
+            .unwrap();
+        std::fs::write(root.join("tools/cli/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("README.md"), "workspace\n").unwrap();
+        run(&root, &["add", "."]);
+        run(&root, &["commit", "-qm", "init"]);
+
+        let url = remote.to_string_lossy().to_string();
+        extract_project(&root.join("tools/cli"), &url, None, "main", &root).unwrap();
+
+        let cli = root.join("tools/cli");
+        assert!(cli.join(".git").is_dir());
+        assert_eq!(
+            git(&cli, &["status", "--porcelain", "-b"]).unwrap(),
+            "## main...origin/main"
+        );
+        assert_eq!(
+            git(&remote, &["ls-tree", "--name-only", "main"]).unwrap(),
+            "main.rs"
+        );
+        assert_eq!(git(&root, &["ls-files", "--cached", "tools"]).unwrap(), "");
+
+        let config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        assert_eq!(config.get_project_url("tools/cli"), Some(url.clone()));
+        let ignore = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert_eq!(gitignore::managed_entries(&ignore), ["tools/cli"]);
+
+        // Already registered: a second extract is refused.
+        assert!(extract_project(&cli, &url, None, "main", &root).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! `meta project group add/remove/list`: edit the `groups` block of `.meta`
+//! (see [`metarepo_core::ProjectGroup`]).
+
+use super::locate_workspace_config;
+use anyhow::Result;
+use colored::*;
+use metarepo_core::{MetaConfig, Table};
+use std::path::Path;
+
+/// What `meta project group add` changes besides membership.
+#[derive(Debug, Default)]
+pub struct GroupSettings<'a> {
+    pub description: Option<&'a str>,
+    pub default_script: Option<&'a str>,
+}
+
+/// Add `members` to group `name`, creating the group if needed, and apply
+/// `settings`. Every member must match at least one project; members already
+/// in the group are skipped.
+pub fn add_to_group(
+    name: &str,
+    members: &[String],
+    settings: GroupSettings,
+    base_path: &Path,
+) -> Result<()> {
+    if name.is_empty() || name.contains(',') || name.contains(char::is_whitespace) {
+        return Err(anyhow::anyhow!(
+            "Invalid group name '{}': use no commas or spaces",
+            name
+        ));
+    }
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+    for member in members {
+        if config.select_projects(&[member]).projects.is_empty() {
+            return Err(anyhow::anyhow!("No projects match '{}'", member));
+        }
+    }
+
+    let groups = config.groups.get_or_insert_with(Default::default);
+    let created = !groups.contains_key(name);
+    let group = groups.entry(name.to_string()).or_default();
+    let mut added = 0;
+    for member in members {
+        if !group.projects.contains(member) {
+            group.projects.push(member.clone());
+            added += 1;
+        }
+    }
+    if let Some(description) = settings.description {
+        group.description = Some(description.to_string()).filter(|d| !d.is_empty());
+    }
+    if let Some(script) = settings.default_script {
+        group.default_script = Some(script.to_string()).filter(|s| !s.is_empty());
+    }
+    config.save_to_file(&meta_file_path)?;
+
+    let verb = if created { "Created" } else { "Updated" };
+    println!(
+        "\n  {} {}",
+        "✅".green(),
+        format!("{} group '{}'", verb, name).bold().green()
+    );
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        format!(
+            "{} member(s) added; the group has {} project(s)",
+            added,
+            member_count(&config, name)
+        )
+        .italic()
+        .bright_black()
+    );
+    println!();
+    Ok(())
+}
+
+/// Remove `members` from group `name`, or the whole group when `members` is
+/// empty.
+pub fn remove_from_group(name: &str, members: &[String], base_path: &Path) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+    config.group(name)?;
+    let groups = config.groups.get_or_insert_with(Default::default);
+
+    let message = if members.is_empty() {
+        groups.remove(name);
+        format!("Removed group '{}'", name)
+    } else {
+        let group = groups.get_mut(name).expect("group checked above");
+        for member in members {
+            if !group.projects.contains(member) {
+                return Err(anyhow::anyhow!(
+                    "'{}' is not a member of group '{}' (members: {})",
+                    member,
+                    name,
+                    group.projects.join(", ")
+                ));
+            }
+        }
+        group.projects.retain(|m| !members.contains(m));
+        format!("Removed {} member(s) from group '{}'", members.len(), name)
+    };
+    if groups.is_empty() {
+        config.groups = None;
+    }
+    config.save_to_file(&meta_file_path)?;
+
+    println!("\n  {} {}", "🗑".red(), message.bold());
+    println!();
+    Ok(())
+}
+
+/// Print the workspace's groups as a table.
+pub fn list_groups(config: &MetaConfig) {
+    let names = config.group_names();
+    if names.is_empty() {
+        println!("No groups defined. Add one with 'meta project group add <name> <projects>...'.");
+        return;
+    }
+    println!("\n  {} {}", "🗂".cyan(), "Project Groups".bold());
+    println!();
+    let mut table = Table::new(["Group", "Projects", "Default script", "Description"])
+        .indent(2)
+        .shrink(1)
+        .shrink(3);
+    for name in &names {
+        let Ok(group) = config.group(name) else {
+            continue;
+        };
+        let projects = config.group_projects(name).unwrap_or_default();
+        table.row([
+            name.bold().to_string(),
+            if projects.is_empty() {
+                "(none)".bright_black().to_string()
+            } else {
+                projects.join(", ")
+            },
+            group.default_script.clone().unwrap_or_default(),
+            group.description.clone().unwrap_or_default(),
+        ]);
+    }
+    table.print();
+    println!();
+}
+
+/// How many projects group `name` resolves to.
+fn member_count(config: &MetaConfig, name: &str) -> usize {
+    config.group_projects(name).map(|p| p.len()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join(".meta"),
+            r#"{"projects": {"api": "local:api", "services/billing": "local:services/billing"}}"#,
+        )
+        .unwrap();
+        tmp
+    }
+
+    fn load(root: &Path) -> MetaConfig {
+        MetaConfig::load_from_file(root.join(".meta")).unwrap()
+    }
+
+    #[test]
+    fn add_creates_and_extends_groups() {
+        let tmp = workspace();
+        let settings = GroupSettings {
+            description: Some("Server side"),
+            default_script: Some("test"),
+        };
+        add_to_group("backend", &["api".to_string()], settings, tmp.path()).unwrap();
+        add_to_group(
+            "backend",
+            &["api".to_string(), "services/*".to_string()],
+            GroupSettings::default(),
+            tmp.path(),
+        )
+        .unwrap();
+
+        let config = load(tmp.path());
+        let group = config.group("backend").unwrap();
+        assert_eq!(group.projects, vec!["api", "services/*"]);
+        assert_eq!(group.description.as_deref(), Some("Server side"));
+        assert_eq!(group.default_script.as_deref(), Some("test"));
+        assert_eq!(
+            config.group_projects("backend").unwrap(),
+            vec!["api", "services/billing"]
+        );
+    }
+
+    #[test]
+    fn add_rejects_members_that_match_nothing_and_bad_names() {
+        let tmp = workspace();
+        let members = ["web".to_string()];
+        assert!(add_to_group("front", &members, GroupSettings::default(), tmp.path()).is_err());
+        assert!(add_to_group("a,b", &[], GroupSettings::default(), tmp.path()).is_err());
+        assert!(load(tmp.path()).groups.is_none());
+    }
+
+    #[test]
+    fn remove_drops_members_then_the_group() {
+        let tmp = workspace();
+        let members = ["api".to_string(), "services/*".to_string()];
+        add_to_group("backend", &members, GroupSettings::default(), tmp.path()).unwrap();
+
+        assert!(remove_from_group("backend", &["web".to_string()], tmp.path()).is_err());
+        remove_from_group("backend", &["api".to_string()], tmp.path()).unwrap();
+        assert_eq!(
+            load(tmp.path()).group("backend").unwrap().projects,
+            vec!["services/*"]
+        );
+
+        remove_from_group("backend", &[], tmp.path()).unwrap();
+        assert!(load(tmp.path()).groups.is_none());
+        assert!(remove_from_group("backend", &[], tmp.path()).is_err());
+    }
+}
@@ -1,14 +1,19 @@
 use anyhow::{Context, Result};
 use colored::*;
-use git2::{Cred, FetchOptions, RemoteCallbacks, Repository, Status, StatusOptions};
-use metarepo_core::{MetaConfig, NestedConfig, ProjectEntry};
-use std::collections::{HashSet, VecDeque};
+use git2::{FetchOptions, Repository, Status, StatusOptions};
+use metarepo_core::{
+    canonical_repo_url, DedupeMode, MetaConfig, NestedConfig, ProjectEntry, Table, Visibility,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 // Import shared git operations
-use crate::plugins::shared::{clone_with_auth, create_default_worktree};
+use crate::plugins::shared::{
+    auth_callbacks, clone_with_auth, create_default_worktree, display_name, gitignore, state,
+    submodules as git_submodules, transfer, vcs,
+};
 
 #[cfg(unix)]
 use std::os::unix::fs;
@@ -18,10 +23,29 @@ use std::os::windows::fs;
 
 // Export the main plugin
 pub use self::convert::convert_to_bare;
+pub use self::doctor::doctor_projects;
+pub use self::extract::extract_project;
+pub use self::groups::{add_to_group, list_groups, remove_from_group, GroupSettings};
+pub use self::pin::{pin_project, unpin_project};
+pub use self::plan::{github_slug, github_token};
 pub use self::plugin::ProjectPlugin;
+pub use self::relocate::move_project;
+pub use self::scaffold::{list_templates, new_project, NewProjectOptions};
+pub use self::submodules::import_submodules;
+pub use self::vendor::{unvendor_project, vendor_project};
 
 mod convert;
+mod doctor;
+mod extract;
+mod groups;
+mod pin;
+mod plan;
 mod plugin;
+mod relocate;
+mod scaffold;
+pub mod sizes;
+mod submodules;
+mod vendor;
 
 /// Locate the workspace config file inside `base_path`, honoring every
 /// supported filename/format (`.meta`, `.metarepo`, `.metarepo.yaml`, ...)
@@ -156,6 +180,10 @@ pub struct ImportContext {
     flatten: bool,
     /// Base path for all imports
     base_path: PathBuf,
+    /// How repeated repositories are handled (`nested.dedupe`)
+    dedupe: DedupeMode,
+    /// Canonical URL -> path of the first clone of that repository
+    clones: HashMap<String, PathBuf>,
 }
 
 impl ImportContext {
@@ -171,6 +199,34 @@ impl ImportContext {
             ignore_nested: config.ignore_nested.iter().cloned().collect(),
             flatten: config.flatten,
             base_path: base_path.to_path_buf(),
+            dedupe: config.dedupe,
+            clones: HashMap::new(),
+        }
+    }
+
+    /// Remember that `url` is checked out at `path`, unless an earlier clone of
+    /// the same repository is already recorded.
+    pub fn record_clone(&mut self, url: &str, path: &Path) {
+        if let Some(key) = canonical_repo_url(url) {
+            self.clones.entry(key).or_insert_with(|| path.to_path_buf());
+        }
+    }
+
+    /// Path of an earlier clone of the same repository as `url`, if any.
+    pub fn existing_clone(&self, url: &str) -> Option<&PathBuf> {
+        canonical_repo_url(url).and_then(|key| self.clones.get(&key))
+    }
+
+    /// Record the workspace's own projects that are already on disk, so nested
+    /// repositories duplicating them are detected too.
+    pub fn seed_from_workspace(&mut self, config: &MetaConfig) {
+        let mut names: Vec<&String> = config.projects.keys().collect();
+        names.sort();
+        for name in names {
+            let path = self.base_path.join(name);
+            if let (Some(url), true) = (config.get_project_url(name), path.exists()) {
+                self.record_clone(&url, &path);
+            }
         }
     }
 
@@ -531,6 +587,7 @@ pub fn import_project_with_options(
                 "Source:".bright_black(),
                 final_repo_url.bright_cyan()
             );
+            sizes::warn_if_large_clone(&final_repo_url, sizes::clone_warn_threshold_kb(&config));
 
             if bare {
                 println!(
@@ -586,12 +643,19 @@ pub fn import_project_with_options(
             ProjectEntry::Metadata(ProjectMetadata {
                 url: final_repo_url.clone(),
                 aliases: Vec::new(),
+                tags: Vec::new(),
                 scripts: std::collections::HashMap::new(),
                 env: std::collections::HashMap::new(),
                 worktree_init: None,
                 bare: if bare { Some(true) } else { None },
+                default_script: None,
                 enabled: None,
                 depth: clone_depth,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
             }),
         );
     } else {
@@ -708,6 +772,10 @@ pub fn import_project_recursive_with_options(
 
     // If recursive import is enabled, process nested repositories
     if nested_config.recursive_import {
+        // Reload so the project just added is known for dedupe as well.
+        let config = MetaConfig::load_from_file(&meta_file_path)?;
+        context.seed_from_workspace(&config);
+
         let project_path_buf = base_path.join(project_path);
         if let Err(e) = process_nested_repositories(&project_path_buf, &mut context, &nested_config)
         {
@@ -723,6 +791,56 @@ pub fn import_project_recursive_with_options(
     Ok(())
 }
 
+/// Print the pre-flight plan for a recursive import of `project_path` without
+/// cloning anything: the tree of nested repositories with their depths, sizes
+/// (from the host API when available), and any cycle or depth-limit issues.
+/// When `source` is omitted, the remote of an existing `project_path` is used.
+pub fn plan_recursive_import(
+    project_path: &str,
+    source: Option<&str>,
+    base_path: &Path,
+    max_depth: Option<usize>,
+    flatten: bool,
+) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let config = MetaConfig::load_from_file(&meta_file_path)?;
+
+    let mut nested_config = config.nested.clone().unwrap_or_default();
+    if let Some(depth) = max_depth {
+        nested_config.max_depth = depth;
+    }
+    nested_config.flatten = flatten;
+
+    let url = match source {
+        Some(s) => s.to_string(),
+        None => Repository::open(base_path.join(project_path))
+            .ok()
+            .and_then(|repo| get_remote_url(&repo).ok().flatten())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "--plan needs a repository URL: 'meta project add {} <url> --plan'",
+                    project_path
+                )
+            })?,
+    };
+
+    let inspector = plan::HostApiInspector::from_env();
+    let tree = plan::build_import_plan(
+        project_path,
+        &url,
+        base_path,
+        &nested_config,
+        Some(&config),
+        &inspector,
+    );
+    plan::print_import_plan(
+        &tree,
+        nested_config.max_depth,
+        sizes::clone_warn_threshold_kb(&config),
+    );
+    Ok(())
+}
+
 /// Process nested repositories in a project
 fn process_nested_repositories(
     project_path: &Path,
@@ -878,6 +996,49 @@ fn process_nested_repositories(
             url.clone()
         };
 
+        // The same repository may appear under different names across nested
+        // configs; share or flag it according to nested.dedupe.
+        if let Some(existing) = context.existing_clone(&actual_url).cloned() {
+            match context.dedupe {
+                DedupeMode::Link => {
+                    if let Err(e) = create_symlink(&existing, &target_path) {
+                        eprintln!(
+                            "     {} {}",
+                            "❌".red(),
+                            format!("Failed to link '{}': {}", name, e).red()
+                        );
+                    } else {
+                        println!(
+                            "     {} {}",
+                            "🔗".blue(),
+                            format!(
+                                "Linked '{}' to existing clone at '{}'",
+                                name,
+                                existing.display()
+                            )
+                            .bright_white()
+                        );
+                    }
+                    context.exit_import();
+                    continue;
+                }
+                DedupeMode::Warn => {
+                    println!(
+                        "     {} {}",
+                        "⚠️".yellow(),
+                        format!(
+                            "'{}' is the same repository as '{}'; consolidate them under one name \
+                             or set nested.dedupe to \"link\" to share a single clone",
+                            name,
+                            existing.display()
+                        )
+                        .yellow()
+                    );
+                }
+                DedupeMode::Off => {}
+            }
+        }
+
         println!(
             "     {} {}",
             "📦".blue(),
@@ -893,6 +1054,7 @@ fn process_nested_repositories(
             context.exit_import();
             continue;
         }
+        context.record_clone(&actual_url, &target_path);
 
         // Recursively process this nested repository if it's also a meta repo
         if nested_config.recursive_import && !context.at_max_depth() {
@@ -942,7 +1104,8 @@ fn create_symlink(target: &Path, link: &Path) -> Result<()> {
     }
 }
 
-fn get_remote_url(repo: &Repository) -> Result<Option<String>> {
+/// The URL of the `origin` remote, else of the first remote that has one.
+pub(crate) fn get_remote_url(repo: &Repository) -> Result<Option<String>> {
     // Try to get the 'origin' remote first, then fallback to first available remote
     let remote_names = repo.remotes()?;
 
@@ -983,8 +1146,9 @@ fn get_remote_url(repo: &Repository) -> Result<Option<String>> {
 /// - **Untracked repository** (report only): a top-level git repository on disk
 ///   that is not tracked in the config. Suggests `meta project add`.
 ///
-/// Stale `.gitignore` lines are intentionally not auto-removed: without
-/// provenance we cannot tell a former project entry from a hand-added ignore.
+/// Stale `.gitignore` lines are intentionally not auto-removed here: lines
+/// outside the managed block cannot be told apart from hand-added ignores, and
+/// the block itself is rebuilt by `meta gitignore sync`.
 /// If `name` is currently tracked as a `local:` project but its on-disk
 /// directory is a git repository with a configured remote, return that remote
 /// URL. This is the signal that a local project is ready to be promoted to an
@@ -998,21 +1162,45 @@ fn detect_promotable_remote(base_path: &Path, name: &str) -> Option<String> {
     get_remote_url(&repo).ok().flatten()
 }
 
-pub fn check_workspace(base_path: &Path, fix: bool) -> Result<()> {
-    let meta_file_path = locate_workspace_config(base_path)?;
-    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+/// Drift found between a workspace config and its tree, one list per check
+/// run by [`check_workspace`]. Each list is sorted.
+#[derive(Debug, Default)]
+pub struct WorkspaceDrift {
+    /// Remote-backed projects missing from `.gitignore`.
+    pub missing_ignore: Vec<String>,
+    /// Projects whose directory does not exist.
+    pub missing_dirs: Vec<String>,
+    /// Top-level git repositories not tracked in the config.
+    pub untracked: Vec<String>,
+    /// `local:` projects whose repository now has a remote, with that URL.
+    pub promotable: Vec<(String, String)>,
+}
+
+impl WorkspaceDrift {
+    pub fn total(&self) -> usize {
+        self.missing_ignore.len()
+            + self.missing_dirs.len()
+            + self.untracked.len()
+            + self.promotable.len()
+    }
+}
 
-    // Current .gitignore lines, trimmed, for membership checks.
+/// Current `.gitignore` lines, trimmed, for membership checks.
+fn ignored_lines(base_path: &Path) -> Result<HashSet<String>> {
     let gitignore_path = base_path.join(".gitignore");
-    let ignored: HashSet<String> = if gitignore_path.exists() {
-        std::fs::read_to_string(&gitignore_path)?
-            .lines()
-            .map(|l| l.trim().to_string())
-            .filter(|l| !l.is_empty())
-            .collect()
-    } else {
-        HashSet::new()
-    };
+    if !gitignore_path.exists() {
+        return Ok(HashSet::new());
+    }
+    Ok(std::fs::read_to_string(&gitignore_path)?
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+/// Run the [`check_workspace`] checks without reporting or fixing anything.
+pub fn workspace_drift(base_path: &Path, config: &MetaConfig) -> Result<WorkspaceDrift> {
+    let ignored = ignored_lines(base_path)?;
 
     // Check A: remote-backed projects missing from .gitignore (auto-fixable).
     let mut missing_ignore: Vec<String> = Vec::new();
@@ -1024,7 +1212,7 @@ pub fn check_workspace(base_path: &Path, fix: bool) -> Result<()> {
     for name in config.projects.keys() {
         let url = config.get_project_url(name).unwrap_or_default();
         let is_local = url.is_empty() || url.starts_with("local:");
-        let should_ignore = !is_local;
+        let should_ignore = !is_local && !config.is_vendored(name);
         if should_ignore && !ignored.contains(name) {
             missing_ignore.push(name.clone());
         }
@@ -1073,7 +1261,26 @@ pub fn check_workspace(base_path: &Path, fix: bool) -> Result<()> {
     untracked.sort();
     promotable.sort();
 
-    let total = missing_ignore.len() + missing_dirs.len() + untracked.len() + promotable.len();
+    Ok(WorkspaceDrift {
+        missing_ignore,
+        missing_dirs,
+        untracked,
+        promotable,
+    })
+}
+
+pub fn check_workspace(base_path: &Path, fix: bool) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+
+    let drift = workspace_drift(base_path, &config)?;
+    let total = drift.total();
+    let WorkspaceDrift {
+        missing_ignore,
+        missing_dirs,
+        untracked,
+        promotable,
+    } = drift;
     if total == 0 {
         println!("  {} Workspace is in sync.", "✓".green());
         return Ok(());
@@ -1176,27 +1383,11 @@ pub fn check_workspace(base_path: &Path, fix: bool) -> Result<()> {
     ))
 }
 
+/// Add `project_path` to the managed block of the workspace `.gitignore`,
+/// leaving user-written lines untouched.
 fn update_gitignore(base_path: &Path, project_path: &str) -> Result<()> {
-    let gitignore_path = base_path.join(".gitignore");
-
-    let mut content = if gitignore_path.exists() {
-        std::fs::read_to_string(&gitignore_path)?
-    } else {
-        String::new()
-    };
-
-    // Check if project path is already ignored
-    if !content.lines().any(|line| line.trim() == project_path) {
-        if !content.is_empty() && !content.ends_with('\n') {
-            content.push('\n');
-        }
-        content.push_str(project_path);
-        content.push('\n');
-
-        std::fs::write(&gitignore_path, content)?;
-        // Silent - shown in summary
-    }
-
+    gitignore::add_managed_entry(base_path, project_path)?;
+    // Silent - shown in summary
     Ok(())
 }
 
@@ -1223,10 +1414,20 @@ pub fn list_projects(base_path: &Path, scope: &[String]) -> Result<()> {
         return Ok(());
     }
 
-    println!("\n  {} {}", "📦".bright_blue(), "Workspace Projects".bold());
-    println!("  {}", "═".repeat(60).bright_black());
+    println!(
+        "\n  {} {}\n",
+        "📦".bright_blue(),
+        "Workspace Projects".bold()
+    );
 
-    for name in config.projects.keys() {
+    let mut names: Vec<&String> = config.projects.keys().collect();
+    names.sort();
+    let today = state::today();
+    let mut table = Table::new(["Project", "Status", "Remote", "Notes"])
+        .indent(2)
+        .shrink(2)
+        .shrink(3);
+    for name in names {
         let project_path = base_path.join(name);
         let url = config
             .get_project_url(name)
@@ -1238,89 +1439,83 @@ pub fn list_projects(base_path: &Path, scope: &[String]) -> Result<()> {
             .map(|m| m.file_type().is_symlink())
             .unwrap_or(false);
 
-        let (status_text, status_color) = if project_path.exists() {
+        let status = if project_path.exists() {
             if is_symlink {
-                ("External", "cyan")
-            } else if project_path.join(".git").exists() {
-                ("Active", "green")
+                "External".cyan()
+            } else if vcs::detect(&project_path).is_some() {
+                "Active".green()
             } else {
-                ("No Git", "yellow")
+                "No Git".yellow()
             }
         } else {
-            ("Missing", "red")
+            "Missing".red()
         };
 
-        // Project name and status
-        println!();
-        print!("  {}", name.bold());
-
-        match status_color {
-            "green" => println!(" {}", format!("[{}]", status_text).green()),
-            "cyan" => println!(" {}", format!("[{}]", status_text).cyan()),
-            "yellow" => println!(" {}", format!("[{}]", status_text).yellow()),
-            "red" => println!(" {}", format!("[{}]", status_text).red()),
-            _ => println!(" [{}]", status_text),
+        let mut notes = Vec::new();
+        let kind = config.get_project_vcs(name);
+        if kind != metarepo_core::Vcs::Git {
+            notes.push(kind.to_string());
         }
-
-        // Project details with proper indentation and styling
-        if url.starts_with("external:local:") {
-            let path = url.strip_prefix("external:local:").unwrap();
-            println!(
-                "  {}  {} {}",
-                "│".bright_black(),
-                "Type:".bright_black(),
-                "Local (no remote)".italic()
-            );
-            println!(
-                "  {}  {} {}",
-                "│".bright_black(),
-                "Path:".bright_black(),
-                path.bright_white()
-            );
-        } else if url.starts_with("external:") {
-            let remote_url = url.strip_prefix("external:").unwrap();
-            println!(
-                "  {}  {} {}",
-                "│".bright_black(),
-                "Type:".bright_black(),
-                "External".cyan().italic()
-            );
-            println!(
-                "  {}  {} {}",
-                "│".bright_black(),
-                "Remote:".bright_black(),
-                remote_url.bright_white()
+        if let Some(pin) = config.get_project_pin(name) {
+            notes.push(format!("pinned {}", pin.cyan()));
+        }
+        if let Some(commit) = config.get_vendored_commit(name) {
+            notes.push(format!(
+                "vendored {}",
+                commit[..commit.len().min(12)].cyan()
+            ));
+        }
+        if let Some(visibility) = config.get_project_visibility(name) {
+            let level = visibility.to_string();
+            notes.push(
+                match visibility {
+                    Visibility::Public => level.green(),
+                    Visibility::Internal => level.cyan(),
+                    Visibility::Restricted => level.red(),
+                }
+                .to_string(),
             );
+        }
+
+        if let Some(deprecation) = config.get_project_deprecation(name) {
+            let summary = deprecation.summary();
+            notes.push(if deprecation.is_overdue(&today) {
+                format!("{} (overdue)", summary).red().to_string()
+            } else {
+                summary.yellow().to_string()
+            });
+        }
+
+        let remote = if let Some(path) = url.strip_prefix("external:local:") {
+            notes.push("local, no remote".italic().to_string());
+            path.to_string()
+        } else if let Some(remote_url) = url.strip_prefix("external:") {
             if is_symlink {
                 if let Ok(target) = std::fs::read_link(&project_path) {
-                    println!(
-                        "  {}  {} {}",
-                        "└".bright_black(),
-                        "Links to:".bright_black(),
+                    notes.push(format!(
+                        "→ {}",
                         target.display().to_string().bright_magenta()
-                    );
+                    ));
                 }
             }
+            remote_url.to_string()
         } else if url.starts_with("local:") {
-            println!(
-                "  {}  {} {}",
-                "└".bright_black(),
-                "Type:".bright_black(),
-                "Local (no remote)".italic()
-            );
+            "(local, no remote)".italic().to_string()
         } else {
-            println!(
-                "  {}  {} {}",
-                "└".bright_black(),
-                "Remote:".bright_black(),
-                url.bright_white()
-            );
-        }
+            url
+        };
+
+        table.row([
+            name.bold().to_string(),
+            status.to_string(),
+            remote,
+            notes.join(", "),
+        ]);
     }
+    table.print();
 
-    println!("\n  {}", "─".repeat(60).bright_black());
     println!(
-        "  {} {} projects total\n",
+        "\n  {} {} projects total\n",
         config.projects.len().to_string().cyan().bold(),
         "workspace".dimmed()
     );
@@ -1663,6 +1858,21 @@ pub fn update_projects(base_path: &Path, recursive: bool, depth: Option<usize>)
             continue;
         }
 
+        // Pinned projects stay at their pin; only report how far the remote
+        // has moved on.
+        if let Some(pin) = config.get_project_pin(name) {
+            println!(
+                "\n  {} {} {}",
+                "📌".cyan(),
+                name.bright_white(),
+                format!("(pinned at {})", pin).cyan()
+            );
+            if let Some(warning) = crate::plugins::shared::pin::check(name, &project_path, pin) {
+                println!("     {} {}", "⚠️".yellow(), warning.yellow());
+            }
+            continue;
+        }
+
         println!(
             "\n  {} {}",
             "📥".green(),
@@ -1677,6 +1887,21 @@ pub fn update_projects(base_path: &Path, recursive: bool, depth: Option<usize>)
                     Ok(_) => {
                         println!("     {} {}", "✅".green(), "Updated successfully".green());
                         updated += 1;
+                        if git_submodules::enabled() {
+                            match git_submodules::update(&project_path) {
+                                Ok(0) => {}
+                                Ok(n) => println!(
+                                    "     {} {}",
+                                    "✅".green(),
+                                    format!("Updated {} submodule(s)", n).green()
+                                ),
+                                Err(e) => eprintln!(
+                                    "     {} {}",
+                                    "⚠️".yellow(),
+                                    format!("{:#}", e).yellow()
+                                ),
+                            }
+                        }
 
                         // If recursive and this is a meta repo, update nested projects
                         if recursive && dir_has_meta_config(&project_path) {
@@ -1745,33 +1970,12 @@ fn pull_repository(repo: &Repository) -> Result<()> {
     let branch = head.shorthand().unwrap_or("main");
 
     // Set up fetch options with authentication
-    let mut callbacks = RemoteCallbacks::new();
-    callbacks.credentials(|_url, username_from_url, allowed_types| {
-        if allowed_types.contains(git2::CredentialType::SSH_KEY) {
-            let username = username_from_url.unwrap_or("git");
-            if let Ok(home) = std::env::var("HOME") {
-                let ssh_dir = Path::new(&home).join(".ssh");
-                let key_names = ["id_ed25519", "id_rsa", "id_ecdsa", "id_dsa"];
-
-                for key_name in &key_names {
-                    let private_key = ssh_dir.join(key_name);
-                    if private_key.exists() {
-                        if let Ok(cred) = Cred::ssh_key(username, None, private_key.as_path(), None)
-                        {
-                            return Ok(cred);
-                        }
-                    }
-                }
-            }
-
-            if let Ok(cred) = Cred::ssh_key_from_agent(username) {
-                return Ok(cred);
-            }
-        }
-
-        Err(git2::Error::from_str("Authentication failed"))
-    });
-
+    let mut callbacks = auth_callbacks();
+    let label = repo
+        .workdir()
+        .map(display_name)
+        .unwrap_or_else(|| branch.to_string());
+    transfer::apply(&mut callbacks, &label);
     let mut fetch_options = FetchOptions::new();
     fetch_options.remote_callbacks(callbacks);
 
@@ -1995,25 +2199,42 @@ pub fn remove_project(project_name: &str, base_path: &Path, force: bool) -> Resu
     Ok(())
 }
 
+/// Drop `project_name` from the managed block of the workspace `.gitignore`.
+/// A matching line the user wrote outside the block is kept.
 fn remove_from_gitignore(base_path: &Path, project_name: &str) -> Result<()> {
-    let gitignore_path = base_path.join(".gitignore");
-
-    if !gitignore_path.exists() {
-        return Ok(());
-    }
-
-    let content = std::fs::read_to_string(&gitignore_path)?;
-    let new_content: Vec<&str> = content
-        .lines()
-        .filter(|line| line.trim() != project_name)
-        .collect();
-
-    std::fs::write(&gitignore_path, new_content.join("\n") + "\n")?;
+    gitignore::remove_managed_entry(base_path, project_name)?;
     // Silent - shown in summary
-
     Ok(())
 }
 
+/// Whether the working tree at `path` has staged, modified, or untracked
+/// files.
+fn has_uncommitted_changes(path: &Path) -> Result<bool> {
+    let repo = Repository::open(path)?;
+
+    let mut status_opts = StatusOptions::new();
+    status_opts.include_untracked(true);
+    status_opts.include_ignored(false);
+
+    let statuses = repo.statuses(Some(&mut status_opts))?;
+
+    Ok(statuses.iter().any(|entry| {
+        let status = entry.status();
+        status.intersects(
+            Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::INDEX_TYPECHANGE
+                | Status::WT_NEW
+                | Status::WT_MODIFIED
+                | Status::WT_DELETED
+                | Status::WT_TYPECHANGE
+                | Status::WT_RENAMED,
+        )
+    }))
+}
+
 /// Rename a project in the workspace
 pub fn rename_project(old_name: &str, new_name: &str, base_path: &Path) -> Result<()> {
     // Load the workspace config
@@ -2058,37 +2279,15 @@ pub fn rename_project(old_name: &str, new_name: &str, base_path: &Path) -> Resul
         .unwrap_or_else(|| "".to_string());
 
     // Check for uncommitted changes if it's a git repository (not for symlinks)
-    if !is_symlink && old_path.exists() && old_path.join(".git").exists() {
-        let repo = Repository::open(&old_path)?;
-
-        let mut status_opts = StatusOptions::new();
-        status_opts.include_untracked(true);
-        status_opts.include_ignored(false);
-
-        let statuses = repo.statuses(Some(&mut status_opts))?;
-
-        let has_changes = statuses.iter().any(|entry| {
-            let status = entry.status();
-            status.intersects(
-                Status::INDEX_NEW
-                    | Status::INDEX_MODIFIED
-                    | Status::INDEX_DELETED
-                    | Status::INDEX_RENAMED
-                    | Status::INDEX_TYPECHANGE
-                    | Status::WT_NEW
-                    | Status::WT_MODIFIED
-                    | Status::WT_DELETED
-                    | Status::WT_TYPECHANGE
-                    | Status::WT_RENAMED,
-            )
-        });
-
-        if has_changes {
-            return Err(anyhow::anyhow!(
-                "Project '{}' has uncommitted changes. Please commit or stash them first.",
-                old_name
-            ));
-        }
+    if !is_symlink
+        && old_path.exists()
+        && old_path.join(".git").exists()
+        && has_uncommitted_changes(&old_path)?
+    {
+        return Err(anyhow::anyhow!(
+            "Project '{}' has uncommitted changes. Please commit or stash them first.",
+            old_name
+        ));
     }
 
     println!(
@@ -2227,9 +2426,28 @@ mod project_ops_tests {
         check_workspace(root, true).unwrap();
         let gitignore = std::fs::read_to_string(root.join(".gitignore")).unwrap();
         assert!(gitignore.lines().any(|l| l.trim() == "web"));
+        assert_eq!(
+            crate::plugins::shared::gitignore::managed_entries(&gitignore),
+            vec!["web".to_string()]
+        );
         assert!(check_workspace(root, false).is_ok());
     }
 
+    #[test]
+    fn remove_from_gitignore_keeps_user_lines_outside_block() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::write(root.join(".gitignore"), "web\n*.log\n").unwrap();
+
+        update_gitignore(root, "api").unwrap();
+        remove_from_gitignore(root, "web").unwrap();
+        remove_from_gitignore(root, "api").unwrap();
+
+        let content = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert!(content.starts_with("web\n*.log\n"));
+        assert!(!content.lines().any(|l| l.trim() == "api"));
+    }
+
     #[test]
     fn check_workspace_reports_missing_directory() {
         let tmp = tempdir().unwrap();
@@ -0,0 +1,98 @@
+//! `meta project pin` / `meta project unpin`: freeze a project at a ref so
+//! `meta git pull`, `meta project update`, and `meta git update` leave it there.
+
+use super::locate_workspace_config;
+use crate::plugins::shared::pin;
+use anyhow::Result;
+use colored::*;
+use metarepo_core::MetaConfig;
+use std::path::Path;
+
+/// Pin `project_name` at `reference` and check the ref out when the project is
+/// on disk (bare repositories keep their worktrees as they are).
+pub fn pin_project(project_name: &str, reference: &str, base_path: &Path) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+    if !config.projects.contains_key(project_name) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' not found in workspace config",
+            project_name
+        ));
+    }
+
+    let project_path = base_path.join(project_name);
+    let cloned = project_path.join(".git").exists();
+    let bare = config.is_bare_repo(project_name);
+    if cloned && !bare {
+        // Fetch only when the ref is not known locally yet, e.g. a new tag.
+        if pin::resolve(&project_path, reference).is_err() {
+            let _ = pin::fetch(&project_path);
+        }
+        pin::resolve(&project_path, reference)?;
+        pin::check_out(&project_path, reference)?;
+    }
+
+    config.set_project_pin(project_name, Some(reference.to_string()))?;
+    config.save_to_file(&meta_file_path)?;
+
+    println!(
+        "\n  {} {}",
+        "📌".cyan(),
+        format!("Pinned '{}' at {}", project_name, reference)
+            .bold()
+            .green()
+    );
+    let note = if bare {
+        format!(
+            "Bare repository: check out {} in its worktrees yourself",
+            reference
+        )
+    } else if cloned {
+        format!("Checked out {}", reference)
+    } else {
+        "Not cloned yet; 'meta git update' checks out the pin".to_string()
+    };
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        note.italic().bright_black()
+    );
+    println!();
+    Ok(())
+}
+
+/// Remove `project_name`'s pin. The working tree stays where it is.
+pub fn unpin_project(project_name: &str, base_path: &Path) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+    let Some(previous) = config.get_project_pin(project_name).map(str::to_string) else {
+        if !config.projects.contains_key(project_name) {
+            return Err(anyhow::anyhow!(
+                "Project '{}' not found in workspace config",
+                project_name
+            ));
+        }
+        println!("Project '{}' is not pinned", project_name);
+        return Ok(());
+    };
+
+    config.set_project_pin(project_name, None)?;
+    config.save_to_file(&meta_file_path)?;
+
+    println!(
+        "\n  {} {}",
+        "✅".green(),
+        format!("Unpinned '{}' (was {})", project_name, previous)
+            .bold()
+            .green()
+    );
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        "Check out a branch in it to follow the remote again"
+            .italic()
+            .bright_black()
+    );
+    println!();
+    Ok(())
+}
@@ -0,0 +1,560 @@
+//! Pre-flight plan for `meta project add --recursive --plan`.
+//!
+//! Walks the tree of nested meta repositories a recursive import would clone,
+//! without cloning anything. Nested configs are read from disk for directories
+//! that already exist, and otherwise fetched through the hosting provider's API
+//! (GitHub today), which also supplies each repository's size. Repositories the
+//! host API cannot see are shown with unknown size and contents.
+
+use anyhow::Result;
+use colored::*;
+use metarepo_core::{api, DedupeMode, MetaConfig, NestedConfig, KNOWN_FILENAMES};
+use std::path::{Path, PathBuf};
+
+use super::sizes::{exceeds_threshold, format_size_kb};
+use super::{load_dir_meta_config, ImportContext};
+use crate::plugins::shared::keychain;
+
+/// What the import would do with one repository in the plan.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanStatus {
+    /// Would be cloned.
+    Clone,
+    /// Target directory already exists; left as-is.
+    Exists,
+    /// `local:` project; nothing to clone.
+    Local,
+    /// Listed in `nested.ignore_nested`.
+    Ignored,
+    /// Importing it would close a cycle; the URL chain that leads back.
+    Cycle(Vec<String>),
+    /// Beyond `max_depth`; the import stops here.
+    DepthLimit,
+    /// Same repository as an earlier clone; symlinked to it (`nested.dedupe: link`).
+    Linked(PathBuf),
+}
+
+/// One repository in the import plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanNode {
+    pub name: String,
+    pub url: String,
+    /// Target directory, relative to the workspace root.
+    pub path: PathBuf,
+    /// Nesting depth (the imported project itself is 0).
+    pub depth: usize,
+    /// Repository size in KiB as reported by the host API.
+    pub size_kb: Option<u64>,
+    pub status: PlanStatus,
+    /// Whether the nested config could not be read (no host API, private repo).
+    pub contents_unknown: bool,
+    /// Earlier clone of the same repository, when it is cloned again anyway
+    /// (`nested.dedupe: warn`).
+    pub duplicate_of: Option<PathBuf>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    fn walk<'a>(&'a self, out: &mut Vec<&'a PlanNode>) {
+        out.push(self);
+        for child in &self.children {
+            child.walk(out);
+        }
+    }
+
+    /// Every node in the plan, depth-first.
+    pub fn nodes(&self) -> Vec<&PlanNode> {
+        let mut out = Vec::new();
+        self.walk(&mut out);
+        out
+    }
+}
+
+/// Read-only access to a remote repository, so the plan can be built without
+/// cloning. Implemented over the host API; tests substitute a fixture.
+pub trait RepoInspector {
+    /// Repository size in KiB, if the host reports it.
+    fn size_kb(&self, url: &str) -> Option<u64>;
+    /// The repository's metarepo config, if it has one the host can serve.
+    /// `Err` means the contents could not be inspected at all.
+    fn nested_config(&self, url: &str) -> Result<Option<MetaConfig>>;
+}
+
+/// Extract `(owner, repo)` from a GitHub URL in https, scp-like ssh, or
+/// `ssh://` form. Returns `None` for other hosts.
+pub fn github_slug(url: &str) -> Option<(String, String)> {
+    let url = url.strip_prefix("external:").unwrap_or(url);
+    let rest = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .or_else(|| url.strip_prefix("git@github.com:"))
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))?;
+    let rest = rest.trim_end_matches('/');
+    let rest = rest.strip_suffix(".git").unwrap_or(rest);
+    let (owner, repo) = rest.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some((owner.to_string(), repo.to_string()))
+}
+
+/// [`RepoInspector`] backed by the GitHub REST API, through the shared
+/// rate-limited client. Honors `GITHUB_TOKEN` / `GH_TOKEN`, else a token from
+/// `meta auth login github`, for private repositories and higher rate limits. Responses are cached for the run, so
+/// the size and visibility checks for one repository cost a single request.
+pub struct HostApiInspector {
+    token: Option<String>,
+}
+
+/// The token for GitHub API calls: `GITHUB_TOKEN` / `GH_TOKEN`, else one
+/// stored with `meta auth login github`.
+pub fn github_token() -> Option<String> {
+    std::env::var("GITHUB_TOKEN")
+        .or_else(|_| std::env::var("GH_TOKEN"))
+        .ok()
+        .filter(|t| !t.is_empty())
+        .or_else(|| keychain::get("github.com"))
+}
+
+impl HostApiInspector {
+    pub fn from_env() -> Self {
+        Self {
+            token: github_token(),
+        }
+    }
+}
+
+impl RepoInspector for HostApiInspector {
+    fn size_kb(&self, url: &str) -> Option<u64> {
+        let (owner, repo) = github_slug(url)?;
+        let body = api::client()
+            .get_cached(
+                &format!("https://api.github.com/repos/{owner}/{repo}"),
+                self.token.as_deref(),
+            )
+            .ok()?;
+        let json: serde_json::Value = serde_json::from_str(&body).ok()?;
+        json.get("size")?.as_u64()
+    }
+
+    fn nested_config(&self, url: &str) -> Result<Option<MetaConfig>> {
+        let (owner, repo) =
+            github_slug(url).ok_or_else(|| anyhow::anyhow!("no host API available for {}", url))?;
+        // Confirm the repository is visible first, so an unreachable host or a
+        // private repo reads as "unknown" rather than "has no nested config".
+        api::client().get_cached(
+            &format!("https://api.github.com/repos/{owner}/{repo}"),
+            self.token.as_deref(),
+        )?;
+        for name in KNOWN_FILENAMES {
+            let raw_url = format!("https://raw.githubusercontent.com/{owner}/{repo}/HEAD/{name}");
+            // A missing file is a 404; try the next recognized name.
+            let Ok(content) = api::client().get_cached(&raw_url, self.token.as_deref()) else {
+                continue;
+            };
+            let format = metarepo_core::ConfigFormat::from_path(Path::new(name))
+                .unwrap_or(metarepo_core::ConfigFormat::Json);
+            let config: MetaConfig =
+                metarepo_core::config_format::deserialize_from_str(&content, format)?;
+            return Ok(Some(config));
+        }
+        Ok(None)
+    }
+}
+
+/// Build the plan for importing `project_path` from `url` into `base_path`.
+/// `workspace` is the current config, whose checked-out projects count as
+/// existing clones for duplicate detection.
+pub fn build_import_plan(
+    project_path: &str,
+    url: &str,
+    base_path: &Path,
+    nested_config: &NestedConfig,
+    workspace: Option<&MetaConfig>,
+    inspector: &dyn RepoInspector,
+) -> PlanNode {
+    let mut context = ImportContext::new(base_path, Some(nested_config));
+    if let Some(config) = workspace {
+        context.seed_from_workspace(config);
+    }
+    let target = base_path.join(project_path);
+    let exists = target.exists();
+
+    let mut root = PlanNode {
+        name: project_path.to_string(),
+        url: url.to_string(),
+        path: PathBuf::from(project_path),
+        depth: 0,
+        size_kb: None,
+        status: if exists {
+            PlanStatus::Exists
+        } else {
+            PlanStatus::Clone
+        },
+        contents_unknown: false,
+        duplicate_of: None,
+        children: Vec::new(),
+    };
+    if !exists {
+        root.size_kb = inspector.size_kb(url);
+    }
+    context.record_clone(url, &target);
+
+    // The root is part of the chain so a nested repo pointing back at it is
+    // reported as a cycle.
+    context.import_chain.push(url.to_string());
+    let local_dir = exists.then_some(target.as_path());
+    plan_children(&mut root, local_dir, &mut context, inspector);
+    root
+}
+
+/// Fill in `node.children` from its nested config, read from `local_dir` when
+/// the repository is already on disk and through the inspector otherwise.
+fn plan_children(
+    node: &mut PlanNode,
+    local_dir: Option<&Path>,
+    context: &mut ImportContext,
+    inspector: &dyn RepoInspector,
+) {
+    let nested = if let Some(dir) = local_dir {
+        load_dir_meta_config(dir)
+    } else {
+        match inspector.nested_config(&node.url) {
+            Ok(config) => config,
+            Err(_) => {
+                node.contents_unknown = true;
+                None
+            }
+        }
+    };
+    let Some(nested) = nested else {
+        return;
+    };
+
+    let mut names: Vec<&String> = nested.projects.keys().collect();
+    names.sort();
+    for name in names {
+        let url = nested
+            .get_project_url(name)
+            .unwrap_or_else(|| format!("local:{}", name));
+        let path = if context.should_flatten() {
+            PathBuf::from(name)
+        } else {
+            node.path.join(name)
+        };
+        let mut child = PlanNode {
+            name: name.clone(),
+            url: url.clone(),
+            path,
+            depth: node.depth + 1,
+            size_kb: None,
+            status: PlanStatus::Clone,
+            contents_unknown: false,
+            duplicate_of: None,
+            children: Vec::new(),
+        };
+        let earlier = context.existing_clone(&url).map(|p| {
+            p.strip_prefix(&context.base_path)
+                .unwrap_or(p)
+                .to_path_buf()
+        });
+
+        if context.should_ignore(name) {
+            child.status = PlanStatus::Ignored;
+        } else if url.starts_with("local:") || url.starts_with("external:local:") {
+            child.status = PlanStatus::Local;
+        } else if let Some(cycle) = context.would_create_cycle(&url) {
+            child.status = PlanStatus::Cycle(cycle);
+        } else if context.at_max_depth() {
+            child.status = PlanStatus::DepthLimit;
+        } else if context.base_path.join(&child.path).exists() {
+            child.status = PlanStatus::Exists;
+        } else if let (Some(earlier), DedupeMode::Link) = (&earlier, context.dedupe) {
+            child.status = PlanStatus::Linked(earlier.clone());
+        } else {
+            if context.dedupe == DedupeMode::Warn {
+                child.duplicate_of = earlier.clone();
+            }
+            let target = context.base_path.join(&child.path);
+            context.record_clone(&url, &target);
+            let clone_url = url.strip_prefix("external:").unwrap_or(&url).to_string();
+            child.size_kb = inspector.size_kb(&clone_url);
+            context.import_chain.push(url.clone());
+            context.current_depth += 1;
+            plan_children(&mut child, None, context, inspector);
+            context.exit_import();
+        }
+        node.children.push(child);
+    }
+}
+
+fn describe(node: &PlanNode, warn_kb: Option<u64>) -> String {
+    let size = node
+        .size_kb
+        .map(format_size_kb)
+        .unwrap_or_else(|| "size unknown".to_string());
+    match &node.status {
+        PlanStatus::Clone => {
+            let mut s = size.bright_black().to_string();
+            if exceeds_threshold(node.size_kb, warn_kb) {
+                s.push_str(&format!(" {}", "⚠ large clone".yellow()));
+            }
+            if node.contents_unknown {
+                s.push_str(&format!(" {}", "(nested contents unknown)".bright_black()));
+            }
+            if let Some(earlier) = &node.duplicate_of {
+                s.push_str(&format!(
+                    " {} {}",
+                    "⚠ duplicate of".yellow(),
+                    earlier.display()
+                ));
+            }
+            s
+        }
+        PlanStatus::Exists => "already present, skipped".bright_black().to_string(),
+        PlanStatus::Local => "local, not cloned".bright_black().to_string(),
+        PlanStatus::Ignored => "ignored (nested.ignore_nested)".bright_black().to_string(),
+        PlanStatus::Cycle(chain) => format!("{} {}", "⚠ cycle:".yellow(), chain.join(" → ")),
+        PlanStatus::DepthLimit => "⚠ beyond max depth, not imported".yellow().to_string(),
+        PlanStatus::Linked(earlier) => format!(
+            "{} {}",
+            "linked to existing clone".bright_black(),
+            earlier.display()
+        ),
+    }
+}
+
+fn print_node(node: &PlanNode, prefix: &str, last: bool, is_root: bool, warn_kb: Option<u64>) {
+    let (connector, child_prefix) = if is_root {
+        ("", "  ".to_string())
+    } else if last {
+        ("└── ", format!("{}    ", prefix))
+    } else {
+        ("├── ", format!("{}│   ", prefix))
+    };
+    println!(
+        "{}{}{} {} {}",
+        if is_root { "  " } else { prefix },
+        connector.bright_black(),
+        node.name.bold(),
+        node.url.bright_cyan(),
+        describe(node, warn_kb)
+    );
+    for (i, child) in node.children.iter().enumerate() {
+        print_node(
+            child,
+            &child_prefix,
+            i == node.children.len() - 1,
+            false,
+            warn_kb,
+        );
+    }
+}
+
+/// Print the plan tree and a summary line. Repositories the host reports as
+/// larger than `warn_kb` are flagged as large clones.
+pub fn print_import_plan(plan: &PlanNode, max_depth: usize, warn_kb: Option<u64>) {
+    println!(
+        "\n  {} {}",
+        "📋".cyan(),
+        format!("Import plan for '{}' (max depth {})", plan.name, max_depth).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    print_node(plan, "  ", true, true, warn_kb);
+
+    let nodes = plan.nodes();
+    let to_clone: Vec<&&PlanNode> = nodes
+        .iter()
+        .filter(|n| n.status == PlanStatus::Clone)
+        .collect();
+    let known_kb: u64 = to_clone.iter().filter_map(|n| n.size_kb).sum();
+    let unknown = to_clone.iter().filter(|n| n.size_kb.is_none()).count();
+    let issues = nodes
+        .iter()
+        .filter(|n| {
+            matches!(n.status, PlanStatus::Cycle(_) | PlanStatus::DepthLimit)
+                || n.duplicate_of.is_some()
+        })
+        .count();
+    let deepest = nodes.iter().map(|n| n.depth).max().unwrap_or(0);
+    let large = to_clone
+        .iter()
+        .filter(|n| exceeds_threshold(n.size_kb, warn_kb))
+        .count();
+
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} to clone, {} known{}, deepest level {}, {}",
+        "Summary:".bright_black(),
+        format!("{} repositories", to_clone.len()).green(),
+        format_size_kb(known_kb),
+        if unknown > 0 {
+            format!(" ({} of unknown size)", unknown)
+        } else {
+            String::new()
+        },
+        deepest,
+        if issues > 0 {
+            format!("{} issue(s)", issues).yellow()
+        } else {
+            "no issues".bright_black()
+        }
+    );
+    if let Some(limit) = warn_kb.filter(|_| large > 0) {
+        println!(
+            "  {} {}",
+            "⚠".yellow(),
+            format!(
+                "{} repositor{} above the {} clone warning; consider --depth 1",
+                large,
+                if large == 1 { "y is" } else { "ies are" },
+                format_size_kb(limit)
+            )
+            .yellow()
+        );
+    }
+    println!(
+        "  {} {}",
+        "└".bright_black(),
+        "Nothing was cloned. Re-run without --plan to import."
+            .italic()
+            .bright_black()
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    /// Fixture host: url -> (size, nested config JSON).
+    struct FakeHost(HashMap<String, (u64, Option<&'static str>)>);
+
+    impl RepoInspector for FakeHost {
+        fn size_kb(&self, url: &str) -> Option<u64> {
+            self.0.get(url).map(|(size, _)| *size)
+        }
+
+        fn nested_config(&self, url: &str) -> Result<Option<MetaConfig>> {
+            match self.0.get(url) {
+                Some((_, Some(json))) => Ok(Some(serde_json::from_str(json)?)),
+                Some((_, None)) => Ok(None),
+                None => Err(anyhow::anyhow!("unknown host")),
+            }
+        }
+    }
+
+    #[test]
+    fn github_slug_parses_common_url_forms() {
+        let expected = Some(("acme".to_string(), "web".to_string()));
+        assert_eq!(github_slug("https://github.com/acme/web.git"), expected);
+        assert_eq!(github_slug("git@github.com:acme/web.git"), expected);
+        assert_eq!(github_slug("ssh://git@github.com/acme/web"), expected);
+        assert_eq!(
+            github_slug("external:https://github.com/acme/web/"),
+            expected
+        );
+        assert_eq!(github_slug("https://gitlab.com/acme/web.git"), None);
+    }
+
+    #[test]
+    fn plan_reports_tree_sizes_cycles_and_depth_limit() {
+        let tmp = tempdir().unwrap();
+        let mut host = HashMap::new();
+        host.insert(
+            "u:root".to_string(),
+            (
+                100,
+                Some(r#"{"projects":{"a":"u:a","b":"u:root","docs":"local:docs"}}"#),
+            ),
+        );
+        host.insert(
+            "u:a".to_string(),
+            (50, Some(r#"{"projects":{"deep":"u:deep"}}"#)),
+        );
+        host.insert("u:deep".to_string(), (7, None));
+        let nested = NestedConfig {
+            max_depth: 1,
+            ..NestedConfig::default()
+        };
+
+        let plan = build_import_plan("mono", "u:root", tmp.path(), &nested, None, &FakeHost(host));
+
+        assert_eq!(plan.size_kb, Some(100));
+        let by_name: HashMap<&str, &PlanNode> = plan
+            .nodes()
+            .into_iter()
+            .map(|n| (n.name.as_str(), n))
+            .collect();
+        assert_eq!(by_name["a"].status, PlanStatus::Clone);
+        assert_eq!(by_name["a"].path, PathBuf::from("mono/a"));
+        assert_eq!(by_name["a"].size_kb, Some(50));
+        assert!(matches!(by_name["b"].status, PlanStatus::Cycle(_)));
+        assert_eq!(by_name["docs"].status, PlanStatus::Local);
+        assert_eq!(by_name["deep"].status, PlanStatus::DepthLimit);
+        assert_eq!(by_name["deep"].depth, 2);
+    }
+
+    #[test]
+    fn plan_marks_uninspectable_repos() {
+        let tmp = tempdir().unwrap();
+        let plan = build_import_plan(
+            "mono",
+            "u:private",
+            tmp.path(),
+            &NestedConfig::default(),
+            None,
+            &FakeHost(HashMap::new()),
+        );
+        assert!(plan.contents_unknown);
+        assert_eq!(plan.size_kb, None);
+        assert!(plan.children.is_empty());
+    }
+
+    #[test]
+    fn plan_flags_duplicate_repositories_by_canonical_url() {
+        let tmp = tempdir().unwrap();
+        let mut host = HashMap::new();
+        host.insert(
+            "https://github.com/acme/root.git".to_string(),
+            (
+                1,
+                Some(
+                    r#"{"projects":{"a":"https://github.com/acme/lib.git","b":"git@github.com:acme/lib"}}"#,
+                ),
+            ),
+        );
+        host.insert("https://github.com/acme/lib.git".to_string(), (1, None));
+        host.insert("git@github.com:acme/lib".to_string(), (1, None));
+
+        let warn = build_import_plan(
+            "mono",
+            "https://github.com/acme/root.git",
+            tmp.path(),
+            &NestedConfig::default(),
+            None,
+            &FakeHost(host.clone()),
+        );
+        assert_eq!(warn.children[0].duplicate_of, None);
+        assert_eq!(warn.children[1].duplicate_of, Some(PathBuf::from("mono/a")));
+
+        let link = NestedConfig {
+            dedupe: DedupeMode::Link,
+            ..NestedConfig::default()
+        };
+        let linked = build_import_plan(
+            "mono",
+            "https://github.com/acme/root.git",
+            tmp.path(),
+            &link,
+            None,
+            &FakeHost(host),
+        );
+        assert_eq!(
+            linked.children[1].status,
+            PlanStatus::Linked(PathBuf::from("mono/a"))
+        );
+    }
+}
@@ -1,9 +1,13 @@
+use super::sizes::show_project_sizes;
 use super::{
-    check_workspace, convert_to_bare, import_project_recursive_with_options,
-    import_project_with_options, init_child_workspace, list_projects, list_projects_minimal,
-    remove_project, rename_project, show_project_tree, update_projects,
+    add_to_group, check_workspace, convert_to_bare, doctor_projects, extract_project,
+    import_project_recursive_with_options, import_project_with_options, import_submodules,
+    init_child_workspace, list_groups, list_projects, list_projects_minimal, list_templates,
+    move_project, new_project, pin_project, plan_recursive_import, remove_from_group,
+    remove_project, rename_project, show_project_tree, unpin_project, unvendor_project,
+    update_projects, vendor_project, GroupSettings, NewProjectOptions,
 };
-use crate::plugins::shared::parse_depth_arg;
+use crate::plugins::shared::{confirm, parse_depth_arg, selection, submodules, throttle};
 use anyhow::Result;
 use clap::ArgMatches;
 use colored::Colorize;
@@ -72,19 +76,29 @@ impl ProjectPlugin {
                          workspace via default_bare); pass --bare to force it. Use --depth to\n\
                          perform a shallow git clone (the depth is recorded so re-clones via\n\
                          meta git update stay shallow); this does not apply to recursive\n\
-                         imports. Use --init-git to git init a plain directory before tracking\n\
+                         imports. --recurse-submodules also checks out the clone's git\n\
+                         submodules. Use --init-git to git init a plain directory before tracking\n\
                          it. Use --recursive\n\
                          (with --max-depth, --flatten) to import nested meta repositories, or\n\
-                         --no-recursive to override a workspace that enables it by default. If\n\
+                         --no-recursive to override a workspace that enables it by default. Add\n\
+                         --plan to print the tree of repositories a recursive import would clone\n\
+                         (depths, sizes from the GitHub API, cycles, depth limits) without\n\
+                         cloning anything. If\n\
                          the added repo declares itself a meta module, you are shown it and,\n\
                          in a TTY, offered to enable it.\n\
                          \n\
+                         Before cloning, the host is asked for the repository size and a\n\
+                         warning is printed when it exceeds clone.size-warn-mb (default 500;\n\
+                         0 disables, METAREPO_CLONE_SIZE_WARN_MB overrides). --plan flags\n\
+                         such repositories too.\n\
+                         \n\
                          Examples:\n\
                          \n\
                            meta project add web https://github.com/acme/web.git   clone and track\n\
                            meta project add libs ../shared-libs                   symlink an external repo\n\
                            meta project add docs                                  adopt an existing directory\n\
-                           meta project add mono URL --recursive --flatten        import nested repos flat",
+                           meta project add mono URL --recursive --flatten        import nested repos flat\n\
+                           meta project add mono URL --recursive --plan           preview a nested import",
                     )
                     .with_help_formatting()
                     .arg(
@@ -137,6 +151,73 @@ impl ProjectPlugin {
                             .help("Git shallow clone depth (limits history fetched when cloning)")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("recurse-submodules")
+                            .long("recurse-submodules")
+                            .help("Also initialize and update the clone's git submodules, recursively")
+                    )
+                    .arg(
+                        arg("plan")
+                            .long("plan")
+                            .help("Preview the nested repositories a recursive import would clone, without cloning")
+                    )
+            )
+            .command(
+                command("new")
+                    .about("Create a project from a template repository")
+                    .help_description(
+                        "Create a new project from a template and track it in .meta.\n\
+                         \n\
+                         Copies the template repository (without its history) into <name>\n\
+                         and fills in {{project}} (the project path) and {{name}} (its last\n\
+                         path component) in file contents and file names. File names of\n\
+                         nested projects (svc/api) must use {{name}}. Binary files are\n\
+                         copied as-is. The copy is committed to a fresh repository and\n\
+                         registered in .meta.\n\
+                         \n\
+                         --template takes a template name from the templates block (usually\n\
+                         in the user config, ~/.config/metarepo/config.toml) or a repository\n\
+                         URL. A template's remote (or --remote) becomes the project's origin,\n\
+                         and --create-remote runs the template's create_remote command and\n\
+                         pushes the first commit. Both may use {project} and {name}.\n\
+                         --list-templates shows the configured templates.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project new services/billing --template rust-service\n\
+                           meta project new web --template https://github.com/acme/tpl-web.git\n\
+                           meta project new api --template svc --create-remote\n\
+                           meta project new --list-templates",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("name")
+                            .help("Path of the new project")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("template")
+                            .long("template")
+                            .short('t')
+                            .help("Template name from the templates config, or a repository URL")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("remote")
+                            .long("remote")
+                            .help("Remote URL for the new project (overrides the template's remote)")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("create-remote")
+                            .long("create-remote")
+                            .help("Create the remote with the template's create_remote command and push")
+                    )
+                    .arg(
+                        arg("list-templates")
+                            .long("list-templates")
+                            .help("List the configured templates")
+                    )
             )
             .command(
                 command("list")
@@ -151,11 +232,17 @@ impl ProjectPlugin {
                          missing, or symlink), and --minimal prints just the names, one per\n\
                          line, for scripting.\n\
                          \n\
+                         --tag TAG lists only the projects with that tag in .meta, across the\n\
+                         whole workspace, and --exclude-tag TAG leaves them out; both are\n\
+                         repeatable. Projects in the workspace default_excludes list are left\n\
+                         out, and --exclude drops more by name, alias, or glob.\n\
+                         \n\
                          Examples:\n\
                          \n\
                            meta project list             tree view of the workspace\n\
                            meta project list --flat       names with URLs and status\n\
-                           meta project list --minimal    bare names for scripts",
+                           meta project list --minimal    bare names for scripts\n\
+                           meta project list --tag web    only the projects tagged web",
                     )
                     .with_help_formatting()
                     .aliases(vec!["ls".to_string(), "l".to_string()])
@@ -171,6 +258,24 @@ impl ProjectPlugin {
                             .short('m')
                             .help("Display only project names (minimal output)")
                     )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .help("Only list projects with this tag in .meta (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("exclude-tag")
+                            .long("exclude-tag")
+                            .help("Leave out projects with this tag in .meta (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Leave out these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true)
+                    )
             )
             .command(
                 command("tree")
@@ -214,14 +319,22 @@ impl ProjectPlugin {
                          reported without aborting the rest. A summary of updated and failed\n\
                          counts is printed at the end.\n\
                          \n\
+                         Projects pinned with `meta project pin` are left at their pin;\n\
+                         they are fetched only to warn when the remote has moved past it.\n\
+                         \n\
                          With --recursive, any updated project that is itself a meta workspace\n\
                          has its nested projects updated too, down to --depth levels (default\n\
                          3). Aliased as \"pull\".\n\
                          \n\
+                         With --recurse-submodules (or clone.recurse-submodules in .meta),\n\
+                         each pulled project's git submodules are updated to the commits it\n\
+                         now records, initializing any new ones.\n\
+                         \n\
                          Examples:\n\
                          \n\
                            meta project update                 pull every project\n\
-                           meta project update --recursive      also update nested workspaces",
+                           meta project update --recursive      also update nested workspaces\n\
+                           meta project update --recurse-submodules   also update submodules",
                     )
                     .aliases(vec!["pull".to_string()])
                     .with_help_formatting()
@@ -237,6 +350,11 @@ impl ProjectPlugin {
                             .help("Maximum depth for recursive updates (default: 3)")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("recurse-submodules")
+                            .long("recurse-submodules")
+                            .help("Also update git submodules, recursively")
+                    )
             )
             .command(
                 command("remove")
@@ -249,7 +367,9 @@ impl ProjectPlugin {
                          working tree is checked for uncommitted changes (across all worktrees\n\
                          for bare repos); if any are found the command refuses unless --force\n\
                          is given. Plain --remove only edits .meta and leaves files in place;\n\
-                         --force additionally deletes the project directory from disk.\n\
+                         --force additionally deletes the project directory from disk. When\n\
+                         the workspace lists \"remove\" under \"protected\", --force asks you to\n\
+                         type the workspace name first (or pass --yes-i-know).\n\
                          \n\
                          Examples:\n\
                          \n\
@@ -270,6 +390,11 @@ impl ProjectPlugin {
                             .short('f')
                             .help("Force removal even with uncommitted changes, and delete directory")
                     )
+                    .arg(
+                        arg(confirm::YES_FLAG)
+                            .long(confirm::YES_FLAG)
+                            .help("Confirm a protected --force removal without typing the workspace name")
+                    )
             )
             .command(
                 command("rename")
@@ -282,13 +407,13 @@ impl ProjectPlugin {
                          is already tracked or the target directory already exists. For real\n\
                          git repositories (not symlinks) the working tree is checked first and\n\
                          the rename is refused when there are uncommitted changes; commit or\n\
-                         stash them before retrying. Aliased as \"mv\" and \"move\".\n\
+                         stash them before retrying. To relocate a project and update the\n\
+                         references to it, use 'meta project move'.\n\
                          \n\
                          Examples:\n\
                          \n\
                            meta project rename web frontend     rename web to frontend",
                     )
-                    .aliases(vec!["mv".to_string(), "move".to_string()])
                     .with_help_formatting()
                     .arg(
                         arg("old_name")
@@ -303,6 +428,45 @@ impl ProjectPlugin {
                             .takes_value(true)
                     )
             )
+            .command(
+                command("move")
+                    .about("Move a project to another path, updating references to it")
+                    .help_description(
+                        "Move a tracked project to another path in the workspace.\n\
+                         \n\
+                         Moves the project's directory to <new_path>, creating missing parent\n\
+                         directories and removing the ones left empty, and re-keys its entry\n\
+                         in .meta. References to the project follow it: global aliases that\n\
+                         point at it, group and profile members that name it exactly, the\n\
+                         disabled and default_excludes lists, services, the MCP exec\n\
+                         allowlist, and the managed block of .gitignore. Linked worktrees (a\n\
+                         bare project's branch directories, or .worktrees/) are repaired with\n\
+                         'git worktree repair' so they keep working from the new path.\n\
+                         \n\
+                         Group and profile globs such as 'libs/*' are left as they are. A\n\
+                         normal clone with uncommitted changes is refused; commit or stash\n\
+                         them first.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project move libs/foo packages/foo\n\
+                           meta project mv web apps/web",
+                    )
+                    .aliases(vec!["mv".to_string()])
+                    .with_help_formatting()
+                    .arg(
+                        arg("old_path")
+                            .help("Current path of the project")
+                            .required(true)
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("new_path")
+                            .help("Path to move the project to")
+                            .required(true)
+                            .takes_value(true)
+                    )
+            )
             .command(
                 command("convert-to-bare")
                     .about("Convert a normal repository to a bare repo with worktrees")
@@ -374,8 +538,8 @@ impl ProjectPlugin {
                            - a top-level git repo on disk not tracked in the config (report)\n\
                          \n\
                          Stale .gitignore lines are reported context permitting but never\n\
-                         auto-removed, since a former project entry cannot be told apart from a\n\
-                         hand-added ignore.\n\
+                         auto-removed here; use `meta gitignore sync` to rebuild the managed\n\
+                         block from the config.\n\
                          \n\
                          Examples:\n\
                          \n\
@@ -389,21 +553,393 @@ impl ProjectPlugin {
                             .help("Apply the fixable corrections instead of only reporting"),
                     ),
             )
+            .command(
+                command("doctor")
+                    .about("Run health checks on each project")
+                    .aliases(vec!["dr".to_string()])
+                    .help_description(
+                        "Check each project in scope for problems and list them.\n\
+                         \n\
+                         Where `meta project check` looks at the workspace as a whole, the\n\
+                         doctor looks inside each project. Exits non-zero while problems\n\
+                         remain. Pass --fix to repair the fixable ones.\n\
+                         \n\
+                         Checks:\n  \
+                           - the directory exists, and a symlinked project's target exists\n  \
+                           - it is a git repository\n  \
+                           - origin matches the configured URL (fixable)\n  \
+                           - .gitignore has an entry for it (fixable)\n  \
+                           - every worktree git knows about is on disk (fixable: prune)\n  \
+                           - a bare project has a worktree checked out (fixable)\n  \
+                           - no checkout has a detached HEAD (pinned projects excepted)\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project doctor              check the projects in scope\n\
+                           meta project doctor web api      check only these projects\n\
+                           meta project doctor --fix        repair what can be repaired",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to check (names, aliases, or globs)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("fix")
+                            .long("fix")
+                            .help("Repair the fixable problems instead of only reporting"),
+                    ),
+            )
+            .command(
+                command("sizes")
+                    .about("Report working-tree and .git sizes per project")
+                    .help_description(
+                        "Report how much disk each project uses.\n\
+                         \n\
+                         Measures every project in scope (in parallel) and prints its\n\
+                         working-tree size, its .git size, and the total, largest first.\n\
+                         Name projects (or globs) to measure only those; --exclude and the\n\
+                         workspace default_excludes apply as elsewhere.\n\
+                         \n\
+                         --remote also asks the host API for each repository's size, which\n\
+                         works before a project is cloned. Missing projects whose remote\n\
+                         size exceeds the clone.size-warn-mb threshold (default 500 MB,\n\
+                         METAREPO_CLONE_SIZE_WARN_MB overrides it) are flagged. Set\n\
+                         GITHUB_TOKEN for private repositories.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project sizes                 sizes for the current scope\n\
+                           meta project sizes 'services/*'     only matching projects\n\
+                           meta project sizes --remote --json  include host sizes, as JSON",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to measure (names, aliases, or globs)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("remote")
+                            .long("remote")
+                            .help("Also query the host API for each repository's size"),
+                    )
+                    .arg(arg("json").long("json").help("Output as JSON")),
+            )
+            .command(
+                command("pin")
+                    .about("Freeze a project at a tag, branch, or commit")
+                    .help_description(
+                        "Pin a project at a git ref so updates leave it there.\n\
+                         \n\
+                         Records \"pinned\": \"<ref>\" on the project's .meta entry and, when the\n\
+                         project is cloned, checks the ref out (detached). From then on\n\
+                         `meta git pull` and `meta project update` fetch the project but never\n\
+                         move it, warning when the remote's default branch has moved past the\n\
+                         pin, and `meta git update` checks the pin out after cloning. Meant for\n\
+                         vendored or release-frozen dependencies.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project pin vendor/lib v1.2.0   freeze vendor/lib at v1.2.0\n\
+                           meta project unpin vendor/lib        follow the remote again",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("name")
+                            .help("Name of the project to pin")
+                            .required(true)
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("ref")
+                            .help("Tag, branch, or commit to pin at")
+                            .required(true)
+                            .takes_value(true),
+                    ),
+            )
+            .command(
+                command("unpin")
+                    .about("Remove a project's pin")
+                    .help_description(
+                        "Remove a project's pin so updates move it again.\n\
+                         \n\
+                         Drops \"pinned\" from the project's .meta entry. The working tree is\n\
+                         left at the pinned ref; check out a branch to follow the remote.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project unpin vendor/lib        stop pinning vendor/lib",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("name")
+                            .help("Name of the project to unpin")
+                            .required(true)
+                            .takes_value(true),
+                    ),
+            )
+            .command(
+                command("vendor")
+                    .about("Commit a project's files into the workspace repository")
+                    .help_description(
+                        "Turn a project into an in-tree, vendored copy.\n\
+                         \n\
+                         Removes the project's .git directory, drops it from the managed\n\
+                         .gitignore block, and records the commit it was vendored from as\n\
+                         \"vendored\" on its .meta entry (the URL stays as provenance). Commit\n\
+                         the files with the workspace afterwards. The project must be a clean,\n\
+                         non-bare clone of a remote whose HEAD and local branches are pushed,\n\
+                         with no stashes or linked worktrees.\n\
+                         \n\
+                         `meta project unvendor` restores the separate clone: it clones the URL\n\
+                         again, resets it to the vendored commit, and keeps the in-tree files,\n\
+                         so edits made while vendored show up as local changes.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project vendor vendor/lib      commit vendor/lib in-tree\n\
+                           meta project unvendor vendor/lib    make it a separate clone again",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("name")
+                            .help("Name of the project to vendor")
+                            .required(true)
+                            .takes_value(true),
+                    ),
+            )
+            .command(
+                command("unvendor")
+                    .about("Restore a vendored project as a separate clone")
+                    .help_description(
+                        "Restore a vendored project as its own clone.\n\
+                         \n\
+                         Clones the project's URL, moves the clone's .git under the in-tree\n\
+                         files, and resets it to the commit recorded by `meta project vendor`.\n\
+                         Edits made while vendored remain as uncommitted changes. The project\n\
+                         is added back to the managed .gitignore block; run\n\
+                         `git rm -r --cached <name>` in the workspace to stop tracking the\n\
+                         in-tree copy.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project unvendor vendor/lib    make vendor/lib a clone again",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("name")
+                            .help("Name of the project to restore")
+                            .required(true)
+                            .takes_value(true),
+                    ),
+            )
+            .command(
+                command("extract")
+                    .about("Split a directory into its own repository and register it")
+                    .help_description(
+                        "Graduate a directory into its own repository.\n\
+                         \n\
+                         Splits the directory's history out with `git subtree split`, pushes it\n\
+                         to --to (as --branch, default main), and registers the new repository\n\
+                         as a project. A directory of the meta repository becomes a project in\n\
+                         place: the workspace stops tracking its files and a clone of the new\n\
+                         repository takes them over. A directory inside a project is cloned as\n\
+                         a new top-level project (named by --name, default the directory name),\n\
+                         leaving the original for you to remove from its project.\n\
+                         \n\
+                         The directory must have no uncommitted changes, the target repository\n\
+                         should be empty, and git-subtree must be installed.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project extract tools/cli --to git@github.com:org/cli.git\n\
+                           meta project extract api/sdk --to git@github.com:org/sdk.git --name sdk",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("path")
+                            .help("Directory to extract")
+                            .required(true)
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("to")
+                            .long("to")
+                            .help("URL of the new (empty) repository")
+                            .required(true)
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("name")
+                            .long("name")
+                            .help("Project name for a directory inside a project")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("branch")
+                            .long("branch")
+                            .help("Branch to push the split history to (default: main)")
+                            .takes_value(true),
+                    ),
+            )
+            .command(
+                command("import-submodules")
+                    .about("Convert a repository's git submodules into meta projects")
+                    .help_description(
+                        "Replace git submodules with meta projects.\n\
+                         \n\
+                         Reads the .gitmodules of the repository at PATH (default: the\n\
+                         workspace root) and registers each submodule as a project named by its\n\
+                         path from the workspace root, resolving relative URLs against the\n\
+                         repository's origin. Checked-out submodules stay where they are and\n\
+                         become ordinary clones: their git directory moves out of\n\
+                         .git/modules into the working copy. The repository then stops\n\
+                         tracking them: each is deinitialized, its gitlink is removed from the\n\
+                         index, and its .gitmodules section is dropped (the file too, once\n\
+                         empty). Submodules that were never checked out are only registered;\n\
+                         run meta git update to clone them.\n\
+                         \n\
+                         Submodules pin an exact commit; --pin keeps that by pinning each\n\
+                         project at it. Every submodule must be free of uncommitted changes.\n\
+                         Commit the repository afterwards to record the removal.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project import-submodules\n\
+                           meta project import-submodules --pin\n\
+                           meta project import-submodules services/api",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("path")
+                            .help("Repository whose submodules to import (default: workspace root)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("pin")
+                            .long("pin")
+                            .help("Pin each project at the commit the submodule recorded"),
+                    ),
+            )
+            .command(
+                command("group")
+                    .about("Manage named project groups")
+                    .help_description(
+                        "Manage the named project groups in .meta.\n\
+                         \n\
+                         A group is a named, described set of projects (keys, aliases, or globs)\n\
+                         with an optional default script. Select a group's members with --group\n\
+                         on exec, run, git status, git pull, and the worktree commands;\n\
+                         `meta run --group NAME` with no script runs the group's default script.\n\
+                         Unlike a profile, a group does not hide the rest of the workspace.\n\
+                         Running group with no subcommand lists the groups.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta project group add backend api 'services/*' --default-script test\n\
+                           meta project group add backend --description \"Server side\"\n\
+                           meta project group remove backend api\n\
+                           meta project group remove backend\n\
+                           meta project group list\n\
+                           meta run --group backend",
+                    )
+                    .with_help_formatting()
+                    .subcommand(
+                        command("add")
+                            .about("Create a group or add projects to it")
+                            .arg(
+                                arg("name")
+                                    .help("Group name")
+                                    .required(true)
+                                    .takes_value(true),
+                            )
+                            .arg(
+                                arg("projects")
+                                    .help("Projects to add (names, aliases, or globs)")
+                                    .multiple(true),
+                            )
+                            .arg(
+                                arg("description")
+                                    .long("description")
+                                    .help("Describe the group (empty to clear)")
+                                    .takes_value(true),
+                            )
+                            .arg(
+                                arg("default-script")
+                                    .long("default-script")
+                                    .help("Script 'meta run --group' runs by default (empty clears)")
+                                    .takes_value(true),
+                            ),
+                    )
+                    .subcommand(
+                        command("remove")
+                            .about("Remove projects from a group, or the whole group")
+                            .arg(
+                                arg("name")
+                                    .help("Group name")
+                                    .required(true)
+                                    .takes_value(true),
+                            )
+                            .arg(
+                                arg("projects")
+                                    .help("Members to remove; none removes the whole group")
+                                    .multiple(true),
+                            ),
+                    )
+                    .subcommand(command("list").about("List the groups and their projects")),
+            )
             .handler("add", handle_add)
             .handler("list", handle_list)
             .handler("tree", handle_tree)
             .handler("update", handle_update)
             .handler("remove", handle_remove)
             .handler("rename", handle_rename)
+            .handler("move", handle_move)
+            .handler("new", handle_new)
             .handler("convert-to-bare", handle_convert_to_bare)
             .handler("init", handle_init)
             .handler("check", handle_check)
+            .handler("doctor", handle_doctor)
+            .handler("sizes", handle_sizes)
+            .handler("pin", handle_pin)
+            .handler("unpin", handle_unpin)
+            .handler("vendor", handle_vendor)
+            .handler("unvendor", handle_unvendor)
+            .handler("extract", handle_extract)
+            .handler("import-submodules", handle_import_submodules)
+            .handler("group", handle_group)
             .build()
     }
 }
 
 /// Handler for the add command
 fn handle_add(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    throttle::configure(matches, &config.meta_config)?;
+    submodules::configure(matches, &config.meta_config);
     let non_interactive = config
         .non_interactive
         .unwrap_or(NonInteractiveMode::Defaults);
@@ -441,7 +977,13 @@ fn handle_add(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
 
     let init_git = matches.get_flag("init-git");
     let bare = matches.get_flag("bare");
-    let clone_depth = parse_depth_arg(matches.get_one::<String>("depth"))?;
+    let clone_depth = parse_depth_arg(matches.get_one::<String>("depth"))?.or_else(|| {
+        config
+            .meta_config
+            .clone_settings
+            .as_ref()
+            .and_then(|c| c.depth)
+    });
 
     let base_path = if config.meta_root().is_some() {
         config.meta_root().unwrap()
@@ -472,6 +1014,16 @@ fn handle_add(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
             .unwrap_or(false)
     };
 
+    // Pre-flight plan: show what a recursive import would clone and stop.
+    if matches.get_flag("plan") {
+        if no_recursive {
+            return Err(anyhow::anyhow!(
+                "--plan previews a recursive import and cannot be combined with --no-recursive"
+            ));
+        }
+        return plan_recursive_import(&path, source, &base_path, max_depth, flatten);
+    }
+
     // Determine if we should use bare repository
     let use_bare = if bare {
         true // Explicitly enabled via flag
@@ -514,7 +1066,7 @@ fn handle_list(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let base_path = config
         .meta_root()
         .unwrap_or_else(|| config.working_dir.clone());
-    let scope = config.scoped_project_keys();
+    let scope = listed_projects(matches, config)?;
 
     // Check flags for output format
     if matches.get_flag("minimal") {
@@ -530,6 +1082,16 @@ fn handle_list(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     Ok(())
 }
 
+/// The projects `meta project list` shows: the directory-aware scope (or,
+/// with `--tag` alone, the whole workspace), less `--exclude` and the
+/// workspace `default_excludes`, narrowed by the tag filters.
+fn listed_projects(matches: &ArgMatches, config: &RuntimeConfig) -> Result<Vec<String>> {
+    let mut scope = selection::implicit_scope(matches, config);
+    selection::apply_excludes(matches, config, &mut scope, false);
+    selection::apply_tags(matches, config, &mut scope)?;
+    Ok(scope)
+}
+
 /// Handler for the tree command
 fn handle_tree(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let base_path = config
@@ -553,6 +1115,8 @@ fn handle_tree(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
 
 /// Handler for the update command
 fn handle_update(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    throttle::configure(matches, &config.meta_config)?;
+    submodules::configure(matches, &config.meta_config);
     let base_path = if config.meta_root().is_some() {
         config.meta_root().unwrap()
     } else {
@@ -600,6 +1164,14 @@ fn handle_remove(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     };
 
     let force = matches.get_flag("force");
+    if force {
+        confirm::require(
+            confirm::REMOVE,
+            &format!("Removing '{}' and deleting its directory", name),
+            matches,
+            config,
+        )?;
+    }
 
     let base_path = if config.meta_root().is_some() {
         config.meta_root().unwrap()
@@ -626,6 +1198,42 @@ fn handle_rename(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     Ok(())
 }
 
+/// Handler for the move command
+fn handle_move(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let old_path = matches.get_one::<String>("old_path").unwrap();
+    let new_path = matches.get_one::<String>("new_path").unwrap();
+
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+
+    move_project(old_path, new_path, &base_path)
+}
+
+/// Handler for the new command
+fn handle_new(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    if matches.get_flag("list-templates") {
+        list_templates(&config.meta_config);
+        return Ok(());
+    }
+    let name = matches
+        .get_one::<String>("name")
+        .ok_or_else(|| anyhow::anyhow!("Project name is required: meta project new <name>"))?;
+    let template = matches.get_one::<String>("template").ok_or_else(|| {
+        anyhow::anyhow!("--template is required (see meta project new --list-templates)")
+    })?;
+    let options = NewProjectOptions {
+        remote: matches.get_one::<String>("remote").cloned(),
+        create_remote: matches.get_flag("create-remote"),
+    };
+
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+
+    new_project(name, template, &options, &config.meta_config, &base_path)
+}
+
 /// Handler for the convert-to-bare command
 fn handle_convert_to_bare(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let project = matches.get_one::<String>("project").unwrap();
@@ -668,6 +1276,145 @@ fn handle_check(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     Ok(())
 }
 
+/// Handler for the doctor command
+fn handle_doctor(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    let (mut projects, explicit) = match selection::explicit_projects(matches, config)? {
+        Some(selected) => (selected, true),
+        None => (config.scoped_project_keys(), false),
+    };
+    selection::apply_excludes(matches, config, &mut projects, explicit);
+    if projects.is_empty() {
+        println!("\n{}", "No projects in this directory".dimmed());
+        return Ok(());
+    }
+    doctor_projects(&base_path, &projects, matches.get_flag("fix"))
+}
+
+/// Handler for the sizes command
+fn handle_sizes(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    let (mut projects, explicit) = match selection::explicit_projects(matches, config)? {
+        Some(selected) => (selected, true),
+        None => (config.scoped_project_keys(), false),
+    };
+    selection::apply_excludes(matches, config, &mut projects, explicit);
+    if projects.is_empty() {
+        println!("\n{}", "No projects in this directory".dimmed());
+        return Ok(());
+    }
+    show_project_sizes(
+        &base_path,
+        &config.meta_config,
+        &projects,
+        matches.get_flag("remote"),
+        matches.get_flag("json"),
+    )
+}
+
+/// Handler for the group command and its subcommands
+fn handle_group(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    let members = |sub: &ArgMatches| -> Vec<String> {
+        sub.get_many::<String>("projects")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default()
+    };
+    match matches.subcommand() {
+        Some(("add", sub)) => {
+            let settings = GroupSettings {
+                description: sub.get_one::<String>("description").map(String::as_str),
+                default_script: sub.get_one::<String>("default-script").map(String::as_str),
+            };
+            let name = sub.get_one::<String>("name").unwrap();
+            add_to_group(name, &members(sub), settings, &base_path)
+        }
+        Some(("remove", sub)) => {
+            let name = sub.get_one::<String>("name").unwrap();
+            remove_from_group(name, &members(sub), &base_path)
+        }
+        _ => {
+            list_groups(&config.meta_config);
+            Ok(())
+        }
+    }
+}
+
+/// Handler for the pin command
+fn handle_pin(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let name = matches.get_one::<String>("name").unwrap();
+    let reference = matches.get_one::<String>("ref").unwrap();
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    pin_project(name, reference, &base_path)
+}
+
+/// Handler for the unpin command
+fn handle_unpin(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let name = matches.get_one::<String>("name").unwrap();
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    unpin_project(name, &base_path)
+}
+
+/// Handler for the vendor command
+fn handle_vendor(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let name = matches.get_one::<String>("name").unwrap();
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    vendor_project(name, &base_path)
+}
+
+/// Handler for the unvendor command
+fn handle_unvendor(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let name = matches.get_one::<String>("name").unwrap();
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    unvendor_project(name, &base_path)
+}
+
+/// Handler for the extract command
+fn handle_extract(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let path = matches.get_one::<String>("path").unwrap();
+    let url = matches.get_one::<String>("to").unwrap();
+    let branch = matches
+        .get_one::<String>("branch")
+        .map(String::as_str)
+        .unwrap_or("main");
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    extract_project(
+        &config.working_dir.join(path),
+        url,
+        matches.get_one::<String>("name").map(String::as_str),
+        branch,
+        &base_path,
+    )
+}
+
+/// Handler for the import-submodules command
+fn handle_import_submodules(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    let repo = match matches.get_one::<String>("path") {
+        Some(path) => config.working_dir.join(path),
+        None => base_path.clone(),
+    };
+    import_submodules(&repo, matches.get_flag("pin"), &base_path)
+}
+
 // Traditional implementation for backward compatibility
 impl MetaPlugin for ProjectPlugin {
     fn name(&self) -> &str {
@@ -685,6 +1432,17 @@ impl MetaPlugin for ProjectPlugin {
         let plugin = Self::create_plugin();
         plugin.handle_command(matches, config)
     }
+
+    fn settings(&self) -> Vec<metarepo_core::ConfigSetting> {
+        use metarepo_core::{ConfigSetting, ConfigValueType};
+        vec![ConfigSetting::new(
+            "clone.size-warn-mb",
+            "Warn before cloning a repository larger than this many MB (0 disables)",
+            ConfigValueType::Integer,
+        )
+        .with_default("500")
+        .with_env(super::sizes::CLONE_WARN_ENV)]
+    }
 }
 
 impl BasePlugin for ProjectPlugin {
@@ -706,3 +1464,47 @@ impl Default for ProjectPlugin {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_leaves_out_default_excludes_and_excluded_projects() {
+        let config = RuntimeConfig {
+            meta_config: serde_json::from_str(
+                r#"{
+                    "default_excludes": ["old"],
+                    "projects": {
+                        "api": "local:api",
+                        "web": "local:web",
+                        "old": "local:old"
+                    }
+                }"#,
+            )
+            .unwrap(),
+            working_dir: std::path::PathBuf::from("/ws"),
+            meta_file_path: Some(std::path::PathBuf::from("/ws/.meta")),
+            experimental: false,
+            non_interactive: None,
+            scope_workspace: false,
+            profile: None,
+            settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
+        };
+        let listed = |args: &[&str]| {
+            let app = ProjectPlugin::new().register_commands(clap::Command::new("meta"));
+            let matches = app
+                .try_get_matches_from(["meta", "project", "list"].iter().chain(args))
+                .unwrap();
+            let project = matches.subcommand_matches("project").unwrap();
+            let mut listed =
+                listed_projects(project.subcommand_matches("list").unwrap(), &config).unwrap();
+            listed.sort();
+            listed
+        };
+
+        assert_eq!(listed(&[]), ["api", "web"]);
+        assert_eq!(listed(&["--exclude", "web"]), ["api"]);
+    }
+}
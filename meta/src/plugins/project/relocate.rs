@@ -0,0 +1,397 @@
+//! `meta project move OLD NEW`: relocate a project to another path in the
+//! workspace, such as `libs/foo` to `packages/foo`.
+//!
+//! Besides moving the directory (creating any missing parent directories and
+//! removing the ones left empty), a move follows the project everywhere the
+//! workspace refers to it: the `.meta` key, global aliases, group and profile
+//! members, the `disabled` and `default_excludes` lists, services, the MCP
+//! exec allowlist, and the managed block of `.gitignore`. Linked worktrees
+//! (a bare project's `<project>/<branch>` directories, or `.worktrees/`) store
+//! absolute paths, so they are repaired with `git worktree repair`.
+
+use super::{has_uncommitted_changes, locate_workspace_config};
+use crate::plugins::shared::gitignore;
+use crate::plugins::worktree::list_worktrees;
+use anyhow::{Context, Result};
+use colored::*;
+use git2::Repository;
+use metarepo_core::{ensure_within_base, validate_path_segment, MetaConfig};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Replace every reference to project `old` in `config` with `new`, except
+/// the `projects` key itself. Returns a line describing each change. Group and
+/// profile members are only rewritten when they name the project exactly;
+/// globs and aliases keep matching on their own.
+fn retarget(config: &mut MetaConfig, old: &str, new: &str) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut replace = |list: &mut Vec<String>, what: String| {
+        for entry in list.iter_mut().filter(|entry| entry.as_str() == old) {
+            *entry = new.to_string();
+            changes.push(what.clone());
+        }
+    };
+
+    if let Some(groups) = config.groups.as_mut() {
+        let mut names: Vec<String> = groups.keys().cloned().collect();
+        names.sort();
+        for name in names {
+            let group = groups.get_mut(&name).expect("name came from the map");
+            replace(&mut group.projects, format!("group '{}'", name));
+        }
+    }
+    if let Some(profiles) = config.profiles.as_mut() {
+        let mut names: Vec<String> = profiles.keys().cloned().collect();
+        names.sort();
+        for name in names {
+            let members = profiles.get_mut(&name).expect("name came from the map");
+            replace(members, format!("profile '{}'", name));
+        }
+    }
+    replace(&mut config.disabled, "disabled list".to_string());
+    replace(&mut config.default_excludes, "default_excludes".to_string());
+    if let Some(projects) = config
+        .mcp
+        .as_mut()
+        .and_then(|mcp| mcp.serve.as_mut())
+        .and_then(|serve| serve.projects.as_mut())
+    {
+        replace(projects, "mcp.serve.projects".to_string());
+    }
+
+    if let Some(aliases) = config.aliases.as_mut() {
+        let mut retargeted: Vec<&String> = aliases
+            .iter_mut()
+            .filter(|(_, target)| target.as_str() == old)
+            .map(|(alias, target)| {
+                *target = new.to_string();
+                alias
+            })
+            .collect();
+        retargeted.sort();
+        changes.extend(retargeted.into_iter().map(|a| format!("alias '{}'", a)));
+    }
+    if let Some(services) = config.services.as_mut() {
+        if let Some(spec) = services.remove(old) {
+            services.insert(new.to_string(), spec);
+            changes.push("services".to_string());
+        }
+    }
+    changes
+}
+
+/// Absolute paths of the linked worktrees of the repository at `git_dir`,
+/// leaving out the main working tree and a bare repository's own entry.
+fn linked_worktrees(git_dir: &Path, project_path: &Path) -> Result<Vec<PathBuf>> {
+    let project_path = project_path.canonicalize()?;
+    Ok(list_worktrees(git_dir)?
+        .into_iter()
+        .filter(|wt| !wt.is_bare)
+        .map(|wt| wt.path)
+        .filter(|path| path.canonicalize().ok().as_ref() != Some(&project_path))
+        .collect())
+}
+
+/// Remove `dir` and its parents while they are empty, stopping at `base_path`.
+fn remove_empty_parents(base_path: &Path, dir: Option<&Path>) {
+    let mut dir = dir;
+    while let Some(current) = dir {
+        if current == base_path || !current.starts_with(base_path) {
+            break;
+        }
+        if std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+/// Move project `old_name` to `new_name` (see the module docs).
+pub fn move_project(old_name: &str, new_name: &str, base_path: &Path) -> Result<()> {
+    let old_name = old_name.trim_end_matches('/');
+    let new_name = new_name.trim_end_matches('/');
+    validate_path_segment("project name", new_name)?;
+
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+
+    if !config.projects.contains_key(old_name) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' not found in workspace config",
+            old_name
+        ));
+    }
+    if config.projects.contains_key(new_name) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' already exists in workspace config",
+            new_name
+        ));
+    }
+    if Path::new(new_name).starts_with(old_name) || Path::new(old_name).starts_with(new_name) {
+        return Err(anyhow::anyhow!(
+            "Cannot move '{}' to '{}': one path contains the other",
+            old_name,
+            new_name
+        ));
+    }
+
+    let old_path = base_path.join(old_name);
+    let new_path = base_path.join(new_name);
+    ensure_within_base(base_path, &new_path)?;
+    if new_path.symlink_metadata().is_ok() {
+        return Err(anyhow::anyhow!("Directory '{}' already exists", new_name));
+    }
+
+    let is_symlink = old_path
+        .symlink_metadata()
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false);
+    let is_repo = !is_symlink && old_path.join(".git").exists();
+    // Detected on disk: an entry without `bare` may still be a bare clone.
+    let is_bare = is_repo
+        && Repository::open(&old_path)
+            .map(|repo| repo.is_bare())
+            .unwrap_or(false);
+    // A bare project's files live in its worktrees, which move along intact.
+    if is_repo && !is_bare && has_uncommitted_changes(&old_path)? {
+        return Err(anyhow::anyhow!(
+            "Project '{}' has uncommitted changes. Please commit or stash them first.",
+            old_name
+        ));
+    }
+    let git_dir = |project: &Path| {
+        if is_bare {
+            project.join(".git")
+        } else {
+            project.to_path_buf()
+        }
+    };
+    let (worktrees, old_abs) = if is_repo {
+        (
+            linked_worktrees(&git_dir(old_path.as_path()), &old_path)?,
+            old_path.canonicalize()?,
+        )
+    } else {
+        (Vec::new(), old_path.clone())
+    };
+
+    println!(
+        "\n  {} {}",
+        "🚚".cyan(),
+        format!("Moving project '{}' to '{}'", old_name, new_name).bold()
+    );
+
+    if old_path.symlink_metadata().is_ok() {
+        if let Some(parent) = new_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::rename(&old_path, &new_path)
+            .with_context(|| format!("Failed to move {} to {}", old_name, new_name))?;
+        remove_empty_parents(base_path, old_path.parent());
+        let label = if is_symlink {
+            "Moved symlink"
+        } else {
+            "Moved directory"
+        };
+        println!("     {} {}", "✅".green(), label.green());
+
+        if !worktrees.is_empty() {
+            let new_abs = new_path.canonicalize()?;
+            let moved: Vec<PathBuf> = worktrees
+                .iter()
+                .map(|path| match path.strip_prefix(&old_abs) {
+                    Ok(rest) => new_abs.join(rest),
+                    Err(_) => path.clone(),
+                })
+                .collect();
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(git_dir(new_path.as_path()))
+                .args(["worktree", "repair"])
+                .args(&moved)
+                .output()
+                .context("Failed to run git worktree repair")?;
+            if output.status.success() {
+                println!(
+                    "     {} {}",
+                    "✅".green(),
+                    format!("Repaired {} worktree(s)", moved.len()).green()
+                );
+            } else {
+                println!(
+                    "     {} {}",
+                    "⚠️".yellow(),
+                    format!(
+                        "Worktree repair failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )
+                    .yellow()
+                );
+                println!(
+                    "     {} {}",
+                    " ".bright_black(),
+                    format!("To retry: meta worktree repair {}", new_name).dimmed()
+                );
+            }
+        }
+    }
+
+    let entry = config
+        .projects
+        .remove(old_name)
+        .expect("checked that the project exists");
+    config.projects.insert(new_name.to_string(), entry);
+    let changes = retarget(&mut config, old_name, new_name);
+    config.save_to_file(&meta_file_path)?;
+    println!(
+        "     {} {}",
+        "✅".green(),
+        "Updated workspace config".green()
+    );
+    for change in &changes {
+        println!(
+            "     {} {}",
+            "└".bright_black(),
+            format!("Retargeted {}", change).bright_black()
+        );
+    }
+
+    let url = config.get_project_url(new_name).unwrap_or_default();
+    if !url.starts_with("local:") {
+        gitignore::remove_managed_entry(base_path, old_name)?;
+        gitignore::add_managed_entry(base_path, new_name)?;
+        println!("     {} {}", "✅".green(), "Updated .gitignore".green());
+    }
+
+    println!(
+        "\n  {} {}",
+        "✅".green(),
+        format!("Moved '{}' to '{}'", old_name, new_name)
+            .bold()
+            .green()
+    );
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::{McpServeSettings, McpSettings, ProjectEntry, ProjectGroup};
+    use std::collections::HashMap;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn retarget_follows_exact_references() {
+        let mut config = MetaConfig {
+            aliases: Some(HashMap::from([
+                ("f".to_string(), "libs/foo".to_string()),
+                ("b".to_string(), "libs/bar".to_string()),
+            ])),
+            groups: Some(HashMap::from([(
+                "libs".to_string(),
+                ProjectGroup {
+                    projects: vec!["libs/foo".to_string(), "libs/*".to_string()],
+                    ..Default::default()
+                },
+            )])),
+            profiles: Some(HashMap::from([(
+                "core".to_string(),
+                vec!["libs/foo".to_string()],
+            )])),
+            disabled: vec!["libs/foo".to_string()],
+            mcp: Some(McpSettings {
+                serve: Some(McpServeSettings {
+                    projects: Some(vec!["libs/foo".to_string()]),
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        };
+        let changes = retarget(&mut config, "libs/foo", "packages/foo");
+        assert_eq!(
+            changes,
+            [
+                "group 'libs'",
+                "profile 'core'",
+                "disabled list",
+                "mcp.serve.projects",
+                "alias 'f'",
+            ]
+        );
+        let aliases = config.aliases.unwrap();
+        assert_eq!(aliases["f"], "packages/foo");
+        assert_eq!(aliases["b"], "libs/bar");
+        // The glob is left alone.
+        assert_eq!(
+            config.groups.unwrap()["libs"].projects,
+            ["packages/foo", "libs/*"]
+        );
+        assert_eq!(config.profiles.unwrap()["core"], ["packages/foo"]);
+        assert_eq!(config.disabled, ["packages/foo"]);
+    }
+
+    #[test]
+    fn move_relocates_a_bare_project_and_repairs_its_worktrees() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let upstream = root.join("upstream");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        run(&upstream, &["commit", "-q", "--allow-empty", "-m", "init"]);
+
+        std::fs::create_dir_all(root.join("libs/foo")).unwrap();
+        run(
+            &root,
+            &["clone", "-q", "--bare", "upstream", "libs/foo/.git"],
+        );
+        run(
+            &root.join("libs/foo/.git"),
+            &["worktree", "add", "-q", "../main", "main"],
+        );
+
+        let mut config = MetaConfig::default();
+        config.projects.insert(
+            "libs/foo".to_string(),
+            ProjectEntry::Url(upstream.to_string_lossy().to_string()),
+        );
+        config.save_to_file(root.join(".meta")).unwrap();
+        gitignore::add_managed_entry(&root, "libs/foo").unwrap();
+
+        move_project("libs/foo", "packages/foo/", &root).unwrap();
+
+        assert!(!root.join("libs").exists());
+        let worktree = root.join("packages/foo/main");
+        let status = Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(&worktree)
+            .output()
+            .unwrap();
+        assert!(status.status.success());
+        let link = std::fs::read_to_string(worktree.join(".git")).unwrap();
+        assert!(
+            link.contains("packages/foo/.git/worktrees/main"),
+            "{}",
+            link
+        );
+
+        let config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        assert!(config.projects.contains_key("packages/foo"));
+        assert!(!config.projects.contains_key("libs/foo"));
+        let ignore = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert_eq!(gitignore::managed_entries(&ignore), ["packages/foo"]);
+
+        // Moving into itself is refused.
+        assert!(move_project("packages/foo", "packages/foo/inner", &root).is_err());
+    }
+}
@@ -0,0 +1,394 @@
+//! `meta project new NAME --template URL|NAME`: start a project from a
+//! template repository.
+//!
+//! The template is cloned without its history into `NAME`, and every
+//! `{{project}}` (the new project's path) and `{{name}}` (its last path
+//! component) in file contents and file names is filled in. A file name
+//! cannot take a nested project's path, so `{{project}}` there is an error
+//! for `svc/api`; such templates use `{{name}}`. The result is
+//! committed to a fresh repository, optionally pushed to a newly created
+//! remote, and registered in `.meta` like any existing directory. Named
+//! templates come from the `templates` block, usually in the user config.
+
+use super::import_project_with_options;
+use crate::plugins::shared::git;
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::{is_supported_git_url, validate_path_segment, MetaConfig};
+use std::path::Path;
+use std::process::Command;
+
+/// What `meta project new` does beyond copying the template.
+#[derive(Debug, Default)]
+pub struct NewProjectOptions {
+    /// Remote URL, overriding the template's `remote`.
+    pub remote: Option<String>,
+    /// Run the template's `create_remote` command and push to the remote.
+    pub create_remote: bool,
+}
+
+/// Fill the `{project}` and `{name}` placeholders of a config value.
+fn expand(template: &str, project: &str) -> String {
+    let name = project.rsplit('/').next().unwrap_or(project);
+    template
+        .replace("{project}", project)
+        .replace("{name}", name)
+}
+
+/// Fill the `{{project}}` and `{{name}}` placeholders of template content.
+/// Double braces keep single-brace code such as format strings intact.
+fn render(text: &str, project: &str) -> String {
+    let name = project.rsplit('/').next().unwrap_or(project);
+    text.replace("{{project}}", project)
+        .replace("{{name}}", name)
+}
+
+/// Fill the placeholders of a file name. A name cannot hold a `/`, so
+/// `{{project}}` only works in names when the project is not nested.
+fn render_name(file_name: &str, project: &str) -> Result<String> {
+    let renamed = render(file_name, project);
+    if renamed.contains('/') {
+        return Err(anyhow::anyhow!(
+            "Template file name '{}' would become '{}': {{{{project}}}} is a path for nested \
+             project '{}'; use {{{{name}}}} in file names",
+            file_name,
+            renamed,
+            project
+        ));
+    }
+    Ok(renamed)
+}
+
+/// Render every text file and file name under `dir`, skipping `.git`.
+/// Binary files are left as they are. Returns how many paths changed.
+fn render_tree(dir: &Path, project: &str) -> Result<usize> {
+    let mut changed = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name == ".git" {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            changed += render_tree(&path, project)?;
+        } else if file_type.is_file() {
+            if let Ok(text) = String::from_utf8(std::fs::read(&path)?) {
+                let rendered = render(&text, project);
+                if rendered != text {
+                    std::fs::write(&path, rendered)?;
+                    changed += 1;
+                }
+            }
+        }
+        let renamed = render_name(&file_name, project)?;
+        if renamed != file_name {
+            let target = dir.join(&renamed);
+            std::fs::rename(&path, &target)
+                .with_context(|| format!("Failed to rename {}", path.display()))?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// The repository to copy for `template`: a configured template's URL, or
+/// `template` itself when it is a git URL or an existing directory.
+fn template_url(config: &MetaConfig, template: &str, base_path: &Path) -> Result<String> {
+    if let Some(spec) = config.templates.as_ref().and_then(|t| t.get(template)) {
+        return Ok(spec.url.clone());
+    }
+    if is_supported_git_url(template) || base_path.join(template).is_dir() {
+        return Ok(template.to_string());
+    }
+    let mut names: Vec<&String> = config.templates.iter().flat_map(|t| t.keys()).collect();
+    names.sort();
+    Err(anyhow::anyhow!(
+        "Unknown template '{}'. {}",
+        template,
+        if names.is_empty() {
+            "No templates are configured; add a [templates.<name>] table with a url to \
+             ~/.config/metarepo/config.toml, or pass a repository URL."
+                .to_string()
+        } else {
+            format!(
+                "Configured templates: {}",
+                names
+                    .iter()
+                    .map(|n| n.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    ))
+}
+
+/// Print the configured templates.
+pub fn list_templates(config: &MetaConfig) {
+    let Some(templates) = config.templates.as_ref().filter(|t| !t.is_empty()) else {
+        println!("No templates configured.");
+        println!(
+            "{}",
+            "Add [templates.<name>] with a url to ~/.config/metarepo/config.toml".dimmed()
+        );
+        return;
+    };
+    let mut names: Vec<&String> = templates.keys().collect();
+    names.sort();
+    let mut table = metarepo_core::Table::new(["Template", "Description", "URL"]).shrink(1);
+    for name in names {
+        let spec = &templates[name];
+        table.row([
+            name.bold().to_string(),
+            spec.description.clone().unwrap_or_default(),
+            spec.url.clone(),
+        ]);
+    }
+    table.print();
+}
+
+/// Copy the template at `url` into `target` as a fresh repository with one
+/// commit.
+fn scaffold(url: &str, target: &Path, project: &str, base_path: &Path) -> Result<()> {
+    let target_arg = target.to_string_lossy();
+    git(
+        base_path,
+        &["clone", "-q", "--depth", "1", url, target_arg.as_ref()],
+    )
+    .with_context(|| format!("Failed to clone template {}", url))?;
+    std::fs::remove_dir_all(target.join(".git"))?;
+    let rendered = render_tree(target, project)?;
+    println!(
+        "     {} {}",
+        "✅".green(),
+        format!("Copied template ({} path(s) filled in)", rendered).green()
+    );
+
+    git(target, &["init", "-q"])?;
+    git(target, &["add", "-A"])?;
+    git(
+        target,
+        &[
+            "commit",
+            "-q",
+            "-m",
+            &format!("Initial commit from template {}", url),
+        ],
+    )
+    .context("Failed to create the initial commit")?;
+    println!("     {} {}", "✅".green(), "Created initial commit".green());
+    Ok(())
+}
+
+/// Create project `project` from `template` (see the module docs).
+pub fn new_project(
+    project: &str,
+    template: &str,
+    options: &NewProjectOptions,
+    config: &MetaConfig,
+    base_path: &Path,
+) -> Result<()> {
+    let project = project.trim_end_matches('/');
+    validate_path_segment("project name", project)?;
+    if config.projects.contains_key(project) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' already exists in workspace config",
+            project
+        ));
+    }
+    let target = base_path.join(project);
+    metarepo_core::ensure_within_base(base_path, &target)?;
+    if target.exists() {
+        return Err(anyhow::anyhow!("Directory '{}' already exists", project));
+    }
+    // The outermost directory this run creates, so a failure can remove the
+    // parents of a nested project along with it.
+    let created = Path::new(project)
+        .ancestors()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(|p| base_path.join(p))
+        .filter(|p| !p.exists())
+        .last()
+        .unwrap_or_else(|| target.clone());
+
+    let url = template_url(config, template, base_path)?;
+    let spec = config.templates.as_ref().and_then(|t| t.get(template));
+    let remote = options.remote.clone().or_else(|| {
+        spec.and_then(|s| s.remote.as_deref())
+            .map(|r| expand(r, project))
+    });
+    let create_command = if options.create_remote {
+        let command = spec
+            .and_then(|s| s.create_remote.as_deref())
+            .ok_or_else(|| {
+                anyhow::anyhow!("--create-remote needs a template with a create_remote command")
+            })?;
+        if remote.is_none() {
+            return Err(anyhow::anyhow!(
+                "--create-remote needs a remote URL: pass --remote or set the template's remote"
+            ));
+        }
+        Some(expand(command, project))
+    } else {
+        None
+    };
+
+    println!(
+        "\n  {} {}",
+        "🧩".cyan(),
+        format!("Creating '{}' from template {}", project, template).bold()
+    );
+    if let Err(e) = scaffold(&url, &target, project, base_path) {
+        // Leave nothing half-made behind.
+        std::fs::remove_dir_all(&created).ok();
+        return Err(e);
+    }
+
+    if let Some(remote) = &remote {
+        git(&target, &["remote", "add", "origin", remote])?;
+        println!(
+            "     {} {}",
+            "✅".green(),
+            format!("Remote: {}", remote).green()
+        );
+    }
+    if let Some(command) = create_command {
+        println!(
+            "     {} {}",
+            "→".bright_black(),
+            format!("Running: {}", command).bright_black()
+        );
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .current_dir(&target)
+            .status()
+            .context("Failed to run the create_remote command")?;
+        if !status.success() {
+            return Err(anyhow::anyhow!(
+                "create_remote command failed ({}); the project is in '{}' but not yet registered",
+                status,
+                project
+            ));
+        }
+        git(&target, &["push", "-q", "-u", "origin", "HEAD"])
+            .context("Failed to push to the new remote")?;
+        println!(
+            "     {} {}",
+            "✅".green(),
+            "Created and pushed to the remote".green()
+        );
+    }
+
+    // Register it like any existing repository in the workspace.
+    import_project_with_options(project, None, base_path, false, false, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ProjectTemplate;
+    use std::collections::HashMap;
+
+    #[test]
+    fn placeholders_are_filled_in_contents_and_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src/{{name}}")).unwrap();
+        std::fs::write(
+            root.join("src/{{name}}/main.rs"),
+            "// {{project}}\nprintln!(\"{}\", \"{{name}}\");\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("logo.bin"), [0xff, 0xfe, b'{', b'{']).unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(root.join(".git/{{name}}"), "{{name}}").unwrap();
+
+        assert_eq!(render_tree(root, "services/billing").unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/billing/main.rs")).unwrap(),
+            "// services/billing\nprintln!(\"{}\", \"billing\");\n"
+        );
+        assert!(root.join(".git/{{name}}").exists());
+        assert_eq!(std::fs::read(root.join("logo.bin")).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn project_paths_in_file_names_work_only_for_top_level_projects() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("{{project}}.md"), "# {{project}}\n").unwrap();
+        assert_eq!(render_tree(tmp.path(), "api").unwrap(), 2);
+        assert!(tmp.path().join("api.md").exists());
+
+        assert_eq!(render_name("{{name}}.md", "svc/api").unwrap(), "api.md");
+        let err = render_name("{{project}}.md", "svc/api").unwrap_err();
+        assert!(err.to_string().contains("use {{name}} in file names"));
+    }
+
+    #[test]
+    fn failed_nested_project_leaves_no_directories_behind() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().join("ws");
+        let template = tmp.path().join("template");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::create_dir_all(&template).unwrap();
+        std::fs::write(template.join("{{project}}.md"), "# {{project}}\n").unwrap();
+        let git_in = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&template)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git_in(&["init", "-q"]);
+        git_in(&["add", "-A"]);
+        git_in(&["commit", "-q", "-m", "template"]);
+
+        let err = new_project(
+            "svc/api",
+            template.to_str().unwrap(),
+            &NewProjectOptions::default(),
+            &MetaConfig::default(),
+            &base,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("use {{name}} in file names"));
+        assert!(!base.join("svc").exists());
+    }
+
+    #[test]
+    fn templates_resolve_by_name_or_url() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = MetaConfig {
+            templates: Some(HashMap::from([(
+                "svc".to_string(),
+                ProjectTemplate {
+                    url: "git@github.com:acme/template-svc.git".to_string(),
+                    remote: Some("git@github.com:acme/{name}.git".to_string()),
+                    ..Default::default()
+                },
+            )])),
+            ..Default::default()
+        };
+        assert_eq!(
+            template_url(&config, "svc", tmp.path()).unwrap(),
+            "git@github.com:acme/template-svc.git"
+        );
+        assert_eq!(
+            template_url(&config, "https://github.com/acme/t.git", tmp.path()).unwrap(),
+            "https://github.com/acme/t.git"
+        );
+        let err = template_url(&config, "nope", tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("Configured templates: svc"));
+        assert_eq!(
+            expand("git@github.com:acme/{name}.git", "services/billing"),
+            "git@github.com:acme/billing.git"
+        );
+    }
+}
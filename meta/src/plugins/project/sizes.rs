@@ -0,0 +1,320 @@
+//! Repository sizes: `meta project sizes` and the large-clone warning shown by
+//! `meta project add`, `--plan` previews, and `meta git update --plan`.
+//!
+//! On-disk sizes are measured by walking each project directory (in parallel
+//! across projects), split into the working tree and the `.git` directory.
+//! Remote sizes come from the host API (see [`HostApiInspector`]) and are only
+//! available for hosts it understands; anything else reads as unknown.
+
+use super::plan::{HostApiInspector, RepoInspector};
+use anyhow::Result;
+use colored::*;
+use metarepo_core::MetaConfig;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Large-clone threshold used when neither the environment nor the config
+/// sets one.
+pub const DEFAULT_CLONE_WARN_MB: u64 = 500;
+/// Environment variable overriding `clone.size-warn-mb`.
+pub const CLONE_WARN_ENV: &str = "METAREPO_CLONE_SIZE_WARN_MB";
+
+/// The large-clone threshold in KB, resolved env > config > default. `None`
+/// when the warning is disabled (threshold 0).
+pub fn clone_warn_threshold_kb(config: &MetaConfig) -> Option<u64> {
+    let mb = std::env::var(CLONE_WARN_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .or_else(|| config.clone_settings.as_ref().and_then(|c| c.size_warn_mb))
+        .unwrap_or(DEFAULT_CLONE_WARN_MB);
+    (mb > 0).then_some(mb * 1024)
+}
+
+/// Whether a host-reported size is over the threshold.
+pub fn exceeds_threshold(size_kb: Option<u64>, threshold_kb: Option<u64>) -> bool {
+    matches!((size_kb, threshold_kb), (Some(size), Some(limit)) if size > limit)
+}
+
+/// Human-readable size for a KB count.
+pub fn format_size_kb(kb: u64) -> String {
+    if kb >= 1024 * 1024 {
+        format!("{:.1} GB", kb as f64 / (1024.0 * 1024.0))
+    } else if kb >= 1024 {
+        format!("{:.1} MB", kb as f64 / 1024.0)
+    } else {
+        format!("{} KB", kb)
+    }
+}
+
+/// The host-reported size of `url` in KB, when the host API knows it.
+pub fn remote_size_kb(url: &str) -> Option<u64> {
+    HostApiInspector::from_env().size_kb(url)
+}
+
+/// Ask the host how large `url` is and print a warning when it exceeds the
+/// threshold. Does nothing (and makes no request) when the warning is off.
+pub fn warn_if_large_clone(url: &str, threshold_kb: Option<u64>) {
+    let Some(limit) = threshold_kb else {
+        return;
+    };
+    let Some(size) = remote_size_kb(url) else {
+        return;
+    };
+    if size <= limit {
+        return;
+    }
+    println!(
+        "     {} {}",
+        "⚠️".yellow(),
+        format!(
+            "Large repository: the host reports {}, above the {} clone warning",
+            format_size_kb(size),
+            format_size_kb(limit)
+        )
+        .yellow()
+    );
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        format!("Consider --depth 1, or raise clone.size-warn-mb / {CLONE_WARN_ENV}")
+            .bright_black()
+    );
+}
+
+/// Sizes for one project.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProjectSize {
+    pub name: String,
+    /// Whether the project directory exists locally.
+    pub exists: bool,
+    /// Bytes outside the top-level `.git`.
+    pub worktree_bytes: u64,
+    /// Bytes inside the top-level `.git`.
+    pub git_bytes: u64,
+    /// Host-reported repository size in KB (with `--remote`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_kb: Option<u64>,
+}
+
+impl ProjectSize {
+    pub fn total_bytes(&self) -> u64 {
+        self.worktree_bytes + self.git_bytes
+    }
+}
+
+/// Split the size of `dir` into (working tree, `.git`) bytes. Symlinks are
+/// not followed, so a symlinked external project measures as empty.
+pub fn measure_dir(dir: &Path) -> (u64, u64) {
+    let git_dir = dir.join(".git");
+    let mut worktree = 0;
+    let mut git = 0;
+    for entry in walkdir::WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if entry.path().starts_with(&git_dir) {
+            git += len;
+        } else {
+            worktree += len;
+        }
+    }
+    (worktree, git)
+}
+
+/// Measure `projects` under `base_path`, spreading the directory walks across
+/// threads. The result keeps the order of `projects`.
+pub fn measure_projects(base_path: &Path, projects: &[String]) -> Vec<ProjectSize> {
+    let results: Mutex<Vec<Option<ProjectSize>>> = Mutex::new(vec![None; projects.len()]);
+    let next = AtomicUsize::new(0);
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(projects.len().max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(name) = projects.get(i) else {
+                    break;
+                };
+                let path = base_path.join(name);
+                let exists = path.is_dir();
+                let (worktree_bytes, git_bytes) = if exists { measure_dir(&path) } else { (0, 0) };
+                let size = ProjectSize {
+                    name: name.clone(),
+                    exists,
+                    worktree_bytes,
+                    git_bytes,
+                    remote_kb: None,
+                };
+                results.lock().unwrap()[i] = Some(size);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Report sizes for `projects`, largest first. With `remote`, each project's
+/// URL is also looked up on its host; projects that are not cloned yet are
+/// flagged when that size crosses the clone warning threshold.
+pub fn show_project_sizes(
+    base_path: &Path,
+    config: &MetaConfig,
+    projects: &[String],
+    remote: bool,
+    json: bool,
+) -> Result<()> {
+    let mut sizes = measure_projects(base_path, projects);
+    if remote {
+        let inspector = HostApiInspector::from_env();
+        for size in &mut sizes {
+            if let Some(url) = config.get_project_url(&size.name) {
+                size.remote_kb = inspector.size_kb(&url);
+            }
+        }
+    }
+    sizes.sort_by(|a, b| {
+        b.total_bytes()
+            .cmp(&a.total_bytes())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&sizes)?);
+        return Ok(());
+    }
+
+    let threshold_kb = clone_warn_threshold_kb(config);
+    let width = sizes.iter().map(|s| s.name.len()).max().unwrap_or(0).max(7);
+    let remote_col = |kb: Option<u64>| {
+        if remote {
+            format!(
+                "  {:>10}",
+                kb.map(format_size_kb).unwrap_or_else(|| "?".to_string())
+            )
+        } else {
+            String::new()
+        }
+    };
+
+    println!("\n  {} {}", "📦".cyan(), "Project sizes".bold());
+    println!("  {}", "═".repeat(60).bright_black());
+    let mut header = format!(
+        "{:<width$}  {:>10}  {:>10}  {:>10}",
+        "Project", "Tree", ".git", "Total"
+    );
+    if remote {
+        header.push_str(&format!("  {:>10}", "Remote"));
+    }
+    println!("  {}", header.bright_black());
+
+    for size in &sizes {
+        if size.exists {
+            println!(
+                "  {:<width$}  {:>10}  {:>10}  {}{}",
+                size.name,
+                format_size_kb(size.worktree_bytes / 1024),
+                format_size_kb(size.git_bytes / 1024),
+                format!("{:>10}", format_size_kb(size.total_bytes() / 1024)).bold(),
+                remote_col(size.remote_kb),
+            );
+        } else {
+            let note = if exceeds_threshold(size.remote_kb, threshold_kb) {
+                format!("{:>34}", "⚠ large clone").yellow()
+            } else {
+                format!("{:>34}", "not cloned").bright_black()
+            };
+            println!(
+                "  {}  {}{}",
+                format!("{:<width$}", size.name).bright_black(),
+                note,
+                remote_col(size.remote_kb),
+            );
+        }
+    }
+
+    let worktree: u64 = sizes.iter().map(|s| s.worktree_bytes).sum();
+    let git: u64 = sizes.iter().map(|s| s.git_bytes).sum();
+    println!("  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {}  {:>10}  {:>10}  {}",
+        format!("{:<width$}", "Total").bold(),
+        format_size_kb(worktree / 1024),
+        format_size_kb(git / 1024),
+        format!("{:>10}", format_size_kb((worktree + git) / 1024)).bold(),
+    );
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn measure_dir_splits_git_from_working_tree() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(".git/objects")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(".git/objects/pack"), vec![0u8; 300]).unwrap();
+        std::fs::write(root.join("src/main.rs"), vec![0u8; 120]).unwrap();
+        // A file merely named like .git elsewhere is part of the tree.
+        std::fs::write(root.join("src/.gitkeep"), vec![0u8; 5]).unwrap();
+
+        assert_eq!(measure_dir(root), (125, 300));
+    }
+
+    #[test]
+    fn measure_projects_keeps_order_and_flags_missing() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("a")).unwrap();
+        std::fs::write(tmp.path().join("a/f"), vec![0u8; 10]).unwrap();
+
+        let names = vec!["missing".to_string(), "a".to_string()];
+        let sizes = measure_projects(tmp.path(), &names);
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[0].name, "missing");
+        assert!(!sizes[0].exists);
+        assert_eq!(sizes[1].worktree_bytes, 10);
+    }
+
+    #[test]
+    fn threshold_resolution_and_comparison() {
+        let mut config = MetaConfig {
+            clone_settings: Some(metarepo_core::CloneSettings {
+                size_warn_mb: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        // Only assert on the config path when the env override is unset.
+        if std::env::var(CLONE_WARN_ENV).is_err() {
+            assert_eq!(clone_warn_threshold_kb(&config), None);
+            config.clone_settings = None;
+            assert_eq!(
+                clone_warn_threshold_kb(&config),
+                Some(DEFAULT_CLONE_WARN_MB * 1024)
+            );
+        }
+        assert!(exceeds_threshold(Some(2048), Some(1024)));
+        assert!(!exceeds_threshold(Some(1024), Some(1024)));
+        assert!(!exceeds_threshold(None, Some(1024)));
+        assert!(!exceeds_threshold(Some(2048), None));
+    }
+}
@@ -0,0 +1,440 @@
+//! `meta project import-submodules [PATH]`: turn a repository's git submodules
+//! into meta projects.
+//!
+//! Each submodule in the repository's `.gitmodules` is registered in `.meta`
+//! under its path relative to the workspace root. Its working copy stays where
+//! it is: the submodule's git directory (kept by git under
+//! `.git/modules/<name>`) is moved into the working copy, which becomes an
+//! ordinary clone. The repository then stops tracking the submodule: it is
+//! deinitialized, its gitlink is removed from the index, and its section is
+//! removed from `.gitmodules`. Submodules that were never initialized are only
+//! registered, for `meta git update` to clone.
+
+use super::locate_workspace_config;
+use crate::plugins::shared::{git, gitignore};
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::{validate_project_url, MetaConfig, ProjectEntry};
+use std::path::{Path, PathBuf};
+
+/// One `[submodule "<name>"]` section of `.gitmodules`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Submodule {
+    name: String,
+    path: String,
+    url: String,
+}
+
+/// Parse the output of `git config -f .gitmodules --get-regexp` into the
+/// submodules that have both a path and a URL, in file order.
+fn parse_gitmodules(config: &str) -> Vec<Submodule> {
+    let mut submodules: Vec<Submodule> = Vec::new();
+    for line in config.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+        let Some(rest) = key.strip_prefix("submodule.") else {
+            continue;
+        };
+        // Submodule names may contain dots; the variable is the last part.
+        let Some((name, variable)) = rest.rsplit_once('.') else {
+            continue;
+        };
+        let index = match submodules.iter().position(|s| s.name == name) {
+            Some(index) => index,
+            None => {
+                submodules.push(Submodule {
+                    name: name.to_string(),
+                    path: String::new(),
+                    url: String::new(),
+                });
+                submodules.len() - 1
+            }
+        };
+        match variable {
+            "path" => submodules[index].path = value.trim_end_matches('/').to_string(),
+            "url" => submodules[index].url = value.to_string(),
+            _ => {}
+        }
+    }
+    submodules.retain(|s| !s.path.is_empty() && !s.url.is_empty());
+    submodules
+}
+
+/// Resolve a submodule URL relative to the superproject's remote
+/// (`../lib.git` next to `git@host:org/app.git` is `git@host:org/lib.git`).
+/// Absolute URLs are returned as they are.
+fn resolve_url(url: &str, remote: Option<&str>) -> Result<String> {
+    if !url.starts_with("./") && !url.starts_with("../") {
+        return Ok(url.to_string());
+    }
+    let remote = remote.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Submodule URL '{}' is relative, but the repository has no origin remote",
+            url
+        )
+    })?;
+    let mut base = remote.trim_end_matches('/').to_string();
+    for part in url.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                let cut = base.rfind(['/', ':']).ok_or_else(|| {
+                    anyhow::anyhow!("Cannot resolve '{}' against '{}'", url, remote)
+                })?;
+                // Keep the ':' of an scp-style URL (git@host:org).
+                let keep = if base[cut..].starts_with(':') {
+                    cut + 1
+                } else {
+                    cut
+                };
+                base.truncate(keep);
+            }
+            _ => {
+                if !base.ends_with(':') {
+                    base.push('/');
+                }
+                base.push_str(part);
+            }
+        }
+    }
+    Ok(base)
+}
+
+/// Import the submodules of the repository at `repo` (inside the workspace at
+/// `base_path`) as projects (see the module docs). With `pin`, each project is
+/// pinned at the commit the repository recorded for it.
+pub fn import_submodules(repo: &Path, pin: bool, base_path: &Path) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+
+    let base = base_path.canonicalize()?;
+    let repo = PathBuf::from(
+        git(repo, &["rev-parse", "--show-toplevel"])
+            .map_err(|_| anyhow::anyhow!("'{}' is not inside a git repository", repo.display()))?,
+    )
+    .canonicalize()?;
+    let prefix = repo
+        .strip_prefix(&base)
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Repository '{}' is not inside the workspace",
+                repo.display()
+            )
+        })?
+        .to_path_buf();
+    if !repo.join(".gitmodules").is_file() {
+        return Err(anyhow::anyhow!(
+            "'{}' has no .gitmodules file",
+            repo.display()
+        ));
+    }
+
+    let listing = git(
+        &repo,
+        &[
+            "config",
+            "-f",
+            ".gitmodules",
+            "--get-regexp",
+            r"^submodule\..*\.(path|url)$",
+        ],
+    )
+    .unwrap_or_default();
+    let submodules = parse_gitmodules(&listing);
+    if submodules.is_empty() {
+        println!("\n  {} No submodules to import\n", "ℹ".cyan());
+        return Ok(());
+    }
+    let remote = git(&repo, &["remote", "get-url", "origin"]).ok();
+
+    // Check everything before changing anything.
+    let mut plan = Vec::new();
+    for submodule in &submodules {
+        let name = prefix
+            .join(&submodule.path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        if config.projects.contains_key(&name) {
+            return Err(anyhow::anyhow!(
+                "Project '{}' already exists in workspace config",
+                name
+            ));
+        }
+        let url = resolve_url(&submodule.url, remote.as_deref())?;
+        validate_project_url(&url)
+            .with_context(|| format!("Submodule '{}' has an invalid URL", submodule.name))?;
+        let path = repo.join(&submodule.path);
+        let initialized = path.join(".git").exists();
+        if initialized && !git(&path, &["status", "--porcelain"])?.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Submodule '{}' has uncommitted changes. Please commit or stash them first.",
+                submodule.path
+            ));
+        }
+        // The commit the superproject records, from the gitlink in the index.
+        let commit = git(&repo, &["ls-files", "-s", "--", &submodule.path])?
+            .split_whitespace()
+            .nth(1)
+            .map(str::to_string);
+        plan.push((submodule, name, url, path, initialized, commit));
+    }
+
+    println!(
+        "\n  {} {}",
+        "📦".cyan(),
+        format!("Importing {} submodule(s)", plan.len()).bold()
+    );
+    for (submodule, name, url, path, initialized, commit) in plan {
+        if initialized {
+            detach_submodule(&path)?;
+        } else if path.is_dir() && path.read_dir()?.next().is_none() {
+            // An uninitialized submodule is an empty directory.
+            std::fs::remove_dir(&path)?;
+        }
+
+        // Register the project as soon as its working copy is detached, so a
+        // failure further on never leaves a clone the workspace does not know.
+        config
+            .projects
+            .insert(name.clone(), ProjectEntry::Url(url.clone()));
+        if pin {
+            if let Some(commit) = &commit {
+                config.set_project_pin(&name, Some(commit.clone()))?;
+            }
+        }
+        config.save_to_file(&meta_file_path)?;
+        gitignore::add_managed_entry(base_path, &name)?;
+        if repo != base {
+            // A project's own submodules also leave that project's status.
+            gitignore::add_managed_entry(&repo, &submodule.path)?;
+        }
+
+        untrack_submodule(&repo, submodule).with_context(|| {
+            format!(
+                "'{}' is registered as a project, but the repository still tracks it as a \
+                 submodule; remove it with 'git rm --cached {}' and its .gitmodules section",
+                name, submodule.path
+            )
+        })?;
+        let state = if initialized {
+            "moved into place"
+        } else {
+            "not cloned yet"
+        };
+        println!(
+            "     {} {} {} {}",
+            "│".bright_black(),
+            name.bright_white(),
+            url.bright_black(),
+            format!("({})", state).bright_black()
+        );
+    }
+
+    // Drop .gitmodules once its last section is gone.
+    let gitmodules = repo.join(".gitmodules");
+    let remaining = std::fs::read_to_string(&gitmodules).unwrap_or_default();
+    if remaining.trim().is_empty() {
+        git(&repo, &["rm", "--quiet", "-f", "--", ".gitmodules"])?;
+    }
+
+    println!(
+        "\n  {} {}",
+        "✅".green(),
+        "Submodules imported as projects".bold().green()
+    );
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        "Commit the repository to record the removal, then run 'meta git update' to clone \
+         any that were not checked out"
+            .italic()
+            .bright_black()
+    );
+    println!();
+    Ok(())
+}
+
+/// Stop the repository at `repo` tracking `submodule`: drop its gitlink from
+/// the index and its sections from `.git/config` and `.gitmodules`.
+fn untrack_submodule(repo: &Path, submodule: &Submodule) -> Result<()> {
+    // Untrack it before editing .gitmodules, and stage each edit: git
+    // refuses to remove a submodule while .gitmodules has unstaged changes.
+    git(repo, &["rm", "--cached", "--quiet", "--", &submodule.path])?;
+    let section = format!("submodule.{}", submodule.name);
+    let _ = git(repo, &["config", "--remove-section", &section]);
+    let _ = git(
+        repo,
+        &["config", "-f", ".gitmodules", "--remove-section", &section],
+    );
+    git(repo, &["add", "--", ".gitmodules"])?;
+    Ok(())
+}
+
+/// Turn the initialized submodule at `path` into a standalone clone: move its
+/// git directory out of the superproject's `.git/modules` into `path/.git`.
+fn detach_submodule(path: &Path) -> Result<()> {
+    let git_dir = PathBuf::from(git(path, &["rev-parse", "--absolute-git-dir"])?);
+    let dot_git = path.join(".git");
+    if git_dir == dot_git {
+        return Ok(()); // already a standalone repository
+    }
+    // Submodule git directories point back at their working tree, which would
+    // be wrong once the directory is moved.
+    let git_config = git_dir.join("config");
+    let _ = git(
+        path,
+        &[
+            "config",
+            "-f",
+            &git_config.to_string_lossy(),
+            "--unset",
+            "core.worktree",
+        ],
+    );
+    std::fs::remove_file(&dot_git)
+        .with_context(|| format!("Failed to remove {}", dot_git.display()))?;
+    std::fs::rename(&git_dir, &dot_git)
+        .with_context(|| format!("Failed to move {} into place", git_dir.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(["-c", "protocol.file.allow=always"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn gitmodules_are_parsed_in_order() {
+        let listing = "submodule.libs/core.path libs/core\n\
+                       submodule.libs/core.url ../core.git\n\
+                       submodule.docs.url https://example.com/docs.git\n\
+                       submodule.docs.path docs/\n\
+                       submodule.broken.path broken\n";
+        assert_eq!(
+            parse_gitmodules(listing),
+            vec![
+                Submodule {
+                    name: "libs/core".to_string(),
+                    path: "libs/core".to_string(),
+                    url: "../core.git".to_string(),
+                },
+                Submodule {
+                    name: "docs".to_string(),
+                    path: "docs".to_string(),
+                    url: "https://example.com/docs.git".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn relative_urls_resolve_against_the_remote() {
+        let scp = Some("git@github.com:org/app.git");
+        assert_eq!(
+            resolve_url("../lib.git", scp).unwrap(),
+            "git@github.com:org/lib.git"
+        );
+        assert_eq!(
+            resolve_url("../../other/lib.git", scp).unwrap(),
+            "git@github.com:other/lib.git"
+        );
+        let https = Some("https://github.com/org/app");
+        assert_eq!(
+            resolve_url("./lib", https).unwrap(),
+            "https://github.com/org/app/lib"
+        );
+        assert_eq!(
+            resolve_url("https://x.io/a.git", None).unwrap(),
+            "https://x.io/a.git"
+        );
+        assert!(resolve_url("../lib.git", None).is_err());
+    }
+
+    #[test]
+    fn submodules_become_projects_in_place() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("lib");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q"]);
+        std::fs::write(upstream.join("lib.rs"), "v1\n").unwrap();
+        run(&upstream, &["add", "."]);
+        run(&upstream, &["commit", "-qm", "one"]);
+        let head = git(&upstream, &["rev-parse", "HEAD"]).unwrap();
+        let url = upstream.to_string_lossy().to_string();
+
+        let root = tmp.path().join("workspace");
+        std::fs::create_dir(&root).unwrap();
+        run(&root, &["init", "-q"]);
+        MetaConfig::default()
+            .save_to_file(root.join(".meta"))
+            .unwrap();
+        run(&root, &["submodule", "add", "-q", &url, "libs/lib"]);
+        run(&root, &["add", "."]);
+        run(&root, &["commit", "-qm", "init"]);
+
+        import_submodules(&root, true, &root).unwrap();
+
+        let lib = root.join("libs/lib");
+        assert!(lib.join(".git").is_dir());
+        assert_eq!(git(&lib, &["rev-parse", "HEAD"]).unwrap(), head);
+        assert_eq!(git(&lib, &["status", "--porcelain"]).unwrap(), "");
+        assert!(!root.join(".gitmodules").exists());
+        assert!(!root.join(".git/modules/libs/lib").exists());
+        assert_eq!(git(&root, &["ls-files", "--", "libs"]).unwrap(), "");
+
+        let config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        assert_eq!(config.get_project_url("libs/lib"), Some(url));
+        assert_eq!(config.get_project_pin("libs/lib"), Some(head.as_str()));
+        let ignore = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert_eq!(gitignore::managed_entries(&ignore), ["libs/lib"]);
+
+        // Nothing left to import.
+        assert!(import_submodules(&root, false, &root).is_err());
+    }
+
+    #[test]
+    fn imported_submodules_stay_registered_when_a_later_one_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("lib");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q"]);
+        run(&upstream, &["commit", "-q", "--allow-empty", "-m", "one"]);
+        let url = upstream.to_string_lossy().to_string();
+
+        let root = tmp.path().join("workspace");
+        std::fs::create_dir(&root).unwrap();
+        run(&root, &["init", "-q"]);
+        MetaConfig::default()
+            .save_to_file(root.join(".meta"))
+            .unwrap();
+        run(&root, &["submodule", "add", "-q", &url, "libs/lib"]);
+        // A second section with no gitlink behind it: untracking it fails
+        // after the first submodule has been detached.
+        for (key, value) in [("path", "libs/extra"), ("url", url.as_str())] {
+            let key = format!("submodule.extra.{}", key);
+            run(&root, &["config", "-f", ".gitmodules", &key, value]);
+        }
+        run(&root, &["add", "."]);
+        run(&root, &["commit", "-qm", "init"]);
+
+        let err = import_submodules(&root, false, &root).unwrap_err();
+        assert!(format!("{:#}", err).contains("libs/extra"), "{:#}", err);
+
+        assert!(root.join("libs/lib/.git").is_dir());
+        assert_eq!(git(&root, &["ls-files", "--", "libs/lib"]).unwrap(), "");
+        let config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        assert_eq!(config.get_project_url("libs/lib"), Some(url));
+    }
+}
@@ -0,0 +1,371 @@
+//! `meta project vendor` / `meta project unvendor`: move a project between its
+//! own clone and an in-tree copy committed in the workspace repository.
+//!
+//! Vendoring drops the project's `.git`, takes it out of the managed
+//! `.gitignore` block, and records the commit it came from as `"vendored"` on
+//! its `.meta` entry next to the URL. Unvendoring clones the URL again, puts
+//! the clone's `.git` back under the in-tree files, and resets it to the
+//! recorded commit, so edits made while vendored show up as local changes.
+//! If the remote no longer has that commit, the project stays vendored.
+
+use super::locate_workspace_config;
+use crate::plugins::shared::{clone_with_auth, git, gitignore};
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::MetaConfig;
+use std::path::Path;
+
+/// Describe the work in the clone at `path` that exists only in its `.git`:
+/// a HEAD no remote-tracking branch contains, local branches with unpushed
+/// commits, stashes, or linked worktrees. `None` when there is none.
+fn unpushed_work(path: &Path) -> Result<Option<String>> {
+    if git(path, &["branch", "-r", "--contains", "HEAD"])?.is_empty() {
+        return Ok(Some(
+            "has a HEAD commit that is not on any remote branch; push it first".to_string(),
+        ));
+    }
+    let branches = git(
+        path,
+        &["for-each-ref", "--format=%(refname:short)", "refs/heads"],
+    )?;
+    let mut unpushed = Vec::new();
+    for branch in branches.lines() {
+        let ahead = git(path, &["rev-list", "--count", branch, "--not", "--remotes"])?;
+        if ahead != "0" {
+            unpushed.push(branch);
+        }
+    }
+    if !unpushed.is_empty() {
+        return Ok(Some(format!(
+            "has unpushed commits on {}; push or delete them first",
+            unpushed.join(", ")
+        )));
+    }
+    if !git(path, &["stash", "list"])?.is_empty() {
+        return Ok(Some(
+            "has stashed changes; apply or drop them first".to_string(),
+        ));
+    }
+    let worktrees = git(path, &["worktree", "list", "--porcelain"])?;
+    if worktrees
+        .lines()
+        .filter(|line| line.starts_with("worktree "))
+        .count()
+        > 1
+    {
+        return Ok(Some("has linked worktrees; remove them first".to_string()));
+    }
+    Ok(None)
+}
+
+/// Clone `url` next to `dir` and move only the clone's `.git` into `dir`, so
+/// the files already there (and any edits to them) are kept.
+pub(super) fn attach_clone(url: &str, dir: &Path) -> Result<()> {
+    let file_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let staging = dir.with_file_name(format!(".{}.clone", file_name));
+    if staging.exists() {
+        return Err(anyhow::anyhow!(
+            "Staging directory {} already exists; remove it and retry",
+            staging.display()
+        ));
+    }
+    let attached = clone_with_auth(url, &staging, false, None)
+        .map(drop)
+        .and_then(|_| {
+            std::fs::rename(staging.join(".git"), dir.join(".git"))
+                .context("Failed to move the clone into place")
+        });
+    let _ = std::fs::remove_dir_all(&staging);
+    attached
+}
+
+/// Convert `project_name` into an in-tree copy (see the module docs). The
+/// project must be a clean, non-bare clone of a remote with nothing that
+/// lives only in its `.git` (see [`unpushed_work`]).
+pub fn vendor_project(project_name: &str, base_path: &Path) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+    let url = config.get_project_url(project_name).ok_or_else(|| {
+        anyhow::anyhow!("Project '{}' not found in workspace config", project_name)
+    })?;
+    if config.is_vendored(project_name) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' is already vendored",
+            project_name
+        ));
+    }
+    if url.is_empty() || url.starts_with("local:") {
+        return Err(anyhow::anyhow!(
+            "Project '{}' has no remote; it is already committed with the workspace",
+            project_name
+        ));
+    }
+    if config.is_bare_repo(project_name) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' is a bare repository; convert it to a normal clone first",
+            project_name
+        ));
+    }
+
+    let project_path = base_path.join(project_name);
+    let git_dir = project_path.join(".git");
+    if !git_dir.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Project '{}' is not a cloned git repository",
+            project_name
+        ));
+    }
+    if !git(&project_path, &["status", "--porcelain"])?.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Project '{}' has uncommitted changes. Please commit or stash them first.",
+            project_name
+        ));
+    }
+    if let Some(reason) = unpushed_work(&project_path)? {
+        return Err(anyhow::anyhow!(
+            "Project '{}' {}; vendoring would delete it with the .git directory",
+            project_name,
+            reason
+        ));
+    }
+    let commit = git(&project_path, &["rev-parse", "HEAD"])?;
+
+    std::fs::remove_dir_all(&git_dir)
+        .with_context(|| format!("Failed to remove {}", git_dir.display()))?;
+    gitignore::remove_managed_entry(base_path, project_name)?;
+    config.project_metadata_mut(project_name)?.vendored = Some(commit.clone());
+    config.save_to_file(&meta_file_path)?;
+
+    println!(
+        "\n  {} {}",
+        "✅".green(),
+        format!("Vendored '{}'", project_name).bold().green()
+    );
+    println!(
+        "     {} {} {} {}",
+        "│".bright_black(),
+        "From:".bright_black(),
+        url.bright_white(),
+        format!("@ {}", &commit[..commit.len().min(12)]).bright_black()
+    );
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        format!("Commit it with: git add {}", project_name)
+            .italic()
+            .bright_black()
+    );
+    println!();
+    Ok(())
+}
+
+/// Restore a vendored project as a separate clone (see the module docs).
+pub fn unvendor_project(project_name: &str, base_path: &Path) -> Result<()> {
+    let meta_file_path = locate_workspace_config(base_path)?;
+    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+    let url = config.get_project_url(project_name).ok_or_else(|| {
+        anyhow::anyhow!("Project '{}' not found in workspace config", project_name)
+    })?;
+    let commit = config
+        .get_vendored_commit(project_name)
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Project '{}' is not vendored", project_name))?;
+
+    let project_path = base_path.join(project_name);
+    if !project_path.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Vendored project directory '{}' is missing",
+            project_name
+        ));
+    }
+    if project_path.join(".git").exists() {
+        return Err(anyhow::anyhow!(
+            "Project '{}' already has a .git directory",
+            project_name
+        ));
+    }
+
+    attach_clone(&url, &project_path)?;
+
+    // Without the recorded commit the in-tree edits cannot be told apart
+    // from upstream changes, so give the clone back and stay vendored.
+    if let Err(e) = git(&project_path, &["reset", "--quiet", "--mixed", &commit]) {
+        let _ = std::fs::remove_dir_all(project_path.join(".git"));
+        return Err(e.context(format!(
+            "Vendored commit {} of '{}' is not on the remote; it stays vendored",
+            commit, project_name
+        )));
+    }
+    gitignore::add_managed_entry(base_path, project_name)?;
+    config.project_metadata_mut(project_name)?.vendored = None;
+    config.save_to_file(&meta_file_path)?;
+
+    println!(
+        "\n  {} {}",
+        "✅".green(),
+        format!("Restored '{}' as a separate clone", project_name)
+            .bold()
+            .green()
+    );
+    println!(
+        "     {} {}",
+        "└".bright_black(),
+        format!(
+            "Stop tracking the in-tree copy with: git rm -r --cached {}",
+            project_name
+        )
+        .italic()
+        .bright_black()
+    );
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ProjectEntry;
+    use std::process::Command;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    /// An upstream repository with one commit and a workspace at
+    /// `workspace/` with a clone of it as the project `lib`.
+    fn workspace(tmp: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+        let upstream = tmp.join("upstream");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q"]);
+        std::fs::write(upstream.join("lib.rs"), "v1\n").unwrap();
+        run(&upstream, &["add", "."]);
+        run(&upstream, &["commit", "-qm", "one"]);
+
+        let root = tmp.join("workspace");
+        std::fs::create_dir(&root).unwrap();
+        run(&root, &["clone", "-q", upstream.to_str().unwrap(), "lib"]);
+        let mut config = MetaConfig::default();
+        config.projects.insert(
+            "lib".to_string(),
+            ProjectEntry::Url(upstream.to_string_lossy().to_string()),
+        );
+        config.save_to_file(root.join(".meta")).unwrap();
+        gitignore::add_managed_entry(&root, "lib").unwrap();
+        (upstream, root)
+    }
+
+    /// Vendoring must fail with `expected` in the error and keep the `.git`.
+    fn assert_refused(root: &Path, expected: &str) {
+        let err = vendor_project("lib", root).unwrap_err().to_string();
+        assert!(err.contains(expected), "{}", err);
+        assert!(root.join("lib/.git").is_dir());
+    }
+
+    #[test]
+    fn vendor_and_unvendor_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (upstream, root) = workspace(tmp.path());
+        let head = git(&root.join("lib"), &["rev-parse", "HEAD"]).unwrap();
+
+        vendor_project("lib", &root).unwrap();
+        assert!(!root.join("lib/.git").exists());
+        let config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        assert_eq!(config.get_vendored_commit("lib"), Some(head.as_str()));
+        let ignore = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert!(gitignore::managed_entries(&ignore).is_empty());
+        assert!(vendor_project("lib", &root).is_err());
+
+        // Upstream moves on and the vendored copy is edited in-tree.
+        std::fs::write(upstream.join("lib.rs"), "v2\n").unwrap();
+        run(&upstream, &["commit", "-qam", "two"]);
+        std::fs::write(root.join("lib/lib.rs"), "patched\n").unwrap();
+
+        unvendor_project("lib", &root).unwrap();
+        let lib = root.join("lib");
+        assert_eq!(git(&lib, &["rev-parse", "HEAD"]).unwrap(), head);
+        assert_eq!(git(&lib, &["status", "--porcelain"]).unwrap(), "M lib.rs");
+        assert!(!root.join(".lib.clone").exists());
+        let config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        assert!(!config.is_vendored("lib"));
+        assert!(unvendor_project("lib", &root).is_err());
+    }
+
+    #[test]
+    fn unvendor_keeps_the_project_vendored_when_its_commit_is_gone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_upstream, root) = workspace(tmp.path());
+        vendor_project("lib", &root).unwrap();
+        let missing = "0123456789abcdef0123456789abcdef01234567";
+        let mut config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        config.project_metadata_mut("lib").unwrap().vendored = Some(missing.to_string());
+        config.save_to_file(root.join(".meta")).unwrap();
+
+        let err = unvendor_project("lib", &root).unwrap_err().to_string();
+        assert!(err.contains(missing), "{}", err);
+        assert!(!root.join("lib/.git").exists());
+        assert!(root.join("lib/lib.rs").is_file());
+        let config = MetaConfig::load_from_file(root.join(".meta")).unwrap();
+        assert_eq!(config.get_vendored_commit("lib"), Some(missing));
+        let ignore = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert!(gitignore::managed_entries(&ignore).is_empty());
+    }
+
+    #[test]
+    fn refuses_a_head_that_is_not_pushed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, root) = workspace(tmp.path());
+        let lib = root.join("lib");
+        std::fs::write(lib.join("lib.rs"), "local\n").unwrap();
+        run(&lib, &["commit", "-qam", "local"]);
+        assert_refused(&root, "not on any remote branch");
+    }
+
+    #[test]
+    fn refuses_unpushed_branches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, root) = workspace(tmp.path());
+        let lib = root.join("lib");
+        run(&lib, &["checkout", "-qb", "feature"]);
+        std::fs::write(lib.join("lib.rs"), "feature\n").unwrap();
+        run(&lib, &["commit", "-qam", "feature"]);
+        run(&lib, &["checkout", "-q", "-"]);
+        assert_refused(&root, "unpushed commits on feature");
+    }
+
+    #[test]
+    fn refuses_stashes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, root) = workspace(tmp.path());
+        let lib = root.join("lib");
+        std::fs::write(lib.join("lib.rs"), "wip\n").unwrap();
+        run(&lib, &["stash", "-q"]);
+        assert_refused(&root, "stashed changes");
+    }
+
+    #[test]
+    fn refuses_linked_worktrees() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, root) = workspace(tmp.path());
+        let linked = tmp.path().join("lib-linked");
+        run(
+            &root.join("lib"),
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "--detach",
+                linked.to_str().unwrap(),
+            ],
+        );
+        assert_refused(&root, "linked worktrees");
+    }
+}
@@ -0,0 +1,286 @@
+//! `meta reconstruct` — rebuild a lost or corrupted workspace config from
+//! what is on disk.
+//!
+//! The workspace is walked for git repositories and for symlinks to
+//! repositories outside it, the way `meta project add` records them:
+//!
+//! - a checkout becomes an entry with its `origin` URL, or `local:<name>`
+//!   without a remote;
+//! - a bare-layout project (`<project>/.git` is a bare repository, with
+//!   worktrees beside it) is recorded with `"bare": true`;
+//! - a symlink to a repository outside the workspace becomes
+//!   `external:<url>`, or `external:local:<target>` without a remote.
+//!
+//! The walk does not descend into a repository, so worktrees and nested
+//! checkouts inside a project are not mistaken for projects. The result is a
+//! candidate config written next to the workspace for review; the real one
+//! is never touched.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use git2::Repository;
+use metarepo_core::{MetaConfig, ProjectEntry, ProjectMetadata, Table};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::plugins::project::get_remote_url;
+
+pub use self::plugin::ReconstructPlugin;
+
+mod plugin;
+
+/// Where the candidate config is written, relative to the workspace root.
+pub const CANDIDATE_FILENAME: &str = ".metarepo.reconstructed";
+
+/// How deep below the workspace root to look for repositories by default.
+pub const DEFAULT_DEPTH: usize = 3;
+
+/// Directory names never searched for projects.
+const SKIPPED_DIRS: [&str; 3] = [".git", "node_modules", "target"];
+
+/// What was found at one project path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Found {
+    /// A git checkout, or a bare-layout project when `bare`.
+    Repo { url: Option<String>, bare: bool },
+    /// A symlink to a repository outside the workspace.
+    External {
+        target: PathBuf,
+        url: Option<String>,
+    },
+}
+
+impl Found {
+    /// The project URL as `.meta` spells it.
+    pub fn config_url(&self, key: &str) -> String {
+        match self {
+            Found::Repo { url: Some(url), .. } => url.clone(),
+            Found::Repo { url: None, .. } => format!("local:{}", key),
+            Found::External { url: Some(url), .. } => format!("external:{}", url),
+            Found::External { target, url: None } => {
+                format!("external:local:{}", target.display())
+            }
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Found::Repo { bare: true, .. } => "bare",
+            Found::Repo { .. } => "repo",
+            Found::External { .. } => "external",
+        }
+    }
+}
+
+/// A repository's remote URL and whether it is bare. `None` when `path` is
+/// not a repository.
+fn inspect_repo(path: &Path) -> Option<(Option<String>, bool)> {
+    let repo = Repository::open(path).ok()?;
+    let url = get_remote_url(&repo).ok().flatten();
+    Some((url, repo.is_bare()))
+}
+
+/// Project key of `path` below `root`, with `/` separators.
+fn key_of(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+fn walk(root: &Path, dir: &Path, depth: usize, found: &mut BTreeMap<String, Found>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if SKIPPED_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+        let path = entry.path();
+        let Some(key) = key_of(root, &path) else {
+            continue;
+        };
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_symlink() {
+            // Links within the workspace point at something the walk finds
+            // by its own path.
+            let Ok(target) = path.canonicalize() else {
+                continue;
+            };
+            if target.starts_with(root) || !target.is_dir() {
+                continue;
+            }
+            if let Some((url, _)) = inspect_repo(&target) {
+                found.insert(key, Found::External { target, url });
+            }
+            continue;
+        }
+        if !file_type.is_dir() {
+            continue;
+        }
+        if path.join(".git").exists() {
+            if let Some((url, bare)) = inspect_repo(&path) {
+                found.insert(key, Found::Repo { url, bare });
+            }
+            continue;
+        }
+        if depth > 1 {
+            walk(root, &path, depth - 1, found);
+        }
+    }
+}
+
+/// The projects under `root`, up to `depth` directories down, keyed by path.
+/// `root` should be canonical so symlink targets compare against it.
+pub fn scan(root: &Path, depth: usize) -> BTreeMap<String, Found> {
+    let mut found = BTreeMap::new();
+    walk(root, root, depth, &mut found);
+    found
+}
+
+/// A workspace config listing `found`.
+pub fn candidate_config(found: &BTreeMap<String, Found>) -> MetaConfig {
+    let mut config = MetaConfig::default();
+    for (key, item) in found {
+        let url = item.config_url(key);
+        let entry = match item {
+            Found::Repo { bare: true, .. } => ProjectEntry::Metadata(ProjectMetadata {
+                url,
+                aliases: Vec::new(),
+                tags: Vec::new(),
+                scripts: Default::default(),
+                env: Default::default(),
+                worktree_init: None,
+                bare: Some(true),
+                default_script: None,
+                enabled: None,
+                depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
+            }),
+            _ => ProjectEntry::Url(url),
+        };
+        config.projects.insert(key.clone(), entry);
+    }
+    config
+}
+
+/// Print what was found as a table.
+pub fn print_found(root: &Path, found: &BTreeMap<String, Found>) {
+    println!(
+        "\n  {} {} {}",
+        "🔎".cyan(),
+        "Reconstructed projects".bold(),
+        format!("({})", root.display()).bright_black()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    if found.is_empty() {
+        println!("  No repositories found.");
+        return;
+    }
+    let mut table = Table::new(["Project", "Kind", "URL"]);
+    for (key, item) in found {
+        let mut url = item.config_url(key);
+        if let Found::External { target, .. } = item {
+            url = format!("{} → {}", url, target.display());
+        }
+        table.row([key.bold().to_string(), item.label().to_string(), url]);
+    }
+    table.indent(2).print();
+}
+
+/// Write `config` as JSON to `path`, refusing to replace an existing file
+/// unless `force`.
+pub fn write_candidate(config: &MetaConfig, path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        return Err(anyhow::anyhow!(
+            "{} already exists; pass --force to replace it",
+            path.display()
+        ));
+    }
+    let json = serde_json::to_string_pretty(config)?;
+    std::fs::write(path, json + "\n").with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn finds_checkouts_bare_projects_and_external_links() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("ws");
+        let outside = tmp.path().join("outside");
+
+        for dir in ["services/api", "docs", "tools", "notes"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::create_dir_all(&outside).unwrap();
+        git(&root.join("services/api"), &["init", "-q", "-b", "main"]);
+        git(
+            &root.join("services/api"),
+            &["remote", "add", "origin", "git@github.com:acme/api.git"],
+        );
+        git(&root.join("docs"), &["init", "-q", "-b", "main"]);
+        // A nested checkout inside a project is not a project of its own.
+        std::fs::create_dir_all(root.join("docs/vendor/lib")).unwrap();
+        git(&root.join("docs/vendor/lib"), &["init", "-q", "-b", "main"]);
+        git(
+            &root.join("tools"),
+            &["init", "-q", "--bare", "-b", "main", ".git"],
+        );
+        git(&outside, &["init", "-q", "-b", "main"]);
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&outside, root.join("shared")).unwrap();
+
+        let root = root.canonicalize().unwrap();
+        let found = scan(&root, DEFAULT_DEPTH);
+        assert_eq!(
+            found["services/api"],
+            Found::Repo {
+                url: Some("git@github.com:acme/api.git".to_string()),
+                bare: false
+            }
+        );
+        assert_eq!(found["docs"].config_url("docs"), "local:docs");
+        assert_eq!(found["tools"].label(), "bare");
+        assert!(!found.contains_key("notes"));
+        assert!(!found.contains_key("docs/vendor/lib"));
+        #[cfg(unix)]
+        assert!(found["shared"]
+            .config_url("shared")
+            .starts_with("external:local:"));
+
+        let config = candidate_config(&found);
+        assert!(config.is_bare_repo("tools"));
+        assert_eq!(
+            config.get_project_url("services/api").as_deref(),
+            Some("git@github.com:acme/api.git")
+        );
+        assert!(!scan(&root, 1).contains_key("services/api"));
+    }
+}
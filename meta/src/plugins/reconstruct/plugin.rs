@@ -0,0 +1,166 @@
+//! Plugin wiring for `meta reconstruct`.
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig, KNOWN_FILENAMES};
+use std::path::{Path, PathBuf};
+
+use super::{
+    candidate_config, print_found, scan, write_candidate, CANDIDATE_FILENAME, DEFAULT_DEPTH,
+};
+
+/// Registers the top-level `meta reconstruct` command.
+pub struct ReconstructPlugin;
+
+impl ReconstructPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ReconstructPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for ReconstructPlugin {
+    fn name(&self) -> &str {
+        "reconstruct"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("reconstruct")
+                .about("Rebuild a lost or corrupted workspace config from the repositories on disk")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Walk the workspace for git repositories and symlinks to repositories\n\
+                     outside it, and write a candidate config listing them as projects:\n\
+                     checkouts with their origin URL (local:<name> without a remote),\n\
+                     bare-layout projects with \"bare\": true, and external links as\n\
+                     external:<url> (external:local:<target> without a remote).\n\
+                     \n\
+                     The workspace root is the directory holding the workspace config,\n\
+                     even when that file no longer parses, else the current directory;\n\
+                     --path overrides it. The walk goes --depth directories down\n\
+                     (default: 3) and does not descend into repositories.\n\
+                     \n\
+                     The candidate is written to .metarepo.reconstructed (or --output)\n\
+                     for review; the workspace config itself is never changed. Copy over\n\
+                     the settings you still have, then move it over the workspace config.\n\
+                     \n\
+                     Examples:\n  \
+                       meta reconstruct\n  \
+                       meta reconstruct --stdout --depth 2\n  \
+                       meta reconstruct --path ~/src/acme --output /tmp/acme.json\n",
+                ))
+                .arg(
+                    Arg::new("path")
+                        .long("path")
+                        .value_name("DIR")
+                        .help("Workspace root to scan (default: the workspace, else the current directory)"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("3")
+                        .help("How many directories below the root to search"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .value_name("FILE")
+                        .help("Where to write the candidate config (default: .metarepo.reconstructed)"),
+                )
+                .arg(
+                    Arg::new("stdout")
+                        .long("stdout")
+                        .help("Print the candidate config instead of writing it")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("output"),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help("Replace an existing candidate file")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let root = match matches.get_one::<String>("path") {
+            Some(path) => PathBuf::from(path),
+            None => config
+                .meta_root()
+                .or_else(|| config_dir(&config.working_dir))
+                .unwrap_or_else(|| config.working_dir.clone()),
+        };
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Cannot read {}", root.display()))?;
+        let depth = matches
+            .get_one::<usize>("depth")
+            .copied()
+            .unwrap_or(DEFAULT_DEPTH)
+            .max(1);
+
+        let found = scan(&root, depth);
+        let candidate = candidate_config(&found);
+        if matches.get_flag("stdout") {
+            println!("{}", serde_json::to_string_pretty(&candidate)?);
+            return Ok(());
+        }
+
+        print_found(&root, &found);
+        if found.is_empty() {
+            return Ok(());
+        }
+        let output = matches
+            .get_one::<String>("output")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| root.join(CANDIDATE_FILENAME));
+        write_candidate(&candidate, &output, matches.get_flag("force"))?;
+        println!(
+            "\n  {} Wrote {} project(s) to {}",
+            "✓".green(),
+            found.len(),
+            output.display().to_string().bold()
+        );
+        println!(
+            "  {}",
+            "Review it, restore any other settings, then move it over the workspace config."
+                .bright_black()
+        );
+        println!();
+        Ok(())
+    }
+}
+
+/// The nearest directory from `start` up that holds a workspace config file,
+/// readable or not.
+fn config_dir(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| KNOWN_FILENAMES.iter().any(|name| dir.join(name).is_file()))
+        .map(Path::to_path_buf)
+}
+
+impl BasePlugin for ReconstructPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Rebuild a workspace config from the repositories on disk")
+    }
+}
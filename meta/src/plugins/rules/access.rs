@@ -0,0 +1,206 @@
+//! Access rules derived from each project's `"visibility"` in `.meta`: a
+//! public project must not depend on a restricted one. Unlike the
+//! `.rules.yaml` rules these come from the workspace config, so they apply to
+//! every project whatever rule set it resolves to.
+//!
+//! A dependency on another workspace project is recognised by a path
+//! dependency into its directory (`path = "../billing"` in Cargo.toml,
+//! `"file:../billing"` in package.json) or by a dependency named after the
+//! project's directory.
+
+use super::engine::{Severity, Violation};
+use anyhow::Result;
+use metarepo_core::{MetaConfig, Visibility};
+use std::path::{Path, PathBuf};
+
+/// The rule ID access violations are reported under.
+pub const ACCESS_RULE_ID: &str = "access:restricted";
+
+/// One dependency declared in a project manifest.
+struct Dependency {
+    name: String,
+    /// The directory of a path dependency.
+    path: Option<PathBuf>,
+    manifest: PathBuf,
+}
+
+/// Dependencies declared in `project_path`'s package.json and Cargo.toml.
+fn declared_dependencies(project_path: &Path) -> Result<Vec<Dependency>> {
+    let mut found = Vec::new();
+
+    let package_json = project_path.join("package.json");
+    if package_json.exists() {
+        let content = std::fs::read_to_string(&package_json)?;
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+            for section in ["dependencies", "devDependencies"] {
+                for (name, spec) in json[section].as_object().into_iter().flatten() {
+                    let spec = spec.as_str().unwrap_or_default();
+                    let path = spec
+                        .strip_prefix("file:")
+                        .or_else(|| spec.strip_prefix("link:"))
+                        .map(|p| project_path.join(p));
+                    found.push(Dependency {
+                        name: name.clone(),
+                        path,
+                        manifest: package_json.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    let cargo_toml = project_path.join("Cargo.toml");
+    if cargo_toml.exists() {
+        let content = std::fs::read_to_string(&cargo_toml)?;
+        if let Ok(toml) = toml::from_str::<toml::Value>(&content) {
+            for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+                let Some(deps) = toml.get(section).and_then(|d| d.as_table()) else {
+                    continue;
+                };
+                for (name, spec) in deps {
+                    let path = spec
+                        .get("path")
+                        .and_then(|p| p.as_str())
+                        .map(|p| project_path.join(p));
+                    found.push(Dependency {
+                        name: name.clone(),
+                        path,
+                        manifest: cargo_toml.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Whether `dependency` refers to the workspace project at `target_dir`.
+fn refers_to(dependency: &Dependency, target_dir: &Path) -> bool {
+    if let Some(path) = &dependency.path {
+        return match (path.canonicalize(), target_dir.canonicalize()) {
+            (Ok(path), Ok(target)) => path == target,
+            _ => false,
+        };
+    }
+    target_dir
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy() == dependency.name)
+}
+
+/// Check `project_name` against the access rules (see the module docs).
+pub fn check_access_rules(
+    config: &MetaConfig,
+    base_path: &Path,
+    project_name: &str,
+) -> Result<Vec<Violation>> {
+    if config.get_project_visibility(project_name) != Some(Visibility::Public) {
+        return Ok(Vec::new());
+    }
+    let mut restricted: Vec<&String> = config
+        .projects
+        .keys()
+        .filter(|name| {
+            *name != project_name
+                && config.get_project_visibility(name) == Some(Visibility::Restricted)
+        })
+        .collect();
+    if restricted.is_empty() {
+        return Ok(Vec::new());
+    }
+    restricted.sort();
+
+    let mut violations = Vec::new();
+    for dependency in declared_dependencies(&base_path.join(project_name))? {
+        for target in &restricted {
+            if refers_to(&dependency, &base_path.join(target)) {
+                violations.push(Violation {
+                    rule: ACCESS_RULE_ID.to_string(),
+                    message: format!(
+                        "Public project '{}' depends on restricted project '{}' ('{}')",
+                        project_name, target, dependency.name
+                    ),
+                    severity: Severity::Error,
+                    path: Some(dependency.manifest.clone()),
+                    fixable: false,
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::{ProjectEntry, ProjectMetadata};
+    use std::collections::HashMap;
+
+    fn project(config: &mut MetaConfig, name: &str, visibility: Option<Visibility>) {
+        config.projects.insert(
+            name.to_string(),
+            ProjectEntry::Metadata(ProjectMetadata {
+                url: format!("git@example.com:{}.git", name),
+                aliases: Vec::new(),
+                tags: Vec::new(),
+                scripts: HashMap::new(),
+                env: HashMap::new(),
+                worktree_init: None,
+                bare: None,
+                default_script: None,
+                enabled: None,
+                depth: None,
+                pinned: None,
+                vendored: None,
+                visibility,
+                vcs: None,
+                deprecated: None,
+            }),
+        );
+    }
+
+    #[test]
+    fn public_projects_may_not_depend_on_restricted_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut config = MetaConfig::default();
+        project(&mut config, "web", Some(Visibility::Public));
+        project(&mut config, "sdk", Some(Visibility::Public));
+        project(&mut config, "libs/billing", Some(Visibility::Restricted));
+        project(&mut config, "ui", None);
+        for dir in ["web", "sdk", "libs/billing", "ui"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(
+            root.join("web/Cargo.toml"),
+            "[package]\nname = \"web\"\n\n[dependencies]\nledger = { path = \"../libs/billing\" }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("sdk/package.json"),
+            r#"{"dependencies": {"billing": "^1.0.0", "ui": "file:../ui"}}"#,
+        )
+        .unwrap();
+        // Restricted and unclassified projects may depend on anything.
+        std::fs::write(
+            root.join("ui/package.json"),
+            r#"{"dependencies": {"billing": "^1.0.0"}}"#,
+        )
+        .unwrap();
+
+        let web = check_access_rules(&config, root, "web").unwrap();
+        assert_eq!(web.len(), 1);
+        assert_eq!(web[0].rule, "access:restricted");
+        assert!(web[0].message.contains("'libs/billing' ('ledger')"));
+        assert_eq!(
+            web[0].path.as_deref(),
+            Some(root.join("web/Cargo.toml").as_path())
+        );
+
+        let sdk = check_access_rules(&config, root, "sdk").unwrap();
+        assert_eq!(sdk.len(), 1);
+        assert!(sdk[0].message.contains("('billing')"));
+
+        assert!(check_access_rules(&config, root, "ui").unwrap().is_empty());
+    }
+}
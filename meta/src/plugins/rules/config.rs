@@ -1,5 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -31,6 +32,37 @@ pub struct RulesConfig {
 
     #[serde(default)]
     pub security: Vec<SecurityRule>,
+
+    #[serde(default)]
+    pub policies: Vec<PolicyRule>,
+}
+
+/// The ID and documentation every rule may carry, shown by
+/// `meta rules explain <id>` and next to the rule's violations.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleDocs {
+    /// Stable ID for the rule (default: `<kind>:<pattern or path>`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Page explaining the policy behind the rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs_url: Option<String>,
+    /// Why the rule exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+    /// What a violation looks like.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<String>,
+    /// How to fix a violation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl RuleDocs {
+    /// The explicit ID, or `default`.
+    pub fn id_or(&self, default: String) -> String {
+        self.id.clone().unwrap_or(default)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +70,8 @@ pub struct DirectoryRule {
     pub path: String,
     #[serde(default)]
     pub required: bool,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -46,6 +80,8 @@ pub struct DirectoryRule {
 pub struct ComponentRule {
     pub pattern: String,
     pub structure: Vec<String>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -55,6 +91,8 @@ pub struct FileRule {
     pub pattern: String,
     #[serde(default)]
     pub requires: HashMap<String, String>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -65,6 +103,8 @@ pub struct NamingRule {
     pub naming_pattern: String,
     #[serde(default)]
     pub case_style: Option<String>, // PascalCase, camelCase, snake_case, UPPER_CASE, kebab-case
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -79,6 +119,8 @@ pub struct DependencyRule {
     pub required: HashMap<String, String>, // package -> version constraint
     #[serde(default)]
     pub max_depth: Option<usize>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -94,6 +136,8 @@ pub struct ImportRule {
     pub require_absolute: bool,
     #[serde(default)]
     pub max_depth: Option<usize>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -109,6 +153,8 @@ pub struct DocumentationRule {
     pub min_description_length: Option<usize>,
     #[serde(default)]
     pub required_sections: Vec<String>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -124,6 +170,8 @@ pub struct SizeRule {
     pub max_functions: Option<usize>,
     #[serde(default)]
     pub max_complexity: Option<usize>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
@@ -139,10 +187,97 @@ pub struct SecurityRule {
     pub no_hardcoded_secrets: bool,
     #[serde(default)]
     pub forbidden_functions: Vec<String>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
     #[serde(default)]
     pub description: Option<String>,
 }
 
+/// A Rego policy evaluated with `opa` against a project's facts; see
+/// [`super::policy`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub name: String,
+    /// Policy file, relative to the workspace root.
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Inline policy source, instead of `file`.
+    #[serde(default)]
+    pub rego: Option<String>,
+    /// Query yielding the violation messages (default `data.metarepo.deny`).
+    #[serde(default)]
+    pub query: Option<String>,
+    /// `error` (default), `warning`, or `info`.
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(flatten)]
+    pub docs: RuleDocs,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl DirectoryRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("directory:{}", self.path))
+    }
+}
+
+impl ComponentRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("component:{}", self.pattern))
+    }
+}
+
+impl FileRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("file:{}", self.pattern))
+    }
+}
+
+impl NamingRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("naming:{}", self.pattern))
+    }
+}
+
+impl DependencyRule {
+    /// Dependency rules have no pattern, so the default ID uses the rule's
+    /// 1-based position in the `dependencies` list.
+    pub fn id(&self, position: usize) -> String {
+        self.docs.id_or(format!("dependency:{}", position))
+    }
+}
+
+impl ImportRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("import:{}", self.source_pattern))
+    }
+}
+
+impl DocumentationRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("documentation:{}", self.pattern))
+    }
+}
+
+impl SizeRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("size:{}", self.pattern))
+    }
+}
+
+impl SecurityRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("security:{}", self.pattern))
+    }
+}
+
+impl PolicyRule {
+    pub fn id(&self) -> String {
+        self.docs.id_or(format!("policy:{}", self.name))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum RuleType {
     Directory(DirectoryRule),
@@ -154,6 +289,7 @@ pub enum RuleType {
     Documentation(DocumentationRule),
     Size(SizeRule),
     Security(SecurityRule),
+    Policy(PolicyRule),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -180,6 +316,7 @@ impl RulesConfig {
             documentation: Vec::new(),
             size: Vec::new(),
             security: Vec::new(),
+            policies: Vec::new(),
         }
     }
 
@@ -189,11 +326,13 @@ impl RulesConfig {
                 DirectoryRule {
                     path: "src".to_string(),
                     required: true,
+                    docs: RuleDocs::default(),
                     description: Some("Source code directory".to_string()),
                 },
                 DirectoryRule {
                     path: "tests".to_string(),
                     required: false,
+                    docs: RuleDocs::default(),
                     description: Some("Test files directory".to_string()),
                 },
             ],
@@ -205,6 +344,7 @@ impl RulesConfig {
             documentation: Vec::new(),
             size: Vec::new(),
             security: Vec::new(),
+            policies: Vec::new(),
         }
     }
 
@@ -214,16 +354,19 @@ impl RulesConfig {
                 DirectoryRule {
                     path: "components".to_string(),
                     required: true,
+                    docs: RuleDocs::default(),
                     description: Some("Vue/React components directory".to_string()),
                 },
                 DirectoryRule {
                     path: "tests".to_string(),
                     required: true,
+                    docs: RuleDocs::default(),
                     description: Some("Test files directory".to_string()),
                 },
                 DirectoryRule {
                     path: "docs".to_string(),
                     required: false,
+                    docs: RuleDocs::default(),
                     description: Some("Documentation directory".to_string()),
                 },
             ],
@@ -235,6 +378,7 @@ impl RulesConfig {
                     "__tests__/[ComponentName].test.js".to_string(),
                     "[ComponentName].stories.js".to_string(),
                 ],
+                docs: RuleDocs::default(),
                 description: Some("Vue component structure".to_string()),
             }],
             files: vec![
@@ -244,11 +388,13 @@ impl RulesConfig {
                         ("test".to_string(), "__tests__/*.test.js".to_string()),
                         ("story".to_string(), "*.stories.js".to_string()),
                     ]),
+                    docs: RuleDocs::default(),
                     description: Some("Vue files must have tests and stories".to_string()),
                 },
                 FileRule {
                     pattern: "src/**/*.rs".to_string(),
                     requires: HashMap::from([("test".to_string(), "#[test]".to_string())]),
+                    docs: RuleDocs::default(),
                     description: Some("Rust files should have tests".to_string()),
                 },
             ],
@@ -256,6 +402,7 @@ impl RulesConfig {
                 pattern: "src/components/**/*.vue".to_string(),
                 naming_pattern: "[A-Z][a-zA-Z0-9]+\\.vue$".to_string(),
                 case_style: Some("PascalCase".to_string()),
+                docs: RuleDocs::default(),
                 description: Some("Vue components must be PascalCase".to_string()),
             }],
             dependencies: Vec::new(),
@@ -267,6 +414,7 @@ impl RulesConfig {
                 max_bytes: None,
                 max_functions: Some(10),
                 max_complexity: None,
+                docs: RuleDocs::default(),
                 description: Some("JavaScript files should be reasonably sized".to_string()),
             }],
             security: vec![SecurityRule {
@@ -278,8 +426,10 @@ impl RulesConfig {
                 require_https: true,
                 no_hardcoded_secrets: true,
                 forbidden_functions: vec!["eval".to_string(), "exec".to_string()],
+                docs: RuleDocs::default(),
                 description: Some("Basic security checks".to_string()),
             }],
+            policies: Vec::new(),
         }
     }
 
@@ -289,11 +439,13 @@ impl RulesConfig {
                 DirectoryRule {
                     path: "src/components".to_string(),
                     required: true,
+                    docs: RuleDocs::default(),
                     description: Some("React components directory".to_string()),
                 },
                 DirectoryRule {
                     path: "src/__tests__".to_string(),
                     required: true,
+                    docs: RuleDocs::default(),
                     description: Some("Test files directory".to_string()),
                 },
             ],
@@ -305,11 +457,13 @@ impl RulesConfig {
                     "[ComponentName].stories.tsx".to_string(),
                     "index.ts".to_string(),
                 ],
+                docs: RuleDocs::default(),
                 description: Some("React TypeScript component structure".to_string()),
             }],
             files: vec![FileRule {
                 pattern: "**/*.tsx".to_string(),
                 requires: HashMap::from([("test".to_string(), "*.test.tsx".to_string())]),
+                docs: RuleDocs::default(),
                 description: Some("TypeScript React files must have tests".to_string()),
             }],
             naming: vec![
@@ -317,12 +471,14 @@ impl RulesConfig {
                     pattern: "src/components/**/*.tsx".to_string(),
                     naming_pattern: "[A-Z][a-zA-Z0-9]+\\.tsx$".to_string(),
                     case_style: Some("PascalCase".to_string()),
+                    docs: RuleDocs::default(),
                     description: Some("React components must be PascalCase".to_string()),
                 },
                 NamingRule {
                     pattern: "src/hooks/**/*.ts".to_string(),
                     naming_pattern: "use[A-Z][a-zA-Z0-9]+\\.ts$".to_string(),
                     case_style: None,
+                    docs: RuleDocs::default(),
                     description: Some("React hooks must start with 'use'".to_string()),
                 },
             ],
@@ -331,8 +487,125 @@ impl RulesConfig {
             documentation: Vec::new(),
             size: Vec::new(),
             security: Vec::new(),
+            policies: Vec::new(),
         }
     }
+
+    /// The JSON Schema (draft-07) for `.rules.yaml`, printed by
+    /// `meta config schema --rules --json-schema`.
+    pub fn json_schema() -> Value {
+        fn rules(description: &str, required: &[&str], mut properties: Value) -> Value {
+            for field in ["id", "docs_url", "rationale", "example", "fix"] {
+                properties[field] = json!({ "type": "string" });
+            }
+            json!({
+                "type": "array",
+                "description": description,
+                "items": {
+                    "type": "object",
+                    "required": required,
+                    "additionalProperties": false,
+                    "properties": properties,
+                }
+            })
+        }
+        let text = json!({ "type": "string" });
+        let list = json!({ "type": "array", "items": { "type": "string" } });
+        let map = json!({ "type": "object", "additionalProperties": { "type": "string" } });
+        let flag = json!({ "type": "boolean" });
+        let limit = json!({ "type": "integer", "minimum": 0 });
+
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "metarepo rules",
+            "description": "Project structure rules checked by meta rules check.",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "directories": rules("Directories a project must or may have.", &["path"], json!({
+                    "path": text,
+                    "required": flag,
+                    "description": text,
+                })),
+                "components": rules(
+                    "Files every component directory must contain.",
+                    &["pattern", "structure"],
+                    json!({ "pattern": text, "structure": list, "description": text }),
+                ),
+                "files": rules("Companion files a file must have.", &["pattern"], json!({
+                    "pattern": text,
+                    "requires": map,
+                    "description": text,
+                })),
+                "naming": rules(
+                    "Naming patterns for files.",
+                    &["pattern", "naming_pattern"],
+                    json!({
+                        "pattern": text,
+                        "naming_pattern": text,
+                        "case_style": {
+                            "type": "string",
+                            "enum": [
+                                "PascalCase", "camelCase", "snake_case", "UPPER_CASE", "kebab-case"
+                            ]
+                        },
+                        "description": text,
+                    }),
+                ),
+                "dependencies": rules("Allowed, forbidden, and required dependencies.", &[], json!({
+                    "allowed": list,
+                    "forbidden": list,
+                    "required": map,
+                    "max_depth": limit,
+                    "description": text,
+                })),
+                "imports": rules("What source files may import.", &["source_pattern"], json!({
+                    "source_pattern": text,
+                    "allowed_imports": list,
+                    "forbidden_imports": list,
+                    "require_absolute": flag,
+                    "max_depth": limit,
+                    "description": text,
+                })),
+                "documentation": rules("Documentation files must have.", &["pattern"], json!({
+                    "pattern": text,
+                    "require_header": flag,
+                    "require_examples": flag,
+                    "min_description_length": limit,
+                    "required_sections": list,
+                    "description": text,
+                })),
+                "size": rules("Size limits for files.", &["pattern"], json!({
+                    "pattern": text,
+                    "max_lines": limit,
+                    "max_bytes": limit,
+                    "max_functions": limit,
+                    "max_complexity": limit,
+                    "description": text,
+                })),
+                "policies": rules(
+                    "Rego policies evaluated with opa against each project's facts.",
+                    &["name"],
+                    json!({
+                        "name": text,
+                        "file": text,
+                        "rego": text,
+                        "query": text,
+                        "severity": { "type": "string", "enum": ["error", "warning", "info"] },
+                        "description": text,
+                    }),
+                ),
+                "security": rules("Patterns and calls files must avoid.", &["pattern"], json!({
+                    "pattern": text,
+                    "forbidden_patterns": list,
+                    "require_https": flag,
+                    "no_hardcoded_secrets": flag,
+                    "forbidden_functions": list,
+                    "description": text,
+                })),
+            }
+        })
+    }
 }
 
 pub fn load_config<P: AsRef<Path>>(path: P) -> Result<RulesConfig> {
@@ -386,4 +659,24 @@ mod tests {
         assert_eq!(config.components.len(), parsed.components.len());
         assert_eq!(config.files.len(), parsed.files.len());
     }
+
+    #[test]
+    fn schema_describes_every_rule_field() {
+        let schema = RulesConfig::json_schema();
+        let written = serde_json::to_value(RulesConfig::default_config()).unwrap();
+        for (kind, rules) in written.as_object().unwrap() {
+            let described = &schema["properties"][kind]["items"]["properties"];
+            assert!(described.is_object(), "{} is not described", kind);
+            for rule in rules.as_array().unwrap() {
+                for field in rule.as_object().unwrap().keys() {
+                    assert!(
+                        !described[field].is_null(),
+                        "{}.{} is not described",
+                        kind,
+                        field
+                    );
+                }
+            }
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use super::config::{ComponentRule, DirectoryRule, FileRule, RulesConfig};
+use super::config::{ComponentRule, DirectoryRule, FileRule, RuleDocs, RulesConfig};
 use anyhow::Result;
 use colored::*;
 use std::collections::HashMap;
@@ -34,6 +34,7 @@ impl RuleCreator {
         let new_rule = DirectoryRule {
             path: path.to_string(),
             required,
+            docs: RuleDocs::default(),
             description: description.clone(),
         };
 
@@ -79,6 +80,7 @@ impl RuleCreator {
         let new_rule = ComponentRule {
             pattern: pattern.to_string(),
             structure,
+            docs: RuleDocs::default(),
             description: description.clone(),
         };
 
@@ -120,6 +122,7 @@ impl RuleCreator {
         let new_rule = FileRule {
             pattern: pattern.to_string(),
             requires,
+            docs: RuleDocs::default(),
             description: description.clone(),
         };
 
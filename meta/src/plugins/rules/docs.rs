@@ -311,6 +311,59 @@ pub fn print_security_rule_docs() {
     );
 }
 
+pub fn print_policy_rule_docs() {
+    println!("{}", "POLICY RULES".cyan().bold());
+    println!("{}", "════════════".blue());
+    println!();
+    println!("Policy rules are Rego policies, evaluated with the opa CLI against a");
+    println!("document of facts about each project (see `meta rules facts <project>`).");
+    println!();
+    println!("{}", "Configuration:".yellow());
+    println!("```yaml");
+    println!("policies:");
+    println!("  - name: licensed");
+    println!("    file: policies/license.rego   # relative to the workspace root");
+    println!("    severity: warning");
+    println!("    description: Every package declares a license");
+    println!("```");
+    println!();
+    println!("{}", "Properties:".yellow());
+    println!("  • {}: Name shown with each violation", "name".green());
+    println!(
+        "  • {}: Policy file, relative to the workspace root",
+        "file".green()
+    );
+    println!(
+        "  • {}: Inline policy source, instead of file",
+        "rego".green()
+    );
+    println!(
+        "  • {}: Query listing violation messages (default data.metarepo.deny)",
+        "query".green()
+    );
+    println!(
+        "  • {}: error (default), warning, or info",
+        "severity".green()
+    );
+    println!();
+    println!("{}", "Input:".yellow());
+    println!(
+        "  • {}: The project's .meta entry and name",
+        "input.project".cyan()
+    );
+    println!(
+        "  • {}: Every file, as relative paths",
+        "input.files".cyan()
+    );
+    println!(
+        "  • {}: Parsed package.json, Cargo.toml, pyproject.toml",
+        "input.manifests".cyan()
+    );
+    println!();
+    println!("{}", "Auto-fix:".yellow());
+    println!("  ❌ Policy violations must be fixed manually");
+}
+
 pub fn print_file_rule_docs() {
     println!("{}", "FILE RULES".cyan().bold());
     println!("{}", "══════════".blue());
@@ -426,6 +479,18 @@ fn print_configuration_format() {
     println!("  2. Project rules in .meta");
     println!("  3. Workspace .rules.yaml");
     println!();
+    println!("{}", "Rule IDs and docs (any rule):".yellow());
+    println!("  • {}: Stable ID shown with violations", "id".green());
+    println!(
+        "  • {}: Link to the team's page for the rule",
+        "docs_url".green()
+    );
+    println!(
+        "  • {}: Why the rule exists, an example violation, how to fix it",
+        "rationale, example, fix".green()
+    );
+    println!("  See them with: meta rules explain <rule-id>");
+    println!();
 }
 
 fn print_examples() {
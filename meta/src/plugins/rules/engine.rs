@@ -72,7 +72,7 @@ impl RuleEngine {
             if !dir_path.exists() {
                 if rule.required {
                     violations.push(Violation {
-                        rule: format!("directory:{}", rule.path),
+                        rule: rule.id(),
                         message: format!("Required directory '{}' is missing", rule.path),
                         severity: Severity::Error,
                         path: Some(dir_path.clone()),
@@ -80,7 +80,7 @@ impl RuleEngine {
                     });
                 } else {
                     violations.push(Violation {
-                        rule: format!("directory:{}", rule.path),
+                        rule: rule.id(),
                         message: format!("Optional directory '{}' is missing", rule.path),
                         severity: Severity::Info,
                         path: Some(dir_path.clone()),
@@ -121,7 +121,7 @@ impl RuleEngine {
                         let item_type = if is_dir { "directory" } else { "file" };
 
                         violations.push(Violation {
-                            rule: format!("component:{}", rule.pattern),
+                            rule: rule.id(),
                             message: format!(
                                 "Component '{}' is missing {} '{}'",
                                 component_name,
@@ -152,7 +152,7 @@ impl RuleEngine {
 
                     if !required_exists {
                         violations.push(Violation {
-                            rule: format!("file:{}", rule.pattern),
+                            rule: rule.id(),
                             message: format!(
                                 "File '{}' is missing required {} matching '{}'",
                                 file_path
@@ -280,7 +280,7 @@ impl RuleEngine {
                             .unwrap_or_default();
 
                         violations.push(Violation {
-                            rule: format!("naming:{}", rule.pattern),
+                            rule: rule.id(),
                             message: format!(
                                 "File '{}' does not match naming pattern '{}'{}",
                                 file_path
@@ -305,7 +305,7 @@ impl RuleEngine {
     fn check_dependency_rules(&self, project_path: &Path) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
-        for rule in &self.config.dependencies {
+        for (index, rule) in self.config.dependencies.iter().enumerate() {
             // Check package.json
             let package_json_path = project_path.join("package.json");
             if package_json_path.exists() {
@@ -320,7 +320,7 @@ impl RuleEngine {
                             || dev_deps.is_some_and(|d| d.contains_key(forbidden))
                         {
                             violations.push(Violation {
-                                rule: "dependency:forbidden".to_string(),
+                                rule: rule.id(index + 1),
                                 message: format!(
                                     "Forbidden dependency '{}' found in package.json",
                                     forbidden
@@ -338,7 +338,7 @@ impl RuleEngine {
                             && !dev_deps.is_some_and(|d| d.contains_key(pkg))
                         {
                             violations.push(Violation {
-                                rule: "dependency:required".to_string(),
+                                rule: rule.id(index + 1),
                                 message: format!(
                                     "Required dependency '{}' ({}) is missing",
                                     pkg, version
@@ -365,7 +365,7 @@ impl RuleEngine {
                             || dev_deps.and_then(|d| d.get(forbidden)).is_some()
                         {
                             violations.push(Violation {
-                                rule: "dependency:forbidden".to_string(),
+                                rule: rule.id(index + 1),
                                 message: format!(
                                     "Forbidden dependency '{}' found in Cargo.toml",
                                     forbidden
@@ -398,7 +398,7 @@ impl RuleEngine {
                     for forbidden in &rule.forbidden_imports {
                         if content.contains(forbidden) {
                             violations.push(Violation {
-                                rule: format!("import:{}", rule.source_pattern),
+                                rule: rule.id(),
                                 message: format!(
                                     "File '{}' contains forbidden import '{}'",
                                     file_path
@@ -417,7 +417,7 @@ impl RuleEngine {
                     // Check for relative imports if absolute required
                     if rule.require_absolute && relative_import_regex.is_match(&content) {
                         violations.push(Violation {
-                            rule: format!("import:{}", rule.source_pattern),
+                            rule: rule.id(),
                             message: format!(
                                 "File '{}' uses relative imports but absolute imports are required",
                                 file_path
@@ -452,7 +452,7 @@ impl RuleEngine {
                             || content.starts_with("#");
                         if !has_header {
                             violations.push(Violation {
-                                rule: format!("documentation:{}", rule.pattern),
+                                rule: rule.id(),
                                 message: format!(
                                     "File '{}' is missing required header documentation",
                                     file_path
@@ -472,7 +472,7 @@ impl RuleEngine {
                         for section in &rule.required_sections {
                             if !content.contains(section) {
                                 violations.push(Violation {
-                                    rule: format!("documentation:{}", rule.pattern),
+                                    rule: rule.id(),
                                     message: format!(
                                         "Documentation file '{}' is missing required section '{}'",
                                         file_path
@@ -507,7 +507,7 @@ impl RuleEngine {
                     if let Some(max_bytes) = rule.max_bytes {
                         if metadata.len() as usize > max_bytes {
                             violations.push(Violation {
-                                rule: format!("size:{}", rule.pattern),
+                                rule: rule.id(),
                                 message: format!(
                                     "File '{}' exceeds maximum size ({} bytes > {} bytes)",
                                     file_path
@@ -530,7 +530,7 @@ impl RuleEngine {
                             let line_count = content.lines().count();
                             if line_count > max_lines {
                                 violations.push(Violation {
-                                    rule: format!("size:{}", rule.pattern),
+                                    rule: rule.id(),
                                     message: format!(
                                         "File '{}' exceeds maximum line count ({} lines > {} lines)",
                                         file_path.strip_prefix(project_path).unwrap_or(&file_path).display(),
@@ -564,7 +564,7 @@ impl RuleEngine {
                         if let Ok(pattern) = regex::Regex::new(pattern_str) {
                             if pattern.is_match(&content) {
                                 violations.push(Violation {
-                                    rule: format!("security:{}", rule.pattern),
+                                    rule: rule.id(),
                                     message: format!(
                                         "File '{}' contains forbidden pattern matching '{}'",
                                         file_path
@@ -585,7 +585,7 @@ impl RuleEngine {
                     for func in &rule.forbidden_functions {
                         if content.contains(func) {
                             violations.push(Violation {
-                                rule: format!("security:{}", rule.pattern),
+                                rule: rule.id(),
                                 message: format!(
                                     "File '{}' uses forbidden function '{}'",
                                     file_path
@@ -604,7 +604,7 @@ impl RuleEngine {
                     // Check for non-HTTPS URLs
                     if rule.require_https && content.contains("http://") {
                         violations.push(Violation {
-                            rule: format!("security:{}", rule.pattern),
+                            rule: rule.id(),
                             message: format!(
                                 "File '{}' contains non-HTTPS URL",
                                 file_path
@@ -634,11 +634,8 @@ pub fn fix_violations<P: AsRef<Path>>(project_path: P, violations: &[Violation])
         }
 
         if let Some(path) = &violation.path {
-            // Only fix directory creation for now
-            if (violation.rule.starts_with("directory:")
-                || violation.rule.starts_with("component:"))
-                && !path.exists()
-            {
+            // Only missing directories are fixable for now
+            if !path.exists() {
                 std::fs::create_dir_all(path)?;
                 println!("  Created directory: {}", path.display());
             }
@@ -651,7 +648,9 @@ pub fn fix_violations<P: AsRef<Path>>(project_path: P, violations: &[Violation])
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::plugins::rules::config::{DirectoryRule, NamingRule, SecurityRule, SizeRule};
+    use crate::plugins::rules::config::{
+        DirectoryRule, NamingRule, RuleDocs, SecurityRule, SizeRule,
+    };
     use std::fs;
     use tempfile::tempdir;
 
@@ -662,6 +661,7 @@ mod tests {
             directories: vec![DirectoryRule {
                 path: "src".to_string(),
                 required: true,
+                docs: RuleDocs::default(),
                 description: None,
             }],
             components: Vec::new(),
@@ -672,6 +672,7 @@ mod tests {
             documentation: Vec::new(),
             size: Vec::new(),
             security: Vec::new(),
+            policies: Vec::new(),
         };
 
         let engine = RuleEngine::new(config);
@@ -715,6 +716,7 @@ mod tests {
                 pattern: "*.tsx".to_string(),
                 naming_pattern: "^[A-Z][a-zA-Z0-9]+\\.tsx$".to_string(),
                 case_style: Some("PascalCase".to_string()),
+                docs: RuleDocs::default(),
                 description: Some("React components must be PascalCase".to_string()),
             }],
             dependencies: Vec::new(),
@@ -722,6 +724,7 @@ mod tests {
             documentation: Vec::new(),
             size: Vec::new(),
             security: Vec::new(),
+            policies: Vec::new(),
         };
 
         let engine = RuleEngine::new(config);
@@ -759,9 +762,11 @@ mod tests {
                 max_bytes: None,
                 max_functions: None,
                 max_complexity: None,
+                docs: RuleDocs::default(),
                 description: Some("JavaScript files should be reasonably sized".to_string()),
             }],
             security: Vec::new(),
+            policies: Vec::new(),
         };
 
         let engine = RuleEngine::new(config);
@@ -798,8 +803,10 @@ mod tests {
                 require_https: true,
                 no_hardcoded_secrets: true,
                 forbidden_functions: vec!["eval".to_string()],
+                docs: RuleDocs::default(),
                 description: Some("Basic security checks".to_string()),
             }],
+            policies: Vec::new(),
         };
 
         let engine = RuleEngine::new(config);
@@ -0,0 +1,489 @@
+//! `meta rules explain <rule-id>`: what a rule checks, why, what a violation
+//! looks like, and how to fix one. The why, example, and fix come from the
+//! rule's own `rationale`, `example`, and `fix` in `.rules.yaml` (see
+//! [`RuleDocs`]); rules without them get a generic text for their kind.
+
+use super::access::ACCESS_RULE_ID;
+use super::config::{RuleDocs, RulesConfig};
+use anyhow::{anyhow, Result};
+use colored::*;
+
+/// One rule, described for `meta rules explain`.
+#[derive(Debug, Clone)]
+pub struct RuleEntry {
+    pub id: String,
+    /// The rule kind, e.g. `directory` or `policy`.
+    pub kind: &'static str,
+    pub description: Option<String>,
+    pub docs: RuleDocs,
+    /// What the rule checks, generated from its settings.
+    pub checks: String,
+    /// A violation and a fix for when the rule does not describe its own.
+    pub default_example: String,
+    pub default_fix: String,
+}
+
+impl RuleEntry {
+    fn new(
+        id: String,
+        kind: &'static str,
+        description: &Option<String>,
+        docs: &RuleDocs,
+        checks: String,
+    ) -> Self {
+        Self {
+            id,
+            kind,
+            description: description.clone(),
+            docs: docs.clone(),
+            checks,
+            default_example: String::new(),
+            default_fix: String::new(),
+        }
+    }
+
+    fn example(mut self, example: impl Into<String>, fix: impl Into<String>) -> Self {
+        self.default_example = example.into();
+        self.default_fix = fix.into();
+        self
+    }
+}
+
+/// Every rule in `config`, plus the built-in access rule, in `.rules.yaml`
+/// order.
+pub fn rule_entries(config: &RulesConfig) -> Vec<RuleEntry> {
+    let mut entries = Vec::new();
+    for rule in &config.directories {
+        let level = if rule.required {
+            "an error"
+        } else {
+            "informational"
+        };
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "directory",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "The directory '{}' exists in the project; its absence is {}.",
+                    rule.path, level
+                ),
+            )
+            .example(
+                format!(
+                    "{} directory '{}' is missing",
+                    if rule.required {
+                        "Required"
+                    } else {
+                        "Optional"
+                    },
+                    rule.path
+                ),
+                format!(
+                    "Create {}/, or run 'meta rules check --fix' to create it.",
+                    rule.path
+                ),
+            ),
+        );
+    }
+    for rule in &config.components {
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "component",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "Every directory matching '{}' contains: {}.",
+                    rule.pattern,
+                    rule.structure.join(", ")
+                ),
+            )
+            .example(
+                format!(
+                    "Component 'Button' is missing file '{}'",
+                    rule.structure
+                        .first()
+                        .map(|item| item.replace("[ComponentName]", "Button"))
+                        .unwrap_or_default()
+                ),
+                "Add the missing files; --fix creates missing directories.",
+            ),
+        );
+    }
+    for rule in &config.files {
+        let mut requires: Vec<String> = rule
+            .requires
+            .iter()
+            .map(|(kind, pattern)| format!("{} ({})", kind, pattern))
+            .collect();
+        requires.sort();
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "file",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "Every file matching '{}' has: {}.",
+                    rule.pattern,
+                    requires.join(", ")
+                ),
+            )
+            .example(
+                format!(
+                    "File 'src/app.js' is missing required {}",
+                    requires.first().cloned().unwrap_or_default()
+                ),
+                "Add the companion file next to the file named in the violation.",
+            ),
+        );
+    }
+    for rule in &config.naming {
+        let style = rule
+            .case_style
+            .as_ref()
+            .map(|s| format!(" ({})", s))
+            .unwrap_or_default();
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "naming",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "The names of files matching '{}' match /{}/{}.",
+                    rule.pattern, rule.naming_pattern, style
+                ),
+            )
+            .example(
+                format!(
+                    "File 'src/my_file.ext' does not match naming pattern '{}'",
+                    rule.naming_pattern
+                ),
+                format!("Rename the file to follow the pattern{}.", style),
+            ),
+        );
+    }
+    for (index, rule) in config.dependencies.iter().enumerate() {
+        let mut parts = Vec::new();
+        if !rule.forbidden.is_empty() {
+            parts.push(format!("never depends on {}", rule.forbidden.join(", ")));
+        }
+        if !rule.required.is_empty() {
+            let mut required: Vec<&String> = rule.required.keys().collect();
+            required.sort();
+            let required: Vec<&str> = required.iter().map(|s| s.as_str()).collect();
+            parts.push(format!("depends on {}", required.join(", ")));
+        }
+        entries.push(
+            RuleEntry::new(
+                rule.id(index + 1),
+                "dependency",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "The project's package.json or Cargo.toml {}.",
+                    if parts.is_empty() {
+                        "declares any dependencies".to_string()
+                    } else {
+                        parts.join(" and ")
+                    }
+                ),
+            )
+            .example(
+                "Forbidden dependency 'left-pad' found in package.json",
+                "Remove forbidden dependencies and add the required ones.",
+            ),
+        );
+    }
+    for rule in &config.imports {
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "import",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "Files matching '{}' import only what the rule allows{}.",
+                    rule.source_pattern,
+                    if rule.forbidden_imports.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" and never {}", rule.forbidden_imports.join(", "))
+                    }
+                ),
+            )
+            .example(
+                format!(
+                    "File 'src/lib.rs' contains forbidden import '{}'",
+                    rule.forbidden_imports.first().cloned().unwrap_or_default()
+                ),
+                "Change the import to one the rule allows.",
+            ),
+        );
+    }
+    for rule in &config.documentation {
+        let mut needs = Vec::new();
+        if rule.require_header {
+            needs.push("a header comment".to_string());
+        }
+        if rule.require_examples {
+            needs.push("examples".to_string());
+        }
+        needs.extend(
+            rule.required_sections
+                .iter()
+                .map(|s| format!("a '{}' section", s)),
+        );
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "documentation",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "Files matching '{}' have {}.",
+                    rule.pattern,
+                    if needs.is_empty() {
+                        "documentation".to_string()
+                    } else {
+                        needs.join(", ")
+                    }
+                ),
+            )
+            .example(
+                "File 'src/lib.rs' is missing required header documentation",
+                "Add the missing documentation to the file.",
+            ),
+        );
+    }
+    for rule in &config.size {
+        let mut limits = Vec::new();
+        if let Some(lines) = rule.max_lines {
+            limits.push(format!("{} lines", lines));
+        }
+        if let Some(bytes) = rule.max_bytes {
+            limits.push(format!("{} bytes", bytes));
+        }
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "size",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "Files matching '{}' stay within {}.",
+                    rule.pattern,
+                    limits.join(" and ")
+                ),
+            )
+            .example(
+                match (rule.max_lines, rule.max_bytes) {
+                    (Some(lines), _) => format!(
+                        "File 'src/big.js' exceeds maximum line count ({} lines > {} lines)",
+                        lines + 1,
+                        lines
+                    ),
+                    (None, bytes) => format!(
+                        "File 'src/big.js' exceeds maximum size ({} bytes > {} bytes)",
+                        bytes.unwrap_or(0) + 1,
+                        bytes.unwrap_or(0)
+                    ),
+                },
+                "Split the file into smaller modules.",
+            ),
+        );
+    }
+    for rule in &config.security {
+        let mut checks = Vec::new();
+        if !rule.forbidden_patterns.is_empty() {
+            checks.push(format!(
+                "text matching {}",
+                rule.forbidden_patterns.join(", ")
+            ));
+        }
+        if !rule.forbidden_functions.is_empty() {
+            checks.push(format!("calls to {}", rule.forbidden_functions.join(", ")));
+        }
+        if rule.no_hardcoded_secrets {
+            checks.push("hardcoded secrets".to_string());
+        }
+        if rule.require_https {
+            checks.push("http:// URLs".to_string());
+        }
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "security",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "Files matching '{}' contain no {}.",
+                    rule.pattern,
+                    checks.join(", ")
+                ),
+            )
+            .example(
+                "File 'src/api.js' uses forbidden function 'eval'",
+                "Remove the flagged code; keep secrets in environment variables \
+                 or a secret store.",
+            ),
+        );
+    }
+    for rule in &config.policies {
+        let source = match (&rule.file, &rule.rego) {
+            (Some(file), _) => file.clone(),
+            _ => "an inline policy".to_string(),
+        };
+        entries.push(
+            RuleEntry::new(
+                rule.id(),
+                "policy",
+                &rule.description,
+                &rule.docs,
+                format!(
+                    "The Rego policy in {} ({}) reports nothing for the project's facts \
+                     (see 'meta rules facts <project>').",
+                    source,
+                    rule.query
+                        .as_deref()
+                        .unwrap_or(super::policy::DEFAULT_QUERY)
+                ),
+            )
+            .example(
+                "Whatever message the policy returns",
+                format!("Read {} to see what it expects.", source),
+            ),
+        );
+    }
+    entries.push(
+        RuleEntry::new(
+            ACCESS_RULE_ID.to_string(),
+            "access",
+            &Some("Public projects do not depend on restricted ones".to_string()),
+            &RuleDocs::default(),
+            "A project marked \"visibility\": \"public\" in .meta has no dependency on a \
+             project marked \"restricted\"."
+                .to_string(),
+        )
+        .example(
+            "Public project 'web' depends on restricted project 'billing' ('billing')",
+            "Remove the dependency, or change one project's visibility in .meta.",
+        ),
+    );
+    entries
+}
+
+/// The rule with ID `id` in `config`, or an error listing the IDs there are.
+pub fn find_rule(config: &RulesConfig, id: &str) -> Result<RuleEntry> {
+    let entries = rule_entries(config);
+    if let Some(entry) = entries.iter().find(|e| e.id == id) {
+        return Ok(entry.clone());
+    }
+    let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+    Err(anyhow!(
+        "No rule with ID '{}'. Rule IDs: {}",
+        id,
+        ids.join(", ")
+    ))
+}
+
+/// Print `entry` for `meta rules explain`.
+pub fn print_explanation(entry: &RuleEntry) {
+    println!("\n  {} {}", "📘".cyan(), entry.id.bold());
+    if let Some(description) = &entry.description {
+        println!("  {}", description.italic());
+    }
+    println!();
+    let why = entry
+        .docs
+        .rationale
+        .clone()
+        .or_else(|| entry.description.clone())
+        .unwrap_or_else(|| "Not given; add a rationale to the rule in .rules.yaml.".to_string());
+    let example = entry
+        .docs
+        .example
+        .clone()
+        .unwrap_or_else(|| entry.default_example.clone());
+    let fix = entry
+        .docs
+        .fix
+        .clone()
+        .unwrap_or_else(|| entry.default_fix.clone());
+    let rows = [
+        ("Kind", format!("{} rule", entry.kind)),
+        ("Checks", entry.checks.clone()),
+        ("Why", why),
+        ("Violation", example),
+        ("Fix", fix),
+    ];
+    for (label, text) in rows {
+        let label = format!("{:<10}", format!("{}:", label));
+        println!("  {} {}", label.bright_black(), text);
+    }
+    if let Some(url) = &entry.docs.docs_url {
+        println!("  {} {}", "Docs:     ".bright_black(), url.underline());
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RulesConfig {
+        serde_yaml::from_str(
+            r#"
+directories:
+  - path: src
+    required: true
+    description: Source code directory
+  - path: docs
+    id: has-docs
+    docs_url: https://wiki.example.com/docs-policy
+    rationale: Every project is documented
+    fix: Run 'make docs'
+dependencies:
+  - forbidden: [left-pad]
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rules_have_default_or_explicit_ids() {
+        let config = config();
+        let ids: Vec<String> = rule_entries(&config).into_iter().map(|e| e.id).collect();
+        assert_eq!(
+            ids,
+            vec!["directory:src", "has-docs", "dependency:1", ACCESS_RULE_ID]
+        );
+
+        let docs = find_rule(&config, "has-docs").unwrap();
+        assert_eq!(
+            docs.docs.docs_url.as_deref(),
+            Some("https://wiki.example.com/docs-policy")
+        );
+        assert_eq!(
+            docs.docs.rationale.as_deref(),
+            Some("Every project is documented")
+        );
+        assert!(docs.checks.contains("'docs'"), "{}", docs.checks);
+
+        let err = find_rule(&config, "directory:lib").unwrap_err().to_string();
+        assert!(err.contains("directory:src, has-docs"), "{}", err);
+    }
+
+    #[test]
+    fn docs_round_trip_through_yaml() {
+        let config = config();
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert!(yaml.contains("id: has-docs"), "{}", yaml);
+        assert!(!yaml.contains("rationale: null"), "{}", yaml);
+        let reparsed: RulesConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(reparsed.directories[1].docs, config.directories[1].docs);
+    }
+}
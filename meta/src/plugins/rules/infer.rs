@@ -0,0 +1,340 @@
+//! `meta rules init --infer`: suggest a rules config from what the projects
+//! already look like, so a team starts from its own conventions instead of a
+//! generic sample.
+//!
+//! Three things are inferred, each only where the evidence is unanimous:
+//!
+//! - directory rules for the top-level directories the projects share
+//!   (required when every project has one, optional when at least half do);
+//! - naming rules for source files under a top-level directory whose names all
+//!   follow one case style;
+//! - size rules capping each such directory's files of one language a little
+//!   above the largest that exists today.
+//!
+//! The result passes `meta rules check` on the projects it was inferred from.
+
+use super::config::{DirectoryRule, NamingRule, RuleDocs, RulesConfig, SizeRule};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Dependency and build output directories, which say nothing about the
+/// project's own layout.
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "out",
+    "vendor",
+    "coverage",
+    "__pycache__",
+];
+
+/// Extensions of files whose names and sizes are worth a rule.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "js", "jsx", "ts", "tsx", "vue", "svelte", "py", "go", "rb", "java", "kt", "swift", "c",
+    "h", "cpp", "hpp", "cs", "php",
+];
+
+/// Manifests that identify a project's language.
+const MANIFESTS: &[(&str, &str)] = &[
+    ("Cargo.toml", "Rust"),
+    ("package.json", "JavaScript/TypeScript"),
+    ("pyproject.toml", "Python"),
+    ("setup.py", "Python"),
+    ("go.mod", "Go"),
+    ("pom.xml", "Java"),
+    ("build.gradle", "Java/Kotlin"),
+    ("Gemfile", "Ruby"),
+    ("composer.json", "PHP"),
+];
+
+/// Fewest files of a kind before their names or sizes count as a convention.
+const MIN_FILES: usize = 3;
+
+/// What [`infer_rules`] found.
+#[derive(Debug, Default)]
+pub struct Inference {
+    pub rules: RulesConfig,
+    /// Languages detected from manifests, with how many projects use each.
+    pub languages: BTreeMap<String, usize>,
+    /// How many projects were inspected.
+    pub projects: usize,
+}
+
+/// Source files of one extension under one top-level directory, across all
+/// projects.
+#[derive(Debug, Default)]
+struct SourceFiles {
+    names: Vec<String>,
+    max_lines: usize,
+}
+
+/// Suggest rules capturing the conventions `projects` (project directories)
+/// share.
+pub fn infer_rules(projects: &[PathBuf]) -> Result<Inference> {
+    let mut inference = Inference {
+        projects: projects.len(),
+        ..Default::default()
+    };
+    let mut dir_counts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut sources: BTreeMap<(String, String), SourceFiles> = BTreeMap::new();
+
+    for project in projects {
+        for dir in top_level_dirs(project)? {
+            *dir_counts.entry(dir).or_default() += 1;
+        }
+        let languages: BTreeSet<&str> = MANIFESTS
+            .iter()
+            .filter(|(manifest, _)| project.join(manifest).exists())
+            .map(|(_, language)| *language)
+            .collect();
+        for language in languages {
+            *inference.languages.entry(language.to_string()).or_default() += 1;
+        }
+        collect_sources(project, &mut sources);
+    }
+
+    let total = projects.len();
+    for (dir, count) in dir_counts {
+        if count == total || (total > 1 && count * 2 >= total) {
+            inference.rules.directories.push(DirectoryRule {
+                path: dir,
+                required: count == total,
+                docs: RuleDocs::default(),
+                description: Some(format!("Found in {} of {} project(s)", count, total)),
+            });
+        }
+    }
+
+    for ((dir, ext), files) in sources {
+        if files.names.len() < MIN_FILES {
+            continue;
+        }
+        let pattern = format!("{}/**/*.{}", dir, ext);
+        if let Some(style) = shared_case_style(&files.names, &ext) {
+            inference.rules.naming.push(NamingRule {
+                pattern: pattern.clone(),
+                naming_pattern: style.naming_pattern(&ext),
+                case_style: Some(style.label().to_string()),
+                docs: RuleDocs::default(),
+                description: Some(format!(
+                    "All {} .{} files under {}/ are {}",
+                    files.names.len(),
+                    ext,
+                    dir,
+                    style.label()
+                )),
+            });
+        }
+        inference.rules.size.push(SizeRule {
+            pattern,
+            max_lines: Some((files.max_lines / 100 + 1) * 100),
+            max_bytes: None,
+            max_functions: None,
+            max_complexity: None,
+            docs: RuleDocs::default(),
+            description: Some(format!(
+                "The largest .{} file under {}/ has {} lines",
+                ext, dir, files.max_lines
+            )),
+        });
+    }
+
+    Ok(inference)
+}
+
+fn is_skipped(name: &str) -> bool {
+    name.starts_with('.') || SKIPPED_DIRS.contains(&name)
+}
+
+fn top_level_dirs(project: &Path) -> Result<Vec<String>> {
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(project)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_dir() && !is_skipped(&name) {
+            dirs.push(name);
+        }
+    }
+    Ok(dirs)
+}
+
+/// Add `project`'s source files below its top-level directories to `sources`.
+fn collect_sources(project: &Path, sources: &mut BTreeMap<(String, String), SourceFiles>) {
+    let walker = WalkDir::new(project)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !is_skipped(&e.file_name().to_string_lossy()));
+    for entry in walker.flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(project) else {
+            continue;
+        };
+        let mut components = relative.components();
+        let (Some(top), Some(_)) = (components.next(), components.next()) else {
+            continue; // files at the project root have no directory to scope to
+        };
+        let Some(ext) = entry.path().extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+        if !SOURCE_EXTENSIONS.contains(&ext) {
+            continue;
+        }
+        let files = sources
+            .entry((
+                top.as_os_str().to_string_lossy().to_string(),
+                ext.to_string(),
+            ))
+            .or_default();
+        files
+            .names
+            .push(entry.file_name().to_string_lossy().to_string());
+        if let Ok(content) = std::fs::read_to_string(entry.path()) {
+            files.max_lines = files.max_lines.max(content.lines().count());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseStyle {
+    Pascal,
+    Snake,
+    Kebab,
+    Camel,
+}
+
+impl CaseStyle {
+    fn label(self) -> &'static str {
+        match self {
+            CaseStyle::Pascal => "PascalCase",
+            CaseStyle::Snake => "snake_case",
+            CaseStyle::Kebab => "kebab-case",
+            CaseStyle::Camel => "camelCase",
+        }
+    }
+
+    /// Regex for a file name in this style: the part before the first dot
+    /// follows the style, and any further dotted parts (`.test`) are free.
+    fn naming_pattern(self, ext: &str) -> String {
+        let stem = match self {
+            CaseStyle::Pascal => "[A-Z][a-zA-Z0-9]*",
+            CaseStyle::Snake => "[a-z0-9_]+",
+            CaseStyle::Kebab => "[a-z0-9-]+",
+            CaseStyle::Camel => "[a-z][a-zA-Z0-9]*",
+        };
+        format!("^{}(\\.[A-Za-z0-9_-]+)*\\.{}$", stem, regex::escape(ext))
+    }
+
+    /// Whether `stem` shows this style rather than just fitting it: a plain
+    /// lowercase word fits snake, kebab, and camel case alike.
+    fn is_evident_in(self, stem: &str) -> bool {
+        match self {
+            CaseStyle::Pascal => true,
+            CaseStyle::Snake => stem.contains('_'),
+            CaseStyle::Kebab => stem.contains('-'),
+            CaseStyle::Camel => stem.chars().any(|c| c.is_ascii_uppercase()),
+        }
+    }
+}
+
+/// The case style every name in `names` follows, if any name shows it.
+fn shared_case_style(names: &[String], ext: &str) -> Option<CaseStyle> {
+    [
+        CaseStyle::Pascal,
+        CaseStyle::Snake,
+        CaseStyle::Kebab,
+        CaseStyle::Camel,
+    ]
+    .into_iter()
+    .find(|style| {
+        let Ok(pattern) = Regex::new(&style.naming_pattern(ext)) else {
+            return false;
+        };
+        names.iter().all(|name| pattern.is_match(name))
+            && names
+                .iter()
+                .any(|name| style.is_evident_in(name.split('.').next().unwrap_or_default()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::rules::{RuleEngine, Severity};
+    use std::fs;
+
+    fn write(root: &Path, path: &str, lines: usize) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "x\n".repeat(lines)).unwrap();
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn case_styles_need_unanimous_evidence() {
+        let pascal = names(&["Button.tsx", "NavBar.tsx", "Modal.test.tsx"]);
+        assert_eq!(shared_case_style(&pascal, "tsx"), Some(CaseStyle::Pascal));
+        let snake = names(&["main.rs", "git_ops.rs", "lib.rs"]);
+        assert_eq!(shared_case_style(&snake, "rs"), Some(CaseStyle::Snake));
+        assert_eq!(shared_case_style(&names(&["a.rs", "b.rs"]), "rs"), None);
+        let mixed = names(&["Button.tsx", "index.tsx", "useThing.tsx"]);
+        assert_eq!(shared_case_style(&mixed, "tsx"), None);
+    }
+
+    #[test]
+    fn inferred_rules_capture_and_pass_the_existing_layout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (web, api) = (tmp.path().join("web"), tmp.path().join("api"));
+        for (project, components) in [(&web, ["Button", "NavBar"]), (&api, ["Table", "Form"])] {
+            fs::create_dir_all(project.join("docs")).unwrap();
+            write(project, "package.json", 1);
+            write(project, &format!("src/{}.tsx", components[0]), 120);
+            write(project, &format!("src/{}.tsx", components[1]), 40);
+            write(project, "node_modules/lib/index.js", 5000);
+        }
+        fs::create_dir_all(web.join("scripts")).unwrap();
+
+        let inference = infer_rules(&[web.clone(), api.clone()]).unwrap();
+        assert_eq!(inference.projects, 2);
+        assert_eq!(inference.languages["JavaScript/TypeScript"], 2);
+
+        let dirs: Vec<(&str, bool)> = inference
+            .rules
+            .directories
+            .iter()
+            .map(|d| (d.path.as_str(), d.required))
+            .collect();
+        assert_eq!(
+            dirs,
+            vec![("docs", true), ("scripts", false), ("src", true)]
+        );
+        assert_eq!(inference.rules.naming.len(), 1);
+        assert_eq!(inference.rules.naming[0].pattern, "src/**/*.tsx");
+        assert_eq!(
+            inference.rules.naming[0].case_style.as_deref(),
+            Some("PascalCase")
+        );
+        assert_eq!(inference.rules.size.len(), 1);
+        assert_eq!(inference.rules.size[0].max_lines, Some(200));
+
+        let engine = RuleEngine::new(inference.rules);
+        for project in [&web, &api] {
+            let violations = engine.validate(project).unwrap();
+            assert!(
+                violations
+                    .iter()
+                    .all(|v| matches!(v.severity, Severity::Info)),
+                "{:?}",
+                violations
+            );
+        }
+    }
+}
@@ -1,15 +1,19 @@
+pub mod access;
 pub mod config;
 pub mod create;
 pub mod docs;
 pub mod engine;
+pub mod explain;
+pub mod infer;
 pub mod plugin;
+pub mod policy;
 pub mod project;
 pub mod validators;
 
 // Export the main plugin
 pub use config::{
     ComponentRule, DependencyRule, DirectoryRule, DocumentationRule, FileRule, ImportRule,
-    NamingRule, RulesConfig, SecurityRule, SizeRule,
+    NamingRule, PolicyRule, RuleDocs, RulesConfig, SecurityRule, SizeRule,
 };
 pub use engine::{RuleEngine, Severity, Violation};
 pub use plugin::RulesPlugin;
@@ -1,6 +1,9 @@
 use super::config::RulesConfig;
 use super::create::RuleCreator;
 use super::engine::RuleEngine;
+use super::explain::{find_rule, print_explanation, rule_entries};
+use super::infer::infer_rules;
+use super::policy::{check_policy_rules, project_facts};
 use super::project::{ProjectRulesManager, RulesStats};
 use anyhow::Result;
 use clap::ArgMatches;
@@ -54,6 +57,9 @@ impl RulesPlugin {
                          projects listed in .meta are checked; missing project directories\n\
                          are skipped with a notice.\n\
                          \n\
+                         Projects marked \"visibility\": \"public\" in .meta are also\n\
+                         checked for dependencies on restricted projects.\n\
+                         \n\
                          Pass --fix to auto-create missing directories and other fixable\n\
                          items; violations marked fixable are flagged with a hint. A summary\n\
                          of total violations is printed at the end.\n\
@@ -92,10 +98,17 @@ impl RulesPlugin {
                          target it is left untouched and a warning is printed; the generated\n\
                          YAML is echoed to stdout so you can review and edit it.\n\
                          \n\
+                         With --infer the rules are suggested from the projects instead: the\n\
+                         top-level directories they share, the case style their source file\n\
+                         names follow, and size limits just above their largest files. Only\n\
+                         conventions every project follows become rules, so the result passes\n\
+                         meta rules check today and catches drift from then on.\n\
+                         \n\
                          Examples:\n\
                          \n\
                            meta rules init\n\
-                           meta rules init --project frontend\n\
+                           meta rules init --infer\n\
+                           meta rules init --infer --project frontend\n\
                            meta rules init --output custom-rules.yaml",
                     )
                     .aliases(vec!["i".to_string()])
@@ -115,6 +128,11 @@ impl RulesPlugin {
                             .help("Initialize rules for specific project")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("infer")
+                            .long("infer")
+                            .help("Suggest rules from the conventions the projects already follow")
+                    )
             )
             .command(
                 command("list")
@@ -154,8 +172,8 @@ impl RulesPlugin {
                          rule type. Pass a type to show just that section; accepted values\n\
                          (with aliases) are directory (dir), component (comp), file (files),\n\
                          naming (name), dependency (dep, deps), import (imports),\n\
-                         documentation (doc, docs), size, and security (sec). An unknown\n\
-                         type prints the list of valid types.\n\
+                         documentation (doc, docs), size, security (sec), and policy\n\
+                         (policies). An unknown type prints the list of valid types.\n\
                          \n\
                          Examples:\n\
                          \n\
@@ -370,6 +388,65 @@ impl RulesPlugin {
                             .takes_value(true)
                     )
             )
+            .command(
+                command("facts")
+                    .about("Print the facts document policy rules see for a project")
+                    .help_description(
+                        "Print the JSON document policy rules are evaluated against.\n\
+                         \n\
+                         Policy rules (the policies list in .rules.yaml) are Rego policies run\n\
+                         with the opa CLI. Each sees one project's facts as its input: the\n\
+                         project's .meta entry under project, every file under files, parsed\n\
+                         package.json, Cargo.toml, and pyproject.toml (and go.mod as text)\n\
+                         under manifests, and the workspace's project names. Use this to see\n\
+                         what a policy can test, or to run opa eval by hand.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta rules facts frontend\n\
+                           meta rules facts frontend | opa eval -I -d policy.rego data.metarepo.deny",
+                    )
+                    .arg(
+                        arg("project")
+                            .help("Project to describe")
+                            .required(true)
+                            .takes_value(true)
+                    )
+            )
+            .command(
+                command("explain")
+                    .about("Explain what a rule checks, why, and how to fix a violation")
+                    .help_description(
+                        "Describe one rule by its ID.\n\
+                         \n\
+                         Every rule has an ID: the id it is given in .rules.yaml, or one\n\
+                         derived from its kind and pattern (directory:src, size:**/*.rs,\n\
+                         policy:licensed, dependency:1 for the first dependency rule).\n\
+                         meta rules check prints the ID under each violation. This command\n\
+                         prints what the rule checks, why (its rationale or description), an\n\
+                         example violation, how to fix it, and its docs_url, taking the\n\
+                         rule's own rationale, example, and fix from .rules.yaml when given.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta rules explain directory:src\n\
+                           meta rules explain has-license --project frontend",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("rule-id")
+                            .help("ID of the rule to explain")
+                            .required(true)
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("project")
+                            .long("project")
+                            .short('p')
+                            .help("Look the rule up in a project's resolved rules")
+                            .takes_value(true)
+                    )
+            )
             .handler("check", handle_check)
             .handler("init", handle_init)
             .handler("list", handle_list)
@@ -377,6 +454,8 @@ impl RulesPlugin {
             .handler("create", handle_create)
             .handler("status", handle_status)
             .handler("copy", handle_copy)
+            .handler("facts", handle_facts)
+            .handler("explain", handle_explain)
             .build()
     }
 }
@@ -395,6 +474,9 @@ fn handle_check(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
         config.meta_config.projects.keys().cloned().collect()
     };
 
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
     let mut total_violations = 0;
 
     for project_name in projects {
@@ -420,7 +502,23 @@ fn handle_check(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
         stats.print();
         println!();
 
-        let violations = engine.validate(&project_path)?;
+        let mut violations = engine.validate(&project_path)?;
+        violations.extend(super::access::check_access_rules(
+            &config.meta_config,
+            &base_path,
+            &project_name,
+        )?);
+        violations.extend(check_policy_rules(
+            &rules_config,
+            &config.meta_config,
+            &base_path,
+            &project_name,
+        )?);
+
+        let docs_urls: HashMap<String, String> = rule_entries(&rules_config)
+            .into_iter()
+            .filter_map(|entry| Some((entry.id, entry.docs.docs_url?)))
+            .collect();
 
         if violations.is_empty() {
             println!("✅ {}", "All rules passed!".green());
@@ -443,6 +541,10 @@ fn handle_check(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
                 if let Some(path) = &violation.path {
                     println!("   {}: {}", "Path".dimmed(), path.display());
                 }
+                println!("   {}: {}", "Rule".dimmed(), violation.rule);
+                if let Some(url) = docs_urls.get(&violation.rule) {
+                    println!("   {}: {}", "Docs".dimmed(), url);
+                }
 
                 if violation.fixable {
                     println!("   {} This can be auto-fixed", "→".green());
@@ -470,6 +572,7 @@ fn handle_check(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
         if !fix {
             println!("💡 Run with --fix to automatically fix fixable violations");
         }
+        println!("💡 Run 'meta rules explain <rule>' to see what a rule checks and why");
     }
 
     Ok(())
@@ -499,8 +602,12 @@ fn handle_init(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
         return Ok(());
     }
 
-    let default_config = RulesConfig::default_config();
-    let yaml = serde_yaml::to_string(&default_config)?;
+    let rules = if matches.get_flag("infer") {
+        infer_from_projects(project, config)?
+    } else {
+        RulesConfig::default_config()
+    };
+    let yaml = serde_yaml::to_string(&rules)?;
     std::fs::write(&full_path, &yaml)?;
 
     println!("✅ Created rules configuration at: {}", full_path.display());
@@ -513,6 +620,53 @@ fn handle_init(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     Ok(())
 }
 
+/// Infer rules from `project`, or from every cloned workspace project.
+fn infer_from_projects(project: Option<&String>, config: &RuntimeConfig) -> Result<RulesConfig> {
+    let manager = ProjectRulesManager::new(config);
+    let names: Vec<String> = match project {
+        Some(name) => vec![name.clone()],
+        None => {
+            let mut names: Vec<String> = config.meta_config.projects.keys().cloned().collect();
+            names.sort();
+            names
+        }
+    };
+    let mut paths = Vec::new();
+    for name in &names {
+        let path = manager.get_project_path(name)?;
+        if path.is_dir() {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No cloned projects to infer rules from. Run 'meta git update' first."
+        ));
+    }
+
+    let inference = infer_rules(&paths)?;
+    println!(
+        "🔎 Inferred rules from {} project(s)",
+        inference.projects.to_string().cyan()
+    );
+    if !inference.languages.is_empty() {
+        let languages: Vec<String> = inference
+            .languages
+            .iter()
+            .map(|(language, count)| format!("{} ({})", language, count))
+            .collect();
+        println!("   Languages: {}", languages.join(", "));
+    }
+    let rules = &inference.rules;
+    println!(
+        "   {} directory, {} naming, and {} size rule(s)",
+        rules.directories.len(),
+        rules.naming.len(),
+        rules.size.len()
+    );
+    Ok(inference.rules)
+}
+
 /// Handler for the list command
 fn handle_list(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let project = matches.get_one::<String>("project");
@@ -572,6 +726,21 @@ fn handle_list(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
                 }
             }
         }
+        println!();
+    }
+
+    if !rules_config.policies.is_empty() {
+        println!("{}", "📜 Policy Rules:".cyan().bold());
+        for policy in &rules_config.policies {
+            let source = match (&policy.file, &policy.rego) {
+                (Some(file), _) => file.as_str(),
+                _ => "inline",
+            };
+            println!("   • {} ({})", policy.name.yellow(), source.dimmed());
+            if let Some(desc) = &policy.description {
+                println!("     {}", desc.dimmed());
+            }
+        }
     }
 
     Ok(())
@@ -590,9 +759,10 @@ fn handle_docs(matches: &ArgMatches, _config: &RuntimeConfig) -> Result<()> {
             "documentation" | "doc" | "docs" => super::docs::print_documentation_rule_docs(),
             "size" => super::docs::print_size_rule_docs(),
             "security" | "sec" => super::docs::print_security_rule_docs(),
+            "policy" | "policies" => super::docs::print_policy_rule_docs(),
             _ => {
                 println!("{} Unknown rule type: {}", "Error:".red(), rule_type);
-                println!("Valid types: directory, component, file, naming, dependency, import, documentation, size, security");
+                println!("Valid types: directory, component, file, naming, dependency, import, documentation, size, security, policy");
             }
         }
     } else {
@@ -685,6 +855,38 @@ fn handle_copy(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     Ok(())
 }
 
+/// Handler for the facts command
+fn handle_facts(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let project_name = matches.get_one::<String>("project").unwrap();
+    let manager = ProjectRulesManager::new(config);
+    let project_path = manager.get_project_path(project_name)?;
+    if !project_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Project '{}' is not cloned at {}",
+            project_name,
+            project_path.display()
+        ));
+    }
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    let facts = project_facts(&config.meta_config, &base_path, project_name)?;
+    println!("{}", serde_json::to_string_pretty(&facts)?);
+    Ok(())
+}
+
+/// Handler for the explain command
+fn handle_explain(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let rule_id = matches.get_one::<String>("rule-id").unwrap();
+    let rules_config = if let Some(project_name) = matches.get_one::<String>("project") {
+        ProjectRulesManager::new(config).load_project_rules(project_name)?
+    } else {
+        load_rules_config(config)?
+    };
+    print_explanation(&find_rule(&rules_config, rule_id)?);
+    Ok(())
+}
+
 /// Load rules config helper function
 fn load_rules_config(config: &RuntimeConfig) -> Result<RulesConfig> {
     let rules_path = if config.meta_root().is_some() {
@@ -0,0 +1,287 @@
+//! Policy rules: organization policies written in Rego and evaluated with the
+//! `opa` CLI against a document of facts about each project, so a new check
+//! needs a policy file rather than a new rule category.
+//!
+//! ```yaml
+//! policies:
+//!   - name: licensed
+//!     file: policies/license.rego
+//!     description: Every package declares a license
+//! ```
+//!
+//! with `policies/license.rego`:
+//!
+//! ```rego
+//! package metarepo
+//!
+//! deny contains msg if {
+//!     pkg := input.manifests["package.json"]
+//!     not pkg.license
+//!     msg := sprintf("%s has no license in package.json", [input.project.name])
+//! }
+//! ```
+//!
+//! The policy's query (default `data.metarepo.deny`) yields the violations: a
+//! set or list of messages, or of objects with a `msg`. `meta rules facts
+//! <project>` prints the input document a policy sees.
+
+use super::config::{PolicyRule, RulesConfig};
+use super::engine::{Severity, Violation};
+use anyhow::{anyhow, Context, Result};
+use metarepo_core::{MetaConfig, ProjectEntry};
+use serde_json::{json, Map, Value};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use walkdir::WalkDir;
+
+/// The query a policy answers when it names none.
+pub const DEFAULT_QUERY: &str = "data.metarepo.deny";
+
+/// Manifests included in the facts document.
+const MANIFESTS: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "pyproject.toml",
+    "go.mod",
+    "composer.json",
+];
+
+/// Directories left out of the file list.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", "build", "vendor"];
+
+/// The facts document for `project_name`: its `.meta` entry, every file in it
+/// (relative paths), its parsed manifests, and the workspace's project names.
+pub fn project_facts(config: &MetaConfig, base_path: &Path, project_name: &str) -> Result<Value> {
+    let project_path = base_path.join(project_name);
+    let mut project = match config.projects.get(project_name) {
+        Some(ProjectEntry::Metadata(metadata)) => serde_json::to_value(metadata)?,
+        Some(ProjectEntry::Url(url)) => json!({ "url": url }),
+        None => json!({}),
+    };
+    project["name"] = json!(project_name);
+
+    let mut files = Vec::new();
+    let walker = WalkDir::new(&project_path).into_iter().filter_entry(|e| {
+        e.depth() == 0 || !SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref())
+    });
+    for entry in walker.flatten() {
+        if entry.file_type().is_file() {
+            if let Ok(relative) = entry.path().strip_prefix(&project_path) {
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    files.sort();
+
+    let mut manifests = Map::new();
+    for name in MANIFESTS {
+        let path = project_path.join(name);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let parsed = if name.ends_with(".json") {
+            serde_json::from_str(&content).ok()
+        } else if name.ends_with(".toml") {
+            toml::from_str::<Value>(&content).ok()
+        } else {
+            None
+        };
+        // Unparseable manifests, and formats without a parser, are passed as text.
+        manifests.insert(name.to_string(), parsed.unwrap_or(Value::String(content)));
+    }
+
+    let mut projects: Vec<&String> = config.projects.keys().collect();
+    projects.sort();
+    Ok(json!({
+        "project": project,
+        "files": files,
+        "manifests": manifests,
+        "workspace": { "projects": projects },
+    }))
+}
+
+/// Evaluate `rules`' policies for `project_name`. Does nothing, and needs no
+/// `opa`, when there are none.
+pub fn check_policy_rules(
+    rules: &RulesConfig,
+    config: &MetaConfig,
+    base_path: &Path,
+    project_name: &str,
+) -> Result<Vec<Violation>> {
+    if rules.policies.is_empty() {
+        return Ok(Vec::new());
+    }
+    let facts = project_facts(config, base_path, project_name)?;
+    let mut violations = Vec::new();
+    for policy in &rules.policies {
+        let output = evaluate(policy, base_path, &facts)
+            .with_context(|| format!("Policy '{}' failed to evaluate", policy.name))?;
+        let severity = severity(policy)?;
+        for message in messages(&output) {
+            violations.push(Violation {
+                rule: policy.id(),
+                message,
+                severity: severity.clone(),
+                path: None,
+                fixable: false,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+fn severity(policy: &PolicyRule) -> Result<Severity> {
+    match policy.severity.as_deref().unwrap_or("error") {
+        "error" => Ok(Severity::Error),
+        "warning" => Ok(Severity::Warning),
+        "info" => Ok(Severity::Info),
+        other => Err(anyhow!(
+            "Policy '{}' has severity '{}'; use error, warning, or info",
+            policy.name,
+            other
+        )),
+    }
+}
+
+/// Run `opa eval` for `policy` with `facts` as input, returning its JSON output.
+fn evaluate(policy: &PolicyRule, base_path: &Path, facts: &Value) -> Result<Value> {
+    // Inline policies are written to a temporary file, which must outlive opa.
+    let mut inline = None;
+    let policy_path = match (&policy.file, &policy.rego) {
+        (Some(file), None) => base_path.join(file),
+        (None, Some(rego)) => {
+            let mut file = tempfile::Builder::new().suffix(".rego").tempfile()?;
+            file.write_all(rego.as_bytes())?;
+            let path = file.path().to_path_buf();
+            inline = Some(file);
+            path
+        }
+        _ => {
+            return Err(anyhow!("Give either 'file' or 'rego', not both or neither"));
+        }
+    };
+    if !policy_path.exists() {
+        return Err(anyhow!("{} does not exist", policy_path.display()));
+    }
+
+    let query = policy.query.as_deref().unwrap_or(DEFAULT_QUERY);
+    let mut child = Command::new("opa")
+        .args(["eval", "--format", "json", "--stdin-input", "--data"])
+        .arg(&policy_path)
+        .arg(query)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => anyhow!(
+                "Policy rules need the 'opa' CLI on PATH \
+                 (https://www.openpolicyagent.org/docs/latest/#1-download-opa)"
+            ),
+            _ => e.into(),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(serde_json::to_string(facts)?.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    drop(inline);
+    if !output.status.success() {
+        return Err(anyhow!(
+            "opa eval failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// The violation messages in `opa eval --format json` output. An undefined
+/// query (no `result`) means no violations.
+fn messages(output: &Value) -> Vec<String> {
+    let mut messages = Vec::new();
+    for result in output["result"].as_array().into_iter().flatten() {
+        for expression in result["expressions"].as_array().into_iter().flatten() {
+            let value = &expression["value"];
+            let items = match value.as_array() {
+                Some(items) => items.clone(),
+                None if value.is_null() || value == &json!(false) => Vec::new(),
+                None => vec![value.clone()],
+            };
+            for item in items {
+                messages.push(match &item {
+                    Value::String(message) => message.clone(),
+                    _ => match item["msg"].as_str() {
+                        Some(message) => message.to_string(),
+                        None => item.to_string(),
+                    },
+                });
+            }
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn facts_describe_the_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        let web = tmp.path().join("web");
+        std::fs::create_dir_all(web.join("src")).unwrap();
+        std::fs::create_dir_all(web.join("node_modules/left-pad")).unwrap();
+        std::fs::write(web.join("src/index.js"), "").unwrap();
+        std::fs::write(web.join("node_modules/left-pad/index.js"), "").unwrap();
+        std::fs::write(
+            web.join("package.json"),
+            r#"{"name": "web", "license": "MIT"}"#,
+        )
+        .unwrap();
+        std::fs::write(web.join("Cargo.toml"), "[package]\nname = \"web\"\n").unwrap();
+        let config: MetaConfig = serde_json::from_str(
+            r#"{"projects": {
+                "web": {"url": "git@h:web.git", "visibility": "public"},
+                "api": "git@h:api.git"
+            }}"#,
+        )
+        .unwrap();
+
+        let facts = project_facts(&config, tmp.path(), "web").unwrap();
+        assert_eq!(facts["project"]["name"], "web");
+        assert_eq!(facts["project"]["visibility"], "public");
+        assert_eq!(
+            facts["files"],
+            json!(["Cargo.toml", "package.json", "src/index.js"])
+        );
+        assert_eq!(facts["manifests"]["package.json"]["license"], "MIT");
+        assert_eq!(facts["manifests"]["Cargo.toml"]["package"]["name"], "web");
+        assert_eq!(facts["workspace"]["projects"], json!(["api", "web"]));
+
+        let facts = project_facts(&config, tmp.path(), "api").unwrap();
+        assert_eq!(facts["project"]["url"], "git@h:api.git");
+    }
+
+    #[test]
+    fn opa_output_becomes_messages() {
+        let output = json!({"result": [{"expressions": [{
+            "value": ["no license", {"msg": "no README"}, {"code": 3}]
+        }]}]});
+        assert_eq!(
+            messages(&output),
+            vec!["no license", "no README", r#"{"code":3}"#]
+        );
+        assert!(messages(&json!({})).is_empty());
+        let denied = json!({"result": [{"expressions": [{"value": "one"}]}]});
+        assert_eq!(messages(&denied), vec!["one"]);
+    }
+
+    #[test]
+    fn policies_are_skipped_when_none_are_configured() {
+        let config = MetaConfig::default();
+        let rules = RulesConfig::new();
+        let violations =
+            check_policy_rules(&rules, &config, Path::new("/nonexistent"), "web").unwrap();
+        assert!(violations.is_empty());
+    }
+}
@@ -163,6 +163,7 @@ impl<'a> ProjectRulesManager<'a> {
             documentation: [project_rules.documentation, workspace_rules.documentation].concat(),
             size: [project_rules.size, workspace_rules.size].concat(),
             security: [project_rules.security, workspace_rules.security].concat(),
+            policies: [project_rules.policies, workspace_rules.policies].concat(),
         }
     }
 }
@@ -0,0 +1,144 @@
+//! `meta start` and `meta dev`: shorthands for running each project's entry
+//! point. A project runs the script of the same name when it defines one, and
+//! otherwise its `default_script` (or the workspace `default_script`).
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::plugin::run_entry_points;
+use crate::plugins::shared::selection;
+
+/// Registers one entry-point command (`start` or `dev`).
+pub struct EntryPointPlugin {
+    name: &'static str,
+    about: &'static str,
+}
+
+impl EntryPointPlugin {
+    /// `meta start`: run each project's `start` script or default script.
+    pub fn start() -> Self {
+        Self {
+            name: "start",
+            about: "Run each project's start script (or its default script)",
+        }
+    }
+
+    /// `meta dev`: run each project's `dev` script or default script.
+    pub fn dev() -> Self {
+        Self {
+            name: "dev",
+            about: "Run each project's dev script (or its default script)",
+        }
+    }
+}
+
+impl MetaPlugin for EntryPointPlugin {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new(self.name)
+                .about(self.about)
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(&format!(
+                    "Run the `{name}` entry point of the projects in your directory scope,\n\
+                     or of the named projects.\n\
+                     \n\
+                     Each project runs its own `{name}` script when it defines one (in .meta,\n\
+                     or through run.fallback). Otherwise it runs its `default_script`, or\n\
+                     the workspace `default_script` if the project defines that script.\n\
+                     Projects with none of these are skipped.\n\
+                     \n\
+                     Exits 0 when every project succeeds, 1 when all fail, and 2 when some\n\
+                     fail; --min-success PCT (or min_success.{name} in .meta) exits 0 as long\n\
+                     as at least PCT percent succeed.\n\
+                     \n\
+                     Examples:\n  \
+                       meta {name}\n  \
+                       meta {name} api web --parallel\n  \
+                       meta {name} -e PORT=4000 api\n",
+                    name = self.name
+                )))
+                .arg(
+                    Arg::new(selection::TARGETS_ARG)
+                        .help("Projects to run in (names, aliases, or globs)")
+                        .num_args(1..)
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("strict")
+                        .long("strict")
+                        .help("Fail if any project selector matches nothing")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("exclude")
+                        .long("exclude")
+                        .help(
+                            "Skip these projects (names, aliases, globs, or tags; comma-separated)",
+                        )
+                        .value_name("PROJECTS"),
+                )
+                .arg(
+                    Arg::new("parallel")
+                        .long("parallel")
+                        .help("Run in all projects at once")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("env")
+                        .long("env")
+                        .short('e')
+                        .help("Set environment variable (KEY=VALUE)")
+                        .action(ArgAction::Append)
+                        .value_name("KEY=VALUE"),
+                )
+                .arg(
+                    Arg::new("min-success")
+                        .long("min-success")
+                        .help("Exit 0 when at least this percentage of projects succeed")
+                        .value_name("PCT"),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let mut projects = Vec::new();
+        if let Some(mut selected) = selection::explicit_projects(matches, config)? {
+            selection::apply_excludes(matches, config, &mut selected, true);
+            if selected.is_empty() {
+                println!(
+                    "  {} Every selected project is excluded",
+                    "ℹ".bright_black()
+                );
+                return Ok(());
+            }
+            projects = selected;
+        }
+        if run_entry_points(matches, config, &projects, Some(self.name))? {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "No project in scope defines a '{}' script or a default_script",
+            self.name
+        ))
+    }
+}
+
+impl BasePlugin for EntryPointPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some(self.about)
+    }
+}
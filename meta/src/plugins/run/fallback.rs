@@ -0,0 +1,199 @@
+//! Native task-runner fallback for `meta run`.
+//!
+//! When a project has no `.meta` script of the requested name, the runners
+//! listed in `run.fallback` are tried in order; the first one whose manifest in
+//! the project defines the task supplies the command. This lets `meta run`
+//! drive `package.json` scripts, justfile recipes, and cargo-make tasks before
+//! they are copied into `.meta`.
+
+use colored::*;
+use metarepo_core::MetaConfig;
+use std::path::Path;
+
+/// Runner names accepted in `run.fallback`.
+pub const RUNNERS: &[&str] = &["npm", "yarn", "pnpm", "just", "cargo-make", "make"];
+
+/// The configured fallback runners, in order, skipping unknown names (see
+/// [`warn_unknown_runners`]).
+pub fn configured_runners(config: &MetaConfig) -> Vec<String> {
+    config
+        .run_settings
+        .iter()
+        .flat_map(|settings| &settings.fallback)
+        .filter(|name| RUNNERS.contains(&name.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Report `run.fallback` entries that are not a known runner.
+pub fn warn_unknown_runners(config: &MetaConfig) {
+    for name in config.run_settings.iter().flat_map(|s| &s.fallback) {
+        if !RUNNERS.contains(&name.as_str()) {
+            eprintln!(
+                "  {} Unknown run.fallback runner '{}' (expected one of: {})",
+                "⚠️".yellow(),
+                name,
+                RUNNERS.join(", ")
+            );
+        }
+    }
+}
+
+/// Whether `package.json` in `project_path` defines `scripts.<task>`.
+fn package_json_has(project_path: &Path, task: &str) -> bool {
+    std::fs::read_to_string(project_path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|json| json["scripts"].get(task).is_some())
+}
+
+/// Recipe names declared in a justfile: unindented `name ...:` lines that are
+/// not assignments (`:=`), settings, or comments.
+pub fn justfile_recipes(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#']) && !line.contains(":="))
+        .filter_map(|line| {
+            let head = line.split(':').next()?;
+            if head.len() == line.len() {
+                return None;
+            }
+            let name = head.split_whitespace().next()?.trim_start_matches('@');
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            (valid && !matches!(name, "set" | "alias" | "export" | "import" | "mod"))
+                .then(|| name.to_string())
+        })
+        .collect()
+}
+
+fn justfile_has(project_path: &Path, task: &str) -> bool {
+    ["justfile", "Justfile", ".justfile"].iter().any(|file| {
+        std::fs::read_to_string(project_path.join(file))
+            .map(|content| justfile_recipes(&content).iter().any(|r| r == task))
+            .unwrap_or(false)
+    })
+}
+
+/// Whether `Makefile.toml` defines `[tasks.<task>]`.
+fn cargo_make_has(project_path: &Path, task: &str) -> bool {
+    std::fs::read_to_string(project_path.join("Makefile.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        .is_some_and(|manifest| {
+            manifest
+                .get("tasks")
+                .and_then(|tasks| tasks.as_table())
+                .is_some_and(|tasks| tasks.contains_key(task))
+        })
+}
+
+/// Whether a Makefile has a `task:` target line.
+fn makefile_has(project_path: &Path, task: &str) -> bool {
+    ["GNUmakefile", "makefile", "Makefile"].iter().any(|file| {
+        std::fs::read_to_string(project_path.join(file))
+            .map(|content| {
+                content.lines().any(|line| {
+                    line.strip_prefix(task)
+                        .is_some_and(|rest| rest.starts_with(':') && !rest.starts_with(":="))
+                })
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// The command `runner` would use to run `task` in `project_path`, if the
+/// project's manifest defines it.
+pub fn runner_command(runner: &str, project_path: &Path, task: &str) -> Option<String> {
+    let defined = match runner {
+        "npm" | "yarn" | "pnpm" => package_json_has(project_path, task),
+        "just" => justfile_has(project_path, task),
+        "cargo-make" => cargo_make_has(project_path, task),
+        "make" => makefile_has(project_path, task),
+        _ => false,
+    };
+    if !defined {
+        return None;
+    }
+    Some(match runner {
+        "cargo-make" => format!("cargo make {}", task),
+        "just" | "make" => format!("{} {}", runner, task),
+        _ => format!("{} run {}", runner, task),
+    })
+}
+
+/// The first fallback command for `task` in `project_path` among `runners`.
+pub fn fallback_command(runners: &[String], project_path: &Path, task: &str) -> Option<String> {
+    runners
+        .iter()
+        .find_map(|runner| runner_command(runner, project_path, task))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn justfile_recipe_names() {
+        let content = "set shell := [\"bash\", \"-c\"]\n\
+                       version := \"1\"\n\
+                       # comment: ignored\n\
+                       build:\n    cargo build\n\
+                       @test filter='': build\n    cargo test {{filter}}\n\
+                       alias b := build\n";
+        assert_eq!(justfile_recipes(content), vec!["build", "test"]);
+    }
+
+    #[test]
+    fn picks_first_runner_that_defines_the_task() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"scripts": {"lint": "eslint ."}}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join("justfile"), "lint:\n    echo just\ndeploy:\n").unwrap();
+        std::fs::write(root.join("Makefile.toml"), "[tasks.ci]\ncommand = \"x\"\n").unwrap();
+        std::fs::write(root.join("Makefile"), "clean:\n\trm -rf out\nVAR := 1\n").unwrap();
+
+        let runners: Vec<String> = ["npm", "just", "cargo-make", "make"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            fallback_command(&runners, root, "lint").as_deref(),
+            Some("npm run lint")
+        );
+        assert_eq!(
+            fallback_command(&runners, root, "deploy").as_deref(),
+            Some("just deploy")
+        );
+        assert_eq!(
+            fallback_command(&runners, root, "ci").as_deref(),
+            Some("cargo make ci")
+        );
+        assert_eq!(
+            fallback_command(&runners, root, "clean").as_deref(),
+            Some("make clean")
+        );
+        assert_eq!(fallback_command(&runners, root, "VAR"), None);
+        assert_eq!(
+            fallback_command(&runners[1..], root, "lint").as_deref(),
+            Some("just lint")
+        );
+    }
+
+    #[test]
+    fn unknown_runners_are_skipped() {
+        let mut config = MetaConfig::default();
+        assert!(configured_runners(&config).is_empty());
+        config.run_settings = Some(metarepo_core::RunSettings {
+            fallback: vec!["just".to_string(), "gradle".to_string()],
+        });
+        assert_eq!(configured_runners(&config), vec!["just".to_string()]);
+    }
+}
@@ -1,32 +1,42 @@
 use crate::plugins::exec::ProjectIterator;
-use crate::plugins::shared::{OutputManager, ProgressIndicator};
+use crate::plugins::shared::outcome::results_table;
+use crate::plugins::shared::{
+    secrets, selection, LogMultiplexer, MuxOptions, OutputManager, ProgressIndicator, RunOutcome,
+};
 use anyhow::{Context, Result};
 use colored::*;
-use metarepo_core::{MetaConfig, ProjectEntry};
+use metarepo_core::{MetaConfig, ProjectEntry, ScriptEntry};
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-pub use self::plugin::RunPlugin;
+pub use self::entry::EntryPointPlugin;
+pub use self::plugin::{env_vars_from, RunPlugin};
+pub use self::verify::VerifySetupPlugin;
 
+mod entry;
+pub mod fallback;
 mod plugin;
 mod tui;
+pub mod verify;
 
 /// Load the workspace config at `base_path` with global scripts cascaded down
 /// the enclosing .meta chain (outermost defaults, nearest overrides). Project
 /// scripts are untouched and still override globals at lookup time. In a flat
 /// (single-.meta) workspace this returns the same scripts the config already
-/// had, so the cascade is a no-op there.
+/// had, so the cascade is a no-op there. Each config's `extends` fragments are
+/// merged in first.
 pub(crate) fn load_config_with_script_cascade(base_path: &Path) -> Result<MetaConfig> {
     let meta_file_path = MetaConfig::locate_in(base_path)?.path;
-    let mut config = MetaConfig::load_from_file(&meta_file_path)?;
+    let mut config = MetaConfig::load_resolved(&meta_file_path)?;
 
     if let Ok(found) = MetaConfig::discover_chain_from(base_path) {
         if found.len() > 1 {
             let chain: Vec<MetaConfig> = found
                 .iter()
-                .filter_map(|d| MetaConfig::load_from_file(&d.path).ok())
+                .filter_map(|d| MetaConfig::load_resolved(&d.path).ok())
                 .collect();
             let merged = MetaConfig::merge_global_scripts(&chain);
             if !merged.is_empty() {
@@ -44,13 +54,15 @@ pub(crate) fn load_config_with_script_cascade(base_path: &Path) -> Result<MetaCo
 pub(crate) struct ScriptInfo {
     pub name: String,
     pub command: String,
+    pub description: Option<String>,
     pub projects: Vec<String>,
 }
 
 /// Build (but do not run) the `Command` for `script_name` in `project_name`,
-/// resolving the script via the config cascade and applying global and
-/// project-specific environment variables. Returns the configured command and
-/// its display string. Shared by the sequential, buffered, and streaming paths.
+/// resolving the script via the config cascade (then the `run.fallback`
+/// runners, see [`fallback`]) and applying global and project-specific
+/// environment variables. Returns the configured command and its display
+/// string. Shared by the sequential, buffered, and streaming paths.
 pub(crate) fn build_script_command(
     config: &MetaConfig,
     script_name: &str,
@@ -67,18 +79,26 @@ pub(crate) fn build_script_command(
     }
 
     let scripts = config.get_all_scripts(Some(project_name));
-    let script_cmd = scripts.get(script_name).ok_or_else(|| {
-        anyhow::anyhow!(
-            "Script '{}' not found for project '{}'",
+    let script_cmd = match scripts.get(script_name) {
+        Some(cmd) => cmd.clone(),
+        None => fallback::fallback_command(
+            &fallback::configured_runners(config),
+            &project_path,
             script_name,
-            project_name
         )
-    })?;
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Script '{}' not found for project '{}'",
+                script_name,
+                project_name
+            )
+        })?,
+    };
 
     // Parse the script command with shell-style tokenization so quoted args
     // with spaces survive intact. shlex returns None for unbalanced quotes —
     // surface that to the caller instead of silently mis-splitting.
-    let parts = shlex::split(script_cmd).ok_or_else(|| {
+    let parts = shlex::split(&script_cmd).ok_or_else(|| {
         anyhow::anyhow!(
             "Failed to parse script command (unbalanced quotes?): {}",
             script_cmd
@@ -98,12 +118,77 @@ pub(crate) fn build_script_command(
         cmd.env(key, value);
     }
     if let Some(ProjectEntry::Metadata(metadata)) = config.projects.get(project_name) {
-        for (key, value) in &metadata.env {
-            cmd.env(key, value);
-        }
+        cmd.envs(secrets::resolve_env(&metadata.env)?);
     }
 
-    Ok((cmd, script_cmd.to_string()))
+    Ok((cmd, script_cmd))
+}
+
+/// Whether `name` is a `.meta` script anywhere in the workspace (global or in
+/// any project), as opposed to, say, a project name.
+pub(crate) fn is_script_name(config: &MetaConfig, name: &str) -> bool {
+    config
+        .scripts
+        .as_ref()
+        .is_some_and(|scripts| scripts.contains_key(name))
+        || config.projects.values().any(|entry| {
+            matches!(entry, ProjectEntry::Metadata(metadata) if metadata.scripts.contains_key(name))
+        })
+}
+
+/// Whether `script_name` can run in `project_name`: a `.meta` script (global or
+/// project) or, with `run.fallback`, a task in one of the project's manifests.
+pub(crate) fn project_has_script(
+    config: &MetaConfig,
+    script_name: &str,
+    project_name: &str,
+    base_path: &Path,
+) -> bool {
+    config
+        .get_all_scripts(Some(project_name))
+        .contains_key(script_name)
+        || fallback::fallback_command(
+            &fallback::configured_runners(config),
+            &base_path.join(project_name),
+            script_name,
+        )
+        .is_some()
+}
+
+/// Decide which script each project runs when none is named, grouped by script
+/// (name-sorted). A project runs `preferred` when it defines it, else its
+/// `default_script`, else the workspace `default_script` when it defines that.
+/// Projects with nothing to run are left out.
+pub(crate) fn entry_point_plan(
+    config: &MetaConfig,
+    projects: &[String],
+    preferred: Option<&str>,
+    base_path: &Path,
+) -> Vec<(String, Vec<String>)> {
+    use std::collections::BTreeMap;
+
+    let mut plan: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for project in projects {
+        let own_default = match config.projects.get(project) {
+            Some(ProjectEntry::Metadata(metadata)) => metadata.default_script.as_deref(),
+            _ => None,
+        };
+        let script = preferred
+            .filter(|p| project_has_script(config, p, project, base_path))
+            .or(own_default)
+            .or_else(|| {
+                config
+                    .default_script
+                    .as_deref()
+                    .filter(|d| project_has_script(config, d, project, base_path))
+            });
+        if let Some(script) = script {
+            plan.entry(script.to_string())
+                .or_default()
+                .push(project.clone());
+        }
+    }
+    plan.into_iter().collect()
 }
 
 /// Collect the distinct scripts available across the workspace: global scripts
@@ -112,14 +197,14 @@ pub(crate) fn build_script_command(
 pub(crate) fn gather_scripts(config: &MetaConfig) -> Vec<ScriptInfo> {
     use std::collections::BTreeMap;
 
-    // name -> (command, projects). BTreeMap keeps the result name-sorted.
-    let mut map: BTreeMap<String, (String, Vec<String>)> = BTreeMap::new();
+    // name -> (entry, projects). BTreeMap keeps the result name-sorted.
+    let mut map: BTreeMap<String, (ScriptEntry, Vec<String>)> = BTreeMap::new();
 
     // Global scripts run in every project that does not shadow them.
     if let Some(global) = &config.scripts {
-        for (name, cmd) in global {
+        for (name, entry) in global {
             map.entry(name.clone())
-                .or_insert_with(|| (cmd.clone(), Vec::new()))
+                .or_insert_with(|| (entry.clone(), Vec::new()))
                 .1 = find_projects_with_script(config, name);
         }
     }
@@ -128,10 +213,10 @@ pub(crate) fn gather_scripts(config: &MetaConfig) -> Vec<ScriptInfo> {
     // name; keep the project command for display when no global exists.
     for (project_name, entry) in &config.projects {
         if let ProjectEntry::Metadata(metadata) = entry {
-            for (name, cmd) in &metadata.scripts {
+            for (name, script) in &metadata.scripts {
                 let e = map
                     .entry(name.clone())
-                    .or_insert_with(|| (cmd.clone(), Vec::new()));
+                    .or_insert_with(|| (script.clone(), Vec::new()));
                 if !e.1.contains(project_name) {
                     e.1.push(project_name.clone());
                 }
@@ -140,33 +225,56 @@ pub(crate) fn gather_scripts(config: &MetaConfig) -> Vec<ScriptInfo> {
     }
 
     map.into_iter()
-        .map(|(name, (command, mut projects))| {
+        .map(|(name, (entry, mut projects))| {
             projects.sort();
             ScriptInfo {
                 name,
-                command,
+                command: entry.command().to_string(),
+                description: entry.description().map(str::to_string),
                 projects,
             }
         })
         .collect()
 }
 
+/// Script names and descriptions for shell completion, from the workspace
+/// enclosing `base_path`. Empty outside a workspace.
+pub(crate) fn completion_hints(base_path: &Path) -> Vec<(String, Option<String>)> {
+    load_config_with_script_cascade(base_path)
+        .map(|config| {
+            gather_scripts(&config)
+                .into_iter()
+                .map(|s| (s.name, s.description))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// The in-scope projects that define `script_name` (the default target set when
-/// no explicit projects are named). Shared by the CLI default path and the TUI.
+/// no explicit projects are named), counting tasks found by the `run.fallback`
+/// runners. Shared by the CLI default path and the TUI.
 pub(crate) fn scripts_scoped_projects(
     config: &MetaConfig,
     script_name: &str,
     scope: &[String],
+    base_path: &Path,
 ) -> Vec<String> {
     let with_script = find_projects_with_script(config, script_name);
+    let runners = fallback::configured_runners(config);
     scope
         .iter()
-        .filter(|p| with_script.contains(p))
+        .filter(|p| {
+            with_script.contains(p)
+                || (!runners.is_empty()
+                    && fallback::fallback_command(&runners, &base_path.join(p), script_name)
+                        .is_some())
+        })
         .cloned()
         .collect()
 }
 
-/// Execute a script for selected projects
+/// Execute a script for selected projects, returning how many succeeded and
+/// failed
 #[allow(clippy::too_many_arguments)]
 pub fn run_script(
     script_name: &str,
@@ -179,14 +287,16 @@ pub fn run_script(
     no_progress: bool,
     streaming: bool,
     env_vars: &HashMap<String, String>,
-) -> Result<()> {
+    mux: &MuxOptions,
+) -> Result<RunOutcome> {
     let config = load_config_with_script_cascade(base_path)?;
+    fallback::warn_unknown_runners(&config);
 
     // Determine which projects to operate on
     let mut selected_projects = if projects.is_empty() {
         // No explicit projects: run in the in-scope projects that define the
         // script (scope already reflects the cwd and the --workspace flag).
-        scripts_scoped_projects(&config, script_name, scope)
+        scripts_scoped_projects(&config, script_name, scope, base_path)
     } else if projects.len() == 1 && projects[0] == "--all" {
         // Run in all projects
         config.projects.keys().cloned().collect()
@@ -228,8 +338,9 @@ pub fn run_script(
             "  {} No projects selected or script not found",
             "ℹ".bright_black()
         );
-        return Ok(());
+        return Ok(RunOutcome::default());
     }
+    selection::warn_deprecated(&config, &selected_projects);
 
     println!(
         "\n  {} {}",
@@ -243,10 +354,36 @@ pub fn run_script(
     );
     println!("  {}", "═".repeat(60).bright_black());
 
-    let mut success_count = 0;
-    let mut failed = Vec::new();
+    let mut outcome = RunOutcome::default();
 
-    if parallel && selected_projects.len() > 1 && !streaming {
+    if mux.is_active() || (parallel && streaming && selected_projects.len() > 1) {
+        // Live output from every project through the shared multiplexer
+        let multiplexer = Arc::new(LogMultiplexer::new(&selected_projects, mux)?);
+        let mut jobs = Vec::new();
+        for project_name in &selected_projects {
+            match build_script_command(&config, script_name, project_name, base_path, env_vars) {
+                Ok((cmd, _)) => jobs.push((project_name.clone(), cmd)),
+                Err(e) => {
+                    eprintln!("     {} {}: {}", "❌".red(), project_name, e);
+                    outcome.record(project_name, None, Duration::ZERO);
+                }
+            }
+        }
+        let workers = if parallel { jobs.len() } else { 1 };
+        for (project_name, result, started, duration) in multiplexer.run_all(jobs, workers) {
+            match &result {
+                Ok(0) => {}
+                Ok(code) => eprintln!(
+                    "     {} {}: exited with code {}",
+                    "❌".red(),
+                    project_name,
+                    code
+                ),
+                Err(e) => eprintln!("     {} {}: {}", "❌".red(), project_name, e),
+            }
+            outcome.record_span(&project_name, result.ok(), started, duration);
+        }
+    } else if parallel && selected_projects.len() > 1 && !streaming {
         // Use buffered output for parallel execution
         let output_manager = Arc::new(OutputManager::new(selected_projects.clone()));
         let mut progress_indicator =
@@ -314,16 +451,15 @@ pub fn run_script(
 
         // Wait for all threads to complete
         for (project_name, handle) in handles {
-            match handle.join() {
-                Ok(()) => {
-                    if let Some(output) = output_manager.get_project_output(&project_name) {
-                        match output.status {
-                            crate::plugins::shared::JobStatus::Completed => {}
-                            _ => failed.push(project_name),
-                        }
-                    }
-                }
-                Err(_) => failed.push(project_name),
+            let output = match handle.join() {
+                Ok(()) => output_manager.get_project_output(&project_name),
+                Err(_) => None,
+            };
+            let exit_code = output.as_ref().and_then(|o| o.exit_code);
+            let duration = output.as_ref().and_then(|o| o.duration).unwrap_or_default();
+            match output.and_then(|o| o.start_time) {
+                Some(started) => outcome.record_span(&project_name, exit_code, started, duration),
+                None => outcome.record(&project_name, exit_code, duration),
             }
         }
 
@@ -336,43 +472,56 @@ pub fn run_script(
         }
         output_manager.display_final_results();
 
-        return Ok(());
+        return Ok(outcome);
     } else {
         for project_name in &selected_projects {
-            match execute_script_in_project(script_name, project_name, base_path, &config, env_vars)
-            {
-                Ok(_) => success_count += 1,
-                Err(e) => {
-                    eprintln!("     {} {}", "❌".red(), format!("Failed: {}", e).red());
-                    failed.push(project_name.clone());
-                }
+            let started = Instant::now();
+            let result =
+                execute_script_in_project(script_name, project_name, base_path, &config, env_vars);
+            let failure = match &result {
+                Ok(0) => None,
+                Ok(code) => Some(format!("Script failed with exit code: {}", code)),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(failure) = failure {
+                eprintln!(
+                    "     {} {}",
+                    "❌".red(),
+                    format!("Failed: {}", failure).red()
+                );
             }
+            outcome.record(project_name, result.ok(), started.elapsed());
         }
     }
 
+    if outcome.projects.len() > 1 {
+        println!();
+        results_table(&outcome.projects).indent(2).print();
+    }
     println!("\n  {}", "─".repeat(60).bright_black());
     println!(
         "  {} {} scripts completed, {} failed",
         "Summary:".bright_black(),
-        success_count.to_string().green(),
-        if !failed.is_empty() {
-            failed.len().to_string().red()
+        outcome.succeeded.to_string().green(),
+        if outcome.failed > 0 {
+            outcome.failed.to_string().red()
         } else {
             "0".bright_black()
         }
     );
 
-    Ok(())
+    Ok(outcome)
 }
 
-/// Execute a script in a specific project
+/// Execute a script in a specific project, returning its exit code (-1 when
+/// it was killed by a signal)
 fn execute_script_in_project(
     script_name: &str,
     project_name: &str,
     base_path: &Path,
     config: &MetaConfig,
     env_vars: &HashMap<String, String>,
-) -> Result<()> {
+) -> Result<i32> {
     println!("\n  {} {}", "📦".blue(), project_name.bold());
 
     let (mut cmd, script_cmd) =
@@ -389,17 +538,11 @@ fn execute_script_in_project(
             print!("{}", String::from_utf8_lossy(&output.stdout));
         }
         println!("     {} {}", "✅".green(), "Completed successfully".green());
-    } else {
-        if !output.stderr.is_empty() {
-            eprint!("{}", String::from_utf8_lossy(&output.stderr));
-        }
-        return Err(anyhow::anyhow!(
-            "Script failed with exit code: {}",
-            output.status.code().unwrap_or(-1)
-        ));
+    } else if !output.stderr.is_empty() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
     }
 
-    Ok(())
+    Ok(output.status.code().unwrap_or(-1))
 }
 
 /// Execute a script in a specific project with buffered output (for parallel execution)
@@ -486,8 +629,50 @@ fn resolve_project_identifier(config: &MetaConfig, identifier: &str) -> Option<S
     None
 }
 
-/// List all available scripts
-pub fn list_scripts(base_path: &Path, project: Option<&str>) -> Result<()> {
+/// Print one `name → command` line, with the description and tags beneath.
+fn print_script(name: &str, entry: &ScriptEntry) {
+    println!(
+        "     {} {} {}",
+        name.bright_white(),
+        "→".bright_black(),
+        entry.command().bright_black()
+    );
+    let tags = if entry.tags().is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", entry.tags().join(", "))
+    };
+    if let Some(description) = entry.description() {
+        println!("       {}{}", description, tags.cyan());
+    } else if !tags.is_empty() {
+        println!("      {}", tags.cyan());
+    }
+}
+
+/// Whether `entry` carries `tag`; every script matches when no tag is given.
+fn tagged(entry: &ScriptEntry, tag: Option<&str>) -> bool {
+    tag.is_none_or(|t| entry.tags().iter().any(|x| x == t))
+}
+
+/// Print `scripts` sorted by name, keeping only those tagged `tag` (when
+/// given). Returns how many were printed.
+fn print_scripts<'a>(
+    scripts: impl IntoIterator<Item = (&'a String, &'a ScriptEntry)>,
+    tag: Option<&str>,
+) -> usize {
+    let mut scripts: Vec<_> = scripts
+        .into_iter()
+        .filter(|(_, entry)| tagged(entry, tag))
+        .collect();
+    scripts.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, entry) in &scripts {
+        print_script(name, entry);
+    }
+    scripts.len()
+}
+
+/// List all available scripts, optionally only those tagged `tag`
+pub fn list_scripts(base_path: &Path, project: Option<&str>, tag: Option<&str>) -> Result<()> {
     let config = load_config_with_script_cascade(base_path)?;
 
     println!("\n  {} {}", "📜".cyan(), "Available Scripts".bold());
@@ -495,16 +680,10 @@ pub fn list_scripts(base_path: &Path, project: Option<&str>) -> Result<()> {
 
     // Show global scripts
     if let Some(global_scripts) = &config.scripts {
-        if !global_scripts.is_empty() {
+        let matching = global_scripts.values().filter(|e| tagged(e, tag)).count();
+        if matching > 0 {
             println!("\n  {} {}", "🌍".blue(), "Global Scripts".bold());
-            for (name, cmd) in global_scripts {
-                println!(
-                    "     {} {} {}",
-                    name.bright_white(),
-                    "→".bright_black(),
-                    cmd.bright_black()
-                );
-            }
+            print_scripts(global_scripts, tag);
         }
     }
 
@@ -517,34 +696,23 @@ pub fn list_scripts(base_path: &Path, project: Option<&str>) -> Result<()> {
                 "Project Scripts".bold(),
                 format!("({})", project_name).bright_black()
             );
-            for (name, cmd) in project_scripts {
-                println!(
-                    "     {} {} {}",
-                    name.bright_white(),
-                    "→".bright_black(),
-                    cmd.bright_black()
-                );
-            }
+            print_scripts(&project_scripts, tag);
         }
     } else {
         // Show all project scripts
-        for (project_name, entry) in &config.projects {
-            if let ProjectEntry::Metadata(metadata) = entry {
-                if !metadata.scripts.is_empty() {
+        let mut names: Vec<&String> = config.projects.keys().collect();
+        names.sort();
+        for project_name in names {
+            if let Some(ProjectEntry::Metadata(metadata)) = config.projects.get(project_name) {
+                let matching = metadata.scripts.values().filter(|e| tagged(e, tag)).count();
+                if matching > 0 {
                     println!(
                         "\n  {} {} {}",
                         "📦".blue(),
                         project_name.bold(),
                         "(project)".bright_black()
                     );
-                    for (name, cmd) in &metadata.scripts {
-                        println!(
-                            "     {} {} {}",
-                            name.bright_white(),
-                            "→".bright_black(),
-                            cmd.bright_black()
-                        );
-                    }
+                    print_scripts(&metadata.scripts, tag);
                 }
             }
         }
@@ -553,3 +721,72 @@ pub fn list_scripts(base_path: &Path, project: Option<&str>) -> Result<()> {
     println!();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace_config() -> MetaConfig {
+        serde_json::from_str(
+            r#"{
+                "default_script": "serve",
+                "scripts": { "serve": "echo global-serve" },
+                "projects": {
+                    "api": { "url": "u", "default_script": "watch",
+                             "scripts": { "watch": "cargo watch", "dev": "cargo run" } },
+                    "web": "u",
+                    "docs": { "url": "u", "scripts": { "build": "mdbook build" } }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn entry_points_prefer_named_script_then_project_then_workspace_default() {
+        let config = workspace_config();
+        let tmp = tempfile::tempdir().unwrap();
+        let projects: Vec<String> = ["api", "docs", "web"].map(String::from).to_vec();
+
+        assert_eq!(
+            entry_point_plan(&config, &projects, None, tmp.path()),
+            vec![
+                (
+                    "serve".to_string(),
+                    vec!["docs".to_string(), "web".to_string()]
+                ),
+                ("watch".to_string(), vec!["api".to_string()]),
+            ]
+        );
+        assert_eq!(
+            entry_point_plan(&config, &projects, Some("dev"), tmp.path()),
+            vec![
+                ("dev".to_string(), vec!["api".to_string()]),
+                (
+                    "serve".to_string(),
+                    vec!["docs".to_string(), "web".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn workspace_default_is_skipped_where_the_script_is_missing() {
+        let mut config = workspace_config();
+        config.scripts = None;
+        let tmp = tempfile::tempdir().unwrap();
+        let projects: Vec<String> = ["api", "web"].map(String::from).to_vec();
+        assert_eq!(
+            entry_point_plan(&config, &projects, None, tmp.path()),
+            vec![("watch".to_string(), vec!["api".to_string()])]
+        );
+    }
+
+    #[test]
+    fn script_names_are_told_apart_from_projects() {
+        let config = workspace_config();
+        assert!(is_script_name(&config, "serve"));
+        assert!(is_script_name(&config, "build"));
+        assert!(!is_script_name(&config, "api"));
+    }
+}
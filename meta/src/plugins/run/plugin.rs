@@ -1,4 +1,7 @@
-use super::{list_scripts, run_script};
+use super::{
+    entry_point_plan, is_script_name, list_scripts, load_config_with_script_cascade, run_script,
+};
+use crate::plugins::shared::{outcome, selection, MuxOptions, RunOutcome};
 use anyhow::Result;
 use clap::ArgMatches;
 use colored::Colorize;
@@ -7,6 +10,7 @@ use metarepo_core::{
     NonInteractiveMode, RuntimeConfig,
 };
 use std::collections::HashMap;
+use std::time::Instant;
 
 /// RunPlugin for executing project scripts
 pub struct RunPlugin;
@@ -34,14 +38,23 @@ impl RunPlugin {
                          the in-scope projects that define it (based on your current directory). Run\n\
                          without a script name in a terminal to pick one interactively.\n\
                          \n\
-                         Use -p/--project or --projects to target specific projects, -a/--all to run\n\
-                         across the whole workspace, and --parallel to run concurrently. --git-only and\n\
-                         --existing-only restrict the project set, and -e/--env KEY=VALUE injects\n\
-                         environment variables into each run.\n\
+                         Use -p/--project, --projects, or project names after the script to target\n\
+                         specific projects; names may be globs ('services/*', 'libs/**'). A selector\n\
+                         that matches nothing is a warning, or an error with --strict. --exclude drops\n\
+                         projects after selection, and projects in the workspace default_excludes\n\
+                         list are skipped unless named. Use -a/--all to run across the whole\n\
+                         workspace, and --parallel to run concurrently. --git-only and --existing-only\n\
+                         restrict the project set, and -e/--env KEY=VALUE injects environment\n\
+                         variables into each run. --group NAME (repeatable) runs in the members\n\
+                         of a project group from .meta and, with no script name, runs the\n\
+                         group's default_script. --tag TAG keeps only projects with that tag in\n\
+                         .meta (across the workspace when nothing else is selected), and\n\
+                         --exclude-tag TAG drops them; both are repeatable.\n\
                          \n\
                          Examples:\n  \
                            meta run test\n  \
                            meta run build --all --parallel\n  \
+                           meta run build 'services/*' --strict\n  \
                            meta run deploy -p api -e ENV=staging",
                     )
                     .long_about("Run a script defined in the .meta file.\n\n\
@@ -72,6 +85,40 @@ impl RunPlugin {
                             .help("Comma-separated list of projects")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("targets")
+                            .help("Projects to run in (names, aliases, or globs such as 'services/*')")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Run in the members of a project group (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .help("Only run in projects with this tag in .meta (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("exclude-tag")
+                            .long("exclude-tag")
+                            .help("Skip projects with this tag in .meta (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing")
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true)
+                    )
                     .arg(
                         arg("all")
                             .long("all")
@@ -115,6 +162,29 @@ impl RunPlugin {
                             .long("tui")
                             .help("Open the interactive picker and live per-project output view")
                     )
+                    .arg(
+                        arg("only")
+                            .long("only")
+                            .help("Only show output from these projects (comma-separated)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("timestamps")
+                            .long("timestamps")
+                            .help("Prefix each output line with the time")
+                    )
+                    .arg(
+                        arg("log-file")
+                            .long("log-file")
+                            .help("Also write all output to this file")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("min-success")
+                            .long("min-success")
+                            .help("Exit 0 when at least this percentage of projects succeed")
+                            .takes_value(true)
+                    )
             )
             .command(
                 command("list")
@@ -125,10 +195,13 @@ impl RunPlugin {
                          Shows the scripts defined in the config (.meta), both global and\n\
                          per-project. By default it lists the scripts in scope for the current\n\
                          directory; pass -p/--project to list the scripts for a specific project.\n\
+                         Script descriptions are shown beneath each command, with tags in\n\
+                         brackets; --script-tag narrows the list to scripts carrying that tag.\n\
                          \n\
                          Examples:\n  \
                            meta run list\n  \
-                           meta run list -p api",
+                           meta run list -p api\n  \
+                           meta run list --script-tag ci",
                     )
                     .aliases(vec!["ls".to_string(), "l".to_string()])
                     .with_help_formatting()
@@ -139,6 +212,12 @@ impl RunPlugin {
                             .help("Show scripts for specific project")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("script-tag")
+                            .long("script-tag")
+                            .help("Only list scripts with this tag")
+                            .takes_value(true)
+                    )
             )
             .handler("script", handle_run_script)
             .handler("list", handle_list)
@@ -148,24 +227,26 @@ impl RunPlugin {
 
 /// Handler for the script command
 fn handle_run_script(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let started = Instant::now();
     let non_interactive = config
         .non_interactive
         .unwrap_or(NonInteractiveMode::Defaults);
 
     let base_path = config.meta_root().unwrap_or(config.working_dir.clone());
     // Directory-aware scope: when no explicit project is given, run only in the
-    // in-scope projects that define the script.
-    let scope = config.scoped_project_keys();
+    // in-scope projects that define the script. Excludes (--exclude and the
+    // workspace default_excludes) and tag filters are applied after the scope
+    // is resolved; --tag on its own widens the scope to the whole workspace.
+    let mut scope = selection::implicit_scope(matches, config);
+    selection::apply_excludes(matches, config, &mut scope, false);
+    selection::apply_tags(matches, config, &mut scope)?;
 
-    // Parse environment variables (shared by the CLI and TUI paths).
-    let mut env_vars = HashMap::new();
-    if let Some(env_args) = matches.get_many::<String>("env") {
-        for env_str in env_args {
-            if let Some((key, value)) = env_str.split_once('=') {
-                env_vars.insert(key.to_string(), value.to_string());
-            }
-        }
-    }
+    let env_vars = env_vars_from(matches);
+    // A script named on the command line wins over the --group default.
+    let script = match matches.get_one::<String>("script") {
+        Some(name) => Some(name.clone()),
+        None => group_default_script(matches, config)?,
+    };
 
     // Interactive TUI: fuzzy-pick a script (unless one was named) and show a live
     // per-project output view.
@@ -173,15 +254,54 @@ fn handle_run_script(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()>
         if !is_interactive() {
             return Err(anyhow::anyhow!("--tui requires an interactive terminal"));
         }
-        let preselected = matches.get_one::<String>("script").map(|s| s.as_str());
+        let preselected = script.as_deref();
         super::tui::run_tui(preselected, &base_path, &scope, &env_vars)?;
         return Ok(());
     }
 
+    // Collect selected projects; --exclude is applied after inclusion.
+    let mut projects = Vec::new();
+
+    if matches.get_flag("all") {
+        projects = selection::all_projects(matches, config);
+    } else if let Some(mut selected) = selection::explicit_projects(matches, config)? {
+        selection::apply_excludes(matches, config, &mut selected, true);
+        projects = selected;
+    }
+    selection::apply_tags(matches, config, &mut projects)?;
+    if projects.is_empty() && (matches.get_flag("all") || selection::has_selection(matches)) {
+        println!(
+            "  {} Every selected project is excluded",
+            "ℹ".bright_black()
+        );
+        return Ok(());
+    }
+    // If no projects specified, will use current project or find projects with script
+
+    // `meta run <project>`: a name that is no script but resolves to a project
+    // runs that project's default script.
+    if let Some(name) = &script {
+        let scripts = load_config_with_script_cascade(&base_path)?;
+        if projects.is_empty() && !is_script_name(&scripts, name) {
+            if let Some(project) = config.resolve_project(name) {
+                if run_entry_points(matches, config, std::slice::from_ref(&project), None)? {
+                    return Ok(());
+                }
+                return Err(anyhow::anyhow!(
+                    "Project '{}' has no default_script; name a script to run",
+                    project
+                ));
+            }
+        }
+    }
+
     // Get or prompt for script name
-    let script_name = match matches.get_one::<String>("script") {
-        Some(s) => s.clone(),
+    let script_name = match script {
+        Some(s) => s,
         None => {
+            if run_entry_points(matches, config, &projects, None)? {
+                return Ok(());
+            }
             if is_interactive() {
                 // Get all available scripts
                 let all_scripts = config.meta_config.get_all_scripts(None);
@@ -202,50 +322,126 @@ fn handle_run_script(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()>
         }
     };
 
-    let parallel = matches.get_flag("parallel");
-    let existing_only = matches.get_flag("existing-only");
-    let git_only = matches.get_flag("git-only");
-    let no_progress = matches.get_flag("no-progress");
-    let streaming = matches.get_flag("streaming");
-
-    // Collect selected projects
-    let mut projects = Vec::new();
+    run_script(
+        &script_name,
+        &projects,
+        &base_path,
+        &scope,
+        matches.get_flag("parallel"),
+        matches.get_flag("existing-only"),
+        matches.get_flag("git-only"),
+        matches.get_flag("no-progress"),
+        matches.get_flag("streaming"),
+        &env_vars,
+        &MuxOptions::from_matches(matches),
+    )?
+    .finish(
+        &config.meta_config,
+        &base_path,
+        "run",
+        started,
+        outcome::min_success(matches, &config.meta_config, "run")?,
+    )
+}
 
-    if matches.get_flag("all") {
-        projects.push("--all".to_string());
-    } else if let Some(project) = matches.get_one::<String>("project") {
-        // Use resolve_project to handle aliases
-        if let Some(resolved) = config.resolve_project(project) {
-            projects.push(resolved);
-        } else {
-            projects.push(project.clone());
+/// The `default_script` of the groups selected with `--group`, or `None` when
+/// none of them sets one. Groups that disagree are an error.
+fn group_default_script(matches: &ArgMatches, config: &RuntimeConfig) -> Result<Option<String>> {
+    let mut scripts = Vec::new();
+    for name in selection::groups_from(matches) {
+        if let Some(script) = &config.meta_config.group(&name)?.default_script {
+            if !scripts.contains(script) {
+                scripts.push(script.clone());
+            }
         }
-    } else if let Some(project_list) = matches.get_one::<String>("projects") {
-        for p in project_list.split(',') {
-            let trimmed = p.trim();
-            // Use resolve_project to handle aliases
-            if let Some(resolved) = config.resolve_project(trimmed) {
-                projects.push(resolved);
-            } else {
-                projects.push(trimmed.to_string());
+    }
+    match scripts.len() {
+        0 | 1 => Ok(scripts.pop()),
+        _ => Err(anyhow::anyhow!(
+            "The selected groups have different default scripts ({}); name a script to run",
+            scripts.join(", ")
+        )),
+    }
+}
+
+/// Parse `-e KEY=VALUE` pairs (shared by the CLI and TUI paths, and by
+/// `meta services up`).
+pub fn env_vars_from(matches: &ArgMatches) -> HashMap<String, String> {
+    let mut env_vars = HashMap::new();
+    if let Ok(Some(env_args)) = matches.try_get_many::<String>("env") {
+        for env_str in env_args {
+            if let Some((key, value)) = env_str.split_once('=') {
+                env_vars.insert(key.to_string(), value.to_string());
             }
         }
     }
-    // If no projects specified, will use current project or find projects with script
+    env_vars
+}
 
-    run_script(
-        &script_name,
-        &projects,
+/// Whether `--<id>` was passed; false when the command has no such flag.
+fn flag(matches: &ArgMatches, id: &str) -> bool {
+    matches
+        .try_get_one::<bool>(id)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Run each project's entry point (see [`entry_point_plan`]) in `projects`, or
+/// in the directory scope when `projects` is empty. Returns false, running
+/// nothing, when no project has an entry point, and an error carrying the exit
+/// code when projects fail (see [`outcome`]).
+pub(crate) fn run_entry_points(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+    projects: &[String],
+    preferred: Option<&str>,
+) -> Result<bool> {
+    let started = Instant::now();
+    let base_path = config.meta_root().unwrap_or(config.working_dir.clone());
+    let mut scope = selection::implicit_scope(matches, config);
+    selection::apply_excludes(matches, config, &mut scope, false);
+    selection::apply_tags(matches, config, &mut scope)?;
+    let candidates = if projects.is_empty() {
+        &scope
+    } else {
+        projects
+    };
+
+    let scripts = load_config_with_script_cascade(&base_path)?;
+    let plan = entry_point_plan(&scripts, candidates, preferred, &base_path);
+    if plan.is_empty() {
+        return Ok(false);
+    }
+
+    let env_vars = env_vars_from(matches);
+    let min_success = outcome::min_success(matches, &scripts, preferred.unwrap_or("run"))?;
+    let mut total = RunOutcome::default();
+    for (script, group) in &plan {
+        let result = run_script(
+            script,
+            group,
+            &base_path,
+            &scope,
+            flag(matches, "parallel"),
+            flag(matches, "existing-only"),
+            flag(matches, "git-only"),
+            flag(matches, "no-progress"),
+            flag(matches, "streaming"),
+            &env_vars,
+            &MuxOptions::from_matches(matches),
+        )?;
+        total.merge(result);
+    }
+    total.finish(
+        &scripts,
         &base_path,
-        &scope,
-        parallel,
-        existing_only,
-        git_only,
-        no_progress,
-        streaming,
-        &env_vars,
+        preferred.unwrap_or("run"),
+        started,
+        min_success,
     )?;
-    Ok(())
+    Ok(true)
 }
 
 /// Handler for the list command
@@ -258,7 +454,8 @@ fn handle_list(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
         config.current_project()
     };
 
-    list_scripts(&base_path, project.as_deref())?;
+    let tag = matches.get_one::<String>("script-tag").map(|s| s.as_str());
+    list_scripts(&base_path, project.as_deref(), tag)?;
     Ok(())
 }
 
@@ -278,20 +475,50 @@ impl MetaPlugin for RunPlugin {
                  Scripts can be defined globally or per-project; a project script overrides a\n\
                  global script of the same name. With no project selection, run uses the\n\
                  directory-aware scope and runs the script in the in-scope projects that define\n\
-                 it. Run with no script name (or -l/--list) lists the available scripts instead.\n\
+                 it. With no script name, run executes each in-scope project's default_script\n\
+                 (falling back to the workspace default_script); without one it lists the\n\
+                 available scripts, as -l/--list does. `meta run <project>` runs that\n\
+                 project's default script. --group NAME (repeatable) runs in the members of a\n\
+                 project group from .meta and, with no script name, runs the group's\n\
+                 default_script. --tag TAG keeps only projects with that tag in .meta\n\
+                 (across the workspace when nothing else is selected), and --exclude-tag\n\
+                 TAG drops them; both are repeatable. --script-tag TAG lists only the\n\
+                 scripts carrying that tag, as -l/--list does for every script.\n\
                  \n\
-                 Use -p/--project or --projects to target specific projects, -a/--all to run\n\
-                 across the whole workspace, and --parallel to run concurrently. --git-only and\n\
-                 --existing-only restrict the project set, and -e/--env KEY=VALUE injects\n\
-                 environment variables into each run.\n\
+                 Use -p/--project, --projects, or project names after the script to target\n\
+                 specific projects; names may be globs ('services/*', 'libs/**'). A selector\n\
+                 that matches nothing is a warning, or an error with --strict. --exclude drops\n\
+                 projects after selection, and projects in the workspace default_excludes\n\
+                 list are skipped unless named. Use -a/--all to run across the whole\n\
+                 workspace, and --parallel to run concurrently. --git-only and --existing-only\n\
+                 restrict the project set, and -e/--env KEY=VALUE injects environment\n\
+                 variables into each run.\n\
                  \n\
                  Pass --tui for an interactive picker of the available scripts followed by a\n\
                  live per-project output view (requires a terminal).\n\
                  \n\
+                 --only, --timestamps, and --log-file stream each project's output live with\n\
+                 a colored project prefix: --only shows just the named projects, --timestamps\n\
+                 adds the time to each line, and --log-file also writes all output to one\n\
+                 file. --parallel --streaming uses the same live output.\n\
+                 \n\
+                 Exit codes: 0 when every project succeeds, 1 when all fail, and 2 when some\n\
+                 fail. --min-success PCT (or min_success.run in .meta) exits 0 as long as at\n\
+                 least PCT percent of the projects succeed.\n\
+                 \n\
+                 Projects without a .meta script of that name can fall back to their own task\n\
+                 runner: set run.fallback (e.g. [\"npm\", \"just\"]) and the first listed runner\n\
+                 whose package.json, justfile, Makefile.toml (cargo-make), or Makefile defines\n\
+                 the task runs it.\n\
+                 \n\
                  Examples:\n  \
                    meta run test\n  \
                    meta run build --all --parallel\n  \
+                   meta run build 'services/*' --strict\n  \
+                   meta run test --tag backend --exclude-tag legacy\n  \
+                   meta run --script-tag ci\n  \
                    meta run --tui\n  \
+                   meta run test --all --parallel --only api --log-file test.log\n  \
                    meta run deploy -p api -e ENV=staging",
             ))
             .version(env!("CARGO_PKG_VERSION"))
@@ -314,6 +541,32 @@ impl MetaPlugin for RunPlugin {
                     .help("Comma-separated list of projects")
                     .value_name("PROJECTS"),
             )
+            .arg(
+                clap::Arg::new("targets")
+                    .help("Projects to run in (names, aliases, or globs such as 'services/*')")
+                    .index(2)
+                    .num_args(1..)
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("group")
+                    .long("group")
+                    .help("Run in the members of a project group (repeatable)")
+                    .value_name("GROUP")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("strict")
+                    .long("strict")
+                    .help("Fail if any project selector matches nothing")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("exclude")
+                    .long("exclude")
+                    .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                    .value_name("PROJECTS"),
+            )
             .arg(
                 clap::Arg::new("all")
                     .long("all")
@@ -342,6 +595,27 @@ impl MetaPlugin for RunPlugin {
                     .help("List available scripts")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                clap::Arg::new("tag")
+                    .long("tag")
+                    .help("Only run in projects with this tag in .meta (repeatable)")
+                    .value_name("TAG")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("exclude-tag")
+                    .long("exclude-tag")
+                    .help("Skip projects with this tag in .meta (repeatable)")
+                    .value_name("TAG")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("script-tag")
+                    .long("script-tag")
+                    .help("List only the scripts with this tag (implies --list)")
+                    .value_name("TAG")
+                    .conflicts_with("script"),
+            )
             .arg(
                 clap::Arg::new("existing-only")
                     .long("existing-only")
@@ -371,6 +645,31 @@ impl MetaPlugin for RunPlugin {
                     .long("tui")
                     .help("Open the interactive picker and live per-project output view")
                     .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("only")
+                    .long("only")
+                    .help("Only show output from these projects (comma-separated)")
+                    .action(clap::ArgAction::Append)
+                    .value_name("PROJECTS"),
+            )
+            .arg(
+                clap::Arg::new("timestamps")
+                    .long("timestamps")
+                    .help("Prefix each output line with the time")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("log-file")
+                    .long("log-file")
+                    .help("Also write all output to this file")
+                    .value_name("FILE"),
+            )
+            .arg(
+                clap::Arg::new("min-success")
+                    .long("min-success")
+                    .help("Exit 0 when at least this percentage of projects succeed")
+                    .value_name("PCT"),
             );
 
         app.subcommand(run_cmd)
@@ -383,19 +682,46 @@ impl MetaPlugin for RunPlugin {
             return handle_run_script(matches, config);
         }
 
-        // Check for list flag
-        if matches.get_flag("list") {
+        // Check for list flag; --script-tag implies it
+        if matches.get_flag("list") || matches.contains_id("script-tag") {
+            if selection::has_tag_filter(matches) {
+                return Err(anyhow::anyhow!(
+                    "--tag and --exclude-tag select projects, not scripts; \
+                     use --script-tag to list only the scripts with a tag"
+                ));
+            }
             return handle_list(matches, config);
         }
 
         // Check if script is provided
-        if matches.get_one::<String>("script").is_some() {
+        if matches.get_one::<String>("script").is_some()
+            || group_default_script(matches, config)?.is_some()
+        {
             return handle_run_script(matches, config);
         }
 
-        // No script provided, list available scripts
+        // No script provided: run the default script where one is configured,
+        // otherwise list available scripts
+        let mut projects = Vec::new();
+        if let Some(mut selected) = selection::explicit_projects(matches, config)? {
+            selection::apply_excludes(matches, config, &mut selected, true);
+            projects = selected;
+        }
+        selection::apply_tags(matches, config, &mut projects)?;
+        if run_entry_points(matches, config, &projects, None)? {
+            return Ok(());
+        }
         handle_list(matches, config)
     }
+
+    fn settings(&self) -> Vec<metarepo_core::ConfigSetting> {
+        use metarepo_core::{ConfigSetting, ConfigValueType};
+        vec![ConfigSetting::new(
+            "run.fallback",
+            "Task runners to try when a project has no .meta script of that name",
+            ConfigValueType::StringList,
+        )]
+    }
 }
 
 impl BasePlugin for RunPlugin {
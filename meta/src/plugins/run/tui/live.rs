@@ -95,12 +95,10 @@ impl LiveRunView {
         }
 
         match code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selected + 1 < self.projects.len() {
-                    self.selected += 1;
-                    self.scroll = 0;
-                    self.follow = true;
-                }
+            KeyCode::Char('j') | KeyCode::Down if self.selected + 1 < self.projects.len() => {
+                self.selected += 1;
+                self.scroll = 0;
+                self.follow = true;
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 self.selected = self.selected.saturating_sub(1);
@@ -65,7 +65,7 @@ pub(crate) fn run_tui(
     let script = scripts[idx].clone();
 
     // Projects: those in scope that define this script.
-    let projects = scripts_scoped_projects(&config, &script.name, scope);
+    let projects = scripts_scoped_projects(&config, &script.name, scope, base_path);
     if projects.is_empty() {
         restore_terminal(terminal)?;
         println!("No in-scope projects define script '{}'.", script.name);
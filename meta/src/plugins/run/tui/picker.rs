@@ -2,7 +2,7 @@
 //!
 //! Modeled on the skill picker but single-select: Enter confirms the script row
 //! under the cursor. Printable keys extend a case-insensitive filter over the
-//! script name and command, so this surface deliberately does NOT use the shared
+//! script name, command, and description, so this surface deliberately does NOT use the shared
 //! `Action` keymap. The selection/filter logic lives in [`PickerState`]
 //! (unit-tested); the render + event loop wraps it.
 
@@ -34,7 +34,7 @@ pub(crate) struct PickerState {
     visible: Vec<usize>,
     /// Cursor position as an index into `visible`.
     cursor: usize,
-    /// Current filter query (case-insensitive substring on name/command).
+    /// Current filter query (case-insensitive substring on name/command/description).
     filter: String,
 }
 
@@ -81,6 +81,10 @@ impl PickerState {
                 q.is_empty()
                     || it.name.to_lowercase().contains(&q)
                     || it.command.to_lowercase().contains(&q)
+                    || it
+                        .description
+                        .as_ref()
+                        .is_some_and(|d| d.to_lowercase().contains(&q))
             })
             .map(|(i, _)| i)
             .collect();
@@ -194,7 +198,13 @@ fn render(f: &mut Frame, state: &PickerState, table_state: &mut TableState) {
             let it = &state.items[i];
             Row::new(vec![
                 Cell::from(it.name.clone()).style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from(it.command.clone()).style(Style::default().fg(Color::DarkGray)),
+                // The description says more than the raw command when present.
+                match &it.description {
+                    Some(description) => Cell::from(description.clone()),
+                    None => {
+                        Cell::from(it.command.clone()).style(Style::default().fg(Color::DarkGray))
+                    }
+                },
                 Cell::from(it.projects.len().to_string()).style(Style::default().fg(Color::Cyan)),
             ])
         })
@@ -209,7 +219,7 @@ fn render(f: &mut Frame, state: &PickerState, table_state: &mut TableState) {
         ],
     )
     .header(
-        Row::new(vec!["Script", "Command", "Projects"])
+        Row::new(vec!["Script", "Description / command", "Projects"])
             .style(Style::default().add_modifier(Modifier::BOLD)),
     )
     .block(
@@ -236,16 +246,19 @@ mod tests {
             ScriptInfo {
                 name: "build".into(),
                 command: "cargo build".into(),
+                description: None,
                 projects: vec!["a".into()],
             },
             ScriptInfo {
                 name: "test".into(),
                 command: "cargo test".into(),
+                description: Some("Run the unit tests".into()),
                 projects: vec!["a".into(), "b".into()],
             },
             ScriptInfo {
                 name: "lint".into(),
                 command: "cargo clippy".into(),
+                description: None,
                 projects: vec!["b".into()],
             },
         ]
@@ -262,6 +275,13 @@ mod tests {
         assert_eq!(s.visible_indices(), &[0]);
     }
 
+    #[test]
+    fn filter_matches_description() {
+        let mut s = PickerState::new(items());
+        s.set_filter("UNIT");
+        assert_eq!(s.visible_indices(), &[1]);
+    }
+
     #[test]
     fn typing_builds_filter_then_enter_confirms_current() {
         let mut s = PickerState::new(items());
@@ -0,0 +1,306 @@
+//! `meta verify-setup`: run each project's `verify` script, a health check
+//! for a fresh checkout (dependencies installed, the build compiles, the
+//! environment is set), and report the workspace as a checklist. Meant for
+//! new developers after `meta git update` has cloned everything.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use metarepo_core::{BasePlugin, MetaConfig, MetaPlugin, RuntimeConfig};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{build_script_command, load_config_with_script_cascade, project_has_script};
+use crate::plugins::shared::selection;
+
+/// The script a project declares to check its own setup.
+pub const VERIFY_SCRIPT: &str = "verify";
+
+/// One project's line in the checklist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Check {
+    Passed(Duration),
+    /// The script failed (or could not start); `detail` is the last line it
+    /// printed, usually the reason.
+    Failed {
+        code: i32,
+        detail: String,
+    },
+    NotCloned,
+    NoScript,
+}
+
+/// The last non-empty line of `output`, trimmed.
+fn last_line(output: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Run `project`'s verify script and classify the result.
+pub(crate) fn check_project(config: &MetaConfig, project: &str, base_path: &Path) -> Check {
+    if !base_path.join(project).exists() {
+        return Check::NotCloned;
+    }
+    if !project_has_script(config, VERIFY_SCRIPT, project, base_path) {
+        return Check::NoScript;
+    }
+    let started = Instant::now();
+    let output = build_script_command(config, VERIFY_SCRIPT, project, base_path, &HashMap::new())
+        .and_then(|(mut cmd, _)| Ok(cmd.output()?));
+    match output {
+        Ok(output) if output.status.success() => Check::Passed(started.elapsed()),
+        Ok(output) => Check::Failed {
+            code: output.status.code().unwrap_or(-1),
+            detail: last_line(&output.stderr)
+                .or_else(|| last_line(&output.stdout))
+                .unwrap_or_default(),
+        },
+        Err(e) => Check::Failed {
+            code: -1,
+            detail: e.to_string(),
+        },
+    }
+}
+
+/// Print the checklist and return how many projects are not ready (failed
+/// or not cloned).
+pub(crate) fn print_checklist(results: &[(String, Check)]) -> usize {
+    let width = results
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let (mut passed, mut failed, mut missing, mut unchecked) = (0, 0, 0, 0);
+    println!("\n  {}\n", "Setup checklist".bold());
+    for (name, check) in results {
+        let name = format!("{:<width$}", name, width = width);
+        match check {
+            Check::Passed(took) => {
+                passed += 1;
+                let took = format!("({:.1}s)", took.as_secs_f64());
+                println!(
+                    "  {} {}  verify passed {}",
+                    "✓".green(),
+                    name,
+                    took.bright_black()
+                );
+            }
+            Check::Failed { code, detail } => {
+                failed += 1;
+                let status = match code {
+                    -1 => "verify failed".to_string(),
+                    code => format!("verify failed (exit {})", code),
+                };
+                println!("  {} {}  {}", "✗".red(), name, status.red());
+                if !detail.is_empty() {
+                    println!("  {}  {}", " ".repeat(width + 2), detail.bright_black());
+                }
+            }
+            Check::NotCloned => {
+                missing += 1;
+                println!(
+                    "  {} {}  {}",
+                    "✗".red(),
+                    name,
+                    "not cloned; run `meta git update`".red()
+                );
+            }
+            Check::NoScript => {
+                unchecked += 1;
+                println!(
+                    "  {} {}  {}",
+                    "·".bright_black(),
+                    name,
+                    "no verify script".bright_black()
+                );
+            }
+        }
+    }
+    println!(
+        "\n  {} passed, {} failed, {} not cloned, {} without a verify script",
+        passed.to_string().green(),
+        if failed > 0 {
+            failed.to_string().red()
+        } else {
+            "0".normal()
+        },
+        if missing > 0 {
+            missing.to_string().red()
+        } else {
+            "0".normal()
+        },
+        unchecked
+    );
+    failed + missing
+}
+
+/// Registers `meta verify-setup`.
+pub struct VerifySetupPlugin;
+
+impl MetaPlugin for VerifySetupPlugin {
+    fn name(&self) -> &str {
+        "verify-setup"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("verify-setup")
+                .about("Check that each project is set up, using its verify script")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Run the `verify` script of each project in your directory scope (or\n\
+                     the named projects) and print a checklist: which projects pass, which\n\
+                     fail and why, which are not cloned yet, and which declare no check.\n\
+                     \n\
+                     A verify script is an ordinary script in the project's .meta entry\n\
+                     (or a `verify` task found through run.fallback) that exits non-zero\n\
+                     when something is missing: dependencies not installed, a build that\n\
+                     does not compile, an unset environment variable.\n\
+                     \n\
+                     Exits 1 when any project fails its check or is not cloned.\n\
+                     \n\
+                     Examples:\n  \
+                       meta verify-setup\n  \
+                       meta verify-setup api web --parallel\n",
+                ))
+                .arg(
+                    Arg::new(selection::TARGETS_ARG)
+                        .help("Projects to check (names, aliases, or globs)")
+                        .num_args(1..)
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("strict")
+                        .long("strict")
+                        .help("Fail if any project selector matches nothing")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("exclude")
+                        .long("exclude")
+                        .help(
+                            "Skip these projects (names, aliases, globs, or tags; comma-separated)",
+                        )
+                        .value_name("PROJECTS"),
+                )
+                .arg(
+                    Arg::new("parallel")
+                        .long("parallel")
+                        .help("Run the checks at once")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let base_path = config
+            .meta_root()
+            .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+        let meta_config = load_config_with_script_cascade(&base_path)?;
+
+        let explicit = selection::explicit_projects(matches, config)?;
+        let mut projects = match &explicit {
+            Some(selected) => selected.clone(),
+            None => config.scoped_project_keys(),
+        };
+        selection::apply_excludes(matches, config, &mut projects, explicit.is_some());
+        projects.sort();
+        if projects.is_empty() {
+            println!("No projects in scope.");
+            return Ok(());
+        }
+
+        let check = |project: &String| check_project(&meta_config, project, &base_path);
+        let results: Vec<(String, Check)> = if matches.get_flag("parallel") {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = projects
+                    .iter()
+                    .map(|project| scope.spawn(move || (project.clone(), check(project))))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("verify thread panicked"))
+                    .collect()
+            })
+        } else {
+            projects.iter().map(|p| (p.clone(), check(p))).collect()
+        };
+
+        if results.iter().all(|(_, check)| *check == Check::NoScript) {
+            println!(
+                "No project in scope declares a '{}' script. Add one to a project's\n\
+                 .meta entry, e.g. \"scripts\": {{\"verify\": \"npm run build\"}}.",
+                VERIFY_SCRIPT
+            );
+            return Ok(());
+        }
+        let not_ready = print_checklist(&results);
+        if not_ready > 0 {
+            return Err(anyhow::anyhow!(
+                "{} project(s) are not set up correctly",
+                not_ready
+            ));
+        }
+        println!("\n  {} Workspace is ready.", "✓".green());
+        Ok(())
+    }
+}
+
+impl BasePlugin for VerifySetupPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Check each project's setup with its verify script")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_each_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path();
+        for dir in ["ok", "broken", "plain"] {
+            std::fs::create_dir(base.join(dir)).unwrap();
+        }
+        let config: MetaConfig = serde_json::from_str(
+            r#"{
+                "projects": {
+                    "ok": { "url": "u", "scripts": { "verify": "true" } },
+                    "broken": { "url": "u", "scripts": {
+                        "verify": "sh -c 'echo checking; echo missing node_modules >&2; exit 2'"
+                    } },
+                    "missing": { "url": "u", "scripts": { "verify": "true" } },
+                    "plain": "u"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            check_project(&config, "ok", base),
+            Check::Passed(_)
+        ));
+        assert_eq!(
+            check_project(&config, "broken", base),
+            Check::Failed {
+                code: 2,
+                detail: "missing node_modules".to_string()
+            }
+        );
+        assert_eq!(check_project(&config, "missing", base), Check::NotCloned);
+        assert_eq!(check_project(&config, "plain", base), Check::NoScript);
+    }
+}
@@ -0,0 +1,321 @@
+//! Five-field cron expressions, and their translation for the schedulers
+//! that do not read cron syntax themselves.
+
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+/// The fields of a cron expression: `(name, lowest, highest)`.
+const FIELDS: [(&str, u32, u32); 5] = [
+    ("minute", 0, 59),
+    ("hour", 0, 23),
+    ("day of month", 1, 31),
+    ("month", 1, 12),
+    ("day of week", 0, 7),
+];
+
+/// Shorthands accepted in place of the five fields.
+const ALIASES: [(&str, &str); 4] = [
+    ("@hourly", "0 * * * *"),
+    ("@daily", "0 0 * * *"),
+    ("@weekly", "0 0 * * 0"),
+    ("@monthly", "0 0 1 * *"),
+];
+
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// The values one field matches; `None` for `*`.
+pub type Field = Option<BTreeSet<u32>>;
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    /// The expression as given, with aliases expanded.
+    pub text: String,
+    pub minute: Field,
+    pub hour: Field,
+    pub day: Field,
+    pub month: Field,
+    /// Sunday is 0; a 7 in the expression is read as 0 too.
+    pub weekday: Field,
+}
+
+/// One part of a field: `*`, `*/n`, `a`, `a-b`, or `a-b/n`.
+fn parse_part(part: &str, low: u32, high: u32) -> Option<Vec<u32>> {
+    let (range, step) = match part.split_once('/') {
+        Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)?),
+        None => (part, 1),
+    };
+    let (start, end) = match range {
+        "*" => (low, high),
+        _ => match range.split_once('-') {
+            Some((a, b)) => (a.parse().ok()?, b.parse().ok()?),
+            None if step > 1 => (range.parse().ok()?, high),
+            None => {
+                let value = range.parse().ok()?;
+                (value, value)
+            }
+        },
+    };
+    if start < low || end > high || start > end {
+        return None;
+    }
+    Some((start..=end).step_by(step as usize).collect())
+}
+
+fn parse_field(text: &str, (name, low, high): (&str, u32, u32)) -> Result<Field> {
+    if text == "*" {
+        return Ok(None);
+    }
+    let mut values = BTreeSet::new();
+    for part in text.split(',') {
+        let parsed = parse_part(part, low, high).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid {} '{}': use numbers from {} to {}, ranges, lists, or steps",
+                name,
+                text,
+                low,
+                high
+            )
+        })?;
+        values.extend(parsed);
+    }
+    Ok(Some(values))
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = expr.trim();
+        let text = ALIASES
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(expr))
+            .map(|(_, fields)| fields.to_string())
+            .unwrap_or_else(|| expr.split_whitespace().collect::<Vec<_>>().join(" "));
+        let fields: Vec<&str> = text.split(' ').collect();
+        if fields.len() != 5 {
+            return Err(anyhow::anyhow!(
+                "Invalid schedule '{}': expected five fields (minute hour day month weekday), \
+                 such as \"0 9 * * 1-5\"",
+                expr
+            ));
+        }
+        let mut parsed = fields
+            .iter()
+            .zip(FIELDS)
+            .map(|(text, field)| parse_field(text, field))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+        let mut next = || parsed.next().expect("five fields");
+        let (minute, hour, day, month) = (next(), next(), next(), next());
+        let weekday = next().map(|days| days.into_iter().map(|d| d % 7).collect());
+        Ok(Cron {
+            text,
+            minute,
+            hour,
+            day,
+            month,
+            weekday,
+        })
+    }
+
+    /// launchd `StartCalendarInterval` entries: `(key, value)` pairs, one
+    /// list per entry, firing when any entry matches. Like cron, a
+    /// restricted day of the month and day of the week each fire on their
+    /// own.
+    pub fn calendar_intervals(&self) -> Vec<Vec<(&'static str, u32)>> {
+        let common = [
+            ("Month", &self.month),
+            ("Hour", &self.hour),
+            ("Minute", &self.minute),
+        ];
+        let days: Vec<[(&'static str, &Field); 1]> = match (&self.day, &self.weekday) {
+            (Some(_), Some(_)) => vec![[("Day", &self.day)], [("Weekday", &self.weekday)]],
+            (Some(_), None) => vec![[("Day", &self.day)]],
+            _ => vec![[("Weekday", &self.weekday)]],
+        };
+        let mut intervals = Vec::new();
+        for day in days {
+            let mut entries: Vec<Vec<(&'static str, u32)>> = vec![Vec::new()];
+            for (key, field) in day.iter().chain(common.iter()) {
+                let Some(values) = field else {
+                    continue;
+                };
+                entries = entries
+                    .into_iter()
+                    .flat_map(|entry| {
+                        values.iter().map(move |value| {
+                            let mut entry = entry.clone();
+                            entry.push((*key, *value));
+                            entry
+                        })
+                    })
+                    .collect();
+            }
+            intervals.extend(entries);
+        }
+        intervals
+    }
+
+    /// `schtasks /Create` arguments for the schedule. Task Scheduler takes a
+    /// repeat every few minutes, or one time of day (or minute past each
+    /// hour) on every day, some weekdays, or some days of the month.
+    pub fn schtasks_args(&self) -> Result<Vec<String>> {
+        let single = |field: &Field| match field {
+            Some(values) if values.len() == 1 => values.first().copied(),
+            _ => None,
+        };
+        let unsupported = || {
+            anyhow::anyhow!(
+                "Task Scheduler cannot run on '{}': use one time of day (with weekdays or days \
+                 of the month), a minute past every hour, or */N minutes",
+                self.text
+            )
+        };
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let dates_any = self.day.is_none() && self.month.is_none() && self.weekday.is_none();
+
+        if self.hour.is_none() && dates_any {
+            if let Some(minute) = single(&self.minute) {
+                return Ok(args(&[
+                    "/SC",
+                    "HOURLY",
+                    "/ST",
+                    &format!("00:{:02}", minute),
+                ]));
+            }
+            // `*/N` lists every Nth minute from 0; N must divide the hour
+            // for a plain repeat to match it.
+            let every = match &self.minute {
+                None => Some(1),
+                Some(values) => {
+                    let first: Vec<u32> = values.iter().take(2).copied().collect();
+                    let step = first.get(1).map(|b| b - first[0]);
+                    step.filter(|step| {
+                        first[0] == 0
+                            && 60 % step == 0
+                            && values.iter().copied().eq((0..60).step_by(*step as usize))
+                    })
+                }
+            };
+            let every = every.ok_or_else(unsupported)?;
+            return Ok(args(&["/SC", "MINUTE", "/MO", &every.to_string()]));
+        }
+
+        let (Some(hour), Some(minute)) = (single(&self.hour), single(&self.minute)) else {
+            return Err(unsupported());
+        };
+        let time = format!("{:02}:{:02}", hour, minute);
+        if self.month.is_some() {
+            return Err(unsupported());
+        }
+        let list = |values: &BTreeSet<u32>, name: &dyn Fn(u32) -> String| {
+            values
+                .iter()
+                .map(|v| name(*v))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        match (&self.day, &self.weekday) {
+            (None, None) => Ok(args(&["/SC", "DAILY", "/ST", &time])),
+            (None, Some(days)) => {
+                let days = list(days, &|d| WEEKDAYS[d as usize].to_string());
+                Ok(args(&["/SC", "WEEKLY", "/D", &days, "/ST", &time]))
+            }
+            (Some(days), None) => {
+                let days = list(days, &|d| d.to_string());
+                Ok(args(&["/SC", "MONTHLY", "/D", &days, "/ST", &time]))
+            }
+            (Some(_), Some(_)) => Err(unsupported()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields_and_aliases() {
+        let cron = Cron::parse("0 9 * * 1-5").unwrap();
+        assert_eq!(cron.minute, Some(BTreeSet::from([0])));
+        assert_eq!(cron.hour, Some(BTreeSet::from([9])));
+        assert_eq!(cron.day, None);
+        assert_eq!(cron.weekday, Some(BTreeSet::from([1, 2, 3, 4, 5])));
+
+        let cron = Cron::parse("*/20 8-18/5 1,15 * 7").unwrap();
+        assert_eq!(cron.minute, Some(BTreeSet::from([0, 20, 40])));
+        assert_eq!(cron.hour, Some(BTreeSet::from([8, 13, 18])));
+        assert_eq!(cron.weekday, Some(BTreeSet::from([0])));
+
+        assert_eq!(Cron::parse("@daily").unwrap().text, "0 0 * * *");
+        assert_eq!(Cron::parse(" 0  9 * *  * ").unwrap().text, "0 9 * * *");
+
+        for bad in [
+            "0 9 * *",
+            "60 * * * *",
+            "0 9 0 * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+        ] {
+            assert!(Cron::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn translates_for_launchd_and_task_scheduler() {
+        let weekdays = Cron::parse("30 9 * * 1,3").unwrap();
+        assert_eq!(
+            weekdays.calendar_intervals(),
+            vec![
+                vec![("Weekday", 1), ("Hour", 9), ("Minute", 30)],
+                vec![("Weekday", 3), ("Hour", 9), ("Minute", 30)],
+            ]
+        );
+        assert_eq!(
+            weekdays.schtasks_args().unwrap(),
+            ["/SC", "WEEKLY", "/D", "MON,WED", "/ST", "09:30"]
+        );
+
+        // Day of month and day of week fire on their own, as in cron.
+        let either = Cron::parse("0 6 1 * 0").unwrap();
+        assert_eq!(
+            either.calendar_intervals(),
+            vec![
+                vec![("Day", 1), ("Hour", 6), ("Minute", 0)],
+                vec![("Weekday", 0), ("Hour", 6), ("Minute", 0)],
+            ]
+        );
+        assert!(either.schtasks_args().is_err());
+
+        // Every minute is one interval with no keys.
+        assert_eq!(
+            Cron::parse("* * * * *").unwrap().calendar_intervals(),
+            vec![vec![]]
+        );
+        assert_eq!(
+            Cron::parse("*/15 * * * *")
+                .unwrap()
+                .schtasks_args()
+                .unwrap(),
+            ["/SC", "MINUTE", "/MO", "15"]
+        );
+        assert_eq!(
+            Cron::parse("@hourly").unwrap().schtasks_args().unwrap(),
+            ["/SC", "HOURLY", "/ST", "00:00"]
+        );
+        assert_eq!(
+            Cron::parse("0 9 1,15 * *")
+                .unwrap()
+                .schtasks_args()
+                .unwrap(),
+            ["/SC", "MONTHLY", "/D", "1,15", "/ST", "09:00"]
+        );
+        for unsupported in ["*/7 * * * *", "0 9,17 * * *", "0 9 * 6 *"] {
+            assert!(
+                Cron::parse(unsupported).unwrap().schtasks_args().is_err(),
+                "{}",
+                unsupported
+            );
+        }
+    }
+}
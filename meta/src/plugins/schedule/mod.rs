@@ -0,0 +1,486 @@
+//! `meta schedule` — run meta commands against the workspace on a timer.
+//!
+//! `add` takes a five-field cron expression and a meta command, writes a
+//! small runner script to `.meta-state/schedules/<name>.sh` (`.cmd` on
+//! Windows) that changes into the workspace and runs the command, and
+//! installs a user-level task for it with the platform scheduler (see
+//! [`scheduler`]). Each run appends its output to `<name>.log` next to the
+//! script. The tasks this workspace installed are recorded in
+//! `.meta-state/schedules.json`, which `list` and `remove` read.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use metarepo_core::Table;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::plugins::shared::state;
+
+use self::cron::Cron;
+use self::scheduler::Scheduler;
+
+pub use self::plugin::SchedulePlugin;
+
+mod cron;
+mod plugin;
+mod scheduler;
+
+/// State file recording the tasks a workspace installed.
+pub const SCHEDULES_FILE: &str = "schedules.json";
+
+/// Directory, under the state directory, holding runner scripts and logs.
+const SCRIPTS_DIR: &str = "schedules";
+
+/// Logs are cut back to half this size when a run finds them larger.
+const LOG_LIMIT: u64 = 1024 * 1024;
+
+/// One scheduled command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Cron expression, aliases expanded.
+    pub cron: String,
+    /// Arguments to `meta`.
+    pub command: Vec<String>,
+    /// Name of the task with the platform scheduler.
+    pub task: String,
+    /// Unix seconds when it was added.
+    pub added: u64,
+}
+
+/// The contents of [`SCHEDULES_FILE`], keyed by schedule name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedules {
+    #[serde(default)]
+    pub schedules: BTreeMap<String, Schedule>,
+}
+
+/// The meta arguments in `words`: one argument is split like a shell
+/// would, and a leading `meta` is dropped.
+pub fn parse_command(words: &[String]) -> Result<Vec<String>> {
+    let mut args = match words {
+        [one] => shlex::split(one)
+            .ok_or_else(|| anyhow::anyhow!("Cannot parse command '{}': unbalanced quotes", one))?,
+        _ => words.to_vec(),
+    };
+    if args.first().is_some_and(|first| first == "meta") {
+        args.remove(0);
+    }
+    if args.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No command given: name a meta command, such as 'git fetch --all'"
+        ));
+    }
+    Ok(args)
+}
+
+/// A name for `command` not yet taken in `existing`: its words joined by
+/// dashes, numbered when needed.
+pub fn default_name(command: &[String], existing: &Schedules) -> String {
+    let words: Vec<String> = command
+        .iter()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect::<String>()
+                .trim_matches('-')
+                .to_ascii_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut base = words.join("-");
+    base.truncate(40);
+    let base = base.trim_end_matches('-').to_string();
+    let base = if base.is_empty() {
+        "task".to_string()
+    } else {
+        base
+    };
+    (1..)
+        .map(|n| match n {
+            1 => base.clone(),
+            n => format!("{}-{}", base, n),
+        })
+        .find(|name| !existing.schedules.contains_key(name))
+        .expect("some name is free")
+}
+
+fn check_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(anyhow::anyhow!(
+            "Invalid schedule name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// The scheduler task name for schedule `name` of the workspace at
+/// `base_path`; the hash keeps workspaces with the same names apart.
+pub fn task_name(base_path: &Path, name: &str) -> String {
+    let digest = Sha256::digest(base_path.to_string_lossy().as_bytes());
+    let hash: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("metarepo.{}.{}", hash, name)
+}
+
+fn script_path(base_path: &Path, name: &str) -> PathBuf {
+    let ext = if cfg!(windows) { "cmd" } else { "sh" };
+    state::state_path(base_path, SCRIPTS_DIR).join(format!("{}.{}", name, ext))
+}
+
+fn log_path(base_path: &Path, name: &str) -> PathBuf {
+    state::state_path(base_path, SCRIPTS_DIR).join(format!("{}.log", name))
+}
+
+/// `args` with `--non-interactive=fail` in front unless they set a mode: a
+/// scheduled run has nobody to answer prompts.
+fn unattended(args: &[String]) -> Vec<String> {
+    let mut all = Vec::with_capacity(args.len() + 1);
+    if !args.iter().any(|a| a.starts_with("--non-interactive")) {
+        all.push("--non-interactive=fail".to_string());
+    }
+    all.extend(args.iter().cloned());
+    all
+}
+
+fn quote(text: &str) -> String {
+    shlex::try_quote(text)
+        .map(|q| q.into_owned())
+        .unwrap_or_else(|_| text.to_string())
+}
+
+/// The `sh` runner: the PATH of the shell that added it (schedulers start
+/// tasks with a minimal one), then the command in the workspace, logged.
+pub fn shell_script(
+    name: &str,
+    root: &Path,
+    meta: &Path,
+    path_var: &str,
+    args: &[String],
+) -> String {
+    let log = quote(&log_path(root, name).to_string_lossy());
+    let command: Vec<String> = unattended(args).iter().map(|a| quote(a)).collect();
+    format!(
+        "#!/bin/sh\n\
+         # Written by `meta schedule add`; remove with `meta schedule remove {name}`.\n\
+         PATH={path}\n\
+         export PATH\n\
+         cd {root} || exit 1\n\
+         if [ -f {log} ] && [ \"$(wc -c < {log})\" -gt {limit} ]; then\n\
+         \x20   tail -c {keep} {log} > {log}.tmp && mv {log}.tmp {log}\n\
+         fi\n\
+         echo \"== $(date '+%Y-%m-%d %H:%M:%S') \"{shown} >> {log}\n\
+         exec {meta} {command} >> {log} 2>&1\n",
+        name = name,
+        path = quote(path_var),
+        root = quote(&root.to_string_lossy()),
+        log = log,
+        limit = LOG_LIMIT,
+        keep = LOG_LIMIT / 2,
+        shown = quote(&format!("meta {}", args.join(" "))),
+        meta = quote(&meta.to_string_lossy()),
+        command = command.join(" "),
+    )
+}
+
+/// The `cmd` runner for Task Scheduler.
+pub fn cmd_script(name: &str, root: &Path, meta: &Path, args: &[String]) -> Result<String> {
+    if let Some(arg) = args.iter().find(|a| a.contains('"')) {
+        return Err(anyhow::anyhow!(
+            "Cannot schedule an argument with a double quote on Windows: {}",
+            arg
+        ));
+    }
+    let escape = |text: &str| text.replace('%', "%%");
+    // Outside quotes, as in the echo line, cmd also reads these.
+    let echo_escape = |text: &str| {
+        text.chars().fold(String::new(), |mut out, c| {
+            if "&|<>^()".contains(c) {
+                out.push('^');
+            }
+            out.push(c);
+            out
+        })
+    };
+    let log = format!("\"{}\"", log_path(root, name).display());
+    let command: Vec<String> = unattended(args)
+        .iter()
+        .map(|a| format!("\"{}\"", escape(a)))
+        .collect();
+    Ok(format!(
+        "@echo off\r\n\
+         rem Written by `meta schedule add`; remove with `meta schedule remove {name}`.\r\n\
+         cd /d \"{root}\" || exit /b 1\r\n\
+         echo == %DATE% %TIME% meta {shown} >> {log}\r\n\
+         \"{meta}\" {command} >> {log} 2>&1\r\n",
+        name = name,
+        root = root.display(),
+        shown = echo_escape(&escape(&args.join(" "))),
+        log = log,
+        meta = meta.display(),
+        command = command.join(" "),
+    ))
+}
+
+/// Schedule `args` on `expr` for the workspace at `base_path`.
+pub fn add(base_path: &Path, expr: &str, args: &[String], name: Option<&str>) -> Result<()> {
+    let cron = Cron::parse(expr)?;
+    let scheduler = Scheduler::current();
+    if scheduler == Scheduler::Schtasks {
+        // Fail on schedules Task Scheduler cannot express before writing
+        // anything.
+        cron.schtasks_args()?;
+    }
+    let mut schedules: Schedules = state::load(base_path, SCHEDULES_FILE)?;
+    let name = match name {
+        Some(name) => {
+            check_name(name)?;
+            if schedules.schedules.contains_key(name) {
+                return Err(anyhow::anyhow!(
+                    "A schedule named '{}' exists: remove it first, or pick another --name",
+                    name
+                ));
+            }
+            name.to_string()
+        }
+        None => default_name(args, &schedules),
+    };
+
+    let meta = std::env::current_exe().context("Cannot find the meta executable")?;
+    let script = script_path(base_path, &name);
+    let content = if cfg!(windows) {
+        cmd_script(&name, base_path, &meta, args)?
+    } else {
+        let path_var = std::env::var("PATH").unwrap_or_default();
+        shell_script(&name, base_path, &meta, &path_var, args)
+    };
+    if let Some(dir) = script.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&script, content)
+        .with_context(|| format!("Failed to write {}", script.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    let task = task_name(base_path, &name);
+    if let Err(e) = scheduler.install(&task, &cron, &script) {
+        let _ = std::fs::remove_file(&script);
+        return Err(e.context(format!(
+            "Could not install the task with {}",
+            scheduler.name()
+        )));
+    }
+    schedules.schedules.insert(
+        name.clone(),
+        Schedule {
+            cron: cron.text.clone(),
+            command: args.to_vec(),
+            task,
+            added: state::now_secs(),
+        },
+    );
+    state::save(base_path, SCHEDULES_FILE, &schedules)?;
+
+    println!(
+        "  {} Scheduled {} ({}) with {}: meta {}",
+        "✓".green(),
+        name.bold(),
+        cron.text.cyan(),
+        scheduler.name(),
+        args.join(" ")
+    );
+    println!(
+        "  {}",
+        format!("Output goes to {}", log_path(base_path, &name).display()).bright_black()
+    );
+    Ok(())
+}
+
+/// Print the workspace's schedules and whether each is still installed.
+pub fn list(base_path: &Path, json: bool) -> Result<()> {
+    let schedules: Schedules = state::load(base_path, SCHEDULES_FILE)?;
+    let scheduler = Scheduler::current();
+    if json {
+        let entries: Vec<serde_json::Value> = schedules
+            .schedules
+            .iter()
+            .map(|(name, schedule)| {
+                serde_json::json!({
+                    "name": name,
+                    "cron": schedule.cron,
+                    "command": schedule.command,
+                    "task": schedule.task,
+                    "installed": scheduler.installed(&schedule.task),
+                    "log": log_path(base_path, name),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if schedules.schedules.is_empty() {
+        println!("No schedules in this workspace. Add one with `meta schedule add`.");
+        return Ok(());
+    }
+    let mut table = Table::new(["Name", "Schedule", "Command", "Last run"]).indent(2);
+    let mut missing = 0;
+    for (name, schedule) in &schedules.schedules {
+        let last_run = std::fs::metadata(log_path(base_path, name))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| state::format_timestamp(d.as_secs()))
+            .unwrap_or_else(|| "never".to_string());
+        let name = if scheduler.installed(&schedule.task) {
+            name.bold().to_string()
+        } else {
+            missing += 1;
+            format!("{} {}", name.bold(), "(not installed)".red())
+        };
+        table.row([
+            name,
+            schedule.cron.cyan().to_string(),
+            format!("meta {}", schedule.command.join(" ")),
+            last_run,
+        ]);
+    }
+    println!();
+    table.print();
+    println!(
+        "\n  {}",
+        format!(
+            "Run by {} in local time; last runs are shown in UTC.",
+            scheduler.name()
+        )
+        .bright_black()
+    );
+    if missing > 0 {
+        println!(
+            "  {}",
+            format!(
+                "{} schedule(s) are no longer installed: remove them, then add them again.",
+                missing
+            )
+            .yellow()
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// Uninstall the schedules `names` and delete their scripts; logs stay.
+pub fn remove(base_path: &Path, names: &[String]) -> Result<()> {
+    let mut schedules: Schedules = state::load(base_path, SCHEDULES_FILE)?;
+    if let Some(unknown) = names.iter().find(|n| !schedules.schedules.contains_key(*n)) {
+        return Err(anyhow::anyhow!(
+            "No schedule named '{}' (see `meta schedule list`)",
+            unknown
+        ));
+    }
+    let scheduler = Scheduler::current();
+    for name in names {
+        let schedule = &schedules.schedules[name];
+        scheduler.uninstall(&schedule.task)?;
+        let _ = std::fs::remove_file(script_path(base_path, name));
+        schedules.schedules.remove(name);
+        state::save(base_path, SCHEDULES_FILE, &schedules)?;
+        println!("  {} Removed {}", "✓".green(), name.bold());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn commands_and_names() {
+        assert_eq!(
+            parse_command(&words(&["git fetch --all"])).unwrap(),
+            ["git", "fetch", "--all"]
+        );
+        assert_eq!(
+            parse_command(&words(&["meta", "exec", "echo 'a b'"])).unwrap(),
+            ["exec", "echo 'a b'"]
+        );
+        assert!(parse_command(&words(&["meta"])).is_err());
+        assert!(parse_command(&words(&["git 'fetch"])).is_err());
+
+        let mut existing = Schedules::default();
+        let fetch = words(&["git", "fetch", "--all"]);
+        assert_eq!(default_name(&fetch, &existing), "git-fetch-all");
+        existing.schedules.insert(
+            "git-fetch-all".to_string(),
+            Schedule {
+                cron: "@daily".to_string(),
+                command: fetch.clone(),
+                task: String::new(),
+                added: 0,
+            },
+        );
+        assert_eq!(default_name(&fetch, &existing), "git-fetch-all-2");
+        assert_eq!(default_name(&words(&["!!"]), &existing), "task");
+
+        assert!(check_name("nightly_fetch-2").is_ok());
+        assert!(check_name("a/b").is_err());
+
+        let a = task_name(Path::new("/work/a"), "fetch");
+        assert!(a.starts_with("metarepo.") && a.ends_with(".fetch"));
+        assert_ne!(a, task_name(Path::new("/work/b"), "fetch"));
+    }
+
+    #[test]
+    fn runner_scripts_change_into_the_workspace_and_log() {
+        let root = Path::new("/work/my ws");
+        let args = words(&["git", "pull", "--rebase"]);
+        let script = shell_script(
+            "pull",
+            root,
+            Path::new("/usr/bin/meta"),
+            "/usr/bin:/bin",
+            &args,
+        );
+        assert!(script.contains("PATH=/usr/bin:/bin\n"));
+        assert!(script.contains("cd '/work/my ws' || exit 1\n"));
+        assert!(script.contains(
+            "exec /usr/bin/meta '--non-interactive=fail' git pull --rebase \
+             >> '/work/my ws/.meta-state/schedules/pull.log' 2>&1\n"
+        ));
+        assert!(script
+            .contains("echo \"== $(date '+%Y-%m-%d %H:%M:%S') \"'meta git pull --rebase' >> "));
+
+        let args = words(&["exec", "echo 100%", "--non-interactive=defaults"]);
+        let cmd = cmd_script(
+            "echo",
+            Path::new("C:\\ws"),
+            Path::new("C:\\bin\\meta.exe"),
+            &args,
+        )
+        .unwrap();
+        assert!(cmd.contains("cd /d \"C:\\ws\" || exit /b 1\r\n"));
+        assert!(cmd.contains("echo == %DATE% %TIME% meta exec echo 100%% --non"));
+        assert!(cmd.contains(
+            "\"C:\\bin\\meta.exe\" \"exec\" \"echo 100%%\" \"--non-interactive=defaults\""
+        ));
+        assert!(cmd_script(
+            "q",
+            Path::new("C:\\ws"),
+            Path::new("meta"),
+            &words(&["say \"hi\""])
+        )
+        .is_err());
+    }
+}
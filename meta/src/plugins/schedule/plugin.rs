@@ -0,0 +1,163 @@
+//! Plugin wiring for `meta schedule`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{add, list, parse_command, remove};
+
+/// Registers the top-level `meta schedule` command.
+pub struct SchedulePlugin;
+
+impl SchedulePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SchedulePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for SchedulePlugin {
+    fn name(&self) -> &str {
+        "schedule"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("schedule")
+                .about("Run meta commands on a schedule with the OS scheduler")
+                .version(env!("CARGO_PKG_VERSION"))
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .after_long_help(metarepo_core::format_help_description(
+                    "Keep a workspace fresh without manual syncs: install a user-level\n\
+                     task (a crontab entry on Linux, a launchd agent on macOS, a Task\n\
+                     Scheduler task on Windows) that runs a meta command in this\n\
+                     workspace on a cron schedule.\n\
+                     \n\
+                     Each task runs a script in .meta-state/schedules/ with the PATH of\n\
+                     the shell that added it, in non-interactive mode, and appends its\n\
+                     output to a log beside the script. Tasks run as you, but outside\n\
+                     your login session: clones and fetches need credentials that work\n\
+                     without a prompt or an SSH agent, such as a token in the OS keychain\n\
+                     (meta auth login).\n\
+                     \n\
+                     Examples:\n  \
+                       meta schedule add \"0 9 * * 1-5\" 'git fetch --all'\n  \
+                       meta schedule add --name pull @hourly git pull\n  \
+                       meta schedule list\n  \
+                       meta schedule remove pull\n",
+                ))
+                .subcommand(
+                    Command::new("add")
+                        .about("Schedule a meta command")
+                        .version(env!("CARGO_PKG_VERSION"))
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Run COMMAND (a meta command, with or without the leading\n\
+                             `meta`) in this workspace on SCHEDULE: five cron fields\n\
+                             (minute hour day-of-month month day-of-week) in local time,\n\
+                             or @hourly, @daily, @weekly, @monthly. Fields take numbers,\n\
+                             ranges (1-5), lists (1,15), and steps (*/15). Task Scheduler\n\
+                             takes one time of day on some days, a minute past every hour,\n\
+                             or */N minutes.\n\
+                             \n\
+                             Examples:\n  \
+                               meta schedule add \"0 9 * * *\" 'git fetch --all'\n  \
+                               meta schedule add --name status \"*/30 * * * *\" git status\n",
+                        ))
+                        .arg(
+                            Arg::new("schedule")
+                                .value_name("SCHEDULE")
+                                .required(true)
+                                .help("Cron expression, such as \"0 9 * * *\", or @daily"),
+                        )
+                        .arg(
+                            Arg::new("command")
+                                .value_name("COMMAND")
+                                .required(true)
+                                .num_args(1..)
+                                .trailing_var_arg(true)
+                                .allow_hyphen_values(true)
+                                .help("The meta command to run, quoted or as separate words"),
+                        )
+                        .arg(
+                            Arg::new("name")
+                                .long("name")
+                                .value_name("NAME")
+                                .help("Name for the schedule (default: from the command)"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("list")
+                        .about("List this workspace's schedules")
+                        .version(env!("CARGO_PKG_VERSION"))
+                        .arg(
+                            Arg::new("json")
+                                .long("json")
+                                .action(ArgAction::SetTrue)
+                                .help("Print the schedules as JSON"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("remove")
+                        .about("Uninstall schedules")
+                        .version(env!("CARGO_PKG_VERSION"))
+                        .arg(
+                            Arg::new("name")
+                                .value_name("NAME")
+                                .required(true)
+                                .num_args(1..)
+                                .help("Schedules to remove (see meta schedule list)"),
+                        ),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let base_path = config
+            .meta_root()
+            .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+        match matches.subcommand() {
+            Some(("add", m)) => {
+                let schedule = m.get_one::<String>("schedule").expect("required");
+                let words: Vec<String> = m
+                    .get_many::<String>("command")
+                    .expect("required")
+                    .cloned()
+                    .collect();
+                let name = m.get_one::<String>("name").map(String::as_str);
+                add(&base_path, schedule, &parse_command(&words)?, name)
+            }
+            Some(("list", m)) => list(&base_path, m.get_flag("json")),
+            Some(("remove", m)) => {
+                let names: Vec<String> = m
+                    .get_many::<String>("name")
+                    .expect("required")
+                    .cloned()
+                    .collect();
+                remove(&base_path, &names)
+            }
+            _ => Err(anyhow::anyhow!(
+                "Unknown schedule command: use add, list, or remove"
+            )),
+        }
+    }
+}
+
+impl BasePlugin for SchedulePlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Run meta commands on a schedule with the OS scheduler")
+    }
+}
@@ -0,0 +1,317 @@
+//! Installing tasks with the user's own scheduler: the crontab on Linux and
+//! other Unixes, a launchd agent on macOS, Task Scheduler on Windows. Each
+//! task runs a script `meta schedule add` wrote into the workspace.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::cron::Cron;
+
+/// A platform scheduler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheduler {
+    Cron,
+    Launchd,
+    Schtasks,
+}
+
+/// Prefix of the crontab comment line above each entry meta installs.
+const CRON_MARKER: &str = "# metarepo schedule ";
+
+impl Scheduler {
+    /// The scheduler of the platform meta was built for.
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Scheduler::Launchd
+        } else if cfg!(windows) {
+            Scheduler::Schtasks
+        } else {
+            Scheduler::Cron
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scheduler::Cron => "cron",
+            Scheduler::Launchd => "launchd",
+            Scheduler::Schtasks => "Task Scheduler",
+        }
+    }
+
+    /// Install the task `id`, running `script` on `cron`, replacing any
+    /// task of the same id.
+    pub fn install(&self, id: &str, cron: &Cron, script: &Path) -> Result<()> {
+        match self {
+            Scheduler::Cron => {
+                let crontab = read_crontab()?;
+                write_crontab(&with_cron_entry(&crontab, id, Some((cron, script))))
+            }
+            Scheduler::Launchd => {
+                let plist = launch_agent(id)?;
+                if let Some(dir) = plist.parent() {
+                    std::fs::create_dir_all(dir)
+                        .with_context(|| format!("Failed to create {}", dir.display()))?;
+                }
+                // Unload a previous version first; there may be none.
+                let _ = run("launchctl", &["unload".as_ref(), plist.as_os_str()]);
+                std::fs::write(&plist, launchd_plist(id, cron, script))
+                    .with_context(|| format!("Failed to write {}", plist.display()))?;
+                run(
+                    "launchctl",
+                    &["load".as_ref(), "-w".as_ref(), plist.as_os_str()],
+                )
+            }
+            Scheduler::Schtasks => {
+                let mut args: Vec<String> = ["/Create", "/F", "/TN", id, "/TR"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+                args.push(format!("\"{}\"", script.display()));
+                args.extend(cron.schtasks_args()?);
+                let args: Vec<&std::ffi::OsStr> = args.iter().map(|a| a.as_ref()).collect();
+                run("schtasks", &args)
+            }
+        }
+    }
+
+    /// Remove the task `id`. Removing one that is not installed is not an
+    /// error.
+    pub fn uninstall(&self, id: &str) -> Result<()> {
+        match self {
+            Scheduler::Cron => {
+                let crontab = read_crontab()?;
+                let updated = with_cron_entry(&crontab, id, None);
+                if updated != crontab {
+                    write_crontab(&updated)?;
+                }
+                Ok(())
+            }
+            Scheduler::Launchd => {
+                let plist = launch_agent(id)?;
+                if plist.exists() {
+                    let _ = run("launchctl", &["unload".as_ref(), plist.as_os_str()]);
+                    std::fs::remove_file(&plist)
+                        .with_context(|| format!("Failed to remove {}", plist.display()))?;
+                }
+                Ok(())
+            }
+            Scheduler::Schtasks => {
+                if self.installed(id) {
+                    run(
+                        "schtasks",
+                        &[
+                            "/Delete".as_ref(),
+                            "/F".as_ref(),
+                            "/TN".as_ref(),
+                            id.as_ref(),
+                        ],
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether the task `id` is installed.
+    pub fn installed(&self, id: &str) -> bool {
+        match self {
+            Scheduler::Cron => read_crontab()
+                .map(|crontab| crontab.lines().any(|line| is_marker(line, id)))
+                .unwrap_or(false),
+            Scheduler::Launchd => launch_agent(id).is_ok_and(|plist| plist.exists()),
+            Scheduler::Schtasks => run(
+                "schtasks",
+                &["/Query".as_ref(), "/TN".as_ref(), id.as_ref()],
+            )
+            .is_ok(),
+        }
+    }
+}
+
+/// Run `program` with `args`, failing with its stderr.
+fn run(program: &str, args: &[&std::ffi::OsStr]) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "{} failed: {}",
+        program,
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
+
+/// The user's crontab; empty when they have none yet.
+fn read_crontab() -> Result<String> {
+    let output = Command::new("crontab")
+        .arg("-l")
+        .output()
+        .context("Failed to run crontab")?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("no crontab") {
+        return Ok(String::new());
+    }
+    Err(anyhow::anyhow!("crontab -l failed: {}", stderr.trim()))
+}
+
+fn write_crontab(content: &str) -> Result<()> {
+    let mut child = Command::new("crontab")
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run crontab")?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(content.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "crontab failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn is_marker(line: &str, id: &str) -> bool {
+    line.strip_prefix(CRON_MARKER) == Some(id)
+}
+
+/// `crontab` without the entry for `id`, then with the new entry appended
+/// when one is given. Every other line is kept as it was.
+fn with_cron_entry(crontab: &str, id: &str, entry: Option<(&Cron, &Path)>) -> String {
+    let mut lines = Vec::new();
+    let mut skip_next = false;
+    for line in crontab.lines() {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if is_marker(line, id) {
+            skip_next = true;
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    if let Some((cron, script)) = entry {
+        lines.push(format!("{}{}", CRON_MARKER, id));
+        lines.push(format!(
+            "{} {}",
+            cron.text,
+            shlex::try_quote(&script.to_string_lossy()).unwrap_or_default()
+        ));
+    }
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content
+}
+
+/// Where the launchd agent for `id` lives.
+fn launch_agent(id: &str) -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").ok_or_else(|| anyhow::anyhow!("HOME is not set"))?;
+    Ok(PathBuf::from(home)
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", id)))
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A launchd agent running `script` on `cron`.
+fn launchd_plist(id: &str, cron: &Cron, script: &Path) -> String {
+    let mut intervals = String::new();
+    for interval in cron.calendar_intervals() {
+        intervals.push_str("        <dict>\n");
+        for (key, value) in interval {
+            intervals.push_str(&format!(
+                "            <key>{}</key>\n            <integer>{}</integer>\n",
+                key, value
+            ));
+        }
+        intervals.push_str("        </dict>\n");
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>/bin/sh</string>
+        <string>{}</string>
+    </array>
+    <key>StartCalendarInterval</key>
+    <array>
+{}    </array>
+</dict>
+</plist>
+"#,
+        xml_escape(id),
+        xml_escape(&script.to_string_lossy()),
+        intervals
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crontab_entries_are_replaced_and_removed_in_place() {
+        let cron = Cron::parse("0 9 * * *").unwrap();
+        let script = Path::new("/work/my ws/.meta-state/schedules/fetch.sh");
+        let user = "MAILTO=me@example.com\n15 * * * * backup.sh\n";
+
+        let added = with_cron_entry(user, "metarepo.ab12.fetch", Some((&cron, script)));
+        assert_eq!(
+            added,
+            "MAILTO=me@example.com\n15 * * * * backup.sh\n\
+             # metarepo schedule metarepo.ab12.fetch\n\
+             0 9 * * * '/work/my ws/.meta-state/schedules/fetch.sh'\n"
+        );
+
+        let hourly = Cron::parse("@hourly").unwrap();
+        let replaced = with_cron_entry(&added, "metarepo.ab12.fetch", Some((&hourly, script)));
+        assert_eq!(replaced.matches("metarepo schedule").count(), 1);
+        assert!(replaced.contains("0 * * * * '/work/my ws"));
+
+        // Another workspace's entry with a similar id stays.
+        let other = with_cron_entry(&replaced, "metarepo.cd34.fetch", Some((&cron, script)));
+        assert_eq!(
+            with_cron_entry(&other, "metarepo.ab12.fetch", None)
+                .lines()
+                .count(),
+            4
+        );
+        assert_eq!(with_cron_entry(&added, "metarepo.ab12.fetch", None), user);
+        assert_eq!(with_cron_entry("", "metarepo.ab12.fetch", None), "");
+    }
+
+    #[test]
+    fn launchd_agent_lists_each_calendar_interval() {
+        let cron = Cron::parse("0 9 * * 1,5").unwrap();
+        let plist = launchd_plist("metarepo.ab12.fetch", &cron, Path::new("/ws/a&b.sh"));
+        assert!(plist.contains("<string>metarepo.ab12.fetch</string>"));
+        assert!(plist.contains("<string>/ws/a&amp;b.sh</string>"));
+        assert_eq!(plist.matches("<key>Weekday</key>").count(), 2);
+        assert!(plist.contains("<key>Hour</key>\n            <integer>9</integer>"));
+    }
+}
@@ -0,0 +1,807 @@
+//! Long-running services across projects.
+//!
+//! `meta services up` starts several projects' dev servers at once, prefixes
+//! every output line with the project name through the shared
+//! [`LogMultiplexer`], restarts a service when it exits, and stops them all on
+//! Ctrl-C. The supervisor records its pid and the service pids in the
+//! workspace state directory so `meta services down` can stop it from another
+//! terminal.
+//!
+//! Declared ports are checked before anything starts: a port claimed by two
+//! services, or already bound on this machine, is an error unless
+//! `--auto-ports` moves the service to a free port.
+
+use anyhow::{Context, Result};
+use colored::*;
+use metarepo_core::{MetaConfig, ServiceSpec};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub use self::plugin::ServicesPlugin;
+
+mod plugin;
+
+use crate::plugins::run;
+use crate::plugins::shared::{state, LogMultiplexer, MuxOptions, Stream};
+
+/// State file recording the running supervisor and its services.
+pub const STATE_FILE: &str = "services.json";
+
+/// How often child processes and the stop flag are checked.
+const POLL: Duration = Duration::from_millis(100);
+/// How long a stopped service gets to exit before it is killed.
+const GRACE: Duration = Duration::from_secs(5);
+/// Delay before the first restart; doubles on each quick crash.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A service that ran at least this long restarts after [`INITIAL_BACKOFF`].
+const STABLE_AFTER: Duration = Duration::from_secs(10);
+
+/// A service to supervise: the script started in a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    pub project: String,
+    pub script: String,
+    pub restart: bool,
+    pub ports: Vec<u16>,
+}
+
+/// The running supervisor and the current pid of each service.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ServicesState {
+    pub supervisor: Option<u32>,
+    #[serde(default)]
+    pub services: BTreeMap<String, u32>,
+    /// Ports each service was started with, after any reassignment.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ports: BTreeMap<String, Vec<u16>>,
+}
+
+/// Resolve the services to start among `candidates`.
+///
+/// Projects with an entry in the `services` block are used as configured;
+/// with `explicit`, named projects without one run their `dev` entry point
+/// with the defaults. Non-empty `tags` keep only the services carrying one of
+/// them, in their `services` entry or as project tags in `.meta`. Projects
+/// with no script to run are reported and skipped.
+pub fn select_services(
+    config: &MetaConfig,
+    base_path: &Path,
+    candidates: &[String],
+    explicit: bool,
+    tags: &[String],
+) -> Vec<Service> {
+    let configured = config.services.clone().unwrap_or_default();
+    let tagged_projects = config.tagged_project_keys(tags);
+    let mut services = Vec::new();
+    let mut names: Vec<&String> = candidates.iter().collect();
+    names.sort();
+    names.dedup();
+
+    for project in names {
+        let spec = match configured.get(project) {
+            Some(spec) => spec.clone(),
+            None if explicit => ServiceSpec::default(),
+            None => continue,
+        };
+        if !tags.is_empty()
+            && !spec.tags.iter().any(|t| tags.contains(t))
+            && !tagged_projects.contains(project)
+        {
+            continue;
+        }
+        let script = spec.script.clone().or_else(|| {
+            run::entry_point_plan(
+                config,
+                std::slice::from_ref(project),
+                Some("dev"),
+                base_path,
+            )
+            .into_iter()
+            .next()
+            .map(|(script, _)| script)
+        });
+        match script {
+            Some(script) => services.push(Service {
+                project: project.clone(),
+                script,
+                restart: spec.restart.unwrap_or(true),
+                ports: spec.ports.clone(),
+            }),
+            None => eprintln!(
+                "  {} {} has no service script, dev script, or default_script; skipping",
+                "⚠️".yellow(),
+                project
+            ),
+        }
+    }
+    services
+}
+
+/// A declared port moved to a free one by `--auto-ports`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMove {
+    pub project: String,
+    pub from: u16,
+    pub to: u16,
+}
+
+/// Whether something on this machine is already listening on `port`.
+pub fn port_in_use(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_err()
+}
+
+/// A port the OS reports as free right now.
+pub fn free_port() -> Result<u16> {
+    let listener =
+        std::net::TcpListener::bind(("127.0.0.1", 0)).context("Failed to find a free port")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Check the services' declared ports for conflicts: a port declared by more
+/// than one service, or one `in_use` already. With `auto`, conflicting ports
+/// are replaced by ports from `next_free` and the moves are returned;
+/// otherwise any conflict is an error listing all of them.
+pub fn assign_ports(
+    services: &mut [Service],
+    auto: bool,
+    in_use: impl Fn(u16) -> bool,
+    mut next_free: impl FnMut() -> Result<u16>,
+) -> Result<Vec<PortMove>> {
+    let mut claimed: HashMap<u16, String> = HashMap::new();
+    let mut conflicts = Vec::new();
+    let mut moves = Vec::new();
+
+    for service in services.iter_mut() {
+        for port in service.ports.iter_mut() {
+            let reason = match claimed.get(port) {
+                Some(owner) => Some(format!("also declared by {}", owner)),
+                None if in_use(*port) => Some("already in use".to_string()),
+                None => None,
+            };
+            if let Some(reason) = reason {
+                if !auto {
+                    conflicts.push(format!("{} port {}: {}", service.project, port, reason));
+                    continue;
+                }
+                let mut replacement = next_free()?;
+                while claimed.contains_key(&replacement) {
+                    replacement = next_free()?;
+                }
+                moves.push(PortMove {
+                    project: service.project.clone(),
+                    from: *port,
+                    to: replacement,
+                });
+                *port = replacement;
+            }
+            claimed.insert(*port, service.project.clone());
+        }
+    }
+
+    if !conflicts.is_empty() {
+        anyhow::bail!(
+            "Port conflicts:\n  {}\nFree the ports, change `ports` in .meta, or pass --auto-ports",
+            conflicts.join("\n  ")
+        );
+    }
+    Ok(moves)
+}
+
+/// Environment exposing a service's ports: `PORT` (the first) and `PORTS`
+/// (all, comma-separated).
+fn port_env(ports: &[u16]) -> Vec<(String, String)> {
+    match ports.first() {
+        Some(first) => vec![
+            ("PORT".to_string(), first.to_string()),
+            (
+                "PORTS".to_string(),
+                ports
+                    .iter()
+                    .map(u16::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// The restart delay after a service ran for `uptime`: reset once it has
+/// been stable, otherwise double the previous delay up to [`MAX_BACKOFF`].
+pub fn next_backoff(previous: Duration, uptime: Duration) -> Duration {
+    if uptime >= STABLE_AFTER {
+        INITIAL_BACKOFF
+    } else {
+        (previous * 2).min(MAX_BACKOFF)
+    }
+}
+
+/// Whether a process with `pid` is still running.
+fn process_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+    #[cfg(not(unix))]
+    {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()))
+    }
+}
+
+/// Ask a service to stop. Services run in their own process group on Unix, so
+/// the whole group is signalled and servers started through wrappers (npm,
+/// cargo, ...) stop with them.
+fn terminate(pid: u32, force: bool) {
+    #[cfg(unix)]
+    {
+        let signal = if force { "-KILL" } else { "-TERM" };
+        let _ = Command::new("kill")
+            .args([signal, "--", &format!("-{}", pid)])
+            .stderr(Stdio::null())
+            .status();
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = force;
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// Stop `child` gracefully, killing it after [`GRACE`].
+fn stop_child(child: &mut Child) {
+    terminate(child.id(), false);
+    let deadline = Instant::now() + GRACE;
+    while Instant::now() < deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            return;
+        }
+        std::thread::sleep(POLL);
+    }
+    terminate(child.id(), true);
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Set `stop` on Ctrl-C (and SIGTERM on Unix, which `meta services down` sends).
+fn stop_on_signal(stop: Arc<AtomicBool>) {
+    std::thread::spawn(move || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        else {
+            return;
+        };
+        runtime.block_on(async {
+            #[cfg(unix)]
+            {
+                use tokio::signal::unix::{signal, SignalKind};
+                match signal(SignalKind::terminate()) {
+                    Ok(mut term) => {
+                        tokio::select! {
+                            _ = tokio::signal::ctrl_c() => {}
+                            _ = term.recv() => {}
+                        }
+                    }
+                    Err(_) => {
+                        let _ = tokio::signal::ctrl_c().await;
+                    }
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        });
+        stop.store(true, Ordering::SeqCst);
+    });
+}
+
+/// Shared supervisor state: the stop flag, the output multiplexer, and the
+/// pid record.
+struct Supervisor<'a> {
+    config: &'a MetaConfig,
+    base_path: &'a Path,
+    env_vars: &'a HashMap<String, String>,
+    stop: Arc<AtomicBool>,
+    mux: Arc<LogMultiplexer>,
+    state: Mutex<ServicesState>,
+}
+
+impl Supervisor<'_> {
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::SeqCst)
+    }
+
+    /// Record (or clear) a service's pid in the state file.
+    fn record(&self, project: &str, pid: Option<u32>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match pid {
+            Some(pid) => state.services.insert(project.to_string(), pid),
+            None => state.services.remove(project),
+        };
+        let _ = state::save(self.base_path, STATE_FILE, &*state);
+    }
+
+    /// Sleep for `duration`, waking early when stopped.
+    fn sleep(&self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while !self.stopped() && Instant::now() < deadline {
+            std::thread::sleep(POLL);
+        }
+    }
+
+    fn spawn(&self, service: &Service) -> Result<(Child, String)> {
+        // Explicit -e values win over the exported ports.
+        let mut env_vars: HashMap<String, String> = port_env(&service.ports).into_iter().collect();
+        env_vars.extend(self.env_vars.clone());
+        let (mut cmd, command_line) = run::build_script_command(
+            self.config,
+            &service.script,
+            &service.project,
+            self.base_path,
+            &env_vars,
+        )?;
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        let child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to start '{}'", command_line))?;
+        Ok((child, command_line))
+    }
+
+    /// Report a supervisor event as a line of `service`'s output.
+    fn event(&self, service: &Service, message: String) {
+        self.mux.line(&service.project, Stream::Stdout, &message);
+    }
+
+    /// Run `service` until stopped, restarting it when it exits.
+    fn supervise(&self, service: &Service) {
+        let mut backoff = INITIAL_BACKOFF / 2;
+        while !self.stopped() {
+            let started = Instant::now();
+            let (mut child, command_line) = match self.spawn(service) {
+                Ok(spawned) => spawned,
+                Err(e) => {
+                    self.event(service, format!("{} {}", "✗".red(), e));
+                    return;
+                }
+            };
+            self.event(
+                service,
+                format!("{} {}", "▶".green(), command_line.bright_black()),
+            );
+            self.record(&service.project, Some(child.id()));
+            if let Some(stdout) = child.stdout.take() {
+                self.mux.pump(&service.project, Stream::Stdout, stdout);
+            }
+            if let Some(stderr) = child.stderr.take() {
+                self.mux.pump(&service.project, Stream::Stderr, stderr);
+            }
+
+            let status = loop {
+                if self.stopped() {
+                    stop_child(&mut child);
+                    break None;
+                }
+                match child.try_wait() {
+                    Ok(Some(status)) => break Some(status),
+                    Ok(None) => std::thread::sleep(POLL),
+                    Err(_) => break None,
+                }
+            };
+            self.record(&service.project, None);
+
+            let Some(status) = status else {
+                self.event(service, "■ stopped".bright_black().to_string());
+                return;
+            };
+            let code = status
+                .code()
+                .map_or_else(|| "a signal".to_string(), |c| format!("code {}", c));
+            if !service.restart {
+                self.event(service, format!("{} exited with {}", "■".yellow(), code));
+                return;
+            }
+            backoff = next_backoff(backoff, started.elapsed());
+            self.event(
+                service,
+                format!(
+                    "{} exited with {}; restarting in {}s",
+                    "↻".yellow(),
+                    code,
+                    backoff.as_secs()
+                ),
+            );
+            self.sleep(backoff);
+        }
+    }
+}
+
+/// Start `services` and supervise them until Ctrl-C, `meta services down`, or
+/// until every service has exited for good.
+pub fn services_up(
+    config: &MetaConfig,
+    base_path: &Path,
+    services: &mut [Service],
+    env_vars: &HashMap<String, String>,
+    auto_ports: bool,
+    output: &MuxOptions,
+) -> Result<()> {
+    let existing: ServicesState = state::load(base_path, STATE_FILE)?;
+    if let Some(pid) = existing.supervisor {
+        if pid != std::process::id() && process_alive(pid) {
+            anyhow::bail!(
+                "Services are already running (pid {}); stop them with 'meta services down'",
+                pid
+            );
+        }
+    }
+
+    for moved in assign_ports(services, auto_ports, port_in_use, free_port)? {
+        println!(
+            "  {} {} port {} is taken; using {}",
+            "→".cyan(),
+            moved.project,
+            moved.from,
+            moved.to
+        );
+    }
+    let services: &[Service] = services;
+
+    let projects: Vec<String> = services.iter().map(|s| s.project.clone()).collect();
+    let mux = Arc::new(LogMultiplexer::new(&projects, output)?);
+
+    println!(
+        "\n  {} {}",
+        "🚀".cyan(),
+        format!("Starting {} service(s) — Ctrl-C to stop", services.len()).bold()
+    );
+    println!("  {}\n", "═".repeat(60).bright_black());
+
+    let stop = Arc::new(AtomicBool::new(false));
+    stop_on_signal(Arc::clone(&stop));
+    let supervisor = Supervisor {
+        config,
+        base_path,
+        env_vars,
+        stop,
+        mux,
+        state: Mutex::new(ServicesState {
+            supervisor: Some(std::process::id()),
+            services: BTreeMap::new(),
+            ports: services
+                .iter()
+                .filter(|s| !s.ports.is_empty())
+                .map(|s| (s.project.clone(), s.ports.clone()))
+                .collect(),
+        }),
+    };
+    state::save(base_path, STATE_FILE, &*supervisor.state.lock().unwrap())?;
+
+    std::thread::scope(|scope| {
+        for service in services {
+            let supervisor = &supervisor;
+            scope.spawn(move || supervisor.supervise(service));
+        }
+    });
+
+    state::save(base_path, STATE_FILE, &ServicesState::default())?;
+    println!("\n  {} All services stopped", "✓".green());
+    Ok(())
+}
+
+/// Stop the services started by `meta services up` in this workspace.
+pub fn services_down(base_path: &Path) -> Result<()> {
+    let running: ServicesState = state::load(base_path, STATE_FILE)?;
+    let Some(pid) = running.supervisor else {
+        println!("\n{}", "No services are running".dimmed());
+        return Ok(());
+    };
+    if !process_alive(pid) {
+        state::save(base_path, STATE_FILE, &ServicesState::default())?;
+        println!(
+            "\n{}",
+            "No services are running (cleared a stale record)".dimmed()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "\n  {} Stopping {} service(s)...",
+        "■".yellow(),
+        running.services.len()
+    );
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-TERM", &pid.to_string()])
+            .status();
+        let deadline = Instant::now() + GRACE * 2;
+        while Instant::now() < deadline && process_alive(pid) {
+            std::thread::sleep(POLL);
+        }
+    }
+    if process_alive(pid) {
+        // The supervisor did not stop in time (or cannot be signalled): stop
+        // the services directly.
+        for service_pid in running.services.values() {
+            terminate(*service_pid, true);
+        }
+        terminate(pid, true);
+        state::save(base_path, STATE_FILE, &ServicesState::default())?;
+    }
+    println!("  {} Services stopped", "✓".green());
+    Ok(())
+}
+
+/// Print a table of the services in `services` with whether each is running,
+/// its pid, and its ports (as started, or as declared when stopped). Running
+/// ports nothing is listening on yet are dimmed.
+pub fn services_status(base_path: &Path, services: &[Service]) -> Result<()> {
+    let mut running: ServicesState = state::load(base_path, STATE_FILE)?;
+    if running.supervisor.is_some_and(|pid| !process_alive(pid)) {
+        running = ServicesState::default();
+    }
+
+    let width = services
+        .iter()
+        .map(|s| s.project.len())
+        .max()
+        .unwrap_or(0)
+        .max("SERVICE".len());
+    println!(
+        "\n  {}  {:<8}  {:<8}  {}",
+        format!("{:<width$}", "SERVICE", width = width).bold(),
+        "STATE".bold(),
+        "PID".bold(),
+        "PORTS".bold()
+    );
+    println!("  {}", "─".repeat(60).bright_black());
+    for service in services {
+        let pid = running.services.get(&service.project);
+        let state = match pid {
+            Some(_) => format!("{:<8}", "running").green(),
+            None => format!("{:<8}", "stopped").bright_black(),
+        };
+        let ports = running
+            .ports
+            .get(&service.project)
+            .filter(|_| pid.is_some())
+            .unwrap_or(&service.ports);
+        let ports: Vec<String> = ports
+            .iter()
+            .map(|port| {
+                if pid.is_none() || port_in_use(*port) {
+                    port.to_string()
+                } else {
+                    port.to_string().bright_black().to_string()
+                }
+            })
+            .collect();
+        println!(
+            "  {:<width$}  {}  {:<8}  {}",
+            service.project,
+            state,
+            pid.map(u32::to_string).unwrap_or_else(|| "-".to_string()),
+            if ports.is_empty() {
+                "-".to_string()
+            } else {
+                ports.join(", ")
+            },
+            width = width
+        );
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MetaConfig {
+        serde_json::from_str(
+            r#"{
+                "services": {
+                    "api": { "tags": ["backend"], "ports": [3000] },
+                    "worker": { "script": "work", "tags": ["backend"], "restart": false },
+                    "web": { "script": "serve", "tags": ["frontend"] }
+                },
+                "projects": {
+                    "api": { "url": "u", "scripts": { "dev": "cargo run" } },
+                    "worker": "u",
+                    "web": "u",
+                    "docs": { "url": "u", "default_script": "serve-docs",
+                              "scripts": { "serve-docs": "mdbook serve" } }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn all() -> Vec<String> {
+        ["api", "docs", "web", "worker"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn selects_configured_services_by_tag() {
+        let tmp = tempfile::tempdir().unwrap();
+        let backend = select_services(
+            &config(),
+            tmp.path(),
+            &all(),
+            false,
+            &["backend".to_string()],
+        );
+        assert_eq!(
+            backend,
+            vec![
+                Service {
+                    project: "api".into(),
+                    script: "dev".into(),
+                    restart: true,
+                    ports: vec![3000],
+                },
+                Service {
+                    project: "worker".into(),
+                    script: "work".into(),
+                    restart: false,
+                    ports: vec![],
+                },
+            ]
+        );
+        let everything = select_services(&config(), tmp.path(), &all(), false, &[]);
+        let names: Vec<_> = everything.iter().map(|s| s.project.as_str()).collect();
+        assert_eq!(names, ["api", "web", "worker"]);
+    }
+
+    #[test]
+    fn tags_match_project_tags_too() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config: MetaConfig = serde_json::from_str(
+            r#"{
+                "services": {
+                    "api": { "script": "serve" },
+                    "web": { "script": "serve", "tags": ["frontend"] }
+                },
+                "projects": {
+                    "api": { "url": "u", "tags": ["backend"] },
+                    "web": "u",
+                    "docs": { "url": "u", "tags": ["backend"], "default_script": "serve",
+                              "scripts": { "serve": "mdbook serve" } }
+                }
+            }"#,
+        )
+        .unwrap();
+        let candidates = ["api", "docs", "web"].map(String::from).to_vec();
+        let backend = select_services(
+            &config,
+            tmp.path(),
+            &candidates,
+            false,
+            &["backend".to_string()],
+        );
+        let names: Vec<_> = backend.iter().map(|s| s.project.as_str()).collect();
+        // docs is tagged but has no services entry and was not named.
+        assert_eq!(names, ["api"]);
+
+        let named = select_services(
+            &config,
+            tmp.path(),
+            &["docs".to_string(), "web".to_string()],
+            true,
+            &["backend".to_string()],
+        );
+        let names: Vec<_> = named.iter().map(|s| s.project.as_str()).collect();
+        assert_eq!(names, ["docs"]);
+    }
+
+    #[test]
+    fn named_projects_without_a_service_entry_use_their_entry_point() {
+        let tmp = tempfile::tempdir().unwrap();
+        let named = select_services(&config(), tmp.path(), &["docs".to_string()], true, &[]);
+        assert_eq!(
+            named,
+            vec![Service {
+                project: "docs".into(),
+                script: "serve-docs".into(),
+                restart: true,
+                ports: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_on_quick_crashes_and_resets_when_stable() {
+        let quick = Duration::from_millis(200);
+        assert_eq!(next_backoff(INITIAL_BACKOFF, quick), Duration::from_secs(2));
+        assert_eq!(next_backoff(Duration::from_secs(20), quick), MAX_BACKOFF);
+        assert_eq!(
+            next_backoff(Duration::from_secs(16), STABLE_AFTER),
+            INITIAL_BACKOFF
+        );
+    }
+
+    fn service(project: &str, ports: &[u16]) -> Service {
+        Service {
+            project: project.into(),
+            script: "dev".into(),
+            restart: true,
+            ports: ports.to_vec(),
+        }
+    }
+
+    #[test]
+    fn port_conflicts_are_reported_together() {
+        let mut services = vec![
+            service("api", &[3000]),
+            service("web", &[3000, 8080]),
+            service("docs", &[4000]),
+        ];
+        let err = assign_ports(&mut services, false, |p| p == 8080, || unreachable!())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("web port 3000: also declared by api"));
+        assert!(err.contains("web port 8080: already in use"));
+        assert!(!err.contains("docs"));
+    }
+
+    #[test]
+    fn auto_ports_moves_conflicting_ports() {
+        let mut services = vec![service("api", &[3000]), service("web", &[3000, 8080])];
+        let mut free = vec![9002, 3000, 9001].into_iter();
+        let moves = assign_ports(
+            &mut services,
+            true,
+            |p| p == 8080,
+            || Ok(free.next_back().unwrap()),
+        )
+        .unwrap();
+        assert_eq!(
+            moves,
+            vec![
+                PortMove {
+                    project: "web".into(),
+                    from: 3000,
+                    to: 9001
+                },
+                PortMove {
+                    project: "web".into(),
+                    from: 8080,
+                    to: 9002
+                },
+            ]
+        );
+        assert_eq!(services[1].ports, vec![9001, 9002]);
+        assert_eq!(
+            port_env(&services[1].ports),
+            vec![
+                ("PORT".to_string(), "9001".to_string()),
+                ("PORTS".to_string(), "9001,9002".to_string()),
+            ]
+        );
+    }
+}
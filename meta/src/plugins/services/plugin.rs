@@ -0,0 +1,253 @@
+use super::{select_services, services_down, services_status, services_up};
+use crate::plugins::run::env_vars_from;
+use crate::plugins::shared::{selection, MuxOptions};
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::Colorize;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+
+/// ServicesPlugin for running several projects' dev servers together
+pub struct ServicesPlugin;
+
+impl ServicesPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("services")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Run long-lived dev servers across projects")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Run several projects' dev servers side by side, Procfile style.\n\
+                 \n\
+                 Services are declared in the `services` block of .meta, keyed by\n\
+                 project: `script` (default: the project's dev script, else its\n\
+                 default_script), `tags` for selecting groups (alongside the project's\n\
+                 own tags), `ports` it listens on, and `restart` (default true).\n\
+                 Output from every service is interleaved with a colored project prefix.",
+            )
+            .command(
+                command("up")
+                    .about("Start services and stream their output")
+                    .help_description(
+                        "Start the configured services in your directory scope, or the\n\
+                         named projects, and supervise them in the foreground.\n\
+                         \n\
+                         A service that exits is restarted after a delay that doubles on\n\
+                         repeated quick crashes (up to 30s) unless it sets `restart: false`.\n\
+                         Ctrl-C or `meta services down` stops every service; servers get a\n\
+                         few seconds to shut down before they are killed. Named projects\n\
+                         without a `services` entry run their dev entry point.\n\
+                         \n\
+                         Declared ports are exported as PORT (the first) and PORTS. A port\n\
+                         declared twice or already in use stops `up` before anything starts;\n\
+                         --auto-ports moves those services to free ports instead.\n\
+                         \n\
+                         --tag starts the services tagged in their services entry or whose\n\
+                         project carries the tag in .meta, across the whole workspace unless\n\
+                         projects are named.\n\
+                         \n\
+                         --only narrows the terminal to some services' output, --timestamps\n\
+                         prefixes each line with the time, and --log-file writes every line\n\
+                         from every service to one file.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta services up                     every configured service in scope\n\
+                           meta services up --tag backend       only services tagged backend\n\
+                           meta services up api web -e PORT=0   named projects, extra env\n\
+                           meta services up --only api --log-file dev.log",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to start (names, aliases, or globs)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .help("Only start services with this tag (service or project tags; comma-separated)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("auto-ports")
+                            .long("auto-ports")
+                            .help("Move services whose ports conflict to free ports"),
+                    )
+                    .arg(
+                        arg("no-restart")
+                            .long("no-restart")
+                            .help("Do not restart services that exit"),
+                    )
+                    .arg(
+                        arg("env")
+                            .long("env")
+                            .short('e')
+                            .help("Set environment variable (KEY=VALUE)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("only")
+                            .long("only")
+                            .help("Only show output from these projects (comma-separated)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("timestamps")
+                            .long("timestamps")
+                            .help("Prefix each output line with the time"),
+                    )
+                    .arg(
+                        arg("log-file")
+                            .long("log-file")
+                            .help("Also write all output to this file")
+                            .takes_value(true),
+                    ),
+            )
+            .command(
+                command("down")
+                    .about("Stop the services started by `meta services up`")
+                    .help_description(
+                        "Stop the services running in this workspace from another terminal.\n\
+                         \n\
+                         Signals the `meta services up` supervisor, which stops each service\n\
+                         as Ctrl-C would; services that do not stop in time are killed.",
+                    )
+                    .with_help_formatting(),
+            )
+            .command(
+                command("status")
+                    .about("Show services with their state, pid, and ports")
+                    .help_description(
+                        "List the configured services in your directory scope with whether\n\
+                         each is running, its pid, and its ports: the ports it was started\n\
+                         with (dimmed until something listens on them), or the declared\n\
+                         ports when stopped.",
+                    )
+                    .with_help_formatting(),
+            )
+            .handler("up", handle_up)
+            .handler("down", handle_down)
+            .handler("status", handle_status)
+            .build()
+    }
+}
+
+/// Handler for the up command
+fn handle_up(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let (mut candidates, explicit) = match selection::explicit_projects(matches, config)? {
+        Some(selected) => (selected, true),
+        None => (selection::implicit_scope(matches, config), false),
+    };
+    selection::apply_excludes(matches, config, &mut candidates, explicit);
+
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    let tags = selection::tags_from(matches);
+    let mut services = select_services(
+        &config.meta_config,
+        &base_path,
+        &candidates,
+        explicit,
+        &tags,
+    );
+    if services.is_empty() {
+        println!("\n{}", "No services to start".dimmed());
+        return Ok(());
+    }
+    if matches.get_flag("no-restart") {
+        for service in &mut services {
+            service.restart = false;
+        }
+    }
+
+    services_up(
+        &config.meta_config,
+        &base_path,
+        &mut services,
+        &env_vars_from(matches),
+        matches.get_flag("auto-ports"),
+        &MuxOptions::from_matches(matches),
+    )
+}
+
+/// Handler for the down command
+fn handle_down(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    services_down(&base_path)
+}
+
+/// Handler for the status command
+fn handle_status(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    let services = select_services(
+        &config.meta_config,
+        &base_path,
+        &config.scoped_project_keys(),
+        false,
+        &[],
+    );
+    if services.is_empty() {
+        println!("\n{}", "No services configured".dimmed());
+        return Ok(());
+    }
+    services_status(&base_path, &services)
+}
+
+impl MetaPlugin for ServicesPlugin {
+    fn name(&self) -> &str {
+        "services"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for ServicesPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Run long-lived dev servers across projects")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for ServicesPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,70 @@
+//! The `branch_policy` naming rule from `.meta`, checked wherever meta creates
+//! a new branch.
+
+use anyhow::{anyhow, bail, Result};
+use metarepo_core::{BranchPolicy, MetaConfig};
+use regex::Regex;
+
+/// Compile the policy's pattern, anchored so it must match the whole name.
+pub fn compile(policy: &BranchPolicy) -> Result<Regex> {
+    Regex::new(&format!("^(?:{})$", policy.pattern))
+        .map_err(|e| anyhow!("Invalid branch_policy pattern '{}': {}", policy.pattern, e))
+}
+
+/// Reject `branch` if the workspace has a `branch_policy` it does not match.
+/// The error names the expected pattern and, when configured, examples.
+pub fn check_branch_name(config: &MetaConfig, branch: &str) -> Result<()> {
+    let Some(policy) = &config.branch_policy else {
+        return Ok(());
+    };
+    if compile(policy)?.is_match(branch) {
+        return Ok(());
+    }
+    let mut message = format!(
+        "Branch name '{}' does not match the workspace branch policy\n  Expected pattern: {}",
+        branch, policy.pattern
+    );
+    if !policy.examples.is_empty() {
+        message.push_str(&format!("\n  Examples: {}", policy.examples.join(", ")));
+    }
+    bail!(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(pattern: &str, examples: &[&str]) -> MetaConfig {
+        MetaConfig {
+            branch_policy: Some(BranchPolicy {
+                pattern: pattern.to_string(),
+                examples: examples.iter().map(|e| e.to_string()).collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn names_must_match_the_whole_pattern() {
+        let config = config(
+            "(feat|fix)/[a-z0-9-]+",
+            &["feat/login", "fix/crash-on-start"],
+        );
+        assert!(check_branch_name(&config, "feat/login").is_ok());
+        assert!(check_branch_name(&config, "fix/42-typo").is_ok());
+
+        let err = check_branch_name(&config, "my-feat/login").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("'my-feat/login'"));
+        assert!(message.contains("Expected pattern: (feat|fix)/[a-z0-9-]+"));
+        assert!(message.contains("Examples: feat/login, fix/crash-on-start"));
+        assert!(check_branch_name(&config, "feat/Login").is_err());
+    }
+
+    #[test]
+    fn no_policy_allows_anything_and_bad_patterns_error() {
+        assert!(check_branch_name(&MetaConfig::default(), "anything goes").is_ok());
+        let err = check_branch_name(&config("feat/(", &[]), "feat/x").unwrap_err();
+        assert!(err.to_string().contains("Invalid branch_policy pattern"));
+    }
+}
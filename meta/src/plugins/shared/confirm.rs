@@ -0,0 +1,123 @@
+//! Confirmation for protected operations.
+//!
+//! A workspace lists dangerous bulk operations in its `protected` config
+//! (`"protected": ["force-push", "remove"]`). Before one of them runs, the
+//! user types the workspace name, as a deliberate step that a stray up-arrow
+//! and enter cannot take. `--yes-i-know` confirms up front; automation that
+//! cannot pass it sets `METAREPO_ALLOW_PROTECTED=1`. Without a terminal and
+//! without either, the operation is refused.
+
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::Colorize;
+use metarepo_core::{prompt_text, NonInteractiveMode, RuntimeConfig};
+
+/// Flag that confirms a protected operation without a prompt.
+pub const YES_FLAG: &str = "yes-i-know";
+
+/// Environment variable that, set to `1`, confirms every protected operation.
+pub const ALLOW_ENV: &str = "METAREPO_ALLOW_PROTECTED";
+
+/// `meta git push --force`.
+pub const FORCE_PUSH: &str = "force-push";
+/// `meta project remove --force`.
+pub const REMOVE: &str = "remove";
+/// `meta git clean -f`.
+pub const CLEAN: &str = "clean";
+
+/// How a protected operation was (or could be) confirmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Confirmation {
+    /// Not protected in this workspace, or confirmed by flag or env.
+    Granted,
+    /// Ask for the workspace name.
+    Prompt,
+    /// Protected, and nobody is there to ask.
+    Refused,
+}
+
+fn decide(protected: bool, flag: bool, env: Option<&str>, interactive: bool) -> Confirmation {
+    if !protected || flag || env == Some("1") {
+        Confirmation::Granted
+    } else if interactive {
+        Confirmation::Prompt
+    } else {
+        Confirmation::Refused
+    }
+}
+
+/// The name the user types to confirm: the workspace directory's name.
+fn workspace_name(config: &RuntimeConfig) -> String {
+    let root = config
+        .meta_root()
+        .unwrap_or_else(|| config.working_dir.clone());
+    root.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| root.display().to_string())
+}
+
+/// Stop unless `operation` may go ahead: it is not listed in the workspace's
+/// `protected` config, `--yes-i-know` was passed (when the command has the
+/// flag), `METAREPO_ALLOW_PROTECTED=1` is set, or the user types the
+/// workspace name. `what` describes the operation in the prompt.
+pub fn require(
+    operation: &str,
+    what: &str,
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+) -> Result<()> {
+    let protected = config.meta_config.protected.iter().any(|p| p == operation);
+    let flag = matches.try_get_one::<bool>(YES_FLAG).ok().flatten() == Some(&true);
+    let env = std::env::var(ALLOW_ENV).ok();
+    let name = workspace_name(config);
+    match decide(
+        protected,
+        flag,
+        env.as_deref(),
+        metarepo_core::is_interactive(),
+    ) {
+        Confirmation::Granted => Ok(()),
+        Confirmation::Refused => Err(anyhow::anyhow!(
+            "'{}' is protected in this workspace and needs confirmation: pass --{} or set {}=1",
+            operation,
+            YES_FLAG,
+            ALLOW_ENV
+        )),
+        Confirmation::Prompt => {
+            eprintln!(
+                "\n  {} {} is a protected operation in this workspace.",
+                "⚠".yellow(),
+                what
+            );
+            let typed = prompt_text(
+                &format!("Type the workspace name ({}) to continue", name.bold()),
+                None,
+                true,
+                NonInteractiveMode::Fail,
+            )?;
+            if typed.trim() == name {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!(
+                    "Confirmation did not match; nothing was done"
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_protected_operations_need_confirmation() {
+        assert_eq!(decide(false, false, None, false), Confirmation::Granted);
+        assert_eq!(decide(true, true, None, false), Confirmation::Granted);
+        assert_eq!(decide(true, false, Some("1"), false), Confirmation::Granted);
+        // The variable must be set explicitly, not merely present.
+        assert_eq!(decide(true, false, Some(""), false), Confirmation::Refused);
+        assert_eq!(decide(true, false, Some("yes"), true), Confirmation::Prompt);
+        assert_eq!(decide(true, false, None, true), Confirmation::Prompt);
+    }
+}
@@ -0,0 +1,483 @@
+//! Credentials for clones and fetches.
+//!
+//! An SSH remote is offered the key configured for it in the `hosts` block
+//! of the user config, keyed by host or by `host/repo-path` prefix, else the
+//! default keys in `~/.ssh`; then the SSH agent. Each is offered once.
+//!
+//! When an HTTPS remote asks for a username and password, the sources below
+//! are tried in order. Each is offered at most once per transfer, so a
+//! rejected token falls through to the next source instead of looping:
+//!
+//! 1. the host's entry in the `credentials` block of the user config
+//!    (`token-env`, else `token-command`);
+//! 2. environment variables: `METAREPO_GIT_TOKEN` (with
+//!    `METAREPO_GIT_USERNAME`) for the hosts listed in
+//!    `METAREPO_GIT_TOKEN_HOSTS`, then `GH_TOKEN`/`GITHUB_TOKEN` for
+//!    github.com and `GITLAB_TOKEN` for gitlab.com;
+//! 3. a token stored for the host in the OS keychain by `meta auth login`;
+//! 4. git's credential helpers (`credential.helper` in git config);
+//! 5. the `GIT_ASKPASS` program, asked the way git asks it.
+//!
+//! The user config names where a token lives rather than holding it, and a
+//! workspace `.meta` cannot add hosts or keys: cloning a workspace never
+//! sends a credential somewhere its author chose. Tokens from the first three
+//! sources are only sent over `https://`, never in the clear.
+
+use crate::plugins::plugin_loader::expand_tilde;
+use git2::Cred;
+use metarepo_core::{HostCredentials, MetaConfig, RemoteUrl, SshHost};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use super::keychain;
+
+/// Token sent to the hosts in [`TOKEN_HOSTS_ENV`].
+pub const TOKEN_ENV: &str = "METAREPO_GIT_TOKEN";
+/// Comma-separated hosts [`TOKEN_ENV`] is sent to; it goes nowhere else.
+pub const TOKEN_HOSTS_ENV: &str = "METAREPO_GIT_TOKEN_HOSTS";
+/// Username sent with [`TOKEN_ENV`].
+pub const USERNAME_ENV: &str = "METAREPO_GIT_USERNAME";
+
+/// Username sent with a token when none is configured. GitHub and GitLab
+/// accept any username with a token; this is the one GitHub documents.
+const TOKEN_USERNAME: &str = "x-access-token";
+
+/// Where a username and password can come from, in the order tried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Config,
+    Env,
+    Keychain,
+    Helper,
+    Askpass,
+}
+
+const SOURCES: [Source; 5] = [
+    Source::Config,
+    Source::Env,
+    Source::Keychain,
+    Source::Helper,
+    Source::Askpass,
+];
+
+/// Default private keys in `~/.ssh`, in order of preference.
+const DEFAULT_KEYS: [&str; 4] = ["id_ed25519", "id_rsa", "id_ecdsa", "id_dsa"];
+
+/// The `credentials` and `hosts` blocks of the user config.
+#[derive(Default)]
+struct UserAuth {
+    credentials: HashMap<String, HostCredentials>,
+    hosts: HashMap<String, SshHost>,
+}
+
+/// The user config's authentication settings, read once.
+fn user_auth() -> &'static UserAuth {
+    static AUTH: OnceLock<UserAuth> = OnceLock::new();
+    AUTH.get_or_init(|| {
+        metarepo_core::user_config_path()
+            .and_then(|path| MetaConfig::load_user_config(&path).ok())
+            .map(|user| UserAuth {
+                credentials: user.credentials.unwrap_or_default(),
+                hosts: user.hosts.unwrap_or_default(),
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// The host and repository path of a remote URL: `https://`, `http://`,
+/// `ssh://`, or scp-like `user@host:path`, split as [`RemoteUrl`] does.
+pub fn remote_location(url: &str) -> Option<(&str, &str)> {
+    let remote = RemoteUrl::parse(url)?;
+    let supported = matches!(remote.scheme, None | Some("https" | "http" | "ssh"));
+    (supported && !remote.host.is_empty()).then_some((remote.host, remote.path))
+}
+
+/// The host of a remote URL (see [`remote_location`]).
+pub fn host_of(url: &str) -> Option<&str> {
+    remote_location(url).map(|(host, _)| host)
+}
+
+/// The `hosts` entry for `url`: the longest key that is its host, or its
+/// host and a prefix of its repository path.
+fn ssh_host_for<'a>(hosts: &'a HashMap<String, SshHost>, url: &str) -> Option<&'a SshHost> {
+    let (host, path) = remote_location(url)?;
+    let location = format!("{}/{}", host, path);
+    hosts
+        .iter()
+        .filter(|(key, _)| {
+            let key = key.trim_end_matches('/');
+            location == key || location.starts_with(&format!("{}/", key))
+        })
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, entry)| entry)
+}
+
+/// One way to authenticate an SSH remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SshAttempt {
+    Key(PathBuf),
+    Agent,
+}
+
+/// What to offer an SSH remote, in order: the configured key, or else the
+/// default keys that exist in `home`'s `.ssh`; then the agent.
+fn ssh_attempts(entry: Option<&SshHost>, home: Option<&Path>) -> Vec<SshAttempt> {
+    let mut attempts: Vec<SshAttempt> = match entry.and_then(|e| e.ssh_key.as_deref()) {
+        Some(key) => vec![SshAttempt::Key(expand_tilde(key))],
+        None => home
+            .map(|home| {
+                DEFAULT_KEYS
+                    .iter()
+                    .map(|name| home.join(".ssh").join(name))
+                    .filter(|key| key.exists())
+                    .map(SshAttempt::Key)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    attempts.push(SshAttempt::Agent);
+    attempts
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// The SSH username for `url`: the `hosts` entry's, else the URL's, else
+/// `git`.
+pub fn ssh_username(url: &str, username_from_url: Option<&str>) -> String {
+    ssh_host_for(&user_auth().hosts, url)
+        .and_then(|entry| entry.username.clone())
+        .or_else(|| username_from_url.map(String::from))
+        .unwrap_or_else(|| "git".to_string())
+}
+
+/// An SSH key (or the agent) for `url`, trying the attempts from `*next` on.
+/// `*next` moves past the one used, so calling again after the server
+/// rejects it tries the next. `None` once every attempt is exhausted.
+pub fn ssh_key(url: &str, username_from_url: Option<&str>, next: &mut usize) -> Option<Cred> {
+    let username = ssh_username(url, username_from_url);
+    let attempts = ssh_attempts(ssh_host_for(&user_auth().hosts, url), home_dir().as_deref());
+    while let Some(attempt) = attempts.get(*next) {
+        *next += 1;
+        let cred = match attempt {
+            SshAttempt::Key(private_key) => {
+                let mut public_key = private_key.clone().into_os_string();
+                public_key.push(".pub");
+                let public_key = PathBuf::from(public_key);
+                let public_key = public_key.exists().then_some(public_key.as_path());
+                Cred::ssh_key(&username, public_key, private_key, None)
+            }
+            SshAttempt::Agent => Cred::ssh_key_from_agent(&username),
+        };
+        if let Ok(cred) = cred {
+            return Some(cred);
+        }
+    }
+    None
+}
+
+/// Whether `url` is encrypted in transit, so a token may be sent over it.
+fn is_https(url: &str) -> bool {
+    url.starts_with("https://")
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// The token of a host's config entry: its `token-env` variable, else the
+/// output of its `token-command`.
+fn config_token(entry: &HostCredentials, env: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    if let Some(token) = entry
+        .token_env
+        .as_deref()
+        .and_then(|var| non_empty(env(var)))
+    {
+        return Some(token);
+    }
+    let command = entry.token_command.as_deref()?;
+    let output = Command::new("sh").arg("-c").arg(command).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    non_empty(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+/// A token from the environment for `host`: `METAREPO_GIT_TOKEN` when
+/// `METAREPO_GIT_TOKEN_HOSTS` lists the host, else the host's well-known
+/// variables.
+fn env_token(host: &str, env: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    env_token_source(host, env).and_then(|var| non_empty(env(var)))
+}
+
+/// The environment variable [`env_token`] takes `host`'s token from, if any.
+pub fn env_token_source(host: &str, env: &dyn Fn(&str) -> Option<String>) -> Option<&'static str> {
+    let known: &[&str] = match host {
+        "github.com" => &["GH_TOKEN", "GITHUB_TOKEN"],
+        "gitlab.com" => &["GITLAB_TOKEN"],
+        _ => &[],
+    };
+    let opted_in = env(TOKEN_HOSTS_ENV).is_some_and(|hosts| {
+        hosts
+            .split(',')
+            .any(|listed| listed.trim().eq_ignore_ascii_case(host))
+    });
+    opted_in
+        .then_some(TOKEN_ENV)
+        .into_iter()
+        .chain(known.iter().copied())
+        .find(|var| non_empty(env(var)).is_some())
+}
+
+/// Ask the `GIT_ASKPASS` program at `program` for a username (unless one is
+/// known) and password, with git's prompts.
+fn askpass(program: &str, url: &str, username: Option<&str>) -> Option<(String, String)> {
+    let ask = |prompt: String| -> Option<String> {
+        let output = Command::new(program).arg(prompt).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let answer = String::from_utf8_lossy(&output.stdout);
+        Some(answer.trim_end_matches(['\r', '\n']).to_string())
+    };
+    let (scheme, _) = url.split_once("://")?;
+    let host = host_of(url)?;
+    let username = match username {
+        Some(name) => name.to_string(),
+        None => ask(format!("Username for '{}://{}': ", scheme, host))?,
+    };
+    let password = ask(format!(
+        "Password for '{}://{}@{}': ",
+        scheme, username, host
+    ))
+    .filter(|p| !p.is_empty())?;
+    Some((username, password))
+}
+
+/// A username and password for `url` from `source`, if it has one.
+fn from_source(
+    source: Source,
+    url: &str,
+    username_from_url: Option<&str>,
+    hosts: &HashMap<String, HostCredentials>,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Option<Cred> {
+    let host = host_of(url)?;
+    match source {
+        Source::Config | Source::Env | Source::Keychain if !is_https(url) => None,
+        Source::Config => {
+            let entry = hosts.get(host)?;
+            let token = config_token(entry, env)?;
+            let username = entry
+                .username
+                .as_deref()
+                .or(username_from_url)
+                .unwrap_or(TOKEN_USERNAME);
+            Cred::userpass_plaintext(username, &token).ok()
+        }
+        Source::Env => {
+            let token = env_token(host, env)?;
+            let username = non_empty(env(USERNAME_ENV))
+                .or_else(|| username_from_url.map(String::from))
+                .unwrap_or_else(|| TOKEN_USERNAME.to_string());
+            Cred::userpass_plaintext(&username, &token).ok()
+        }
+        Source::Keychain => {
+            let token = keychain::get(host)?;
+            Cred::userpass_plaintext(username_from_url.unwrap_or(TOKEN_USERNAME), &token).ok()
+        }
+        Source::Helper => {
+            let config = git2::Config::open_default().ok()?;
+            Cred::credential_helper(&config, url, username_from_url).ok()
+        }
+        Source::Askpass => {
+            let program = non_empty(env("GIT_ASKPASS"))?;
+            let (username, password) = askpass(&program, url, username_from_url)?;
+            Cred::userpass_plaintext(&username, &password).ok()
+        }
+    }
+}
+
+/// Credentials for an HTTPS remote asking for a username and password,
+/// trying the sources from `*next` on. `*next` moves past the source used,
+/// so calling again after the host rejects them tries the next one. `None`
+/// once every source is exhausted.
+pub fn user_pass(url: &str, username_from_url: Option<&str>, next: &mut usize) -> Option<Cred> {
+    let env = |var: &str| std::env::var(var).ok();
+    while let Some(&source) = SOURCES.get(*next) {
+        *next += 1;
+        if let Some(cred) = from_source(
+            source,
+            url,
+            username_from_url,
+            &user_auth().credentials,
+            &env,
+        ) {
+            return Some(cred);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |var| vars.get(var).cloned()
+    }
+
+    #[test]
+    fn host_ignores_user_info_and_port() {
+        assert_eq!(
+            host_of("https://github.com/acme/api.git"),
+            Some("github.com")
+        );
+        assert_eq!(
+            host_of("https://me:pw@git.example.com:8443/a.git"),
+            Some("git.example.com")
+        );
+        assert_eq!(host_of("git@github.com:acme/api.git"), Some("github.com"));
+        assert_eq!(host_of("/srv/git/api.git"), None);
+        assert_eq!(host_of("file:///srv/git/api.git"), None);
+        assert_eq!(
+            remote_location("ssh://git@gitlab.internal:2222/team/api.git"),
+            Some(("gitlab.internal", "team/api"))
+        );
+    }
+
+    #[test]
+    fn longest_matching_host_entry_wins() {
+        let entry = |key: &str| SshHost {
+            ssh_key: Some(key.to_string()),
+            ..Default::default()
+        };
+        let hosts: HashMap<String, SshHost> = [
+            ("gitlab.internal".to_string(), entry("~/.ssh/gitlab")),
+            ("gitlab.internal/team/api".to_string(), entry("~/.ssh/api")),
+        ]
+        .into_iter()
+        .collect();
+        let key_for = |url: &str| ssh_host_for(&hosts, url).and_then(|e| e.ssh_key.clone());
+        assert_eq!(
+            key_for("git@gitlab.internal:team/api.git").as_deref(),
+            Some("~/.ssh/api")
+        );
+        assert_eq!(
+            key_for("git@gitlab.internal:team/api-docs.git").as_deref(),
+            Some("~/.ssh/gitlab")
+        );
+        assert_eq!(key_for("git@github.com:team/api.git"), None);
+    }
+
+    #[test]
+    fn configured_key_replaces_the_default_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        let home = tmp.path();
+        std::fs::create_dir(home.join(".ssh")).unwrap();
+        std::fs::write(home.join(".ssh/id_rsa"), "").unwrap();
+
+        assert_eq!(
+            ssh_attempts(None, Some(home)),
+            [SshAttempt::Key(home.join(".ssh/id_rsa")), SshAttempt::Agent]
+        );
+        let entry = SshHost {
+            ssh_key: Some("/keys/deploy".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ssh_attempts(Some(&entry), Some(home)),
+            [SshAttempt::Key("/keys/deploy".into()), SshAttempt::Agent]
+        );
+    }
+
+    #[test]
+    fn env_token_prefers_the_generic_variable() {
+        let env = env_of(&[("GITHUB_TOKEN", "gh"), ("GITLAB_TOKEN", "gl")]);
+        assert_eq!(env_token("github.com", &env).as_deref(), Some("gh"));
+        assert_eq!(env_token("gitlab.com", &env).as_deref(), Some("gl"));
+        assert_eq!(env_token("git.example.com", &env), None);
+
+        let env = env_of(&[
+            (TOKEN_ENV, "mine"),
+            (TOKEN_HOSTS_ENV, "git.example.com, GitHub.com"),
+            ("GITHUB_TOKEN", "gh"),
+        ]);
+        assert_eq!(env_token("github.com", &env).as_deref(), Some("mine"));
+    }
+
+    #[test]
+    fn generic_token_only_goes_to_listed_hosts() {
+        let env = env_of(&[(TOKEN_ENV, "mine")]);
+        assert_eq!(env_token("git.example.com", &env), None);
+
+        let env = env_of(&[(TOKEN_ENV, "mine"), (TOKEN_HOSTS_ENV, "git.example.com")]);
+        assert_eq!(env_token("git.example.com", &env).as_deref(), Some("mine"));
+        assert_eq!(env_token("evil.example.com", &env), None);
+        assert_eq!(env_token("example.com", &env), None);
+    }
+
+    #[test]
+    fn tokens_are_never_sent_over_plain_http() {
+        let hosts = HashMap::from([(
+            "git.example.com".to_string(),
+            HostCredentials {
+                token_env: Some("CORP_TOKEN".to_string()),
+                ..Default::default()
+            },
+        )]);
+        let env = env_of(&[
+            ("CORP_TOKEN", "corp"),
+            (TOKEN_ENV, "mine"),
+            (TOKEN_HOSTS_ENV, "git.example.com"),
+        ]);
+        for source in [Source::Config, Source::Env] {
+            let cred = |url| from_source(source, url, None, &hosts, &env);
+            assert!(cred("https://git.example.com/a.git").is_some());
+            assert!(cred("http://git.example.com/a.git").is_none());
+        }
+    }
+
+    #[test]
+    fn config_token_reads_the_variable_then_the_command() {
+        let entry = HostCredentials {
+            token_env: Some("CORP_TOKEN".to_string()),
+            token_command: Some("echo from-command".to_string()),
+            ..Default::default()
+        };
+        let env = env_of(&[("CORP_TOKEN", "from-env")]);
+        assert_eq!(config_token(&entry, &env).as_deref(), Some("from-env"));
+        let env = env_of(&[]);
+        assert_eq!(config_token(&entry, &env).as_deref(), Some("from-command"));
+
+        let failing = HostCredentials {
+            token_command: Some("exit 1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config_token(&failing, &env), None);
+    }
+
+    #[test]
+    fn askpass_is_asked_git_prompts() {
+        // `echo` answers each prompt with the prompt itself.
+        let url = "https://git.example.com/a.git";
+        let (user, pass) = askpass("echo", url, Some("bob")).unwrap();
+        assert_eq!(user, "bob");
+        assert_eq!(pass, "Password for 'https://bob@git.example.com': ");
+
+        let (user, _) = askpass("echo", url, None).unwrap();
+        assert_eq!(user, "Username for 'https://git.example.com': ");
+        assert!(askpass("false", url, Some("bob")).is_none());
+    }
+}
@@ -1,9 +1,28 @@
 use anyhow::{Context, Result};
 use colored::*;
 use git2::{Cred, FetchOptions, RemoteCallbacks, Repository};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use super::{credentials, submodules, transfer};
+
+/// Run `git` in `dir` and return its trimmed stdout, or stderr as the error.
+pub fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 /// Parse and validate a `--depth <N>` CLI argument value into a shallow-clone
 /// depth, shared by `meta git clone` and `meta project add`.
 ///
@@ -67,7 +86,83 @@ pub fn refetch_shallow(repo_path: &Path, depth: i32) -> Result<()> {
     Ok(())
 }
 
-/// Clone a repository with authentication support.
+/// Callbacks that authenticate remotes: SSH remotes with the key configured
+/// for them, the usual key files, or the agent; HTTPS remotes with a token or
+/// password. See [`credentials`] for the sources.
+pub fn auth_callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    // Positions in the SSH and HTTPS sources, so a rejected credential is not
+    // offered again.
+    let mut next_key = 0;
+    let mut next_source = 0;
+    callbacks.credentials(move |url, username_from_url, allowed_types| {
+        if allowed_types.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            return credentials::user_pass(url, username_from_url, &mut next_source).ok_or_else(
+                || {
+                    git2::Error::from_str(&format!(
+                        "HTTPS authentication failed for {}",
+                        credentials::host_of(url).unwrap_or(url)
+                    ))
+                },
+            );
+        }
+        if allowed_types.contains(git2::CredentialType::USERNAME) {
+            return Cred::username(&credentials::ssh_username(url, username_from_url));
+        }
+        if allowed_types.contains(git2::CredentialType::SSH_KEY) {
+            if let Some(cred) = credentials::ssh_key(url, username_from_url, &mut next_key) {
+                return Ok(cred);
+            }
+        }
+
+        // If we couldn't authenticate, return an error
+        Err(git2::Error::from_str(
+            "SSH authentication failed. Please ensure your SSH keys are set up correctly.",
+        ))
+    });
+    callbacks
+}
+
+/// Fetch `origin` into the repository (or worktree) at `repo_path` through
+/// libgit2, so the transfer is subject to the `--limit-rate` throttle and
+/// reports its progress. The git CLI has no rate limit; fetching here first
+/// leaves a following `git pull` nothing to download.
+pub fn fetch_origin(repo_path: &Path) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let mut remote = repo
+        .find_remote("origin")
+        .context("No origin remote to fetch")?;
+    let mut callbacks = auth_callbacks();
+    transfer::apply(&mut callbacks, &display_name(repo_path));
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    // An empty refspec list uses the remote's configured refspecs.
+    remote
+        .fetch::<&str>(&[], Some(&mut fetch_options), None)
+        .map_err(|e| anyhow::anyhow!("Failed to fetch origin: {}", e))
+}
+
+/// The repository name in `url`, for progress output.
+fn repo_name(url: &str) -> String {
+    let trimmed = url.trim_end_matches('/').trim_end_matches(".git");
+    trimmed
+        .rsplit(['/', ':'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(url)
+        .to_string()
+}
+
+/// The last component of `path`, for progress output.
+pub fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Clone a repository with authentication support, reporting transfer
+/// progress as it goes. With [`submodules::enabled`], a checkout's
+/// submodules are cloned too.
 ///
 /// `depth` optionally requests a shallow clone with the given history depth.
 /// A value of `Some(d)` with `d <= 0` is rejected.
@@ -86,103 +181,56 @@ pub fn clone_with_auth(
         }
     }
 
-    // Check if this is an SSH URL
-    if url.starts_with("git@") || url.starts_with("ssh://") {
-        // Set up authentication callbacks for SSH
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.credentials(|_url, username_from_url, allowed_types| {
-            // Get the username (default to "git" for GitHub/GitLab/etc)
-            let username = username_from_url.unwrap_or("git");
-
-            // If SSH agent is requested, try it first
-            if allowed_types.contains(git2::CredentialType::SSH_KEY) {
-                // Try to find SSH keys in standard locations
-                if let Ok(home) = std::env::var("HOME") {
-                    let ssh_dir = Path::new(&home).join(".ssh");
-
-                    // Try common SSH key names in order of preference
-                    let key_names = ["id_ed25519", "id_rsa", "id_ecdsa", "id_dsa"];
-
-                    for key_name in &key_names {
-                        let private_key = ssh_dir.join(key_name);
-                        if private_key.exists() {
-                            // Check if there's a public key as well
-                            let public_key = ssh_dir.join(format!("{}.pub", key_name));
-                            let public_key_path = if public_key.exists() {
-                                Some(public_key.as_path())
-                            } else {
-                                None
-                            };
-
-                            if let Ok(cred) = Cred::ssh_key(
-                                username,
-                                public_key_path,
-                                private_key.as_path(),
-                                None, // No passphrase for now
-                            ) {
-                                return Ok(cred);
-                            }
-                        }
-                    }
-                }
-
-                // Try SSH agent as fallback
-                if let Ok(cred) = Cred::ssh_key_from_agent(username) {
-                    return Ok(cred);
-                }
-            }
-
-            // If we couldn't authenticate, return an error
-            Err(git2::Error::from_str(
-                "SSH authentication failed. Please ensure your SSH keys are set up correctly.",
-            ))
-        });
-
-        // Configure fetch options with our callbacks
-        let mut fetch_options = FetchOptions::new();
-        fetch_options.remote_callbacks(callbacks);
-        if let Some(d) = depth {
-            fetch_options.depth(d);
-        }
+    let mut callbacks = auth_callbacks();
+    transfer::apply(&mut callbacks, &repo_name(url));
 
-        // Build the repository with authentication
-        let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fetch_options);
+    // Configure fetch options with our callbacks
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    if let Some(d) = depth {
+        fetch_options.depth(d);
+    }
 
-        if bare {
-            builder.bare(true);
-        }
+    // Build the repository with authentication
+    let mut builder = git2::build::RepoBuilder::new();
+    builder.fetch_options(fetch_options);
 
-        // Clone the repository
-        builder.clone(url, path).map_err(|e| {
-            if e.to_string().contains("authentication") || e.to_string().contains("SSH") {
-                anyhow::anyhow!("SSH authentication failed. Please ensure:\n  1. Your SSH key is set up correctly (~/.ssh/id_ed25519 or ~/.ssh/id_rsa)\n  2. The key is added to your GitHub/GitLab account\n  3. You have access to the repository\n\nOriginal error: {}", e)
-            } else {
-                anyhow::anyhow!("Failed to clone repository: {}", e)
-            }
-        })
-    } else {
-        // For HTTPS URLs, use RepoBuilder without authentication callbacks so we
-        // can still apply a shallow-clone depth when requested.
-        let mut fetch_options = FetchOptions::new();
-        if let Some(d) = depth {
-            fetch_options.depth(d);
-        }
+    if bare {
+        builder.bare(true);
+    }
 
-        let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fetch_options);
-        if bare {
-            builder.bare(true);
+    // Clone the repository
+    let ssh = url.starts_with("git@") || url.starts_with("ssh://");
+    let repo = builder.clone(url, path).map_err(|e| {
+        let message = e.to_string();
+        if ssh && (message.contains("authentication") || message.contains("SSH")) {
+            anyhow::anyhow!("SSH authentication failed. Please ensure:\n  1. Your SSH key is set up correctly (~/.ssh/id_ed25519 or ~/.ssh/id_rsa, or a [hosts.\"<host>\"] ssh-key in the user config)\n  2. The key is added to your GitHub/GitLab account\n  3. You have access to the repository\n\nOriginal error: {}", e)
+        } else if !ssh && message.contains("authentication") {
+            anyhow::anyhow!("HTTPS authentication failed. Provide a token through one of:\n  1. A [credentials.\"<host>\"] entry (token-env or token-command) in the user config\n  2. {} (or GH_TOKEN/GITHUB_TOKEN, GITLAB_TOKEN)\n  3. meta auth login <provider>, which keeps the token in the OS keychain\n  4. A git credential helper (git config credential.helper)\n  5. GIT_ASKPASS\n\nOriginal error: {}", credentials::TOKEN_ENV, e)
+        } else {
+            anyhow::anyhow!("Failed to clone repository: {}", e)
         }
-
-        builder
-            .clone(url, path)
-            .map_err(|e| anyhow::anyhow!("Failed to clone repository: {}", e))
+    })?;
+    if !bare && submodules::enabled() {
+        submodules::update(path)?;
     }
+    Ok(repo)
 }
 
 /// Create a default worktree for a bare repository
 pub fn create_default_worktree(bare_repo_path: &Path, project_path: &Path) -> Result<()> {
+    let worktree_path = add_default_worktree(bare_repo_path, project_path)?;
+    println!(
+        "     {} {}",
+        "✅".green(),
+        format!("Created default worktree: {}", worktree_path.display()).green()
+    );
+    Ok(())
+}
+
+/// [`create_default_worktree`] without output; returns the worktree's path.
+/// With [`submodules::enabled`], the worktree's submodules are checked out.
+pub fn add_default_worktree(bare_repo_path: &Path, project_path: &Path) -> Result<PathBuf> {
     // Try to detect the default branch
     let default_branch = detect_default_branch(bare_repo_path)?;
 
@@ -206,14 +254,11 @@ pub fn create_default_worktree(bare_repo_path: &Path, project_path: &Path) -> Re
             stderr
         ));
     }
+    if submodules::enabled() {
+        submodules::update(&worktree_path)?;
+    }
 
-    println!(
-        "     {} {}",
-        "✅".green(),
-        format!("Created default worktree: {}", worktree_path.display()).green()
-    );
-
-    Ok(())
+    Ok(worktree_path)
 }
 
 /// Detect the default branch of a repository
@@ -257,6 +302,180 @@ pub fn detect_default_branch(repo_path: &Path) -> Result<String> {
     Ok("main".to_string())
 }
 
+/// Set to `gix` to answer read-only queries with gitoxide, in builds with
+/// the `gix` feature.
+pub const BACKEND_ENV: &str = "METAREPO_GIT_BACKEND";
+
+/// How one path differs, in the terms `git status` reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileStatus {
+    pub wt_new: bool,
+    pub wt_modified: bool,
+    pub wt_deleted: bool,
+    pub index_new: bool,
+    pub index_modified: bool,
+    pub index_deleted: bool,
+}
+
+/// A changed path in a checkout, relative to its root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEntry {
+    pub path: String,
+    pub status: FileStatus,
+}
+
+/// A commit reachable from HEAD.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub short_hash: String,
+    /// Author date, Unix seconds.
+    pub timestamp: i64,
+    pub author: String,
+    pub email: String,
+    /// First paragraph of the message, on one line.
+    pub subject: String,
+    pub message: String,
+}
+
+/// Branch names of a repository, without `refs/heads/` or `refs/remotes/`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Branches {
+    pub local: Vec<String>,
+    /// `<remote>/<branch>`, symbolic refs such as `origin/HEAD` left out.
+    pub remote: Vec<String>,
+}
+
+/// The read-only queries run across every project: status, history, and
+/// branches. libgit2 answers them by default; building with the `gix`
+/// feature adds a gitoxide backend to choose instead (`benches/git_backend.rs`
+/// compares the two). Entries come back sorted by path, commits newest first
+/// by commit date, and branches sorted by name, whichever backend answers.
+pub trait GitBackend: Sync {
+    fn name(&self) -> &'static str;
+
+    /// Changes in the worktree and the index. Untracked files are listed one
+    /// by one; ignored files are not.
+    fn status(&self, path: &Path) -> Result<Vec<StatusEntry>>;
+
+    /// Commits reachable from HEAD, stopping at the first one committed
+    /// before `since` (Unix seconds). An unborn branch has none.
+    fn log(&self, path: &Path, since: Option<i64>) -> Result<Vec<Commit>>;
+
+    fn branches(&self, path: &Path) -> Result<Branches>;
+}
+
+/// The libgit2 backend, always available.
+pub struct Libgit2Backend;
+
+impl GitBackend for Libgit2Backend {
+    fn name(&self) -> &'static str {
+        "libgit2"
+    }
+
+    fn status(&self, path: &Path) -> Result<Vec<StatusEntry>> {
+        let repo = Repository::open(path)?;
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+        let statuses = repo.statuses(Some(&mut options))?;
+        let mut entries: Vec<StatusEntry> = statuses
+            .iter()
+            .map(|entry| {
+                let status = entry.status();
+                StatusEntry {
+                    path: String::from_utf8_lossy(entry.path_bytes()).into_owned(),
+                    status: FileStatus {
+                        wt_new: status.is_wt_new(),
+                        wt_modified: status.is_wt_modified() || status.is_wt_typechange(),
+                        wt_deleted: status.is_wt_deleted(),
+                        index_new: status.is_index_new(),
+                        index_modified: status.is_index_modified() || status.is_index_typechange(),
+                        index_deleted: status.is_index_deleted(),
+                    },
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    fn log(&self, path: &Path, since: Option<i64>) -> Result<Vec<Commit>> {
+        let repo = Repository::open(path)?;
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let Some(head) = head.target() else {
+            return Ok(Vec::new());
+        };
+        // Abbreviate every hash to HEAD's length rather than disambiguating
+        // each one, which costs more than reading the commit.
+        let short_len = repo
+            .find_object(head, None)?
+            .short_id()?
+            .as_str()
+            .map_or(7, str::len);
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(git2::Sort::TIME)?;
+        walk.push(head)?;
+        let mut commits = Vec::new();
+        for id in walk {
+            let commit = repo.find_commit(id?)?;
+            if since.is_some_and(|since| commit.time().seconds() < since) {
+                break;
+            }
+            let author = commit.author();
+            let hash = commit.id().to_string();
+            commits.push(Commit {
+                short_hash: hash[..short_len].to_string(),
+                hash,
+                timestamp: author.when().seconds(),
+                author: String::from_utf8_lossy(author.name_bytes()).into_owned(),
+                email: String::from_utf8_lossy(author.email_bytes()).into_owned(),
+                subject: String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default())
+                    .into_owned(),
+                message: String::from_utf8_lossy(commit.message_bytes()).into_owned(),
+            });
+        }
+        Ok(commits)
+    }
+
+    fn branches(&self, path: &Path) -> Result<Branches> {
+        let repo = Repository::open(path)?;
+        let mut branches = Branches::default();
+        for branch in repo.branches(None)? {
+            let (branch, kind) = branch?;
+            if branch.get().symbolic_target_bytes().is_some() {
+                continue;
+            }
+            let Some(name) = branch.name()? else {
+                continue;
+            };
+            match kind {
+                git2::BranchType::Local => branches.local.push(name.to_string()),
+                git2::BranchType::Remote => branches.remote.push(name.to_string()),
+            }
+        }
+        branches.local.sort();
+        branches.remote.sort();
+        Ok(branches)
+    }
+}
+
+/// The backend for read-only queries: libgit2, or gitoxide when built with
+/// the `gix` feature and [`BACKEND_ENV`] is `gix`.
+pub fn git_backend() -> &'static dyn GitBackend {
+    #[cfg(feature = "gix")]
+    if std::env::var(BACKEND_ENV).as_deref() == Ok("gix") {
+        return &super::gix_backend::GixBackend;
+    }
+    &Libgit2Backend
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,4 +545,147 @@ mod tests {
             "Invalid --depth value 'abc': must be a positive integer"
         );
     }
+
+    fn backends() -> Vec<&'static dyn GitBackend> {
+        #[allow(unused_mut)]
+        let mut backends: Vec<&'static dyn GitBackend> = vec![&Libgit2Backend];
+        #[cfg(feature = "gix")]
+        backends.push(&super::super::gix_backend::GixBackend);
+        backends
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn backends_report_status_log_and_branches_alike() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir(&upstream).unwrap();
+        git(&upstream, &["init", "-q", "-b", "main"]);
+        git(&upstream, &["config", "user.email", "test@example.com"]);
+        git(&upstream, &["config", "user.name", "Test"]);
+        for file in ["kept.txt", "edited.txt", "removed.txt", "staged.txt"] {
+            std::fs::write(upstream.join(file), file).unwrap();
+        }
+        git(&upstream, &["add", "."]);
+        git(&upstream, &["commit", "-q", "-m", "Add files"]);
+        git(&upstream, &["branch", "feature"]);
+        let upstream_arg = upstream.to_str().unwrap();
+        git(tmp.path(), &["clone", "-q", upstream_arg, "repo"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        git(&repo, &["config", "user.name", "Test"]);
+        git(
+            &repo,
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "Second\n\nwith a body",
+            ],
+        );
+        git(&repo, &["branch", "topic"]);
+
+        std::fs::write(repo.join("edited.txt"), "changed").unwrap();
+        std::fs::remove_file(repo.join("removed.txt")).unwrap();
+        std::fs::write(repo.join("staged.txt"), "staged").unwrap();
+        git(&repo, &["add", "staged.txt"]);
+        std::fs::create_dir(repo.join("new")).unwrap();
+        std::fs::write(repo.join("new/file.txt"), "new").unwrap();
+        std::fs::write(repo.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(repo.join("debug.log"), "ignored").unwrap();
+        std::fs::create_dir(repo.join("nested")).unwrap();
+        git(&repo.join("nested"), &["init", "-q"]);
+        std::fs::write(repo.join("nested/inner.txt"), "inner").unwrap();
+
+        for backend in backends() {
+            let status: Vec<(String, FileStatus)> = backend
+                .status(&repo)
+                .unwrap()
+                .into_iter()
+                .map(|entry| (entry.path, entry.status))
+                .collect();
+            let expected = vec![
+                (
+                    ".gitignore".to_string(),
+                    FileStatus {
+                        wt_new: true,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "edited.txt".to_string(),
+                    FileStatus {
+                        wt_modified: true,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "nested/".to_string(),
+                    FileStatus {
+                        wt_new: true,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "new/file.txt".to_string(),
+                    FileStatus {
+                        wt_new: true,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "removed.txt".to_string(),
+                    FileStatus {
+                        wt_deleted: true,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "staged.txt".to_string(),
+                    FileStatus {
+                        index_modified: true,
+                        ..Default::default()
+                    },
+                ),
+            ];
+            assert_eq!(status, expected, "{} status", backend.name());
+
+            let log = backend.log(&repo, None).unwrap();
+            let subjects: Vec<&str> = log.iter().map(|c| c.subject.as_str()).collect();
+            assert_eq!(subjects, ["Second", "Add files"], "{} log", backend.name());
+            assert_eq!(log[0].message.trim_end(), "Second\n\nwith a body");
+            assert_eq!(log[0].author, "Test");
+            assert!(log[0].hash.starts_with(&log[0].short_hash));
+            assert!(backend.log(&repo, Some(i64::MAX)).unwrap().is_empty());
+
+            let branches = backend.branches(&repo).unwrap();
+            assert_eq!(
+                branches.local,
+                ["main", "topic"],
+                "{} branches",
+                backend.name()
+            );
+            assert_eq!(branches.remote, ["origin/feature", "origin/main"]);
+
+            assert!(backend.log(&upstream.join("missing"), None).is_err());
+        }
+
+        let unborn = tmp.path().join("unborn");
+        std::fs::create_dir(&unborn).unwrap();
+        git(&unborn, &["init", "-q"]);
+        for backend in backends() {
+            assert!(backend.log(&unborn, None).unwrap().is_empty());
+            assert!(backend.status(&unborn).unwrap().is_empty());
+        }
+    }
 }
@@ -0,0 +1,272 @@
+//! The metarepo-managed section of a workspace `.gitignore`.
+//!
+//! Project entries are written between a pair of marker comments so `meta`
+//! can add, remove, and rebuild them without touching anything the user wrote
+//! elsewhere in the file:
+//!
+//! ```text
+//! # --- managed by metarepo ---
+//! api
+//! web
+//! # --- end managed by metarepo ---
+//! ```
+//!
+//! Lines outside the block are never rewritten or reordered. Inside the block
+//! entries are kept sorted so `meta gitignore sync` and incremental edits
+//! converge on the same file.
+
+use anyhow::Result;
+use metarepo_core::MetaConfig;
+use std::path::Path;
+
+/// Opening marker of the managed block.
+pub const MANAGED_BEGIN: &str = "# --- managed by metarepo ---";
+/// Closing marker of the managed block.
+pub const MANAGED_END: &str = "# --- end managed by metarepo ---";
+
+/// A `.gitignore` split around its managed block.
+#[derive(Debug, Clone, PartialEq)]
+struct Sections<'a> {
+    before: Vec<&'a str>,
+    /// Entries inside the block; `None` when the file has no block yet.
+    managed: Option<Vec<&'a str>>,
+    after: Vec<&'a str>,
+}
+
+/// Split `content` into the lines before, inside, and after the managed block.
+/// An unterminated block (begin marker without an end) runs to end of file.
+fn split_sections(content: &str) -> Sections<'_> {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some(begin) = lines.iter().position(|l| l.trim() == MANAGED_BEGIN) else {
+        return Sections {
+            before: lines,
+            managed: None,
+            after: Vec::new(),
+        };
+    };
+    let end = lines[begin + 1..]
+        .iter()
+        .position(|l| l.trim() == MANAGED_END)
+        .map(|i| begin + 1 + i);
+
+    let inner_end = end.unwrap_or(lines.len());
+    let managed = lines[begin + 1..inner_end]
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let after = match end {
+        Some(e) => lines[e + 1..].to_vec(),
+        None => Vec::new(),
+    };
+
+    Sections {
+        before: lines[..begin].to_vec(),
+        managed: Some(managed),
+        after,
+    }
+}
+
+/// Render `content` with its managed block replaced by `entries` (sorted and
+/// deduplicated). Creates the block at the end of the file when absent.
+pub fn render_with_managed_entries(content: &str, entries: &[String]) -> String {
+    let sections = split_sections(content);
+
+    let mut sorted: Vec<&str> = entries.iter().map(|e| e.trim()).collect();
+    sorted.retain(|e| !e.is_empty());
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut out = String::new();
+    for line in &sections.before {
+        out.push_str(line);
+        out.push('\n');
+    }
+    // Separate a freshly appended block from existing user content.
+    if sections.managed.is_none() && sections.before.last().is_some_and(|l| !l.trim().is_empty()) {
+        out.push('\n');
+    }
+    out.push_str(MANAGED_BEGIN);
+    out.push('\n');
+    for entry in sorted {
+        out.push_str(entry);
+        out.push('\n');
+    }
+    out.push_str(MANAGED_END);
+    out.push('\n');
+    for line in &sections.after {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Entries currently listed inside the managed block (empty when absent).
+pub fn managed_entries(content: &str) -> Vec<String> {
+    split_sections(content)
+        .managed
+        .unwrap_or_default()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether `content` already contains a managed block.
+pub fn has_managed_block(content: &str) -> bool {
+    split_sections(content).managed.is_some()
+}
+
+/// The entries the managed block should hold for `config`: every project
+/// backed by a real remote (URL not `local:`). Local and vendored projects live
+/// in-tree and are committed with the workspace, so they are never ignored.
+pub fn expected_entries(config: &MetaConfig) -> Vec<String> {
+    let mut entries: Vec<String> = config
+        .projects
+        .keys()
+        .filter(|name| {
+            let url = config.get_project_url(name).unwrap_or_default();
+            !url.is_empty() && !url.starts_with("local:") && !config.is_vendored(name)
+        })
+        .cloned()
+        .collect();
+    entries.sort();
+    entries
+}
+
+fn read_gitignore(base_path: &Path) -> Result<String> {
+    let path = base_path.join(".gitignore");
+    Ok(if path.exists() {
+        std::fs::read_to_string(path)?
+    } else {
+        String::new()
+    })
+}
+
+/// Write `new` over the workspace `.gitignore` when it differs from `old`.
+/// Returns true if the file changed.
+fn write_if_changed(base_path: &Path, old: &str, new: &str) -> Result<bool> {
+    if old == new {
+        return Ok(false);
+    }
+    std::fs::write(base_path.join(".gitignore"), new)?;
+    Ok(true)
+}
+
+/// Ensure the managed block exists, creating an empty one if needed. Returns
+/// true if the file changed.
+pub fn ensure_managed_block(base_path: &Path) -> Result<bool> {
+    let content = read_gitignore(base_path)?;
+    if has_managed_block(&content) {
+        return Ok(false);
+    }
+    let updated = render_with_managed_entries(&content, &[]);
+    write_if_changed(base_path, &content, &updated)
+}
+
+/// Add `entry` to the managed block. A line already present anywhere in the
+/// file (including a hand-written one outside the block) is left alone, since
+/// the path is ignored either way. Returns true if the file changed.
+pub fn add_managed_entry(base_path: &Path, entry: &str) -> Result<bool> {
+    let content = read_gitignore(base_path)?;
+    if content.lines().any(|line| line.trim() == entry) {
+        return Ok(false);
+    }
+    let mut entries = managed_entries(&content);
+    entries.push(entry.to_string());
+    let updated = render_with_managed_entries(&content, &entries);
+    write_if_changed(base_path, &content, &updated)
+}
+
+/// Remove `entry` from the managed block. Matching lines outside the block
+/// belong to the user and are kept. Returns true if the file changed.
+pub fn remove_managed_entry(base_path: &Path, entry: &str) -> Result<bool> {
+    let content = read_gitignore(base_path)?;
+    let entries = managed_entries(&content);
+    if !entries.iter().any(|e| e == entry) {
+        return Ok(false);
+    }
+    let remaining: Vec<String> = entries.into_iter().filter(|e| e != entry).collect();
+    let updated = render_with_managed_entries(&content, &remaining);
+    write_if_changed(base_path, &content, &updated)
+}
+
+/// Replace the managed block with exactly `entries`. Returns true if the file
+/// changed.
+pub fn sync_managed_entries(base_path: &Path, entries: &[String]) -> Result<bool> {
+    let content = read_gitignore(base_path)?;
+    let updated = render_with_managed_entries(&content, entries);
+    write_if_changed(base_path, &content, &updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ProjectEntry;
+    use tempfile::tempdir;
+
+    fn owned(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn render_appends_block_after_user_content() {
+        let out = render_with_managed_entries("*.tmp\n", &owned(&["web", "api"]));
+        assert_eq!(
+            out,
+            format!("*.tmp\n\n{MANAGED_BEGIN}\napi\nweb\n{MANAGED_END}\n")
+        );
+    }
+
+    #[test]
+    fn render_replaces_block_in_place_and_keeps_surrounding_lines() {
+        let content = format!("first\n{MANAGED_BEGIN}\nold\n{MANAGED_END}\nlast\n");
+        let out = render_with_managed_entries(&content, &owned(&["new"]));
+        assert_eq!(
+            out,
+            format!("first\n{MANAGED_BEGIN}\nnew\n{MANAGED_END}\nlast\n")
+        );
+    }
+
+    #[test]
+    fn unterminated_block_runs_to_end_of_file() {
+        let content = format!("keep\n{MANAGED_BEGIN}\na\nb\n");
+        assert_eq!(managed_entries(&content), owned(&["a", "b"]));
+        let out = render_with_managed_entries(&content, &owned(&["a"]));
+        assert_eq!(out, format!("keep\n{MANAGED_BEGIN}\na\n{MANAGED_END}\n"));
+    }
+
+    #[test]
+    fn local_and_vendored_projects_are_not_expected() {
+        let mut config = MetaConfig::default();
+        for (name, url) in [("api", "git@h:api.git"), ("lib", "git@h:lib.git")] {
+            config
+                .projects
+                .insert(name.to_string(), ProjectEntry::Url(url.to_string()));
+        }
+        config
+            .projects
+            .insert("notes".to_string(), ProjectEntry::Url("local:".to_string()));
+        config.project_metadata_mut("lib").unwrap().vendored = Some("abc123".to_string());
+        assert_eq!(expected_entries(&config), owned(&["api"]));
+    }
+
+    #[test]
+    fn add_and_remove_only_touch_the_block() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::write(root.join(".gitignore"), "zeta\nweb-notes\nalpha\n").unwrap();
+
+        assert!(add_managed_entry(root, "web").unwrap());
+        assert!(!add_managed_entry(root, "web").unwrap());
+        // Already ignored by a user line: nothing to add.
+        assert!(!add_managed_entry(root, "alpha").unwrap());
+
+        assert!(remove_managed_entry(root, "web").unwrap());
+        // User lines are never removed, even when they match.
+        assert!(!remove_managed_entry(root, "alpha").unwrap());
+
+        let content = std::fs::read_to_string(root.join(".gitignore")).unwrap();
+        assert!(content.starts_with("zeta\nweb-notes\nalpha\n"));
+        assert!(managed_entries(&content).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! The gitoxide backend for read-only git queries, built with the `gix`
+//! feature. It answers the same questions as
+//! [`Libgit2Backend`](super::git_operations::Libgit2Backend) in the same
+//! shape; see [`GitBackend`] for the contract.
+
+use anyhow::Result;
+use gix::bstr::ByteSlice;
+use gix::revision::walk::Sorting;
+use gix::status::plumbing::index_as_worktree_with_renames::Summary;
+use gix::traverse::commit::simple::CommitTimeOrder;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::git_operations::{Branches, Commit, FileStatus, GitBackend, StatusEntry};
+
+pub struct GixBackend;
+
+fn open(path: &Path) -> Result<gix::Repository> {
+    Ok(gix::open(path)?)
+}
+
+impl GitBackend for GixBackend {
+    fn name(&self) -> &'static str {
+        "gix"
+    }
+
+    fn status(&self, path: &Path) -> Result<Vec<StatusEntry>> {
+        let repo = open(path)?;
+        let items = repo
+            .status(gix::progress::Discard)?
+            .untracked_files(gix::status::UntrackedFiles::Files)
+            .index_worktree_rewrites(None)
+            .tree_index_track_renames(gix::status::tree_index::TrackRenames::Disabled)
+            .into_iter(None)?;
+        // One path can show up twice, changed in the index and again in the
+        // worktree, and items arrive in no particular order.
+        let mut paths: BTreeMap<String, FileStatus> = BTreeMap::new();
+        for item in items {
+            match item? {
+                gix::status::Item::IndexWorktree(item) => {
+                    let Some(summary) = item.summary() else {
+                        continue;
+                    };
+                    let mut path = item.rela_path().to_string();
+                    // Untracked directories, such as nested repositories,
+                    // end in a slash as they do for libgit2 and git.
+                    if let gix::status::index_worktree::Item::DirectoryContents { entry, .. } =
+                        &item
+                    {
+                        if entry.disk_kind.is_some_and(|kind| kind.is_dir()) {
+                            path.push('/');
+                        }
+                    }
+                    let status = paths.entry(path).or_default();
+                    match summary {
+                        Summary::Added => status.wt_new = true,
+                        Summary::Removed => status.wt_deleted = true,
+                        Summary::Modified | Summary::TypeChange => status.wt_modified = true,
+                        // Untracked rewrites are off; conflicts and
+                        // intent-to-add entries show as modified.
+                        Summary::Renamed
+                        | Summary::Copied
+                        | Summary::IntentToAdd
+                        | Summary::Conflict => status.wt_modified = true,
+                    }
+                }
+                gix::status::Item::TreeIndex(change) => {
+                    use gix::diff::index::ChangeRef;
+                    let status = paths.entry(change.location().to_string()).or_default();
+                    match change {
+                        ChangeRef::Addition { .. } => status.index_new = true,
+                        ChangeRef::Deletion { .. } => status.index_deleted = true,
+                        ChangeRef::Modification { .. } | ChangeRef::Rewrite { .. } => {
+                            status.index_modified = true
+                        }
+                    }
+                }
+            }
+        }
+        Ok(paths
+            .into_iter()
+            .map(|(path, status)| StatusEntry { path, status })
+            .collect())
+    }
+
+    fn log(&self, path: &Path, since: Option<i64>) -> Result<Vec<Commit>> {
+        let repo = open(path)?;
+        let head = match repo.head_id() {
+            Ok(head) => head,
+            Err(_) if repo.head()?.is_unborn() => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let order = CommitTimeOrder::NewestFirst;
+        let sorting = match since {
+            Some(seconds) => Sorting::ByCommitTimeCutoff { order, seconds },
+            None => Sorting::ByCommitTime(order),
+        };
+        // Abbreviate every hash to HEAD's length rather than disambiguating
+        // each one, which costs more than reading the commit.
+        let short_len = head.shorten_or_id().hex_len();
+        let mut commits = Vec::new();
+        for info in repo.rev_walk([head]).sorting(sorting).all()? {
+            let info = info?;
+            let commit = info.object()?;
+            let author = commit.author()?;
+            let message = commit.message_raw_sloppy();
+            commits.push(Commit {
+                hash: info.id.to_string(),
+                short_hash: info.id.to_hex_with_len(short_len).to_string(),
+                timestamp: author.time()?.seconds,
+                author: author.name.to_str_lossy().into_owned(),
+                email: author.email.to_str_lossy().into_owned(),
+                subject: commit.message()?.summary().to_str_lossy().into_owned(),
+                message: message.to_str_lossy().into_owned(),
+            });
+        }
+        Ok(commits)
+    }
+
+    fn branches(&self, path: &Path) -> Result<Branches> {
+        let repo = open(path)?;
+        let references = repo.references()?;
+        let mut branches = Branches::default();
+        for (list, iter) in [
+            (&mut branches.local, references.local_branches()?),
+            (&mut branches.remote, references.remote_branches()?),
+        ] {
+            for reference in iter {
+                let reference = reference.map_err(|e| anyhow::anyhow!("{}", e))?;
+                if matches!(reference.target(), gix::refs::TargetRef::Symbolic(_)) {
+                    continue;
+                }
+                list.push(reference.name().shorten().to_string());
+            }
+            list.sort();
+        }
+        Ok(branches)
+    }
+}
@@ -0,0 +1,262 @@
+//! Issue references in branch names and commit subjects, from the
+//! `issue_trackers` list in `.meta`: found for JSON output and shown as
+//! terminal hyperlinks.
+
+use anyhow::{anyhow, Result};
+use metarepo_core::{canonical_repo_url, hyperlink, IssueTracker, MetaConfig};
+use regex::Regex;
+use serde::Serialize;
+
+/// An issue reference found in some text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IssueRef {
+    /// The matched text, e.g. `ENG-42` or `#17`.
+    pub id: String,
+    pub url: String,
+}
+
+/// A JSON entry with the issue references found in it, serialized as the
+/// entry's own fields plus an `issues` array.
+#[derive(Debug, Serialize)]
+pub struct WithIssues<'a, T> {
+    #[serde(flatten)]
+    pub entry: &'a T,
+    pub issues: Vec<IssueRef>,
+}
+
+/// The workspace's issue trackers, compiled.
+#[derive(Debug, Default)]
+pub struct IssueLinker {
+    trackers: Vec<(Regex, String)>,
+}
+
+impl IssueLinker {
+    /// Compile `trackers`, failing on the first invalid pattern.
+    pub fn new(trackers: &[IssueTracker]) -> Result<Self> {
+        let trackers = trackers
+            .iter()
+            .map(|tracker| {
+                Regex::new(&tracker.pattern)
+                    .map(|pattern| (pattern, tracker.url.clone()))
+                    .map_err(|e| {
+                        anyhow!(
+                            "Invalid issue_trackers pattern '{}': {}",
+                            tracker.pattern,
+                            e
+                        )
+                    })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { trackers })
+    }
+
+    /// The linker for `config`. An invalid pattern is reported once and
+    /// links nothing, so a typo never breaks the command showing the links.
+    pub fn from_config(config: &MetaConfig) -> Self {
+        Self::new(&config.issue_trackers).unwrap_or_else(|e| {
+            eprintln!("Warning: {}", e);
+            Self::default()
+        })
+    }
+
+    /// References in `text`, in order of appearance. `project` fills the
+    /// `{repo}` placeholder; templates that need it are skipped for projects
+    /// without a remote.
+    pub fn references(&self, config: &MetaConfig, project: &str, text: &str) -> Vec<IssueRef> {
+        self.spans(config, project, text)
+            .into_iter()
+            .map(|(start, end, url)| IssueRef {
+                id: text[start..end].to_string(),
+                url,
+            })
+            .collect()
+    }
+
+    /// References across `texts` (say a branch name and a subject), each id
+    /// once, in order of appearance.
+    pub fn references_in(
+        &self,
+        config: &MetaConfig,
+        project: &str,
+        texts: &[&str],
+    ) -> Vec<IssueRef> {
+        let mut found: Vec<IssueRef> = Vec::new();
+        for text in texts {
+            for reference in self.references(config, project, text) {
+                if !found.iter().any(|f| f.id == reference.id) {
+                    found.push(reference);
+                }
+            }
+        }
+        found
+    }
+
+    /// `text` with each reference wrapped in a hyperlink, when the terminal
+    /// output is styled; otherwise `text` unchanged.
+    pub fn linkify(&self, config: &MetaConfig, project: &str, text: &str) -> String {
+        if !hyperlinks_enabled() {
+            return text.to_string();
+        }
+        self.linkify_always(config, project, text)
+    }
+
+    fn linkify_always(&self, config: &MetaConfig, project: &str, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end, url) in self.spans(config, project, text) {
+            out.push_str(&text[last..start]);
+            out.push_str(&hyperlink(&text[start..end], &url));
+            last = end;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+
+    /// Non-overlapping `(start, end, url)` matches across every tracker; on
+    /// overlap the earlier match wins, then the earlier tracker.
+    fn spans(&self, config: &MetaConfig, project: &str, text: &str) -> Vec<(usize, usize, String)> {
+        if self.trackers.is_empty() {
+            return Vec::new();
+        }
+        let repo = config
+            .get_project_url(project)
+            .and_then(|url| canonical_repo_url(&url))
+            // Plain filesystem paths have no owner/name.
+            .filter(|remote| !remote.starts_with('/'))
+            .and_then(|remote| remote.split_once('/').map(|(_, path)| path.to_string()));
+        let mut spans = Vec::new();
+        for (pattern, template) in &self.trackers {
+            if template.contains("{repo}") && repo.is_none() {
+                continue;
+            }
+            for captures in pattern.captures_iter(text) {
+                let whole = captures.get(0).expect("group 0 always matches");
+                if whole.is_empty() {
+                    continue;
+                }
+                let id = captures.get(1).unwrap_or(whole).as_str();
+                let mut url = template.replace("{id}", id);
+                if let Some(repo) = &repo {
+                    url = url.replace("{repo}", repo);
+                }
+                spans.push((whole.start(), whole.end(), url));
+            }
+        }
+        spans.sort_by_key(|&(start, end, _)| (start, std::cmp::Reverse(end)));
+        let mut kept: Vec<(usize, usize, String)> = Vec::new();
+        for span in spans {
+            if kept.last().is_none_or(|last| span.0 >= last.1) {
+                kept.push(span);
+            }
+        }
+        kept
+    }
+}
+
+/// Whether to emit hyperlinks: only when output is styled (a terminal, not
+/// disabled by `NO_COLOR` or the `color` setting) and the terminal is not
+/// `dumb`.
+fn hyperlinks_enabled() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+        && std::env::var("TERM").as_deref() != Ok("dumb")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::{strip_hyperlinks, ProjectEntry};
+
+    fn workspace() -> MetaConfig {
+        let mut config = MetaConfig::default();
+        config.projects.insert(
+            "web".to_string(),
+            ProjectEntry::Url("git@github.com:acme/web.git".to_string()),
+        );
+        config.projects.insert(
+            "scratch".to_string(),
+            ProjectEntry::Url("local:scratch".to_string()),
+        );
+        config
+    }
+
+    fn linker() -> IssueLinker {
+        IssueLinker::new(&[
+            IssueTracker {
+                pattern: r"[A-Z][A-Z0-9]+-\d+".to_string(),
+                url: "https://acme.atlassian.net/browse/{id}".to_string(),
+            },
+            IssueTracker {
+                pattern: r"#(\d+)".to_string(),
+                url: "https://github.com/{repo}/issues/{id}".to_string(),
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_references_from_every_tracker() {
+        let config = workspace();
+        let refs = linker().references(&config, "web", "fix/ENG-42-and-#17");
+        assert_eq!(
+            refs,
+            [
+                IssueRef {
+                    id: "ENG-42".to_string(),
+                    url: "https://acme.atlassian.net/browse/ENG-42".to_string(),
+                },
+                IssueRef {
+                    id: "#17".to_string(),
+                    url: "https://github.com/acme/web/issues/17".to_string(),
+                },
+            ]
+        );
+        // No remote, so no {repo}: only the Jira key links.
+        let refs = linker().references(&config, "scratch", "fix/ENG-42-and-#17");
+        assert_eq!(refs.len(), 1);
+        assert!(linker().references(&config, "web", "main").is_empty());
+    }
+
+    #[test]
+    fn entries_serialize_with_their_references() {
+        let config = workspace();
+        let entry = serde_json::json!({ "project": "web", "subject": "Fix ENG-7" });
+        let issues = linker().references_in(&config, "web", &["ENG-7-login", "Fix ENG-7 (#3)"]);
+        let value = serde_json::to_value(WithIssues {
+            entry: &entry,
+            issues,
+        })
+        .unwrap();
+        assert_eq!(value["subject"], "Fix ENG-7");
+        let ids: Vec<&str> = value["issues"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|issue| issue["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["ENG-7", "#3"]);
+    }
+
+    #[test]
+    fn linkify_wraps_each_reference() {
+        let config = workspace();
+        let linked = linker().linkify_always(&config, "web", "feat/ENG-7-login");
+        assert_eq!(
+            linked,
+            format!(
+                "feat/{}-login",
+                hyperlink("ENG-7", "https://acme.atlassian.net/browse/ENG-7")
+            )
+        );
+        assert_eq!(strip_hyperlinks(&linked), "feat/ENG-7-login");
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        let err = IssueLinker::new(&[IssueTracker {
+            pattern: "ENG-(".to_string(),
+            url: "https://example.com/{id}".to_string(),
+        }])
+        .unwrap_err();
+        assert!(err.to_string().contains("Invalid issue_trackers pattern"));
+    }
+}
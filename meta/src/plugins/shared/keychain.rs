@@ -0,0 +1,158 @@
+//! Provider tokens kept in the OS keychain.
+//!
+//! `meta auth login <provider>` stores a token under the `metarepo` service
+//! of the platform's credential store (the macOS Keychain, the Windows
+//! Credential Manager, or the Secret Service on Linux), with the provider's
+//! host as the account. Consumers look there after the sources they already
+//! read, so a token in the environment or the config still wins:
+//!
+//! - HTTPS clones and fetches, for the host of the remote;
+//! - GitHub API lookups (`meta project sizes --remote`, `meta project add
+//!   --plan`, `meta feed --remote`);
+//! - `meta skill`, for the skills.sh API key.
+//!
+//! Setting `METAREPO_NO_KEYCHAIN` turns lookups off, for CI and for desktops
+//! where a locked keychain would prompt in the middle of a clone.
+
+use anyhow::Result;
+use keyring::Entry;
+
+/// Service name every entry is stored under.
+pub const SERVICE: &str = "metarepo";
+
+/// Set to skip keychain lookups entirely.
+pub const DISABLE_ENV: &str = "METAREPO_NO_KEYCHAIN";
+
+/// Something a token is stored for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provider {
+    /// Name given on the command line.
+    pub name: String,
+    /// Keychain account, which is also the host the token is sent to.
+    pub account: String,
+    /// Environment variables that supply the same token, in order.
+    pub env: &'static [&'static str],
+    /// Whether git remotes on `account` authenticate with the token.
+    pub git_host: bool,
+}
+
+/// Providers known by name: `(name, account, env, git_host)`.
+const KNOWN: [(&str, &str, &[&str], bool); 3] = [
+    ("github", "github.com", &["GH_TOKEN", "GITHUB_TOKEN"], true),
+    ("gitlab", "gitlab.com", &["GITLAB_TOKEN"], true),
+    ("skills", "skills.sh", &["SKILLS_SH_API_KEY"], false),
+];
+
+/// The providers known by name.
+pub fn known() -> Vec<Provider> {
+    KNOWN
+        .iter()
+        .map(|&(name, account, env, git_host)| Provider {
+            name: name.to_string(),
+            account: account.to_string(),
+            env,
+            git_host,
+        })
+        .collect()
+}
+
+/// The provider called `name`: a known provider by name or host, else any
+/// other host name, taken as a git host.
+pub fn provider(name: &str) -> Result<Provider> {
+    let name = name.trim().to_ascii_lowercase();
+    if let Some(found) = known()
+        .into_iter()
+        .find(|p| p.name == name || p.account == name)
+    {
+        return Ok(found);
+    }
+    let is_host = name.contains('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    if !is_host {
+        return Err(anyhow::anyhow!(
+            "Unknown provider '{}': use github, gitlab, skills, or a git host name",
+            name
+        ));
+    }
+    Ok(Provider {
+        name: name.clone(),
+        account: name,
+        env: &[],
+        git_host: true,
+    })
+}
+
+/// Whether keychain lookups are turned off with [`DISABLE_ENV`].
+pub fn disabled() -> bool {
+    std::env::var_os(DISABLE_ENV).is_some_and(|v| !v.is_empty())
+}
+
+/// `e` as one line: what failed and the innermost cause. Platform errors
+/// nest the same message several times over.
+fn failure(action: &str, e: keyring::Error) -> anyhow::Error {
+    let mut cause: &dyn std::error::Error = &e;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    anyhow::anyhow!("{}: {}", action, cause)
+}
+
+fn entry(account: &str) -> Result<Entry> {
+    Entry::new(SERVICE, account).map_err(|e| failure("Cannot open the OS keychain", e))
+}
+
+/// The token stored for `account`, or why the keychain could not be read.
+pub fn lookup(account: &str) -> Result<Option<String>> {
+    match entry(account)?.get_password() {
+        Ok(token) => Ok(Some(token).filter(|t| !t.is_empty())),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(failure("Cannot read the OS keychain", e)),
+    }
+}
+
+/// The token stored for `account`, if the keychain is on and has one.
+pub fn get(account: &str) -> Option<String> {
+    if disabled() {
+        return None;
+    }
+    lookup(account).ok().flatten()
+}
+
+/// Store `token` for `account`, replacing any stored before.
+pub fn set(account: &str, token: &str) -> Result<()> {
+    entry(account)?
+        .set_password(token)
+        .map_err(|e| failure("Cannot write to the OS keychain", e))
+}
+
+/// Remove the token for `account`. `false` when there was none.
+pub fn delete(account: &str) -> Result<bool> {
+    match entry(account)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(failure("Cannot remove the token from the OS keychain", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn providers_resolve_by_name_or_host() {
+        let github = provider("github").unwrap();
+        assert_eq!(github.account, "github.com");
+        assert!(github.git_host);
+        assert_eq!(provider("GitHub.com").unwrap(), github);
+        assert!(!provider("skills").unwrap().git_host);
+
+        let custom = provider("git.example.com").unwrap();
+        assert_eq!(custom.account, "git.example.com");
+        assert!(custom.env.is_empty());
+
+        assert!(provider("bitbucket").is_err());
+        assert!(provider("https://git.example.com").is_err());
+    }
+}
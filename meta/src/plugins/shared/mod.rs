@@ -1,8 +1,32 @@
+pub mod branch_policy;
+pub mod confirm;
+pub mod credentials;
 pub mod git_operations;
+pub mod gitignore;
+#[cfg(feature = "gix")]
+pub mod gix_backend;
+pub mod issues;
+pub mod keychain;
+pub mod multiplexer;
+pub mod notify;
+pub mod outcome;
 pub mod output_manager;
+pub mod parallel;
+pub mod pin;
+pub mod secrets;
+pub mod selection;
+pub mod state;
+pub mod submodules;
+pub mod throttle;
+pub mod transfer;
+pub mod vcs;
 
 pub use git_operations::{
-    clone_with_auth, create_default_worktree, detect_default_branch, parse_depth_arg,
+    add_default_worktree, auth_callbacks, clone_with_auth, create_default_worktree,
+    detect_default_branch, display_name, fetch_origin, git, git_backend, parse_depth_arg,
     refetch_shallow,
 };
+pub use multiplexer::{LogMultiplexer, MuxOptions, Stream};
+pub use outcome::{LastRun, RunOutcome};
 pub use output_manager::{JobStatus, OutputManager, ProgressIndicator, ProjectOutput};
+pub use parallel::{jobs_from, parallel_map};
@@ -0,0 +1,509 @@
+//! Live, interleaved output from several projects at once.
+//!
+//! [`LogMultiplexer`] is the one place that formats concurrent output: each
+//! line gets a project prefix (one color per project, padded to a common
+//! width), optionally a timestamp, and can be filtered to a subset of projects
+//! with `--only`. With `--prefix`, each project's output ends with a line
+//! giving its exit status. Every line, shown or not, can also be written uncolored to a
+//! merged log file. `meta exec`, `meta run`, and `meta services` share it.
+//!
+//! With `--pty` each child runs on its own pseudo-terminal, so tools that turn
+//! off color and progress output when writing to a pipe keep them. The
+//! terminal comes from `portable_pty` (a Unix pty, or ConPTY on Windows); its
+//! output (stdout and stderr merged, as on any terminal) still comes through
+//! the multiplexer line by line.
+
+use super::parallel_map;
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use colored::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const PALETTE: [Color; 6] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Blue,
+    Color::BrightRed,
+];
+
+/// Command-line options controlling multiplexed output (`--prefix`,
+/// `--only`, `--timestamps`, `--log-file`, `--pty`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MuxOptions {
+    /// Prefixed output asked for by name; also ends each project's output
+    /// with a line giving its exit status.
+    pub prefix: bool,
+    /// Projects whose output is shown; empty shows every project.
+    pub only: Vec<String>,
+    /// Prefix each line with the time it was received (UTC).
+    pub timestamps: bool,
+    /// Also write every line, uncolored and timestamped, to this file.
+    pub log_file: Option<PathBuf>,
+    /// Run each child on a pseudo-terminal.
+    pub pty: bool,
+}
+
+impl MuxOptions {
+    /// Read the options from `matches`; arguments a command does not define
+    /// are treated as unset. `--only` takes comma-separated project names.
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        let only = match matches.try_get_many::<String>("only") {
+            Ok(Some(values)) => values
+                .flat_map(|v| v.split(','))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        };
+        let prefix = matches
+            .try_get_one::<bool>("prefix")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false);
+        let timestamps = matches
+            .try_get_one::<bool>("timestamps")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false);
+        let log_file = matches
+            .try_get_one::<String>("log-file")
+            .ok()
+            .flatten()
+            .map(PathBuf::from);
+        let pty = matches
+            .try_get_one::<bool>("pty")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false);
+        Self {
+            prefix,
+            only,
+            timestamps,
+            log_file,
+            pty,
+        }
+    }
+
+    /// Whether any option was given, which asks for multiplexed output.
+    pub fn is_active(&self) -> bool {
+        self.prefix
+            || !self.only.is_empty()
+            || self.timestamps
+            || self.log_file.is_some()
+            || self.pty
+    }
+}
+
+/// Which stream a line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Formats and routes lines from several projects (see the module docs).
+pub struct LogMultiplexer {
+    prefixes: HashMap<String, String>,
+    width: usize,
+    only: HashSet<String>,
+    timestamps: bool,
+    pty: bool,
+    exit_lines: bool,
+    /// Serializes terminal and log writes so lines never interleave mid-line.
+    log: Mutex<Option<File>>,
+}
+
+impl LogMultiplexer {
+    /// A multiplexer for `projects`, which fixes their prefix colors and width.
+    pub fn new(projects: &[String], options: &MuxOptions) -> Result<Self> {
+        let width = projects.iter().map(String::len).max().unwrap_or(0);
+        let prefixes = projects
+            .iter()
+            .enumerate()
+            .map(|(i, project)| {
+                let prefix = format!("{:<width$} │", project, width = width)
+                    .color(PALETTE[i % PALETTE.len()])
+                    .bold()
+                    .to_string();
+                (project.clone(), prefix)
+            })
+            .collect();
+        let log = match &options.log_file {
+            Some(path) => Some(
+                File::create(path)
+                    .with_context(|| format!("Failed to create log file {}", path.display()))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            prefixes,
+            width,
+            only: options.only.iter().cloned().collect(),
+            timestamps: options.timestamps,
+            pty: options.pty,
+            exit_lines: options.prefix,
+            log: Mutex::new(log),
+        })
+    }
+
+    /// Whether `project`'s output is shown on the terminal.
+    pub fn shows(&self, project: &str) -> bool {
+        self.only.is_empty() || self.only.contains(project)
+    }
+
+    /// The terminal form of a line: `[time] project │ text`.
+    fn terminal_line(&self, project: &str, clock: &str, text: &str) -> String {
+        let prefix = self
+            .prefixes
+            .get(project)
+            .cloned()
+            .unwrap_or_else(|| format!("{:<width$} │", project, width = self.width));
+        if self.timestamps {
+            format!("{} {} {}", clock.bright_black(), prefix, text)
+        } else {
+            format!("{} {}", prefix, text)
+        }
+    }
+
+    /// The log-file form of a line: always timestamped, never colored.
+    fn log_line(&self, project: &str, clock: &str, stream: Stream, text: &str) -> String {
+        let marker = match stream {
+            Stream::Stdout => "",
+            Stream::Stderr => " (stderr)",
+        };
+        format!(
+            "{} {:<width$}{} │ {}",
+            clock,
+            project,
+            marker,
+            text,
+            width = self.width
+        )
+    }
+
+    /// Emit one line of output from `project`.
+    pub fn line(&self, project: &str, stream: Stream, text: &str) {
+        let clock = clock(std::time::SystemTime::now());
+        let mut log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        if self.shows(project) {
+            let line = self.terminal_line(project, &clock, text);
+            match stream {
+                Stream::Stdout => println!("{}", line),
+                Stream::Stderr => eprintln!("{}", line),
+            }
+        }
+        if let Some(file) = log.as_mut() {
+            let _ = writeln!(file, "{}", self.log_line(project, &clock, stream, text));
+        }
+    }
+
+    /// Emit a chunk of output from `project`, one line at a time.
+    pub fn write(&self, project: &str, stream: Stream, chunk: &[u8]) {
+        for text in String::from_utf8_lossy(chunk).lines() {
+            self.line(project, stream, text);
+        }
+    }
+
+    /// Forward everything read from `reader` as lines from `project`, on a
+    /// background thread that ends when the stream closes.
+    pub fn pump(
+        self: &Arc<Self>,
+        project: &str,
+        stream: Stream,
+        reader: impl Read + Send + 'static,
+    ) -> JoinHandle<()> {
+        let mux = Arc::clone(self);
+        let project = project.to_string();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            let mut buf = Vec::new();
+            while reader.read_until(b'\n', &mut buf).unwrap_or(0) > 0 {
+                let text = String::from_utf8_lossy(&buf);
+                mux.line(&project, stream, text.trim_end_matches(['\n', '\r']));
+                buf.clear();
+            }
+        })
+    }
+
+    /// Run `cmd` on a new pseudo-terminal, forwarding the terminal's output
+    /// as `project`'s stdout. The environment and working directory carry
+    /// over; the terminal's input is closed, as stdin is for piped children.
+    fn run_on_pty(self: &Arc<Self>, project: &str, cmd: &Command) -> Result<i32> {
+        let (cols, rows) = crossterm::terminal::size().unwrap_or((120, 24));
+        let pair = portable_pty::native_pty_system()
+            .openpty(portable_pty::PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .context("Failed to open a pseudo-terminal")?;
+        let mut builder = portable_pty::CommandBuilder::new(cmd.get_program());
+        builder.args(cmd.get_args());
+        for (key, value) in cmd.get_envs() {
+            match value {
+                Some(value) => builder.env(key, value),
+                None => builder.env_remove(key),
+            }
+        }
+        // Without a directory the builder would start in $HOME.
+        match cmd.get_current_dir() {
+            Some(dir) => builder.cwd(dir),
+            None => builder.cwd(std::env::current_dir()?),
+        }
+        let mut child = pair
+            .slave
+            .spawn_command(builder)
+            .with_context(|| format!("Failed to start command in {}", project))?;
+        // Only the child holds the terminal now, so reads end when it exits.
+        drop(pair.slave);
+        let reader = pair.master.try_clone_reader()?;
+        let pump = self.pump(project, Stream::Stdout, reader);
+        // Dropping the writer sends end-of-file to the child's input.
+        drop(pair.master.take_writer()?);
+        let status = child.wait()?;
+        drop(pair.master);
+        let _ = pump.join();
+        Ok(match status.signal() {
+            Some(_) => -1,
+            None => status.exit_code() as i32,
+        })
+    }
+
+    /// Run each `(project, command)` job with its output multiplexed, up to
+    /// `workers` at a time (1 runs them one after another). Returns each
+    /// project's exit code (-1 when it was killed by a signal) or the error
+    /// that kept it from starting, with when it started and how long it ran,
+    /// in job order. With `--pty`, each command runs on a pseudo-terminal.
+    pub fn run_all(
+        self: &Arc<Self>,
+        jobs: Vec<(String, Command)>,
+        workers: usize,
+    ) -> Vec<(String, Result<i32>, Instant, Duration)> {
+        let run = |project: String, cmd: Command| -> (String, Result<i32>, Instant, Duration) {
+            let started = Instant::now();
+            let result = if self.pty {
+                self.run_on_pty(&project, &cmd)
+            } else {
+                let mut cmd = cmd;
+                cmd.stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .with_context(|| format!("Failed to start command in {}", project))
+                    .and_then(|mut child| {
+                        let pumps: Vec<_> = [
+                            child
+                                .stdout
+                                .take()
+                                .map(|out| self.pump(&project, Stream::Stdout, out)),
+                            child
+                                .stderr
+                                .take()
+                                .map(|err| self.pump(&project, Stream::Stderr, err)),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                        let status = child.wait()?;
+                        for pump in pumps {
+                            let _ = pump.join();
+                        }
+                        Ok(status.code().unwrap_or(-1))
+                    })
+            };
+            let duration = started.elapsed();
+            if self.exit_lines {
+                self.line(&project, Stream::Stdout, &exit_line(&result, duration));
+            }
+            (project, result, started, duration)
+        };
+
+        parallel_map(jobs, workers, |(project, cmd)| run(project, cmd))
+    }
+}
+
+/// The line that closes a project's prefixed output: how it exited and
+/// after how long.
+fn exit_line(result: &Result<i32>, duration: Duration) -> String {
+    let took = format!("after {:.1}s", duration.as_secs_f64());
+    match result {
+        Ok(0) => format!("{} exited 0 {}", "✓".green(), took.bright_black()),
+        Ok(code) => format!("{} exited {} {}", "✗".red(), code, took.bright_black()),
+        Err(e) => format!("{} did not start: {}", "✗".red(), e),
+    }
+}
+
+/// Format `time` as `HH:MM:SS` (UTC).
+pub fn clock(time: std::time::SystemTime) -> String {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+        % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn mux(options: &MuxOptions) -> LogMultiplexer {
+        LogMultiplexer::new(&["api".to_string(), "web-app".to_string()], options).unwrap()
+    }
+
+    /// `s` without ANSI color codes.
+    fn plain(s: &str) -> String {
+        regex::Regex::new("\x1b\\[[0-9;]*m")
+            .unwrap()
+            .replace_all(s, "")
+            .into_owned()
+    }
+
+    #[test]
+    fn prefixes_are_padded_to_a_common_width() {
+        let m = mux(&MuxOptions::default());
+        assert_eq!(
+            plain(&m.terminal_line("api", "12:00:00", "ready")),
+            "api     │ ready"
+        );
+        let m = mux(&MuxOptions {
+            timestamps: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            plain(&m.terminal_line("web-app", "12:00:00", "ready")),
+            "12:00:00 web-app │ ready"
+        );
+    }
+
+    #[test]
+    fn only_filters_the_terminal_but_not_the_log() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("merged.log");
+        let m = mux(&MuxOptions {
+            only: vec!["web-app".to_string()],
+            log_file: Some(log.clone()),
+            ..Default::default()
+        });
+        assert!(!m.shows("api"));
+        assert!(m.shows("web-app"));
+
+        m.write("api", Stream::Stderr, b"boom\nagain\n");
+        let written = std::fs::read_to_string(&log).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("api     (stderr) │ boom"));
+    }
+
+    #[test]
+    fn only_accepts_comma_separated_projects() {
+        let cmd = clap::Command::new("t").arg(
+            clap::Arg::new("only")
+                .long("only")
+                .action(clap::ArgAction::Append),
+        );
+        let matches = cmd.get_matches_from(["t", "--only", "api,web", "--only", "docs"]);
+        let options = MuxOptions::from_matches(&matches);
+        assert_eq!(options.only, ["api", "web", "docs"]);
+        assert!(options.is_active());
+        assert!(!options.timestamps);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn prefix_mode_ends_each_project_with_its_exit_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("merged.log");
+        let m = Arc::new(mux(&MuxOptions {
+            prefix: true,
+            log_file: Some(log.clone()),
+            ..Default::default()
+        }));
+        assert!(m.exit_lines);
+        let job = |project: &str, script: &str| {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", script]);
+            (project.to_string(), cmd)
+        };
+        m.run_all(
+            vec![job("api", "echo up"), job("web-app", "echo down; exit 3")],
+            1,
+        );
+        let written = std::fs::read_to_string(&log).unwrap();
+        let lines: Vec<String> = written
+            .lines()
+            .map(|line| plain(line.split_once(' ').unwrap().1))
+            .collect();
+        assert_eq!(lines[0], "api     │ up");
+        assert!(
+            lines[1].starts_with("api     │ ✓ exited 0 after "),
+            "{:?}",
+            lines
+        );
+        assert_eq!(lines[2], "web-app │ down");
+        assert!(
+            lines[3].starts_with("web-app │ ✗ exited 3 after "),
+            "{:?}",
+            lines
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_gives_children_a_terminal() {
+        let tty_test = || {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", "test -t 1 && echo \"$GREETING\""])
+                .env("GREETING", "hello there");
+            ("api".to_string(), cmd)
+        };
+
+        let piped = Arc::new(mux(&MuxOptions::default()));
+        assert_eq!(
+            piped.run_all(vec![tty_test()], 1)[0].1.as_ref().unwrap(),
+            &1
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("merged.log");
+        let on_pty = Arc::new(mux(&MuxOptions {
+            pty: true,
+            log_file: Some(log.clone()),
+            ..Default::default()
+        }));
+        assert_eq!(
+            on_pty.run_all(vec![tty_test()], 1)[0].1.as_ref().unwrap(),
+            &0
+        );
+        let written = std::fs::read_to_string(&log).unwrap();
+        assert!(written.contains("│ hello there\n"), "{:?}", written);
+    }
+
+    #[test]
+    fn clock_formats_utc_time_of_day() {
+        assert_eq!(
+            clock(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            "22:13:20"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Completion notifications for long-running commands.
+//!
+//! When `notify.on_complete_secs` is set and `meta exec`, `meta run`, or a
+//! clone runs at least that long, the terminal bell rings as it finishes, and
+//! with `notify.desktop` a desktop notification is shown too. Failures to
+//! notify are ignored: a missing `notify-send` never fails the command.
+
+use metarepo_core::MetaConfig;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use super::outcome::format_duration;
+
+/// Whether a command that ran for `elapsed` should notify under `config`.
+pub fn should_notify(config: &MetaConfig, elapsed: Duration) -> bool {
+    config
+        .notify
+        .as_ref()
+        .and_then(|n| n.on_complete_secs)
+        .is_some_and(|secs| elapsed >= Duration::from_secs(secs))
+}
+
+/// The notification text for `what` finishing after `elapsed`.
+fn message(what: &str, elapsed: Duration, success: bool) -> String {
+    format!(
+        "{} {} after {}",
+        what,
+        if success { "finished" } else { "failed" },
+        format_duration(elapsed.as_millis() as u64)
+    )
+}
+
+/// Notify that `what` (e.g. "meta exec") finished after `elapsed`, if it ran
+/// long enough to be worth it.
+pub fn on_complete(config: &MetaConfig, what: &str, elapsed: Duration, success: bool) {
+    if !should_notify(config, elapsed) {
+        return;
+    }
+    // The bell only makes sense on a terminal; skip it in CI logs.
+    let mut stderr = std::io::stderr();
+    if stderr.is_terminal() {
+        let _ = stderr.write_all(b"\x07");
+        let _ = stderr.flush();
+    }
+    if config
+        .notify
+        .as_ref()
+        .and_then(|n| n.desktop)
+        .unwrap_or(false)
+    {
+        desktop_notification("meta", &message(what, elapsed, success));
+    }
+}
+
+/// Show a desktop notification with the platform's notifier, if any.
+fn desktop_notification(title: &str, body: &str) {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut cmd = Command::new("osascript");
+        cmd.arg("-e").arg(format!(
+            "display notification \"{}\" with title \"{}\"",
+            quote(body),
+            quote(title)
+        ));
+        cmd
+    } else if cfg!(target_os = "linux") {
+        let mut cmd = Command::new("notify-send");
+        cmd.arg(title).arg(body);
+        cmd
+    } else {
+        return;
+    };
+    let _ = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::NotifySettings;
+
+    #[test]
+    fn notifies_only_past_the_threshold() {
+        let mut config = MetaConfig::default();
+        assert!(!should_notify(&config, Duration::from_secs(3600)));
+
+        config.notify = Some(NotifySettings {
+            on_complete_secs: Some(60),
+            desktop: None,
+        });
+        assert!(!should_notify(&config, Duration::from_secs(59)));
+        assert!(should_notify(&config, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn message_says_how_it_ended() {
+        assert_eq!(
+            message("meta exec", Duration::from_secs(75), true),
+            "meta exec finished after 1m15s"
+        );
+        assert_eq!(
+            message("meta run", Duration::from_millis(61_500), false),
+            "meta run failed after 1m01s"
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Exit codes for commands that run in several projects.
+//!
+//! A multi-project run exits 0 when every project succeeded, 1 when none did,
+//! and 2 when some failed. A minimum success percentage (`--min-success`, or
+//! the command's entry in the `min_success` block of .meta) lets a partial
+//! failure exit 0 as long as enough projects succeeded.
+//!
+//! Every finished run is also written to [`LAST_RUN_FILE`] in the workspace
+//! state directory, which `meta last` displays.
+
+use super::{notify, state};
+use crate::MetarepoError;
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::*;
+use metarepo_core::{Align, MetaConfig, Table};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Every project succeeded (or there was nothing to run).
+pub const EXIT_OK: i32 = 0;
+/// Every project failed.
+pub const EXIT_FAILED: i32 = 1;
+/// Some projects failed.
+pub const EXIT_PARTIAL: i32 = 2;
+
+/// State file holding the summary of the most recent multi-project run.
+pub const LAST_RUN_FILE: &str = "last-run.json";
+
+/// One project's result in a multi-project run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectResult {
+    pub project: String,
+    pub success: bool,
+    /// The command's exit code; absent when it could not be started or was
+    /// killed by a signal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    /// When the project started, in milliseconds after the run began. Absent
+    /// in summaries saved before start times were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_ms: Option<u64>,
+}
+
+/// The projects that succeeded and failed in one run. Skipped projects count
+/// as neither.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOutcome {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub projects: Vec<ProjectResult>,
+    /// When each of `projects` started, turned into offsets by [`Self::finish`].
+    starts: Vec<Instant>,
+}
+
+impl RunOutcome {
+    /// Count one project's result: a success exactly when it exited 0. The
+    /// project is taken to have just finished; use [`Self::record_span`] for
+    /// results collected after the fact.
+    pub fn record(&mut self, project: &str, exit_code: Option<i32>, duration: Duration) {
+        let now = Instant::now();
+        let started = now.checked_sub(duration).unwrap_or(now);
+        self.record_span(project, exit_code, started, duration);
+    }
+
+    /// [`Self::record`] for a project that started at `started`.
+    pub fn record_span(
+        &mut self,
+        project: &str,
+        exit_code: Option<i32>,
+        started: Instant,
+        duration: Duration,
+    ) {
+        let success = exit_code == Some(0);
+        if success {
+            self.succeeded += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.projects.push(ProjectResult {
+            project: project.to_string(),
+            success,
+            exit_code,
+            duration_ms: duration.as_millis() as u64,
+            start_ms: None,
+        });
+        self.starts.push(started);
+    }
+
+    /// Add the results from another run, e.g. one group of a larger run.
+    pub fn merge(&mut self, other: RunOutcome) {
+        self.succeeded += other.succeeded;
+        self.failed += other.failed;
+        self.projects.extend(other.projects);
+        self.starts.extend(other.starts);
+    }
+
+    /// Percentage of the projects that ran which succeeded (100 when none ran).
+    pub fn success_percent(&self) -> f64 {
+        let total = self.succeeded + self.failed;
+        if total == 0 {
+            return 100.0;
+        }
+        self.succeeded as f64 * 100.0 / total as f64
+    }
+
+    /// The process exit code for this run given an optional minimum success
+    /// percentage.
+    pub fn exit_code(&self, min_success: Option<f64>) -> i32 {
+        if self.failed == 0 || min_success.is_some_and(|min| self.success_percent() >= min) {
+            EXIT_OK
+        } else if self.succeeded == 0 {
+            EXIT_FAILED
+        } else {
+            EXIT_PARTIAL
+        }
+    }
+
+    /// `Ok` when the run passes, otherwise an error carrying its exit code.
+    pub fn into_result(self, min_success: Option<f64>) -> Result<()> {
+        let code = self.exit_code(min_success);
+        if code == EXIT_OK {
+            return Ok(());
+        }
+        let total = self.succeeded + self.failed;
+        let mut message = format!("{} of {} project(s) failed", self.failed, total);
+        if let Some(min) = min_success {
+            message.push_str(&format!(
+                " ({:.0}% succeeded, below the {}% minimum)",
+                self.success_percent(),
+                min
+            ));
+        }
+        Err(MetarepoError::ProjectsFailed { code, message }.into())
+    }
+
+    /// Finish a run of `command` begun at `started`: write the summary to
+    /// [`LAST_RUN_FILE`] under `base_path`, notify when the run was long (see
+    /// [`notify`]), then return as [`Self::into_result`]. A summary that cannot
+    /// be written is a warning, not a failure.
+    pub fn finish(
+        self,
+        config: &MetaConfig,
+        base_path: &Path,
+        command: &str,
+        started: Instant,
+        min_success: Option<f64>,
+    ) -> Result<()> {
+        let elapsed = started.elapsed();
+        let mut projects = self.projects.clone();
+        for (project, start) in projects.iter_mut().zip(&self.starts) {
+            project.start_ms = Some(start.saturating_duration_since(started).as_millis() as u64);
+        }
+        let last = LastRun {
+            command: command.to_string(),
+            args: std::env::args().skip(1).collect(),
+            started: state::now_secs().saturating_sub(elapsed.as_secs()),
+            duration_ms: elapsed.as_millis() as u64,
+            exit_code: self.exit_code(min_success),
+            projects,
+        };
+        if let Err(e) = state::save(base_path, LAST_RUN_FILE, &last) {
+            eprintln!("  {} Could not save the run summary: {}", "⚠️".yellow(), e);
+        }
+        notify::on_complete(
+            config,
+            &format!("meta {}", command),
+            elapsed,
+            last.exit_code == EXIT_OK,
+        );
+        self.into_result(min_success)
+    }
+}
+
+/// The summary of a finished multi-project run, as saved in [`LAST_RUN_FILE`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastRun {
+    /// The command that ran (`exec`, `run`, `start`, `dev`, `git update`, ...).
+    pub command: String,
+    /// The full command line after `meta`, so the run can be repeated.
+    pub args: Vec<String>,
+    /// When the run started (seconds since the Unix epoch).
+    pub started: u64,
+    pub duration_ms: u64,
+    /// The exit code `meta` returned.
+    pub exit_code: i32,
+    pub projects: Vec<ProjectResult>,
+}
+
+impl LastRun {
+    /// The projects that failed, in run order.
+    pub fn failed_projects(&self) -> Vec<&str> {
+        self.projects
+            .iter()
+            .filter(|p| !p.success)
+            .map(|p| p.project.as_str())
+            .collect()
+    }
+}
+
+/// Format a duration in milliseconds as `850ms`, `12.3s`, or `4m05s`.
+pub fn format_duration(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}m{:02}s", ms / 60_000, (ms % 60_000) / 1000)
+    }
+}
+
+/// The result column for one project: its exit code, or why there is none.
+fn status_text(success: bool, exit_code: Option<i32>) -> String {
+    match (success, exit_code) {
+        (true, _) => "ok".to_string(),
+        (false, Some(code)) => format!("exit {}", code),
+        (false, None) => "did not run".to_string(),
+    }
+}
+
+/// A table of each project's result and how long it took.
+pub fn results_table(projects: &[ProjectResult]) -> Table {
+    let mut table = Table::new(["Project", "Result", "Duration"]).align(2, Align::Right);
+    for project in projects {
+        let result = status_text(project.success, project.exit_code);
+        let result = if project.success {
+            format!("✓ {}", result).green()
+        } else {
+            format!("✗ {}", result).red()
+        };
+        table.row([
+            project.project.clone(),
+            result.to_string(),
+            format_duration(project.duration_ms)
+                .bright_black()
+                .to_string(),
+        ]);
+    }
+    table
+}
+
+/// Parse a percentage such as `80`, `87.5`, or `80%`.
+pub fn parse_percent(raw: &str) -> Result<f64> {
+    let value: f64 = raw
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid percentage '{}'", raw))?;
+    if !(0.0..=100.0).contains(&value) {
+        return Err(anyhow::anyhow!(
+            "Percentage must be between 0 and 100, got '{}'",
+            raw
+        ));
+    }
+    Ok(value)
+}
+
+/// The minimum success percentage for `command`: `--min-success` when given,
+/// else the command's entry in the `min_success` block of .meta.
+pub fn min_success(
+    matches: &ArgMatches,
+    config: &MetaConfig,
+    command: &str,
+) -> Result<Option<f64>> {
+    if let Ok(Some(raw)) = matches.try_get_one::<String>("min-success") {
+        return parse_percent(raw).map(Some);
+    }
+    match config.min_success.as_ref().and_then(|m| m.get(command)) {
+        Some(&value) if (0.0..=100.0).contains(&value) => Ok(Some(value)),
+        Some(value) => Err(anyhow::anyhow!(
+            "min_success.{} must be between 0 and 100, got {}",
+            command,
+            value
+        )),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(succeeded: usize, failed: usize) -> RunOutcome {
+        RunOutcome {
+            succeeded,
+            failed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn status_names_the_exit_code_or_missing_run() {
+        assert_eq!(status_text(true, Some(0)), "ok");
+        assert_eq!(status_text(false, Some(2)), "exit 2");
+        assert_eq!(status_text(false, None), "did not run");
+    }
+
+    #[test]
+    fn exit_codes_distinguish_total_and_partial_failure() {
+        assert_eq!(outcome(3, 0).exit_code(None), EXIT_OK);
+        assert_eq!(outcome(0, 0).exit_code(None), EXIT_OK);
+        assert_eq!(outcome(0, 2).exit_code(None), EXIT_FAILED);
+        assert_eq!(outcome(2, 1).exit_code(None), EXIT_PARTIAL);
+    }
+
+    #[test]
+    fn min_success_lets_enough_successes_pass() {
+        assert_eq!(outcome(4, 1).exit_code(Some(80.0)), EXIT_OK);
+        assert_eq!(outcome(3, 1).exit_code(Some(80.0)), EXIT_PARTIAL);
+        assert_eq!(outcome(0, 1).exit_code(Some(50.0)), EXIT_FAILED);
+        assert_eq!(outcome(0, 1).exit_code(Some(0.0)), EXIT_OK);
+
+        let err = outcome(3, 1).into_result(Some(80.0)).unwrap_err();
+        match err.downcast_ref::<MetarepoError>() {
+            Some(MetarepoError::ProjectsFailed { code, message }) => {
+                assert_eq!(*code, EXIT_PARTIAL);
+                assert_eq!(
+                    message,
+                    "1 of 4 project(s) failed (75% succeeded, below the 80% minimum)"
+                );
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn finish_saves_the_run_summary() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = Instant::now();
+        let mut outcome = RunOutcome::default();
+        outcome.record_span(
+            "api",
+            Some(0),
+            origin + Duration::from_millis(200),
+            Duration::from_millis(1500),
+        );
+        outcome.record("web", Some(3), Duration::from_millis(20));
+        outcome.record("docs", None, Duration::ZERO);
+        assert_eq!((outcome.succeeded, outcome.failed), (1, 2));
+
+        assert!(outcome
+            .finish(&MetaConfig::default(), tmp.path(), "exec", origin, None)
+            .is_err());
+        let last: LastRun = state::load(tmp.path(), LAST_RUN_FILE).unwrap();
+        assert_eq!(last.command, "exec");
+        assert_eq!(last.exit_code, EXIT_PARTIAL);
+        assert_eq!(last.failed_projects(), ["web", "docs"]);
+        assert_eq!(last.projects[1].exit_code, Some(3));
+        assert_eq!(last.projects[0].duration_ms, 1500);
+        assert_eq!(last.projects[0].start_ms, Some(200));
+        assert!(last.projects[1].start_ms.is_some());
+    }
+
+    #[test]
+    fn durations_format_by_magnitude() {
+        assert_eq!(format_duration(850), "850ms");
+        assert_eq!(format_duration(12_340), "12.3s");
+        assert_eq!(format_duration(245_000), "4m05s");
+    }
+
+    #[test]
+    fn percentages_parse_with_or_without_sign() {
+        assert_eq!(parse_percent("80").unwrap(), 80.0);
+        assert_eq!(parse_percent("87.5%").unwrap(), 87.5);
+        assert!(parse_percent("120").is_err());
+        assert!(parse_percent("most").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! A bounded pool of worker threads for per-project work, sized by
+//! `-j/--jobs`.
+
+use anyhow::Result;
+use clap::ArgMatches;
+
+/// The `--jobs` value, which must be a positive integer; `None` when it was
+/// not given or the command has no such option.
+pub fn jobs_from(matches: &ArgMatches) -> Result<Option<usize>> {
+    let Ok(Some(raw)) = matches.try_get_one::<String>("jobs") else {
+        return Ok(None);
+    };
+    match raw.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(Some(n)),
+        _ => Err(anyhow::anyhow!(
+            "Invalid --jobs value '{}': must be a positive integer",
+            raw
+        )),
+    }
+}
+
+/// Apply `f` to every item across a bounded pool of worker threads, preserving
+/// input order in the returned vector.
+///
+/// Used for independent per-repository work: pull preflight checks and
+/// fetches, push plans, clones, and `meta exec --jobs`.
+/// Falls back to a plain sequential map when there is nothing to gain.
+pub fn parallel_map<T, R>(items: Vec<T>, workers: usize, f: impl Fn(T) -> R + Sync) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+{
+    let len = items.len();
+    if len <= 1 || workers <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let workers = workers.min(len);
+    let queue: std::sync::Mutex<std::collections::VecDeque<(usize, T)>> =
+        std::sync::Mutex::new(items.into_iter().enumerate().collect());
+    let slots: Vec<std::sync::Mutex<Option<R>>> =
+        (0..len).map(|_| std::sync::Mutex::new(None)).collect();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().pop_front();
+                match next {
+                    Some((index, item)) => {
+                        let result = f(item);
+                        *slots[index].lock().unwrap() = Some(result);
+                    }
+                    None => break,
+                }
+            });
+        }
+    });
+
+    slots
+        .into_iter()
+        .map(|slot| slot.into_inner().unwrap().expect("worker filled slot"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn keeps_order_and_never_exceeds_the_worker_count() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let doubled = parallel_map((0..20).collect(), 3, |n: u32| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+            n * 2
+        });
+        assert_eq!(doubled, (0..20).map(|n| n * 2).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+}
@@ -0,0 +1,168 @@
+//! Pinned projects: a project whose `.meta` entry has `"pinned": "<ref>"` is
+//! frozen at that ref. Pulls and updates fetch it but never move it, and warn
+//! when the remote's default branch has moved past the pin.
+
+use super::{detect_default_branch, git};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// The commit `pin` names in the repository at `repo_path`.
+pub fn resolve(repo_path: &Path, pin: &str) -> Result<String> {
+    git(
+        repo_path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", pin),
+        ],
+    )
+    .map_err(|_| anyhow::anyhow!("'{}' is not a commit, branch, or tag here", pin))
+}
+
+/// Check out `pin` (detached) in the working tree at `repo_path`.
+pub fn check_out(repo_path: &Path, pin: &str) -> Result<()> {
+    git(
+        repo_path,
+        &[
+            "-c",
+            "advice.detachedHead=false",
+            "checkout",
+            "--quiet",
+            pin,
+        ],
+    )
+    .map(|_| ())
+    .with_context(|| format!("Failed to check out '{}'", pin))
+}
+
+/// Update the remote-tracking refs without touching the working tree.
+pub fn fetch(repo_path: &Path) -> Result<()> {
+    git(repo_path, &["fetch", "--quiet", "--tags", "origin"]).map(|_| ())
+}
+
+/// How a pin relates to the remote's default branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinStatus {
+    /// The pin is the tip of the remote branch.
+    Current,
+    /// The remote branch has `commits` commits past the pin.
+    Behind { remote: String, commits: usize },
+    /// The pin is not on the remote branch at all.
+    Diverged { remote: String },
+}
+
+impl PinStatus {
+    /// The warning to show for `project` pinned at `pin`, if any.
+    pub fn warning(&self, project: &str, pin: &str) -> Option<String> {
+        match self {
+            PinStatus::Current => None,
+            PinStatus::Behind { remote, commits } => Some(format!(
+                "{} is pinned at {}, {} commit(s) behind {}",
+                project, pin, commits, remote
+            )),
+            PinStatus::Diverged { remote } => Some(format!(
+                "{} is pinned at {}, which is not on {}",
+                project, pin, remote
+            )),
+        }
+    }
+}
+
+/// Compare `pin` with the remote default branch (`origin/<default>`) using the
+/// refs already fetched.
+pub fn pin_status(repo_path: &Path, pin: &str) -> Result<PinStatus> {
+    let pinned = resolve(repo_path, pin)?;
+    let remote = format!("origin/{}", detect_default_branch(repo_path)?);
+    let counts = git(
+        repo_path,
+        &[
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("{}...{}", pinned, remote),
+        ],
+    )?;
+    let mut counts = counts.split_whitespace().map(str::parse::<usize>);
+    match (counts.next(), counts.next()) {
+        (Some(Ok(0)), Some(Ok(0))) => Ok(PinStatus::Current),
+        (Some(Ok(0)), Some(Ok(commits))) => Ok(PinStatus::Behind { remote, commits }),
+        (Some(Ok(_)), Some(Ok(_))) => Ok(PinStatus::Diverged { remote }),
+        _ => Err(anyhow::anyhow!("Unexpected rev-list output")),
+    }
+}
+
+/// Fetch a pinned project and return the divergence warning, if any. Errors
+/// (no remote, an unknown pin) become the warning text.
+pub fn check(project: &str, repo_path: &Path, pin: &str) -> Option<String> {
+    if let Err(e) = fetch(repo_path) {
+        return Some(format!("{}: could not fetch to check pin: {}", project, e));
+    }
+    match pin_status(repo_path, pin) {
+        Ok(status) => status.warning(project, pin),
+        Err(e) => Some(format!("{}: could not check pin {}: {}", project, pin, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    fn commit(dir: &Path, message: &str) {
+        run(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+
+    #[test]
+    fn reports_how_far_the_remote_moved_past_the_pin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        commit(&upstream, "one");
+        run(&upstream, &["tag", "v1"]);
+
+        let clone = tmp.path().join("clone");
+        run(
+            tmp.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "clone"],
+        );
+        assert_eq!(pin_status(&clone, "v1").unwrap(), PinStatus::Current);
+
+        commit(&upstream, "two");
+        commit(&upstream, "three");
+        fetch(&clone).unwrap();
+        let status = pin_status(&clone, "v1").unwrap();
+        assert_eq!(
+            status,
+            PinStatus::Behind {
+                remote: "origin/main".to_string(),
+                commits: 2
+            }
+        );
+        assert_eq!(
+            status.warning("lib", "v1").unwrap(),
+            "lib is pinned at v1, 2 commit(s) behind origin/main"
+        );
+
+        // A local-only commit is not on the remote branch.
+        commit(&clone, "local");
+        assert_eq!(
+            pin_status(&clone, "HEAD").unwrap(),
+            PinStatus::Diverged {
+                remote: "origin/main".to_string()
+            }
+        );
+        assert!(resolve(&clone, "no-such-tag").is_err());
+    }
+}
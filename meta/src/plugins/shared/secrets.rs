@@ -0,0 +1,248 @@
+//! Encrypted values in project `env` maps.
+//!
+//! A value written as `enc:<base64>` is sealed with XChaCha20-Poly1305 under
+//! a key the team shares out of band, so a token can live in `.meta` without
+//! being readable by everyone who can read the repository:
+//!
+//! ```json
+//! "env": { "NPM_TOKEN": "enc:9f3k..." }
+//! ```
+//!
+//! The key comes from `METAREPO_ENV_KEY` (base64), else from the OS keychain
+//! (the `env-key` entry of the `metarepo` service, see [`keychain`]), else from
+//! `env.key` next to the user config (`~/.config/metarepo/env.key`).
+//! `meta config keygen` creates one and `meta config encrypt` seals a value.
+//! Values are decrypted only when `meta exec`, `meta run`, or a hook runs with
+//! them, so a missing key fails that run, not every command that loads the
+//! config.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+use super::keychain;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Marks an encrypted value.
+pub const PREFIX: &str = "enc:";
+
+/// Environment variable holding the key, base64-encoded. Wins over the
+/// keychain and the file.
+pub const KEY_ENV: &str = "METAREPO_ENV_KEY";
+
+/// Keychain account the key is stored under, in the `metarepo` service.
+pub const KEY_ACCOUNT: &str = "env-key";
+
+const KEY_FILENAME: &str = "env.key";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+/// Poly1305 tag appended to every ciphertext.
+const TAG_LEN: usize = 16;
+
+/// Whether `value` is an encrypted env value.
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// Where the key file lives: `$XDG_CONFIG_HOME/metarepo/env.key`, else
+/// `~/.config/metarepo/env.key`. `None` without a home directory.
+pub fn key_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            PathBuf::from(std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?)
+                .join(".config")
+        }
+    };
+    Some(base.join("metarepo").join(KEY_FILENAME))
+}
+
+/// A new random key, base64-encoded.
+pub fn generate_key() -> String {
+    STANDARD.encode(XChaCha20Poly1305::generate_key(&mut OsRng))
+}
+
+fn parse_key(text: &str) -> Result<Key> {
+    let bytes = STANDARD
+        .decode(text.trim())
+        .context("The key is not valid base64")?;
+    if bytes.len() != KEY_LEN {
+        return Err(anyhow::anyhow!(
+            "The key must be {} bytes, found {}",
+            KEY_LEN,
+            bytes.len()
+        ));
+    }
+    Ok(*Key::from_slice(&bytes))
+}
+
+/// The key from `METAREPO_ENV_KEY`, else from the keychain, else from `path`.
+/// The keychain is only asked when the environment has no key, since it may
+/// prompt to be unlocked.
+fn load_key_from(
+    env: Option<String>,
+    keychain: impl FnOnce() -> Option<String>,
+    path: Option<&Path>,
+) -> Result<Key> {
+    if let Some(text) = env.filter(|v| !v.trim().is_empty()) {
+        return parse_key(&text).with_context(|| format!("Invalid {}", KEY_ENV));
+    }
+    if let Some(text) = keychain() {
+        return parse_key(&text).context("Invalid key in the OS keychain");
+    }
+    let Some(path) = path.filter(|p| p.exists()) else {
+        return Err(anyhow::anyhow!(
+            "No key for encrypted env values: set {} or run 'meta config keygen'",
+            KEY_ENV
+        ));
+    };
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_key(&text).with_context(|| format!("Invalid key in {}", path.display()))
+}
+
+/// The key for encrypted env values.
+pub fn load_key() -> Result<Key> {
+    load_key_from(
+        std::env::var(KEY_ENV).ok(),
+        || keychain::get(KEY_ACCOUNT),
+        key_path().as_deref(),
+    )
+}
+
+/// Seal `plaintext` as an `enc:` value.
+pub fn encrypt(key: &Key, plaintext: &str) -> Result<String> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let sealed = XChaCha20Poly1305::new(key)
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+    let mut bytes = nonce.to_vec();
+    bytes.extend(sealed);
+    Ok(format!("{}{}", PREFIX, STANDARD.encode(bytes)))
+}
+
+/// The nonce and sealed bytes of an `enc:` value, or why it is malformed.
+fn split(value: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+    let encoded = value
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| anyhow::anyhow!("not an encrypted value"))?;
+    let mut bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|_| anyhow::anyhow!("encrypted value is not valid base64"))?;
+    if bytes.len() < NONCE_LEN + TAG_LEN {
+        return Err(anyhow::anyhow!("encrypted value is truncated"));
+    }
+    let sealed = bytes.split_off(NONCE_LEN);
+    Ok((bytes, sealed))
+}
+
+/// Why an `enc:` value cannot be decrypted by any key, if it cannot.
+pub fn format_problem(value: &str) -> Option<String> {
+    split(value).err().map(|e| e.to_string())
+}
+
+/// Open an `enc:` value.
+pub fn decrypt(key: &Key, value: &str) -> Result<String> {
+    let (nonce, sealed) = split(value)?;
+    let plain = XChaCha20Poly1305::new(key)
+        .decrypt(XNonce::from_slice(&nonce), sealed.as_slice())
+        .map_err(|_| anyhow::anyhow!("wrong key, or the value was altered"))?;
+    String::from_utf8(plain).context("decrypted value is not UTF-8")
+}
+
+/// `env` with its encrypted values opened. The key is only loaded when there
+/// is something to decrypt. Errors carry their whole chain in one message,
+/// since callers print them next to a project name.
+pub fn resolve_env(env: &HashMap<String, String>) -> Result<Vec<(String, String)>> {
+    let mut key = None;
+    let mut resolved = Vec::with_capacity(env.len());
+    for (name, value) in env {
+        if !is_encrypted(value) {
+            resolved.push((name.clone(), value.clone()));
+            continue;
+        }
+        let key = match &key {
+            Some(key) => key,
+            None => key.insert(load_key().map_err(|e| anyhow::anyhow!("{:#}", e))?),
+        };
+        let plain = decrypt(key, value)
+            .map_err(|e| anyhow::anyhow!("Cannot decrypt env var '{}': {:#}", name, e))?;
+        resolved.push((name.clone(), plain));
+    }
+    Ok(resolved)
+}
+
+/// Write a new key to `path`, readable only by the owner.
+pub fn write_key(path: &Path, key: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    std::io::Write::write_all(&mut file, format!("{}\n", key).as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_round_trip_only_with_their_key() {
+        let key = parse_key(&generate_key()).unwrap();
+        let sealed = encrypt(&key, "s3cret").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(format_problem(&sealed), None);
+        assert_eq!(decrypt(&key, &sealed).unwrap(), "s3cret");
+        // A fresh nonce each time: the same secret never looks the same.
+        assert_ne!(encrypt(&key, "s3cret").unwrap(), sealed);
+
+        let other = parse_key(&generate_key()).unwrap();
+        assert!(decrypt(&other, &sealed).is_err());
+        assert!(format_problem("enc:not base64!").is_some());
+        assert!(format_problem("enc:AAAA").is_some());
+    }
+
+    #[test]
+    fn key_comes_from_env_then_keychain_then_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("metarepo").join(KEY_FILENAME);
+        assert!(load_key_from(None, || None, Some(&path)).is_err());
+
+        let file_key = generate_key();
+        write_key(&path, &file_key).unwrap();
+        assert!(write_key(&path, &file_key).is_err());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(
+            load_key_from(None, || None, Some(&path)).unwrap(),
+            parse_key(&file_key).unwrap()
+        );
+
+        let keychain_key = generate_key();
+        assert_eq!(
+            load_key_from(None, || Some(keychain_key.clone()), Some(&path)).unwrap(),
+            parse_key(&keychain_key).unwrap()
+        );
+
+        let env_key = generate_key();
+        assert_eq!(
+            load_key_from(Some(env_key.clone()), || unreachable!(), Some(&path)).unwrap(),
+            parse_key(&env_key).unwrap()
+        );
+        assert!(load_key_from(Some("short".into()), || None, Some(&path)).is_err());
+    }
+}
@@ -0,0 +1,481 @@
+//! Explicit project selection shared by every command that takes project
+//! names. Selectors are project keys, aliases, basenames, or globs
+//! (`services/*`, `libs/**`), resolved by [`metarepo_core::MetaConfig::select_projects`].
+//!
+//! `--group <name>` selects the members of a workspace group (see
+//! [`metarepo_core::ProjectGroup`]) alongside any other selectors.
+//!
+//! `--tag <tag>` narrows a selection to the projects carrying that tag in
+//! `.meta`, and `--exclude-tag <tag>` drops the ones carrying it (see
+//! [`tagged_keys`]). `--tag` on its own selects across the whole workspace
+//! rather than the directory scope (see [`implicit_scope`]).
+//!
+//! Exclusion runs after inclusion: `--exclude` selectors drop projects from
+//! whatever was selected, and the workspace `default_excludes` drop projects
+//! from implicit and bulk selections only. Both also accept tags: a selector
+//! naming no project drops the projects carrying that tag.
+
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::Colorize;
+use metarepo_core::{MetaConfig, RuntimeConfig};
+use std::collections::HashSet;
+
+/// Name of the positional argument holding project selectors.
+pub const TARGETS_ARG: &str = "targets";
+
+/// Collect the selectors given on the command line: `-p/--project`,
+/// `--projects` (comma-separated), and positional [`TARGETS_ARG`] values.
+/// Arguments a command does not define are skipped.
+pub fn selectors_from(matches: &ArgMatches) -> Vec<String> {
+    let mut selectors = Vec::new();
+    for id in ["project", "projects"] {
+        if let Ok(Some(value)) = matches.try_get_one::<String>(id) {
+            selectors.push(value.clone());
+        }
+    }
+    if let Ok(Some(values)) = matches.try_get_many::<String>(TARGETS_ARG) {
+        selectors.extend(values.cloned());
+    }
+    selectors
+}
+
+/// Whether `--strict` was passed (false when the command has no such flag).
+pub fn strict_from(matches: &ArgMatches) -> bool {
+    matches
+        .try_get_one::<bool>("strict")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Collect the group names given with `--group` (repeatable or
+/// comma-separated); empty when the command has no such option.
+pub fn groups_from(matches: &ArgMatches) -> Vec<String> {
+    list_from(matches, "group")
+}
+
+/// Collect the values of the list option `id` (repeatable or
+/// comma-separated); empty when the command has no such option.
+fn list_from(matches: &ArgMatches, id: &str) -> Vec<String> {
+    match matches.try_get_many::<String>(id) {
+        Ok(Some(values)) => values
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Collect the tags given with `--tag`.
+pub fn tags_from(matches: &ArgMatches) -> Vec<String> {
+    list_from(matches, "tag")
+}
+
+/// Collect the tags given with `--exclude-tag`.
+pub fn excluded_tags_from(matches: &ArgMatches) -> Vec<String> {
+    list_from(matches, "exclude-tag")
+}
+
+/// The projects `--tag` and `--exclude-tag` leave in: those carrying any
+/// `--tag` (every project without one), less those carrying any
+/// `--exclude-tag`. `None` when neither was given. Fails when no project
+/// carries any `--tag`, like an unknown group.
+pub fn tagged_keys(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+) -> Result<Option<HashSet<String>>> {
+    let tags = tags_from(matches);
+    let excluded = excluded_tags_from(matches);
+    if tags.is_empty() && excluded.is_empty() {
+        return Ok(None);
+    }
+    let mut kept: HashSet<String> = if tags.is_empty() {
+        config.meta_config.projects.keys().cloned().collect()
+    } else {
+        config.meta_config.tagged_project_keys(&tags)
+    };
+    if kept.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No projects tagged '{}'",
+            tags.join("', '")
+        ));
+    }
+    let dropped = config.meta_config.tagged_project_keys(&excluded);
+    kept.retain(|key| !dropped.contains(key));
+    Ok(Some(kept))
+}
+
+/// Drop the projects `--tag` and `--exclude-tag` leave out of `projects`.
+/// See [`tagged_keys`].
+pub fn apply_tags(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+    projects: &mut Vec<String>,
+) -> Result<()> {
+    if let Some(kept) = tagged_keys(matches, config)? {
+        projects.retain(|key| kept.contains(key));
+    }
+    Ok(())
+}
+
+/// Whether `--tag` or `--exclude-tag` was given.
+pub fn has_tag_filter(matches: &ArgMatches) -> bool {
+    !tags_from(matches).is_empty() || !excluded_tags_from(matches).is_empty()
+}
+
+/// Whether `--tag` is what selects the projects: given without any other
+/// selector or group.
+pub fn selects_by_tag(matches: &ArgMatches) -> bool {
+    !tags_from(matches).is_empty() && !has_selection(matches)
+}
+
+/// The projects a command works on when none were named: with `--tag`,
+/// every project in the workspace, else the directory-aware scope. Tags
+/// and excludes are applied by the caller.
+pub fn implicit_scope(matches: &ArgMatches, config: &RuntimeConfig) -> Vec<String> {
+    if selects_by_tag(matches) {
+        let mut projects: Vec<String> = config.meta_config.projects.keys().cloned().collect();
+        projects.sort();
+        projects
+    } else {
+        config.scoped_project_keys()
+    }
+}
+
+/// Whether any projects were named, by selector or by `--group`.
+pub fn has_selection(matches: &ArgMatches) -> bool {
+    !selectors_from(matches).is_empty() || !groups_from(matches).is_empty()
+}
+
+/// Resolve the explicitly selected projects, or `None` when no selector or
+/// group was given so the caller can fall back to its default scope. Fails
+/// when nothing matches, or with `--strict` when any selector matches
+/// nothing; otherwise selectors that miss are reported as warnings. Unknown
+/// groups are always an error.
+pub fn explicit_projects(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+) -> Result<Option<Vec<String>>> {
+    let selectors = selectors_from(matches);
+    let groups = groups_from(matches);
+    if selectors.is_empty() && groups.is_empty() {
+        return Ok(None);
+    }
+    let mut projects = if selectors.is_empty() {
+        Vec::new()
+    } else {
+        resolve_selectors(&selectors, config, strict_from(matches))?
+    };
+    for group in &groups {
+        for key in config.meta_config.group_projects(group)? {
+            if !projects.contains(&key) {
+                projects.push(key);
+            }
+        }
+    }
+    if projects.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No projects in group '{}'",
+            groups.join("', '")
+        ));
+    }
+    Ok(Some(projects))
+}
+
+/// Resolve `selectors` against the workspace, warning about (or, with
+/// `strict`, rejecting) selectors that match no project.
+pub fn resolve_selectors(
+    selectors: &[String],
+    config: &RuntimeConfig,
+    strict: bool,
+) -> Result<Vec<String>> {
+    let selection = config.select_projects(selectors);
+    if !strict && !selection.projects.is_empty() {
+        for missed in &selection.unmatched {
+            eprintln!("  {} No projects match '{}'", "⚠".yellow(), missed);
+        }
+    }
+    selection.into_projects(strict)
+}
+
+/// Collect the `--exclude` selectors (comma-separated) given on the command line.
+pub fn excludes_from(matches: &ArgMatches) -> Vec<String> {
+    match matches.try_get_many::<String>("exclude") {
+        Ok(Some(values)) => values.cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Canonical keys to leave out of a selection: every project matched by
+/// `--exclude` (projects, globs, or tags; see
+/// [`MetaConfig::select_excluded`]), plus the workspace `default_excludes`
+/// unless the selection was explicit (`explicit` is true when the user named
+/// the projects). `--exclude` selectors that match nothing are warned about.
+pub fn excluded_keys(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+    explicit: bool,
+) -> HashSet<String> {
+    let selection = config.meta_config.select_excluded(&excludes_from(matches));
+    for missed in &selection.unmatched {
+        eprintln!(
+            "  {} No projects or tags match --exclude '{}'",
+            "⚠".yellow(),
+            missed
+        );
+    }
+    let mut excluded: HashSet<String> = selection.projects.into_iter().collect();
+    if !explicit {
+        excluded.extend(config.meta_config.default_excluded_keys());
+    }
+    excluded
+}
+
+/// Drop excluded projects from `projects`. See [`excluded_keys`].
+pub fn apply_excludes(
+    matches: &ArgMatches,
+    config: &RuntimeConfig,
+    projects: &mut Vec<String>,
+    explicit: bool,
+) {
+    let excluded = excluded_keys(matches, config, explicit);
+    if !excluded.is_empty() {
+        projects.retain(|key| !excluded.contains(key));
+    }
+}
+
+/// Warn on stderr about each deprecated project among `projects`, so a
+/// command still aimed at a retiring project says so before it runs.
+pub fn warn_deprecated<I, S>(config: &MetaConfig, projects: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for project in projects {
+        let project = project.as_ref();
+        if let Some(deprecation) = config.get_project_deprecation(project) {
+            eprintln!(
+                "  {} {} is {}",
+                "⚠".yellow(),
+                project,
+                deprecation.summary()
+            );
+        }
+    }
+}
+
+/// Every workspace project except the excluded ones, sorted. This is what
+/// `--all` expands to.
+pub fn all_projects(matches: &ArgMatches, config: &RuntimeConfig) -> Vec<String> {
+    let mut projects: Vec<String> = config.meta_config.projects.keys().cloned().collect();
+    projects.sort();
+    apply_excludes(matches, config, &mut projects, false);
+    projects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    fn cmd() -> Command {
+        Command::new("t")
+            .arg(Arg::new("project").short('p').long("project"))
+            .arg(Arg::new("projects").long("projects"))
+            .arg(Arg::new("strict").long("strict").action(ArgAction::SetTrue))
+            .arg(Arg::new("group").long("group").action(ArgAction::Append))
+            .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+            .arg(
+                Arg::new("exclude-tag")
+                    .long("exclude-tag")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("exclude")
+                    .long("exclude")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new(TARGETS_ARG)
+                    .num_args(1..)
+                    .action(ArgAction::Append),
+            )
+    }
+
+    #[test]
+    fn collects_every_selector_source() {
+        let matches = cmd()
+            .try_get_matches_from(["t", "-p", "api", "--projects", "a,b", "libs/*", "--strict"])
+            .unwrap();
+        assert_eq!(selectors_from(&matches), vec!["api", "a,b", "libs/*"]);
+        assert!(strict_from(&matches));
+    }
+
+    #[test]
+    fn undefined_arguments_are_ignored() {
+        let matches = Command::new("t").try_get_matches_from(["t"]).unwrap();
+        assert!(selectors_from(&matches).is_empty());
+        assert!(!strict_from(&matches));
+    }
+
+    fn runtime(meta: &str) -> RuntimeConfig {
+        RuntimeConfig {
+            meta_config: serde_json::from_str(meta).unwrap(),
+            working_dir: std::path::PathBuf::from("/ws"),
+            meta_file_path: Some(std::path::PathBuf::from("/ws/.meta")),
+            experimental: false,
+            non_interactive: None,
+            scope_workspace: false,
+            profile: None,
+            settings_catalog: Vec::new(),
+            plugin_conflicts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn excludes_apply_after_inclusion_and_defaults_only_to_bulk() {
+        let config = runtime(
+            r#"{
+                "default_excludes": ["vendor/*"],
+                "projects": {
+                    "api": "local:api",
+                    "web": "local:web",
+                    "vendor/zlib": "local:vendor/zlib"
+                }
+            }"#,
+        );
+        let matches = cmd()
+            .try_get_matches_from(["t", "--exclude", "web"])
+            .unwrap();
+        assert_eq!(all_projects(&matches, &config), vec!["api"]);
+
+        let mut named = vec!["web".to_string(), "vendor/zlib".to_string()];
+        apply_excludes(&matches, &config, &mut named, true);
+        assert_eq!(named, vec!["vendor/zlib"]);
+    }
+
+    #[test]
+    fn excludes_and_default_excludes_accept_tags() {
+        let config = runtime(
+            r#"{
+                "default_excludes": ["archived"],
+                "projects": {
+                    "api": { "url": "local:api", "tags": ["backend"] },
+                    "web": "local:web",
+                    "legacy": { "url": "local:legacy", "tags": ["archived"] }
+                }
+            }"#,
+        );
+        let matches = cmd()
+            .try_get_matches_from(["t", "--exclude", "backend,nothing"])
+            .unwrap();
+        assert_eq!(all_projects(&matches, &config), vec!["web"]);
+
+        let mut named = vec!["api".to_string(), "legacy".to_string()];
+        apply_excludes(&matches, &config, &mut named, true);
+        assert_eq!(named, vec!["legacy"]);
+    }
+
+    #[test]
+    fn groups_add_their_members_to_the_selection() {
+        let config = runtime(
+            r#"{
+                "projects": {
+                    "api": "local:api",
+                    "web": "local:web",
+                    "services/billing": "local:services/billing"
+                },
+                "groups": {
+                    "backend": { "projects": ["api", "services/*"] },
+                    "empty": { "projects": ["gone"] }
+                }
+            }"#,
+        );
+        let selected = |args: &[&str]| {
+            let matches = cmd()
+                .try_get_matches_from(std::iter::once("t").chain(args.iter().copied()))
+                .unwrap();
+            explicit_projects(&matches, &config)
+        };
+        assert_eq!(
+            selected(&["--group", "backend"]).unwrap(),
+            Some(vec!["api".to_string(), "services/billing".to_string()])
+        );
+        assert_eq!(
+            selected(&["web", "--group", "backend"]).unwrap(),
+            Some(vec![
+                "web".to_string(),
+                "api".to_string(),
+                "services/billing".to_string()
+            ])
+        );
+        assert_eq!(selected(&[]).unwrap(), None);
+        assert!(selected(&["--group", "empty"]).is_err());
+        let err = selected(&["--group", "mobile"]).unwrap_err().to_string();
+        assert!(err.contains("Available groups: backend, empty"), "{}", err);
+    }
+
+    #[test]
+    fn tags_resolve_to_the_projects_carrying_them() {
+        let config = runtime(
+            r#"{
+                "projects": {
+                    "api": { "url": "local:api", "tags": ["backend"] },
+                    "worker": { "url": "local:worker", "tags": ["backend", "jobs"] },
+                    "web": "local:web"
+                }
+            }"#,
+        );
+        let tagged = |args: &[&str]| {
+            let matches = cmd()
+                .try_get_matches_from(std::iter::once("t").chain(args.iter().copied()))
+                .unwrap();
+            tagged_keys(&matches, &config).map(|keys| {
+                keys.map(|keys| {
+                    let mut keys: Vec<String> = keys.into_iter().collect();
+                    keys.sort();
+                    keys
+                })
+            })
+        };
+        assert_eq!(tagged(&[]).unwrap(), None);
+        assert_eq!(
+            tagged(&["--tag", "backend"]).unwrap(),
+            Some(vec!["api".to_string(), "worker".to_string()])
+        );
+        assert_eq!(
+            tagged(&["--tag", "jobs,frontend"]).unwrap(),
+            Some(vec!["worker".to_string()])
+        );
+        let err = tagged(&["--tag", "mobile"]).unwrap_err().to_string();
+        assert!(err.contains("No projects tagged 'mobile'"), "{}", err);
+
+        // --exclude-tag drops from the tagged projects, or from every project.
+        assert_eq!(
+            tagged(&["--tag", "backend", "--exclude-tag", "jobs"]).unwrap(),
+            Some(vec!["api".to_string()])
+        );
+        assert_eq!(
+            tagged(&["--exclude-tag", "backend"]).unwrap(),
+            Some(vec!["web".to_string()])
+        );
+
+        // --tag alone selects across the workspace; with a name it only narrows.
+        let matches = cmd()
+            .try_get_matches_from(["t", "--tag", "backend"])
+            .unwrap();
+        assert!(selects_by_tag(&matches));
+        assert_eq!(implicit_scope(&matches, &config), ["api", "web", "worker"]);
+        let matches = cmd()
+            .try_get_matches_from(["t", "web", "--tag", "backend"])
+            .unwrap();
+        assert!(!selects_by_tag(&matches));
+        let mut named = vec!["api".to_string(), "web".to_string()];
+        apply_tags(&matches, &config, &mut named).unwrap();
+        assert_eq!(named, ["api"]);
+    }
+}
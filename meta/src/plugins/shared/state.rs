@@ -0,0 +1,107 @@
+//! Workspace state: small JSON files that commands keep between runs (history,
+//! progress), stored under [`STATE_DIR`] at the workspace root. State is local
+//! to a checkout; `meta init` adds the directory to `.gitignore`.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Directory, relative to the workspace root, holding state files.
+pub const STATE_DIR: &str = ".meta-state";
+
+/// Path of the state file `name` for the workspace at `base_path`.
+pub fn state_path(base_path: &Path, name: &str) -> PathBuf {
+    base_path.join(STATE_DIR).join(name)
+}
+
+/// Load the state file `name`, or the default value when it does not exist yet.
+pub fn load<T: DeserializeOwned + Default>(base_path: &Path, name: &str) -> Result<T> {
+    let path = state_path(base_path, name);
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Write the state file `name`, creating the state directory as needed. The
+/// file is replaced atomically so an interrupted run never leaves it truncated.
+pub fn save<T: Serialize>(base_path: &Path, name: &str, value: &T) -> Result<()> {
+    let path = state_path(base_path, name);
+    let dir = base_path.join(STATE_DIR);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(value)? + "\n")
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Seconds since the Unix epoch, for timestamping state records.
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Today's date (UTC) as `YYYY-MM-DD`.
+pub fn today() -> String {
+    format_timestamp(now_secs())[..10].to_string()
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM` (UTC).
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days (Howard Hinnant's algorithm), proleptic Gregorian.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        runs: u32,
+    }
+
+    #[test]
+    fn load_defaults_then_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let initial: Counter = load(tmp.path(), "counter.json").unwrap();
+        assert_eq!(initial, Counter::default());
+
+        save(tmp.path(), "counter.json", &Counter { runs: 3 }).unwrap();
+        let loaded: Counter = load(tmp.path(), "counter.json").unwrap();
+        assert_eq!(loaded.runs, 3);
+        assert!(tmp.path().join(STATE_DIR).join("counter.json").exists());
+        assert!(!tmp.path().join(STATE_DIR).join("counter.tmp").exists());
+    }
+
+    #[test]
+    fn formats_timestamps_in_utc() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13");
+    }
+}
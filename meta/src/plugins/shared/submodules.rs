@@ -0,0 +1,186 @@
+//! Git submodules inside projects: `--recurse-submodules`, or
+//! `recurse-submodules` in the `clone` block of `.meta`.
+//!
+//! When on, clones and `meta project update` initialize every submodule and
+//! check it out at the commit its parent records, recursively, fetching
+//! through libgit2 with the same credentials and `--limit-rate` as the parent.
+//! Status reports submodules with changes of their own either way.
+
+use super::{auth_callbacks, transfer};
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use git2::{FetchOptions, Repository, SubmoduleIgnore, SubmoduleStatus, SubmoduleUpdateOptions};
+use metarepo_core::MetaConfig;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static RECURSE: AtomicBool = AtomicBool::new(false);
+
+/// Turn recursion on from `--recurse-submodules` when the command has it and
+/// it was given, else from the config's `clone.recurse-submodules`.
+pub fn configure(matches: &ArgMatches, config: &MetaConfig) {
+    let flag = matches
+        .try_get_one::<bool>("recurse-submodules")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false);
+    let configured = config
+        .clone_settings
+        .as_ref()
+        .and_then(|c| c.recurse_submodules)
+        .unwrap_or(false);
+    set(flag || configured);
+}
+
+pub fn set(enabled: bool) {
+    RECURSE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether clones and updates bring submodules along.
+pub fn enabled() -> bool {
+    RECURSE.load(Ordering::Relaxed)
+}
+
+/// Initialize and update the submodules of the checkout at `path`, and
+/// theirs in turn. Returns how many were updated.
+pub fn update(path: &Path) -> Result<usize> {
+    update_repo(&Repository::open(path)?)
+}
+
+fn update_repo(repo: &Repository) -> Result<usize> {
+    let mut updated = 0;
+    for mut submodule in repo.submodules()? {
+        let name = submodule.path().display().to_string();
+        let mut callbacks = auth_callbacks();
+        transfer::apply(&mut callbacks, &name);
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        let mut options = SubmoduleUpdateOptions::new();
+        options.fetch(fetch_options);
+        submodule
+            .update(true, Some(&mut options))
+            .with_context(|| format!("Failed to update submodule {}", name))?;
+        updated += 1;
+        let nested = submodule
+            .open()
+            .with_context(|| format!("Failed to open submodule {}", name))?;
+        updated += update_repo(&nested)?;
+    }
+    Ok(updated)
+}
+
+/// A submodule whose checkout differs from what its parent records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmoduleChange {
+    /// Path within the parent.
+    pub path: String,
+    /// Checked out at another commit than the parent records.
+    pub new_commits: bool,
+    /// Modified or staged files inside.
+    pub modified: bool,
+    pub untracked: bool,
+}
+
+impl SubmoduleChange {
+    /// What changed, as `git status` words it: `new commits, modified
+    /// content`.
+    pub fn describe(&self) -> String {
+        [
+            (self.new_commits, "new commits"),
+            (self.modified, "modified content"),
+            (self.untracked, "untracked content"),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, words)| *words)
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// The initialized submodules of the repository at `path` that have changes.
+/// A repository without submodules, or one that cannot be read, has none.
+pub fn changes(path: &Path) -> Vec<SubmoduleChange> {
+    let Ok(repo) = Repository::open(path) else {
+        return Vec::new();
+    };
+    let Ok(submodules) = repo.submodules() else {
+        return Vec::new();
+    };
+    submodules
+        .iter()
+        .filter_map(|submodule| {
+            let name = submodule.name().ok()?;
+            let status = repo.submodule_status(name, SubmoduleIgnore::None).ok()?;
+            if status.contains(SubmoduleStatus::WD_UNINITIALIZED) {
+                return None;
+            }
+            let change = SubmoduleChange {
+                path: submodule.path().display().to_string(),
+                new_commits: status.contains(SubmoduleStatus::WD_MODIFIED),
+                modified: status.intersects(
+                    SubmoduleStatus::WD_WD_MODIFIED | SubmoduleStatus::WD_INDEX_MODIFIED,
+                ),
+                untracked: status.contains(SubmoduleStatus::WD_UNTRACKED),
+            };
+            (change.new_commits || change.modified || change.untracked).then_some(change)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let ok = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(["-c", "protocol.file.allow=always"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap()
+            .success();
+        assert!(ok, "git {:?} failed", args);
+    }
+
+    #[test]
+    fn updates_nested_submodules_and_reports_their_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for name in ["inner", "lib", "app"] {
+            std::fs::create_dir(root.join(name)).unwrap();
+            git(&root.join(name), &["init", "-q", "-b", "main"]);
+            std::fs::write(root.join(name).join("README"), name).unwrap();
+            git(&root.join(name), &["add", "README"]);
+            git(&root.join(name), &["commit", "-qm", "init"]);
+        }
+        let url = |name: &str| root.join(name).display().to_string();
+        git(
+            &root.join("lib"),
+            &["submodule", "-q", "add", &url("inner"), "inner"],
+        );
+        git(&root.join("lib"), &["commit", "-qm", "add inner"]);
+        git(
+            &root.join("app"),
+            &["submodule", "-q", "add", &url("lib"), "lib"],
+        );
+        git(&root.join("app"), &["commit", "-qm", "add lib"]);
+
+        git(root, &["clone", "-q", &url("app"), "clone"]);
+        let clone = root.join("clone");
+        assert!(!clone.join("lib/README").exists());
+        assert_eq!(update(&clone).unwrap(), 2);
+        assert!(clone.join("lib/inner/README").exists());
+        assert!(changes(&clone).is_empty());
+
+        std::fs::write(clone.join("lib/README"), "edited").unwrap();
+        std::fs::write(clone.join("lib/scratch"), "").unwrap();
+        let found = changes(&clone);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "lib");
+        assert_eq!(found[0].describe(), "modified content, untracked content");
+    }
+}
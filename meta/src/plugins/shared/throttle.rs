@@ -0,0 +1,113 @@
+//! Bandwidth limiting for clones and fetches: `--limit-rate <KB/s>`, or
+//! `limit-rate` in the `clone` block of `.meta`.
+//!
+//! libgit2 has no rate option, so the transfer-progress callback pauses
+//! whenever the bytes received so far are ahead of the allowed rate; reads
+//! stall and TCP flow control slows the sender. The budget is shared by every
+//! transfer in the process, so parallel clones together stay under the limit.
+
+use anyhow::Result;
+use clap::ArgMatches;
+use metarepo_core::MetaConfig;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The limit in KB/s; 0 means unlimited.
+static LIMIT_KBPS: AtomicU64 = AtomicU64::new(0);
+
+/// When limiting started and the bytes received since, across transfers.
+static BUDGET: Mutex<Option<(Instant, u64)>> = Mutex::new(None);
+
+/// Set the limit from `--limit-rate` when the command has one and it was
+/// given, else from the config's `clone.limit-rate`.
+pub fn configure(matches: &ArgMatches, config: &MetaConfig) -> Result<()> {
+    let flag = matches.try_get_one::<String>("limit-rate").ok().flatten();
+    let limit = match flag {
+        Some(raw) => Some(parse_rate(raw)?),
+        None => config.clone_settings.as_ref().and_then(|c| c.limit_rate),
+    };
+    set_limit(limit);
+    Ok(())
+}
+
+/// Parse a `--limit-rate` value in KB/s.
+pub fn parse_rate(raw: &str) -> Result<u64> {
+    match raw.trim().parse::<u64>() {
+        Ok(kbps) if kbps > 0 => Ok(kbps),
+        _ => Err(anyhow::anyhow!(
+            "Invalid --limit-rate value '{}': expected a positive number of KB/s",
+            raw
+        )),
+    }
+}
+
+/// Set (or with `None` or 0, clear) the limit in KB/s.
+pub fn set_limit(kbps: Option<u64>) {
+    LIMIT_KBPS.store(kbps.unwrap_or(0), Ordering::Relaxed);
+    *BUDGET.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// The limit in KB/s, if one is set.
+pub fn limit() -> Option<u64> {
+    Some(LIMIT_KBPS.load(Ordering::Relaxed)).filter(|&kbps| kbps > 0)
+}
+
+/// How long to wait so that `received` bytes after `elapsed` stay within
+/// `kbps`.
+fn pause(received: u64, elapsed: Duration, kbps: u64) -> Duration {
+    let allowed = Duration::from_secs_f64(received as f64 / (kbps as f64 * 1024.0));
+    allowed.saturating_sub(elapsed)
+}
+
+/// Count `bytes` more against the shared budget and return how long to wait.
+fn record(bytes: u64, kbps: u64) -> Duration {
+    let mut budget = BUDGET.lock().unwrap_or_else(|e| e.into_inner());
+    let (start, total) = budget.get_or_insert_with(|| (Instant::now(), 0));
+    *total += bytes;
+    pause(*total, start.elapsed(), kbps)
+}
+
+/// Throttles one transfer. libgit2 allows a single transfer-progress
+/// callback, so [`super::transfer::apply`] drives this from the one it
+/// installs.
+pub struct Throttle {
+    kbps: u64,
+    /// Bytes of this transfer already counted against the budget.
+    seen: u64,
+}
+
+impl Throttle {
+    /// A throttle for a new transfer, or `None` when no limit is set.
+    pub fn new() -> Option<Self> {
+        limit().map(|kbps| Throttle { kbps, seen: 0 })
+    }
+
+    /// The transfer has `received` bytes in total: wait as long as the
+    /// limit requires.
+    pub fn received(&mut self, received: u64) {
+        let wait = record(received.saturating_sub(self.seen), self.kbps);
+        self.seen = received;
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_until_the_rate_catches_up() {
+        // 512 KB at 256 KB/s takes two seconds; after one, wait another.
+        assert_eq!(
+            pause(512 * 1024, Duration::from_secs(1), 256),
+            Duration::from_secs(1)
+        );
+        assert!(pause(512 * 1024, Duration::from_secs(3), 256).is_zero());
+        assert_eq!(parse_rate("500").unwrap(), 500);
+        assert!(parse_rate("0").is_err());
+        assert!(parse_rate("fast").is_err());
+    }
+}
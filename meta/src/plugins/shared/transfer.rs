@@ -0,0 +1,256 @@
+//! Progress of clones and fetches made through libgit2.
+//!
+//! [`apply`] installs the one transfer-progress callback libgit2 allows on a
+//! set of remote callbacks, and uses it both for the `--limit-rate` throttle
+//! and for reporting. On a terminal the transfer redraws a single line in
+//! place (objects received, bytes, then deltas resolved) and clears it when
+//! done; with stdout redirected it prints a plain line every few seconds, so
+//! CI logs show a long clone is alive without filling up with redraws.
+//!
+//! Commands that run transfers side by side draw their own aggregate
+//! progress and turn the per-transfer line off with [`without_bars`].
+
+use colored::Colorize;
+use git2::RemoteCallbacks;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use super::throttle;
+
+/// How often a terminal line is redrawn.
+const BAR_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often a plain progress line is printed when stdout is not a terminal.
+const LINE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Callers currently inside [`without_bars`].
+static BARS_OFF: AtomicUsize = AtomicUsize::new(0);
+
+/// How a transfer reports progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Redraw one line in place.
+    Bar,
+    /// Print a line every [`LINE_INTERVAL`].
+    Lines,
+    /// Report nothing.
+    Off,
+}
+
+impl Style {
+    /// The style for the current process: lines when stdout is redirected,
+    /// else a bar unless [`without_bars`] is in effect.
+    pub fn current() -> Self {
+        if !std::io::stdout().is_terminal() {
+            Style::Lines
+        } else if BARS_OFF.load(Ordering::Relaxed) > 0 {
+            Style::Off
+        } else {
+            Style::Bar
+        }
+    }
+}
+
+/// Run `f` with the per-transfer terminal line turned off, for transfers
+/// that run side by side under a progress display of the caller's own.
+pub fn without_bars<R>(f: impl FnOnce() -> R) -> R {
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            BARS_OFF.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+    BARS_OFF.fetch_add(1, Ordering::Relaxed);
+    let _guard = Guard;
+    f()
+}
+
+/// The counters of a transfer, copied out of libgit2's progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    pub total_objects: usize,
+    pub received_objects: usize,
+    pub received_bytes: usize,
+    pub total_deltas: usize,
+    pub indexed_deltas: usize,
+}
+
+impl From<&git2::Progress<'_>> for Snapshot {
+    fn from(stats: &git2::Progress<'_>) -> Self {
+        Snapshot {
+            total_objects: stats.total_objects(),
+            received_objects: stats.received_objects(),
+            received_bytes: stats.received_bytes(),
+            total_deltas: stats.total_deltas(),
+            indexed_deltas: stats.indexed_deltas(),
+        }
+    }
+}
+
+fn percent(done: usize, total: usize) -> usize {
+    (done * 100).checked_div(total).unwrap_or(0)
+}
+
+/// `bytes` in the largest unit that keeps it above 1.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// One line describing where a transfer is: receiving objects until all
+/// have arrived, then resolving deltas.
+pub fn describe(stats: &Snapshot) -> String {
+    let received = format!(
+        "{}/{} objects ({}%), {}",
+        stats.received_objects,
+        stats.total_objects,
+        percent(stats.received_objects, stats.total_objects),
+        format_bytes(stats.received_bytes)
+    );
+    if stats.total_deltas > 0 && stats.received_objects == stats.total_objects {
+        format!(
+            "{}, resolving deltas {}/{} ({}%)",
+            received,
+            stats.indexed_deltas,
+            stats.total_deltas,
+            percent(stats.indexed_deltas, stats.total_deltas)
+        )
+    } else {
+        format!("receiving {}", received)
+    }
+}
+
+/// Reports one transfer's progress in a [`Style`].
+pub struct Reporter {
+    label: String,
+    style: Style,
+    started: Instant,
+    last: Option<Instant>,
+    /// A terminal line is showing and needs clearing.
+    drawn: bool,
+}
+
+impl Reporter {
+    pub fn new(label: &str, style: Style) -> Self {
+        Self {
+            label: label.to_string(),
+            style,
+            started: Instant::now(),
+            last: None,
+            drawn: false,
+        }
+    }
+
+    /// Whether enough time has passed to report again. Lines wait a full
+    /// interval before the first one, so short transfers print nothing.
+    fn due(&self, now: Instant) -> bool {
+        match self.style {
+            Style::Off => false,
+            Style::Bar => self
+                .last
+                .is_none_or(|last| now.duration_since(last) >= BAR_INTERVAL),
+            Style::Lines => now.duration_since(self.last.unwrap_or(self.started)) >= LINE_INTERVAL,
+        }
+    }
+
+    pub fn update(&mut self, stats: &Snapshot) {
+        let now = Instant::now();
+        if !self.due(now) {
+            return;
+        }
+        self.last = Some(now);
+        let text = describe(stats);
+        let mut stdout = std::io::stdout().lock();
+        match self.style {
+            Style::Bar => {
+                let _ = write!(stdout, "\r\x1b[K  {} {}", self.label.bright_white(), text);
+                let _ = stdout.flush();
+                self.drawn = true;
+            }
+            Style::Lines => {
+                let _ = writeln!(stdout, "  {}: {}", self.label, text);
+            }
+            Style::Off => {}
+        }
+    }
+}
+
+impl Drop for Reporter {
+    fn drop(&mut self) {
+        if self.drawn {
+            print!("\r\x1b[K");
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
+
+/// Report progress of transfers made with `callbacks` under `label` (the
+/// project or repository name), and throttle them when a limit is set.
+pub fn apply(callbacks: &mut RemoteCallbacks<'_>, label: &str) {
+    let mut throttle = throttle::Throttle::new();
+    let mut reporter = Reporter::new(label, Style::current());
+    callbacks.transfer_progress(move |stats| {
+        if let Some(throttle) = throttle.as_mut() {
+            throttle.received(stats.received_bytes() as u64);
+        }
+        reporter.update(&Snapshot::from(&stats));
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_objects_then_deltas() {
+        let mut stats = Snapshot {
+            total_objects: 200,
+            received_objects: 50,
+            received_bytes: 3 * 1024 * 1024 + 512 * 1024,
+            total_deltas: 0,
+            indexed_deltas: 0,
+        };
+        assert_eq!(describe(&stats), "receiving 50/200 objects (25%), 3.5 MiB");
+
+        stats.received_objects = 200;
+        stats.total_deltas = 40;
+        stats.indexed_deltas = 10;
+        assert_eq!(
+            describe(&stats),
+            "200/200 objects (100%), 3.5 MiB, resolving deltas 10/40 (25%)"
+        );
+        // Nothing advertised yet.
+        assert_eq!(
+            describe(&Snapshot::default()),
+            "receiving 0/0 objects (0%), 0 B"
+        );
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(2048), "2.0 KiB");
+    }
+
+    #[test]
+    fn lines_wait_a_full_interval_and_bars_can_be_turned_off() {
+        let lines = Reporter::new("api", Style::Lines);
+        assert!(!lines.due(lines.started));
+        assert!(lines.due(lines.started + LINE_INTERVAL));
+        let bar = Reporter::new("api", Style::Bar);
+        assert!(bar.due(bar.started));
+        assert!(!Reporter::new("api", Style::Off).due(Instant::now()));
+
+        without_bars(|| {
+            assert_ne!(Style::current(), Style::Bar);
+        });
+    }
+}
@@ -0,0 +1,263 @@
+//! Version-control backends behind one trait, so projects checked out with
+//! Jujutsu, Mercurial, or Sapling (`"vcs": "jj"` etc. in `.meta`) can be
+//! cloned, listed, and shown by `meta status` next to git ones.
+//!
+//! Git stays the fully featured backend: worktrees, pins, vendoring, and the
+//! `meta git` commands are git-only. The other backends shell out to their
+//! CLI for the handful of operations here.
+
+use super::clone_with_auth;
+use anyhow::{anyhow, Context, Result};
+use metarepo_core::Vcs;
+use std::path::Path;
+use std::process::Command;
+
+/// The state of a working copy, as far as every backend can tell it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcsStatus {
+    /// Current branch or bookmark, else a short revision id.
+    pub branch: String,
+    /// Changed (and untracked) files.
+    pub dirty: usize,
+}
+
+/// Operations every VCS backend provides.
+pub trait VcsBackend: Sync {
+    fn vcs(&self) -> Vcs;
+    /// Clone `url` into `path`, which must not exist yet.
+    fn clone_repo(&self, url: &str, path: &Path) -> Result<()>;
+    fn status(&self, path: &Path) -> Result<VcsStatus>;
+    /// Bring in upstream changes, updating the working copy where the VCS
+    /// does that on pull.
+    fn pull(&self, path: &Path) -> Result<()>;
+    /// Create a branch (a bookmark, outside git) at the working copy.
+    fn create_branch(&self, path: &Path, name: &str) -> Result<()>;
+}
+
+/// The backend for `vcs`.
+pub fn backend(vcs: Vcs) -> &'static dyn VcsBackend {
+    match vcs {
+        Vcs::Git => &Git,
+        Vcs::Jj => &Jujutsu,
+        Vcs::Hg => &MERCURIAL,
+        Vcs::Sapling => &SAPLING,
+    }
+}
+
+/// The VCS of the checkout at `path`, from its marker directory. A Jujutsu
+/// repository colocated with git has both `.jj` and `.git`; jj owns it.
+pub fn detect(path: &Path) -> Option<Vcs> {
+    [Vcs::Jj, Vcs::Sapling, Vcs::Hg, Vcs::Git]
+        .into_iter()
+        .find(|vcs| path.join(vcs.marker()).exists())
+}
+
+/// Run `program args` (in `dir`, when given) and return its trimmed stdout.
+fn run(program: &str, dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut cmd = Command::new(program);
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    let output = match cmd.args(args).output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(anyhow!("{} is not installed or not on PATH", program));
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to run {}", program)),
+    };
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} {} failed: {}",
+            program,
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn count_lines(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count()
+}
+
+fn path_arg(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| anyhow!("Path is not valid UTF-8: {}", path.display()))
+}
+
+struct Git;
+
+impl VcsBackend for Git {
+    fn vcs(&self) -> Vcs {
+        Vcs::Git
+    }
+
+    fn clone_repo(&self, url: &str, path: &Path) -> Result<()> {
+        clone_with_auth(url, path, false, None).map(|_| ())
+    }
+
+    fn status(&self, path: &Path) -> Result<VcsStatus> {
+        let mut branch = run("git", Some(path), &["rev-parse", "--abbrev-ref", "HEAD"])?;
+        if branch == "HEAD" {
+            branch = run("git", Some(path), &["rev-parse", "--short", "HEAD"])?;
+        }
+        let changes = run("git", Some(path), &["status", "--porcelain"])?;
+        Ok(VcsStatus {
+            branch,
+            dirty: count_lines(&changes),
+        })
+    }
+
+    fn pull(&self, path: &Path) -> Result<()> {
+        run("git", Some(path), &["pull", "--ff-only", "--quiet"]).map(|_| ())
+    }
+
+    fn create_branch(&self, path: &Path, name: &str) -> Result<()> {
+        run("git", Some(path), &["branch", name]).map(|_| ())
+    }
+}
+
+struct Jujutsu;
+
+impl VcsBackend for Jujutsu {
+    fn vcs(&self) -> Vcs {
+        Vcs::Jj
+    }
+
+    fn clone_repo(&self, url: &str, path: &Path) -> Result<()> {
+        run("jj", None, &["git", "clone", url, path_arg(path)?]).map(|_| ())
+    }
+
+    fn status(&self, path: &Path) -> Result<VcsStatus> {
+        let log = |template: &str| {
+            run(
+                "jj",
+                Some(path),
+                &["log", "-r", "@", "--no-graph", "-T", template],
+            )
+        };
+        // The working-copy commit rarely carries a bookmark; show its
+        // change id when it has none.
+        let branch = match log("bookmarks") {
+            Ok(bookmarks) if !bookmarks.is_empty() => bookmarks,
+            _ => log("change_id.short()")?,
+        };
+        let changes = run("jj", Some(path), &["diff", "--summary"])?;
+        Ok(VcsStatus {
+            branch,
+            dirty: count_lines(&changes),
+        })
+    }
+
+    fn pull(&self, path: &Path) -> Result<()> {
+        run("jj", Some(path), &["git", "fetch"]).map(|_| ())
+    }
+
+    fn create_branch(&self, path: &Path, name: &str) -> Result<()> {
+        run("jj", Some(path), &["bookmark", "create", name, "-r", "@"]).map(|_| ())
+    }
+}
+
+/// Mercurial and Sapling share a command set; they differ in the program
+/// and in how pull moves the working copy.
+struct Mercurial {
+    vcs: Vcs,
+    program: &'static str,
+    pull: &'static [&'static str],
+}
+
+const MERCURIAL: Mercurial = Mercurial {
+    vcs: Vcs::Hg,
+    program: "hg",
+    pull: &["pull", "--update"],
+};
+
+const SAPLING: Mercurial = Mercurial {
+    vcs: Vcs::Sapling,
+    program: "sl",
+    pull: &["pull"],
+};
+
+impl VcsBackend for Mercurial {
+    fn vcs(&self) -> Vcs {
+        self.vcs
+    }
+
+    fn clone_repo(&self, url: &str, path: &Path) -> Result<()> {
+        run(self.program, None, &["clone", url, path_arg(path)?]).map(|_| ())
+    }
+
+    fn status(&self, path: &Path) -> Result<VcsStatus> {
+        let log = |template: &str| {
+            run(
+                self.program,
+                Some(path),
+                &["log", "-r", ".", "-T", template],
+            )
+        };
+        let branch = match log("{activebookmark}")? {
+            bookmark if bookmark.is_empty() => log("{node|short}")?,
+            bookmark => bookmark,
+        };
+        let changes = run(self.program, Some(path), &["status"])?;
+        Ok(VcsStatus {
+            branch,
+            dirty: count_lines(&changes),
+        })
+    }
+
+    fn pull(&self, path: &Path) -> Result<()> {
+        run(self.program, Some(path), self.pull).map(|_| ())
+    }
+
+    fn create_branch(&self, path: &Path, name: &str) -> Result<()> {
+        run(self.program, Some(path), &["bookmark", name]).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_vcs_from_marker_directories() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        assert_eq!(detect(dir), None);
+        std::fs::create_dir(dir.join(".git")).unwrap();
+        assert_eq!(detect(dir), Some(Vcs::Git));
+        // A colocated jj repository belongs to jj.
+        std::fs::create_dir(dir.join(".jj")).unwrap();
+        assert_eq!(detect(dir), Some(Vcs::Jj));
+        for vcs in Vcs::ALL {
+            assert_eq!(backend(vcs).vcs(), vcs);
+        }
+    }
+
+    #[test]
+    fn git_status_counts_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let git = |args: &[&str]| run("git", Some(dir), args).unwrap();
+        git(&["init", "-q", "-b", "trunk"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        std::fs::write(dir.join("b.txt"), "b").unwrap();
+        assert_eq!(
+            backend(Vcs::Git).status(dir).unwrap(),
+            VcsStatus {
+                branch: "trunk".to_string(),
+                dirty: 2,
+            }
+        );
+
+        git(&["switch", "-q", "--detach"]);
+        let head = git(&["rev-parse", "--short", "HEAD"]);
+        assert_eq!(backend(Vcs::Git).status(dir).unwrap().branch, head);
+    }
+}
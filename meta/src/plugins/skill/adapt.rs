@@ -166,7 +166,7 @@ fn backup_dir(skill_dir: &Path) -> PathBuf {
 
 /// Locate `cmd` on `PATH`, returning its full path. An absolute/relative path
 /// that exists is accepted as-is.
-fn which(cmd: &str) -> Option<PathBuf> {
+pub(crate) fn which(cmd: &str) -> Option<PathBuf> {
     let p = Path::new(cmd);
     if p.is_absolute() || cmd.contains('/') {
         return p.is_file().then(|| p.to_path_buf());
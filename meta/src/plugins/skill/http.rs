@@ -1,51 +1,17 @@
-//! Minimal HTTP GET via the system `curl`.
+//! Minimal HTTP GET for the skills.sh registry.
 //!
-//! The skills.sh registry is the only place this plugin fetches JSON over the
-//! network, so rather than pull an async HTTP stack into the binary we shell out
-//! to `curl`, which is ubiquitous on developer machines. Keep this surface tiny.
+//! Requests go through the shared rate-limited client in
+//! [`metarepo_core::api`], which shells out to `curl` rather than pulling an
+//! async HTTP stack into the binary. Keep this surface tiny.
 
-use anyhow::{anyhow, Context, Result};
-use std::process::Command;
+use anyhow::Result;
+use metarepo_core::api;
 
 /// GET `url`, returning the response body. When `bearer` is set it is sent as an
 /// `Authorization: Bearer` header. Non-2xx responses are turned into errors that
-/// include the response body (curl `--fail-with-body`).
+/// include the response body.
 pub fn get(url: &str, bearer: Option<&str>) -> Result<String> {
-    let mut cmd = Command::new("curl");
-    cmd.args([
-        "-sS",
-        "--fail-with-body",
-        "-m",
-        "30",
-        "-H",
-        "Accept: application/json",
-    ]);
-    if let Some(token) = bearer {
-        cmd.arg("-H").arg(format!("Authorization: Bearer {token}"));
-    }
-    cmd.arg(url);
-
-    let out = match cmd.output() {
-        Ok(o) => o,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return Err(anyhow!(
-                "curl is required to reach skills.sh but was not found on PATH"
-            ));
-        }
-        Err(e) => return Err(e).context("running curl"),
-    };
-
-    if !out.status.success() {
-        let body = String::from_utf8_lossy(&out.stdout);
-        let errs = String::from_utf8_lossy(&out.stderr);
-        return Err(anyhow!(
-            "request to {} failed: {}{}",
-            url,
-            body.trim(),
-            errs.trim()
-        ));
-    }
-    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    api::client().get(url, bearer)
 }
 
 /// Percent-encode a string for use in a URL query value (RFC 3986 unreserved set
@@ -8,6 +8,8 @@ use colored::Colorize;
 use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
 use std::path::{Path, PathBuf};
 
+use crate::plugins::shared::keychain;
+
 /// Resolve the bundled meta-tool skill's install directory. When `[skill] dest`
 /// is configured it installs at `<dest>/meta-tool` (tilde-expanded), matching
 /// where stolen skills land; otherwise it falls back to the workspace default
@@ -81,7 +83,8 @@ fn resolved_search_limit(flag: Option<usize>, config: &RuntimeConfig) -> usize {
     .unwrap_or(25)
 }
 
-/// skills.sh API key. Precedence: `SKILLS_SH_API_KEY` env > `[skill] api-key`.
+/// skills.sh API key. Precedence: `SKILLS_SH_API_KEY` env, then `[skill]
+/// api-key`, then the OS keychain (`meta auth login skills`).
 /// Env wins so secrets need not live in `.meta`.
 fn resolved_api_key(config: &RuntimeConfig) -> Option<String> {
     if let Ok(k) = std::env::var("SKILLS_SH_API_KEY") {
@@ -94,6 +97,7 @@ fn resolved_api_key(config: &RuntimeConfig) -> Option<String> {
         .skill
         .as_ref()
         .and_then(|s| s.api_key.clone())
+        .or_else(|| keychain::get("skills.sh"))
 }
 
 /// Print the resolved `[skill]` configuration under `meta skill locations`.
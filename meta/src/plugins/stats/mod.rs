@@ -0,0 +1,362 @@
+//! `meta stats` — activity across the workspace's repositories.
+//!
+//! `meta stats contributors` counts who committed to which project over a
+//! time window and flags the bus-factor risks: projects with one active
+//! contributor. Authors are read through git's mailmap (`%aN`/`%aE`), so a
+//! project's `.mailmap` folds one person's old names and emails together.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use metarepo_core::{Align, Table};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::plugins::shared::state;
+
+pub use self::plugin::StatsPlugin;
+
+mod plugin;
+
+/// Window used when no `--since` is given.
+pub const DEFAULT_SINCE: &str = "90 days ago";
+
+/// Separates the fields of one `--format` line.
+const FIELD_SEP: char = '\x1f';
+
+/// One author's commits to one project in the window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Contribution {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    /// Unix seconds of the author's newest commit.
+    pub last_commit: u64,
+}
+
+/// The active contributors of one project, most commits first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectActivity {
+    pub project: String,
+    pub contributors: Vec<Contribution>,
+}
+
+impl ProjectActivity {
+    pub fn commits(&self) -> usize {
+        self.contributors.iter().map(|c| c.commits).sum()
+    }
+
+    /// One active contributor: the project depends on a single person.
+    pub fn single_contributor(&self) -> bool {
+        self.contributors.len() == 1
+    }
+}
+
+/// One author's activity across all projects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Contributor {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub projects: Vec<String>,
+    pub last_commit: u64,
+}
+
+/// Everything `meta stats contributors` reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContributorReport {
+    pub since: String,
+    pub contributors: Vec<Contributor>,
+    pub projects: Vec<ProjectActivity>,
+    /// Projects with exactly one active contributor.
+    pub single_contributor: Vec<String>,
+    /// Projects without commits in the window.
+    pub inactive: Vec<String>,
+}
+
+/// Group `git log` lines (`name, email, timestamp`) into contributions, most
+/// commits first. Emails compare case-insensitively.
+fn parse_contributions(log: &str) -> Vec<Contribution> {
+    let mut by_email: BTreeMap<String, Contribution> = BTreeMap::new();
+    for line in log.lines() {
+        let mut fields = line.splitn(3, FIELD_SEP);
+        let (Some(name), Some(email), Some(Ok(timestamp))) = (
+            fields.next(),
+            fields.next(),
+            fields.next().map(str::parse::<u64>),
+        ) else {
+            continue;
+        };
+        let entry = by_email
+            .entry(email.to_lowercase())
+            .or_insert_with(|| Contribution {
+                name: name.to_string(),
+                email: email.to_string(),
+                commits: 0,
+                last_commit: 0,
+            });
+        entry.commits += 1;
+        entry.last_commit = entry.last_commit.max(timestamp);
+    }
+    let mut contributions: Vec<Contribution> = by_email.into_values().collect();
+    contributions.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.name.cmp(&b.name)));
+    contributions
+}
+
+/// The contributions to the repository at `path` since `since` (any date
+/// `git log --since` accepts). A repository without commits has none.
+pub fn project_contributions(path: &Path, since: &str) -> Result<Vec<Contribution>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["log", "--use-mailmap"])
+        .arg(format!("--format=%aN{0}%aE{0}%at", FIELD_SEP))
+        .arg(format!("--since={}", since))
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // An unborn branch has no history yet.
+        if stderr.contains("does not have any commits") {
+            return Ok(Vec::new());
+        }
+        return Err(anyhow::anyhow!("{}", stderr.trim()));
+    }
+    Ok(parse_contributions(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Combine per-project activity into the report.
+pub fn build_report(since: &str, activity: Vec<ProjectActivity>) -> ContributorReport {
+    let mut people: BTreeMap<String, Contributor> = BTreeMap::new();
+    for project in &activity {
+        for c in &project.contributors {
+            let person = people
+                .entry(c.email.to_lowercase())
+                .or_insert_with(|| Contributor {
+                    name: c.name.clone(),
+                    email: c.email.clone(),
+                    commits: 0,
+                    projects: Vec::new(),
+                    last_commit: 0,
+                });
+            person.commits += c.commits;
+            person.projects.push(project.project.clone());
+            person.last_commit = person.last_commit.max(c.last_commit);
+        }
+    }
+    let mut contributors: Vec<Contributor> = people.into_values().collect();
+    contributors.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.name.cmp(&b.name)));
+
+    let single_contributor = activity
+        .iter()
+        .filter(|p| p.single_contributor())
+        .map(|p| p.project.clone())
+        .collect();
+    let inactive = activity
+        .iter()
+        .filter(|p| p.contributors.is_empty())
+        .map(|p| p.project.clone())
+        .collect();
+    ContributorReport {
+        since: since.to_string(),
+        contributors,
+        projects: activity,
+        single_contributor,
+        inactive,
+    }
+}
+
+/// Quote a CSV field when it needs it.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The report as CSV, one row per project and contributor.
+pub fn render_csv(report: &ContributorReport) -> String {
+    let mut out = String::from("project,name,email,commits,last_commit\n");
+    for project in &report.projects {
+        for c in &project.contributors {
+            out.push_str(&format!(
+                "{},{},{},{},{}\n",
+                csv_field(&project.project),
+                csv_field(&c.name),
+                csv_field(&c.email),
+                c.commits,
+                state::format_timestamp(c.last_commit)
+            ));
+        }
+    }
+    out
+}
+
+/// Print the report as tables: contributors, then projects with their
+/// bus-factor flags.
+pub fn print_report(report: &ContributorReport) {
+    println!(
+        "\n  {} {} {}",
+        "👥".cyan(),
+        "Contributors".bold(),
+        format!("(since {})", report.since).bright_black()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    if report.contributors.is_empty() {
+        println!("  No commits in {} project(s).", report.projects.len());
+        return;
+    }
+    let mut table = Table::new(["Contributor", "Commits", "Projects", "Last commit"])
+        .align(1, Align::Right)
+        .align(2, Align::Right);
+    for c in &report.contributors {
+        table.row([
+            format!("{} <{}>", c.name, c.email),
+            c.commits.to_string(),
+            c.projects.len().to_string(),
+            state::format_timestamp(c.last_commit),
+        ]);
+    }
+    table.indent(2).print();
+
+    println!("\n  {}", "Projects".bold());
+    println!("  {}", "─".repeat(60).bright_black());
+    let mut table = Table::new(["Project", "Commits", "Contributors", "Top contributor"])
+        .align(1, Align::Right)
+        .align(2, Align::Right);
+    for project in &report.projects {
+        let top = project
+            .contributors
+            .first()
+            .map(|c| c.name.clone())
+            .unwrap_or_else(|| "-".to_string());
+        let count = project.contributors.len().to_string();
+        table.row([
+            project.project.clone(),
+            project.commits().to_string(),
+            if project.single_contributor() {
+                count.yellow().to_string()
+            } else {
+                count
+            },
+            top,
+        ]);
+    }
+    table.indent(2).print();
+
+    if !report.single_contributor.is_empty() {
+        println!(
+            "\n  {} Bus-factor risk: one active contributor in {}",
+            "⚠".yellow(),
+            report.single_contributor.join(", ")
+        );
+    }
+    if !report.inactive.is_empty() {
+        println!(
+            "  {} No commits in the window: {}",
+            "·".bright_black(),
+            report.inactive.join(", ")
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(project: &str, people: &[(&str, usize)]) -> ProjectActivity {
+        ProjectActivity {
+            project: project.to_string(),
+            contributors: people
+                .iter()
+                .map(|(name, commits)| Contribution {
+                    name: name.to_string(),
+                    email: format!("{}@example.com", name.to_lowercase()),
+                    commits: *commits,
+                    last_commit: 100,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn contributions_group_by_email_case_insensitively() {
+        let log = "Ada\u{1f}ada@example.com\u{1f}200\n\
+                   Bob\u{1f}bob@example.com\u{1f}150\n\
+                   Ada\u{1f}ADA@example.com\u{1f}300\n";
+        let found = parse_contributions(log);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].name, "Ada");
+        assert_eq!(found[0].commits, 2);
+        assert_eq!(found[0].last_commit, 300);
+    }
+
+    #[test]
+    fn report_flags_single_contributor_and_inactive_projects() {
+        let report = build_report(
+            DEFAULT_SINCE,
+            vec![
+                activity("api", &[("Ada", 3), ("Bob", 1)]),
+                activity("web", &[("Ada", 2)]),
+                activity("docs", &[]),
+            ],
+        );
+        assert_eq!(report.single_contributor, ["web"]);
+        assert_eq!(report.inactive, ["docs"]);
+        assert_eq!(report.contributors[0].name, "Ada");
+        assert_eq!(report.contributors[0].commits, 5);
+        assert_eq!(report.contributors[0].projects, ["api", "web"]);
+
+        let csv = render_csv(&report);
+        assert!(csv.starts_with("project,name,email,commits,last_commit\n"));
+        assert_eq!(csv.lines().count(), 4);
+    }
+
+    #[test]
+    fn mailmap_folds_old_identities() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        assert!(project_contributions(dir, DEFAULT_SINCE)
+            .unwrap()
+            .is_empty());
+
+        for author in ["Ada <ada@old.example.com>", "Ada L <ada@example.com>"] {
+            git(&[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "--author",
+                author,
+                "-m",
+                "c",
+            ]);
+        }
+        assert_eq!(project_contributions(dir, DEFAULT_SINCE).unwrap().len(), 2);
+
+        std::fs::write(
+            dir.join(".mailmap"),
+            "Ada L <ada@example.com> <ada@old.example.com>\n",
+        )
+        .unwrap();
+        let found = project_contributions(dir, DEFAULT_SINCE).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "Ada L");
+        assert_eq!(found[0].commits, 2);
+    }
+}
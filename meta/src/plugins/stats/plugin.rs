@@ -0,0 +1,185 @@
+//! Plugin wiring for `meta stats`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{
+    build_report, print_report, project_contributions, render_csv, ProjectActivity, DEFAULT_SINCE,
+};
+use crate::plugins::shared::selection;
+
+/// Registers the top-level `meta stats` command.
+pub struct StatsPlugin;
+
+impl StatsPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for StatsPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for StatsPlugin {
+    fn name(&self) -> &str {
+        "stats"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("stats")
+                .about("Report activity across the workspace's projects")
+                .version(env!("CARGO_PKG_VERSION"))
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("contributors")
+                        .about("Show who committed to which project, and single-contributor risks")
+                        .version(env!("CARGO_PKG_VERSION"))
+                        .after_long_help(metarepo_core::format_help_description(
+                            "Count the commits of each author in every project in your directory\n\
+                             scope (or the named projects) since --since (default: 90 days ago),\n\
+                             then list the contributors across projects and each project's\n\
+                             active contributors. Projects with a single active contributor are\n\
+                             flagged as a bus-factor risk; projects without commits are listed\n\
+                             as inactive.\n\
+                             \n\
+                             Authors are read through git's mailmap, so a project's .mailmap\n\
+                             merges one person's names and emails. --json prints the full\n\
+                             report; --csv prints one row per project and contributor.\n\
+                             \n\
+                             Examples:\n  \
+                               meta stats contributors\n  \
+                               meta stats contributors --since \"1 year ago\" --group backend\n  \
+                               meta stats contributors --csv > contributors.csv\n",
+                        ))
+                        .arg(
+                            Arg::new(selection::TARGETS_ARG)
+                                .help("Projects to include (names, aliases, or globs)")
+                                .num_args(1..)
+                                .action(ArgAction::Append),
+                        )
+                        .arg(
+                            Arg::new("group")
+                                .long("group")
+                                .help("Select the members of a project group (repeatable)")
+                                .action(ArgAction::Append),
+                        )
+                        .arg(
+                            Arg::new("strict")
+                                .long("strict")
+                                .help("Fail if any project selector matches nothing")
+                                .action(ArgAction::SetTrue),
+                        )
+                        .arg(
+                            Arg::new("exclude")
+                                .long("exclude")
+                                .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                                .value_name("PROJECTS"),
+                        )
+                        .arg(
+                            Arg::new("since")
+                                .long("since")
+                                .value_name("DATE")
+                                .default_value(DEFAULT_SINCE)
+                                .help("Start of the window (any date git log --since accepts)"),
+                        )
+                        .arg(
+                            Arg::new("json")
+                                .long("json")
+                                .help("Print the report as JSON")
+                                .action(ArgAction::SetTrue)
+                                .conflicts_with("csv"),
+                        )
+                        .arg(
+                            Arg::new("csv")
+                                .long("csv")
+                                .help("Print one CSV row per project and contributor")
+                                .action(ArgAction::SetTrue),
+                        ),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        match matches.subcommand() {
+            Some(("contributors", sub)) => contributors(sub, config),
+            _ => Err(anyhow::anyhow!("Usage: meta stats contributors")),
+        }
+    }
+}
+
+fn contributors(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let since = matches
+        .get_one::<String>("since")
+        .expect("since has a default");
+
+    let explicit = selection::explicit_projects(matches, config)?;
+    let mut projects = match &explicit {
+        Some(selected) => selected.clone(),
+        None => config.scoped_project_keys(),
+    };
+    selection::apply_excludes(matches, config, &mut projects, explicit.is_some());
+    projects.sort();
+
+    let mut activity = Vec::new();
+    let mut failed = 0;
+    for project in &projects {
+        let path = base_path.join(project);
+        if !path.join(".git").exists() {
+            continue;
+        }
+        match project_contributions(&path, since) {
+            Ok(contributors) => activity.push(ProjectActivity {
+                project: project.clone(),
+                contributors,
+            }),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {} {}: {}", "✗".red(), project, e);
+            }
+        }
+    }
+    if activity.is_empty() && failed == 0 {
+        println!("No cloned projects in scope.");
+        return Ok(());
+    }
+
+    let report = build_report(since, activity);
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if matches.get_flag("csv") {
+        print!("{}", render_csv(&report));
+    } else {
+        print_report(&report);
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "could not read history in {} project(s)",
+            failed
+        ));
+    }
+    Ok(())
+}
+
+impl BasePlugin for StatsPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Report activity across the workspace's projects")
+    }
+}
@@ -15,7 +15,7 @@ use ratatui::{
 };
 use std::path::PathBuf;
 
-use super::{gather_all, RepoState, RepoStatus};
+use super::{gather, RepoState, RepoStatus};
 
 /// Read-only multi-repo status dashboard.
 pub struct Dashboard {
@@ -31,7 +31,7 @@ impl Dashboard {
     /// Build a dashboard for `projects` (keys relative to `base_path`),
     /// gathering their status immediately.
     pub fn new(base_path: PathBuf, projects: Vec<String>) -> Self {
-        let statuses = gather_all(&base_path, &projects);
+        let statuses = gather(&base_path, &projects, false);
         let tree_roots = Self::build_tree(&statuses);
         Self {
             base_path,
@@ -52,7 +52,7 @@ impl Dashboard {
             let mut node =
                 TreeNode::with_value(&s.name, s.state.summary(), format!("repo:{}", s.name));
             node.depth = 1;
-            if matches!(s.state, RepoState::Ok { dirty, .. } if dirty > 0)
+            if matches!(s.state, RepoState::Ok { dirty, submodules, .. } if dirty + submodules > 0)
                 || matches!(s.state, RepoState::Ok { behind, .. } if behind > 0)
             {
                 // Flag repos needing attention with the shared dirty marker.
@@ -66,7 +66,7 @@ impl Dashboard {
     /// Re-gather status for all projects and rebuild the tree, keeping the
     /// selection in range.
     fn refresh(&mut self) {
-        self.statuses = gather_all(&self.base_path, &self.projects);
+        self.statuses = gather(&self.base_path, &self.projects, true);
         self.tree_roots = Self::build_tree(&self.statuses);
         let count = self.tree_roots.iter().flat_map(|r| r.flatten(true)).count();
         if self.state.tree_state.selected >= count {
@@ -137,6 +137,7 @@ impl Dashboard {
                 ahead,
                 behind,
                 dirty,
+                submodules,
             } => {
                 let row = |label: &str, val: String| {
                     Line::from(vec![
@@ -155,6 +156,9 @@ impl Dashboard {
                         format!("{dirty} change(s)")
                     },
                 ));
+                if *submodules > 0 {
+                    lines.push(row("Submodules", format!("{submodules} with changes")));
+                }
             }
             other => lines.push(Line::from(Span::styled(
                 other.summary(),
@@ -410,6 +414,7 @@ mod tests {
                     ahead: 0,
                     behind: 0,
                     dirty: 0,
+                    submodules: 0,
                 },
             },
             RepoStatus {
@@ -419,6 +424,7 @@ mod tests {
                     ahead: 1,
                     behind: 2,
                     dirty: 3,
+                    submodules: 0,
                 },
             },
             RepoStatus {
@@ -3,9 +3,17 @@
 //! Gathers per-project git state (branch, ahead/behind vs upstream, dirty file
 //! count) and presents it in a navigable TUI built on the shared tree-shell
 //! primitives ([`metarepo_core::tui::tree_shell`]). Read-only in this version:
-//! navigate, search, and drill into a repo's detail; refresh with `r`.
+//! navigate, search, and drill into a repo's detail; refresh with `r`. When
+//! `meta daemon` is running, status comes from its cache instead of a fresh
+//! scan. Without a terminal (or with `--non-interactive`) the same status is
+//! printed as a table instead.
 
+use crate::plugins::shared::issues::IssueLinker;
+use crate::plugins::shared::{submodules, vcs};
 use git2::{Repository, StatusOptions};
+use metarepo_core::{Align, MetaConfig, Table, Vcs};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::path::Path;
 use std::process::Command;
 
@@ -22,9 +30,13 @@ pub(super) fn fetch(path: &Path) -> Result<(), String> {
 
 /// Fast-forward pull the selected repository (`git pull --ff-only`). Fails
 /// cleanly (surfaced in the status line) for bare or diverged repos rather than
-/// creating a merge commit.
+/// creating a merge commit. Other version-control systems pull through their
+/// backend.
 pub(super) fn pull(path: &Path) -> Result<(), String> {
-    run_git(path, &["pull", "--ff-only", "--quiet"])
+    match vcs::detect(path) {
+        Some(kind) if kind != Vcs::Git => vcs::backend(kind).pull(path).map_err(|e| e.to_string()),
+        _ => run_git(path, &["pull", "--ff-only", "--quiet"]),
+    }
 }
 
 /// Run `git -C <path> <args>`, returning the trimmed stderr on failure. Uses the
@@ -51,7 +63,7 @@ fn run_git(path: &Path, args: &[&str]) -> Result<(), String> {
 }
 
 /// The git state of one tracked project, as shown in the dashboard.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RepoStatus {
     /// Project key (its path under the workspace root).
     pub name: String,
@@ -60,7 +72,7 @@ pub struct RepoStatus {
 }
 
 /// The outcome of inspecting a project's directory.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RepoState {
     /// The project directory does not exist on disk.
     Missing,
@@ -78,6 +90,9 @@ pub enum RepoState {
         behind: usize,
         /// Number of changed working-tree/index entries (0 = clean).
         dirty: usize,
+        /// Initialized submodules with changes of their own.
+        #[serde(default)]
+        submodules: usize,
     },
 }
 
@@ -93,6 +108,7 @@ impl RepoState {
                 ahead,
                 behind,
                 dirty,
+                submodules,
             } => {
                 let mut parts = vec![branch.clone()];
                 if *ahead > 0 {
@@ -101,11 +117,15 @@ impl RepoState {
                 if *behind > 0 {
                     parts.push(format!("-{behind}"));
                 }
-                parts.push(if *dirty > 0 {
-                    format!("*{dirty}")
-                } else {
-                    "clean".to_string()
-                });
+                if *dirty > 0 {
+                    parts.push(format!("*{dirty}"));
+                }
+                if *submodules > 0 {
+                    parts.push(format!("~{submodules}"));
+                }
+                if *dirty == 0 && *submodules == 0 {
+                    parts.push("clean".to_string());
+                }
                 parts.join(" ")
             }
         }
@@ -123,11 +143,74 @@ pub fn gather_all(base_path: &Path, projects: &[String]) -> Vec<RepoStatus> {
         .collect()
 }
 
+/// Status for each project, from the workspace's daemon when one is running
+/// (`refresh` makes it rescan rather than answer from its cache), otherwise
+/// gathered directly.
+pub fn gather(base_path: &Path, projects: &[String], refresh: bool) -> Vec<RepoStatus> {
+    crate::plugins::daemon::cached_status(base_path, Some(projects), refresh)
+        .unwrap_or_else(|| gather_all(base_path, projects))
+}
+
+/// The status as a table: one row per project, with the counts right-aligned
+/// and left blank for projects that could not be read.
+pub fn status_table(statuses: &[RepoStatus]) -> Table {
+    let mut table = Table::new(["Project", "Branch", "Ahead", "Behind", "Changes"])
+        .align(2, Align::Right)
+        .align(3, Align::Right)
+        .align(4, Align::Right)
+        .shrink(1);
+    for status in statuses {
+        match &status.state {
+            RepoState::Ok {
+                branch,
+                ahead,
+                behind,
+                dirty,
+                submodules,
+            } => table.row([
+                status.name.clone(),
+                branch.clone(),
+                ahead.to_string(),
+                behind.to_string(),
+                match submodules {
+                    0 => dirty.to_string(),
+                    1 => format!("{dirty} (1 submodule)"),
+                    n => format!("{dirty} ({n} submodules)"),
+                },
+            ]),
+            other => table.row([status.name.clone(), other.summary()]),
+        }
+    }
+    table
+}
+
+/// The status as JSON: each project's name and state, plus the issue
+/// references found in its branch name (see `issue_trackers` in `.meta`).
+pub fn status_json(statuses: &[RepoStatus], config: &MetaConfig, linker: &IssueLinker) -> Value {
+    statuses
+        .iter()
+        .map(|status| {
+            let issues = match &status.state {
+                RepoState::Ok { branch, .. } => linker.references(config, &status.name, branch),
+                _ => Vec::new(),
+            };
+            json!({ "name": status.name, "state": status.state, "issues": issues })
+        })
+        .collect()
+}
+
 /// Inspect a single repository directory.
-fn gather_one(path: &Path) -> RepoState {
+pub fn gather_one(path: &Path) -> RepoState {
     if !path.exists() {
         return RepoState::Missing;
     }
+    // A jj repository colocated with git is read through jj: git's HEAD sits
+    // detached at the working-copy commit's parent and its index is jj's
+    // leftover, so git2 would misreport both the branch and the changes.
+    match vcs::detect(path) {
+        None | Some(Vcs::Git) => {}
+        Some(kind) => return gather_other_vcs(path, kind),
+    }
     let repo = match Repository::open(path) {
         Ok(r) => r,
         Err(_) => return RepoState::NotGit,
@@ -148,6 +231,22 @@ fn gather_one(path: &Path) -> RepoState {
         ahead,
         behind,
         dirty,
+        submodules: submodules::changes(path).len(),
+    }
+}
+
+/// State of a Jujutsu, Mercurial, or Sapling checkout, through its backend.
+/// Ahead/behind is a git notion and stays zero.
+fn gather_other_vcs(path: &Path, kind: Vcs) -> RepoState {
+    match vcs::backend(kind).status(path) {
+        Ok(status) => RepoState::Ok {
+            branch: status.branch,
+            ahead: 0,
+            behind: 0,
+            dirty: status.dirty,
+            submodules: 0,
+        },
+        Err(e) => RepoState::Error(e.to_string()),
     }
 }
 
@@ -219,6 +318,21 @@ mod tests {
         assert_eq!(gather_one(&tmp.path().join("plain")), RepoState::NotGit);
     }
 
+    #[test]
+    fn colocated_jj_repos_are_read_through_jj() {
+        let tmp = tempdir().unwrap();
+        let repo = tmp.path().join("r");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        std::fs::create_dir(repo.join(".jj")).unwrap();
+        // The stub .jj is no real store, so jj (installed or not) reports an
+        // error; git2 would have read the repository as clean.
+        match gather_one(&repo) {
+            RepoState::Error(e) => assert!(e.starts_with("jj "), "unexpected error: {e}"),
+            other => panic!("expected a jj error, got {other:?}"),
+        }
+    }
+
     #[test]
     fn clean_then_dirty_repo() {
         let tmp = tempdir().unwrap();
@@ -235,10 +349,12 @@ mod tests {
                 ahead,
                 behind,
                 dirty,
+                submodules,
             } => {
                 assert_eq!(branch, "main");
                 assert_eq!((ahead, behind), (0, 0));
                 assert_eq!(dirty, 0);
+                assert_eq!(submodules, 0);
             }
             other => panic!("expected clean Ok, got {other:?}"),
         }
@@ -257,7 +373,7 @@ mod tests {
         let bare = tmp.path().join("remote.git");
         git(
             tmp.path(),
-            &["init", "-q", "--bare", bare.to_str().unwrap()],
+            &["init", "-q", "-b", "main", "--bare", bare.to_str().unwrap()],
         );
 
         // Clone A, push an initial commit on main.
@@ -305,19 +421,96 @@ mod tests {
                 ahead: 2,
                 behind: 1,
                 dirty: 3,
+                submodules: 0,
             }
             .summary(),
             "main +2 -1 *3"
         );
+        assert_eq!(
+            RepoState::Ok {
+                branch: "main".into(),
+                ahead: 0,
+                behind: 0,
+                dirty: 0,
+                submodules: 2,
+            }
+            .summary(),
+            "main ~2"
+        );
         assert_eq!(
             RepoState::Ok {
                 branch: "dev".into(),
                 ahead: 0,
                 behind: 0,
                 dirty: 0,
+                submodules: 0,
             }
             .summary(),
             "dev clean"
         );
     }
+
+    #[test]
+    fn status_table_right_aligns_counts() {
+        let statuses = [
+            RepoStatus {
+                name: "api".into(),
+                state: RepoState::Ok {
+                    branch: "main".into(),
+                    ahead: 2,
+                    behind: 0,
+                    dirty: 11,
+                    submodules: 0,
+                },
+            },
+            RepoStatus {
+                name: "web".into(),
+                state: RepoState::Missing,
+            },
+        ];
+        let lines: Vec<String> = status_table(&statuses)
+            .max_width(None)
+            .render()
+            .iter()
+            .map(|line| console::strip_ansi_codes(line).into_owned())
+            .collect();
+        assert_eq!(lines[0], "Project  Branch     Ahead  Behind  Changes");
+        assert_eq!(lines[2], "api      main           2       0       11");
+        assert_eq!(lines[3], "web      (missing)");
+    }
+
+    #[test]
+    fn status_json_lists_issue_references_in_branches() {
+        let config = MetaConfig {
+            issue_trackers: vec![metarepo_core::IssueTracker {
+                pattern: r"ENG-\d+".into(),
+                url: "https://acme.atlassian.net/browse/{id}".into(),
+            }],
+            ..Default::default()
+        };
+        let linker = IssueLinker::from_config(&config);
+        let statuses = [
+            RepoStatus {
+                name: "api".into(),
+                state: RepoState::Ok {
+                    branch: "feat/ENG-42-login".into(),
+                    ahead: 0,
+                    behind: 0,
+                    dirty: 0,
+                    submodules: 0,
+                },
+            },
+            RepoStatus {
+                name: "web".into(),
+                state: RepoState::Missing,
+            },
+        ];
+        let value = status_json(&statuses, &config, &linker);
+        assert_eq!(
+            value[0]["issues"],
+            json!([{ "id": "ENG-42", "url": "https://acme.atlassian.net/browse/ENG-42" }])
+        );
+        assert_eq!(value[0]["state"]["Ok"]["branch"], "feat/ENG-42-login");
+        assert_eq!(value[1]["issues"], json!([]));
+    }
 }
@@ -1,10 +1,12 @@
 //! Plugin wiring for the `meta status` dashboard.
 
 use anyhow::Result;
-use clap::{ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
 
 use super::dashboard::Dashboard;
+use super::{gather, status_json, status_table, RepoState};
+use crate::plugins::shared::issues::IssueLinker;
 
 /// Registers the top-level `meta status` command.
 pub struct StatusPlugin;
@@ -39,14 +41,28 @@ impl MetaPlugin for StatusPlugin {
                      project to see its details on the right. The view is read-only: navigate\n\
                      with the arrow keys or j/k, press r to refresh, ? for help, and q to quit.\n\
                      \n\
+                     When stdout is not a terminal, or with --non-interactive, the same status\n\
+                     is printed once as a table instead. --json prints it as JSON.\n\
+                     \n\
+                     Issue references in branch names (matched by the issue_trackers list in\n\
+                     .meta) are shown as links in terminals that support them, and listed\n\
+                     under \"issues\" in the JSON output.\n\
+                     \n\
                      Examples:\n  \
                        meta status                 Dashboard for the whole workspace\n  \
-                       cd team/api && meta status  Dashboard scoped to the current directory\n",
-                )),
+                       cd team/api && meta status  Dashboard scoped to the current directory\n  \
+                       meta status --json          Status and issue links as JSON\n",
+                ))
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the status as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
         )
     }
 
-    fn handle_command(&self, _matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
         let base_path = config
             .meta_root()
             .unwrap_or_else(|| config.working_dir.clone());
@@ -55,6 +71,23 @@ impl MetaPlugin for StatusPlugin {
             println!("No projects in this workspace. Run 'meta project add' to track one.");
             return Ok(());
         }
+        let json = matches.get_flag("json");
+        if json || config.non_interactive.is_some() || !metarepo_core::is_interactive() {
+            let mut statuses = gather(&base_path, &projects, false);
+            let linker = IssueLinker::from_config(&config.meta_config);
+            if json {
+                let value = status_json(&statuses, &config.meta_config, &linker);
+                println!("{}", serde_json::to_string_pretty(&value)?);
+                return Ok(());
+            }
+            for status in &mut statuses {
+                if let RepoState::Ok { branch, .. } = &mut status.state {
+                    *branch = linker.linkify(&config.meta_config, &status.name, branch);
+                }
+            }
+            status_table(&statuses).print();
+            return Ok(());
+        }
         Dashboard::new(base_path, projects).run()
     }
 }
@@ -0,0 +1,273 @@
+//! Full-screen browser for `meta test report --tui`: a filterable table of
+//! failing tests on top and the selected failure's message and details below.
+//! The cursor/filter logic lives in [`BrowserState`] (unit-tested); the render
+//! and event loop wrap it.
+
+use super::junit::{Outcome, TestCase};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use metarepo_core::tui::{init_terminal, poll_event, restore_terminal};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::Frame;
+
+/// A failing test and the project it failed in.
+pub struct Failure {
+    pub project: String,
+    pub case: TestCase,
+}
+
+/// Cursor and filter state, independent of rendering so it can be unit-tested.
+pub struct BrowserState {
+    failures: Vec<Failure>,
+    /// Indices into `failures` visible under the filter.
+    visible: Vec<usize>,
+    /// Cursor position as an index into `visible`.
+    cursor: usize,
+    /// Case-insensitive substring matched against project, test, and message.
+    filter: String,
+    /// Lines the details pane is scrolled down by.
+    scroll: u16,
+}
+
+impl BrowserState {
+    pub fn new(failures: Vec<Failure>) -> Self {
+        let visible = (0..failures.len()).collect();
+        Self {
+            failures,
+            visible,
+            cursor: 0,
+            filter: String::new(),
+            scroll: 0,
+        }
+    }
+
+    /// The failure under the cursor, if any.
+    pub fn current(&self) -> Option<&Failure> {
+        self.visible.get(self.cursor).map(|&i| &self.failures[i])
+    }
+
+    fn move_to(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.visible.len().saturating_sub(1));
+        self.scroll = 0;
+    }
+
+    pub fn set_filter(&mut self, query: &str) {
+        self.filter = query.to_string();
+        let q = query.to_lowercase();
+        self.visible = self
+            .failures
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| {
+                q.is_empty()
+                    || f.project.to_lowercase().contains(&q)
+                    || f.case.label().to_lowercase().contains(&q)
+                    || f.case
+                        .message
+                        .as_deref()
+                        .is_some_and(|m| m.to_lowercase().contains(&q))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.move_to(self.cursor);
+    }
+
+    /// Handle a key press. Returns false when the browser should close.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Esc if self.filter.is_empty() => return false,
+            KeyCode::Esc => self.set_filter(""),
+            KeyCode::Down => self.move_to(self.cursor + 1),
+            KeyCode::Up => self.move_to(self.cursor.saturating_sub(1)),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(5),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(5),
+            KeyCode::Backspace => {
+                let mut f = self.filter.clone();
+                f.pop();
+                self.set_filter(&f);
+            }
+            KeyCode::Char(c) if !c.is_control() => {
+                let mut f = self.filter.clone();
+                f.push(c);
+                self.set_filter(&f);
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Run the browser until the user closes it.
+pub fn run(failures: Vec<Failure>) -> Result<()> {
+    let mut state = BrowserState::new(failures);
+    let mut terminal = init_terminal()?;
+    let result = run_loop(&mut terminal, &mut state);
+    restore_terminal(terminal)?;
+    result
+}
+
+fn run_loop(
+    terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<std::io::Stdout>>,
+    state: &mut BrowserState,
+) -> Result<()> {
+    let mut table_state = TableState::default();
+    loop {
+        table_state.select(Some(state.cursor));
+        terminal.draw(|f| draw(f, state, &mut table_state))?;
+        let Some(ev) = poll_event()? else {
+            continue;
+        };
+        if let Event::Key(k) = ev {
+            if k.kind == KeyEventKind::Press && !state.handle_key(k.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn draw(f: &mut Frame, state: &BrowserState, table_state: &mut TableState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),      // filter line (bordered)
+            Constraint::Percentage(45), // failures
+            Constraint::Min(5),         // details
+            Constraint::Length(1),      // hints
+        ])
+        .split(f.area());
+
+    let filter = Paragraph::new(Line::from(vec![
+        Span::styled("/", Style::default().fg(Color::DarkGray)),
+        Span::raw(state.filter.clone()),
+    ]))
+    .block(Block::default().borders(Borders::ALL).title(" filter "));
+    f.render_widget(filter, chunks[0]);
+
+    let rows: Vec<Row> = state
+        .visible
+        .iter()
+        .map(|&i| {
+            let failure = &state.failures[i];
+            let kind = match failure.case.outcome {
+                Outcome::Error => "error",
+                _ => "failed",
+            };
+            Row::new(vec![
+                Cell::from(failure.project.clone()).style(Style::default().fg(Color::Cyan)),
+                Cell::from(failure.case.label()),
+                Cell::from(kind).style(Style::default().fg(Color::Red)),
+            ])
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(20),
+            Constraint::Percentage(68),
+            Constraint::Percentage(12),
+        ],
+    )
+    .header(
+        Row::new(vec!["Project", "Test", "Outcome"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(format!(
+        " failing tests ({}/{}) ",
+        state.visible.len(),
+        state.failures.len()
+    )))
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    f.render_stateful_widget(table, chunks[1], table_state);
+
+    let mut lines = Vec::new();
+    if let Some(failure) = state.current() {
+        let case = &failure.case;
+        if !case.suite.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("suite: {}", case.suite),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        if let Some(message) = &case.message {
+            lines.push(Line::from(Span::styled(
+                message.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+        }
+        if let Some(details) = &case.details {
+            lines.push(Line::from(""));
+            lines.extend(details.lines().map(|l| Line::from(l.to_string())));
+        }
+    }
+    let details = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((state.scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(" details "));
+    f.render_widget(details, chunks[2]);
+
+    let hints = Paragraph::new(Line::from(Span::styled(
+        "type to filter · ↑/↓ move · pgup/pgdn scroll details · esc clear/quit",
+        Style::default().fg(Color::DarkGray),
+    )));
+    f.render_widget(hints, chunks[3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(project: &str, name: &str, message: &str) -> Failure {
+        Failure {
+            project: project.into(),
+            case: TestCase {
+                suite: String::new(),
+                classname: String::new(),
+                name: name.into(),
+                time: None,
+                outcome: Outcome::Failed,
+                message: Some(message.into()),
+                details: None,
+            },
+        }
+    }
+
+    fn state() -> BrowserState {
+        BrowserState::new(vec![
+            failure("api", "loads config", "bad path"),
+            failure("web", "renders", "timeout"),
+            failure("web", "loads config", "bad path"),
+        ])
+    }
+
+    #[test]
+    fn cursor_stays_in_bounds() {
+        let mut s = state();
+        s.handle_key(KeyCode::Up);
+        assert_eq!(s.current().unwrap().project, "api");
+        for _ in 0..5 {
+            s.handle_key(KeyCode::Down);
+        }
+        assert_eq!(s.current().unwrap().case.name, "loads config");
+        assert_eq!(s.current().unwrap().project, "web");
+    }
+
+    #[test]
+    fn typing_filters_on_project_test_and_message() {
+        let mut s = state();
+        for c in "time".chars() {
+            s.handle_key(KeyCode::Char(c));
+        }
+        assert_eq!(s.visible, [1]);
+        s.set_filter("API");
+        assert_eq!(s.visible, [0]);
+        s.set_filter("nothing");
+        assert!(s.current().is_none());
+        // Esc clears the filter, then closes.
+        assert!(s.handle_key(KeyCode::Esc));
+        assert_eq!(s.visible, [0, 1, 2]);
+        assert!(!s.handle_key(KeyCode::Esc));
+    }
+}
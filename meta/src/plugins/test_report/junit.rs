@@ -0,0 +1,339 @@
+//! A small reader for JUnit XML, the report format most test runners can
+//! write (surefire, pytest `--junitxml`, jest-junit, cargo2junit, go-junit-report).
+//!
+//! Only the parts triage needs are read: `<testsuite name>`, and each
+//! `<testcase>` with its `<failure>`, `<error>`, or `<skipped>` child. Everything
+//! else — properties, `<system-out>`, suite totals — is skipped.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+/// How a test case ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Error,
+    Skipped,
+}
+
+impl Outcome {
+    /// Whether this outcome needs attention.
+    pub fn is_failure(self) -> bool {
+        matches!(self, Outcome::Failed | Outcome::Error)
+    }
+}
+
+/// One `<testcase>`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestCase {
+    /// Name of the enclosing `<testsuite>`, if any.
+    pub suite: String,
+    pub classname: String,
+    pub name: String,
+    /// Duration in seconds.
+    pub time: Option<f64>,
+    pub outcome: Outcome,
+    /// The `message` attribute of the failure, error, or skip.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The failure's body, typically a stack trace or assertion diff.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl TestCase {
+    /// `classname › name`, or just the name.
+    pub fn label(&self) -> String {
+        if self.classname.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} › {}", self.classname, self.name)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Start {
+        name: &'a str,
+        attrs: Vec<(&'a str, String)>,
+        empty: bool,
+    },
+    End(&'a str),
+    Text(String),
+}
+
+/// Replace the predefined and numeric character references.
+fn decode(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Index of the `>` closing the tag that starts `tag`, skipping quoted values.
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attrs(mut rest: &str) -> Result<Vec<(&str, String)>> {
+    let mut attrs = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(attrs);
+        }
+        let (key, value) = rest
+            .split_once('=')
+            .ok_or_else(|| anyhow!("malformed attribute near '{}'", rest))?;
+        let value = value.trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| anyhow!("unquoted value for attribute '{}'", key.trim()))?;
+        let end = value[1..]
+            .find(quote)
+            .ok_or_else(|| anyhow!("unterminated value for attribute '{}'", key.trim()))?;
+        attrs.push((key.trim(), decode(&value[1..end + 1])));
+        rest = &value[end + 2..];
+    }
+}
+
+fn tokenize(xml: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = xml;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            tokens.push(Token::Text(decode(rest)));
+            break;
+        };
+        if lt > 0 {
+            tokens.push(Token::Text(decode(&rest[..lt])));
+        }
+        rest = &rest[lt..];
+
+        if let Some(body) = rest.strip_prefix("<![CDATA[") {
+            let end = body
+                .find("]]>")
+                .ok_or_else(|| anyhow!("unterminated CDATA section"))?;
+            tokens.push(Token::Text(body[..end].to_string()));
+            rest = &body[end + 3..];
+        } else if let Some(body) = rest.strip_prefix("<!--") {
+            let end = body
+                .find("-->")
+                .ok_or_else(|| anyhow!("unterminated comment"))?;
+            rest = &body[end + 3..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            // Declarations and processing instructions.
+            let end = rest.find('>').ok_or_else(|| anyhow!("unterminated tag"))?;
+            rest = &rest[end + 1..];
+        } else {
+            let end = tag_end(rest).ok_or_else(|| anyhow!("unterminated tag"))?;
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                tokens.push(Token::End(name.trim()));
+                continue;
+            }
+            let (tag, empty) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            tokens.push(Token::Start {
+                name: &tag[..name_end],
+                attrs: parse_attrs(&tag[name_end..])?,
+                empty,
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+fn attr(attrs: &[(&str, String)], key: &str) -> String {
+    attrs
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.clone())
+        .unwrap_or_default()
+}
+
+/// Read the test cases from a JUnit XML document.
+pub fn parse(xml: &str) -> Result<Vec<TestCase>> {
+    let mut cases = Vec::new();
+    let mut suites: Vec<String> = Vec::new();
+    let mut case: Option<TestCase> = None;
+    // Inside a <failure>, <error>, or <skipped> whose text is the details.
+    let mut capture = false;
+
+    for token in tokenize(xml)? {
+        match token {
+            Token::Start {
+                name: "testsuite",
+                attrs,
+                empty: false,
+            } => suites.push(attr(&attrs, "name")),
+            Token::End("testsuite") => {
+                suites.pop();
+            }
+            Token::Start {
+                name: "testcase",
+                attrs,
+                empty,
+            } => {
+                let new = TestCase {
+                    suite: suites.last().cloned().unwrap_or_default(),
+                    classname: attr(&attrs, "classname"),
+                    name: attr(&attrs, "name"),
+                    time: attr(&attrs, "time").parse().ok(),
+                    outcome: Outcome::Passed,
+                    message: None,
+                    details: None,
+                };
+                if empty {
+                    cases.push(new);
+                } else {
+                    case = Some(new);
+                }
+            }
+            Token::Start { name, attrs, empty }
+                if matches!(name, "failure" | "error" | "skipped") =>
+            {
+                if let Some(case) = case.as_mut() {
+                    // A failure outranks a skip reported alongside it.
+                    if !case.outcome.is_failure() {
+                        case.outcome = match name {
+                            "failure" => Outcome::Failed,
+                            "error" => Outcome::Error,
+                            _ => Outcome::Skipped,
+                        };
+                        case.message = Some(attr(&attrs, "message")).filter(|m| !m.is_empty());
+                    }
+                    capture = !empty;
+                }
+            }
+            Token::End("failure" | "error" | "skipped") => capture = false,
+            Token::Text(text) if capture => {
+                if let Some(case) = case.as_mut() {
+                    case.details.get_or_insert_with(String::new).push_str(&text);
+                }
+            }
+            Token::End("testcase") => {
+                if let Some(mut done) = case.take() {
+                    done.details = done
+                        .details
+                        .map(|d| d.trim().to_string())
+                        .filter(|d| !d.is_empty());
+                    cases.push(done);
+                }
+                capture = false;
+            }
+            _ => {}
+        }
+    }
+    Ok(cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- written by a test runner -->
+<testsuites>
+  <testsuite name="parser" tests="4">
+    <properties><property name="seed" value="42"/></properties>
+    <testcase classname="parser.tests" name="empty input" time="0.01"/>
+    <testcase classname="parser.tests" name="unicode" time="0.20">
+      <failure message="expected &quot;é&quot;" type="AssertionError">left: "e"
+right: "&#xE9;" &amp; more</failure>
+      <system-out>noise</system-out>
+    </testcase>
+    <testcase classname="parser.tests" name="big file">
+      <error message="timeout"><![CDATA[<stack> at parse()]]></error>
+    </testcase>
+    <testcase name="later"><skipped/></testcase>
+  </testsuite>
+</testsuites>"#;
+
+    #[test]
+    fn reads_outcomes_messages_and_details() {
+        let cases = parse(REPORT).unwrap();
+        let summary: Vec<(&str, Outcome)> =
+            cases.iter().map(|c| (c.name.as_str(), c.outcome)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("empty input", Outcome::Passed),
+                ("unicode", Outcome::Failed),
+                ("big file", Outcome::Error),
+                ("later", Outcome::Skipped),
+            ]
+        );
+        let unicode = &cases[1];
+        assert_eq!(unicode.suite, "parser");
+        assert_eq!(unicode.label(), "parser.tests › unicode");
+        assert_eq!(unicode.time, Some(0.2));
+        assert_eq!(unicode.message.as_deref(), Some("expected \"é\""));
+        assert_eq!(
+            unicode.details.as_deref(),
+            Some("left: \"e\"\nright: \"é\" & more")
+        );
+        assert_eq!(cases[2].details.as_deref(), Some("<stack> at parse()"));
+        assert_eq!(cases[3].label(), "later");
+        assert_eq!(cases[3].message, None);
+    }
+
+    #[test]
+    fn malformed_documents_are_errors() {
+        assert!(parse("<testsuite><testcase name=x/>").is_err());
+        assert!(parse("<testsuite name=\"a").is_err());
+        assert_eq!(decode("a &unknown; b &lt;"), "a &unknown; b <");
+    }
+}
@@ -0,0 +1,336 @@
+//! `meta test report` — gather the JUnit XML reports each project's test run
+//! left behind and turn them into one triage view: failures grouped by
+//! project or by test (the same test failing in several projects usually
+//! means a shared cause), as text, JSON, or an interactive browser.
+
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+pub use self::junit::{Outcome, TestCase};
+pub use self::plugin::TestPlugin;
+
+mod browser;
+pub mod junit;
+mod plugin;
+
+/// Where test runners commonly write JUnit XML, relative to the project.
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    "**/junit*.xml",
+    "**/TEST-*.xml",
+    "**/test-results/**/*.xml",
+    "**/test-reports/**/*.xml",
+];
+
+/// Directories never searched for reports.
+const SKIP_DIRS: &[&str] = &[".git", "node_modules"];
+
+/// The test results found in one project.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectResults {
+    pub project: String,
+    /// Report files read, relative to the project.
+    pub files: Vec<String>,
+    /// Report files that could not be read, with the reason.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unreadable: Vec<String>,
+    pub cases: Vec<TestCase>,
+}
+
+impl ProjectResults {
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.cases.iter().filter(|c| c.outcome == outcome).count()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &TestCase> {
+        self.cases.iter().filter(|c| c.outcome.is_failure())
+    }
+}
+
+/// Report files under `dir` matching any of `patterns`, relative to `dir`.
+fn find_reports(dir: &Path, patterns: &[glob::Pattern]) -> Vec<String> {
+    let mut files: Vec<String> = WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.file_type().is_dir() && SKIP_DIRS.iter().any(|s| e.file_name() == *s))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(dir).ok()?;
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            patterns
+                .iter()
+                .any(|p| p.matches(&relative))
+                .then_some(relative)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Read the reports of each of `projects` under `base_path`.
+pub fn collect(
+    base_path: &Path,
+    projects: &[String],
+    patterns: &[String],
+) -> Result<Vec<ProjectResults>> {
+    let patterns = patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut projects = projects.to_vec();
+    projects.sort();
+
+    let mut results = Vec::new();
+    for project in projects {
+        let dir = base_path.join(&project);
+        let mut found = ProjectResults {
+            project,
+            files: Vec::new(),
+            unreadable: Vec::new(),
+            cases: Vec::new(),
+        };
+        if dir.is_dir() {
+            for file in find_reports(&dir, &patterns) {
+                let parsed = std::fs::read_to_string(dir.join(&file))
+                    .map_err(anyhow::Error::from)
+                    .and_then(|xml| junit::parse(&xml));
+                match parsed {
+                    Ok(cases) => {
+                        found.cases.extend(cases);
+                        found.files.push(file);
+                    }
+                    Err(e) => found.unreadable.push(format!("{}: {}", file, e)),
+                }
+            }
+        }
+        results.push(found);
+    }
+    Ok(results)
+}
+
+/// A test failing somewhere in the workspace, and every project it fails in.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailureGroup<'a> {
+    pub test: String,
+    pub failures: Vec<(&'a str, &'a TestCase)>,
+}
+
+/// Failures grouped by test, those failing in the most projects first.
+pub fn failures_by_test(results: &[ProjectResults]) -> Vec<FailureGroup<'_>> {
+    let mut groups: BTreeMap<String, Vec<(&str, &TestCase)>> = BTreeMap::new();
+    for r in results {
+        for case in r.failures() {
+            groups
+                .entry(case.label())
+                .or_default()
+                .push((r.project.as_str(), case));
+        }
+    }
+    let mut groups: Vec<FailureGroup> = groups
+        .into_iter()
+        .map(|(test, failures)| FailureGroup { test, failures })
+        .collect();
+    groups.sort_by_key(|g| std::cmp::Reverse(g.failures.len()));
+    groups
+}
+
+/// The first line of a failure's message, or of its details.
+pub fn headline(case: &TestCase) -> String {
+    case.message
+        .as_deref()
+        .or(case.details.as_deref())
+        .and_then(|m| m.lines().find(|l| !l.trim().is_empty()))
+        .unwrap_or("(no message)")
+        .trim()
+        .to_string()
+}
+
+fn outcome_mark(outcome: Outcome) -> ColoredString {
+    match outcome {
+        Outcome::Error => "!".red(),
+        _ => "✗".red(),
+    }
+}
+
+fn print_header(title: &str) {
+    println!("\n  {} {}", "🧪".bright_blue(), title.bold());
+    println!("  {}", "═".repeat(60).bright_black());
+}
+
+fn print_summary(results: &[ProjectResults]) {
+    let total = |o| results.iter().map(|r| r.count(o)).sum::<usize>();
+    println!("\n  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {} passed, {} failed, {} errors, {} skipped across {} project(s)",
+        "Summary:".bright_black(),
+        total(Outcome::Passed).to_string().green(),
+        total(Outcome::Failed).to_string().red(),
+        total(Outcome::Error).to_string().red(),
+        total(Outcome::Skipped).to_string().yellow(),
+        results.iter().filter(|r| !r.files.is_empty()).count()
+    );
+    println!();
+}
+
+/// Print the results project by project.
+pub fn print_by_project(results: &[ProjectResults]) {
+    print_header("Test report by project");
+    for r in results {
+        if r.files.is_empty() && r.unreadable.is_empty() {
+            println!(
+                "  {} {} {}",
+                "⏭".yellow(),
+                r.project.bright_white(),
+                "(no test reports)".yellow()
+            );
+            continue;
+        }
+        let failed = r.count(Outcome::Failed) + r.count(Outcome::Error);
+        let mark = if failed > 0 || !r.unreadable.is_empty() {
+            "✗".red()
+        } else {
+            "✓".green()
+        };
+        println!(
+            "  {} {} {}",
+            mark,
+            r.project.bright_white(),
+            format!(
+                "{} passed, {} failed, {} skipped ({} report(s))",
+                r.count(Outcome::Passed),
+                failed,
+                r.count(Outcome::Skipped),
+                r.files.len()
+            )
+            .bright_black()
+        );
+        let lines: Vec<String> = r
+            .failures()
+            .map(|c| format!("{} {}: {}", outcome_mark(c.outcome), c.label(), headline(c)))
+            .chain(
+                r.unreadable
+                    .iter()
+                    .map(|u| format!("{} {}", "⚠️".yellow(), u)),
+            )
+            .collect();
+        for (i, line) in lines.iter().enumerate() {
+            let branch = if i + 1 == lines.len() { "└" } else { "│" };
+            println!("     {} {}", branch.bright_black(), line);
+        }
+    }
+    print_summary(results);
+}
+
+/// Print the failures grouped by test.
+pub fn print_by_test(results: &[ProjectResults]) {
+    print_header("Failing tests");
+    let groups = failures_by_test(results);
+    if groups.is_empty() {
+        println!("  {} {}", "✓".green(), "No failing tests".green());
+    }
+    for group in &groups {
+        let projects: Vec<&str> = group.failures.iter().map(|(p, _)| *p).collect();
+        println!(
+            "  {} {} {}",
+            "✗".red(),
+            group.test.bright_white(),
+            format!("({})", projects.join(", ")).bright_black()
+        );
+        for (i, (project, case)) in group.failures.iter().enumerate() {
+            let branch = if i + 1 == group.failures.len() {
+                "└"
+            } else {
+                "│"
+            };
+            println!(
+                "     {} {}: {}",
+                branch.bright_black(),
+                project.cyan(),
+                headline(case)
+            );
+        }
+    }
+    print_summary(results);
+}
+
+/// Open the interactive failure browser.
+pub fn browse(results: &[ProjectResults]) -> Result<()> {
+    let failures: Vec<browser::Failure> = results
+        .iter()
+        .flat_map(|r| {
+            r.failures().map(|c| browser::Failure {
+                project: r.project.clone(),
+                case: c.clone(),
+            })
+        })
+        .collect();
+    if failures.is_empty() {
+        println!("{} No failing tests", "✓".green());
+        return Ok(());
+    }
+    browser::run(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn collects_reports_and_groups_failures_by_test() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let shared = r#"<testsuite name="s">
+            <testcase classname="config" name="loads"><failure message="bad path"/></testcase>
+            <testcase classname="config" name="saves"/>
+        </testsuite>"#;
+        write(
+            &root.join("api/target/surefire-reports/TEST-config.xml"),
+            shared,
+        );
+        write(&root.join("web/reports/junit.xml"), shared);
+        write(
+            &root.join("web/reports/junit-ui.xml"),
+            r#"<testsuite><testcase name="renders"><error message="boom"/></testcase></testsuite>"#,
+        );
+        write(&root.join("web/node_modules/pkg/junit.xml"), "<not xml");
+        write(&root.join("web/test-results/broken.xml"), "<testsuite");
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+
+        let projects = ["web", "docs", "api"].map(String::from);
+        let patterns: Vec<String> = DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect();
+        let results = collect(root, &projects, &patterns).unwrap();
+
+        let names: Vec<&str> = results.iter().map(|r| r.project.as_str()).collect();
+        assert_eq!(names, ["api", "docs", "web"]);
+        assert_eq!(
+            results[0].files,
+            ["target/surefire-reports/TEST-config.xml"]
+        );
+        assert!(results[1].files.is_empty());
+        assert_eq!(
+            results[2].files,
+            ["reports/junit-ui.xml", "reports/junit.xml"]
+        );
+        assert_eq!(results[2].unreadable.len(), 1);
+        assert!(results[2].unreadable[0].starts_with("test-results/broken.xml"));
+        assert_eq!(results[2].count(Outcome::Passed), 1);
+        assert_eq!(results[2].count(Outcome::Error), 1);
+
+        let groups = failures_by_test(&results);
+        assert_eq!(groups[0].test, "config › loads");
+        let projects: Vec<&str> = groups[0].failures.iter().map(|(p, _)| *p).collect();
+        assert_eq!(projects, ["api", "web"]);
+        assert_eq!(headline(groups[0].failures[0].1), "bad path");
+        assert_eq!(groups[1].test, "renders");
+    }
+}
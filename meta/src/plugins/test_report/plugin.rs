@@ -0,0 +1,178 @@
+use super::{browse, collect, print_by_project, print_by_test, DEFAULT_PATTERNS};
+use crate::plugins::shared::selection;
+use anyhow::Result;
+use clap::ArgMatches;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+
+/// TestPlugin for triaging test results across the workspace
+pub struct TestPlugin;
+
+impl TestPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("test")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Triage test results across the workspace")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Triage test results across the workspace.\n\
+                 \n\
+                 `meta test report` reads the JUnit XML reports left behind by each\n\
+                 project's test run and aggregates the failures.",
+            )
+            .command(
+                command("report")
+                    .about("Aggregate JUnit XML test reports from every project")
+                    .help_description(
+                        "Aggregate the JUnit XML reports from every project's last test run.\n\
+                         \n\
+                         Reports are found by glob, relative to each project. By default\n\
+                         these are junit*.xml, TEST-*.xml (surefire), and any XML under\n\
+                         test-results/ or test-reports/; node_modules and .git are never\n\
+                         searched. Pass --pattern (repeatable) to look elsewhere.\n\
+                         \n\
+                         --by project (the default) lists every project with its pass/fail\n\
+                         counts and failing tests. --by test groups failures by test name,\n\
+                         most widespread first: the same test failing in several projects\n\
+                         usually points at a shared cause. --tui opens a browser over the\n\
+                         failures with their messages and stack traces, and --json prints\n\
+                         every parsed test case.\n\
+                         \n\
+                         Exits non-zero when any test failed, so it can gate CI after\n\
+                         `meta exec` has run the suites.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta exec --parallel cargo nextest run --profile ci\n\
+                           meta test report\n\
+                           meta test report --by test\n\
+                           meta test report --tui\n\
+                           meta test report --pattern 'build/reports/**/*.xml' 'services/*'",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("targets")
+                            .help("Projects to report on (names, aliases, or globs)")
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("pattern")
+                            .long("pattern")
+                            .help("Glob for report files, relative to each project (repeatable)")
+                            .takes_value(true)
+                            .multiple(true),
+                    )
+                    .arg(
+                        arg("by")
+                            .long("by")
+                            .help("Group failures by project or by test name")
+                            .takes_value(true)
+                            .possible_value("project")
+                            .possible_value("test")
+                            .default_value("project"),
+                    )
+                    .arg(
+                        arg("json")
+                            .long("json")
+                            .help("Print the parsed results as JSON"),
+                    )
+                    .arg(
+                        arg("tui")
+                            .long("tui")
+                            .help("Browse the failures interactively"),
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing"),
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true),
+                    ),
+            )
+            .handler("report", handle_report)
+            .build()
+    }
+}
+
+/// Handler for the report command
+fn handle_report(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config
+        .meta_root()
+        .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+    let projects = match selection::explicit_projects(matches, config)? {
+        Some(mut selected) => {
+            selection::apply_excludes(matches, config, &mut selected, true);
+            selected
+        }
+        None => selection::all_projects(matches, config),
+    };
+    let patterns: Vec<String> = match matches.get_many::<String>("pattern") {
+        Some(patterns) => patterns.cloned().collect(),
+        None => DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+    };
+
+    let results = collect(&base_path, &projects, &patterns)?;
+
+    if matches.get_flag("tui") {
+        return browse(&results);
+    }
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else if matches.get_one::<String>("by").map(String::as_str) == Some("test") {
+        print_by_test(&results);
+    } else {
+        print_by_project(&results);
+    }
+
+    let failing: usize = results.iter().map(|r| r.failures().count()).sum();
+    if failing > 0 {
+        return Err(anyhow::anyhow!("{} failing test(s)", failing));
+    }
+    Ok(())
+}
+
+impl MetaPlugin for TestPlugin {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for TestPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Triage test results across the workspace")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for TestPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,176 @@
+//! Contextual hints: after a command succeeds, a line on stderr suggesting
+//! the next step the workspace's state calls for, such as cloning the
+//! projects `.meta` lists but the disk does not have.
+//!
+//! Hints are only printed to a terminal and never in `--non-interactive` or
+//! `--json` runs, so scripts see the same output as before. `"hints": false`
+//! in `.meta` or the user config turns them off.
+
+use crate::plugins::lock::{Lockfile, LOCK_FILE};
+use crate::plugins::status::{gather_one, RepoState};
+use clap::ArgMatches;
+use colored::Colorize;
+use metarepo_core::{MetaConfig, RuntimeConfig};
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// How many projects with uncommitted changes it takes to suggest working
+/// on them together.
+const DIRTY_THRESHOLD: usize = 3;
+
+/// Print the hints for `command` (the top-level command name) run with
+/// `matches`, if hints are on.
+pub fn show_hints(command: &str, matches: &ArgMatches, config: &RuntimeConfig) {
+    if config.meta_config.hints == Some(false)
+        || config.non_interactive.is_some()
+        || !std::io::stderr().is_terminal()
+    {
+        return;
+    }
+    let (path, leaf) = match matches.subcommand() {
+        Some((sub, sub_matches)) => (format!("{} {}", command, sub), sub_matches),
+        None => (command.to_string(), matches),
+    };
+    if leaf
+        .try_get_one::<bool>("json")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+    {
+        return;
+    }
+    let (Some(base_path), Some(meta_file)) = (config.meta_root(), &config.meta_file_path) else {
+        return;
+    };
+    // The command may just have changed .meta (`meta project add`), so read
+    // the projects from disk again.
+    let workspace =
+        MetaConfig::load_from_file(meta_file).unwrap_or_else(|_| config.meta_config.clone());
+    for hint in hints_for(&path, &base_path, &workspace) {
+        eprintln!("{} {}", "hint:".cyan(), hint.dimmed());
+    }
+}
+
+/// The hints that apply after `command` (e.g. `project add`) in the
+/// workspace at `base_path`.
+pub fn hints_for(command: &str, base_path: &Path, config: &MetaConfig) -> Vec<String> {
+    let mut hints = Vec::new();
+    let after = |commands: &[&str]| commands.contains(&command);
+
+    if after(&["init", "project remove"]) && config.projects.is_empty() {
+        hints.push("no projects yet: track one with `meta project add <path> <url>`".to_string());
+    }
+
+    if after(&["project add", "git status", "git pull", "status"]) {
+        let missing = config
+            .projects
+            .keys()
+            .filter(|name| !base_path.join(name).exists())
+            .count();
+        if missing > 0 {
+            hints.push(format!(
+                "{} in .meta {} not cloned: `meta git update` clones {}",
+                plural(missing, "project"),
+                if missing == 1 { "is" } else { "are" },
+                if missing == 1 { "it" } else { "them" },
+            ));
+        }
+    }
+
+    if after(&["project add", "project new"]) {
+        if let Ok(Some(lock)) = Lockfile::load(base_path) {
+            let unlocked = config
+                .projects
+                .keys()
+                .filter(|name| !lock.projects.contains_key(*name))
+                .count();
+            if unlocked > 0 {
+                hints.push(format!(
+                    "{} has no commit for {}: `meta lock` records {}",
+                    LOCK_FILE,
+                    plural(unlocked, "project"),
+                    if unlocked == 1 { "it" } else { "them" },
+                ));
+            }
+        }
+    }
+
+    if after(&["git status", "status"]) {
+        let dirty = config
+            .projects
+            .keys()
+            .filter(|name| {
+                matches!(
+                    gather_one(&base_path.join(name)),
+                    RepoState::Ok { dirty, submodules, .. } if dirty + submodules > 0
+                )
+            })
+            .count();
+        if dirty >= DIRTY_THRESHOLD {
+            hints.push(format!(
+                "{} have uncommitted changes: `meta git diff --stat` reviews them together, \
+                 `meta git commit -m <message>` commits them in one go",
+                plural(dirty, "project"),
+            ));
+        }
+    }
+
+    hints
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_follow_the_workspace_state() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path();
+        let config: MetaConfig = serde_json::from_str(
+            r#"{"projects": {"api": "git@h:api.git", "web": "git@h:web.git",
+                             "cli": "git@h:cli.git", "docs": "git@h:docs.git"}}"#,
+        )
+        .unwrap();
+
+        assert!(hints_for("init", base, &MetaConfig::default())[0].contains("meta project add"));
+        let hints = hints_for("project add", base, &config);
+        assert_eq!(
+            hints,
+            ["4 projects in .meta are not cloned: `meta git update` clones them"]
+        );
+        // Hints are tied to commands.
+        assert!(hints_for("exec", base, &config).is_empty());
+
+        for name in ["api", "web", "cli"] {
+            let repo = git2::Repository::init(base.join(name)).unwrap();
+            std::fs::write(repo.workdir().unwrap().join("new.txt"), "").unwrap();
+        }
+        std::fs::write(
+            base.join(LOCK_FILE),
+            r#"{"version": 1, "projects": {"api": {"url": "git@h:api.git", "commit": "abc"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            hints_for("project add", base, &config),
+            [
+                "1 project in .meta is not cloned: `meta git update` clones it",
+                "meta.lock has no commit for 3 projects: `meta lock` records them",
+            ]
+        );
+        let hints = hints_for("git status", base, &config);
+        assert!(
+            hints[1].starts_with("3 projects have uncommitted changes"),
+            "{:?}",
+            hints
+        );
+    }
+}
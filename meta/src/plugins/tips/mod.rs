@@ -0,0 +1,178 @@
+//! `meta tips` — short workflow recipes strung together from meta's
+//! commands, and the contextual hints printed after commands (see
+//! [`hints`]).
+
+use anyhow::Result;
+use colored::Colorize;
+
+pub use self::hints::show_hints;
+pub use self::plugin::TipsPlugin;
+
+mod hints;
+mod plugin;
+
+/// A workflow: the commands that make it up, in order.
+#[derive(Debug, Clone, Copy)]
+pub struct Recipe {
+    /// What `meta tips <name>` takes.
+    pub name: &'static str,
+    pub title: &'static str,
+    /// Each command with what it does.
+    pub steps: &'static [(&'static str, &'static str)],
+}
+
+pub const RECIPES: &[Recipe] = &[
+    Recipe {
+        name: "setup",
+        title: "Check out a workspace on a new machine",
+        steps: &[
+            (
+                "meta git clone <url>",
+                "clone the workspace and every project",
+            ),
+            ("meta onboard", "walk through the workspace's setup steps"),
+            ("meta verify-setup", "check each project is ready to run"),
+        ],
+    },
+    Recipe {
+        name: "add",
+        title: "Add a repository to the workspace",
+        steps: &[
+            (
+                "meta project add <path> <url>",
+                "clone it and track it in .meta",
+            ),
+            ("meta project group add <group> <path>", "put it in a group"),
+            ("meta lock", "record its commit in meta.lock"),
+        ],
+    },
+    Recipe {
+        name: "sync",
+        title: "Start the day in sync",
+        steps: &[
+            ("meta git update", "clone projects others added to .meta"),
+            (
+                "meta git fetch -p",
+                "fetch every remote, pruning deleted branches",
+            ),
+            (
+                "meta git divergence",
+                "see which branches are behind or diverged",
+            ),
+            ("meta git pull", "pull every project"),
+        ],
+    },
+    Recipe {
+        name: "change",
+        title: "Make one change across several repositories",
+        steps: &[
+            (
+                "meta git branch create <branch> <projects...> --checkout",
+                "branch the projects involved",
+            ),
+            (
+                "meta exec --changed-files origin/main <linter> {changed_files}",
+                "check only what changed",
+            ),
+            ("meta git diff --stat", "review the changes together"),
+            (
+                "meta git commit -m <message>",
+                "commit them with one message",
+            ),
+            (
+                "meta git push --set-upstream",
+                "publish the branch everywhere",
+            ),
+        ],
+    },
+    Recipe {
+        name: "test",
+        title: "Run the tests everywhere and chase the failures",
+        steps: &[
+            (
+                "meta exec --parallel cargo test",
+                "test every project at once",
+            ),
+            ("meta last", "see how each project did"),
+            (
+                "meta exec --projects \"$(meta last --failed | paste -sd,)\" cargo test",
+                "re-run only the failures",
+            ),
+        ],
+    },
+    Recipe {
+        name: "release",
+        title: "Tag a release across the workspace",
+        steps: &[
+            ("meta git status", "make sure every project is clean"),
+            (
+                "meta git tag <version> --annotate -m <message> --push",
+                "tag every project, all or nothing",
+            ),
+            ("meta lock", "record the released commits"),
+            ("meta verify", "later: check nothing drifted from the lock"),
+        ],
+    },
+];
+
+/// Print every recipe, or only the one called `name`.
+pub fn show_tips(name: Option<&str>) -> Result<()> {
+    let recipes: Vec<&Recipe> = match name {
+        None => RECIPES.iter().collect(),
+        Some(name) => vec![find(name)?],
+    };
+    for (i, recipe) in recipes.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!(
+            "{} {}",
+            recipe.title.bold(),
+            format!("(meta tips {})", recipe.name).dimmed()
+        );
+        let width = recipe.steps.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+        for (command, what) in recipe.steps {
+            let command = format!("{:width$}", command, width = width);
+            println!("  {}  {}", command.cyan(), what.dimmed());
+        }
+    }
+    if name.is_none() {
+        println!();
+        println!(
+            "{}",
+            "After a command, meta may suggest a next step; set \"hints\": false in .meta \
+             or the user config to turn that off."
+                .dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn find(name: &str) -> Result<&'static Recipe> {
+    RECIPES.iter().find(|r| r.name == name).ok_or_else(|| {
+        let names: Vec<&str> = RECIPES.iter().map(|r| r.name).collect();
+        anyhow::anyhow!("No tip '{}'. Tips: {}", name, names.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipes_are_meta_commands_with_unique_names() {
+        let mut names: Vec<&str> = RECIPES.iter().map(|r| r.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), RECIPES.len());
+        for recipe in RECIPES {
+            assert!(!recipe.steps.is_empty());
+            for (command, _) in recipe.steps {
+                assert!(command.starts_with("meta "), "{}", command);
+            }
+        }
+        assert_eq!(find("sync").unwrap().name, "sync");
+        let err = find("nope").unwrap_err().to_string();
+        assert!(err.contains("Tips: setup, add"), "{}", err);
+    }
+}
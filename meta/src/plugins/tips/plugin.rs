@@ -0,0 +1,76 @@
+//! Plugin wiring for `meta tips`.
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::show_tips;
+
+/// Registers the top-level `meta tips` command.
+pub struct TipsPlugin;
+
+impl TipsPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TipsPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for TipsPlugin {
+    fn name(&self) -> &str {
+        "tips"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("tips")
+                .about("Show workflow recipes built from meta's commands")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "List short recipes for common workflows (setting up a checkout,\n\
+                     adding a repository, syncing, cross-repository changes, testing,\n\
+                     releasing), each as the meta commands that make it up. Name a\n\
+                     recipe to show only that one.\n\
+                     \n\
+                     Separately, after a command succeeds meta may print a hint on\n\
+                     stderr when the workspace calls for a next step: projects in .meta\n\
+                     that are not cloned, a meta.lock missing new projects, or many\n\
+                     projects with uncommitted changes. Hints only go to a terminal,\n\
+                     never with --non-interactive or --json; set \"hints\": false in\n\
+                     .meta or the user config to turn them off.\n\
+                     \n\
+                     Examples:\n  \
+                       meta tips\n  \
+                       meta tips sync\n",
+                ))
+                .arg(
+                    Arg::new("recipe")
+                        .help("Show only this recipe")
+                        .value_name("RECIPE"),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, _config: &RuntimeConfig) -> Result<()> {
+        show_tips(matches.get_one::<String>("recipe").map(String::as_str))
+    }
+}
+
+impl BasePlugin for TipsPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Show workflow recipes built from meta's commands")
+    }
+}
@@ -0,0 +1,530 @@
+//! `meta todos` — the TODO/FIXME/HACK markers left in the workspace's
+//! projects, for auditing tech debt across repositories.
+//!
+//! Each project is searched with `git grep`, so only tracked text files are
+//! read and ignored build output never shows up. The markers come from
+//! `--marker`, else the `todos.markers` setting, else [`DEFAULT_MARKERS`].
+//! With `--blame` each marker is attributed to the author of its line
+//! (through git's mailmap), and the report also counts them by author.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use metarepo_core::{Align, MetaConfig, Table};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub use self::plugin::TodosPlugin;
+
+mod plugin;
+
+/// Markers searched for when neither `--marker` nor `todos.markers` names any.
+pub const DEFAULT_MARKERS: &[&str] = &["TODO", "FIXME", "HACK"];
+
+/// What `git blame` reports for lines not committed yet.
+const UNCOMMITTED: &str = "Not Committed Yet";
+
+/// One marker in one project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Todo {
+    /// Path within the project.
+    pub file: String,
+    pub line: usize,
+    pub marker: String,
+    /// What follows the marker on its line.
+    pub text: String,
+    /// Author of the line, with `--blame`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+/// The markers found in one project, in file and line order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectTodos {
+    pub project: String,
+    pub todos: Vec<Todo>,
+}
+
+/// How many markers one author's lines carry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthorTodos {
+    pub author: String,
+    pub count: usize,
+}
+
+/// Everything `meta todos` reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TodoReport {
+    pub markers: Vec<String>,
+    pub total: usize,
+    /// Projects with at least one marker.
+    pub projects: Vec<ProjectTodos>,
+    /// Most markers first; empty without `--blame`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<AuthorTodos>,
+}
+
+/// The markers to search for: those given on the command line, else the
+/// `todos.markers` setting, else [`DEFAULT_MARKERS`].
+pub fn markers(config: &MetaConfig, given: Vec<String>) -> Vec<String> {
+    if !given.is_empty() {
+        return given;
+    }
+    match config.todos.as_ref().map(|t| &t.markers) {
+        Some(markers) if !markers.is_empty() => markers.clone(),
+        _ => DEFAULT_MARKERS.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The first of `markers` in `line` as a whole word, with the text after it
+/// (leading `:` and `-` and a trailing comment closer trimmed).
+fn find_marker<'a>(line: &str, markers: &'a [String]) -> Option<(&'a str, String)> {
+    let mut found: Option<(usize, &'a str)> = None;
+    for marker in markers {
+        let hit = line.match_indices(marker.as_str()).find(|(at, _)| {
+            let before = line[..*at].chars().next_back();
+            let after = line[at + marker.len()..].chars().next();
+            !before.is_some_and(is_word) && !after.is_some_and(is_word)
+        });
+        if let Some((at, _)) = hit {
+            if found.is_none_or(|(first, _)| at < first) {
+                found = Some((at, marker.as_str()));
+            }
+        }
+    }
+    let (at, marker) = found?;
+    let text = line[at + marker.len()..]
+        .trim_start_matches([':', '-', ' ', '\t'])
+        .trim_end()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim_end();
+    Some((marker, text.to_string()))
+}
+
+/// Parse `git grep --null -n` output (`file\0line\0text`) into todos,
+/// keeping only lines where a marker stands as a whole word.
+fn parse_matches(output: &str, markers: &[String]) -> Vec<Todo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\0');
+            let (Some(file), Some(Ok(number)), Some(text)) = (
+                fields.next(),
+                fields.next().map(str::parse::<usize>),
+                fields.next(),
+            ) else {
+                return None;
+            };
+            let (marker, text) = find_marker(text, markers)?;
+            Some(Todo {
+                file: file.to_string(),
+                line: number,
+                marker: marker.to_string(),
+                text,
+                author: None,
+            })
+        })
+        .collect()
+}
+
+/// The markers in the tracked text files of the repository at `path`.
+pub fn scan_project(path: &Path, markers: &[String]) -> Result<Vec<Todo>> {
+    let pattern = markers
+        .iter()
+        .map(|m| regex::escape(m))
+        .collect::<Vec<_>>()
+        .join("|");
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["grep", "--no-color", "--null", "-n", "-I", "-w", "-E", "-e"])
+        .arg(&pattern)
+        .output()
+        .context("Failed to run git")?;
+    // git grep exits 1 when nothing matches.
+    let nothing_found = output.status.code() == Some(1) && output.stderr.is_empty();
+    if !output.status.success() && !nothing_found {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("{}", stderr.trim()));
+    }
+    Ok(parse_matches(
+        &String::from_utf8_lossy(&output.stdout),
+        markers,
+    ))
+}
+
+/// Scan each of `projects` under `base_path`, `workers` at a time, blaming
+/// the lines found when `blame` is set. Results keep the order of `projects`.
+pub fn scan_projects(
+    base_path: &Path,
+    projects: &[String],
+    markers: &[String],
+    blame: bool,
+    workers: usize,
+) -> Vec<Result<Vec<Todo>>> {
+    let slots: Vec<Mutex<Option<Result<Vec<Todo>>>>> =
+        projects.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let workers = workers.clamp(1, projects.len().max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(project) = projects.get(i) else {
+                    break;
+                };
+                let path = base_path.join(project);
+                let result = scan_project(&path, markers).map(|mut todos| {
+                    if blame {
+                        attribute(&path, &mut todos);
+                    }
+                    todos
+                });
+                *slots[i].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    slots
+        .into_iter()
+        .filter_map(|slot| slot.into_inner().unwrap())
+        .collect()
+}
+
+/// Map each line number of `git blame --line-porcelain` output to the
+/// line's author.
+fn parse_blame(output: &str) -> HashMap<usize, String> {
+    let mut authors = HashMap::new();
+    let mut current = None;
+    for line in output.lines() {
+        if let Some(author) = line.strip_prefix("author ") {
+            if let Some(number) = current.take() {
+                authors.insert(number, author.to_string());
+            }
+        } else if !line.starts_with('\t') {
+            // A header: `<sha> <original line> <final line> [<group size>]`.
+            let mut fields = line.split(' ');
+            if let (Some(sha), Some(_), Some(Ok(number))) = (
+                fields.next(),
+                fields.next(),
+                fields.next().map(str::parse::<usize>),
+            ) {
+                if sha.len() >= 40 && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                    current = Some(number);
+                }
+            }
+        }
+    }
+    authors
+}
+
+/// Fill in the author of each todo's line in the repository at `path`,
+/// running `git blame` once per file. Files blame cannot read keep no author.
+pub fn attribute(path: &Path, todos: &mut [Todo]) {
+    let mut by_file: BTreeMap<String, HashMap<usize, String>> = BTreeMap::new();
+    for todo in todos.iter_mut() {
+        let authors = by_file.entry(todo.file.clone()).or_insert_with(|| {
+            Command::new("git")
+                .arg("-C")
+                .arg(path)
+                .args(["blame", "--line-porcelain", "--"])
+                .arg(&todo.file)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| parse_blame(&String::from_utf8_lossy(&output.stdout)))
+                .unwrap_or_default()
+        });
+        todo.author = authors.get(&todo.line).cloned();
+    }
+}
+
+/// Combine the projects' todos into the report, dropping projects without
+/// any and counting them by author when they were blamed.
+pub fn build_report(markers: Vec<String>, projects: Vec<ProjectTodos>) -> TodoReport {
+    let projects: Vec<ProjectTodos> = projects
+        .into_iter()
+        .filter(|p| !p.todos.is_empty())
+        .collect();
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for todo in projects.iter().flat_map(|p| &p.todos) {
+        if let Some(author) = &todo.author {
+            *counts.entry(author).or_default() += 1;
+        }
+    }
+    let mut authors: Vec<AuthorTodos> = counts
+        .into_iter()
+        .map(|(author, count)| AuthorTodos {
+            author: author.to_string(),
+            count,
+        })
+        .collect();
+    authors.sort_by(|a, b| b.count.cmp(&a.count).then(a.author.cmp(&b.author)));
+    TodoReport {
+        markers,
+        total: projects.iter().map(|p| p.todos.len()).sum(),
+        projects,
+        authors,
+    }
+}
+
+/// The report as Markdown: a section per project with one bullet per
+/// marker, then the counts by author.
+pub fn render_markdown(report: &TodoReport) -> String {
+    let mut out = format!("# {} ({})\n", report.markers.join(" / "), report.total);
+    for project in &report.projects {
+        out.push_str(&format!(
+            "\n## {} ({})\n\n",
+            project.project,
+            project.todos.len()
+        ));
+        for todo in &project.todos {
+            out.push_str(&format!(
+                "- `{}:{}` **{}** {}",
+                todo.file, todo.line, todo.marker, todo.text
+            ));
+            if let Some(author) = &todo.author {
+                out.push_str(&format!(" — {}", author));
+            }
+            out.push('\n');
+        }
+    }
+    if !report.authors.is_empty() {
+        out.push_str("\n## By author\n\n| Author | Count |\n| --- | ---: |\n");
+        for a in &report.authors {
+            out.push_str(&format!("| {} | {} |\n", a.author, a.count));
+        }
+    }
+    out
+}
+
+/// Print the report: each project's markers, then the counts by author.
+pub fn print_report(report: &TodoReport) {
+    println!(
+        "\n  {} {} {}",
+        "📝".cyan(),
+        report.markers.join(" / ").bold(),
+        format!("({} in {} project(s))", report.total, report.projects.len()).bright_black()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    if report.projects.is_empty() {
+        println!("  None found.");
+        println!();
+        return;
+    }
+    for project in &report.projects {
+        println!(
+            "\n  {} {}",
+            project.project.bold(),
+            format!("({})", project.todos.len()).bright_black()
+        );
+        for todo in &project.todos {
+            let marker = match todo.marker.as_str() {
+                "FIXME" | "HACK" => todo.marker.red(),
+                _ => todo.marker.yellow(),
+            };
+            let author = match &todo.author {
+                Some(author) if author != UNCOMMITTED => format!("  {}", author).bright_black(),
+                Some(author) => format!("  {}", author).dimmed(),
+                None => "".normal(),
+            };
+            println!(
+                "    {} {} {}{}",
+                format!("{}:{}", todo.file, todo.line).cyan(),
+                marker,
+                todo.text,
+                author
+            );
+        }
+    }
+    if !report.authors.is_empty() {
+        println!("\n  {}", "By author".bold());
+        println!("  {}", "─".repeat(60).bright_black());
+        let mut table = Table::new(["Author", "Count"]).align(1, Align::Right);
+        for a in &report.authors {
+            table.row([a.author.clone(), a.count.to_string()]);
+        }
+        table.indent(2).print();
+    }
+    println!();
+}
+
+/// The arguments that open `file` at `line` in `editor` (the words of
+/// `$VISUAL` or `$EDITOR`): `-g file:line` for VS Code, `+line file` for
+/// the rest.
+fn editor_command(editor: &[String], file: &Path, line: usize) -> Vec<String> {
+    let mut command = editor.to_vec();
+    let program = Path::new(&editor[0])
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    if matches!(program, "code" | "code-insiders" | "codium") {
+        command.push("-g".to_string());
+        command.push(format!("{}:{}", file.display(), line));
+    } else {
+        command.push(format!("+{}", line));
+        command.push(file.display().to_string());
+    }
+    command
+}
+
+/// Open `file` at `line` in `$VISUAL`, `$EDITOR`, or `vi`, and wait for it.
+pub fn open_in_editor(file: &Path, line: usize) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let words = shlex::split(&editor)
+        .filter(|words| !words.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Cannot parse the editor command '{}'", editor))?;
+    let command = editor_command(&words, file, line);
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .status()
+        .with_context(|| format!("Failed to start {}", command[0]))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("{} exited with {}", command[0], status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> Vec<String> {
+        DEFAULT_MARKERS.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn markers_must_stand_as_whole_words() {
+        let output = "src/lib.rs\x0012\x00    // TODO: handle the empty case\n\
+                      src/lib.rs\x0030\x00let todos = TODOS; // FIXME(ada) - slow */\n\
+                      README.md\x004\x00<!-- HACK until v2 -->\n\
+                      notes.txt\x001\x00TODOLIST and HACKER are words\n";
+        let found = parse_matches(output, &defaults());
+        assert_eq!(found.len(), 3);
+        assert_eq!(
+            found[0],
+            Todo {
+                file: "src/lib.rs".to_string(),
+                line: 12,
+                marker: "TODO".to_string(),
+                text: "handle the empty case".to_string(),
+                author: None,
+            }
+        );
+        assert_eq!(found[1].marker, "FIXME");
+        assert_eq!(found[1].text, "(ada) - slow");
+        assert_eq!(found[2].text, "until v2");
+    }
+
+    #[test]
+    fn markers_come_from_flags_then_config() {
+        let mut config = MetaConfig::default();
+        assert_eq!(markers(&config, Vec::new()), defaults());
+        config.todos = Some(metarepo_core::TodoSettings {
+            markers: vec!["XXX".to_string()],
+        });
+        assert_eq!(markers(&config, Vec::new()), ["XXX"]);
+        assert_eq!(markers(&config, vec!["NOTE".to_string()]), ["NOTE"]);
+    }
+
+    #[test]
+    fn report_groups_by_project_and_author() {
+        let todo = |line: usize, author: &str| Todo {
+            file: "a.rs".to_string(),
+            line,
+            marker: "TODO".to_string(),
+            text: "x".to_string(),
+            author: Some(author.to_string()),
+        };
+        let report = build_report(
+            defaults(),
+            vec![
+                ProjectTodos {
+                    project: "api".to_string(),
+                    todos: vec![todo(1, "Bob"), todo(2, "Ada"), todo(3, "Ada")],
+                },
+                ProjectTodos {
+                    project: "web".to_string(),
+                    todos: Vec::new(),
+                },
+            ],
+        );
+        assert_eq!(report.total, 3);
+        assert_eq!(report.projects.len(), 1);
+        assert_eq!(report.authors[0].author, "Ada");
+        assert_eq!(report.authors[0].count, 2);
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.starts_with("# TODO / FIXME / HACK (3)\n"));
+        assert!(markdown.contains("\n## api (3)\n"));
+        assert!(markdown.contains("- `a.rs:2` **TODO** x — Ada\n"));
+        assert!(markdown.contains("| Ada | 2 |\n"));
+    }
+
+    #[test]
+    fn editors_get_the_line_their_way() {
+        let words = |s: &str| shlex::split(s).unwrap();
+        let file = Path::new("/w/api/a.rs");
+        assert_eq!(
+            editor_command(&words("vim"), file, 7),
+            ["vim", "+7", "/w/api/a.rs"]
+        );
+        assert_eq!(
+            editor_command(&words("code --wait"), file, 7),
+            ["code", "--wait", "-g", "/w/api/a.rs:7"]
+        );
+    }
+
+    #[test]
+    fn scans_tracked_files_and_blames_their_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        assert!(scan_project(dir, &defaults()).unwrap().is_empty());
+
+        std::fs::write(dir.join("a.rs"), "fn a() {}\n// TODO: test a\n").unwrap();
+        std::fs::write(dir.join("ignored.rs"), "// FIXME: untracked\n").unwrap();
+        git(&["add", "a.rs"]);
+        git(&[
+            "commit",
+            "-q",
+            "--author",
+            "Ada <ada@example.com>",
+            "-m",
+            "a",
+        ]);
+        std::fs::write(
+            dir.join("a.rs"),
+            "fn a() {}\n// TODO: test a\n// HACK: new\n",
+        )
+        .unwrap();
+
+        let mut found = scan_project(dir, &defaults()).unwrap();
+        assert_eq!(found.len(), 2);
+        attribute(dir, &mut found);
+        assert_eq!(found[0].author.as_deref(), Some("Ada"));
+        assert_eq!(found[1].line, 3);
+        assert_eq!(found[1].author.as_deref(), Some(UNCOMMITTED));
+    }
+}
@@ -0,0 +1,258 @@
+//! Plugin wiring for `meta todos`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use metarepo_core::{
+    is_interactive, prompt_select, BasePlugin, MetaPlugin, NonInteractiveMode, RuntimeConfig,
+};
+
+use super::{
+    build_report, markers, open_in_editor, print_report, render_markdown, scan_projects,
+    ProjectTodos, TodoReport,
+};
+use crate::plugins::shared::selection;
+
+/// Registers the top-level `meta todos` command.
+pub struct TodosPlugin;
+
+impl TodosPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TodosPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for TodosPlugin {
+    fn name(&self) -> &str {
+        "todos"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("todos")
+                .about("Find the TODO, FIXME, and HACK markers across the workspace's projects")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Search the tracked text files of every cloned project in your directory\n\
+                     scope (or the named projects) for TODO, FIXME, and HACK markers, and\n\
+                     list them by project with the text that follows each one. Markers are\n\
+                     matched case-sensitively as whole words; --marker (repeatable or\n\
+                     comma-separated) or todos.markers in .meta changes which are searched.\n\
+                     \n\
+                     --blame attributes each marker to the author of its line (through\n\
+                     git's mailmap) and adds a count by author. --json prints the full\n\
+                     report; --markdown prints it as a Markdown document for an issue or\n\
+                     wiki page. --open picks a marker interactively and opens its file at\n\
+                     that line in $VISUAL or $EDITOR.\n\
+                     \n\
+                     Examples:\n  \
+                       meta todos\n  \
+                       meta todos --blame --group backend\n  \
+                       meta todos --marker FIXME,XXX --json\n  \
+                       meta todos --blame --markdown > TECH_DEBT.md\n  \
+                       meta todos api --open\n",
+                ))
+                .arg(
+                    Arg::new(selection::TARGETS_ARG)
+                        .help("Projects to search (names, aliases, or globs)")
+                        .num_args(1..)
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("group")
+                        .long("group")
+                        .help("Select the members of a project group (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("tag")
+                        .long("tag")
+                        .help("Only search projects with this tag in .meta (repeatable)")
+                        .value_name("TAG")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("exclude-tag")
+                        .long("exclude-tag")
+                        .help("Skip projects with this tag in .meta (repeatable)")
+                        .value_name("TAG")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("strict")
+                        .long("strict")
+                        .help("Fail if any project selector matches nothing")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("exclude")
+                        .long("exclude")
+                        .help(
+                            "Skip these projects (names, aliases, globs, or tags; comma-separated)",
+                        )
+                        .value_name("PROJECTS"),
+                )
+                .arg(
+                    Arg::new("marker")
+                        .long("marker")
+                        .short('m')
+                        .help("Marker to search for instead of the defaults (repeatable)")
+                        .value_name("MARKER")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("blame")
+                        .long("blame")
+                        .help("Attribute each marker to the author of its line")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the report as JSON")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all(["markdown", "open"]),
+                )
+                .arg(
+                    Arg::new("markdown")
+                        .long("markdown")
+                        .help("Print the report as Markdown")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("open"),
+                )
+                .arg(
+                    Arg::new("open")
+                        .long("open")
+                        .help("Pick a marker and open its file at that line in your editor")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let base_path = config
+            .meta_root()
+            .ok_or_else(|| anyhow::anyhow!("No .meta file found. Run 'meta init' first."))?;
+        if matches.get_flag("open") && !is_interactive() {
+            return Err(anyhow::anyhow!("--open requires an interactive terminal"));
+        }
+
+        let explicit = selection::explicit_projects(matches, config)?;
+        let mut projects = match &explicit {
+            Some(selected) => selected.clone(),
+            None => selection::implicit_scope(matches, config),
+        };
+        selection::apply_excludes(matches, config, &mut projects, explicit.is_some());
+        selection::apply_tags(matches, config, &mut projects)?;
+        projects.retain(|project| base_path.join(project).join(".git").exists());
+        projects.sort();
+        if projects.is_empty() {
+            println!("No cloned projects in scope.");
+            return Ok(());
+        }
+
+        let given: Vec<String> = matches
+            .get_many::<String>("marker")
+            .into_iter()
+            .flatten()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect();
+        let markers = markers(&config.meta_config, given);
+        let blame = matches.get_flag("blame");
+
+        let scanned = scan_projects(
+            &base_path,
+            &projects,
+            &markers,
+            blame,
+            config.meta_config.worker_count(),
+        );
+        let mut found = Vec::new();
+        let mut failed = 0;
+        for (project, result) in projects.into_iter().zip(scanned) {
+            match result {
+                Ok(todos) => found.push(ProjectTodos { project, todos }),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("  {} {}: {}", "✗".red(), project, e);
+                }
+            }
+        }
+
+        let report = build_report(markers, found);
+        if matches.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else if matches.get_flag("markdown") {
+            print!("{}", render_markdown(&report));
+        } else if matches.get_flag("open") {
+            open_one(&report, &base_path)?;
+        } else {
+            print_report(&report);
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!("could not search {} project(s)", failed));
+        }
+        Ok(())
+    }
+
+    fn settings(&self) -> Vec<metarepo_core::ConfigSetting> {
+        use metarepo_core::{ConfigSetting, ConfigValueType};
+        vec![ConfigSetting::new(
+            "todos.markers",
+            "Markers meta todos searches for (default: TODO, FIXME, HACK)",
+            ConfigValueType::StringList,
+        )]
+    }
+}
+
+/// Let the user pick one of the report's markers and open it in their editor.
+fn open_one(report: &TodoReport, base_path: &std::path::Path) -> Result<()> {
+    let entries: Vec<(&str, &super::Todo)> = report
+        .projects
+        .iter()
+        .flat_map(|p| p.todos.iter().map(move |todo| (p.project.as_str(), todo)))
+        .collect();
+    if entries.is_empty() {
+        println!("No {} found.", report.markers.join(" / "));
+        return Ok(());
+    }
+    let labels: Vec<String> = entries
+        .iter()
+        .map(|(project, todo)| {
+            format!(
+                "{}/{}:{}  {} {}",
+                project, todo.file, todo.line, todo.marker, todo.text
+            )
+        })
+        .collect();
+    let picked = prompt_select("Open", labels.clone(), None, NonInteractiveMode::Fail)?;
+    let index = labels
+        .iter()
+        .position(|label| *label == picked)
+        .expect("the picked label is one of the labels");
+    let (project, todo) = entries[index];
+    open_in_editor(&base_path.join(project).join(&todo.file), todo.line)
+}
+
+impl BasePlugin for TodosPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Find the TODO, FIXME, and HACK markers across the workspace's projects")
+    }
+}
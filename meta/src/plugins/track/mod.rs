@@ -0,0 +1,282 @@
+//! `meta track` — lightweight time tracking per project. Sessions are opened
+//! and closed explicitly (`meta track start`/`stop`) or by a shell prompt hook
+//! that calls `meta track ping`, and summarized by `meta track report`. The
+//! log lives in the workspace state directory, so it never leaves the machine.
+
+use anyhow::Result;
+use colored::Colorize;
+use metarepo_core::{Align, Table};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::plugins::shared::state;
+
+pub use self::plugin::TrackPlugin;
+
+mod plugin;
+
+/// State file holding the time log.
+pub const TRACK_FILE: &str = "track.json";
+
+/// A gap between prompts longer than this ends a hook-started session at the
+/// last prompt, so time away from the keyboard is not counted.
+pub const IDLE_SECS: u64 = 15 * 60;
+
+/// One stretch of time spent in a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub project: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Unix seconds.
+    pub start: u64,
+    /// Unix seconds. For the active session, the last time it was seen.
+    pub end: u64,
+    /// Started by the shell hook rather than `meta track start`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto: bool,
+}
+
+impl Session {
+    /// When the session ends if it is still open at `now`: now, unless it is
+    /// a hook session that went idle.
+    fn end_at(&self, now: u64) -> u64 {
+        if self.auto && now.saturating_sub(self.end) > IDLE_SECS {
+            self.end
+        } else {
+            now
+        }
+    }
+}
+
+/// The saved time log.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackLog {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<Session>,
+    #[serde(default)]
+    pub sessions: Vec<Session>,
+}
+
+/// Time per project and per tag over some window, in seconds.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub projects: BTreeMap<String, u64>,
+    pub tags: BTreeMap<String, u64>,
+}
+
+impl Totals {
+    pub fn total(&self) -> u64 {
+        self.projects.values().sum()
+    }
+}
+
+impl TrackLog {
+    /// Start an explicit session. Closes any other open session; returns
+    /// false if the same session is already running.
+    pub fn start(&mut self, project: &str, tags: Vec<String>, now: u64) -> bool {
+        if let Some(active) = &self.active {
+            if !active.auto && active.project == project && active.tags == tags {
+                return false;
+            }
+        }
+        self.close(now);
+        self.active = Some(Session {
+            project: project.to_string(),
+            tags,
+            start: now,
+            end: now,
+            auto: false,
+        });
+        true
+    }
+
+    /// Stop the open session, returning it.
+    pub fn stop(&mut self, now: u64) -> Option<Session> {
+        let stopped = self.active.as_ref().map(|active| Session {
+            end: active.end_at(now),
+            ..active.clone()
+        });
+        self.close(now);
+        stopped
+    }
+
+    /// Record a shell prompt in `project` (`None` outside every project).
+    /// Explicit sessions are left alone; hook sessions follow the directory.
+    pub fn ping(&mut self, project: Option<&str>, now: u64) {
+        match &mut self.active {
+            Some(active) if !active.auto => return,
+            Some(active)
+                if Some(active.project.as_str()) == project
+                    && now.saturating_sub(active.end) <= IDLE_SECS =>
+            {
+                active.end = now;
+                return;
+            }
+            _ => {}
+        }
+        self.close(now);
+        if let Some(project) = project {
+            self.active = Some(Session {
+                project: project.to_string(),
+                start: now,
+                end: now,
+                auto: true,
+                ..Default::default()
+            });
+        }
+    }
+
+    fn close(&mut self, now: u64) {
+        if let Some(mut active) = self.active.take() {
+            active.end = active.end_at(now);
+            if active.end > active.start {
+                self.sessions.push(active);
+            }
+        }
+    }
+
+    /// Time in `[since, now]`, counting the open session up to now.
+    pub fn totals(&self, since: u64, now: u64) -> Totals {
+        let open = self.active.as_ref().map(|active| Session {
+            end: active.end_at(now),
+            ..active.clone()
+        });
+        let mut totals = Totals::default();
+        for session in self.sessions.iter().chain(open.as_ref()) {
+            let secs = session
+                .end
+                .min(now)
+                .saturating_sub(session.start.max(since));
+            if secs == 0 {
+                continue;
+            }
+            *totals.projects.entry(session.project.clone()).or_default() += secs;
+            for tag in &session.tags {
+                *totals.tags.entry(tag.clone()).or_default() += secs;
+            }
+        }
+        totals
+    }
+}
+
+/// Midnight UTC of the Monday starting the week that contains `now`.
+pub fn week_start(now: u64) -> u64 {
+    let days = now / 86_400;
+    // 1970-01-01 was a Thursday.
+    let weekday = (days + 3) % 7;
+    (days - weekday) * 86_400
+}
+
+/// `1h 05m`, or `12m` under an hour.
+pub fn format_hours(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Print the time per project and tag since `since` (Unix seconds).
+pub fn show_report(base_path: &Path, since: u64, json: bool) -> Result<()> {
+    let log: TrackLog = state::load(base_path, TRACK_FILE)?;
+    let now = state::now_secs();
+    let totals = log.totals(since, now);
+    if json {
+        let output = json!({
+            "since": since,
+            "until": now,
+            "total_secs": totals.total(),
+            "projects": totals.projects,
+            "tags": totals.tags,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!(
+        "\n  {} {} {}",
+        "⏱".cyan(),
+        "Time tracked since".bold(),
+        format!("{} UTC", state::format_timestamp(since)).bold()
+    );
+    println!("  {}", "═".repeat(60).bright_black());
+    if totals.projects.is_empty() {
+        println!("  {}", "Nothing tracked in this period.".bright_black());
+        return Ok(());
+    }
+    print_totals("Project", &totals.projects);
+    if !totals.tags.is_empty() {
+        println!();
+        print_totals("Tag", &totals.tags);
+    }
+    println!("  {}", "─".repeat(60).bright_black());
+    println!(
+        "  {} {}",
+        "Total:".bright_black(),
+        format_hours(totals.total()).bold()
+    );
+    Ok(())
+}
+
+/// A two-column table of `totals`, longest first.
+fn print_totals(heading: &str, totals: &BTreeMap<String, u64>) {
+    let mut rows: Vec<(&String, &u64)> = totals.iter().collect();
+    rows.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let mut table = Table::new([heading, "Time"]).align(1, Align::Right);
+    for (name, secs) in rows {
+        table.row([name.to_string(), format_hours(*secs)]);
+    }
+    table.indent(2).print();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_sessions_follow_the_directory_and_stop_when_idle() {
+        let mut log = TrackLog::default();
+        log.ping(Some("api"), 1_000);
+        log.ping(Some("api"), 1_600);
+        log.ping(Some("web"), 2_000);
+        // Away from the keyboard: web ends at its last prompt.
+        log.ping(Some("web"), 2_000 + IDLE_SECS + 1);
+        log.ping(None, 2_000 + IDLE_SECS + 301);
+
+        let totals = log.totals(0, 10_000);
+        assert_eq!(totals.projects["api"], 1_000);
+        assert_eq!(totals.projects["web"], 300);
+        assert!(log.active.is_none());
+    }
+
+    #[test]
+    fn explicit_sessions_win_over_the_hook() {
+        let mut log = TrackLog::default();
+        assert!(log.start("api", vec!["billing".to_string()], 0));
+        assert!(!log.start("api", vec!["billing".to_string()], 10));
+        log.ping(Some("web"), 100);
+        assert_eq!(log.active.as_ref().unwrap().project, "api");
+        // Still running: counted up to now, clipped to the window.
+        let totals = log.totals(1_000, 4_600);
+        assert_eq!(totals.projects["api"], 3_600);
+        assert_eq!(totals.tags["billing"], 3_600);
+
+        let stopped = log.stop(7_200).unwrap();
+        assert_eq!((stopped.start, stopped.end), (0, 7_200));
+        assert_eq!(log.sessions.len(), 1);
+        assert!(log.stop(7_300).is_none());
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 2024-01-03 12:00 UTC was a Wednesday; that week began 2024-01-01.
+        assert_eq!(week_start(1_704_283_200), 1_704_067_200);
+        assert_eq!(week_start(1_704_067_200), 1_704_067_200);
+        assert_eq!(format_hours(3_900), "1h 05m");
+        assert_eq!(format_hours(59), "0m");
+    }
+}
@@ -0,0 +1,322 @@
+//! Plugin wiring for `meta track`.
+
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::*;
+use metarepo_core::{arg, command, plugin, BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::{format_hours, show_report, week_start, TrackLog, IDLE_SECS, TRACK_FILE};
+use crate::plugins::shared::state;
+
+/// TrackPlugin for per-project time tracking
+pub struct TrackPlugin;
+
+impl TrackPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create the plugin using the builder pattern
+    pub fn create_plugin() -> impl MetaPlugin {
+        plugin("track")
+            .version(env!("CARGO_PKG_VERSION"))
+            .description("Track time spent in each project")
+            .author("Metarepo Contributors")
+            .help_description(
+                "Track time spent in each project.\n\
+                 \n\
+                 Sessions are started and stopped explicitly, or recorded by a shell\n\
+                 prompt hook that notes which project directory you are working in.\n\
+                 A hook session ends when you leave the project or after 15 minutes\n\
+                 without a prompt; an explicit session runs until `meta track stop`\n\
+                 and takes precedence over the hook. The log is kept in\n\
+                 .meta-state/track.json and never leaves the machine.\n\
+                 \n\
+                 Examples:\n\
+                 \n\
+                   meta track start api --tag billing\n\
+                   meta track stop\n\
+                   meta track report --week\n\
+                   eval \"$(meta track hook bash)\"   # in ~/.bashrc",
+            )
+            .command(
+                command("start")
+                    .about("Start tracking time in a project")
+                    .help_description(
+                        "Start tracking time in a project, stopping any session already\n\
+                         running. The project defaults to the one containing the current\n\
+                         directory. Tags group sessions in the report.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta track start\n\
+                           meta track start api --tag billing --tag oncall",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("project")
+                            .help("Project to track (default: the current one)")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        arg("tag")
+                            .long("tag")
+                            .short('t')
+                            .help("Tag the session (repeatable)")
+                            .multiple(true),
+                    ),
+            )
+            .command(
+                command("stop")
+                    .about("Stop the running session")
+                    .with_help_formatting(),
+            )
+            .command(
+                command("status")
+                    .about("Show the running session")
+                    .with_help_formatting(),
+            )
+            .command(
+                command("report")
+                    .about("Summarize time per project and tag")
+                    .help_description(
+                        "Summarize time per project and tag. Without a period, covers\n\
+                         everything tracked. Weeks start on Monday, midnight UTC.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta track report --week\n\
+                           meta track report --days 30 --json",
+                    )
+                    .with_help_formatting()
+                    .arg(arg("week").long("week").help("Only the current week"))
+                    .arg(
+                        arg("days")
+                            .long("days")
+                            .help("Only the last N days")
+                            .takes_value(true),
+                    )
+                    .arg(arg("json").long("json").help("Output as JSON")),
+            )
+            .command(
+                command("hook")
+                    .about("Print the shell hook that records the current project")
+                    .help_description(
+                        "Print a snippet that runs `meta track ping` before each prompt.\n\
+                         Add it to your shell's startup file.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           eval \"$(meta track hook bash)\"      # ~/.bashrc\n\
+                           eval \"$(meta track hook zsh)\"       # ~/.zshrc\n\
+                           meta track hook fish | source        # config.fish",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("shell")
+                            .help("Shell to print the hook for")
+                            .required(true)
+                            .takes_value(true)
+                            .possible_value("bash")
+                            .possible_value("zsh")
+                            .possible_value("fish"),
+                    ),
+            )
+            .command(
+                command("ping")
+                    .about("Record activity in the current directory (used by the hook)")
+                    .with_help_formatting(),
+            )
+            .handler("start", handle_start)
+            .handler("stop", handle_stop)
+            .handler("status", handle_status)
+            .handler("report", handle_report)
+            .handler("hook", handle_hook)
+            .handler("ping", handle_ping)
+            .build()
+    }
+}
+
+/// Handler for the start command
+fn handle_start(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    let project = match matches.get_one::<String>("project") {
+        Some(project) => project.trim_end_matches('/').to_string(),
+        None => config
+            .current_project()
+            .ok_or_else(|| anyhow::anyhow!("Not inside a project; pass the project to track"))?,
+    };
+    if !config.meta_config.projects.contains_key(&project) {
+        return Err(anyhow::anyhow!(
+            "Project '{}' not found in workspace config",
+            project
+        ));
+    }
+    let tags: Vec<String> = matches
+        .get_many::<String>("tag")
+        .map(|tags| tags.cloned().collect())
+        .unwrap_or_default();
+
+    let mut log: TrackLog = state::load(&base_path, TRACK_FILE)?;
+    let now = state::now_secs();
+    let previous = log.active.clone();
+    if !log.start(&project, tags, now) {
+        println!("{} Already tracking {}", "ℹ".bright_black(), project.bold());
+        return Ok(());
+    }
+    state::save(&base_path, TRACK_FILE, &log)?;
+    if let Some(previous) = previous.filter(|p| p.project != project) {
+        println!("{} Stopped {}", "■".bright_black(), previous.project);
+    }
+    println!("{} Tracking {}", "▶".green(), project.bold());
+    Ok(())
+}
+
+/// Handler for the stop command
+fn handle_stop(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    let mut log: TrackLog = state::load(&base_path, TRACK_FILE)?;
+    match log.stop(state::now_secs()) {
+        Some(session) => {
+            state::save(&base_path, TRACK_FILE, &log)?;
+            println!(
+                "{} Stopped {} after {}",
+                "■".green(),
+                session.project.bold(),
+                format_hours(session.end - session.start)
+            );
+        }
+        None => println!("{} No session running", "ℹ".bright_black()),
+    }
+    Ok(())
+}
+
+/// Handler for the status command
+fn handle_status(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    let log: TrackLog = state::load(&base_path, TRACK_FILE)?;
+    let now = state::now_secs();
+    match log
+        .active
+        .filter(|s| !s.auto || now.saturating_sub(s.end) <= IDLE_SECS)
+    {
+        Some(session) => {
+            let tags = if session.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", session.tags.join(", "))
+            };
+            println!(
+                "{} {}{} for {} (since {} UTC{})",
+                "▶".green(),
+                session.project.bold(),
+                tags,
+                format_hours(session.end_at(now) - session.start),
+                state::format_timestamp(session.start),
+                if session.auto {
+                    ", via the shell hook"
+                } else {
+                    ""
+                }
+            );
+        }
+        None => println!("{} No session running", "ℹ".bright_black()),
+    }
+    Ok(())
+}
+
+/// Handler for the report command
+fn handle_report(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let base_path = config.require_meta_root()?;
+    let now = state::now_secs();
+    let since = if matches.get_flag("week") {
+        week_start(now)
+    } else if let Some(days) = matches.get_one::<String>("days") {
+        let days: u64 = days
+            .parse()
+            .map_err(|_| anyhow::anyhow!("--days expects a number, got '{}'", days))?;
+        now.saturating_sub(days * 86_400)
+    } else {
+        0
+    };
+    show_report(&base_path, since, matches.get_flag("json"))
+}
+
+/// Handler for the hook command
+fn handle_hook(matches: &ArgMatches, _config: &RuntimeConfig) -> Result<()> {
+    let shell = matches
+        .get_one::<String>("shell")
+        .map(|s| s.as_str())
+        .unwrap_or("bash");
+    let snippet = match shell {
+        "zsh" => {
+            "_meta_track() { command meta track ping >/dev/null 2>&1; }\n\
+             autoload -Uz add-zsh-hook\n\
+             add-zsh-hook precmd _meta_track"
+        }
+        "fish" => {
+            "function __meta_track --on-event fish_prompt\n    \
+                 command meta track ping >/dev/null 2>&1\n\
+             end"
+        }
+        _ => {
+            "__meta_track() { command meta track ping >/dev/null 2>&1; }\n\
+             PROMPT_COMMAND=\"__meta_track${PROMPT_COMMAND:+;$PROMPT_COMMAND}\""
+        }
+    };
+    println!("{}", snippet);
+    Ok(())
+}
+
+/// Handler for the ping command. Outside a workspace there is nowhere to
+/// record anything, so it quietly does nothing.
+fn handle_ping(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let Some(base_path) = config.meta_root() else {
+        return Ok(());
+    };
+    let mut log: TrackLog = state::load(&base_path, TRACK_FILE)?;
+    let before = log.clone();
+    log.ping(config.current_project().as_deref(), state::now_secs());
+    if log == before {
+        return Ok(());
+    }
+    state::save(&base_path, TRACK_FILE, &log)
+}
+
+impl MetaPlugin for TrackPlugin {
+    fn name(&self) -> &str {
+        "track"
+    }
+
+    fn register_commands(&self, app: clap::Command) -> clap::Command {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.register_commands(app)
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        // Delegate to the builder-based plugin
+        let plugin = Self::create_plugin();
+        plugin.handle_command(matches, config)
+    }
+}
+
+impl BasePlugin for TrackPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Track time spent in each project")
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+}
+
+impl Default for TrackPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
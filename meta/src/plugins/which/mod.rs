@@ -0,0 +1,243 @@
+//! `meta which <path>` — map a filesystem path back to the project (and
+//! worktree) that owns it.
+
+use anyhow::Result;
+use colored::Colorize;
+use metarepo_core::{Deprecation, MetaConfig};
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+use crate::plugins::worktree::list_worktrees;
+
+pub use self::plugin::WhichPlugin;
+
+mod plugin;
+
+/// The worktree a path falls in, when it is not the project's main checkout.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorktreeMatch {
+    /// Checked-out branch (`refs/heads/` stripped); empty when detached.
+    pub branch: String,
+    /// Worktree directory, relative to the workspace root when inside it.
+    pub path: PathBuf,
+}
+
+/// Which project owns a path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathOwner {
+    /// Project key in the workspace config.
+    pub project: String,
+    /// Repository URL recorded for the project.
+    pub url: Option<String>,
+    /// Worktree containing the path, if any.
+    pub worktree: Option<WorktreeMatch>,
+    /// The path relative to the project (or worktree) root.
+    pub relative_path: PathBuf,
+    /// Set when the project is deprecated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecation>,
+}
+
+/// Make `path` absolute against `cwd` and resolve symlinks where it exists.
+/// Paths that do not exist yet are normalized lexically so `meta which` works
+/// for files about to be created.
+fn absolutize(path: &Path, cwd: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        cwd.join(path)
+    };
+    if let Ok(canonical) = joined.canonicalize() {
+        return canonical;
+    }
+    let mut out = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Resolve the project that contains `path`. Project directories are compared
+/// both as written and with symlinks resolved, so paths inside symlinked
+/// external projects are found too. The deepest matching project wins.
+pub fn resolve_path_owner(
+    config: &MetaConfig,
+    meta_root: &Path,
+    path: &Path,
+    cwd: &Path,
+) -> Option<PathOwner> {
+    let target = absolutize(path, cwd);
+    let root = meta_root
+        .canonicalize()
+        .unwrap_or_else(|_| meta_root.to_path_buf());
+
+    let mut best: Option<(&String, PathBuf)> = None;
+    for name in config.projects.keys() {
+        let dir = root.join(name);
+        let candidates = [dir.clone(), dir.canonicalize().unwrap_or(dir)];
+        for candidate in candidates {
+            if target.starts_with(&candidate)
+                && best.as_ref().is_none_or(|(b, _)| name.len() > b.len())
+            {
+                best = Some((name, candidate));
+            }
+        }
+    }
+    let (project, project_dir) = best?;
+
+    let worktree = find_worktree(&project_dir, &target);
+    let base = worktree
+        .as_ref()
+        .map(|(wt_path, _)| wt_path.clone())
+        .unwrap_or_else(|| project_dir.clone());
+    let relative_path = target
+        .strip_prefix(&base)
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    Some(PathOwner {
+        project: project.clone(),
+        url: config.get_project_url(project),
+        worktree: worktree.map(|(wt_path, branch)| WorktreeMatch {
+            branch,
+            path: wt_path
+                .strip_prefix(&root)
+                .map(Path::to_path_buf)
+                .unwrap_or(wt_path),
+        }),
+        relative_path,
+        deprecated: config.get_project_deprecation(project).cloned(),
+    })
+}
+
+/// The linked worktree of the repository at `project_dir` containing `target`,
+/// as `(worktree path, branch)`. The project's own checkout is not reported.
+fn find_worktree(project_dir: &Path, target: &Path) -> Option<(PathBuf, String)> {
+    let worktrees = list_worktrees(project_dir).ok()?;
+    worktrees
+        .into_iter()
+        .filter(|wt| !wt.is_bare)
+        .map(|wt| {
+            let path = wt.path.canonicalize().unwrap_or(wt.path);
+            let branch = wt
+                .branch
+                .strip_prefix("refs/heads/")
+                .unwrap_or(&wt.branch)
+                .to_string();
+            (path, branch)
+        })
+        .filter(|(path, _)| target.starts_with(path) && path != project_dir)
+        .max_by_key(|(path, _)| path.components().count())
+}
+
+/// Print which project owns `path`, as text or JSON.
+pub fn print_path_owner(
+    config: &MetaConfig,
+    meta_root: &Path,
+    path: &str,
+    cwd: &Path,
+    json: bool,
+) -> Result<()> {
+    let owner = resolve_path_owner(config, meta_root, Path::new(path), cwd)
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not inside any project in this workspace", path))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&owner)?);
+        return Ok(());
+    }
+
+    println!("  {} {}", "project: ".bright_black(), owner.project.bold());
+    println!(
+        "  {} {}",
+        "url:     ".bright_black(),
+        owner.url.as_deref().unwrap_or("-").bright_cyan()
+    );
+    if let Some(wt) = &owner.worktree {
+        println!(
+            "  {} {} {}",
+            "worktree:".bright_black(),
+            if wt.branch.is_empty() {
+                "(detached)".to_string()
+            } else {
+                wt.branch.clone()
+            },
+            format!("({})", wt.path.display()).bright_black()
+        );
+    }
+    println!(
+        "  {} {}",
+        "path:    ".bright_black(),
+        if owner.relative_path.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            owner.relative_path.display().to_string()
+        }
+    );
+    if let Some(deprecation) = &owner.deprecated {
+        println!("  {} {}", "⚠".yellow(), deprecation.summary().yellow());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metarepo_core::ProjectEntry;
+    use tempfile::tempdir;
+
+    fn workspace(root: &Path) -> MetaConfig {
+        let mut config = MetaConfig::default();
+        config.projects.insert(
+            "services".to_string(),
+            ProjectEntry::Url("https://example.com/services.git".to_string()),
+        );
+        config.projects.insert(
+            "services/api".to_string(),
+            ProjectEntry::Url("https://example.com/api.git".to_string()),
+        );
+        std::fs::create_dir_all(root.join("services/api/src")).unwrap();
+        config
+    }
+
+    #[test]
+    fn deepest_project_owns_the_path() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let config = workspace(root);
+
+        let owner =
+            resolve_path_owner(&config, root, Path::new("services/api/src/main.rs"), root).unwrap();
+        assert_eq!(owner.project, "services/api");
+        assert_eq!(owner.url.as_deref(), Some("https://example.com/api.git"));
+        assert_eq!(owner.relative_path, PathBuf::from("src/main.rs"));
+        assert!(owner.worktree.is_none());
+    }
+
+    #[test]
+    fn relative_paths_resolve_against_cwd() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let config = workspace(root);
+
+        let cwd = root.join("services/api/src");
+        let owner = resolve_path_owner(&config, root, Path::new("../README.md"), &cwd).unwrap();
+        assert_eq!(owner.project, "services/api");
+        assert_eq!(owner.relative_path, PathBuf::from("README.md"));
+    }
+
+    #[test]
+    fn paths_outside_projects_are_unowned() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let config = workspace(root);
+
+        assert!(resolve_path_owner(&config, root, Path::new("docs/x.md"), root).is_none());
+        assert!(resolve_path_owner(&config, root, Path::new("servicesX"), root).is_none());
+    }
+}
@@ -0,0 +1,91 @@
+//! Plugin wiring for `meta which`.
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use metarepo_core::{BasePlugin, MetaPlugin, RuntimeConfig};
+
+use super::print_path_owner;
+
+/// Registers the top-level `meta which` command.
+pub struct WhichPlugin;
+
+impl WhichPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WhichPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaPlugin for WhichPlugin {
+    fn name(&self) -> &str {
+        "which"
+    }
+
+    fn register_commands(&self, app: Command) -> Command {
+        app.subcommand(
+            Command::new("which")
+                .about("Show which project owns a path")
+                .version(env!("CARGO_PKG_VERSION"))
+                .after_long_help(metarepo_core::format_help_description(
+                    "Resolve which project (and worktree) contains a path.\n\
+                     \n\
+                     The path may be relative to the current directory or absolute, and does\n\
+                     not need to exist yet. Prints the project name, its repository URL, the\n\
+                     worktree when the path is inside a linked worktree, and the path relative\n\
+                     to the project (or worktree) root. Nested projects resolve to the deepest\n\
+                     match. Exits non-zero when no project contains the path. Pass --json for\n\
+                     machine-readable output in scripts and editor integrations.\n\
+                     \n\
+                     Examples:\n  \
+                       meta which services/api/src/main.rs\n  \
+                       meta which . --json\n",
+                ))
+                .arg(
+                    Arg::new("path")
+                        .help("File or directory to look up")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the result as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+    }
+
+    fn handle_command(&self, matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+        let meta_root = config.require_meta_root()?;
+        let path = matches
+            .get_one::<String>("path")
+            .map(String::as_str)
+            .expect("path is required");
+        print_path_owner(
+            &config.meta_config,
+            &meta_root,
+            path,
+            &config.working_dir,
+            matches.get_flag("json"),
+        )
+    }
+}
+
+impl BasePlugin for WhichPlugin {
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> Option<&str> {
+        Some("Metarepo Contributors")
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Show which project owns a path")
+    }
+}
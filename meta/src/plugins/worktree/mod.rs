@@ -1,6 +1,10 @@
+use crate::plugins::exec::ProjectInfo;
+use crate::plugins::shared::branch_policy::check_branch_name;
+use crate::plugins::shared::issues::IssueLinker;
+use crate::plugins::shared::secrets;
 use anyhow::{Context, Result};
 use colored::*;
-use metarepo_core::MetaConfig;
+use metarepo_core::{MetaConfig, Table};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -28,7 +32,7 @@ pub enum BranchStatus {
 }
 
 /// Check if a branch exists locally or remotely
-fn check_branch_exists(repo_path: &Path, branch: &str) -> Result<BranchStatus> {
+pub(crate) fn check_branch_exists(repo_path: &Path, branch: &str) -> Result<BranchStatus> {
     // Check local branches first
     let local_output = Command::new("git")
         .arg("-C")
@@ -202,7 +206,7 @@ fn git_ref_exists(project_path: &Path, refname: &str) -> bool {
 /// Resolve the ref that worktree branches should be compared against, given the
 /// project's base branch name. Prefers the local base branch; otherwise falls
 /// back to the remote-tracking ref, then the bare name.
-fn resolve_base_ref(project_path: &Path, base_name: &str) -> String {
+pub(crate) fn resolve_base_ref(project_path: &Path, base_name: &str) -> String {
     if git_ref_exists(project_path, base_name) {
         return base_name.to_string();
     }
@@ -215,7 +219,7 @@ fn resolve_base_ref(project_path: &Path, base_name: &str) -> String {
 
 /// True if `branch_ref` is fully contained in `base_ref` (an ordinary merge):
 /// the branch tip is an ancestor of base.
-fn branch_is_merged(project_path: &Path, branch_ref: &str, base_ref: &str) -> bool {
+pub(crate) fn branch_is_merged(project_path: &Path, branch_ref: &str, base_ref: &str) -> bool {
     Command::new("git")
         .arg("-C")
         .arg(project_path)
@@ -231,7 +235,7 @@ fn branch_is_merged(project_path: &Path, branch_ref: &str, base_ref: &str) -> bo
 /// True if `branch_ref` introduces no changes relative to `base_ref`. Uses a
 /// three-dot diff so it catches squash- and rebase-merged branches (whose tips
 /// are not ancestors of base) as well as branches with no commits of their own.
-fn branch_has_no_diff(project_path: &Path, branch_ref: &str, base_ref: &str) -> bool {
+pub(crate) fn branch_has_no_diff(project_path: &Path, branch_ref: &str, base_ref: &str) -> bool {
     Command::new("git")
         .arg("-C")
         .arg(project_path)
@@ -292,6 +296,44 @@ pub(crate) fn gather_project_worktrees(
     out
 }
 
+/// The worktree for `branch` in each of `projects`, as exec targets named
+/// after their project, plus the projects that have no such worktree. A
+/// worktree matches by its branch or by its directory name; the primary
+/// working tree never does, so a project checked out on `branch` itself is
+/// not a target.
+pub(crate) fn branch_worktrees(
+    base_path: &Path,
+    projects: &[String],
+    branch: &str,
+) -> (Vec<ProjectInfo>, Vec<String>) {
+    let mut targets = Vec::new();
+    let mut missing = Vec::new();
+    for project_name in projects {
+        let project_path = base_path.join(project_name);
+        let primary = std::fs::canonicalize(&project_path).unwrap_or_else(|_| project_path.clone());
+        let found = list_worktrees(&project_path)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|wt| {
+                let p = std::fs::canonicalize(&wt.path).unwrap_or_else(|_| wt.path.clone());
+                !wt.is_bare && p != primary
+            })
+            .find(|wt| {
+                short_branch_name(&wt.branch) == branch
+                    || wt.path.file_name().is_some_and(|n| n == branch)
+            });
+        match found {
+            Some(wt) => targets.push(ProjectInfo::new(
+                project_name.clone(),
+                wt.path,
+                String::new(),
+            )),
+            None => missing.push(project_name.clone()),
+        }
+    }
+    (targets, missing)
+}
+
 /// Remove a single worktree via `git worktree remove`, capturing output so it is
 /// safe to call while a TUI holds the terminal in raw mode. Passing `force` maps
 /// to `-f`, allowing removal of a dirty worktree (discarding its changes).
@@ -425,6 +467,12 @@ pub fn add_worktrees(
         return Ok(());
     }
 
+    // An explicit -b always creates the branch, so check the naming policy
+    // before touching any project.
+    if create_branch {
+        check_branch_name(config, branch)?;
+    }
+
     println!(
         "\nCreating worktree '{}' for {} project{}\n",
         branch.bright_white(),
@@ -549,6 +597,11 @@ pub fn add_worktrees(
                 }
                 Ok(BranchStatus::NotFound) => {
                     // Branch doesn't exist - need to create it
+                    if let Err(e) = check_branch_name(config, branch) {
+                        eprintln!("  {} {}", "✗".red(), e);
+                        failed.push(project_name.clone());
+                        continue;
+                    }
                     let start_point = if let Some(start) = starting_point {
                         start.to_string()
                     } else {
@@ -637,8 +690,14 @@ pub fn add_worktrees(
                     if let Some(metarepo_core::ProjectEntry::Metadata(metadata)) =
                         config.projects.get(project_name)
                     {
-                        for (key, value) in &metadata.env {
-                            cmd.env(key, value);
+                        match secrets::resolve_env(&metadata.env) {
+                            Ok(env) => {
+                                cmd.envs(env);
+                            }
+                            Err(e) => {
+                                eprintln!("  {} Skipping hook: {}", "✗".yellow(), e);
+                                continue;
+                            }
                         }
                     }
 
@@ -676,7 +735,8 @@ pub fn add_worktrees(
     Ok(())
 }
 
-/// Remove worktrees for selected projects
+/// Remove worktrees for selected projects. `scope` bounds both auto-detection
+/// (no explicit projects) and `--all`; explicitly named projects ignore it.
 pub fn remove_worktrees(
     branch: &str,
     projects: &[String],
@@ -729,7 +789,11 @@ pub fn remove_worktrees(
             select_projects_for_removal(&in_scope, branch)?
         }
     } else if projects.len() == 1 && projects[0] == "--all" {
+        // `scope` is the whole workspace minus excluded projects here.
         projects_with_worktree
+            .into_iter()
+            .filter(|p| scope.iter().any(|s| s == p))
+            .collect()
     } else {
         // Resolve project identifiers
         let mut selected = Vec::new();
@@ -876,10 +940,13 @@ pub fn list_all_worktrees(base_path: &Path, scope_projects: &[String]) -> Result
         println!("{}", "No worktrees found in workspace".dimmed());
         println!("{}", "Use 'meta worktree add' to create worktrees".dimmed());
     } else {
-        // Display worktrees grouped by branch
+        // One row per worktree, grouped by branch
         let mut missing_count = 0;
-        for (branch, projects) in worktree_map.iter() {
-            println!("{}", branch.bold().white());
+        let mut branches: Vec<_> = worktree_map.iter().collect();
+        branches.sort();
+        let mut table = Table::new(["Branch", "Project", "Path", "Status"]).shrink(2);
+        let linker = IssueLinker::from_config(&config);
+        for (branch, projects) in branches {
             for (project, path) in projects {
                 let status = if path.exists() {
                     "active".green()
@@ -891,15 +958,19 @@ pub fn list_all_worktrees(base_path: &Path, scope_projects: &[String]) -> Result
                 // Show relative path from project root
                 let relative_path = path.strip_prefix(base_path).unwrap_or(path).display();
 
-                println!(
-                    "  {}: {} ({})",
-                    project.bright_blue(),
-                    relative_path,
-                    status
-                );
+                table.row([
+                    linker
+                        .linkify(&config, project, short_branch_name(branch))
+                        .bold()
+                        .to_string(),
+                    project.bright_blue().to_string(),
+                    relative_path.to_string(),
+                    status.to_string(),
+                ]);
             }
-            println!();
         }
+        table.print();
+        println!();
 
         println!(
             "Total: {} worktrees across {} projects",
@@ -1633,6 +1704,47 @@ mod worktree_ops_tests {
         );
     }
 
+    #[test]
+    fn branch_worktrees_match_by_branch_or_directory() {
+        let tmp = tempdir().unwrap();
+        let api = init_repo(tmp.path(), "api");
+        let web = init_repo(tmp.path(), "web");
+        init_repo(tmp.path(), "docs");
+        let api_wt = api.join(".worktrees/feature-x");
+        git(
+            &api,
+            &[
+                "worktree",
+                "add",
+                "-b",
+                "feature/x",
+                api_wt.to_str().unwrap(),
+            ],
+        );
+        let web_wt = web.join(".worktrees/feature/x");
+        git(
+            &web,
+            &["worktree", "add", "-b", "other", web_wt.to_str().unwrap()],
+        );
+
+        let projects = ["api", "web", "docs"].map(String::from);
+        let (targets, missing) = branch_worktrees(tmp.path(), &projects, "feature/x");
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].name, "api");
+        assert_eq!(
+            std::fs::canonicalize(&targets[0].path).unwrap(),
+            std::fs::canonicalize(&api_wt).unwrap()
+        );
+        assert_eq!(missing, ["web", "docs"]);
+
+        // The directory name works too, and the primary tree never matches.
+        let (targets, _) = branch_worktrees(tmp.path(), &projects, "feature-x");
+        assert_eq!(targets[0].name, "api");
+        let (targets, missing) = branch_worktrees(tmp.path(), &projects, "main");
+        assert!(targets.is_empty());
+        assert_eq!(missing.len(), 3);
+    }
+
     #[test]
     fn prune_project_quiet_counts_stale_entries() {
         let tmp = tempdir().unwrap();
@@ -1,8 +1,10 @@
 use super::manager::WorktreeManager;
 use super::{
-    add_worktrees, clean_worktrees, list_all_worktrees, prune_worktrees, remove_worktrees,
-    repair_worktrees, CleanOptions,
+    add_worktrees, branch_worktrees, clean_worktrees, list_all_worktrees, prune_worktrees,
+    remove_worktrees, repair_worktrees, CleanOptions,
 };
+use crate::plugins::exec::{execute_with_projects, OutputMode};
+use crate::plugins::shared::{outcome, selection, MuxOptions};
 use anyhow::Result;
 use clap::ArgMatches;
 use colored::Colorize;
@@ -10,6 +12,7 @@ use metarepo_core::{
     arg, command, is_interactive, plugin, prompt_multiselect, prompt_text, BasePlugin, MetaPlugin,
     NonInteractiveMode, RuntimeConfig,
 };
+use std::time::Instant;
 
 /// WorktreePlugin using the simplified plugin architecture
 pub struct WorktreePlugin;
@@ -48,6 +51,7 @@ impl WorktreePlugin {
                  \n\
                    meta worktree add feature-123        create a worktree for the branch\n\
                    meta worktree list                   show worktrees in the current scope\n\
+                   meta worktree exec feature-123 -- npm test   run it in each worktree\n\
                    meta worktree tui                    manage worktrees interactively\n\
                    meta worktree clean --dry-run        preview merged worktrees to remove",
             )
@@ -123,6 +127,23 @@ impl WorktreePlugin {
                             .help("Comma-separated list of projects")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing")
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true)
+                    )
                     .arg(
                         arg("all")
                             .long("all")
@@ -193,6 +214,23 @@ impl WorktreePlugin {
                             .help("Comma-separated list of projects")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing")
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true)
+                    )
                     .arg(
                         arg("all")
                             .long("all")
@@ -300,6 +338,12 @@ impl WorktreePlugin {
                             .help("Single project to repair worktrees for")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true)
+                    )
                     .arg(
                         arg("dry-run")
                             .long("dry-run")
@@ -380,6 +424,142 @@ impl WorktreePlugin {
                             .help("Clean a comma-separated list of projects (overrides directory context)")
                             .takes_value(true)
                     )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing")
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true)
+                    )
+            )
+            .command(
+                command("exec")
+                    .about("Run a command in each project's worktree for a branch")
+                    .help_description(
+                        "Run a command inside the worktrees of one branch.\n\
+                         \n\
+                         For each selected project that has a worktree for BRANCH (matched by\n\
+                         branch name or worktree directory name), runs the command in that\n\
+                         worktree. Nothing is created: projects without the worktree are\n\
+                         listed and skipped, and the primary checkout is never used. Put the\n\
+                         command after -- so its own flags are not read as meta's.\n\
+                         \n\
+                         Projects are chosen as for meta exec: -p/--project, --projects,\n\
+                         --group, or -a/--all, minus --exclude; with none of them, your\n\
+                         current directory scope. --parallel, --only, --timestamps,\n\
+                         --log-file, --pty, and --min-success (or min_success.exec in .meta)\n\
+                         behave as they do for meta exec, the run ends with the same summary\n\
+                         and exit codes (0 all succeeded, 1 all failed, 2 some failed), and\n\
+                         `meta last` shows it afterwards.\n\
+                         \n\
+                         Examples:\n\
+                         \n\
+                           meta worktree exec feature-123 -- git status\n\
+                           meta worktree exec feature-123 --all --parallel -- npm test\n\
+                           meta worktree exec feature-123 -p api -- cargo test --workspace",
+                    )
+                    .with_help_formatting()
+                    .arg(
+                        arg("branch")
+                            .help("Branch name or worktree directory name")
+                            .required(true)
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("command")
+                            .help("Command to run, after --")
+                            .required(true)
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("project")
+                            .long("project")
+                            .short('p')
+                            .help("Single project to run in")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("projects")
+                            .long("projects")
+                            .help("Comma-separated list of projects")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("group")
+                            .long("group")
+                            .help("Select the members of a project group (repeatable)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("strict")
+                            .long("strict")
+                            .help("Fail if any project selector matches nothing")
+                    )
+                    .arg(
+                        arg("exclude")
+                            .long("exclude")
+                            .help("Skip these projects (names, aliases, globs, or tags; comma-separated)")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("all")
+                            .long("all")
+                            .short('a')
+                            .help("Run in every project that has the worktree")
+                    )
+                    .arg(
+                        arg("parallel")
+                            .long("parallel")
+                            .help("Run in the worktrees concurrently")
+                    )
+                    .arg(
+                        arg("no-progress")
+                            .long("no-progress")
+                            .help("Disable progress indicators (useful for CI environments)")
+                    )
+                    .arg(
+                        arg("streaming")
+                            .long("streaming")
+                            .help("Show output as it happens instead of buffered")
+                    )
+                    .arg(
+                        arg("only")
+                            .long("only")
+                            .help("Only show output from these projects (comma-separated)")
+                            .multiple(true)
+                    )
+                    .arg(
+                        arg("timestamps")
+                            .long("timestamps")
+                            .help("Prefix each output line with the time")
+                    )
+                    .arg(
+                        arg("log-file")
+                            .long("log-file")
+                            .help("Also write all output to this file")
+                            .takes_value(true)
+                    )
+                    .arg(
+                        arg("pty")
+                            .long("pty")
+                            .help("Run commands on a pseudo-terminal to keep colors and progress bars")
+                    )
+                    .arg(
+                        arg("min-success")
+                            .long("min-success")
+                            .help("Exit 0 when at least this percentage of projects succeed")
+                            .takes_value(true)
+                    )
             )
             .command(
                 command("tui")
@@ -412,6 +592,7 @@ impl WorktreePlugin {
             .handler("prune", handle_prune)
             .handler("repair", handle_repair)
             .handler("clean", handle_clean)
+            .handler("exec", handle_exec)
             .build()
     }
 }
@@ -478,24 +659,18 @@ fn handle_add(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let mut projects = Vec::new();
 
     if matches.get_flag("all") || global {
-        projects.push("--all".to_string());
-    } else if let Some(project) = matches.get_one::<String>("project") {
-        // Use resolve_project to handle aliases
-        if let Some(resolved) = config.resolve_project(project) {
-            projects.push(resolved);
-        } else {
-            projects.push(project.clone());
+        projects = selection::all_projects(matches, config);
+        if projects.is_empty() {
+            println!("\n{}", "Every project is excluded".dimmed());
+            return Ok(());
         }
-    } else if let Some(project_list) = matches.get_one::<String>("projects") {
-        for p in project_list.split(',') {
-            let trimmed = p.trim();
-            // Use resolve_project to handle aliases
-            if let Some(resolved) = config.resolve_project(trimmed) {
-                projects.push(resolved);
-            } else {
-                projects.push(trimmed.to_string());
-            }
+    } else if let Some(mut selected) = selection::explicit_projects(matches, config)? {
+        selection::apply_excludes(matches, config, &mut selected, true);
+        if selected.is_empty() {
+            println!("\n{}", "Every selected project is excluded".dimmed());
+            return Ok(());
         }
+        projects = selected;
     } else if is_interactive() && current_project.is_none() {
         // Prompt for project selection if none specified and no current project
         let project_names: Vec<String> = config.meta_config.projects.keys().cloned().collect();
@@ -557,27 +732,27 @@ fn handle_remove(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     // Directory-context-aware scope. When no explicit project is given,
     // remove_worktrees limits auto-detection (and any interactive selection) to
     // this set, so removal never reaches out-of-scope projects.
-    let scope = config.scoped_project_keys();
+    // With --all the scope is the whole workspace; either way excluded
+    // projects are dropped from it.
+    let all = matches.get_flag("all") || global;
+    let mut scope = if all {
+        config.meta_config.projects.keys().cloned().collect()
+    } else {
+        config.scoped_project_keys()
+    };
+    selection::apply_excludes(matches, config, &mut scope, false);
 
     // Collect explicitly selected projects, if any.
     let mut projects = Vec::new();
-    if matches.get_flag("all") || global {
+    if all {
         projects.push("--all".to_string());
-    } else if let Some(project) = matches.get_one::<String>("project") {
-        if let Some(resolved) = config.resolve_project(project) {
-            projects.push(resolved);
-        } else {
-            projects.push(project.clone());
-        }
-    } else if let Some(project_list) = matches.get_one::<String>("projects") {
-        for p in project_list.split(',') {
-            let trimmed = p.trim();
-            if let Some(resolved) = config.resolve_project(trimmed) {
-                projects.push(resolved);
-            } else {
-                projects.push(trimmed.to_string());
-            }
+    } else if let Some(mut selected) = selection::explicit_projects(matches, config)? {
+        selection::apply_excludes(matches, config, &mut selected, true);
+        if selected.is_empty() {
+            println!("\n{}", "Every selected project is excluded".dimmed());
+            return Ok(());
         }
+        projects = selected;
     }
     // If no projects specified, remove_worktrees selects from `scope` (using an
     // interactive multiselect when several in-scope projects have the branch).
@@ -586,6 +761,75 @@ fn handle_remove(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     Ok(())
 }
 
+/// Handler for the exec command
+fn handle_exec(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
+    let started = Instant::now();
+    let branch = matches.get_one::<String>("branch").unwrap();
+    let command: Vec<&str> = matches
+        .get_many::<String>("command")
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    let Some((program, args)) = command.split_first() else {
+        return Err(anyhow::anyhow!("No command given"));
+    };
+    let base_path = config.meta_root().unwrap_or(config.working_dir.clone());
+
+    let projects = if matches.get_flag("all") || config.scope_workspace {
+        selection::all_projects(matches, config)
+    } else if let Some(mut selected) = selection::explicit_projects(matches, config)? {
+        selection::apply_excludes(matches, config, &mut selected, true);
+        selected
+    } else {
+        let mut scope = config.scoped_project_keys();
+        selection::apply_excludes(matches, config, &mut scope, false);
+        scope
+    };
+
+    let (targets, missing) = branch_worktrees(&base_path, &projects, branch);
+    if targets.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No project in scope has a worktree '{}'. Create them with: meta worktree add {}",
+            branch,
+            branch
+        ));
+    }
+    if !missing.is_empty() {
+        println!(
+            "{} {}",
+            format!("No worktree '{}' in:", branch).dimmed(),
+            missing.join(", ").dimmed()
+        );
+    }
+
+    let min_success = outcome::min_success(matches, &config.meta_config, "exec")?;
+    execute_with_projects(
+        program,
+        args,
+        targets,
+        false,
+        if matches.get_flag("parallel") {
+            config.meta_config.worker_count()
+        } else {
+            1
+        },
+        matches.get_flag("no-progress"),
+        matches.get_flag("streaming"),
+        OutputMode::default(),
+        &MuxOptions::from_matches(matches),
+        None,
+        None,
+    )?
+    .finish(
+        &config.meta_config,
+        &base_path,
+        "worktree exec",
+        started,
+        min_success,
+    )
+}
+
 /// Handler for the list command
 fn handle_list(_matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
     let base_path = config.meta_root().unwrap_or(config.working_dir.clone());
@@ -618,13 +862,11 @@ fn handle_repair(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
 
     // Explicit --project wins; otherwise use the directory-context-aware scope
     // (with --global forcing all projects).
-    let scope: Vec<String> = if let Some(project) = matches.get_one::<String>("project") {
-        vec![config
-            .resolve_project(project)
-            .unwrap_or_else(|| project.clone())]
-    } else {
-        config.scoped_project_keys()
+    let (mut scope, explicit) = match selection::explicit_projects(matches, config)? {
+        Some(selected) => (selected, true),
+        None => (config.scoped_project_keys(), false),
     };
+    selection::apply_excludes(matches, config, &mut scope, explicit);
 
     if scope.is_empty() {
         println!("\n{}", "No projects in this directory".dimmed());
@@ -649,22 +891,11 @@ fn handle_clean(matches: &ArgMatches, config: &RuntimeConfig) -> Result<()> {
 
     // Scope resolution: explicit --project/--projects win, otherwise the
     // directory-context-aware scope (with --global forcing all projects).
-    let scope: Vec<String> = if let Some(project) = matches.get_one::<String>("project") {
-        vec![config
-            .resolve_project(project)
-            .unwrap_or_else(|| project.clone())]
-    } else if let Some(list) = matches.get_one::<String>("projects") {
-        list.split(',')
-            .map(|p| {
-                let trimmed = p.trim();
-                config
-                    .resolve_project(trimmed)
-                    .unwrap_or_else(|| trimmed.to_string())
-            })
-            .collect()
-    } else {
-        config.scoped_project_keys()
+    let (mut scope, explicit) = match selection::explicit_projects(matches, config)? {
+        Some(selected) => (selected, true),
+        None => (config.scoped_project_keys(), false),
     };
+    selection::apply_excludes(matches, config, &mut scope, explicit);
 
     if scope.is_empty() {
         println!("\n{}", "No projects in scope for cleanup".dimmed());
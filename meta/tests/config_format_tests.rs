@@ -25,7 +25,8 @@ fn explicit_config_override_loads_arbitrary_path() {
 
     // create_runtime_config_full bypasses discovery when an override is set.
     // We pass it directly here (the CLI does the same after parsing --config).
-    let rc = create_runtime_config_full(false, None, Some(path.clone()), false, false).unwrap();
+    let rc =
+        create_runtime_config_full(false, None, Some(path.clone()), false, false, None).unwrap();
     assert_eq!(rc.meta_file_path, Some(path));
     assert!(rc.meta_config.projects.contains_key("alpha"));
 }
@@ -34,7 +35,7 @@ fn explicit_config_override_loads_arbitrary_path() {
 fn explicit_override_rejects_unreadable_path() {
     let tmp = TempDir::new().unwrap();
     let missing = tmp.path().join("nope.yaml");
-    let err = create_runtime_config_full(false, None, Some(missing), false, false).err();
+    let err = create_runtime_config_full(false, None, Some(missing), false, false, None).err();
     assert!(
         err.is_some(),
         "missing override path should produce an error"
@@ -57,18 +58,25 @@ fn save_then_load_works_for_each_extension() {
 
         let mut config = MetaConfig::default();
         let mut scripts = HashMap::new();
-        scripts.insert("build".to_string(), "cargo build".to_string());
+        scripts.insert("build".to_string(), "cargo build".into());
         config.projects.insert(
             "alpha".to_string(),
             ProjectEntry::Metadata(metarepo_core::ProjectMetadata {
                 url: "https://example.com/x.git".to_string(),
                 aliases: vec!["a".to_string()],
+                tags: vec![],
                 scripts,
                 env: HashMap::new(),
                 worktree_init: None,
                 bare: None,
+                default_script: None,
                 enabled: None,
                 depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
             }),
         );
 
@@ -91,7 +99,7 @@ fn discover_errors_when_multiple_configs_coexist() {
     std::env::remove_var("METAREPO_CONFIG");
     let orig = std::env::current_dir().unwrap();
     std::env::set_current_dir(tmp.path()).unwrap();
-    let err = create_runtime_config_full(false, None, None, false, false)
+    let err = create_runtime_config_full(false, None, None, false, false, None)
         .err()
         .unwrap();
     std::env::set_current_dir(orig).unwrap();
@@ -101,3 +109,52 @@ fn discover_errors_when_multiple_configs_coexist() {
     assert!(msg.contains(".metarepo.yaml"));
     assert!(msg.contains("--config") || msg.contains("migrate"));
 }
+
+#[test]
+fn toml_in_legacy_meta_is_read_and_written_back_as_toml() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join(".meta");
+    std::fs::write(
+        &path,
+        "# Hand-edited workspace\n\
+         [projects.web]\n\
+         url = \"https://example.com/web.git\"\n\
+         \n\
+         [projects.web.scripts]\n\
+         test = \"npm test\"\n",
+    )
+    .unwrap();
+
+    let mut config = MetaConfig::load_from_file(&path).unwrap();
+    assert_eq!(
+        config.get_project_url("web").as_deref(),
+        Some("https://example.com/web.git")
+    );
+    config.projects.insert(
+        "api".to_string(),
+        ProjectEntry::Url("https://example.com/api.git".to_string()),
+    );
+    config.save_to_file(&path).unwrap();
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(!written.trim_start().starts_with('{'), "got: {written}");
+    assert_eq!(ConfigFormat::of_file(&path), Some(ConfigFormat::Toml));
+    assert!(MetaConfig::load_from_file(&path)
+        .unwrap()
+        .projects
+        .contains_key("api"));
+}
+
+#[test]
+fn meta_toml_is_discovered() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(
+        tmp.path().join(".meta.toml"),
+        "[projects]\nweb = \"https://example.com/web.git\"\n",
+    )
+    .unwrap();
+
+    let found = MetaConfig::discover_from(tmp.path()).unwrap().unwrap();
+    assert_eq!(found.path, tmp.path().join(".meta.toml"));
+    assert_eq!(found.format, ConfigFormat::Toml);
+}
@@ -0,0 +1,334 @@
+// End-to-end tests for the daemon API an editor extension builds on (see
+// docs/DAEMON_API.md): the project tree, running scripts, opening worktrees,
+// and event subscriptions.
+//
+// Each test starts a real daemon with `meta daemon start` in a throwaway
+// workspace holding one git project, talks to it over its socket exactly as an
+// extension would, and stops it again, so the contract is checked against the
+// shipped binary rather than the handler functions alone.
+
+#![cfg(unix)]
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const META_BIN: &str = env!("CARGO_BIN_EXE_meta");
+
+/// How long to wait for the daemon to answer or for an event to arrive.
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A workspace with one git project, `api`, and a daemon serving it. The
+/// daemon is stopped when the fixture is dropped.
+struct Fixture {
+    _home: TempDir,
+    _ws: TempDir,
+    home: PathBuf,
+    ws: PathBuf,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let home = TempDir::new().unwrap();
+        let ws = TempDir::new().unwrap();
+        let f = Fixture {
+            home: home.path().to_path_buf(),
+            ws: ws.path().to_path_buf(),
+            _home: home,
+            _ws: ws,
+        };
+        let config = json!({
+            "projects": {
+                "api": {
+                    "url": "https://example.com/api.git",
+                    "scripts": { "hello": "touch hello.txt", "fail": "sh -c 'exit 3'" },
+                },
+            },
+        });
+        std::fs::write(f.ws.join(".meta"), config.to_string()).unwrap();
+
+        let project = f.ws.join("api");
+        std::fs::create_dir(&project).unwrap();
+        run_git(&project, &["init", "-q", "-b", "main"]);
+        std::fs::write(project.join("README.md"), "hello").unwrap();
+        run_git(&project, &["add", "."]);
+        run_git(&project, &["commit", "-q", "-m", "init"]);
+
+        ok(f.meta(&["daemon", "start"]));
+        f
+    }
+
+    /// Run the `meta` binary with this fixture's HOME and workspace cwd.
+    fn meta(&self, args: &[&str]) -> Output {
+        Command::new(META_BIN)
+            .args(args)
+            .current_dir(&self.ws)
+            .env("HOME", &self.home)
+            .env("NO_COLOR", "1")
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("METAREPO_USER_CONFIG")
+            .output()
+            .expect("failed to spawn meta binary")
+    }
+
+    fn connect(&self) -> Client {
+        let stream = UnixStream::connect(self.ws.join(".meta-state").join("daemon.sock"))
+            .expect("daemon socket is not accepting connections");
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+        Client {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            stream,
+            next_id: 1,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = self.meta(&["daemon", "stop"]);
+    }
+}
+
+/// One connection to the daemon, as an editor would hold it.
+struct Client {
+    stream: UnixStream,
+    reader: BufReader<UnixStream>,
+    next_id: u64,
+    /// Notifications that arrived while waiting for a response.
+    events: Vec<Value>,
+}
+
+impl Client {
+    fn read(&mut self) -> Value {
+        let mut line = String::new();
+        self.reader
+            .read_line(&mut line)
+            .expect("timed out waiting for the daemon");
+        serde_json::from_str(&line).unwrap()
+    }
+
+    /// Send a request and return its response, keeping any notifications
+    /// that arrive first.
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.stream, "{}", request).unwrap();
+        loop {
+            let message = self.read();
+            if message.get("id") == Some(&json!(id)) {
+                return message;
+            }
+            assert!(
+                message.get("id").is_none(),
+                "unexpected response {}",
+                message
+            );
+            self.events.push(message);
+        }
+    }
+
+    /// The result of a request that must succeed.
+    fn call(&mut self, method: &str, params: Value) -> Value {
+        let response = self.request(method, params);
+        assert!(
+            response.get("error").is_none(),
+            "{} failed: {}",
+            method,
+            response
+        );
+        response["result"].clone()
+    }
+
+    /// Wait for a notification of `event` matching `accept`.
+    fn event(&mut self, event: &str, accept: impl Fn(&Value) -> bool) -> Value {
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            if let Some(i) = self
+                .events
+                .iter()
+                .position(|e| e["method"] == event && accept(&e["params"]))
+            {
+                return self.events.remove(i)["params"].clone();
+            }
+            assert!(Instant::now() < deadline, "no {} event arrived", event);
+            let message = self.read();
+            self.events.push(message);
+        }
+    }
+}
+
+fn run_git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "commit.gpgsign=false"])
+        .args(["-c", "user.name=Test"])
+        .args(["-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("git {:?} failed to spawn: {}", args, e));
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn git_available() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn ok(out: Output) -> Output {
+    assert!(
+        out.status.success(),
+        "command failed (status {:?})\nstdout: {}\nstderr: {}",
+        out.status.code(),
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    out
+}
+
+#[test]
+fn advertises_the_editor_methods() {
+    if !git_available() {
+        eprintln!("skipping: git not available");
+        return;
+    }
+    let f = Fixture::new();
+    let mut client = f.connect();
+
+    let version = client.call("api.version", Value::Null);
+    assert_eq!(version["version"], 1);
+    for method in [
+        "workspace.tree",
+        "script.run",
+        "script.status",
+        "worktree.open",
+        "events.subscribe",
+        "events.unsubscribe",
+    ] {
+        assert!(
+            version["methods"]
+                .as_array()
+                .unwrap()
+                .contains(&json!(method)),
+            "api.version does not list {}",
+            method
+        );
+    }
+    assert_eq!(
+        version["events"],
+        json!(["status.changed", "script.exited"])
+    );
+}
+
+#[test]
+fn tree_reports_projects_with_status_and_worktrees() {
+    if !git_available() {
+        eprintln!("skipping: git not available");
+        return;
+    }
+    let f = Fixture::new();
+    let mut client = f.connect();
+
+    let tree = client.call("workspace.tree", Value::Null);
+    let api = &tree[0];
+    assert_eq!(api["name"], "api");
+    assert_eq!(api["url"], "https://example.com/api.git");
+    assert_eq!(api["state"]["Ok"]["branch"], "main");
+    assert_eq!(api["state"]["Ok"]["dirty"], 0);
+    assert_eq!(api["worktrees"], json!([]));
+
+    let opened = client.call(
+        "worktree.open",
+        json!({ "project": "api", "branch": "feature" }),
+    );
+    assert_eq!(opened["created"], true);
+    let path = PathBuf::from(opened["path"].as_str().unwrap());
+    assert!(path.join("README.md").exists());
+
+    // Opening it again reuses the worktree.
+    let again = client.call(
+        "worktree.open",
+        json!({ "project": "api", "branch": "feature" }),
+    );
+    assert_eq!(again["created"], false);
+    assert_eq!(again["path"], opened["path"]);
+
+    let tree = client.call("workspace.tree", json!({ "refresh": true }));
+    let worktrees = tree[0]["worktrees"].as_array().unwrap();
+    assert_eq!(worktrees.len(), 1);
+    assert_eq!(worktrees[0]["branch"], "feature");
+
+    let escape = client.request(
+        "worktree.open",
+        json!({ "project": "api", "branch": "../x" }),
+    );
+    assert_eq!(escape["error"]["code"], -32602);
+}
+
+#[test]
+fn runs_scripts_and_reports_their_exit() {
+    if !git_available() {
+        eprintln!("skipping: git not available");
+        return;
+    }
+    let f = Fixture::new();
+    let mut client = f.connect();
+    client.call("events.subscribe", json!({ "events": ["script.exited"] }));
+
+    let run = client.call("script.run", json!({ "project": "api", "script": "hello" }));
+    let id = run["id"].clone();
+    let exited = client.event("script.exited", |p| p["id"] == id);
+    assert_eq!(exited["exit_code"], 0);
+    assert_eq!(exited["project"], "api");
+    assert!(f.ws.join("api").join("hello.txt").exists());
+
+    let status = client.call("script.status", json!({ "id": id }));
+    assert_eq!(status["running"], false);
+    assert_eq!(status["exit_code"], 0);
+    assert!(Path::new(status["log"].as_str().unwrap()).exists());
+
+    let run = client.call("script.run", json!({ "project": "api", "script": "fail" }));
+    let id = run["id"].clone();
+    let exited = client.event("script.exited", |p| p["id"] == id);
+    assert_ne!(exited["exit_code"], 0);
+
+    let unknown = client.request("script.run", json!({ "project": "web", "script": "hello" }));
+    assert_eq!(unknown["error"]["code"], -32602);
+}
+
+#[test]
+fn subscribers_hear_about_status_changes() {
+    if !git_available() {
+        eprintln!("skipping: git not available");
+        return;
+    }
+    let f = Fixture::new();
+    let mut client = f.connect();
+    let subscribed = client.call("events.subscribe", json!({ "events": ["status.changed"] }));
+    assert_eq!(subscribed["events"], json!(["status.changed"]));
+
+    // Staging a file rewrites the index, which the daemon watches.
+    let project = f.ws.join("api");
+    std::fs::write(project.join("new.txt"), "new").unwrap();
+    run_git(&project, &["add", "new.txt"]);
+
+    let changed = client.event("status.changed", |p| {
+        p["projects"]
+            .as_array()
+            .is_some_and(|projects| projects.iter().any(|s| s["name"] == "api"))
+    });
+    assert_eq!(changed["projects"][0]["state"]["Ok"]["dirty"], 1);
+
+    client.call("events.unsubscribe", Value::Null);
+    let info = client.call("daemon.info", Value::Null);
+    assert_eq!(info["connections"], 1);
+}
@@ -84,11 +84,17 @@ fn ok(out: Output) -> Output {
 
 /// Write a minimal shell manifest plugin into `dir` and return that dir.
 fn write_manifest_plugin(dir: &Path) {
+    write_named_manifest_plugin(dir, "greet");
+}
+
+/// The greet plugin under another command name.
+fn write_named_manifest_plugin(dir: &Path, name: &str) {
     fs::create_dir_all(dir).unwrap();
     fs::write(
         dir.join("plugin.manifest.toml"),
-        r#"[plugin]
-name = "greet"
+        format!(
+            r#"[plugin]
+name = "{name}"
 version = "0.1.0"
 description = "test manifest plugin"
 
@@ -109,7 +115,8 @@ help = "Shout the greeting"
 
 [config.execution]
 binary = "./greet.sh"
-"#,
+"#
+        ),
     )
     .unwrap();
     fs::write(
@@ -166,6 +173,58 @@ fn manifest_plugin_installs_and_runs() {
     );
 }
 
+/// A plugin named after a built-in command does not replace it; it runs
+/// under `meta ext` and `meta plugin list` reports the conflict.
+#[test]
+fn plugin_shadowing_a_built_in_command_runs_under_ext() {
+    let f = Fixture::new();
+    let src = f.ws.join("src-which");
+    write_named_manifest_plugin(&src, "which");
+    let from = format!("file:{}", src.display());
+    ok(f.meta(&["plugin", "install", "which", "--from", &from]));
+
+    let out = ok(f.meta(&["which", "--help"]));
+    assert!(
+        stdout(&out).contains("owns a path"),
+        "built-in which should answer: {}",
+        stdout(&out)
+    );
+    let out = ok(f.meta(&["ext", "which", "hello", "Ada"]));
+    assert!(stdout(&out).contains("Hello, Ada!"), "{}", stdout(&out));
+
+    let out = ok(f.meta(&["plugin", "list"]));
+    let s = stdout(&out);
+    assert!(s.contains("Command conflicts"), "{s}");
+    assert!(s.contains("which (built-in)"), "{s}");
+    assert!(s.contains("meta ext which"), "{s}");
+}
+
+#[test]
+fn config_disables_and_overrides_built_in_plugins() {
+    let f = Fixture::new();
+    let src = f.ws.join("src-which");
+    write_named_manifest_plugin(&src, "which");
+    let from = format!("file:{}", src.display());
+    ok(f.meta(&["plugin", "install", "which", "--from", &from]));
+
+    let meta_file = f.ws.join(".metarepo");
+    let mut config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&meta_file).unwrap()).unwrap();
+    config["disabled_plugins"] = serde_json::json!(["tips"]);
+    config["override"] = serde_json::json!({"which": "which"});
+    fs::write(&meta_file, config.to_string()).unwrap();
+
+    assert!(!f.meta(&["tips"]).status.success(), "tips should be gone");
+    let out = ok(f.meta(&["which", "hello", "Ada"]));
+    assert!(stdout(&out).contains("Hello, Ada!"), "{}", stdout(&out));
+
+    let out = ok(f.meta(&["plugin", "list"]));
+    let s = stdout(&out);
+    assert!(s.contains("tips  disabled"), "{s}");
+    assert!(s.contains("which  replaced by plugin which"), "{s}");
+    assert!(!s.contains("Command conflicts"), "{s}");
+}
+
 #[test]
 fn plugin_list_reports_installed_manifest_plugin() {
     let f = Fixture::new();
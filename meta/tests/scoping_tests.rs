@@ -168,3 +168,487 @@ fn root_flag_targets_outermost_metarepo() {
         "rooted got:\n{rooted}"
     );
 }
+
+/// [`workspace`] with a `plugins` profile holding `plugins/a` and `plugins/b`.
+fn profiled_workspace() -> TempDir {
+    let tmp = workspace();
+    fs::write(
+        tmp.path().join(".meta"),
+        r#"{
+  "projects": {
+    "app": "https://example.com/app.git",
+    "plugins/a": "https://example.com/plugins/a.git",
+    "plugins/b": "https://example.com/plugins/b.git"
+  },
+  "profiles": { "plugins": ["plugins/*"], "app": ["app"] }
+}
+"#,
+    )
+    .unwrap();
+    tmp
+}
+
+#[test]
+fn profile_narrows_the_workspace() {
+    if !git_available() {
+        return;
+    }
+    let tmp = profiled_workspace();
+    let s = stdout(&meta_in(
+        tmp.path(),
+        &["--profile", "plugins", "project", "list", "--minimal"],
+    ));
+    assert!(
+        s.contains("plugins/a") && s.contains("plugins/b"),
+        "got:\n{s}"
+    );
+    assert!(
+        !s.contains("app"),
+        "project outside the profile listed:\n{s}"
+    );
+
+    // Named projects outside the profile are not part of the workspace.
+    let out = meta_in(
+        tmp.path(),
+        &["--profile", "plugins", "exec", "--project", "app", "true"],
+    );
+    assert!(!out.status.success(), "got:\n{}", stdout(&out));
+}
+
+#[test]
+fn profile_limits_exec_all() {
+    if !git_available() {
+        return;
+    }
+    let tmp = profiled_workspace();
+    let out = meta_in(
+        tmp.path(),
+        &[
+            "--profile",
+            "app",
+            "exec",
+            "--all",
+            "touch",
+            "profile-marker",
+        ],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("app/profile-marker").exists());
+    assert!(!tmp.path().join("plugins/a/profile-marker").exists());
+}
+
+#[test]
+fn profile_limits_git_update() {
+    if !git_available() {
+        return;
+    }
+    let sources = TempDir::new().unwrap();
+    for name in ["web", "api"] {
+        init_repo(&sources.path().join(name));
+    }
+    let ws = TempDir::new().unwrap();
+    fs::write(
+        ws.path().join(".meta"),
+        format!(
+            r#"{{"projects": {{"web": {:?}, "api": {:?}}}, "profiles": {{"backend": ["api"]}}}}"#,
+            sources.path().join("web").display().to_string(),
+            sources.path().join("api").display().to_string(),
+        ),
+    )
+    .unwrap();
+
+    let out = meta_in(ws.path(), &["--profile", "backend", "git", "update"]);
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(ws.path().join("api/.git").exists());
+    assert!(!ws.path().join("web").exists());
+}
+
+#[test]
+fn unknown_profile_is_an_error() {
+    if !git_available() {
+        return;
+    }
+    let tmp = profiled_workspace();
+    let out = meta_in(tmp.path(), &["--profile", "mobile", "project", "list"]);
+    assert!(!out.status.success());
+    let err = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        err.contains("Available profiles: app, plugins"),
+        "got:\n{err}"
+    );
+}
+
+/// [`workspace`] with a `plugins` group holding `plugins/a` and `plugins/b`.
+fn grouped_workspace() -> TempDir {
+    let tmp = workspace();
+    fs::write(
+        tmp.path().join(".meta"),
+        r#"{
+  "projects": {
+    "app": "https://example.com/app.git",
+    "plugins/a": "https://example.com/plugins/a.git",
+    "plugins/b": "https://example.com/plugins/b.git"
+  },
+  "groups": {
+    "plugins": {
+      "description": "Every plugin",
+      "projects": ["plugins/*"],
+      "default_script": "mark"
+    }
+  },
+  "scripts": { "mark": "touch group-marker" }
+}
+"#,
+    )
+    .unwrap();
+    tmp
+}
+
+#[test]
+fn group_selects_its_members_for_exec() {
+    if !git_available() {
+        return;
+    }
+    let tmp = grouped_workspace();
+    let out = meta_in(
+        tmp.path(),
+        &["exec", "--group", "plugins", "touch", "exec-marker"],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("plugins/a/exec-marker").exists());
+    assert!(tmp.path().join("plugins/b/exec-marker").exists());
+    assert!(!tmp.path().join("app/exec-marker").exists());
+
+    let out = meta_in(tmp.path(), &["exec", "--group", "mobile", "true"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Available groups: plugins"));
+}
+
+#[test]
+fn run_with_a_group_uses_its_default_script() {
+    if !git_available() {
+        return;
+    }
+    let tmp = grouped_workspace();
+    let out = meta_in(tmp.path(), &["run", "--group", "plugins"]);
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("plugins/a/group-marker").exists());
+    assert!(!tmp.path().join("app/group-marker").exists());
+}
+
+#[test]
+fn project_group_commands_edit_the_config() {
+    if !git_available() {
+        return;
+    }
+    let tmp = grouped_workspace();
+    let out = meta_in(
+        tmp.path(),
+        &[
+            "project",
+            "group",
+            "add",
+            "core",
+            "app",
+            "--description",
+            "The app",
+        ],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+
+    let s = stdout(&meta_in(tmp.path(), &["project", "group", "list"]));
+    assert!(s.contains("core") && s.contains("The app"), "got:\n{s}");
+    assert!(s.contains("plugins/a, plugins/b"), "got:\n{s}");
+
+    let out = meta_in(tmp.path(), &["project", "group", "remove", "core"]);
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    let s = stdout(&meta_in(tmp.path(), &["project", "group", "list"]));
+    assert!(!s.contains("core"), "got:\n{s}");
+}
+
+/// [`workspace`] with `plugins/a` and `plugins/b` tagged `backend`, `plugins/b`
+/// also tagged `legacy`, and a `mark` script tagged `greet`.
+fn tagged_workspace() -> TempDir {
+    let tmp = workspace();
+    fs::write(
+        tmp.path().join(".meta"),
+        r#"{
+  "projects": {
+    "app": "https://example.com/app.git",
+    "plugins/a": { "url": "https://example.com/plugins/a.git", "tags": ["backend"] },
+    "plugins/b": { "url": "https://example.com/plugins/b.git", "tags": ["backend", "legacy"] }
+  },
+  "scripts": {
+    "mark": { "cmd": "touch tag-marker", "tags": ["greet"] },
+    "other": "true"
+  }
+}
+"#,
+    )
+    .unwrap();
+    tmp
+}
+
+#[test]
+fn run_tags_select_projects_and_script_tags_select_scripts() {
+    if !git_available() {
+        return;
+    }
+    let tmp = tagged_workspace();
+    let out = meta_in(
+        tmp.path(),
+        &["run", "mark", "--tag", "backend", "--exclude-tag", "legacy"],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("plugins/a/tag-marker").exists());
+    assert!(!tmp.path().join("plugins/b/tag-marker").exists());
+    assert!(!tmp.path().join("app/tag-marker").exists());
+
+    let out = meta_in(tmp.path(), &["run", "--script-tag", "greet"]);
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    let s = stdout(&out);
+    assert!(s.contains("mark") && !s.contains("other"), "got:\n{s}");
+
+    for flag in ["--tag", "--exclude-tag"] {
+        let out = meta_in(tmp.path(), &["run", "-l", flag, "greet"]);
+        assert!(!out.status.success(), "got:\n{}", stdout(&out));
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert!(err.contains("--script-tag"), "got:\n{err}");
+    }
+}
+
+#[test]
+fn tags_select_projects_for_exec() {
+    if !git_available() {
+        return;
+    }
+    let tmp = tagged_workspace();
+    let out = meta_in(
+        tmp.path(),
+        &["exec", "--tag", "backend", "touch", "exec-marker"],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("plugins/a/exec-marker").exists());
+    assert!(tmp.path().join("plugins/b/exec-marker").exists());
+    assert!(!tmp.path().join("app/exec-marker").exists());
+
+    // A tag narrows named projects instead of adding to them.
+    let out = meta_in(
+        tmp.path(),
+        &[
+            "exec",
+            "--projects",
+            "app,plugins/b",
+            "--exclude-tag",
+            "legacy",
+            "touch",
+            "named-marker",
+        ],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("app/named-marker").exists());
+    assert!(!tmp.path().join("plugins/a/named-marker").exists());
+    assert!(!tmp.path().join("plugins/b/named-marker").exists());
+
+    let out = meta_in(tmp.path(), &["exec", "--tag", "mobile", "true"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("No projects tagged 'mobile'"));
+}
+
+#[test]
+fn tags_compose_with_existing_and_git_only_for_exec() {
+    if !git_available() {
+        return;
+    }
+    let tmp = tagged_workspace();
+    fs::remove_dir_all(tmp.path().join("plugins/b/.git")).unwrap();
+    let out = meta_in(
+        tmp.path(),
+        &[
+            "exec",
+            "--tag",
+            "backend",
+            "--git-only",
+            "touch",
+            "git-marker",
+        ],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("plugins/a/git-marker").exists());
+    assert!(!tmp.path().join("plugins/b/git-marker").exists());
+    assert!(!tmp.path().join("app/git-marker").exists());
+
+    fs::remove_dir_all(tmp.path().join("plugins/a")).unwrap();
+    let out = meta_in(
+        tmp.path(),
+        &[
+            "exec",
+            "--tag",
+            "backend",
+            "--existing-only",
+            "touch",
+            "existing-marker",
+        ],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(tmp.path().join("plugins/b/existing-marker").exists());
+    assert!(!tmp.path().join("app/existing-marker").exists());
+}
+
+/// `workspace()` with a second commit adding `lib.rs` in `app`.
+fn changed_workspace() -> TempDir {
+    let tmp = workspace();
+    let app = tmp.path().join("app");
+    fs::write(app.join("lib.rs"), "fn main() {}\n").unwrap();
+    run_git(&app, &["add", "lib.rs"]);
+    run_git(&app, &["commit", "-q", "-m", "add lib"]);
+    tmp
+}
+
+#[test]
+fn changed_files_are_expanded_for_named_projects() {
+    if !git_available() {
+        return;
+    }
+    let tmp = changed_workspace();
+    let out = meta_in(
+        tmp.path(),
+        &[
+            "exec",
+            "-p",
+            "app",
+            "--changed-files",
+            "HEAD~1",
+            "sh",
+            "-c",
+            "echo \"args: $0\"; echo \"env: $METAREPO_CHANGED_FILES\"",
+            "{changed_files}",
+        ],
+    );
+    let s = stdout(&out);
+    assert!(out.status.success(), "got:\n{s}");
+    assert!(s.contains("args: lib.rs"), "got:\n{s}");
+    assert!(s.contains("env: lib.rs"), "got:\n{s}");
+}
+
+#[test]
+fn changed_files_in_the_main_repository_do_not_abort_the_run() {
+    if !git_available() {
+        return;
+    }
+    // The workspace root is not a repository, so HEAD~1 cannot be resolved
+    // there; the projects still run.
+    let tmp = changed_workspace();
+    let out = meta_in(
+        tmp.path(),
+        &[
+            "exec",
+            "--all",
+            "--include-main",
+            "--changed-files",
+            "HEAD~1",
+            "touch",
+            "changed-marker",
+        ],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("cannot diff against HEAD~1"));
+    assert!(tmp.path().join("app/changed-marker").exists());
+    assert!(!tmp.path().join("changed-marker").exists());
+}
+
+#[test]
+fn tags_select_projects_for_git_status() {
+    if !git_available() {
+        return;
+    }
+    let tmp = tagged_workspace();
+    init_repo(tmp.path());
+    let s = stdout(&meta_in(tmp.path(), &["git", "status"]));
+    assert!(s.contains("Main repository"), "got:\n{s}");
+
+    let s = stdout(&meta_in(
+        tmp.path(),
+        &[
+            "git",
+            "status",
+            "--tag",
+            "backend",
+            "--exclude-tag",
+            "legacy",
+        ],
+    ));
+    assert!(s.contains("plugins/a"), "got:\n{s}");
+    assert!(!s.contains("plugins/b") && !s.contains("app:"), "got:\n{s}");
+    assert!(
+        !s.contains("Main repository"),
+        "main repo should not show under a tag filter:\n{s}"
+    );
+}
+
+#[test]
+fn tags_select_projects_for_project_list() {
+    if !git_available() {
+        return;
+    }
+    let tmp = tagged_workspace();
+    let s = stdout(&meta_in(
+        tmp.path(),
+        &["project", "list", "--minimal", "--tag", "backend"],
+    ));
+    assert!(
+        s.contains("plugins/a") && s.contains("plugins/b"),
+        "got:\n{s}"
+    );
+    assert!(!s.contains("app"), "untagged project listed:\n{s}");
+
+    let s = stdout(&meta_in(
+        tmp.path(),
+        &["project", "list", "--minimal", "--exclude-tag", "legacy"],
+    ));
+    assert!(s.contains("app") && s.contains("plugins/a"), "got:\n{s}");
+    assert!(!s.contains("plugins/b"), "excluded project listed:\n{s}");
+}
+
+#[test]
+fn tags_limit_git_update() {
+    if !git_available() {
+        return;
+    }
+    let sources = TempDir::new().unwrap();
+    for name in ["web", "api", "legacy-api"] {
+        init_repo(&sources.path().join(name));
+    }
+    let source = |name: &str| sources.path().join(name).display().to_string();
+    let ws = TempDir::new().unwrap();
+    fs::write(
+        ws.path().join(".meta"),
+        format!(
+            r#"{{"projects": {{
+  "web": {:?},
+  "api": {{"url": {:?}, "tags": ["backend"]}},
+  "legacy-api": {{"url": {:?}, "tags": ["backend", "legacy"]}}
+}}}}"#,
+            source("web"),
+            source("api"),
+            source("legacy-api"),
+        ),
+    )
+    .unwrap();
+
+    let out = meta_in(
+        ws.path(),
+        &[
+            "git",
+            "update",
+            "--tag",
+            "backend",
+            "--exclude-tag",
+            "legacy",
+        ],
+    );
+    assert!(out.status.success(), "got:\n{}", stdout(&out));
+    assert!(ws.path().join("api/.git").exists());
+    assert!(!ws.path().join("legacy-api").exists());
+    assert!(!ws.path().join("web").exists());
+}
@@ -237,16 +237,23 @@ mod config_safety {
             ProjectEntry::Metadata(ProjectMetadata {
                 url: "https://github.com/user/repo.git".to_string(),
                 aliases: vec!["tp".to_string()],
+                tags: vec![],
                 scripts: {
                     let mut s = HashMap::new();
-                    s.insert("build".to_string(), "cargo build".to_string());
+                    s.insert("build".to_string(), "cargo build".into());
                     s
                 },
                 env: HashMap::new(),
                 worktree_init: None,
                 bare: None,
+                default_script: None,
                 enabled: None,
                 depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
             }),
         );
 
@@ -334,12 +341,19 @@ mod config_safety {
             ProjectEntry::Metadata(ProjectMetadata {
                 url: "https://github.com/user/repo.git".to_string(),
                 aliases: vec![],
+                tags: vec![],
                 scripts: HashMap::new(),
                 env: HashMap::new(),
                 worktree_init: Some(dangerous_init.to_string()),
                 bare: None,
+                default_script: None,
                 enabled: None,
                 depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
             }),
         );
 
@@ -378,12 +392,19 @@ mod config_safety {
             ProjectEntry::Metadata(ProjectMetadata {
                 url: "https://github.com/user/b.git".to_string(),
                 aliases: vec![],
+                tags: vec![],
                 scripts: HashMap::new(),
                 env: HashMap::new(),
                 worktree_init: Some("echo project".to_string()),
                 bare: None,
+                default_script: None,
                 enabled: None,
                 depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
             }),
         );
 
@@ -413,12 +434,19 @@ mod config_safety {
             ProjectEntry::Metadata(ProjectMetadata {
                 url: "https://github.com/user/repo.git".to_string(),
                 aliases: vec![],
+                tags: vec![],
                 scripts: HashMap::new(),
                 env,
                 worktree_init: None,
                 bare: None,
+                default_script: None,
                 enabled: None,
                 depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
             }),
         );
         config.save_to_file(&meta_path).unwrap();
@@ -690,10 +718,10 @@ mod exec_config_integration {
     fn config_script_retrieval() {
         let mut config = MetaConfig::default();
         let mut scripts = HashMap::new();
-        scripts.insert("test".to_string(), "cargo test --all".to_string());
+        scripts.insert("test".to_string(), "cargo test --all".into());
         scripts.insert(
             "dangerous".to_string(),
-            "rm -rf / --no-preserve-root".to_string(),
+            "rm -rf / --no-preserve-root".into(),
         );
 
         config.projects.insert(
@@ -701,12 +729,19 @@ mod exec_config_integration {
             ProjectEntry::Metadata(ProjectMetadata {
                 url: "https://github.com/user/repo.git".to_string(),
                 aliases: vec![],
+                tags: vec![],
                 scripts,
                 env: HashMap::new(),
                 worktree_init: None,
                 bare: None,
+                default_script: None,
                 enabled: None,
                 depth: None,
+                pinned: None,
+                vendored: None,
+                visibility: None,
+                vcs: None,
+                deprecated: None,
             }),
         );
 